@@ -0,0 +1,39 @@
+use soroban_sdk::contracterror;
+
+/// Codes sit in [`bridgelet_shared::errors::ACCOUNT_FACTORY_ERRORS`], so
+/// they never collide with another Bridgelet contract's.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// [`AccountFactory::initialize`](crate::AccountFactory::initialize) was
+    /// called more than once.
+    AlreadyInitialized = 201,
+
+    /// An operation was attempted before the factory was initialized.
+    NotInitialized = 202,
+
+    /// The account wasm hash is not attested (or was revoked) in the
+    /// configured attestation registry.
+    WasmNotAttested = 203,
+
+    /// The kill switch halts the factory.
+    Halted = 204,
+
+    /// The account config's explicit base reserve, or the deployment fee,
+    /// is negative.
+    InvalidAmount = 205,
+}
+
+impl Error {
+    /// Name of the variant, as `describe_error` reports it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Error::AlreadyInitialized => "AlreadyInitialized",
+            Error::NotInitialized => "NotInitialized",
+            Error::WasmNotAttested => "WasmNotAttested",
+            Error::Halted => "Halted",
+            Error::InvalidAmount => "InvalidAmount",
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env};
+
+// ─── Event payloads ─────────────────────────────────────────────────────────
+
+/// Emitted when the factory deploys and initializes an ephemeral account.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountDeployed {
+    pub account: Address,
+    pub creator: Address,
+    pub salt: BytesN<32>,
+    pub wasm_hash: BytesN<32>,
+}
+
+/// Emitted when the factory resets a settled account for re-use.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountRecycled {
+    pub account: Address,
+    pub expiry_ledger: u32,
+}
+
+/// Emitted when the admin points the factory at a new account wasm.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WasmHashUpdated {
+    pub old_hash: BytesN<32>,
+    pub new_hash: BytesN<32>,
+}
+
+// ─── Emit helpers ───────────────────────────────────────────────────────────
+
+/// Publish the `deployed` event.
+pub fn emit_account_deployed(
+    env: &Env,
+    account: Address,
+    creator: Address,
+    salt: BytesN<32>,
+    wasm_hash: BytesN<32>,
+) {
+    let event = AccountDeployed {
+        account,
+        creator,
+        salt,
+        wasm_hash,
+    };
+    env.events().publish((symbol_short!("deployed"),), event);
+}
+
+/// Publish the `recycled` event.
+pub fn emit_account_recycled(env: &Env, account: Address, expiry_ledger: u32) {
+    let event = AccountRecycled {
+        account,
+        expiry_ledger,
+    };
+    env.events().publish((symbol_short!("recycled"),), event);
+}
+
+/// Publish the `wasm` event.
+pub fn emit_wasm_hash_updated(env: &Env, old_hash: BytesN<32>, new_hash: BytesN<32>) {
+    let event = WasmHashUpdated { old_hash, new_hash };
+    env.events().publish((symbol_short!("wasm"),), event);
+}
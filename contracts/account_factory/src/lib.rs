@@ -0,0 +1,526 @@
+#![no_std]
+
+mod errors;
+mod events;
+mod storage;
+#[cfg(test)]
+mod test;
+
+use attestation_registry::AttestationRegistryClient;
+use bridgelet_shared::errors::UNKNOWN_ERROR;
+use bridgelet_shared::{
+    units, AccountConfig, AssetKind, DeploymentSettings, EphemeralAccountClient, ReserveClient,
+};
+use insurance_pool::InsurancePoolClient;
+use kill_switch::KillSwitchClient;
+use soroban_sdk::{
+    contract, contractimpl, symbol_short, token, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol,
+};
+
+pub use errors::Error;
+pub use events::{AccountDeployed, AccountRecycled, WasmHashUpdated};
+pub use storage::{DataKey, DeploymentFee, InsurancePremium};
+
+/// Subsystem this contract checks on the kill switch.
+const KILL_SWITCH_SUBSYSTEM: Symbol = symbol_short!("factory");
+
+/// Deploys ephemeral accounts at deterministic addresses.
+///
+/// Each account is an instance of the uploaded `EphemeralAccountContract`
+/// wasm whose hash the factory stores.  The address is derived from the
+/// creator and a caller-chosen salt (e.g. a hash of merchant id + invoice
+/// id), so off-chain services can compute it with
+/// [`get_account_address`] before the account exists and share it with the
+/// payer.  Deployment and initialization happen in the same invocation, so
+/// there is no window in which an uninitialized account can be claimed by
+/// someone else.
+///
+/// ## Attestation
+///
+/// When an attestation registry is configured, the factory refuses to
+/// deploy (or switch to) a wasm hash the registry does not attest.
+///
+/// ## Kill switch
+///
+/// When a kill switch is configured, deployments stop while it halts the
+/// `factory` subsystem, and every deployed account that does not name its
+/// own kill switch is configured with the factory's.
+///
+/// ## Re-use
+///
+/// An account deployed as reusable names the factory as the contract
+/// allowed to reset it, so a settled, empty account can be handed to the
+/// next invoice with [`recycle`] instead of deploying a new one.  The
+/// account wasm must be built with its `reuse` feature, as the default
+/// build is.
+///
+/// ## Minimal accounts
+///
+/// An account deployed with no config is initialized with plain
+/// `initialize`, or with `initialize_with_deployment` when there is a kill
+/// switch, an event prefix, an insurance pool or, for a reusable account,
+/// the factory itself to stamp in, so the factory can deploy an account wasm built without
+/// its `config` feature.
+///
+/// ## Deployment fee
+///
+/// When a deployment fee is configured, the creator pays it to the admin
+/// on every deployment.  [`get_required_funding`] includes it when it is
+/// paid in XLM; a fee in another token is only reported by
+/// [`get_deployment_fee`], since it does not add up with stroops.
+///
+/// ## Insurance
+///
+/// When an insurance premium is configured, the creator of every account
+/// deployed without a config pays it into the insurance pool, which
+/// insures the account.  The pool is stamped into the account through
+/// `initialize_with_deployment`, and the account opens a claim with it if
+/// it expires with payments in it.  Accounts deployed with a config are
+/// not insured: `AccountConfig` has no room left for the pool.  The cover
+/// lasts one cycle; [`recycle`] does not renew it.
+///
+/// ## Event prefix
+///
+/// When an event prefix is configured, every deployed account publishes
+/// its events with the prefix as first topic, so independent deployments
+/// sharing an indexer can be told apart without an address allowlist.
+#[contract]
+pub struct AccountFactory;
+
+#[contractimpl]
+impl AccountFactory {
+    /// One-time initialization.
+    ///
+    /// # Arguments
+    /// * `admin`                – Address allowed to change the account wasm.
+    /// * `wasm_hash`            – Hash of the uploaded account wasm.
+    /// * `attestation_registry` – Registry that must attest every wasm hash
+    ///   used by the factory, or `None` to skip the check.
+    ///
+    /// # Errors
+    /// * [`Error::AlreadyInitialized`] – called more than once.
+    /// * [`Error::WasmNotAttested`]    – `wasm_hash` is not attested.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        wasm_hash: BytesN<32>,
+        attestation_registry: Option<Address>,
+    ) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        if storage::has_admin(&env) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+
+        if let Some(registry) = &attestation_registry {
+            storage::set_attestation_registry(&env, registry);
+        }
+        Self::require_attested(&env, &wasm_hash)?;
+
+        storage::set_admin(&env, &admin);
+        storage::set_wasm_hash(&env, &wasm_hash);
+
+        Ok(())
+    }
+
+    /// Point the factory at a new account wasm.  Admin-only.
+    ///
+    /// Accounts that were already deployed keep running their original
+    /// wasm.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`]  – contract has not been initialized.
+    /// * [`Error::WasmNotAttested`] – `wasm_hash` is not attested.
+    pub fn set_wasm_hash(env: Env, wasm_hash: BytesN<32>) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        Self::require_attested(&env, &wasm_hash)?;
+
+        let old_hash = storage::get_wasm_hash(&env).ok_or(Error::NotInitialized)?;
+        storage::set_wasm_hash(&env, &wasm_hash);
+        events::emit_wasm_hash_updated(&env, old_hash, wasm_hash);
+
+        Ok(())
+    }
+
+    /// Set or clear the kill switch.  Admin-only.
+    ///
+    /// Only accounts deployed afterwards pick up the change.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    pub fn set_kill_switch(env: Env, kill_switch: Option<Address>) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        match &kill_switch {
+            Some(kill_switch) => storage::set_kill_switch(&env, kill_switch),
+            None => storage::remove_kill_switch(&env),
+        }
+
+        Ok(())
+    }
+
+    /// Set or clear the event topic prefix.  Admin-only.
+    ///
+    /// Only accounts deployed afterwards pick up the change.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    pub fn set_event_prefix(env: Env, prefix: Option<Symbol>) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        match &prefix {
+            Some(prefix) => storage::set_event_prefix(&env, prefix),
+            None => storage::remove_event_prefix(&env),
+        }
+
+        Ok(())
+    }
+
+    /// Set or clear the fee charged to the creator on every deployment.
+    /// Admin-only.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::InvalidAmount`]  – the fee amount is negative.
+    pub fn set_deployment_fee(env: Env, fee: Option<DeploymentFee>) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        match &fee {
+            Some(fee) if fee.amount < 0 => return Err(Error::InvalidAmount),
+            Some(fee) => storage::set_deployment_fee(&env, fee),
+            None => storage::remove_deployment_fee(&env),
+        }
+
+        Ok(())
+    }
+
+    /// Set or clear the insurance premium charged to the creator on every
+    /// deployment without a config.  Admin-only.
+    ///
+    /// The pool must name this factory with its `set_factory`, or
+    /// deployments fail.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::InvalidAmount`]  – the premium amount is negative.
+    pub fn set_insurance(env: Env, insurance: Option<InsurancePremium>) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        match &insurance {
+            Some(insurance) if insurance.amount < 0 => return Err(Error::InvalidAmount),
+            Some(insurance) => storage::set_insurance(&env, insurance),
+            None => storage::remove_insurance(&env),
+        }
+
+        Ok(())
+    }
+
+    /// Deploy and initialize a new ephemeral account.
+    ///
+    /// The account is created at the address returned by
+    /// [`get_account_address`] for the same `creator` and `salt`, and is
+    /// initialized with `creator` as its creator.  Deploying twice with the
+    /// same pair fails because the address is already taken.
+    ///
+    /// With `reusable`, the account names the factory as the contract
+    /// allowed to [`recycle`] it.  Without a config the account is
+    /// initialized with plain `initialize`, or `initialize_with_deployment`
+    /// to stamp in the kill switch, the event prefix, the insurance pool and
+    /// the factory;
+    /// otherwise with `initialize_with_config`, which needs the account
+    /// wasm's `config` feature.
+    ///
+    /// The creator pays the deployment fee, if one is configured, and for
+    /// an account without a config the insurance premium.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`]  – contract has not been initialized.
+    /// * [`Error::WasmNotAttested`] – the stored wasm hash was revoked.
+    /// * [`Error::Halted`]          – the kill switch halts the factory.
+    ///
+    /// Initialization errors of the account itself (e.g. an expiry in the
+    /// past) abort the whole invocation, deployment included.
+    pub fn deploy(
+        env: Env,
+        creator: Address,
+        salt: BytesN<32>,
+        expiry_ledger: u32,
+        recovery_address: Address,
+        config: Option<AccountConfig>,
+        reusable: bool,
+    ) -> Result<Address, Error> {
+        storage::extend_instance_ttl(&env);
+
+        let wasm_hash = storage::get_wasm_hash(&env).ok_or(Error::NotInitialized)?;
+        creator.require_auth();
+
+        Self::require_not_halted(&env)?;
+        let insurance = storage::get_insurance(&env).filter(|_| config.is_none());
+        let deployment = Self::deployment_settings(&env, reusable, &insurance);
+
+        // Re-checked on every deployment so a revocation takes effect
+        // immediately.
+        Self::require_attested(&env, &wasm_hash)?;
+
+        if let Some(fee) = storage::get_deployment_fee(&env) {
+            if fee.amount > 0 {
+                let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+                token::Client::new(&env, &fee.token).transfer(&creator, &admin, &fee.amount);
+            }
+        }
+
+        let account = env
+            .deployer()
+            .with_current_contract(Self::account_salt(&env, &creator, &salt))
+            .deploy_v2(wasm_hash.clone(), ());
+
+        let client = EphemeralAccountClient::new(&env, &account);
+        match config {
+            None if deployment == DeploymentSettings::default() => {
+                client.initialize(&creator, &expiry_ledger, &recovery_address)
+            }
+            None => client.initialize_with_deployment(
+                &creator,
+                &expiry_ledger,
+                &recovery_address,
+                &deployment,
+            ),
+            Some(config) => client.initialize_with_config(
+                &creator,
+                &expiry_ledger,
+                &recovery_address,
+                &Self::stamp_config(config, deployment),
+            ),
+        }
+
+        if let Some(insurance) = insurance {
+            if insurance.amount > 0 {
+                token::Client::new(&env, &insurance.token).transfer(
+                    &creator,
+                    &insurance.pool,
+                    &insurance.amount,
+                );
+            }
+            InsurancePoolClient::new(&env, &insurance.pool).insure(
+                &account,
+                &insurance.token,
+                &insurance.amount,
+            );
+        }
+
+        events::emit_account_deployed(&env, account.clone(), creator, salt, wasm_hash);
+
+        Ok(account)
+    }
+
+    /// Reset a settled account deployed by this factory for a new payment.
+    ///
+    /// Requires the authorization of the account's creator, who keeps it.
+    /// The account must be closed and empty; it returns to `Active` with
+    /// the new expiry and recovery address and starts its next epoch.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::Halted`]         – the kill switch halts the factory.
+    ///
+    /// The account's own errors (not closed, funds remaining, an expiry in
+    /// the past) abort the invocation.
+    pub fn recycle(
+        env: Env,
+        account: Address,
+        expiry_ledger: u32,
+        recovery_address: Address,
+    ) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        if !storage::has_admin(&env) {
+            return Err(Error::NotInitialized);
+        }
+        Self::require_not_halted(&env)?;
+
+        let client = EphemeralAccountClient::new(&env, &account);
+        client.get_info().creator.require_auth();
+        client.reset(
+            &expiry_ledger,
+            &recovery_address,
+            &env.current_contract_address(),
+        );
+
+        events::emit_account_recycled(&env, account, expiry_ledger);
+
+        Ok(())
+    }
+
+    /// Stroops the operator must fund to deploy an account with `config`.
+    ///
+    /// This is the reserve the account tracks from initialization: with a
+    /// `reserve_contract`, its `compute_required_reserve` for
+    /// `config.trustlines`, at the contract's current values; otherwise
+    /// `config.base_reserve`, or the 100 XLM default.  A deployment fee
+    /// paid in the native asset is added on top.  A fee in any other token
+    /// is left out, so the total stays in stroops; the creator must hold
+    /// it as well, as [`get_deployment_fee`] reports.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidAmount`] – `config.base_reserve` is negative.
+    ///
+    /// A reserve contract with no base reserve aborts the invocation with
+    /// its `ReserveNotSet`.
+    pub fn get_required_funding(env: Env, config: AccountConfig) -> Result<i128, Error> {
+        storage::extend_instance_ttl(&env);
+
+        let reserve = match (&config.reserve_contract, config.base_reserve) {
+            (Some(reserve_contract), _) => ReserveClient::new(&env, reserve_contract)
+                .compute_required_reserve(&config.trustlines),
+            (None, Some(amount)) if amount < 0 => return Err(Error::InvalidAmount),
+            (None, Some(amount)) => amount,
+            (None, None) => units::DEFAULT_BASE_RESERVE_STROOPS,
+        };
+        let native = AssetKind::native_address(&env);
+        let fee = storage::get_deployment_fee(&env)
+            .filter(|fee| fee.token == native)
+            .map_or(0, |fee| fee.amount);
+        Ok(reserve + fee)
+    }
+
+    /// Address at which [`deploy`] creates the account for `creator` and
+    /// `salt`, whether or not it has been deployed yet.
+    pub fn get_account_address(env: Env, creator: Address, salt: BytesN<32>) -> Address {
+        storage::extend_instance_ttl(&env);
+        env.deployer()
+            .with_current_contract(Self::account_salt(&env, &creator, &salt))
+            .deployed_address()
+    }
+
+    /// Hash of the account wasm the factory currently deploys.
+    pub fn get_wasm_hash(env: Env) -> Option<BytesN<32>> {
+        storage::extend_instance_ttl(&env);
+        storage::get_wasm_hash(&env)
+    }
+
+    /// Attestation registry consulted before deployments, if any.
+    pub fn get_attestation_registry(env: Env) -> Option<Address> {
+        storage::extend_instance_ttl(&env);
+        storage::get_attestation_registry(&env)
+    }
+
+    /// Kill switch consulted before deployments, if any.
+    pub fn get_kill_switch(env: Env) -> Option<Address> {
+        storage::extend_instance_ttl(&env);
+        storage::get_kill_switch(&env)
+    }
+
+    /// Insurance premium charged to the creator on every deployment without
+    /// a config, if any.
+    pub fn get_insurance(env: Env) -> Option<InsurancePremium> {
+        storage::extend_instance_ttl(&env);
+        storage::get_insurance(&env)
+    }
+
+    /// Fee charged to the creator on every deployment, if any.
+    pub fn get_deployment_fee(env: Env) -> Option<DeploymentFee> {
+        storage::extend_instance_ttl(&env);
+        storage::get_deployment_fee(&env)
+    }
+
+    /// Event topic prefix stamped into deployed accounts, if any.
+    pub fn get_event_prefix(env: Env) -> Option<Symbol> {
+        storage::extend_instance_ttl(&env);
+        storage::get_event_prefix(&env)
+    }
+
+    /// Current admin address, if the contract has been initialized.
+    pub fn get_admin(env: Env) -> Option<Address> {
+        storage::extend_instance_ttl(&env);
+        storage::get_admin(&env)
+    }
+
+    // ─── Internal helpers ───────────────────────────────────────────────────
+
+    /// Mix the creator into the deployment salt so one creator cannot
+    /// front-run another by deploying at their predicted address.
+    fn account_salt(env: &Env, creator: &Address, salt: &BytesN<32>) -> BytesN<32> {
+        let mut preimage = Bytes::new(env);
+        preimage.append(&creator.clone().to_xdr(env));
+        preimage.append(&Bytes::from(salt.clone()));
+        env.crypto().sha256(&preimage).into()
+    }
+
+    /// What the factory stamps into every account it deploys: its kill
+    /// switch, its event prefix, the pool of an insured account and, for a
+    /// `reusable` account, its own address.
+    fn deployment_settings(
+        env: &Env,
+        reusable: bool,
+        insurance: &Option<InsurancePremium>,
+    ) -> DeploymentSettings {
+        DeploymentSettings {
+            factory: reusable.then(|| env.current_contract_address()),
+            kill_switch: storage::get_kill_switch(env),
+            event_prefix: storage::get_event_prefix(env),
+            insurance_pool: insurance.as_ref().map(|insurance| insurance.pool.clone()),
+        }
+    }
+
+    /// `config` with the deployment settings stamped in.  A kill switch the
+    /// creator named is kept.
+    fn stamp_config(mut config: AccountConfig, deployment: DeploymentSettings) -> AccountConfig {
+        if config.kill_switch.is_none() {
+            config.kill_switch = deployment.kill_switch;
+        }
+        // The prefix identifies the deployment, not the creator.
+        if deployment.event_prefix.is_some() {
+            config.event_prefix = deployment.event_prefix;
+        }
+        config.factory = deployment.factory;
+        config
+    }
+
+    fn require_not_halted(env: &Env) -> Result<(), Error> {
+        match storage::get_kill_switch(env) {
+            Some(kill_switch)
+                if KillSwitchClient::new(env, &kill_switch).is_halted(&KILL_SWITCH_SUBSYSTEM) =>
+            {
+                Err(Error::Halted)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn require_attested(env: &Env, wasm_hash: &BytesN<32>) -> Result<(), Error> {
+        match storage::get_attestation_registry(env) {
+            Some(registry)
+                if !AttestationRegistryClient::new(env, &registry).is_attested(wasm_hash) =>
+            {
+                Err(Error::WasmNotAttested)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Return the name of the error raised as `Error(Contract, #code)`, or
+    /// `Unknown` for a code this contract never raises.
+    pub fn describe_error(env: Env, code: u32) -> Symbol {
+        let name = Error::try_from(soroban_sdk::Error::from_contract_error(code))
+            .map_or(UNKNOWN_ERROR, Error::name);
+        Symbol::new(&env, name)
+    }
+}
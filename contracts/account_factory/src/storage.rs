@@ -0,0 +1,167 @@
+use soroban_sdk::{contracttype, Address, BytesN, Env, Symbol};
+
+/// Fee the creator pays the admin for every deployment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeploymentFee {
+    /// Token the fee is paid in.  Only a fee in the native XLM contract is
+    /// counted in `get_required_funding`, which is in stroops.
+    pub token: Address,
+    /// Amount charged per deployment.
+    pub amount: i128,
+}
+
+/// Premium the creator pays into an insurance pool for every deployment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsurancePremium {
+    /// `InsurancePool` the premium is paid into and the accounts claim
+    /// with.  The factory must be its factory.
+    pub pool: Address,
+    /// Token the premium is paid in.
+    pub token: Address,
+    /// Amount charged per deployment.
+    pub amount: i128,
+}
+
+/// Storage keys used by the account factory.
+///
+/// The factory only holds a handful of configuration values, all kept in
+/// instance storage.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Address allowed to change the account wasm hash.
+    Admin,
+
+    /// Hash of the uploaded `EphemeralAccountContract` wasm to deploy.
+    WasmHash,
+
+    /// Optional attestation registry consulted before every deployment.
+    AttestationRegistry,
+
+    /// Optional kill switch consulted before every deployment and handed
+    /// to the accounts it deploys.
+    KillSwitch,
+
+    /// Optional event topic prefix stamped into every deployed account.
+    EventPrefix,
+
+    /// Optional [`DeploymentFee`] charged to the creator on every
+    /// deployment.
+    DeploymentFee,
+
+    /// Optional [`InsurancePremium`] charged to the creator on every
+    /// deployment without a config.
+    Insurance,
+}
+
+// Admin
+
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
+
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Admin)
+}
+
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().instance().has(&DataKey::Admin)
+}
+
+// Account wasm
+
+pub fn set_wasm_hash(env: &Env, wasm_hash: &BytesN<32>) {
+    env.storage().instance().set(&DataKey::WasmHash, wasm_hash);
+}
+
+pub fn get_wasm_hash(env: &Env) -> Option<BytesN<32>> {
+    env.storage().instance().get(&DataKey::WasmHash)
+}
+
+// Attestation registry
+
+pub fn set_attestation_registry(env: &Env, registry: &Address) {
+    env.storage()
+        .instance()
+        .set(&DataKey::AttestationRegistry, registry);
+}
+
+pub fn get_attestation_registry(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::AttestationRegistry)
+}
+
+// Kill switch
+
+pub fn set_kill_switch(env: &Env, kill_switch: &Address) {
+    env.storage()
+        .instance()
+        .set(&DataKey::KillSwitch, kill_switch);
+}
+
+pub fn remove_kill_switch(env: &Env) {
+    env.storage().instance().remove(&DataKey::KillSwitch);
+}
+
+pub fn get_kill_switch(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::KillSwitch)
+}
+
+// Event prefix
+
+pub fn set_event_prefix(env: &Env, prefix: &Symbol) {
+    env.storage().instance().set(&DataKey::EventPrefix, prefix);
+}
+
+pub fn remove_event_prefix(env: &Env) {
+    env.storage().instance().remove(&DataKey::EventPrefix);
+}
+
+pub fn get_event_prefix(env: &Env) -> Option<Symbol> {
+    env.storage().instance().get(&DataKey::EventPrefix)
+}
+
+// Deployment fee
+
+pub fn set_deployment_fee(env: &Env, fee: &DeploymentFee) {
+    env.storage().instance().set(&DataKey::DeploymentFee, fee);
+}
+
+pub fn remove_deployment_fee(env: &Env) {
+    env.storage().instance().remove(&DataKey::DeploymentFee);
+}
+
+pub fn get_deployment_fee(env: &Env) -> Option<DeploymentFee> {
+    env.storage().instance().get(&DataKey::DeploymentFee)
+}
+
+// Insurance
+
+pub fn set_insurance(env: &Env, insurance: &InsurancePremium) {
+    env.storage().instance().set(&DataKey::Insurance, insurance);
+}
+
+pub fn remove_insurance(env: &Env) {
+    env.storage().instance().remove(&DataKey::Insurance);
+}
+
+pub fn get_insurance(env: &Env) -> Option<InsurancePremium> {
+    env.storage().instance().get(&DataKey::Insurance)
+}
+
+// TTL management
+
+/// If the remaining TTL drops below this threshold (in ledgers), extend it
+/// (~15 days, half of the extension window).
+const INSTANCE_TTL_THRESHOLD: u32 = 259_200;
+
+/// Extend the instance TTL to this many ledgers (~30 days).
+const INSTANCE_TTL_EXTEND_TO: u32 = 518_400;
+
+/// Keep the factory configuration alive; called from every entry-point.
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND_TO);
+}
@@ -0,0 +1,587 @@
+extern crate std;
+
+use crate::{AccountFactory, AccountFactoryClient, DeploymentFee, Error, InsurancePremium};
+use attestation_registry::{AttestationRegistry, AttestationRegistryClient};
+use bridgelet_shared::errors::{ACCOUNT_FACTORY_ERRORS, UNKNOWN_ERROR};
+use bridgelet_shared::{AccountConfig, AccountStatus, AssetKind, EventTopics};
+use ephemeral_account::{
+    CloseReason, EphemeralAccountContract, EphemeralAccountContractClient, Error as AccountError,
+};
+use insurance_pool::{InsurancePool, InsurancePoolClient};
+use kill_switch::{KillSwitch, KillSwitchClient};
+use reserve_contract::{ReserveContract, ReserveContractClient};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, BytesN, Env, Symbol,
+};
+
+// HELPERS
+
+fn setup() -> (Env, AccountFactoryClient<'static>) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(AccountFactory, ());
+    let client = AccountFactoryClient::new(&env, &contract_id);
+    (env, client)
+}
+
+fn hash(env: &Env, byte: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[byte; 32])
+}
+
+/// Register an attestation registry that attests `attested` as version 1.
+fn setup_registry(env: &Env, attested: &BytesN<32>) -> AttestationRegistryClient<'static> {
+    let registry_id = env.register(AttestationRegistry, ());
+    let registry = AttestationRegistryClient::new(env, &registry_id);
+    registry.initialize(&Address::generate(env));
+    registry.attest(&symbol_short!("account"), &1, attested);
+    registry
+}
+
+//  Initialization
+
+#[test]
+fn test_initialize_stores_config() {
+    let (env, client) = setup();
+    let admin = Address::generate(&env);
+    let wasm_hash = hash(&env, 1);
+
+    client.initialize(&admin, &wasm_hash, &None);
+
+    assert_eq!(client.get_admin(), Some(admin));
+    assert_eq!(client.get_wasm_hash(), Some(wasm_hash));
+    assert_eq!(client.get_attestation_registry(), None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #201)")]
+fn test_initialize_twice_panics() {
+    let (env, client) = setup();
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &hash(&env, 1), &None);
+    client.initialize(&admin, &hash(&env, 1), &None);
+}
+
+/// An unattested wasm hash is rejected at initialization (error #3).
+#[test]
+#[should_panic(expected = "Error(Contract, #203)")]
+fn test_initialize_rejects_unattested_wasm() {
+    let (env, client) = setup();
+    let registry = setup_registry(&env, &hash(&env, 1));
+    client.initialize(
+        &Address::generate(&env),
+        &hash(&env, 2),
+        &Some(registry.address),
+    );
+}
+
+//  Wasm updates
+
+#[test]
+fn test_set_wasm_hash_checks_attestation() {
+    let (env, client) = setup();
+    let registry = setup_registry(&env, &hash(&env, 1));
+    client.initialize(
+        &Address::generate(&env),
+        &hash(&env, 1),
+        &Some(registry.address.clone()),
+    );
+
+    assert_eq!(
+        client.try_set_wasm_hash(&hash(&env, 2)),
+        Err(Ok(crate::Error::WasmNotAttested))
+    );
+
+    registry.attest(&symbol_short!("account"), &2, &hash(&env, 2));
+    client.set_wasm_hash(&hash(&env, 2));
+    assert_eq!(client.get_wasm_hash(), Some(hash(&env, 2)));
+}
+
+/// Updating the wasm before initialization must fail with error #2.
+#[test]
+#[should_panic(expected = "Error(Contract, #202)")]
+fn test_set_wasm_hash_before_initialize_panics() {
+    let (env, client) = setup();
+    client.set_wasm_hash(&hash(&env, 1));
+}
+
+//  Deployment
+
+/// Deploying before initialization must fail with error #2.
+#[test]
+#[should_panic(expected = "Error(Contract, #202)")]
+fn test_deploy_before_initialize_panics() {
+    let (env, client) = setup();
+    client.deploy(
+        &Address::generate(&env),
+        &hash(&env, 9),
+        &1000,
+        &Address::generate(&env),
+        &None,
+        &false,
+    );
+}
+
+/// A revoked wasm hash stops further deployments (error #3).
+#[test]
+fn test_deploy_rejects_revoked_wasm() {
+    let (env, client) = setup();
+    let registry = setup_registry(&env, &hash(&env, 1));
+    client.initialize(
+        &Address::generate(&env),
+        &hash(&env, 1),
+        &Some(registry.address.clone()),
+    );
+    registry.revoke(&hash(&env, 1));
+
+    let result = client.try_deploy(
+        &Address::generate(&env),
+        &hash(&env, 9),
+        &1000,
+        &Address::generate(&env),
+        &None,
+        &false,
+    );
+    assert_eq!(result, Err(Ok(crate::Error::WasmNotAttested)));
+}
+
+#[test]
+fn test_account_address_is_deterministic_per_creator() {
+    let (env, client) = setup();
+    let creator = Address::generate(&env);
+    let other_creator = Address::generate(&env);
+    let salt = hash(&env, 9);
+
+    let address = client.get_account_address(&creator, &salt);
+    assert_eq!(client.get_account_address(&creator, &salt), address);
+    assert_ne!(
+        client.get_account_address(&creator, &hash(&env, 8)),
+        address
+    );
+    assert_ne!(client.get_account_address(&other_creator, &salt), address);
+}
+
+/// A halted factory refuses to deploy (error #4).
+#[test]
+fn test_deploy_rejected_while_halted() {
+    let (env, client) = setup();
+    client.initialize(&Address::generate(&env), &hash(&env, 1), &None);
+
+    let kill_switch_id = env.register(KillSwitch, ());
+    let kill_switch = KillSwitchClient::new(&env, &kill_switch_id);
+    kill_switch.initialize(&Address::generate(&env));
+    kill_switch.set_halted(&symbol_short!("factory"), &true);
+
+    client.set_kill_switch(&Some(kill_switch_id.clone()));
+    assert_eq!(client.get_kill_switch(), Some(kill_switch_id));
+
+    let result = client.try_deploy(
+        &Address::generate(&env),
+        &hash(&env, 9),
+        &1000,
+        &Address::generate(&env),
+        &None,
+        &false,
+    );
+    assert_eq!(result, Err(Ok(crate::Error::Halted)));
+
+    client.set_kill_switch(&None);
+    assert_eq!(client.get_kill_switch(), None);
+}
+
+/// A settled account naming the factory is reset and can be paid again.
+#[test]
+fn test_recycle_resets_settled_account() {
+    let (env, client) = setup();
+    client.initialize(&Address::generate(&env), &hash(&env, 1), &None);
+
+    let account =
+        EphemeralAccountContractClient::new(&env, &env.register(EphemeralAccountContract, ()));
+    account.initialize_with_config(
+        &Address::generate(&env),
+        &(env.ledger().sequence() + 100),
+        &Address::generate(&env),
+        &AccountConfig {
+            factory: Some(client.address.clone()),
+            ..Default::default()
+        },
+    );
+    account.cancel(&CloseReason::CreatorCancelled);
+
+    let recovery = Address::generate(&env);
+    client.recycle(
+        &account.address,
+        &(env.ledger().sequence() + 500),
+        &recovery,
+    );
+    assert_eq!(account.get_status(), AccountStatus::Active);
+    assert_eq!(account.get_info().recovery_address, recovery);
+    assert_eq!(account.get_epoch(), 1);
+}
+
+//  Funding
+
+/// The funding quote is the reserve an account initialized with the same
+/// config goes on to track.
+#[test]
+fn test_required_funding_matches_tracked_reserve() {
+    let (env, client) = setup();
+    assert_eq!(
+        client.get_required_funding(&Default::default()),
+        1_000_000_000
+    );
+    assert_eq!(
+        client.get_required_funding(&AccountConfig {
+            base_reserve: Some(40_000_000),
+            ..Default::default()
+        }),
+        40_000_000
+    );
+    assert_eq!(
+        client.try_get_required_funding(&AccountConfig {
+            base_reserve: Some(-1),
+            ..Default::default()
+        }),
+        Err(Ok(crate::Error::InvalidAmount))
+    );
+
+    let reserve_id = env.register(ReserveContract, ());
+    let reserve = ReserveContractClient::new(&env, &reserve_id);
+    let admin = Address::generate(&env);
+    reserve.initialize(&admin);
+    reserve.set_base_reserve(&admin, &25_000_000);
+    reserve.set_trustline_reserve(&admin, &5_000_000);
+    let config = AccountConfig {
+        reserve_contract: Some(reserve_id),
+        trustlines: 2,
+        ..Default::default()
+    };
+    assert_eq!(client.get_required_funding(&config), 35_000_000);
+
+    let account =
+        EphemeralAccountContractClient::new(&env, &env.register(EphemeralAccountContract, ()));
+    account.initialize_with_config(
+        &Address::generate(&env),
+        &(env.ledger().sequence() + 1000),
+        &Address::generate(&env),
+        &config,
+    );
+    assert_eq!(account.get_reserve_remaining(), 35_000_000);
+}
+
+/// A deployment fee in XLM is part of what the operator must fund.
+#[test]
+fn test_required_funding_includes_deployment_fee() {
+    let (env, client) = setup();
+    client.initialize(&Address::generate(&env), &hash(&env, 1), &None);
+    let fee = DeploymentFee {
+        token: AssetKind::native_address(&env),
+        amount: 5_000_000,
+    };
+
+    client.set_deployment_fee(&Some(fee.clone()));
+    assert_eq!(client.get_deployment_fee(), Some(fee));
+    assert_eq!(
+        client.get_required_funding(&Default::default()),
+        1_005_000_000
+    );
+
+    client.set_deployment_fee(&None);
+    assert_eq!(client.get_deployment_fee(), None);
+    assert_eq!(
+        client.get_required_funding(&Default::default()),
+        1_000_000_000
+    );
+}
+
+/// A fee in another token does not add up with stroops, so it is left
+/// out of the funding and reported on its own.
+#[test]
+fn test_required_funding_leaves_out_non_native_fee() {
+    let (env, client) = setup();
+    client.initialize(&Address::generate(&env), &hash(&env, 1), &None);
+    let fee = DeploymentFee {
+        token: env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address(),
+        amount: 5_000_000,
+    };
+
+    client.set_deployment_fee(&Some(fee.clone()));
+    assert_eq!(
+        client.get_required_funding(&Default::default()),
+        1_000_000_000
+    );
+    assert_eq!(client.get_deployment_fee(), Some(fee));
+}
+
+#[test]
+fn test_set_deployment_fee_rejects_negative_amount() {
+    let (env, client) = setup();
+    client.initialize(&Address::generate(&env), &hash(&env, 1), &None);
+    let result = client.try_set_deployment_fee(&Some(DeploymentFee {
+        token: Address::generate(&env),
+        amount: -1,
+    }));
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+}
+
+/// Upload the account wasm `scripts/check-size.sh` builds before running
+/// the ignored tests below: first the build with only `reuse`, then the
+/// deployable build.
+fn upload_built_account_wasm(env: &Env) -> BytesN<32> {
+    let wasm = std::fs::read(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../target/wasm32-unknown-unknown/release/ephemeral_account.wasm"
+    ))
+    .expect("build the account wasm for wasm32-unknown-unknown first");
+    env.deployer().upload_contract_wasm(wasm.as_slice())
+}
+
+/// Neither built account wasm has `initialize_with_config`; the factory
+/// deploys it with plain `initialize`, or
+/// `initialize_with_deployment` to recycle it later, and charges the
+/// deployment fee.
+#[test]
+#[ignore = "needs a built account wasm; run by scripts/check-size.sh"]
+fn test_deploy_and_recycle_built_account_wasm() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let wasm_hash = upload_built_account_wasm(&env);
+
+    let client = AccountFactoryClient::new(&env, &env.register(AccountFactory, ()));
+    let admin = Address::generate(&env);
+    client.initialize(&admin, &wasm_hash, &None);
+
+    let xlm = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let creator = Address::generate(&env);
+    StellarAssetClient::new(&env, &xlm.address()).mint(&creator, &5_000_000);
+    client.set_deployment_fee(&Some(DeploymentFee {
+        token: xlm.address(),
+        amount: 5_000_000,
+    }));
+
+    let salt = hash(&env, 9);
+    let recovery = Address::generate(&env);
+    let expiry = env.ledger().sequence() + 1000;
+    let address = client.deploy(&creator, &salt, &expiry, &recovery, &None, &false);
+    assert_eq!(address, client.get_account_address(&creator, &salt));
+
+    let account = EphemeralAccountContractClient::new(&env, &address);
+    assert_eq!(account.get_status(), AccountStatus::Active);
+    let info = account.get_info();
+    assert_eq!(info.creator, creator);
+    assert_eq!(info.recovery_address, recovery);
+    assert_eq!(info.expiry_ledger, expiry);
+
+    let xlm = TokenClient::new(&env, &xlm.address());
+    assert_eq!(xlm.balance(&creator), 0);
+    assert_eq!(xlm.balance(&admin), 5_000_000);
+
+    client.set_deployment_fee(&None);
+    let address = client.deploy(&creator, &hash(&env, 10), &expiry, &recovery, &None, &true);
+    let account = EphemeralAccountContractClient::new(&env, &address);
+    env.ledger()
+        .with_mut(|ledger| ledger.sequence_number = expiry);
+    account.expire();
+
+    let next_recovery = Address::generate(&env);
+    client.recycle(&address, &(expiry + 1000), &next_recovery);
+    assert_eq!(account.get_status(), AccountStatus::Active);
+    assert_eq!(account.get_info().recovery_address, next_recovery);
+}
+
+/// The factory's kill switch reaches accounts of the built wasm, stamped
+/// in through `initialize_with_deployment`.
+#[test]
+#[ignore = "needs a built account wasm; run by scripts/check-size.sh"]
+fn test_built_account_wasm_honours_kill_switch() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let wasm_hash = upload_built_account_wasm(&env);
+
+    let client = AccountFactoryClient::new(&env, &env.register(AccountFactory, ()));
+    client.initialize(&Address::generate(&env), &wasm_hash, &None);
+    let kill_switch_id = env.register(KillSwitch, ());
+    let kill_switch = KillSwitchClient::new(&env, &kill_switch_id);
+    kill_switch.initialize(&Address::generate(&env));
+    client.set_kill_switch(&Some(kill_switch_id));
+
+    let expiry = env.ledger().sequence() + 1000;
+    let address = client.deploy(
+        &Address::generate(&env),
+        &hash(&env, 9),
+        &expiry,
+        &Address::generate(&env),
+        &None,
+        &true,
+    );
+    let account = EphemeralAccountContractClient::new(&env, &address);
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    StellarAssetClient::new(&env, &asset.address()).mint(&address, &100);
+
+    kill_switch.set_halted(&symbol_short!("account"), &true);
+    assert_eq!(
+        account.try_record_payment(&100, &asset.address()),
+        Err(Ok(AccountError::Halted))
+    );
+
+    kill_switch.set_halted(&symbol_short!("account"), &false);
+    account.record_payment(&100, &asset.address());
+    assert_eq!(account.get_status(), AccountStatus::PaymentReceived);
+}
+
+/// The factory's event prefix reaches accounts of the built wasm, stamped
+/// in through `initialize_with_deployment`.
+#[test]
+#[ignore = "needs a built account wasm; run by scripts/check-size.sh"]
+fn test_built_account_wasm_takes_event_prefix() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let wasm_hash = upload_built_account_wasm(&env);
+
+    let client = AccountFactoryClient::new(&env, &env.register(AccountFactory, ()));
+    client.initialize(&Address::generate(&env), &wasm_hash, &None);
+    client.set_event_prefix(&Some(symbol_short!("acme_prd")));
+
+    let address = client.deploy(
+        &Address::generate(&env),
+        &hash(&env, 9),
+        &(env.ledger().sequence() + 1000),
+        &Address::generate(&env),
+        &None,
+        &false,
+    );
+
+    let (_, topics, _) = env
+        .events()
+        .all()
+        .iter()
+        .find(|(contract, _, _)| *contract == address)
+        .expect("the account emits `created`");
+    assert_eq!(
+        EventTopics::decode(&env, &topics),
+        Some(
+            EventTopics::with_namespace(symbol_short!("acme_prd"), symbol_short!("created"))
+                .with_seq(1)
+                .with_epoch(0)
+        )
+    );
+}
+
+/// The creator of an account of the built wasm pays the premium into the
+/// pool, and the account claims with the pool when it expires unswept.
+#[test]
+#[ignore = "needs a built account wasm; run by scripts/check-size.sh"]
+fn test_built_account_wasm_is_insured() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let wasm_hash = upload_built_account_wasm(&env);
+
+    let client = AccountFactoryClient::new(&env, &env.register(AccountFactory, ()));
+    client.initialize(&Address::generate(&env), &wasm_hash, &None);
+    let pool = InsurancePoolClient::new(&env, &env.register(InsurancePool, ()));
+    pool.initialize(&Address::generate(&env));
+    pool.set_factory(&Some(client.address.clone()));
+
+    let xlm = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let creator = Address::generate(&env);
+    StellarAssetClient::new(&env, &xlm.address()).mint(&creator, &1_000);
+    client.set_insurance(&Some(InsurancePremium {
+        pool: pool.address.clone(),
+        token: xlm.address(),
+        amount: 1_000,
+    }));
+
+    let expiry = env.ledger().sequence() + 1000;
+    let address = client.deploy(
+        &creator,
+        &hash(&env, 9),
+        &expiry,
+        &Address::generate(&env),
+        &None,
+        &false,
+    );
+    let xlm = TokenClient::new(&env, &xlm.address());
+    assert_eq!(xlm.balance(&creator), 0);
+    assert_eq!(xlm.balance(&pool.address), 1_000);
+    assert_eq!(pool.get_policy(&address).unwrap().premium, 1_000);
+
+    let account = EphemeralAccountContractClient::new(&env, &address);
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    StellarAssetClient::new(&env, &asset.address()).mint(&address, &100);
+    account.record_payment(&100, &asset.address());
+    env.ledger()
+        .with_mut(|ledger| ledger.sequence_number = expiry);
+    account.expire();
+
+    let claim = pool.get_claim(&0).expect("the expiry opens a claim");
+    assert_eq!(claim.account, address);
+    assert_eq!(claim.amount, 100);
+}
+
+#[test]
+fn test_set_insurance() {
+    let (env, client) = setup();
+    client.initialize(&Address::generate(&env), &hash(&env, 1), &None);
+    assert_eq!(client.get_insurance(), None);
+
+    let insurance = InsurancePremium {
+        pool: Address::generate(&env),
+        token: Address::generate(&env),
+        amount: 1_000,
+    };
+    client.set_insurance(&Some(insurance.clone()));
+    assert_eq!(client.get_insurance(), Some(insurance.clone()));
+
+    assert_eq!(
+        client.try_set_insurance(&Some(InsurancePremium {
+            amount: -1,
+            ..insurance
+        })),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    client.set_insurance(&None);
+    assert_eq!(client.get_insurance(), None);
+}
+
+#[test]
+fn test_set_event_prefix() {
+    let (env, client) = setup();
+    client.initialize(&Address::generate(&env), &hash(&env, 1), &None);
+    assert_eq!(client.get_event_prefix(), None);
+
+    client.set_event_prefix(&Some(symbol_short!("acme_prd")));
+    assert_eq!(client.get_event_prefix(), Some(symbol_short!("acme_prd")));
+
+    client.set_event_prefix(&None);
+    assert_eq!(client.get_event_prefix(), None);
+}
+
+/// Changing the prefix before initialization must fail with error #2.
+#[test]
+#[should_panic(expected = "Error(Contract, #202)")]
+fn test_set_event_prefix_before_initialize_panics() {
+    let (_, client) = setup();
+    client.set_event_prefix(&Some(symbol_short!("acme_prd")));
+}
+
+/// Codes stay inside the factory's shared range, so off-chain tooling can
+/// tell them from another contract's.
+#[test]
+fn test_error_codes_use_reserved_range() {
+    let env = Env::default();
+    let client = AccountFactoryClient::new(&env, &env.register(AccountFactory, ()));
+    for code in ACCOUNT_FACTORY_ERRORS {
+        let name = client.describe_error(&code);
+        if let Ok(error) = Error::try_from(soroban_sdk::Error::from_contract_error(code)) {
+            assert_eq!(name, Symbol::new(&env, error.name()));
+        } else {
+            assert_eq!(name, Symbol::new(&env, UNKNOWN_ERROR));
+        }
+    }
+    assert_eq!(client.describe_error(&1), Symbol::new(&env, UNKNOWN_ERROR));
+}
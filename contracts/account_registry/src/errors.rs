@@ -0,0 +1,44 @@
+use soroban_sdk::contracterror;
+
+/// Codes sit in [`bridgelet_shared::errors::ACCOUNT_REGISTRY_ERRORS`], so
+/// they never collide with another Bridgelet contract's.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// The account has already been registered; tags are fixed at deployment.
+    AlreadyRegistered = 301,
+
+    /// More than [`MAX_TAGS`](crate::MAX_TAGS) tags were supplied.
+    TooManyTags = 302,
+
+    /// The same tag appears more than once in the supplied list.
+    DuplicateTag = 303,
+
+    /// The account reports a different creator than the one registering it.
+    ///
+    /// Prevents third parties from attaching tags to accounts they did not
+    /// create.
+    CreatorMismatch = 304,
+
+    /// More accounts were passed to a batch read than
+    /// [`MAX_BATCH_SIZE`](crate::MAX_BATCH_SIZE) allows.
+    BatchTooLarge = 305,
+
+    /// The supplied pagination cursor was not issued by this contract.
+    InvalidCursor = 306,
+}
+
+impl Error {
+    /// Name of the variant, as `describe_error` reports it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Error::AlreadyRegistered => "AlreadyRegistered",
+            Error::TooManyTags => "TooManyTags",
+            Error::DuplicateTag => "DuplicateTag",
+            Error::CreatorMismatch => "CreatorMismatch",
+            Error::BatchTooLarge => "BatchTooLarge",
+            Error::InvalidCursor => "InvalidCursor",
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
+
+// ─── Event payloads ─────────────────────────────────────────────────────────
+
+/// Emitted once when an ephemeral account is registered with its tags.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountRegistered {
+    pub account: Address,
+    pub creator: Address,
+    pub tags: Vec<Symbol>,
+}
+
+// ─── Emit helpers ───────────────────────────────────────────────────────────
+
+/// Publish the `registered` event.
+pub fn emit_account_registered(env: &Env, account: Address, creator: Address, tags: Vec<Symbol>) {
+    let event = AccountRegistered {
+        account,
+        creator,
+        tags,
+    };
+    env.events().publish((symbol_short!("register"),), event);
+}
@@ -0,0 +1,276 @@
+#![no_std]
+
+mod errors;
+mod events;
+mod storage;
+#[cfg(test)]
+mod test;
+
+use bridgelet_shared::errors::UNKNOWN_ERROR;
+use bridgelet_shared::{page_bounds, AccountStatus, AddressPage, Cursor, EphemeralAccountClient};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Vec};
+
+pub use errors::Error;
+pub use events::AccountRegistered;
+pub use storage::DataKey;
+
+/// Maximum number of tags that may be attached to a single account.
+pub const MAX_TAGS: u32 = 5;
+
+/// Maximum number of entries returned by a single page of a listing.
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of accounts accepted by a single batch read.
+///
+/// Each account costs two cross-contract calls, so this keeps a batch well
+/// inside the per-transaction budget.
+pub const MAX_BATCH_SIZE: u32 = 25;
+
+/// Reserve state of one ephemeral account, as returned by [`AccountRegistry::get_reserves`].
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountReserve {
+    pub account: Address,
+    pub reserve_remaining: i128,
+    pub reserve_reclaimed: bool,
+}
+
+/// Lifecycle summary of one ephemeral account, as returned by
+/// [`AccountRegistry::get_summaries`].
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountSummary {
+    pub account: Address,
+    pub status: AccountStatus,
+    pub expiry_ledger: u32,
+    pub payment_count: u32,
+}
+
+/// On-chain index of deployed ephemeral accounts.
+///
+/// Creators attach a small set of `Symbol` tags (store id, region, sales
+/// channel, ...) when an account is deployed.  The registry keeps a
+/// per-creator and a per-tag index so merchant dashboards and fleet
+/// tooling can enumerate and segment accounts without replaying every
+/// ledger.
+#[contract]
+pub struct AccountRegistry;
+
+#[contractimpl]
+impl AccountRegistry {
+    /// Register an ephemeral account together with its tags.
+    ///
+    /// Must be authorized by `creator`, who must also be the creator recorded
+    /// by the account itself.  Tags are fixed once registered.
+    ///
+    /// # Errors
+    /// * [`Error::AlreadyRegistered`] – the account was registered before.
+    /// * [`Error::TooManyTags`]       – more than [`MAX_TAGS`] tags supplied.
+    /// * [`Error::DuplicateTag`]      – a tag appears more than once.
+    /// * [`Error::CreatorMismatch`]   – `creator` did not create `account`.
+    pub fn register_account(
+        env: Env,
+        creator: Address,
+        account: Address,
+        tags: Vec<Symbol>,
+    ) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        if storage::is_registered(&env, &account) {
+            return Err(Error::AlreadyRegistered);
+        }
+
+        if tags.len() > MAX_TAGS {
+            return Err(Error::TooManyTags);
+        }
+        for (i, tag) in tags.iter().enumerate() {
+            if tags.iter().skip(i + 1).any(|other| other == tag) {
+                return Err(Error::DuplicateTag);
+            }
+        }
+
+        creator.require_auth();
+
+        let info = EphemeralAccountClient::new(&env, &account).get_info();
+        if info.creator != creator {
+            return Err(Error::CreatorMismatch);
+        }
+
+        storage::set_account_tags(&env, &account, &tags);
+        storage::push_creator_entry(&env, &creator, &account);
+        for tag in tags.iter() {
+            storage::push_tag_entry(&env, &tag, &account);
+        }
+
+        events::emit_account_registered(&env, account, creator, tags);
+
+        Ok(())
+    }
+
+    /// Tags attached to `account`, or an empty list if it is not registered.
+    pub fn get_tags(env: Env, account: Address) -> Vec<Symbol> {
+        storage::extend_instance_ttl(&env);
+        storage::get_account_tags(&env, &account).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Number of accounts registered by `creator`.
+    pub fn count_accounts(env: Env, creator: Address) -> u32 {
+        storage::extend_instance_ttl(&env);
+        storage::get_creator_count(&env, &creator)
+    }
+
+    /// List accounts registered by `creator`, in registration order.
+    ///
+    /// Paginated like [`Self::list_accounts_by_tag`]; pair with
+    /// [`Self::get_summaries`] to fetch their status.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidCursor`] – `cursor` was not issued by this contract.
+    pub fn list_accounts(
+        env: Env,
+        creator: Address,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<AddressPage, Error> {
+        storage::extend_instance_ttl(&env);
+
+        let count = storage::get_creator_count(&env, &creator);
+        paginate(&env, cursor, limit, count, |position| {
+            storage::get_creator_entry(&env, &creator, position)
+        })
+    }
+
+    /// Number of accounts indexed under `tag`.
+    pub fn count_accounts_by_tag(env: Env, tag: Symbol) -> u32 {
+        storage::extend_instance_ttl(&env);
+        storage::get_tag_count(&env, &tag)
+    }
+
+    /// List accounts carrying `tag`, in registration order.
+    ///
+    /// Pass `None` to start from the beginning and the returned
+    /// `next_cursor` to continue; it is `None` once the index is exhausted.
+    /// `limit` is capped at [`MAX_PAGE_SIZE`].
+    ///
+    /// # Errors
+    /// * [`Error::InvalidCursor`] – `cursor` was not issued by this contract.
+    pub fn list_accounts_by_tag(
+        env: Env,
+        tag: Symbol,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<AddressPage, Error> {
+        storage::extend_instance_ttl(&env);
+
+        let count = storage::get_tag_count(&env, &tag);
+        paginate(&env, cursor, limit, count, |position| {
+            storage::get_tag_entry(&env, &tag, position)
+        })
+    }
+
+    /// Read the reserve state of several accounts in one invocation.
+    ///
+    /// Cross-calls `get_reserve_remaining` and `is_reserve_reclaimed` on
+    /// each account, returning results in input order, so reserve-recovery
+    /// tooling needs one simulation instead of one per account.
+    ///
+    /// # Errors
+    /// * [`Error::BatchTooLarge`] – more than [`MAX_BATCH_SIZE`] accounts.
+    pub fn get_reserves(env: Env, accounts: Vec<Address>) -> Result<Vec<AccountReserve>, Error> {
+        storage::extend_instance_ttl(&env);
+
+        if accounts.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let mut reserves = Vec::new(&env);
+        for account in accounts.iter() {
+            let client = EphemeralAccountClient::new(&env, &account);
+            reserves.push_back(AccountReserve {
+                reserve_remaining: client.get_reserve_remaining(),
+                reserve_reclaimed: client.is_reserve_reclaimed(),
+                account,
+            });
+        }
+        Ok(reserves)
+    }
+
+    /// Read the status of several accounts in one invocation.
+    ///
+    /// Cross-calls `get_status` on each account and returns the statuses in
+    /// input order, so monitoring can poll a fleet with one simulation per
+    /// batch.
+    ///
+    /// # Errors
+    /// * [`Error::BatchTooLarge`] – more than [`MAX_BATCH_SIZE`] accounts.
+    pub fn get_statuses(env: Env, accounts: Vec<Address>) -> Result<Vec<AccountStatus>, Error> {
+        storage::extend_instance_ttl(&env);
+
+        if accounts.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let mut statuses = Vec::new(&env);
+        for account in accounts.iter() {
+            statuses.push_back(EphemeralAccountClient::new(&env, &account).get_status());
+        }
+        Ok(statuses)
+    }
+
+    /// Read the lifecycle summary of several accounts in one invocation.
+    ///
+    /// Cross-calls `get_info` on each account and returns results in input
+    /// order.
+    ///
+    /// # Errors
+    /// * [`Error::BatchTooLarge`] – more than [`MAX_BATCH_SIZE`] accounts.
+    pub fn get_summaries(env: Env, accounts: Vec<Address>) -> Result<Vec<AccountSummary>, Error> {
+        storage::extend_instance_ttl(&env);
+
+        if accounts.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let mut summaries = Vec::new(&env);
+        for account in accounts.iter() {
+            let info = EphemeralAccountClient::new(&env, &account).get_info();
+            summaries.push_back(AccountSummary {
+                account,
+                status: info.status,
+                expiry_ledger: info.expiry_ledger,
+                payment_count: info.payment_count,
+            });
+        }
+        Ok(summaries)
+    }
+
+    /// Return the name of the error raised as `Error(Contract, #code)`, or
+    /// `Unknown` for a code this contract never raises.
+    pub fn describe_error(env: Env, code: u32) -> Symbol {
+        let name = Error::try_from(soroban_sdk::Error::from_contract_error(code))
+            .map_or(UNKNOWN_ERROR, Error::name);
+        Symbol::new(&env, name)
+    }
+}
+
+/// Build one page of an index holding `count` entries, read through
+/// `entry_at`.  `limit` is capped at [`MAX_PAGE_SIZE`].
+fn paginate(
+    env: &Env,
+    cursor: Option<Cursor>,
+    limit: u32,
+    count: u32,
+    entry_at: impl Fn(u32) -> Option<Address>,
+) -> Result<AddressPage, Error> {
+    let (positions, next_cursor) =
+        page_bounds(env, cursor, limit.min(MAX_PAGE_SIZE), 0..count).ok_or(Error::InvalidCursor)?;
+
+    let mut items = Vec::new(env);
+    for position in positions {
+        if let Some(account) = entry_at(position) {
+            items.push_back(account);
+        }
+    }
+
+    Ok(AddressPage { items, next_cursor })
+}
@@ -0,0 +1,132 @@
+use soroban_sdk::{contracttype, Address, Env, Symbol, TryFromVal, Val, Vec};
+
+/// Storage keys used by the account registry.
+///
+/// Tag and creator indexes are kept as one persistent entry per
+/// `(key, position)` pair rather than a single growing vector, so paging
+/// through a large index only loads the entries that are actually returned.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Tags attached to a registered account.
+    AccountTags(Address),
+
+    /// Number of accounts indexed under a tag.
+    TagCount(Symbol),
+
+    /// Account stored at a given position of a tag index.
+    TagEntry(Symbol, u32),
+
+    /// Number of accounts registered by a creator.
+    CreatorCount(Address),
+
+    /// Account stored at a given position of a creator's index.
+    CreatorEntry(Address, u32),
+}
+
+// Account tags
+
+/// Returns `true` if the account has already been registered.
+pub fn is_registered(env: &Env, account: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::AccountTags(account.clone()))
+}
+
+/// Persist the tags attached to an account.
+pub fn set_account_tags(env: &Env, account: &Address, tags: &Vec<Symbol>) {
+    let key = DataKey::AccountTags(account.clone());
+    env.storage().persistent().set(&key, tags);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Read the tags attached to an account, if it is registered.
+pub fn get_account_tags(env: &Env, account: &Address) -> Option<Vec<Symbol>> {
+    read_persistent(env, &DataKey::AccountTags(account.clone()))
+}
+
+// Tag index
+
+/// Number of accounts indexed under `tag`.
+pub fn get_tag_count(env: &Env, tag: &Symbol) -> u32 {
+    read_persistent(env, &DataKey::TagCount(tag.clone())).unwrap_or(0)
+}
+
+/// Append `account` to the index for `tag`.
+pub fn push_tag_entry(env: &Env, tag: &Symbol, account: &Address) {
+    let position = get_tag_count(env, tag);
+
+    let entry_key = DataKey::TagEntry(tag.clone(), position);
+    env.storage().persistent().set(&entry_key, account);
+    extend_persistent_ttl(env, &entry_key);
+
+    let count_key = DataKey::TagCount(tag.clone());
+    env.storage().persistent().set(&count_key, &(position + 1));
+    extend_persistent_ttl(env, &count_key);
+}
+
+/// Read the account stored at `position` of the index for `tag`.
+pub fn get_tag_entry(env: &Env, tag: &Symbol, position: u32) -> Option<Address> {
+    read_persistent(env, &DataKey::TagEntry(tag.clone(), position))
+}
+
+// Creator index
+
+/// Number of accounts registered by `creator`.
+pub fn get_creator_count(env: &Env, creator: &Address) -> u32 {
+    read_persistent(env, &DataKey::CreatorCount(creator.clone())).unwrap_or(0)
+}
+
+/// Append `account` to the index for `creator`.
+pub fn push_creator_entry(env: &Env, creator: &Address, account: &Address) {
+    let position = get_creator_count(env, creator);
+
+    let entry_key = DataKey::CreatorEntry(creator.clone(), position);
+    env.storage().persistent().set(&entry_key, account);
+    extend_persistent_ttl(env, &entry_key);
+
+    let count_key = DataKey::CreatorCount(creator.clone());
+    env.storage().persistent().set(&count_key, &(position + 1));
+    extend_persistent_ttl(env, &count_key);
+}
+
+/// Read the account stored at `position` of the index for `creator`.
+pub fn get_creator_entry(env: &Env, creator: &Address, position: u32) -> Option<Address> {
+    read_persistent(env, &DataKey::CreatorEntry(creator.clone(), position))
+}
+
+// TTL management
+
+/// If the remaining TTL drops below this threshold (in ledgers), extend it.
+/// 259 200 ledgers ≈ 15 days, half of the extension window.
+const TTL_THRESHOLD: u32 = 259_200;
+
+/// Extend TTLs to this many ledgers.
+/// 518 400 ledgers ≈ 30 days (at ~5 s per ledger).
+const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Keep the contract instance alive; called from every public entry-point.
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+/// Extend a persistent entry after it is written or read.
+///
+/// Entries are only renewed when touched, so reads extend them as well:
+/// an index that is queried but rarely written must not be archived.
+fn extend_persistent_ttl(env: &Env, key: &DataKey) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+/// Read a persistent entry, extending it if present.
+fn read_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        extend_persistent_ttl(env, key);
+    }
+    value
+}
@@ -0,0 +1,391 @@
+extern crate std;
+
+use crate::{AccountRegistry, AccountRegistryClient, Error};
+use bridgelet_shared::errors::{ACCOUNT_REGISTRY_ERRORS, UNKNOWN_ERROR};
+use bridgelet_shared::AccountStatus;
+use ed25519_dalek::{Signer, SigningKey};
+use ephemeral_account::{EphemeralAccountContract, EphemeralAccountContractClient};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    token::StellarAssetClient,
+    vec, Address, BytesN, Env, Symbol, Vec,
+};
+
+// HELPERS
+
+fn setup() -> (Env, AccountRegistryClient<'static>) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let registry_id = env.register(AccountRegistry, ());
+    let client = AccountRegistryClient::new(&env, &registry_id);
+    (env, client)
+}
+
+/// Deploy and initialize an ephemeral account owned by `creator`.
+fn deploy_account(env: &Env, creator: &Address) -> Address {
+    let account_id = env.register(EphemeralAccountContract, ());
+    let account = EphemeralAccountContractClient::new(env, &account_id);
+    let recovery = Address::generate(env);
+    account.initialize(creator, &(env.ledger().sequence() + 1000), &recovery);
+    account_id
+}
+
+//  Registration
+
+#[test]
+fn test_register_account_stores_tags() {
+    let (env, client) = setup();
+    let creator = Address::generate(&env);
+    let account = deploy_account(&env, &creator);
+    let tags = vec![&env, symbol_short!("store_12"), symbol_short!("eu_west")];
+
+    client.register_account(&creator, &account, &tags);
+
+    assert_eq!(client.get_tags(&account), tags);
+    assert_eq!(client.count_accounts_by_tag(&symbol_short!("store_12")), 1);
+    assert_eq!(client.count_accounts_by_tag(&symbol_short!("eu_west")), 1);
+}
+
+#[test]
+fn test_get_tags_unregistered_is_empty() {
+    let (env, client) = setup();
+    let account = Address::generate(&env);
+    assert_eq!(client.get_tags(&account).len(), 0);
+}
+
+/// Registering the same account twice must fail with error #1.
+#[test]
+#[should_panic(expected = "Error(Contract, #301)")]
+fn test_register_twice_panics() {
+    let (env, client) = setup();
+    let creator = Address::generate(&env);
+    let account = deploy_account(&env, &creator);
+    let tags = vec![&env, symbol_short!("pos")];
+
+    client.register_account(&creator, &account, &tags);
+    client.register_account(&creator, &account, &tags);
+}
+
+/// More than MAX_TAGS tags must be rejected with error #2.
+#[test]
+#[should_panic(expected = "Error(Contract, #302)")]
+fn test_too_many_tags_rejected() {
+    let (env, client) = setup();
+    let creator = Address::generate(&env);
+    let account = deploy_account(&env, &creator);
+    let tags = vec![
+        &env,
+        symbol_short!("a"),
+        symbol_short!("b"),
+        symbol_short!("c"),
+        symbol_short!("d"),
+        symbol_short!("e"),
+        symbol_short!("f"),
+    ];
+
+    client.register_account(&creator, &account, &tags);
+}
+
+/// Duplicate tags must be rejected with error #3.
+#[test]
+#[should_panic(expected = "Error(Contract, #303)")]
+fn test_duplicate_tags_rejected() {
+    let (env, client) = setup();
+    let creator = Address::generate(&env);
+    let account = deploy_account(&env, &creator);
+    let tags = vec![&env, symbol_short!("web"), symbol_short!("web")];
+
+    client.register_account(&creator, &account, &tags);
+}
+
+/// Only the account's own creator may register it (error #4).
+#[test]
+#[should_panic(expected = "Error(Contract, #304)")]
+fn test_register_by_other_creator_rejected() {
+    let (env, client) = setup();
+    let creator = Address::generate(&env);
+    let intruder = Address::generate(&env);
+    let account = deploy_account(&env, &creator);
+
+    client.register_account(&intruder, &account, &vec![&env, symbol_short!("web")]);
+}
+
+//  Listing
+
+#[test]
+fn test_list_accounts_by_tag_paginates() {
+    let (env, client) = setup();
+    let creator = Address::generate(&env);
+    let tag = symbol_short!("region1");
+
+    let mut registered: Vec<Address> = Vec::new(&env);
+    for _ in 0..5 {
+        let account = deploy_account(&env, &creator);
+        client.register_account(&creator, &account, &vec![&env, tag.clone()]);
+        registered.push_back(account);
+    }
+
+    let first_page = client.list_accounts_by_tag(&tag, &None, &2);
+    assert_eq!(first_page.items, registered.slice(0..2));
+
+    let second_page = client.list_accounts_by_tag(&tag, &first_page.next_cursor, &2);
+    assert_eq!(second_page.items, registered.slice(2..4));
+
+    let last_page = client.list_accounts_by_tag(&tag, &second_page.next_cursor, &2);
+    assert_eq!(last_page.items, registered.slice(4..5));
+    assert_eq!(last_page.next_cursor, None);
+}
+
+#[test]
+fn test_list_accounts_exact_page_has_no_next_cursor() {
+    let (env, client) = setup();
+    let creator = Address::generate(&env);
+    let tag = symbol_short!("exact");
+
+    for _ in 0..2 {
+        let account = deploy_account(&env, &creator);
+        client.register_account(&creator, &account, &vec![&env, tag.clone()]);
+    }
+
+    let page = client.list_accounts_by_tag(&tag, &None, &2);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_list_accounts_segments_by_tag() {
+    let (env, client) = setup();
+    let creator = Address::generate(&env);
+    let online = symbol_short!("online");
+    let in_store = symbol_short!("in_store");
+
+    let web_account = deploy_account(&env, &creator);
+    let pos_account = deploy_account(&env, &creator);
+    client.register_account(&creator, &web_account, &vec![&env, online.clone()]);
+    client.register_account(&creator, &pos_account, &vec![&env, in_store.clone()]);
+
+    assert_eq!(
+        client.list_accounts_by_tag(&online, &None, &10).items,
+        vec![&env, web_account]
+    );
+    assert_eq!(
+        client.list_accounts_by_tag(&in_store, &None, &10).items,
+        vec![&env, pos_account]
+    );
+
+    let unknown = client.list_accounts_by_tag(&Symbol::new(&env, "unknown"), &None, &10);
+    assert_eq!(unknown.items.len(), 0);
+    assert_eq!(unknown.next_cursor, None);
+}
+
+#[test]
+fn test_list_accounts_limit_is_capped() {
+    let (env, client) = setup();
+    let creator = Address::generate(&env);
+    let tag = symbol_short!("bulk");
+
+    for _ in 0..(crate::MAX_PAGE_SIZE + 1) {
+        let account = deploy_account(&env, &creator);
+        client.register_account(&creator, &account, &vec![&env, tag.clone()]);
+    }
+
+    let page = client.list_accounts_by_tag(&tag, &None, &u32::MAX);
+    assert_eq!(page.items.len(), crate::MAX_PAGE_SIZE);
+
+    let rest = client.list_accounts_by_tag(&tag, &page.next_cursor, &u32::MAX);
+    assert_eq!(rest.items.len(), 1);
+    assert_eq!(rest.next_cursor, None);
+}
+
+/// A cursor that was not issued by the contract must be rejected (error #6).
+#[test]
+#[should_panic(expected = "Error(Contract, #306)")]
+fn test_list_accounts_rejects_forged_cursor() {
+    let (env, client) = setup();
+    let forged = BytesN::from_array(&env, &[0xff; 8]);
+    client.list_accounts_by_tag(&symbol_short!("any"), &Some(forged), &10);
+}
+
+//  Creator index
+
+#[test]
+fn test_list_accounts_by_creator_paginates() {
+    let (env, client) = setup();
+    let merchant = Address::generate(&env);
+    let other_merchant = Address::generate(&env);
+
+    let mut created: Vec<Address> = Vec::new(&env);
+    for _ in 0..3 {
+        let account = deploy_account(&env, &merchant);
+        client.register_account(&merchant, &account, &Vec::new(&env));
+        created.push_back(account);
+    }
+    let other_account = deploy_account(&env, &other_merchant);
+    client.register_account(&other_merchant, &other_account, &Vec::new(&env));
+
+    assert_eq!(client.count_accounts(&merchant), 3);
+    assert_eq!(client.count_accounts(&other_merchant), 1);
+    assert_eq!(client.count_accounts(&Address::generate(&env)), 0);
+
+    let first_page = client.list_accounts(&merchant, &None, &2);
+    assert_eq!(first_page.items, created.slice(0..2));
+
+    let last_page = client.list_accounts(&merchant, &first_page.next_cursor, &2);
+    assert_eq!(last_page.items, created.slice(2..3));
+    assert_eq!(last_page.next_cursor, None);
+
+    assert_eq!(
+        client.list_accounts(&other_merchant, &None, &10).items,
+        vec![&env, other_account]
+    );
+}
+
+#[test]
+fn test_get_summaries_reports_status() {
+    let (env, client) = setup();
+    let creator = Address::generate(&env);
+    let idle_account = deploy_account(&env, &creator);
+    let paid_account = deploy_account(&env, &creator);
+    EphemeralAccountContractClient::new(&env, &paid_account)
+        .record_payment(&100, &Address::generate(&env));
+
+    let summaries = client.get_summaries(&vec![&env, idle_account.clone(), paid_account.clone()]);
+    assert_eq!(summaries.len(), 2);
+
+    let idle = summaries.get(0).unwrap();
+    assert_eq!(idle.account, idle_account);
+    assert_eq!(idle.status, AccountStatus::Active);
+    assert_eq!(idle.payment_count, 0);
+
+    let paid = summaries.get(1).unwrap();
+    assert_eq!(paid.account, paid_account);
+    assert_eq!(paid.status, AccountStatus::PaymentReceived);
+    assert_eq!(paid.payment_count, 1);
+}
+
+#[test]
+fn test_get_statuses_in_input_order() {
+    let (env, client) = setup();
+    let creator = Address::generate(&env);
+    let idle_account = deploy_account(&env, &creator);
+    let paid_account = deploy_account(&env, &creator);
+    EphemeralAccountContractClient::new(&env, &paid_account)
+        .record_payment(&100, &Address::generate(&env));
+
+    assert_eq!(
+        client.get_statuses(&vec![&env, paid_account, idle_account]),
+        vec![&env, AccountStatus::PaymentReceived, AccountStatus::Active]
+    );
+}
+
+#[test]
+fn test_get_statuses_rejects_oversized_batch() {
+    let (env, client) = setup();
+    let mut accounts = Vec::new(&env);
+    for _ in 0..(crate::MAX_BATCH_SIZE + 1) {
+        accounts.push_back(Address::generate(&env));
+    }
+    assert_eq!(
+        client.try_get_statuses(&accounts),
+        Err(Ok(Error::BatchTooLarge))
+    );
+}
+
+//  Batch reserve reads
+
+#[test]
+fn test_get_reserves_reports_each_account() {
+    let (env, client) = setup();
+    let creator = Address::generate(&env);
+
+    let open_account = deploy_account(&env, &creator);
+    let swept_account = deploy_account(&env, &creator);
+
+    let swept = EphemeralAccountContractClient::new(&env, &swept_account);
+    let issuer = Address::generate(&env);
+    let asset = env.register_stellar_asset_contract_v2(issuer).address();
+    StellarAssetClient::new(&env, &asset).mint(&swept_account, &100);
+    swept.record_payment(&100, &asset);
+
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+    swept.set_sweep_signer(&BytesN::from_array(
+        &env,
+        &signing_key.verifying_key().to_bytes(),
+    ));
+    let destination = Address::generate(&env);
+    let digest = swept.get_sweep_message(&destination).to_array();
+    let signature = BytesN::from_array(&env, &signing_key.sign(&digest).to_bytes());
+    swept.sweep(&destination, &vec![&env, signature]);
+
+    let reserves = client.get_reserves(&vec![&env, open_account.clone(), swept_account.clone()]);
+    assert_eq!(reserves.len(), 2);
+
+    let open = reserves.get(0).unwrap();
+    assert_eq!(open.account, open_account);
+    assert_eq!(open.reserve_remaining, 1_000_000_000);
+    assert!(!open.reserve_reclaimed);
+
+    let closed = reserves.get(1).unwrap();
+    assert_eq!(closed.account, swept_account);
+    assert_eq!(closed.reserve_remaining, 0);
+    assert!(closed.reserve_reclaimed);
+}
+
+#[test]
+fn test_get_reserves_empty_batch() {
+    let (env, client) = setup();
+    assert_eq!(client.get_reserves(&Vec::new(&env)).len(), 0);
+}
+
+/// Batches above MAX_BATCH_SIZE must be rejected with error #5.
+#[test]
+#[should_panic(expected = "Error(Contract, #305)")]
+fn test_get_reserves_rejects_oversized_batch() {
+    let (env, client) = setup();
+    let mut accounts = Vec::new(&env);
+    for _ in 0..(crate::MAX_BATCH_SIZE + 1) {
+        accounts.push_back(Address::generate(&env));
+    }
+    client.get_reserves(&accounts);
+}
+
+//  Long-running TTL
+
+/// Tag indexes are queried far more often than written; those reads alone
+/// must keep every index entry live for a year.
+#[test]
+fn test_periodic_listing_keeps_index_alive() {
+    let (env, client) = setup();
+    env.ledger().with_mut(|li| li.max_entry_ttl = 600_000);
+    let creator = Address::generate(&env);
+    let tag = symbol_short!("soak");
+    let account = deploy_account(&env, &creator);
+    client.register_account(&creator, &account, &vec![&env, tag.clone()]);
+
+    for _ in 0..18 {
+        env.ledger().with_mut(|li| li.sequence_number += 345_600);
+        assert_eq!(
+            client.list_accounts_by_tag(&tag, &None, &10).items,
+            vec![&env, account.clone()]
+        );
+        assert_eq!(client.get_tags(&account), vec![&env, tag.clone()]);
+    }
+}
+
+/// Codes stay inside the registry's shared range, so off-chain tooling can
+/// tell them from another contract's.
+#[test]
+fn test_error_codes_use_reserved_range() {
+    let env = Env::default();
+    let client = AccountRegistryClient::new(&env, &env.register(AccountRegistry, ()));
+    for code in ACCOUNT_REGISTRY_ERRORS {
+        let name = client.describe_error(&code);
+        if let Ok(error) = Error::try_from(soroban_sdk::Error::from_contract_error(code)) {
+            assert_eq!(name, Symbol::new(&env, error.name()));
+        } else {
+            assert_eq!(name, Symbol::new(&env, UNKNOWN_ERROR));
+        }
+    }
+    assert_eq!(client.describe_error(&1), Symbol::new(&env, UNKNOWN_ERROR));
+}
@@ -0,0 +1,25 @@
+use soroban_sdk::contracterror;
+
+/// Codes sit in [`bridgelet_shared::errors::ACCOUNT_VIEWER_ERRORS`], so
+/// they never collide with another Bridgelet contract's.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// [`AccountViewer::initialize`](crate::AccountViewer::initialize) was
+    /// called more than once.
+    AlreadyInitialized = 401,
+
+    /// A query was made before the registry was configured.
+    NotInitialized = 402,
+}
+
+impl Error {
+    /// Name of the variant, as `describe_error` reports it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Error::AlreadyInitialized => "AlreadyInitialized",
+            Error::NotInitialized => "NotInitialized",
+        }
+    }
+}
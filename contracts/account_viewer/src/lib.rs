@@ -0,0 +1,214 @@
+#![no_std]
+
+mod errors;
+mod storage;
+#[cfg(test)]
+mod test;
+
+use account_registry::AccountRegistryClient;
+use bridgelet_shared::errors::UNKNOWN_ERROR;
+use bridgelet_shared::{AccountStatus, AddressPage, Cursor, EphemeralAccountClient};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Map, Symbol};
+
+pub use errors::Error;
+pub use storage::DataKey;
+
+/// Maximum number of accounts read by a single query.
+///
+/// Each account costs up to two cross-contract calls, so a page stays well
+/// inside the per-transaction budget.
+pub const MAX_PAGE_SIZE: u32 = 25;
+
+/// Account counts by status over one page of a registry index, as returned
+/// by [`AccountViewer::get_merchant_summary`] and
+/// [`AccountViewer::get_tag_summary`].
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FleetSummary {
+    /// Number of accounts read in this page.
+    pub accounts: u32,
+    pub active: u32,
+    pub payment_received: u32,
+    pub pending_release: u32,
+    pub swept: u32,
+    pub expired: u32,
+    pub cancelled: u32,
+    pub emergency_recovered: u32,
+    /// Cursor for the next page, or `None` once the index is exhausted.
+    pub next_cursor: Option<Cursor>,
+}
+
+/// Funds still held by one page of a creator's accounts, as returned by
+/// [`AccountViewer::get_open_balances`].
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OpenBalances {
+    /// Outstanding (recorded and not yet swept) amount per asset.
+    pub balances: Map<Address, i128>,
+    /// Number of accounts in this page still holding funds.
+    pub open_accounts: u32,
+    /// Cursor for the next page, or `None` once the index is exhausted.
+    pub next_cursor: Option<Cursor>,
+}
+
+/// Read-only aggregation over the registry and the accounts it indexes.
+///
+/// Dashboards need fleet-wide views (status breakdowns, per-merchant open
+/// balances) that would otherwise take one simulation per account.  The
+/// viewer pages through an `AccountRegistry` index and reads each account
+/// in the same invocation, so those queries live here instead of growing
+/// the account and registry contracts that sit on the payment path.
+///
+/// Every query takes a registry cursor and returns the next one; totals
+/// across a whole index are the sum of its pages.
+#[contract]
+pub struct AccountViewer;
+
+#[contractimpl]
+impl AccountViewer {
+    /// One-time initialization that sets the registry to query.
+    ///
+    /// # Errors
+    /// * [`Error::AlreadyInitialized`] – called more than once.
+    pub fn initialize(env: Env, registry: Address) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        if storage::has_registry(&env) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        storage::set_registry(&env, &registry);
+
+        Ok(())
+    }
+
+    /// Status breakdown of one page of the accounts registered by `creator`.
+    ///
+    /// `cursor` is a registry cursor (`None` to start); `limit` is capped at
+    /// [`MAX_PAGE_SIZE`].  A cursor the registry rejects aborts the query.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – the registry was never configured.
+    pub fn get_merchant_summary(
+        env: Env,
+        creator: Address,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<FleetSummary, Error> {
+        storage::extend_instance_ttl(&env);
+
+        let page =
+            Self::registry(&env)?.list_accounts(&creator, &cursor, &limit.min(MAX_PAGE_SIZE));
+        Ok(Self::summarize(&env, page))
+    }
+
+    /// Status breakdown of one page of the accounts carrying `tag`.
+    ///
+    /// Paginated like [`Self::get_merchant_summary`].
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – the registry was never configured.
+    pub fn get_tag_summary(
+        env: Env,
+        tag: Symbol,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<FleetSummary, Error> {
+        storage::extend_instance_ttl(&env);
+
+        let page =
+            Self::registry(&env)?.list_accounts_by_tag(&tag, &cursor, &limit.min(MAX_PAGE_SIZE));
+        Ok(Self::summarize(&env, page))
+    }
+
+    /// Funds still held by one page of the accounts registered by `creator`.
+    ///
+    /// Sums, per asset, the payments of accounts that were paid but not
+    /// yet settled (`PaymentReceived` or `PendingRelease`), leaving out
+    /// assets already moved by a partial sweep.  Paginated like
+    /// [`Self::get_merchant_summary`].
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – the registry was never configured.
+    pub fn get_open_balances(
+        env: Env,
+        creator: Address,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<OpenBalances, Error> {
+        storage::extend_instance_ttl(&env);
+
+        let page =
+            Self::registry(&env)?.list_accounts(&creator, &cursor, &limit.min(MAX_PAGE_SIZE));
+
+        let mut balances: Map<Address, i128> = Map::new(&env);
+        let mut open_accounts = 0;
+        for account in page.items.iter() {
+            let client = EphemeralAccountClient::new(&env, &account);
+            let info = client.get_info();
+            if !matches!(
+                info.status,
+                AccountStatus::PaymentReceived | AccountStatus::PendingRelease
+            ) {
+                continue;
+            }
+
+            open_accounts += 1;
+            let swept_assets = client.get_swept_assets();
+            for payment in info.payments.iter() {
+                if swept_assets.contains(&payment.asset) {
+                    continue;
+                }
+                let total = balances.get(payment.asset.clone()).unwrap_or(0);
+                balances.set(payment.asset, total.saturating_add(payment.amount));
+            }
+        }
+
+        Ok(OpenBalances {
+            balances,
+            open_accounts,
+            next_cursor: page.next_cursor,
+        })
+    }
+
+    /// Registry this viewer queries, if it has been initialized.
+    pub fn get_registry(env: Env) -> Option<Address> {
+        storage::extend_instance_ttl(&env);
+        storage::get_registry(&env)
+    }
+
+    // ─── Internal helpers ───────────────────────────────────────────────────
+
+    fn registry(env: &Env) -> Result<AccountRegistryClient<'_>, Error> {
+        let registry = storage::get_registry(env).ok_or(Error::NotInitialized)?;
+        Ok(AccountRegistryClient::new(env, &registry))
+    }
+
+    fn summarize(env: &Env, page: AddressPage) -> FleetSummary {
+        let mut summary = FleetSummary {
+            accounts: page.items.len(),
+            next_cursor: page.next_cursor,
+            ..Default::default()
+        };
+        for account in page.items.iter() {
+            match EphemeralAccountClient::new(env, &account).get_status() {
+                AccountStatus::Active => summary.active += 1,
+                AccountStatus::PaymentReceived => summary.payment_received += 1,
+                AccountStatus::PendingRelease => summary.pending_release += 1,
+                AccountStatus::Swept => summary.swept += 1,
+                AccountStatus::Expired => summary.expired += 1,
+                AccountStatus::Cancelled => summary.cancelled += 1,
+                AccountStatus::EmergencyRecovered => summary.emergency_recovered += 1,
+            }
+        }
+        summary
+    }
+
+    /// Return the name of the error raised as `Error(Contract, #code)`, or
+    /// `Unknown` for a code this contract never raises.
+    pub fn describe_error(env: Env, code: u32) -> Symbol {
+        let name = Error::try_from(soroban_sdk::Error::from_contract_error(code))
+            .map_or(UNKNOWN_ERROR, Error::name);
+        Symbol::new(&env, name)
+    }
+}
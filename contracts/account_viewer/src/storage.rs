@@ -0,0 +1,40 @@
+use soroban_sdk::{contracttype, Address, Env};
+
+/// Storage keys used by the account viewer.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// The `AccountRegistry` whose indexes are queried.
+    Registry,
+}
+
+// Registry helpers
+
+pub fn set_registry(env: &Env, registry: &Address) {
+    env.storage().instance().set(&DataKey::Registry, registry);
+}
+
+pub fn get_registry(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Registry)
+}
+
+pub fn has_registry(env: &Env) -> bool {
+    env.storage().instance().has(&DataKey::Registry)
+}
+
+// TTL management
+
+/// If the remaining TTL drops below this threshold (in ledgers), extend it.
+/// 259 200 ledgers ≈ 15 days, half of the extension window.
+const TTL_THRESHOLD: u32 = 259_200;
+
+/// Extend TTLs to this many ledgers.
+/// 518 400 ledgers ≈ 30 days (at ~5 s per ledger).
+const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Keep the contract instance alive; called from every public entry-point.
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
@@ -0,0 +1,161 @@
+extern crate std;
+
+use crate::{AccountViewer, AccountViewerClient, Error, FleetSummary};
+use account_registry::{AccountRegistry, AccountRegistryClient};
+use bridgelet_shared::errors::{ACCOUNT_VIEWER_ERRORS, UNKNOWN_ERROR};
+use ephemeral_account::{CloseReason, EphemeralAccountContract, EphemeralAccountContractClient};
+use soroban_sdk::{symbol_short, testutils::Address as _, vec, Address, Env, Symbol, Vec};
+
+// HELPERS
+
+fn setup() -> (
+    Env,
+    AccountRegistryClient<'static>,
+    AccountViewerClient<'static>,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let registry_id = env.register(AccountRegistry, ());
+    let registry = AccountRegistryClient::new(&env, &registry_id);
+    let viewer_id = env.register(AccountViewer, ());
+    let viewer = AccountViewerClient::new(&env, &viewer_id);
+    viewer.initialize(&registry_id);
+    (env, registry, viewer)
+}
+
+/// Deploy, initialize and register an ephemeral account owned by `creator`.
+fn deploy_account(
+    env: &Env,
+    registry: &AccountRegistryClient,
+    creator: &Address,
+    tags: Vec<Symbol>,
+) -> EphemeralAccountContractClient<'static> {
+    let account_id = env.register(EphemeralAccountContract, ());
+    let account = EphemeralAccountContractClient::new(env, &account_id);
+    account.initialize(
+        creator,
+        &(env.ledger().sequence() + 1000),
+        &Address::generate(env),
+    );
+    registry.register_account(creator, &account_id, &tags);
+    account
+}
+
+//  Initialization
+
+#[test]
+fn test_initialize_stores_registry() {
+    let env = Env::default();
+    let viewer = AccountViewerClient::new(&env, &env.register(AccountViewer, ()));
+    let registry = Address::generate(&env);
+
+    assert_eq!(viewer.get_registry(), None);
+    viewer.initialize(&registry);
+    assert_eq!(viewer.get_registry(), Some(registry));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #401)")]
+fn test_initialize_twice_panics() {
+    let (env, _, viewer) = setup();
+    viewer.initialize(&Address::generate(&env));
+}
+
+/// Queries before initialization must fail with error #2.
+#[test]
+#[should_panic(expected = "Error(Contract, #402)")]
+fn test_query_before_initialize_panics() {
+    let env = Env::default();
+    let viewer = AccountViewerClient::new(&env, &env.register(AccountViewer, ()));
+    viewer.get_merchant_summary(&Address::generate(&env), &None, &10);
+}
+
+//  Summaries
+
+#[test]
+fn test_merchant_summary_counts_statuses() {
+    let (env, registry, viewer) = setup();
+    let merchant = Address::generate(&env);
+
+    deploy_account(&env, &registry, &merchant, Vec::new(&env));
+    deploy_account(&env, &registry, &merchant, Vec::new(&env))
+        .record_payment(&100, &Address::generate(&env));
+    deploy_account(&env, &registry, &merchant, Vec::new(&env))
+        .cancel(&CloseReason::CreatorCancelled);
+    deploy_account(&env, &registry, &Address::generate(&env), Vec::new(&env));
+
+    assert_eq!(
+        viewer.get_merchant_summary(&merchant, &None, &10),
+        FleetSummary {
+            accounts: 3,
+            active: 1,
+            payment_received: 1,
+            cancelled: 1,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_tag_summary_paginates() {
+    let (env, registry, viewer) = setup();
+    let tag = symbol_short!("eu_west");
+
+    for _ in 0..3 {
+        deploy_account(
+            &env,
+            &registry,
+            &Address::generate(&env),
+            vec![&env, tag.clone()],
+        );
+    }
+
+    let first = viewer.get_tag_summary(&tag, &None, &2);
+    assert_eq!(first.accounts, 2);
+    assert_eq!(first.active, 2);
+    assert!(first.next_cursor.is_some());
+
+    let last = viewer.get_tag_summary(&tag, &first.next_cursor, &2);
+    assert_eq!(last.accounts, 1);
+    assert_eq!(last.next_cursor, None);
+}
+
+//  Open balances
+
+#[test]
+fn test_open_balances_sum_unsettled_payments() {
+    let (env, registry, viewer) = setup();
+    let merchant = Address::generate(&env);
+    let usdc = Address::generate(&env);
+    let xlm = Address::generate(&env);
+
+    let first = deploy_account(&env, &registry, &merchant, Vec::new(&env));
+    first.record_payment(&100, &usdc);
+    first.record_payment(&7, &xlm);
+    deploy_account(&env, &registry, &merchant, Vec::new(&env)).record_payment(&50, &usdc);
+    deploy_account(&env, &registry, &merchant, Vec::new(&env));
+
+    let open = viewer.get_open_balances(&merchant, &None, &10);
+    assert_eq!(open.open_accounts, 2);
+    assert_eq!(open.balances.len(), 2);
+    assert_eq!(open.balances.get(usdc), Some(150));
+    assert_eq!(open.balances.get(xlm), Some(7));
+    assert_eq!(open.next_cursor, None);
+}
+
+/// Codes stay inside the viewer's shared range, so off-chain tooling can
+/// tell them from another contract's.
+#[test]
+fn test_error_codes_use_reserved_range() {
+    let env = Env::default();
+    let client = AccountViewerClient::new(&env, &env.register(AccountViewer, ()));
+    for code in ACCOUNT_VIEWER_ERRORS {
+        let name = client.describe_error(&code);
+        if let Ok(error) = Error::try_from(soroban_sdk::Error::from_contract_error(code)) {
+            assert_eq!(name, Symbol::new(&env, error.name()));
+        } else {
+            assert_eq!(name, Symbol::new(&env, UNKNOWN_ERROR));
+        }
+    }
+    assert_eq!(client.describe_error(&1), Symbol::new(&env, UNKNOWN_ERROR));
+}
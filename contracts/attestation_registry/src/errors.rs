@@ -0,0 +1,38 @@
+use soroban_sdk::contracterror;
+
+/// Codes sit in [`bridgelet_shared::errors::ATTESTATION_REGISTRY_ERRORS`], so
+/// they never collide with another Bridgelet contract's.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// [`AttestationRegistry::initialize`] was called more than once.
+    AlreadyInitialized = 501,
+
+    /// A state-changing operation was attempted before initialization.
+    NotInitialized = 502,
+
+    /// A hash is already published for this contract name and version.
+    ///
+    /// Releases are immutable; publish a new version instead.
+    VersionAlreadyAttested = 503,
+
+    /// The wasm hash is already attested under another release.
+    HashAlreadyAttested = 504,
+
+    /// The wasm hash has no active attestation.
+    NotAttested = 505,
+}
+
+impl Error {
+    /// Name of the variant, as `describe_error` reports it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Error::AlreadyInitialized => "AlreadyInitialized",
+            Error::NotInitialized => "NotInitialized",
+            Error::VersionAlreadyAttested => "VersionAlreadyAttested",
+            Error::HashAlreadyAttested => "HashAlreadyAttested",
+            Error::NotAttested => "NotAttested",
+        }
+    }
+}
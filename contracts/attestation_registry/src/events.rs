@@ -0,0 +1,43 @@
+use soroban_sdk::{contracttype, symbol_short, BytesN, Env, Symbol};
+
+// ─── Event payloads ─────────────────────────────────────────────────────────
+
+/// Emitted when a release wasm hash is published.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WasmAttested {
+    pub contract_name: Symbol,
+    pub version: u32,
+    pub wasm_hash: BytesN<32>,
+}
+
+/// Emitted when a previously published wasm hash is revoked.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WasmRevoked {
+    pub contract_name: Symbol,
+    pub version: u32,
+    pub wasm_hash: BytesN<32>,
+}
+
+// ─── Emit helpers ───────────────────────────────────────────────────────────
+
+/// Publish the `attested` event.
+pub fn emit_wasm_attested(env: &Env, contract_name: Symbol, version: u32, wasm_hash: BytesN<32>) {
+    let event = WasmAttested {
+        contract_name,
+        version,
+        wasm_hash,
+    };
+    env.events().publish((symbol_short!("attested"),), event);
+}
+
+/// Publish the `revoked` event.
+pub fn emit_wasm_revoked(env: &Env, contract_name: Symbol, version: u32, wasm_hash: BytesN<32>) {
+    let event = WasmRevoked {
+        contract_name,
+        version,
+        wasm_hash,
+    };
+    env.events().publish((symbol_short!("revoked"),), event);
+}
@@ -0,0 +1,149 @@
+#![no_std]
+
+mod errors;
+mod events;
+mod storage;
+#[cfg(test)]
+mod test;
+
+use bridgelet_shared::errors::UNKNOWN_ERROR;
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Symbol};
+
+pub use errors::Error;
+pub use events::{WasmAttested, WasmRevoked};
+pub use storage::{Attestation, DataKey};
+
+/// Registry of wasm hashes published for each released Bridgelet contract
+/// version.
+///
+/// Release builds are reproducible, so the project publishes the expected
+/// hash of every released contract here.  The factory refuses to deploy
+/// account wasm that is not attested, and off-chain tooling can check a
+/// deployed contract's executable against the same registry before
+/// interacting with it.
+///
+/// ## Access control
+///
+/// Only the admin stored by [`initialize`] may publish or revoke hashes.
+/// Published releases are immutable: a `(contract_name, version)` pair can
+/// be attested once and a revoked hash stays revoked.
+#[contract]
+pub struct AttestationRegistry;
+
+#[contractimpl]
+impl AttestationRegistry {
+    /// One-time initialization that sets the admin address.
+    ///
+    /// # Errors
+    /// * [`Error::AlreadyInitialized`] – called more than once.
+    pub fn initialize(env: Env, admin: Address) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        if storage::has_admin(&env) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+        storage::set_admin(&env, &admin);
+
+        Ok(())
+    }
+
+    /// Publish the expected wasm hash for a released contract version.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`]         – contract has not been initialized.
+    /// * [`Error::VersionAlreadyAttested`] – the release already has a hash.
+    /// * [`Error::HashAlreadyAttested`]    – the hash belongs to another release.
+    pub fn attest(
+        env: Env,
+        contract_name: Symbol,
+        version: u32,
+        wasm_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        if storage::get_release(&env, &contract_name, version).is_some() {
+            return Err(Error::VersionAlreadyAttested);
+        }
+        if storage::get_attestation(&env, &wasm_hash).is_some() {
+            return Err(Error::HashAlreadyAttested);
+        }
+
+        storage::set_release(&env, &contract_name, version, &wasm_hash);
+        storage::set_attestation(
+            &env,
+            &wasm_hash,
+            &Attestation {
+                contract_name: contract_name.clone(),
+                version,
+                attested_at: env.ledger().sequence(),
+                revoked: false,
+            },
+        );
+        events::emit_wasm_attested(&env, contract_name, version, wasm_hash);
+
+        Ok(())
+    }
+
+    /// Withdraw the attestation for a wasm hash (e.g. a vulnerable release).
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::NotAttested`]    – the hash is unknown or already revoked.
+    pub fn revoke(env: Env, wasm_hash: BytesN<32>) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        let mut attestation = storage::get_attestation(&env, &wasm_hash)
+            .filter(|attestation| !attestation.revoked)
+            .ok_or(Error::NotAttested)?;
+        attestation.revoked = true;
+        storage::set_attestation(&env, &wasm_hash, &attestation);
+        events::emit_wasm_revoked(
+            &env,
+            attestation.contract_name,
+            attestation.version,
+            wasm_hash,
+        );
+
+        Ok(())
+    }
+
+    /// Returns `true` if `wasm_hash` is attested and not revoked.
+    pub fn is_attested(env: Env, wasm_hash: BytesN<32>) -> bool {
+        storage::extend_instance_ttl(&env);
+        storage::get_attestation(&env, &wasm_hash).is_some_and(|attestation| !attestation.revoked)
+    }
+
+    /// Attestation record for `wasm_hash`, including revoked ones.
+    pub fn get_attestation(env: Env, wasm_hash: BytesN<32>) -> Option<Attestation> {
+        storage::extend_instance_ttl(&env);
+        storage::get_attestation(&env, &wasm_hash)
+    }
+
+    /// Wasm hash published for a released contract version, if any.
+    pub fn get_release_hash(env: Env, contract_name: Symbol, version: u32) -> Option<BytesN<32>> {
+        storage::extend_instance_ttl(&env);
+        storage::get_release(&env, &contract_name, version)
+    }
+
+    /// Returns the admin address, if the contract has been initialized.
+    pub fn get_admin(env: Env) -> Option<Address> {
+        storage::extend_instance_ttl(&env);
+        storage::get_admin(&env)
+    }
+
+    /// Return the name of the error raised as `Error(Contract, #code)`, or
+    /// `Unknown` for a code this contract never raises.
+    pub fn describe_error(env: Env, code: u32) -> Symbol {
+        let name = Error::try_from(soroban_sdk::Error::from_contract_error(code))
+            .map_or(UNKNOWN_ERROR, Error::name);
+        Symbol::new(&env, name)
+    }
+}
@@ -0,0 +1,99 @@
+use soroban_sdk::{contracttype, Address, BytesN, Env, Symbol, TryFromVal, Val};
+
+/// Storage keys used by the attestation registry.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// The admin address allowed to publish and revoke attestations.
+    Admin,
+
+    /// Wasm hash published for a `(contract_name, version)` release.
+    Release(Symbol, u32),
+
+    /// Attestation record for a wasm hash.
+    Attestation(BytesN<32>),
+}
+
+/// Attestation record for a single released wasm hash.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    pub contract_name: Symbol,
+    pub version: u32,
+    /// Ledger at which the hash was published.
+    pub attested_at: u32,
+    /// `true` once the admin has withdrawn the attestation.
+    pub revoked: bool,
+}
+
+// Admin helpers
+
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
+
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Admin)
+}
+
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().instance().has(&DataKey::Admin)
+}
+
+// Release helpers
+
+pub fn set_release(env: &Env, contract_name: &Symbol, version: u32, wasm_hash: &BytesN<32>) {
+    let key = DataKey::Release(contract_name.clone(), version);
+    env.storage().persistent().set(&key, wasm_hash);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn get_release(env: &Env, contract_name: &Symbol, version: u32) -> Option<BytesN<32>> {
+    read_persistent(env, &DataKey::Release(contract_name.clone(), version))
+}
+
+// Attestation helpers
+
+pub fn set_attestation(env: &Env, wasm_hash: &BytesN<32>, attestation: &Attestation) {
+    let key = DataKey::Attestation(wasm_hash.clone());
+    env.storage().persistent().set(&key, attestation);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn get_attestation(env: &Env, wasm_hash: &BytesN<32>) -> Option<Attestation> {
+    read_persistent(env, &DataKey::Attestation(wasm_hash.clone()))
+}
+
+// TTL management
+
+/// If the remaining TTL drops below this threshold (in ledgers), extend it.
+/// 259 200 ledgers ≈ 15 days, half of the extension window.
+const TTL_THRESHOLD: u32 = 259_200;
+
+/// Extend TTLs to this many ledgers.
+/// 518 400 ledgers ≈ 30 days (at ~5 s per ledger).
+const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Keep the contract instance alive; called from every public entry-point.
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+/// Extend a persistent entry after it is written or read.
+fn extend_persistent_ttl(env: &Env, key: &DataKey) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+/// Read a persistent entry, extending it if present so attestations that
+/// are checked on every deployment but never rewritten stay live.
+fn read_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        extend_persistent_ttl(env, key);
+    }
+    value
+}
@@ -0,0 +1,186 @@
+extern crate std;
+
+use crate::{AttestationRegistry, AttestationRegistryClient, Error};
+use bridgelet_shared::errors::{ATTESTATION_REGISTRY_ERRORS, UNKNOWN_ERROR};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env, Symbol,
+};
+
+// HELPERS
+
+fn setup() -> (Env, AttestationRegistryClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(AttestationRegistry, ());
+    let client = AttestationRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    (env, client, admin)
+}
+
+fn hash(env: &Env, byte: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[byte; 32])
+}
+
+//  Initialization
+
+#[test]
+fn test_initialize_stores_admin() {
+    let (_env, client, admin) = setup();
+    assert_eq!(client.get_admin(), Some(admin));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #501)")]
+fn test_initialize_twice_panics() {
+    let (env, client, _admin) = setup();
+    client.initialize(&Address::generate(&env));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #502)")]
+fn test_attest_before_initialize_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(AttestationRegistry, ());
+    let client = AttestationRegistryClient::new(&env, &contract_id);
+    client.attest(&symbol_short!("account"), &1, &hash(&env, 1));
+}
+
+//  Attestation
+
+#[test]
+fn test_attest_and_lookup() {
+    let (env, client, _admin) = setup();
+    let wasm_hash = hash(&env, 7);
+
+    assert!(!client.is_attested(&wasm_hash));
+    client.attest(&symbol_short!("account"), &1, &wasm_hash);
+
+    assert!(client.is_attested(&wasm_hash));
+    assert_eq!(
+        client.get_release_hash(&symbol_short!("account"), &1),
+        Some(wasm_hash.clone())
+    );
+
+    let attestation = client.get_attestation(&wasm_hash).unwrap();
+    assert_eq!(attestation.contract_name, symbol_short!("account"));
+    assert_eq!(attestation.version, 1);
+    assert_eq!(attestation.attested_at, env.ledger().sequence());
+    assert!(!attestation.revoked);
+}
+
+#[test]
+fn test_unknown_release_is_none() {
+    let (_env, client, _admin) = setup();
+    assert_eq!(client.get_release_hash(&symbol_short!("account"), &9), None);
+}
+
+/// A release can only be published once (error #3).
+#[test]
+#[should_panic(expected = "Error(Contract, #503)")]
+fn test_attest_same_version_twice_panics() {
+    let (env, client, _admin) = setup();
+    client.attest(&symbol_short!("account"), &1, &hash(&env, 1));
+    client.attest(&symbol_short!("account"), &1, &hash(&env, 2));
+}
+
+/// One hash cannot be published under two releases (error #4).
+#[test]
+#[should_panic(expected = "Error(Contract, #504)")]
+fn test_attest_same_hash_twice_panics() {
+    let (env, client, _admin) = setup();
+    client.attest(&symbol_short!("account"), &1, &hash(&env, 1));
+    client.attest(&symbol_short!("account"), &2, &hash(&env, 1));
+}
+
+//  Revocation
+
+#[test]
+fn test_revoke_withdraws_attestation() {
+    let (env, client, _admin) = setup();
+    let wasm_hash = hash(&env, 3);
+    client.attest(&symbol_short!("account"), &1, &wasm_hash);
+
+    client.revoke(&wasm_hash);
+
+    assert!(!client.is_attested(&wasm_hash));
+    assert!(client.get_attestation(&wasm_hash).unwrap().revoked);
+    // The release slot stays taken so the version cannot be silently reused.
+    assert_eq!(
+        client.get_release_hash(&symbol_short!("account"), &1),
+        Some(wasm_hash)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #505)")]
+fn test_revoke_unknown_hash_panics() {
+    let (env, client, _admin) = setup();
+    client.revoke(&hash(&env, 4));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #505)")]
+fn test_revoke_twice_panics() {
+    let (env, client, _admin) = setup();
+    let wasm_hash = hash(&env, 5);
+    client.attest(&symbol_short!("account"), &1, &wasm_hash);
+    client.revoke(&wasm_hash);
+    client.revoke(&wasm_hash);
+}
+
+//  Access control
+
+/// Without the admin's signature publishing must fail.
+#[test]
+fn test_attest_requires_admin_auth() {
+    let env = Env::default();
+    let contract_id = env.register(AttestationRegistry, ());
+    let client = AttestationRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.mock_all_auths().initialize(&admin);
+
+    let result = client.try_attest(&symbol_short!("account"), &1, &hash(&env, 1));
+    assert!(result.is_err());
+}
+
+//  Long-running TTL
+
+/// A published hash is checked on every factory deployment but never
+/// rewritten; those reads alone must keep it live for a year.
+#[test]
+fn test_periodic_checks_keep_attestation_alive() {
+    let (env, client, _admin) = setup();
+    env.ledger().with_mut(|li| li.max_entry_ttl = 600_000);
+    let wasm_hash = hash(&env, 6);
+    client.attest(&symbol_short!("account"), &1, &wasm_hash);
+
+    for _ in 0..18 {
+        env.ledger().with_mut(|li| li.sequence_number += 345_600);
+        assert!(client.is_attested(&wasm_hash));
+        assert_eq!(
+            client.get_release_hash(&symbol_short!("account"), &1),
+            Some(wasm_hash.clone())
+        );
+    }
+}
+
+/// Codes stay inside the attestation registry's shared range, so off-chain tooling can
+/// tell them from another contract's.
+#[test]
+fn test_error_codes_use_reserved_range() {
+    let env = Env::default();
+    let client = AttestationRegistryClient::new(&env, &env.register(AttestationRegistry, ()));
+    for code in ATTESTATION_REGISTRY_ERRORS {
+        let name = client.describe_error(&code);
+        if let Ok(error) = Error::try_from(soroban_sdk::Error::from_contract_error(code)) {
+            assert_eq!(name, Symbol::new(&env, error.name()));
+        } else {
+            assert_eq!(name, Symbol::new(&env, UNKNOWN_ERROR));
+        }
+    }
+    assert_eq!(client.describe_error(&1), Symbol::new(&env, UNKNOWN_ERROR));
+}
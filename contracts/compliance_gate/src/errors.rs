@@ -0,0 +1,40 @@
+use soroban_sdk::contracterror;
+
+/// Codes sit in [`bridgelet_shared::errors::COMPLIANCE_GATE_ERRORS`], so
+/// they never collide with another Bridgelet contract's.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// [`ComplianceGate::initialize`] was called more than once.
+    AlreadyInitialized = 1101,
+
+    /// A state-changing operation was attempted before initialization.
+    NotInitialized = 1102,
+
+    /// The account reporting a payment was never enrolled.
+    NotEnrolled = 1103,
+
+    /// The reported payment amount is not positive.
+    InvalidAmount = 1104,
+
+    /// A cap is negative, or the weekly cap is below the daily one.
+    InvalidLimit = 1105,
+
+    /// The account's sweeps are held until the admin releases them.
+    SweepHeld = 1106,
+}
+
+impl Error {
+    /// Name of the variant, as `describe_error` reports it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Error::AlreadyInitialized => "AlreadyInitialized",
+            Error::NotInitialized => "NotInitialized",
+            Error::NotEnrolled => "NotEnrolled",
+            Error::InvalidAmount => "InvalidAmount",
+            Error::InvalidLimit => "InvalidLimit",
+            Error::SweepHeld => "SweepHeld",
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use bridgelet_shared::EventTopics;
+use soroban_sdk::{contracttype, symbol_short, Address, Env};
+
+use crate::{Role, VelocityLimits, Window};
+
+// ─── Event payloads ─────────────────────────────────────────────────────────
+
+/// Emitted when a recorded payment takes a subject past a cap.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LimitExceeded {
+    /// Account that recorded the payment; its sweeps are now held.
+    pub account: Address,
+    pub role: Role,
+    /// The merchant or payer whose cap was passed.
+    pub subject: Address,
+    pub asset: Address,
+    pub window: Window,
+    /// Volume in the window, including the payment.
+    pub volume: i128,
+    pub cap: i128,
+}
+
+/// Emitted when caps are set, for a role's defaults (`subject` unset) or
+/// for one subject.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LimitsSet {
+    pub role: Role,
+    pub subject: Option<Address>,
+    pub asset: Address,
+    /// Caps now in force, the role's defaults once a subject's own are
+    /// cleared.
+    pub limits: VelocityLimits,
+}
+
+/// Emitted when an account is enrolled for a merchant.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Enrolled {
+    pub account: Address,
+    pub merchant: Address,
+}
+
+/// Emitted when the admin releases an account's held sweeps.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SweepReleased {
+    pub account: Address,
+}
+
+// ─── Emit helpers ───────────────────────────────────────────────────────────
+
+/// Publish the `limit_hit` event, indexed by the subject.
+pub fn emit_limit_exceeded(env: &Env, event: LimitExceeded) {
+    EventTopics::new(symbol_short!("limit_hit")).publish_indexed(env, event.subject.clone(), event);
+}
+
+/// Publish the `limits` event.
+pub fn emit_limits_set(env: &Env, event: LimitsSet) {
+    EventTopics::new(symbol_short!("limits")).publish(env, event);
+}
+
+/// Publish the `enrolled` event, indexed by the merchant.
+pub fn emit_enrolled(env: &Env, account: Address, merchant: Address) {
+    let event = Enrolled { account, merchant };
+    EventTopics::new(symbol_short!("enrolled")).publish_indexed(env, event.merchant.clone(), event);
+}
+
+/// Publish the `released` event, indexed by the account.
+pub fn emit_sweep_released(env: &Env, account: Address) {
+    let event = SweepReleased { account };
+    EventTopics::new(symbol_short!("released")).publish_indexed(env, event.account.clone(), event);
+}
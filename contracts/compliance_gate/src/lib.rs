@@ -0,0 +1,372 @@
+#![no_std]
+
+mod errors;
+mod events;
+mod storage;
+#[cfg(test)]
+mod test;
+
+use bridgelet_shared::errors::UNKNOWN_ERROR;
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Map, Symbol};
+
+pub use errors::Error;
+pub use events::{Enrolled, LimitExceeded, LimitsSet, SweepReleased};
+pub use storage::DataKey;
+
+/// Seconds in one volume bucket; windows roll by the hour.
+pub const BUCKET_SECONDS: u64 = 3_600;
+
+/// Hour buckets in the daily window.
+pub const DAILY_BUCKETS: u64 = 24;
+
+/// Hour buckets in the weekly window, and the history kept per subject.
+pub const WEEKLY_BUCKETS: u64 = 7 * DAILY_BUCKETS;
+
+/// Whose volume a cap limits.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Role {
+    /// The merchant an account is enrolled for, across all its accounts.
+    Merchant,
+    /// The sender a payment was recorded from, across every account.
+    Payer,
+}
+
+/// Rolling window a cap applies to.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Window {
+    /// The current hour and the 23 before it.
+    Daily,
+    /// The current hour and the 167 before it.
+    Weekly,
+}
+
+/// Value caps of one subject in one asset, in the asset's own units.
+/// An unset cap does not limit.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VelocityLimits {
+    pub daily: Option<i128>,
+    pub weekly: Option<i128>,
+}
+
+/// Volume of one subject in one asset over each window.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Volume {
+    pub daily: i128,
+    pub weekly: i128,
+}
+
+/// Rolling value limits for ephemeral accounts.
+///
+/// Register the gate as an account's `AccountConfig.compliance_gate` and
+/// enroll the account for its merchant.  The account reports every
+/// payment it records through [`ComplianceGate::check_payment`], which
+/// adds it to the merchant's and, when the sender is known, the payer's
+/// daily and weekly volume.  By the time a payment is recorded the funds
+/// are already in the account, so a payment that passes a cap is not
+/// refused: the gate emits `limit_hit` for the risk team and holds the
+/// account's sweeps until the admin calls [`ComplianceGate::release`].
+/// Expiry still returns held funds to the recovery address.
+#[contract]
+pub struct ComplianceGate;
+
+#[contractimpl]
+impl ComplianceGate {
+    /// One-time initialization that sets the admin address.
+    ///
+    /// # Errors
+    /// * [`Error::AlreadyInitialized`] – called more than once.
+    pub fn initialize(env: Env, admin: Address) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        if storage::has_admin(&env) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+        storage::set_admin(&env, &admin);
+
+        Ok(())
+    }
+
+    /// Set the caps every subject of `role` has in `asset` unless it has
+    /// its own.  Admin-only.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::InvalidLimit`] – a cap is negative, or the weekly cap is
+    ///   below the daily one.
+    pub fn set_default_limits(
+        env: Env,
+        role: Role,
+        asset: Address,
+        limits: VelocityLimits,
+    ) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+        Self::require_admin(&env)?;
+        Self::check_limits(&limits)?;
+
+        storage::set_default_limits(&env, role, &asset, &limits);
+        events::emit_limits_set(
+            &env,
+            LimitsSet {
+                role,
+                subject: None,
+                asset,
+                limits,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Set the caps of one merchant or payer in `asset`, or clear them with
+    /// `None` so the role's defaults apply again.  Admin-only.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::InvalidLimit`] – a cap is negative, or the weekly cap is
+    ///   below the daily one.
+    pub fn set_limits(
+        env: Env,
+        role: Role,
+        subject: Address,
+        asset: Address,
+        limits: Option<VelocityLimits>,
+    ) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+        Self::require_admin(&env)?;
+        if let Some(limits) = &limits {
+            Self::check_limits(limits)?;
+        }
+
+        storage::set_limits(&env, role, &subject, &asset, &limits);
+        events::emit_limits_set(
+            &env,
+            LimitsSet {
+                role,
+                limits: Self::limits_for(&env, role, &subject, &asset),
+                subject: Some(subject),
+                asset,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Enroll `account` as collecting for `merchant`.  Admin-only.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    pub fn enroll(env: Env, account: Address, merchant: Address) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+        Self::require_admin(&env)?;
+
+        storage::set_merchant(&env, &account, &merchant);
+        events::emit_enrolled(&env, account, merchant);
+
+        Ok(())
+    }
+
+    /// Let a held account sweep again.  Admin-only.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    pub fn release(env: Env, account: Address) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+        Self::require_admin(&env)?;
+
+        if storage::is_held(&env, &account) {
+            storage::set_held(&env, &account, false);
+            events::emit_sweep_released(&env, account);
+        }
+
+        Ok(())
+    }
+
+    /// Add a payment `account` recorded to its merchant's and payer's
+    /// volume, holding the account's sweeps if either passes a cap.
+    ///
+    /// Requires `account`'s authorization, which an account reporting its
+    /// own payment provides.
+    ///
+    /// # Errors
+    /// * [`Error::NotEnrolled`] – `account` was never enrolled.
+    /// * [`Error::InvalidAmount`] – `amount` is not positive.
+    pub fn check_payment(
+        env: Env,
+        account: Address,
+        payer: Option<Address>,
+        asset: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+        account.require_auth();
+
+        let merchant = storage::get_merchant(&env, &account).ok_or(Error::NotEnrolled)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut exceeded =
+            Self::add_volume(&env, &account, Role::Merchant, &merchant, &asset, amount);
+        if let Some(payer) = payer {
+            exceeded |= Self::add_volume(&env, &account, Role::Payer, &payer, &asset, amount);
+        }
+        if exceeded {
+            storage::set_held(&env, &account, true);
+        }
+
+        Ok(())
+    }
+
+    /// Fail while `account`'s sweeps are held.
+    ///
+    /// # Errors
+    /// * [`Error::SweepHeld`] – a payment passed a cap and the admin has
+    ///   not released the account yet.
+    pub fn check_sweep(env: Env, account: Address) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+        if storage::is_held(&env, &account) {
+            return Err(Error::SweepHeld);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` while `account`'s sweeps are held.
+    pub fn is_held(env: Env, account: Address) -> bool {
+        storage::extend_instance_ttl(&env);
+        storage::is_held(&env, &account)
+    }
+
+    /// Merchant `account` was enrolled for, if any.
+    pub fn get_merchant(env: Env, account: Address) -> Option<Address> {
+        storage::extend_instance_ttl(&env);
+        storage::get_merchant(&env, &account)
+    }
+
+    /// Caps in force for `subject` in `asset`: its own, else the role's
+    /// defaults, else none.
+    pub fn get_limits(env: Env, role: Role, subject: Address, asset: Address) -> VelocityLimits {
+        storage::extend_instance_ttl(&env);
+        Self::limits_for(&env, role, &subject, &asset)
+    }
+
+    /// Volume of `subject` in `asset` over the current windows.
+    pub fn get_volume(env: Env, role: Role, subject: Address, asset: Address) -> Volume {
+        storage::extend_instance_ttl(&env);
+        let buckets = storage::get_volume(&env, role, &subject, &asset);
+        Self::volume(&buckets, Self::current_bucket(&env))
+    }
+
+    /// Returns the admin address, if the contract has been initialized.
+    pub fn get_admin(env: Env) -> Option<Address> {
+        storage::extend_instance_ttl(&env);
+        storage::get_admin(&env)
+    }
+
+    /// Return the name of the error raised as `Error(Contract, #code)`, or
+    /// `Unknown` for a code this contract never raises.
+    pub fn describe_error(env: Env, code: u32) -> Symbol {
+        let name = Error::try_from(soroban_sdk::Error::from_contract_error(code))
+            .map_or(UNKNOWN_ERROR, Error::name);
+        Symbol::new(&env, name)
+    }
+}
+
+impl ComplianceGate {
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin = storage::get_admin(env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn check_limits(limits: &VelocityLimits) -> Result<(), Error> {
+        let negative = |cap: Option<i128>| cap.is_some_and(|cap| cap < 0);
+        if negative(limits.daily) || negative(limits.weekly) {
+            return Err(Error::InvalidLimit);
+        }
+        if let (Some(daily), Some(weekly)) = (limits.daily, limits.weekly) {
+            if weekly < daily {
+                return Err(Error::InvalidLimit);
+            }
+        }
+        Ok(())
+    }
+
+    fn limits_for(env: &Env, role: Role, subject: &Address, asset: &Address) -> VelocityLimits {
+        storage::get_limits(env, role, subject, asset)
+            .or_else(|| storage::get_default_limits(env, role, asset))
+            .unwrap_or_default()
+    }
+
+    fn current_bucket(env: &Env) -> u64 {
+        env.ledger().timestamp() / BUCKET_SECONDS
+    }
+
+    /// Sum the buckets that fall in each window ending at `now`.
+    fn volume(buckets: &Map<u64, i128>, now: u64) -> Volume {
+        let mut volume = Volume::default();
+        for (bucket, amount) in buckets.iter() {
+            let age = now.saturating_sub(bucket);
+            if age < DAILY_BUCKETS {
+                volume.daily = volume.daily.saturating_add(amount);
+            }
+            if age < WEEKLY_BUCKETS {
+                volume.weekly = volume.weekly.saturating_add(amount);
+            }
+        }
+        volume
+    }
+
+    /// Add `amount` to `subject`'s current bucket, dropping buckets older
+    /// than a week, and report each cap the new volume passes.  Returns
+    /// whether any was passed.
+    fn add_volume(
+        env: &Env,
+        account: &Address,
+        role: Role,
+        subject: &Address,
+        asset: &Address,
+        amount: i128,
+    ) -> bool {
+        let now = Self::current_bucket(env);
+        let mut buckets = storage::get_volume(env, role, subject, asset);
+        for bucket in buckets.keys().iter() {
+            if now.saturating_sub(bucket) >= WEEKLY_BUCKETS {
+                buckets.remove(bucket);
+            }
+        }
+        let current = buckets.get(now).unwrap_or(0);
+        buckets.set(now, current.saturating_add(amount));
+        storage::set_volume(env, role, subject, asset, &buckets);
+
+        let volume = Self::volume(&buckets, now);
+        let limits = Self::limits_for(env, role, subject, asset);
+        let mut exceeded = false;
+        for (window, volume, cap) in [
+            (Window::Daily, volume.daily, limits.daily),
+            (Window::Weekly, volume.weekly, limits.weekly),
+        ] {
+            if let Some(cap) = cap.filter(|cap| volume > *cap) {
+                events::emit_limit_exceeded(
+                    env,
+                    LimitExceeded {
+                        account: account.clone(),
+                        role,
+                        subject: subject.clone(),
+                        asset: asset.clone(),
+                        window,
+                        volume,
+                        cap,
+                    },
+                );
+                exceeded = true;
+            }
+        }
+        exceeded
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{Role, VelocityLimits};
+use soroban_sdk::{contracttype, Address, Env, Map, TryFromVal, Val};
+
+/// Storage keys used by the compliance gate.
+///
+/// Settings and the admin live in instance storage.  Per-account and
+/// per-subject entries are persistent, so the instance does not grow
+/// with every merchant and payer seen.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// The admin (the risk team) allowed to set caps and release holds.
+    Admin,
+
+    /// Caps that apply to every subject of a role, per asset.
+    DefaultLimits(Role, Address),
+
+    /// Caps of one subject, per asset, overriding the role's defaults.
+    Limits(Role, Address, Address),
+
+    /// Merchant an enrolled account collects for.
+    Merchant(Address),
+
+    /// Set while an account's sweeps are held.
+    Held(Address),
+
+    /// Volume of a subject in an asset, per hour, over the last week.
+    Volume(Role, Address, Address),
+}
+
+// Admin helpers
+
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
+
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Admin)
+}
+
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().instance().has(&DataKey::Admin)
+}
+
+// Limit helpers
+
+pub fn set_default_limits(env: &Env, role: Role, asset: &Address, limits: &VelocityLimits) {
+    let key = DataKey::DefaultLimits(role, asset.clone());
+    env.storage().instance().set(&key, limits);
+}
+
+pub fn get_default_limits(env: &Env, role: Role, asset: &Address) -> Option<VelocityLimits> {
+    let key = DataKey::DefaultLimits(role, asset.clone());
+    env.storage().instance().get(&key)
+}
+
+pub fn set_limits(
+    env: &Env,
+    role: Role,
+    subject: &Address,
+    asset: &Address,
+    limits: &Option<VelocityLimits>,
+) {
+    let key = DataKey::Limits(role, subject.clone(), asset.clone());
+    match limits {
+        Some(limits) => {
+            env.storage().persistent().set(&key, limits);
+            extend_persistent_ttl(env, &key);
+        }
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+pub fn get_limits(
+    env: &Env,
+    role: Role,
+    subject: &Address,
+    asset: &Address,
+) -> Option<VelocityLimits> {
+    read_persistent(env, &DataKey::Limits(role, subject.clone(), asset.clone()))
+}
+
+// Enrollment helpers
+
+pub fn set_merchant(env: &Env, account: &Address, merchant: &Address) {
+    let key = DataKey::Merchant(account.clone());
+    env.storage().persistent().set(&key, merchant);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn get_merchant(env: &Env, account: &Address) -> Option<Address> {
+    read_persistent(env, &DataKey::Merchant(account.clone()))
+}
+
+// Hold helpers
+
+pub fn set_held(env: &Env, account: &Address, held: bool) {
+    let key = DataKey::Held(account.clone());
+    if held {
+        env.storage().persistent().set(&key, &true);
+        extend_persistent_ttl(env, &key);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+pub fn is_held(env: &Env, account: &Address) -> bool {
+    read_persistent(env, &DataKey::Held(account.clone())).unwrap_or(false)
+}
+
+// Volume helpers
+
+/// Volume per hour bucket (ledger timestamp / 3600).
+pub fn set_volume(
+    env: &Env,
+    role: Role,
+    subject: &Address,
+    asset: &Address,
+    buckets: &Map<u64, i128>,
+) {
+    let key = DataKey::Volume(role, subject.clone(), asset.clone());
+    env.storage().persistent().set(&key, buckets);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn get_volume(env: &Env, role: Role, subject: &Address, asset: &Address) -> Map<u64, i128> {
+    read_persistent(env, &DataKey::Volume(role, subject.clone(), asset.clone()))
+        .unwrap_or_else(|| Map::new(env))
+}
+
+// TTL management
+
+/// If the remaining TTL drops below this threshold (in ledgers), extend it.
+/// 259 200 ledgers ≈ 15 days, half of the extension window.
+const TTL_THRESHOLD: u32 = 259_200;
+
+/// Extend TTLs to this many ledgers.
+/// 518 400 ledgers ≈ 30 days (at ~5 s per ledger).
+const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Keep the contract instance alive; called from every public entry-point.
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+/// Extend a persistent entry after it is written or read.
+fn extend_persistent_ttl(env: &Env, key: &DataKey) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+/// Read a persistent entry, extending it if present, so a hold or an
+/// enrollment that is only checked does not lapse.
+fn read_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        extend_persistent_ttl(env, key);
+    }
+    value
+}
@@ -0,0 +1,349 @@
+extern crate std;
+
+use crate::{
+    ComplianceGate, ComplianceGateClient, Error, LimitExceeded, Role, VelocityLimits, Volume,
+    Window, BUCKET_SECONDS, DAILY_BUCKETS, WEEKLY_BUCKETS,
+};
+use bridgelet_shared::errors::{COMPLIANCE_GATE_ERRORS, UNKNOWN_ERROR};
+use bridgelet_shared::EventTopics;
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    Address, Env, IntoVal, Symbol, TryFromVal,
+};
+
+// HELPERS
+
+struct Setup {
+    env: Env,
+    client: ComplianceGateClient<'static>,
+    account: Address,
+    merchant: Address,
+    payer: Address,
+    asset: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000 * BUCKET_SECONDS);
+
+    let client = ComplianceGateClient::new(&env, &env.register(ComplianceGate, ()));
+    client.initialize(&Address::generate(&env));
+
+    let account = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.enroll(&account, &merchant);
+
+    Setup {
+        payer: Address::generate(&env),
+        asset: Address::generate(&env),
+        env,
+        client,
+        account,
+        merchant,
+    }
+}
+
+fn caps(daily: Option<i128>, weekly: Option<i128>) -> VelocityLimits {
+    VelocityLimits { daily, weekly }
+}
+
+fn advance_hours(env: &Env, hours: u64) {
+    let timestamp = env.ledger().timestamp();
+    env.ledger()
+        .set_timestamp(timestamp + hours * BUCKET_SECONDS);
+}
+
+/// The `limit_hit` event of the last call, which must be read before the
+/// next call replaces the recorded events.
+fn last_limit_hit(s: &Setup) -> LimitExceeded {
+    let topics = EventTopics::new(symbol_short!("limit_hit"));
+    let (_, _, data) = s
+        .env
+        .events()
+        .all()
+        .iter()
+        .filter(|(_, event_topics, _)| {
+            Symbol::try_from_val(&s.env, &event_topics.get(1).unwrap()) == Ok(topics.name.clone())
+        })
+        .last()
+        .expect("no limit_hit event");
+    LimitExceeded::try_from_val(&s.env, &data).unwrap()
+}
+
+//  Initialization
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1101)")]
+fn test_initialize_twice_panics() {
+    let s = setup();
+    s.client.initialize(&Address::generate(&s.env));
+}
+
+#[test]
+fn test_enroll_binds_merchant() {
+    let s = setup();
+    assert_eq!(s.client.get_merchant(&s.account), Some(s.merchant.clone()));
+}
+
+//  Limits
+
+#[test]
+fn test_subject_limits_override_defaults() {
+    let s = setup();
+    s.client
+        .set_default_limits(&Role::Merchant, &s.asset, &caps(Some(100), None));
+    assert_eq!(
+        s.client.get_limits(&Role::Merchant, &s.merchant, &s.asset),
+        caps(Some(100), None)
+    );
+
+    s.client.set_limits(
+        &Role::Merchant,
+        &s.merchant,
+        &s.asset,
+        &Some(caps(Some(500), Some(1_000))),
+    );
+    assert_eq!(
+        s.client.get_limits(&Role::Merchant, &s.merchant, &s.asset),
+        caps(Some(500), Some(1_000))
+    );
+    // Payers keep their own, unset, defaults
+    assert_eq!(
+        s.client.get_limits(&Role::Payer, &s.merchant, &s.asset),
+        VelocityLimits::default()
+    );
+
+    s.client
+        .set_limits(&Role::Merchant, &s.merchant, &s.asset, &None);
+    assert_eq!(
+        s.client.get_limits(&Role::Merchant, &s.merchant, &s.asset),
+        caps(Some(100), None)
+    );
+}
+
+#[test]
+fn test_invalid_limits_rejected() {
+    let s = setup();
+    let set = |limits: VelocityLimits| {
+        s.client
+            .try_set_default_limits(&Role::Payer, &s.asset, &limits)
+    };
+    assert_eq!(set(caps(Some(-1), None)), Err(Ok(Error::InvalidLimit)));
+    assert_eq!(set(caps(None, Some(-1))), Err(Ok(Error::InvalidLimit)));
+    assert_eq!(set(caps(Some(10), Some(5))), Err(Ok(Error::InvalidLimit)));
+}
+
+//  Payments
+
+#[test]
+fn test_payment_within_caps_adds_volume() {
+    let s = setup();
+    s.client
+        .set_default_limits(&Role::Merchant, &s.asset, &caps(Some(100), Some(300)));
+
+    s.client
+        .check_payment(&s.account, &Some(s.payer.clone()), &s.asset, &60);
+    s.client.check_payment(&s.account, &None, &s.asset, &40);
+
+    let merchant = s.client.get_volume(&Role::Merchant, &s.merchant, &s.asset);
+    assert_eq!(
+        merchant,
+        Volume {
+            daily: 100,
+            weekly: 100
+        }
+    );
+    let payer = s.client.get_volume(&Role::Payer, &s.payer, &s.asset);
+    assert_eq!(
+        payer,
+        Volume {
+            daily: 60,
+            weekly: 60
+        }
+    );
+    assert!(!s.client.is_held(&s.account));
+    assert_eq!(s.client.try_check_sweep(&s.account), Ok(Ok(())));
+}
+
+#[test]
+fn test_check_payment_requires_account_auth() {
+    let s = setup();
+    s.client.check_payment(&s.account, &None, &s.asset, &1);
+    let auths = s.env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, s.account);
+}
+
+#[test]
+fn test_merchant_over_daily_cap_holds_sweeps() {
+    let s = setup();
+    s.client
+        .set_default_limits(&Role::Merchant, &s.asset, &caps(Some(100), None));
+
+    s.client.check_payment(&s.account, &None, &s.asset, &101);
+
+    assert_eq!(
+        last_limit_hit(&s),
+        LimitExceeded {
+            account: s.account.clone(),
+            role: Role::Merchant,
+            subject: s.merchant.clone(),
+            asset: s.asset.clone(),
+            window: Window::Daily,
+            volume: 101,
+            cap: 100,
+        }
+    );
+    assert!(s.client.is_held(&s.account));
+    assert_eq!(
+        s.client.try_check_sweep(&s.account),
+        Err(Ok(Error::SweepHeld))
+    );
+}
+
+/// The merchant's cap spans every account enrolled for it.
+#[test]
+fn test_merchant_cap_spans_accounts() {
+    let s = setup();
+    s.client
+        .set_default_limits(&Role::Merchant, &s.asset, &caps(Some(100), None));
+    let other = Address::generate(&s.env);
+    s.client.enroll(&other, &s.merchant);
+
+    s.client.check_payment(&s.account, &None, &s.asset, &60);
+    s.client.check_payment(&other, &None, &s.asset, &60);
+
+    assert!(!s.client.is_held(&s.account));
+    assert!(s.client.is_held(&other));
+}
+
+#[test]
+fn test_payer_over_weekly_cap_holds_sweeps() {
+    let s = setup();
+    s.client.set_limits(
+        &Role::Payer,
+        &s.payer,
+        &s.asset,
+        &Some(caps(None, Some(100))),
+    );
+
+    s.client
+        .check_payment(&s.account, &Some(s.payer.clone()), &s.asset, &70);
+    advance_hours(&s.env, DAILY_BUCKETS * 3);
+    s.client
+        .check_payment(&s.account, &Some(s.payer.clone()), &s.asset, &70);
+
+    let hit = last_limit_hit(&s);
+    assert_eq!(hit.role, Role::Payer);
+    assert_eq!(hit.subject, s.payer);
+    assert_eq!(hit.window, Window::Weekly);
+    assert_eq!(hit.volume, 140);
+    assert!(s.client.is_held(&s.account));
+}
+
+#[test]
+fn test_windows_roll_by_the_hour() {
+    let s = setup();
+    s.client.check_payment(&s.account, &None, &s.asset, &50);
+
+    advance_hours(&s.env, DAILY_BUCKETS - 1);
+    let volume = s.client.get_volume(&Role::Merchant, &s.merchant, &s.asset);
+    assert_eq!(
+        volume,
+        Volume {
+            daily: 50,
+            weekly: 50
+        }
+    );
+
+    advance_hours(&s.env, 1);
+    let volume = s.client.get_volume(&Role::Merchant, &s.merchant, &s.asset);
+    assert_eq!(
+        volume,
+        Volume {
+            daily: 0,
+            weekly: 50
+        }
+    );
+
+    advance_hours(&s.env, WEEKLY_BUCKETS - DAILY_BUCKETS);
+    s.client.check_payment(&s.account, &None, &s.asset, &5);
+    let volume = s.client.get_volume(&Role::Merchant, &s.merchant, &s.asset);
+    assert_eq!(
+        volume,
+        Volume {
+            daily: 5,
+            weekly: 5
+        }
+    );
+}
+
+#[test]
+fn test_release_lets_account_sweep() {
+    let s = setup();
+    s.client
+        .set_default_limits(&Role::Merchant, &s.asset, &caps(Some(0), None));
+    s.client.check_payment(&s.account, &None, &s.asset, &1);
+    assert!(s.client.is_held(&s.account));
+
+    s.client.release(&s.account);
+
+    let (_, topics, _) = s.env.events().all().last().unwrap();
+    let expected = EventTopics::new(symbol_short!("released"));
+    assert_eq!(
+        topics,
+        (
+            expected.namespace,
+            expected.name,
+            expected.version,
+            s.account.clone()
+        )
+            .into_val(&s.env)
+    );
+    assert!(!s.client.is_held(&s.account));
+    assert_eq!(s.client.try_check_sweep(&s.account), Ok(Ok(())));
+}
+
+#[test]
+fn test_unenrolled_account_rejected() {
+    let s = setup();
+    assert_eq!(
+        s.client
+            .try_check_payment(&Address::generate(&s.env), &None, &s.asset, &1),
+        Err(Ok(Error::NotEnrolled))
+    );
+    assert_eq!(
+        s.client.try_check_payment(&s.account, &None, &s.asset, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_admin_calls_before_initialize_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = ComplianceGateClient::new(&env, &env.register(ComplianceGate, ()));
+    assert_eq!(
+        client.try_enroll(&Address::generate(&env), &Address::generate(&env)),
+        Err(Ok(Error::NotInitialized))
+    );
+}
+
+/// Codes stay inside the gate's shared range, so off-chain tooling can
+/// tell them from another contract's.
+#[test]
+fn test_error_codes_use_reserved_range() {
+    let env = Env::default();
+    let client = ComplianceGateClient::new(&env, &env.register(ComplianceGate, ()));
+    for code in COMPLIANCE_GATE_ERRORS {
+        let name = client.describe_error(&code);
+        if let Ok(error) = Error::try_from(soroban_sdk::Error::from_contract_error(code)) {
+            assert_eq!(name, Symbol::new(&env, error.name()));
+        } else {
+            assert_eq!(name, Symbol::new(&env, UNKNOWN_ERROR));
+        }
+    }
+    assert_eq!(client.describe_error(&1), Symbol::new(&env, UNKNOWN_ERROR));
+}
@@ -1,5 +1,6 @@
 use soroban_sdk::contracterror;
 
+/// Codes sit in [`bridgelet_shared::errors::EPHEMERAL_ACCOUNT_ERRORS`].
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
@@ -18,4 +19,94 @@ pub enum Error {
     InvalidStatus = 12,
     DuplicateAsset = 13,
     TooManyPayments = 14,
+    InvalidReason = 15,
+    AssetNotFound = 16,
+    InvalidShares = 17,
+    AssetNotAllowed = 18,
+    Halted = 19,
+    SenderUnknown = 20,
+    HoldNotElapsed = 21,
+    HoldElapsed = 22,
+    HoldRequired = 23,
+    ThresholdNotMet = 24,
+    InvalidSignerSet = 25,
+    DestinationMismatch = 26,
+    InvalidFee = 27,
+    BatchWindowOpen = 28,
+    TimelockActive = 29,
+    SystemPaused = 30,
+    StorageVersionMismatch = 31,
+    InsufficientNativeBalance = 32,
+    GracePeriodActive = 33,
+    BelowMinSweepValue = 34,
+    BelowMinPayment = 35,
+    PaymentCooldown = 36,
+    FundsRemaining = 37,
+    /// Returned by accounts built before keepers were paid a zero bounty
+    /// on a reserve not held in XLM; kept so the code is not reused.
+    KeeperBountyUnpayable = 38,
+    ReserveNotSet = 39,
+    InvalidCursor = 40,
+    DestinationCannotReceive = 41,
+    FeatureDisabled = 42,
+    Depegged = 43,
+    RetryTooEarly = 44,
+    ComplianceHold = 45,
+    NoPreparedSweep = 46,
+    CommitmentMismatch = 47,
+}
+
+impl Error {
+    /// Name of the variant, as `describe_error` reports it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Error::AlreadyInitialized => "AlreadyInitialized",
+            Error::NotInitialized => "NotInitialized",
+            Error::PaymentAlreadyReceived => "PaymentAlreadyReceived",
+            Error::InvalidAmount => "InvalidAmount",
+            Error::InvalidExpiry => "InvalidExpiry",
+            Error::NotExpired => "NotExpired",
+            Error::AlreadySwept => "AlreadySwept",
+            Error::Unauthorized => "Unauthorized",
+            Error::InvalidSignature => "InvalidSignature",
+            Error::NoPaymentReceived => "NoPaymentReceived",
+            Error::AccountExpired => "AccountExpired",
+            Error::InvalidStatus => "InvalidStatus",
+            Error::DuplicateAsset => "DuplicateAsset",
+            Error::TooManyPayments => "TooManyPayments",
+            Error::InvalidReason => "InvalidReason",
+            Error::AssetNotFound => "AssetNotFound",
+            Error::InvalidShares => "InvalidShares",
+            Error::AssetNotAllowed => "AssetNotAllowed",
+            Error::Halted => "Halted",
+            Error::SenderUnknown => "SenderUnknown",
+            Error::HoldNotElapsed => "HoldNotElapsed",
+            Error::HoldElapsed => "HoldElapsed",
+            Error::HoldRequired => "HoldRequired",
+            Error::ThresholdNotMet => "ThresholdNotMet",
+            Error::InvalidSignerSet => "InvalidSignerSet",
+            Error::DestinationMismatch => "DestinationMismatch",
+            Error::InvalidFee => "InvalidFee",
+            Error::BatchWindowOpen => "BatchWindowOpen",
+            Error::TimelockActive => "TimelockActive",
+            Error::SystemPaused => "SystemPaused",
+            Error::StorageVersionMismatch => "StorageVersionMismatch",
+            Error::InsufficientNativeBalance => "InsufficientNativeBalance",
+            Error::GracePeriodActive => "GracePeriodActive",
+            Error::BelowMinSweepValue => "BelowMinSweepValue",
+            Error::BelowMinPayment => "BelowMinPayment",
+            Error::PaymentCooldown => "PaymentCooldown",
+            Error::FundsRemaining => "FundsRemaining",
+            Error::KeeperBountyUnpayable => "KeeperBountyUnpayable",
+            Error::ReserveNotSet => "ReserveNotSet",
+            Error::InvalidCursor => "InvalidCursor",
+            Error::DestinationCannotReceive => "DestinationCannotReceive",
+            Error::FeatureDisabled => "FeatureDisabled",
+            Error::Depegged => "Depegged",
+            Error::RetryTooEarly => "RetryTooEarly",
+            Error::ComplianceHold => "ComplianceHold",
+            Error::NoPreparedSweep => "NoPreparedSweep",
+            Error::CommitmentMismatch => "CommitmentMismatch",
+        }
+    }
 }
@@ -1,11 +1,21 @@
-use bridgelet_shared::Payment;
-use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
+use crate::storage::AccountState;
+#[cfg(feature = "quarantine")]
+use crate::storage::QuarantineRetry;
+#[cfg(feature = "hooks")]
+use bridgelet_shared::AccountStatus;
+pub use bridgelet_shared::ReserveReclaimed;
+use bridgelet_shared::{ChainLink, EventTopics, Payment};
+#[cfg(feature = "limits")]
+use soroban_sdk::Map;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, IntoVal, Symbol, Val, Vec};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct AccountCreated {
     pub creator: Address,
     pub expiry_ledger: u32,
+    /// `AccountMetadata.reference`, if metadata was set.
+    pub reference: Option<BytesN<32>>,
 }
 
 #[contracttype]
@@ -13,6 +23,47 @@ pub struct AccountCreated {
 pub struct PaymentReceived {
     pub amount: i128,
     pub asset: Address,
+    pub payer_info_hash: Option<BytesN<32>>,
+    /// Decimals of `asset`, if it reported them.
+    pub decimals: Option<u32>,
+    pub sender: Option<Address>,
+    pub memo: Option<BytesN<32>>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentToppedUp {
+    pub asset: Address,
+    /// Amount added by this payment.
+    pub amount: i128,
+    /// Recorded amount for the asset after the top-up.
+    pub total_amount: i128,
+    pub payer_info_hash: Option<BytesN<32>>,
+    /// Sender and memo supplied with this top-up.
+    pub sender: Option<Address>,
+    pub memo: Option<BytesN<32>>,
+}
+
+#[cfg(feature = "refunds")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentRefunded {
+    pub asset: Address,
+    pub sender: Address,
+    pub amount: i128,
+}
+
+/// Gross, fee and net amount of one asset in a sweep.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SweepAmount {
+    pub asset: Address,
+    /// Recorded payment amount.
+    pub gross: i128,
+    /// Protocol fee sent to the fee collector.
+    pub fee: i128,
+    /// Amount transferred to the destination.
+    pub net: i128,
 }
 
 #[contracttype]
@@ -20,13 +71,114 @@ pub struct PaymentReceived {
 pub struct SweepExecutedMulti {
     pub destination: Address,
     pub payments: Vec<Payment>,
+    pub external_chain_ref: ChainLink,
+    pub amounts: Vec<SweepAmount>,
+    pub reference: Option<BytesN<32>>,
+    pub sweep_id: BytesN<32>,
+}
+
+#[cfg(feature = "partial")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PartialSweepExecuted {
+    pub destination: Address,
+    pub payments: Vec<Payment>,
+    pub amounts: Vec<SweepAmount>,
+    /// Recorded assets still awaiting a sweep after this one.
+    pub remaining_assets: u32,
+    pub reference: Option<BytesN<32>>,
+    pub sweep_id: BytesN<32>,
+}
+
+/// One destination's part of a `sweep_split`.
+#[cfg(feature = "partial")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SplitSweepExecuted {
+    pub destination: Address,
+    pub share_bps: u32,
+    /// Amounts transferred to this destination, per asset.
+    pub payments: Vec<Payment>,
+    pub reference: Option<BytesN<32>>,
+    /// Shared by every destination of the split.
+    pub sweep_id: BytesN<32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SweepSignerSet {
+    pub signer: BytesN<32>,
+}
+
+#[cfg(feature = "multisig")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SweepSignersSet {
+    pub signers: Vec<BytesN<32>>,
+    pub threshold: u32,
+}
+
+#[cfg(feature = "limits")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SweepPrioritySet {
+    pub assets: Vec<Address>,
+}
+
+#[cfg(feature = "limits")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentLimitsSet {
+    pub max_payments: u32,
+    pub min_amounts: Map<Address, i128>,
+    pub allowed_assets: Option<Vec<Address>>,
+    pub cooldown_ledgers: u32,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct MultiPaymentReceived {
-    pub asset: Address,
-    pub amount: i128,
+    /// Every payment the call recorded: one for `record_payment`, the
+    /// whole batch for `record_payments`.
+    pub payments: Vec<Payment>,
+}
+
+/// Why an account was closed to its recovery address.
+///
+/// Set by the entrypoint that closed the account, so analytics can tell
+/// abandonment from policy enforcement.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum CloseReason {
+    /// The expiry ledger passed (`expire`).
+    Timeout = 0,
+    /// The creator withdrew the payment request (`cancel`).
+    CreatorCancelled = 1,
+    /// A compliance check blocked the payment (`cancel`).
+    ComplianceBlock = 2,
+    /// The creator closed an account that saw no activity (`cancel`).
+    Inactivity = 3,
+}
+
+/// The settlement hook failed; the settlement itself went through.
+#[cfg(feature = "hooks")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HookFailed {
+    pub hook: Address,
+    pub status: AccountStatus,
+}
+
+/// The guardian recovered the account's funds before expiry.
+#[cfg(feature = "guardian")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmergencyRecovered {
+    pub guardian: Address,
+    pub recovery_address: Address,
+    pub payments: Vec<Payment>,
+    pub reserve_amount: i128,
 }
 
 #[contracttype]
@@ -35,42 +187,439 @@ pub struct AccountExpired {
     pub recovery_address: Address,
     pub amount_returned: i128,
     pub reserve_amount: i128,
+    pub reason: CloseReason,
+    pub reference: Option<BytesN<32>>,
 }
 
+#[cfg(feature = "lifecycle")]
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct ReserveReclaimed {
+pub struct AccountCancelled {
+    /// Recovery address, or the creator when no payment was recorded.
     pub destination: Address,
+    pub amount_returned: i128,
+    pub reserve_amount: i128,
+    pub reason: CloseReason,
+}
+
+/// More XLM was recorded as backing the reserve.
+#[cfg(feature = "reconcile")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveToppedUp {
     pub amount: i128,
-    pub sweep_id: u64,
-    pub fully_reclaimed: bool,
+    pub available_reserve: i128,
     pub remaining_reserve: i128,
 }
 
+/// The available reserve was reset from the balance actually held.
+#[cfg(feature = "reconcile")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveReconciled {
+    pub actual_balance: i128,
+    pub old_available_reserve: i128,
+    pub new_available_reserve: i128,
+    pub remaining_reserve: i128,
+}
+
+#[cfg(feature = "lifecycle")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExpiryExtended {
+    pub old_expiry_ledger: u32,
+    pub new_expiry_ledger: u32,
+}
+
+/// The factory reset the settled account for re-use; it starts `epoch`.
+#[cfg(feature = "reuse")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountReset {
+    pub factory: Address,
+    pub epoch: u32,
+    pub expiry_ledger: u32,
+    pub recovery_address: Address,
+}
+
+#[cfg(feature = "upgrade")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractUpgraded {
+    pub new_wasm_hash: BytesN<32>,
+    /// Code version replaced by the upgrade.
+    pub from_version: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StorageMigrated {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+#[cfg(feature = "escrow")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SweepHeld {
+    pub destination: Address,
+    pub release_after_ledger: u32,
+}
+
+/// An insured account expired with payments in it and opened a claim
+/// with its insurance pool.
+#[cfg(feature = "insurance")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuredExpiry {
+    pub pool: Address,
+    pub claim_id: u64,
+}
+
+/// A sweep destination committed to by `prepare_sweep`.
+#[cfg(feature = "prepared")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SweepPrepared {
+    pub commitment: BytesN<32>,
+    pub expires_ledger: u32,
+}
+
+/// A prepared sweep withdrawn before it was executed.
+#[cfg(feature = "prepared")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PreparedSweepCancelled {
+    pub commitment: BytesN<32>,
+    /// Whether the creator withdrew it, rather than the sweep signers.
+    pub by_creator: bool,
+}
+
+/// A sweep held because it moves assets that can be clawed back.
+#[cfg(feature = "escrow")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClawbackHeld {
+    pub destination: Address,
+    pub assets: Vec<Address>,
+    pub release_after_ledger: u32,
+}
+
+/// Clawback-held assets released by the end of the hold.
+#[cfg(feature = "escrow")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClawbackReleased {
+    pub destination: Address,
+    pub assets: Vec<Address>,
+}
+
+/// Assets a sweep left in the account because their transfer failed.
+#[cfg(feature = "quarantine")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetsQuarantined {
+    pub destination: Address,
+    pub assets: Vec<Address>,
+}
+
+/// A retry of a quarantined asset whose transfer failed again, leaving it
+/// quarantined until `next_retry_ledger`.
+#[cfg(feature = "quarantine")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QuarantineRetryFailed {
+    pub asset: Address,
+    pub attempts: u32,
+    pub next_retry_ledger: u32,
+}
+
+/// A quarantined asset sent on to its sweep's destination.
+#[cfg(feature = "quarantine")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QuarantineReleased {
+    pub destination: Address,
+    pub amount: SweepAmount,
+}
+
+/// Assets a sweep left in the account as dust, for `sweep_dust`.
+#[cfg(feature = "dust")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DustLeft {
+    pub destination: Address,
+    pub assets: Vec<Address>,
+}
+
+/// Dust sent on to its sweep's destination.
+#[cfg(feature = "dust")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DustSwept {
+    pub destination: Address,
+    pub amounts: Vec<SweepAmount>,
+}
+
+/// The guardian let sweeps settle guarded stablecoins off peg.
+#[cfg(feature = "depeg")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepegOverridden {
+    pub guardian: Address,
+    pub until_ledger: u32,
+}
+
+/// Part of a held payment clawed back by its issuer before release.
+#[cfg(feature = "escrow")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentClawedBack {
+    pub asset: Address,
+    pub recorded: i128,
+    pub clawed_back: i128,
+}
+
+#[cfg(feature = "escrow")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SweepDisputed {
+    pub arbiter: Address,
+    pub destination: Address,
+    pub recovery_address: Address,
+}
+
+#[cfg(feature = "guardian")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DestinationChangeProposed {
+    pub destination: Address,
+    pub effective_ledger: u32,
+}
+
+#[cfg(feature = "guardian")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DestinationChanged {
+    pub old_destination: Address,
+    pub new_destination: Address,
+}
+
+/// Context for a state-changing call that failed validation.
+///
+/// Only published when the account was initialized with `debug_events`.
+/// The failing invocation is rolled back, so the event is only visible in
+/// simulation / diagnostic output, next to the bare contract error.
+#[cfg(feature = "diagnostics")]
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct FailureDiagnostic {
+    pub function: Symbol,
+    pub error_code: u32,
+    pub params: Vec<Val>,
+}
+
+/// Topics of the `name` event, namespaced by the deployment's event prefix
+/// when one was stamped at initialization.  Carries the state sequence
+/// number the running call took, shared by every event it publishes, and
+/// the epoch the account is in.
+fn topics(env: &Env, name: Symbol) -> EventTopics {
+    let state = AccountState::new(env);
+    let topics = match state.get_event_prefix() {
+        Some(prefix) => EventTopics::with_namespace(prefix, name),
+        None => EventTopics::new(name),
+    };
+    topics
+        .with_seq(state.get_state_seq())
+        .with_epoch(state.get_epoch())
+}
+
+/// Reference of the account's metadata, carried in its lifecycle events.
+fn reference(env: &Env) -> Option<BytesN<32>> {
+    AccountState::new(env)
+        .get_metadata()
+        .map(|metadata| metadata.reference)
+}
+
+// Events are converted to a `Val` before publishing, so the topic
+// plumbing is compiled once rather than once per event type.
+fn publish<D: IntoVal<Env, Val>>(env: &Env, name: Symbol, event: D) {
+    publish_val(env, name, event.into_val(env));
+}
+
+fn publish_val(env: &Env, name: Symbol, event: Val) {
+    topics(env, name).publish(env, event);
+}
+
+/// Publish `event` with `indexed` as its last topic.
+fn publish_indexed<D: IntoVal<Env, Val>>(env: &Env, name: Symbol, indexed: Address, event: D) {
+    publish_indexed_val(env, name, indexed, event.into_val(env));
+}
+
+fn publish_indexed_val(env: &Env, name: Symbol, indexed: Address, event: Val) {
+    topics(env, name).publish_indexed(env, indexed, event);
+}
+
 pub fn emit_account_created(env: &Env, creator: Address, expiry_ledger: u32) {
     let event = AccountCreated {
         creator,
         expiry_ledger,
+        reference: reference(env),
     };
-    env.events().publish((symbol_short!("created"),), event);
+    publish_indexed(env, symbol_short!("created"), event.creator.clone(), event);
 }
 
-pub fn emit_payment_received(env: &Env, amount: i128, asset: Address) {
-    let event = PaymentReceived { amount, asset };
-    env.events().publish((symbol_short!("payment"),), event);
+pub fn emit_payment_received(env: &Env, payment: Payment) {
+    let event = PaymentReceived {
+        amount: payment.amount,
+        asset: payment.asset,
+        payer_info_hash: payment.payer_info_hash,
+        decimals: payment.decimals,
+        sender: payment.sender,
+        memo: payment.memo,
+    };
+    publish_indexed(env, symbol_short!("payment"), event.asset.clone(), event);
 }
 
-pub fn emit_sweep_executed_multi(env: &Env, destination: Address, payments: &Vec<Payment>) {
+pub fn emit_payment_topped_up(
+    env: &Env,
+    asset: Address,
+    amount: i128,
+    total_amount: i128,
+    payer_info_hash: Option<BytesN<32>>,
+    sender: Option<Address>,
+    memo: Option<BytesN<32>>,
+) {
+    let event = PaymentToppedUp {
+        asset,
+        amount,
+        total_amount,
+        payer_info_hash,
+        sender,
+        memo,
+    };
+    publish_indexed(env, symbol_short!("topped_up"), event.asset.clone(), event);
+}
+
+#[cfg(feature = "refunds")]
+pub fn emit_payment_refunded(env: &Env, asset: Address, sender: Address, amount: i128) {
+    let event = PaymentRefunded {
+        asset,
+        sender,
+        amount,
+    };
+    publish_indexed(env, symbol_short!("refunded"), event.asset.clone(), event);
+}
+
+pub fn emit_sweep_executed_multi(
+    env: &Env,
+    destination: Address,
+    payments: &Vec<Payment>,
+    external_chain_ref: ChainLink,
+    amounts: Vec<SweepAmount>,
+    sweep_id: BytesN<32>,
+) {
     let event = SweepExecutedMulti {
         destination,
         payments: payments.clone(),
+        external_chain_ref,
+        amounts,
+        reference: reference(env),
+        sweep_id,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("swept_mul"),
+        event.destination.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "partial")]
+pub fn emit_partial_sweep_executed(
+    env: &Env,
+    destination: Address,
+    payments: &Vec<Payment>,
+    amounts: Vec<SweepAmount>,
+    remaining_assets: u32,
+    sweep_id: BytesN<32>,
+) {
+    let event = PartialSweepExecuted {
+        destination,
+        payments: payments.clone(),
+        amounts,
+        remaining_assets,
+        reference: reference(env),
+        sweep_id,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("swept_prt"),
+        event.destination.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "partial")]
+pub fn emit_split_sweep_executed(
+    env: &Env,
+    destination: Address,
+    share_bps: u32,
+    payments: Vec<Payment>,
+    sweep_id: BytesN<32>,
+) {
+    let event = SplitSweepExecuted {
+        destination,
+        share_bps,
+        payments,
+        reference: reference(env),
+        sweep_id,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("swept_spl"),
+        event.destination.clone(),
+        event,
+    );
+}
+
+pub fn emit_sweep_signer_set(env: &Env, signer: BytesN<32>) {
+    let event = SweepSignerSet { signer };
+    publish(env, symbol_short!("signer"), event);
+}
+
+#[cfg(feature = "multisig")]
+pub fn emit_sweep_signers_set(env: &Env, signers: Vec<BytesN<32>>, threshold: u32) {
+    let event = SweepSignersSet { signers, threshold };
+    publish(env, symbol_short!("signers"), event);
+}
+
+#[cfg(feature = "limits")]
+pub fn emit_sweep_priority_set(env: &Env, assets: Vec<Address>) {
+    let event = SweepPrioritySet { assets };
+    publish(env, symbol_short!("priority"), event);
+}
+
+#[cfg(feature = "limits")]
+pub fn emit_payment_limits_set(
+    env: &Env,
+    max_payments: u32,
+    min_amounts: Map<Address, i128>,
+    allowed_assets: Option<Vec<Address>>,
+    cooldown_ledgers: u32,
+) {
+    let event = PaymentLimitsSet {
+        max_payments,
+        min_amounts,
+        allowed_assets,
+        cooldown_ledgers,
     };
-    env.events().publish((symbol_short!("swept_mul"),), event);
+    publish(env, symbol_short!("limits"), event);
 }
 
-pub fn emit_multi_payment_received(env: &Env, asset: Address, amount: i128) {
-    let event = MultiPaymentReceived { asset, amount };
-    env.events().publish((symbol_short!("multi_pay"),), event);
+pub fn emit_multi_payment_received(env: &Env, payments: Vec<Payment>) {
+    let event = MultiPaymentReceived { payments };
+    publish(env, symbol_short!("multi_pay"), event);
 }
 
 pub fn emit_account_expired(
@@ -83,24 +632,362 @@ pub fn emit_account_expired(
         recovery_address,
         amount_returned,
         reserve_amount,
+        reason: CloseReason::Timeout,
+        reference: reference(env),
     };
-    env.events().publish((symbol_short!("expired"),), event);
+    publish_indexed(
+        env,
+        symbol_short!("expired"),
+        event.recovery_address.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "hooks")]
+pub fn emit_hook_failed(env: &Env, hook: Address, status: AccountStatus) {
+    let event = HookFailed { hook, status };
+    publish_indexed(env, symbol_short!("hook_fail"), event.hook.clone(), event);
 }
 
-pub fn emit_reserve_reclaimed(
+#[cfg(feature = "guardian")]
+pub fn emit_emergency_recovered(
+    env: &Env,
+    guardian: Address,
+    recovery_address: Address,
+    payments: Vec<Payment>,
+    reserve_amount: i128,
+) {
+    let event = EmergencyRecovered {
+        guardian,
+        recovery_address,
+        payments,
+        reserve_amount,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("emergency"),
+        event.recovery_address.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "lifecycle")]
+pub fn emit_account_cancelled(
     env: &Env,
     destination: Address,
+    amount_returned: i128,
+    reserve_amount: i128,
+    reason: CloseReason,
+) {
+    let event = AccountCancelled {
+        destination,
+        amount_returned,
+        reserve_amount,
+        reason,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("cancelled"),
+        event.destination.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "lifecycle")]
+pub fn emit_expiry_extended(env: &Env, old_expiry_ledger: u32, new_expiry_ledger: u32) {
+    let event = ExpiryExtended {
+        old_expiry_ledger,
+        new_expiry_ledger,
+    };
+    publish(env, symbol_short!("extended"), event);
+}
+
+#[cfg(feature = "reuse")]
+pub fn emit_account_reset(
+    env: &Env,
+    factory: Address,
+    epoch: u32,
+    expiry_ledger: u32,
+    recovery_address: Address,
+) {
+    let event = AccountReset {
+        factory,
+        epoch,
+        expiry_ledger,
+        recovery_address,
+    };
+    publish_indexed(env, symbol_short!("reset"), event.factory.clone(), event);
+}
+
+#[cfg(feature = "reconcile")]
+pub fn emit_reserve_topped_up(
+    env: &Env,
     amount: i128,
-    sweep_id: u64,
-    fully_reclaimed: bool,
+    available_reserve: i128,
     remaining_reserve: i128,
 ) {
-    let event = ReserveReclaimed {
-        destination,
+    let event = ReserveToppedUp {
         amount,
-        sweep_id,
-        fully_reclaimed,
+        available_reserve,
         remaining_reserve,
     };
-    env.events().publish((symbol_short!("reserve"),), event);
+    publish(env, symbol_short!("rsv_topup"), event);
+}
+
+#[cfg(feature = "reconcile")]
+pub fn emit_reserve_reconciled(
+    env: &Env,
+    actual_balance: i128,
+    old_available_reserve: i128,
+    new_available_reserve: i128,
+    remaining_reserve: i128,
+) {
+    let event = ReserveReconciled {
+        actual_balance,
+        old_available_reserve,
+        new_available_reserve,
+        remaining_reserve,
+    };
+    publish(env, symbol_short!("rsv_recon"), event);
+}
+
+#[cfg(feature = "upgrade")]
+pub fn emit_contract_upgraded(env: &Env, new_wasm_hash: BytesN<32>, from_version: u32) {
+    let event = ContractUpgraded {
+        new_wasm_hash,
+        from_version,
+    };
+    publish(env, symbol_short!("upgraded"), event);
+}
+
+pub fn emit_storage_migrated(env: &Env, from_version: u32, to_version: u32) {
+    let event = StorageMigrated {
+        from_version,
+        to_version,
+    };
+    publish(env, symbol_short!("migrated"), event);
+}
+
+#[cfg(feature = "escrow")]
+pub fn emit_sweep_held(env: &Env, destination: Address, release_after_ledger: u32) {
+    let event = SweepHeld {
+        destination,
+        release_after_ledger,
+    };
+    publish_indexed(env, symbol_short!("held"), event.destination.clone(), event);
+}
+
+#[cfg(feature = "escrow")]
+pub fn emit_clawback_held(
+    env: &Env,
+    destination: Address,
+    assets: Vec<Address>,
+    release_after_ledger: u32,
+) {
+    let event = ClawbackHeld {
+        destination,
+        assets,
+        release_after_ledger,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("cb_held"),
+        event.destination.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "escrow")]
+pub fn emit_clawback_released(env: &Env, destination: Address, assets: Vec<Address>) {
+    let event = ClawbackReleased {
+        destination,
+        assets,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("cb_freed"),
+        event.destination.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "quarantine")]
+pub fn emit_assets_quarantined(env: &Env, destination: Address, assets: Vec<Address>) {
+    let event = AssetsQuarantined {
+        destination,
+        assets,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("quarantin"),
+        event.destination.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "quarantine")]
+pub fn emit_quarantine_released(env: &Env, destination: Address, amount: SweepAmount) {
+    let event = QuarantineReleased {
+        destination,
+        amount,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("q_release"),
+        event.destination.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "quarantine")]
+pub fn emit_quarantine_retry_failed(env: &Env, asset: Address, retry: QuarantineRetry) {
+    let event = QuarantineRetryFailed {
+        asset,
+        attempts: retry.attempts,
+        next_retry_ledger: retry.next_retry_ledger,
+    };
+    publish_indexed(env, symbol_short!("q_retry"), event.asset.clone(), event);
+}
+
+#[cfg(feature = "dust")]
+pub fn emit_dust_left(env: &Env, destination: Address, assets: Vec<Address>) {
+    let event = DustLeft {
+        destination,
+        assets,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("dust_left"),
+        event.destination.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "dust")]
+pub fn emit_dust_swept(env: &Env, destination: Address, amounts: Vec<SweepAmount>) {
+    let event = DustSwept {
+        destination,
+        amounts,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("dust_sent"),
+        event.destination.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "depeg")]
+pub fn emit_depeg_overridden(env: &Env, guardian: Address, until_ledger: u32) {
+    let event = DepegOverridden {
+        guardian,
+        until_ledger,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("depeg_ovr"),
+        event.guardian.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "escrow")]
+pub fn emit_payment_clawed_back(env: &Env, asset: Address, recorded: i128, clawed_back: i128) {
+    let event = PaymentClawedBack {
+        asset,
+        recorded,
+        clawed_back,
+    };
+    publish_indexed(env, symbol_short!("clawback"), event.asset.clone(), event);
+}
+
+#[cfg(feature = "escrow")]
+pub fn emit_sweep_disputed(
+    env: &Env,
+    arbiter: Address,
+    destination: Address,
+    recovery_address: Address,
+) {
+    let event = SweepDisputed {
+        arbiter,
+        destination,
+        recovery_address,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("disputed"),
+        event.destination.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "guardian")]
+pub fn emit_destination_change_proposed(env: &Env, destination: Address, effective_ledger: u32) {
+    let event = DestinationChangeProposed {
+        destination,
+        effective_ledger,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("dest_prop"),
+        event.destination.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "guardian")]
+pub fn emit_destination_changed(env: &Env, old_destination: Address, new_destination: Address) {
+    let event = DestinationChanged {
+        old_destination,
+        new_destination,
+    };
+    publish_indexed(
+        env,
+        symbol_short!("dest_chg"),
+        event.new_destination.clone(),
+        event,
+    );
+}
+
+pub fn emit_reserve_reclaimed(env: &Env, event: ReserveReclaimed) {
+    publish_indexed(
+        env,
+        symbol_short!("reserve"),
+        event.destination.clone(),
+        event,
+    );
+}
+
+#[cfg(feature = "diagnostics")]
+pub fn emit_failure_diagnostic(env: &Env, function: Symbol, error_code: u32, params: Vec<Val>) {
+    let event = FailureDiagnostic {
+        function,
+        error_code,
+        params,
+    };
+    publish(env, symbol_short!("diag"), event);
+}
+
+#[cfg(feature = "insurance")]
+pub fn emit_insured_expiry(env: &Env, pool: Address, claim_id: u64) {
+    let event = InsuredExpiry { pool, claim_id };
+    publish_indexed(env, symbol_short!("insured"), event.pool.clone(), event);
+}
+
+#[cfg(feature = "prepared")]
+pub fn emit_sweep_prepared(env: &Env, commitment: BytesN<32>, expires_ledger: u32) {
+    let event = SweepPrepared {
+        commitment,
+        expires_ledger,
+    };
+    publish(env, symbol_short!("prepared"), event);
+}
+
+#[cfg(feature = "prepared")]
+pub fn emit_prepared_sweep_cancelled(env: &Env, commitment: BytesN<32>, by_creator: bool) {
+    let event = PreparedSweepCancelled {
+        commitment,
+        by_creator,
+    };
+    publish(env, symbol_short!("prep_cncl"), event);
 }
@@ -6,17 +6,170 @@ mod storage;
 #[cfg(test)]
 mod test;
 
-use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Vec};
+#[cfg(feature = "views")]
+use bridgelet_shared::errors::UNKNOWN_ERROR;
+#[cfg(any(feature = "history", feature = "views"))]
+use bridgelet_shared::page_bounds;
+use bridgelet_shared::units;
+#[cfg(feature = "partial")]
+use bridgelet_shared::units::BPS_DENOMINATOR;
+#[cfg(feature = "compliance")]
+use bridgelet_shared::ComplianceGateClient;
+#[cfg(feature = "insurance")]
+use bridgelet_shared::InsurancePoolClient;
+use bridgelet_shared::ReserveClient;
+#[cfg(feature = "config")]
+use bridgelet_shared::ReserveSnapshot;
+#[cfg(feature = "hooks")]
+use bridgelet_shared::SettlementHookClient;
+#[cfg(feature = "oracle")]
+use bridgelet_shared::{OracleAsset, PriceOracleClient};
+#[cfg(feature = "auth")]
+use soroban_sdk::{
+    auth::{Context, CustomAccountInterface},
+    crypto::Hash,
+};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, symbol_short, token, vec, xdr::ToXdr, Address, Bytes,
+    BytesN, Env, IntoVal, Map, Symbol, Val, Vec,
+};
+use storage::AccountState;
 
-pub use bridgelet_shared::{AccountInfo, AccountStatus, Payment};
+pub use bridgelet_shared::{
+    AccountConfig, AccountInfo, AccountLabel, AccountMetadata, AccountSnapshot, AccountStatus,
+    AssetKind, AuditTrail, AuthPolicy, Capabilities, ChainLink, Cursor, DeploymentSettings,
+    ExternalChainRef, Invariant, Payment, PaymentPage, ReserveEventPage, SignerKind,
+    StateAttestation,
+};
 pub use errors::Error;
+#[cfg(feature = "reuse")]
+pub use events::AccountReset;
+#[cfg(feature = "upgrade")]
+pub use events::ContractUpgraded;
+#[cfg(feature = "depeg")]
+pub use events::DepegOverridden;
+#[cfg(feature = "diagnostics")]
+pub use events::FailureDiagnostic;
+#[cfg(feature = "hooks")]
+pub use events::HookFailed;
+#[cfg(feature = "insurance")]
+pub use events::InsuredExpiry;
+#[cfg(feature = "refunds")]
+pub use events::PaymentRefunded;
+#[cfg(feature = "multisig")]
+pub use events::SweepSignersSet;
+#[cfg(feature = "lifecycle")]
+pub use events::{AccountCancelled, ExpiryExtended};
 pub use events::{
-    AccountCreated, AccountExpired, MultiPaymentReceived, PaymentReceived, ReserveReclaimed,
-    SweepExecutedMulti,
+    AccountCreated, AccountExpired, CloseReason, MultiPaymentReceived, PaymentReceived,
+    PaymentToppedUp, ReserveReclaimed, StorageMigrated, SweepAmount, SweepExecutedMulti,
+    SweepSignerSet,
+};
+#[cfg(feature = "quarantine")]
+pub use events::{AssetsQuarantined, QuarantineReleased, QuarantineRetryFailed};
+#[cfg(feature = "escrow")]
+pub use events::{ClawbackHeld, ClawbackReleased, PaymentClawedBack, SweepDisputed, SweepHeld};
+#[cfg(feature = "guardian")]
+pub use events::{DestinationChangeProposed, DestinationChanged, EmergencyRecovered};
+#[cfg(feature = "dust")]
+pub use events::{DustLeft, DustSwept};
+#[cfg(feature = "partial")]
+pub use events::{PartialSweepExecuted, SplitSweepExecuted};
+#[cfg(feature = "limits")]
+pub use events::{PaymentLimitsSet, SweepPrioritySet};
+#[cfg(feature = "prepared")]
+pub use events::{PreparedSweepCancelled, SweepPrepared};
+#[cfg(feature = "reconcile")]
+pub use events::{ReserveReconciled, ReserveToppedUp};
+#[cfg(feature = "config")]
+pub use storage::ConfigDataKey;
+#[cfg(feature = "guardian")]
+pub use storage::PendingDestination;
+#[cfg(feature = "escrow")]
+pub use storage::PendingRelease;
+#[cfg(feature = "prepared")]
+pub use storage::PreparedSweep;
+#[cfg(feature = "quarantine")]
+pub use storage::QuarantineRetry;
+#[cfg(feature = "audit")]
+pub use storage::SweepPreview;
+pub use storage::{
+    DataKey, ExtDataKey, GuardianConfig, Lifecycle, PaymentLimits, ReserveReclaimSummary,
 };
-pub use storage::DataKey;
 
-const BASE_RESERVE_STROOPS: i128 = 1_000_000_000;
+/// Base reserve used when neither a reserve contract nor an explicit
+/// amount is configured.
+const BASE_RESERVE_STROOPS: i128 = units::DEFAULT_BASE_RESERVE_STROOPS;
+
+/// Number of most recent reserve reclaim events kept in storage.
+const RESERVE_EVENT_HISTORY: u32 = 5;
+
+/// Version of this contract's code, recorded by every account it initializes
+/// and by the storage migration after an `upgrade`.
+pub const WASM_VERSION: u32 = 1;
+
+/// Version of the storage layout this code reads and writes.
+///
+/// Bump whenever a released layout changes: a key is added, removed or
+/// changes type.  Add the matching step to `migrate_layout`.
+pub const STORAGE_VERSION: u32 = 1;
+
+/// Contract kind reported by `get_version`.
+#[cfg(feature = "upgrade")]
+const CONTRACT_NAME: Symbol = symbol_short!("account");
+
+/// Schema version of the `AccountConfig` accepted by `initialize_with_config`.
+///
+/// Bump whenever a field is added to or removed from `AccountConfig`.
+pub const CONFIG_SCHEMA_VERSION: u32 = 23;
+
+/// Maximum number of distinct assets an account accepts payments in.
+///
+/// The creator may configure a lower limit, never a higher one.
+const MAX_PAYMENT_ASSETS: u32 = 10;
+
+/// Maximum number of destinations a `sweep_split` pays out to.
+#[cfg(feature = "partial")]
+const MAX_SPLIT_DESTINATIONS: u32 = 10;
+
+/// Ledgers a prepared sweep may be executed for, about an hour.
+#[cfg(feature = "prepared")]
+pub const PREPARED_SWEEP_LEDGERS: u32 = 720;
+
+/// Maximum number of keys in a sweep signer set.
+#[cfg(feature = "multisig")]
+const MAX_SWEEP_SIGNERS: u32 = 10;
+
+/// Built without `multisig`, a signer set holds a single key.
+#[cfg(all(feature = "config", not(feature = "multisig")))]
+const MAX_SWEEP_SIGNERS: u32 = 1;
+
+/// Ledgers a quarantined asset waits before its first retry (~1 minute).
+/// Each failed retry doubles the wait.
+#[cfg(feature = "quarantine")]
+const QUARANTINE_RETRY_BASE_LEDGERS: u32 = 12;
+
+/// Longest wait between retries of a quarantined asset (~1 day).
+#[cfg(feature = "quarantine")]
+const QUARANTINE_RETRY_MAX_LEDGERS: u32 = 17_280;
+
+/// Subsystem this contract checks on the kill switch.
+const KILL_SWITCH_SUBSYSTEM: Symbol = symbol_short!("account");
+
+/// Half the order of the P-256 group, big-endian: the largest `s` of a
+/// low-S passkey signature.
+#[cfg(feature = "passkey")]
+const P256_HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0x80, 0x00, 0x00, 0x00, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xde, 0x73, 0x7d, 0x56, 0xd3, 0x8b, 0xcf, 0x42, 0x79, 0xdc, 0xe5, 0x61, 0x7e, 0x31, 0x92, 0xa8,
+];
+
+/// The part of the `KillSwitch` contract an account depends on.
+#[contractclient(name = "KillSwitchClient")]
+pub trait KillSwitchInterface {
+    /// Whether the subsystem (or every subsystem) is halted.
+    fn is_halted(env: Env, subsystem: Symbol) -> bool;
+}
 
 #[contract]
 pub struct EphemeralAccountContract;
@@ -38,36 +191,67 @@ impl EphemeralAccountContract {
         expiry_ledger: u32,
         recovery_address: Address,
     ) -> Result<(), Error> {
-        // Check if already initialized
-        if storage::is_initialized(&env) {
-            return Err(Error::AlreadyInitialized);
-        }
-
-        // Verify creator authorization
-        creator.require_auth();
+        Self::apply_initialize(
+            &env,
+            &creator,
+            expiry_ledger,
+            &recovery_address,
+            &AccountConfig::default(),
+        )
+    }
 
-        // Validate expiry is in future
-        let current_ledger = env.ledger().sequence();
-        if expiry_ledger <= current_ledger {
-            return Err(Error::InvalidExpiry);
+    /// Initialize an account deployed by a factory, with the settings the
+    /// factory stamps into every account
+    ///
+    /// Behaves like `initialize`, setting `factory`, `kill_switch` and
+    /// `event_prefix` as `AccountConfig` does, and the `insurance_pool` to
+    /// claim with if the account expires with payments in it.  Every build
+    /// has it, so a factory can deploy an account wasm built without
+    /// `config`.
+    ///
+    /// # Errors
+    /// Returns Error::AlreadyInitialized if called more than once
+    /// Returns Error::InvalidExpiry if `expiry_ledger` is not in the future
+    /// Returns Error::FeatureDisabled if `factory` is set in a build
+    /// without `reuse`, or `insurance_pool` in a build without `insurance`
+    /// Returns Error::Halted if the kill switch already halts accounts
+    pub fn initialize_with_deployment(
+        env: Env,
+        creator: Address,
+        expiry_ledger: u32,
+        recovery_address: Address,
+        deployment: DeploymentSettings,
+    ) -> Result<(), Error> {
+        Self::apply_initialize(
+            &env,
+            &creator,
+            expiry_ledger,
+            &recovery_address,
+            &AccountConfig {
+                factory: deployment.factory,
+                kill_switch: deployment.kill_switch,
+                event_prefix: deployment.event_prefix,
+                ..AccountConfig::default()
+            },
+        )?;
+        match deployment.insurance_pool {
+            #[cfg(feature = "insurance")]
+            Some(pool) => AccountState::new(&env).set_insurance_pool(&pool),
+            #[cfg(not(feature = "insurance"))]
+            Some(_) => return Err(Error::FeatureDisabled),
+            None => {}
         }
-
-        // Store initialization data
-        storage::set_initialized(&env, true);
-        storage::set_creator(&env, &creator);
-        storage::set_expiry_ledger(&env, expiry_ledger);
-        storage::set_recovery_address(&env, &recovery_address);
-        storage::set_status(&env, AccountStatus::Active);
-        storage::init_reserve_tracking(&env, BASE_RESERVE_STROOPS);
-
-        // Emit event
-        events::emit_account_created(&env, creator, expiry_ledger);
-
         Ok(())
     }
 
     /// Record an inbound payment to this ephemeral account
     /// Multiple payments with different assets are supported
+    /// With `accumulate_payments` configured, a repeated asset is added to
+    /// its recorded payment (`topped_up` event)
+    ///
+    /// Must be authorized by the creator.  The variants that name a sender
+    /// need the sender's authorization instead, so nobody can fill the
+    /// payment slots in someone else's name.
     ///
     /// # Arguments
     /// * `amount` - Payment amount
@@ -75,52 +259,20 @@ impl EphemeralAccountContract {
     ///
     /// # Errors
     /// Returns Error::InvalidAmount if amount is not positive
-    /// Returns Error::DuplicateAsset if asset already has a payment
+    /// Returns Error::InvalidStatus if the account is closed or its sweep is held
+    /// Returns Error::DuplicateAsset if asset already has a payment and
+    /// accumulation is off, or its payment was already swept
+    /// Returns Error::AssetNotAllowed if the asset is not on the allowlist
+    /// Returns Error::BelowMinPayment if amount is below the asset's minimum
+    /// Returns Error::PaymentCooldown if the previous payment was recorded
+    /// less than `cooldown_ledgers` ago
+    /// Returns Error::TooManyPayments if the payment limit is reached
     pub fn record_payment(env: Env, amount: i128, asset: Address) -> Result<(), Error> {
-        // Check initialized
-        if !storage::is_initialized(&env) {
-            return Err(Error::NotInitialized);
-        }
-
-        // Validate amount
-        if amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
-
-        // Check for duplicate asset
-        if storage::get_payment(&env, &asset).is_some() {
-            return Err(Error::DuplicateAsset);
-        }
-
-        // Check payment limit to prevent gas issues (max 10 assets)
-        let payment_count = storage::get_total_payments(&env);
-        if payment_count >= 10 {
-            return Err(Error::TooManyPayments);
-        }
-
-        // Create payment with current timestamp
-        let payment = Payment {
-            asset: asset.clone(),
-            amount,
-            timestamp: env.ledger().timestamp(),
-        };
-
-        // Add payment
-        storage::add_payment(&env, payment);
-
-        // Update status only on first payment
-        if payment_count == 0 {
-            storage::set_status(&env, AccountStatus::PaymentReceived);
-        }
-
-        // Emit appropriate event
-        if payment_count == 0 {
-            events::emit_payment_received(&env, amount, asset);
-        } else {
-            events::emit_multi_payment_received(&env, asset, amount);
-        }
-
-        Ok(())
+        let result = Self::apply_record_payment(&env, amount, asset.clone(), None, None, None);
+        Self::report_failure(&env, "record_payment", &result, || {
+            vec![&env, amount.into_val(&env), asset.into_val(&env)]
+        });
+        result
     }
 
     /// Execute sweep to destination wallet
@@ -128,69 +280,102 @@ impl EphemeralAccountContract {
     ///
     /// # Arguments
     /// * `destination` - Recipient wallet address
-    /// * `auth_signature` - Authorization signature from off-chain system
+    /// * `auth_signatures` - Authorization signatures from off-chain system
+    ///
+    /// `auth_signatures` are the sweep signers' ed25519 signatures over the
+    /// digest returned by `get_sweep_message(destination)`.
+    ///
+    /// In escrow mode nothing is transferred yet: the account moves to
+    /// `PendingRelease` until `finalize_sweep` or `dispute` settles it.
     ///
     /// # Errors
-    /// Returns Error::Unauthorized if authorization fails
+    /// Returns Error::Unauthorized if no sweep signer is configured
     /// Returns Error::AlreadySwept if sweep already executed
-    pub fn sweep(env: Env, destination: Address, auth_signature: BytesN<64>) -> Result<(), Error> {
-        // Check initialized
-        if !storage::is_initialized(&env) {
+    /// Returns Error::DestinationMismatch if the account is locked to
+    /// another destination
+    /// Returns Error::DestinationCannotReceive if `destination` cannot
+    /// hold one of the assets, see `get_unreceivable_assets`
+    /// Returns Error::BatchWindowOpen while the batching window holds
+    /// sweeps back
+    /// Returns Error::BelowMinSweepValue if the payments are worth less
+    /// than `min_sweep_value`
+    /// An invalid signature aborts the invocation
+    pub fn sweep(
+        env: Env,
+        destination: Address,
+        auth_signatures: Vec<BytesN<64>>,
+    ) -> Result<(), Error> {
+        let result = Self::apply_sweep(&env, &destination, &auth_signatures, false);
+        Self::report_failure(&env, "sweep", &result, || {
+            vec![&env, destination.into_val(&env)]
+        });
+        result
+    }
+
+    /// Set the ed25519 public key whose signature authorizes `sweep`
+    ///
+    /// Must be authorized by the creator.  Replaces any signer, signer set
+    /// or passkey supplied earlier with this single key (1-of-1); can be
+    /// changed until the account is swept or expired.
+    ///
+    /// # Errors
+    /// Returns Error::InvalidStatus if the account was already swept or expired
+    pub fn set_sweep_signer(env: Env, signer: BytesN<32>) -> Result<(), Error> {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
             return Err(Error::NotInitialized);
         }
+        Self::migrate_if_needed(&env, &state)?;
+        Self::check_not_halted(&env, &state)?;
 
-        // Check not already swept
-        if storage::get_status(&env) == AccountStatus::Swept {
-            return Err(Error::AlreadySwept);
-        }
+        state.get_creator().require_auth();
 
-        // Check payment received
-        if !storage::has_payment_received(&env) {
-            return Err(Error::NoPaymentReceived);
+        let status = state.get_status();
+        if status.is_closed() {
+            return Err(Error::InvalidStatus);
         }
 
-        // Check not expired
-        if Self::is_expired(env.clone()) {
-            return Err(Error::AccountExpired);
-        }
+        state.set_sweep_signers(&vec![&env, signer.clone()], 1);
+        state.set_signer_kind(SignerKind::Ed25519);
+        events::emit_sweep_signer_set(&env, signer);
 
-        // Verify authorization signature
-        // Note: In production, implement proper signature verification
-        // For MVP, we trust the SDK to only call with valid signatures
-        Self::verify_sweep_authorization(&env, &destination, &auth_signature)?;
+        Ok(())
+    }
 
-        // Get all payments
-        let payments = storage::get_all_payments(&env);
-        let mut payments_vec = Vec::new(&env);
-        for payment in payments.values() {
-            payments_vec.push_back(payment);
+    /// Public key authorized to sign sweeps alone, if the signer set is 1-of-1
+    pub fn get_sweep_signer(env: Env) -> Option<BytesN<32>> {
+        let state = AccountState::new(&env);
+        let signers = state.get_sweep_signers();
+        if signers.len() == 1 && state.get_sweep_threshold() == 1 {
+            return signers.first();
         }
+        None
+    }
 
-        // Update status before transfer to prevent reentrancy
-        storage::set_status(&env, AccountStatus::Swept);
-        storage::set_swept_to(&env, &destination);
-
-        // Note: Actual token transfers happen in the SDK via Stellar SDK.
-        // This contract enforces authorization/state transitions and reserve lifecycle.
-        let sweep_id = env.ledger().sequence() as u64;
-        storage::set_last_sweep_id(&env, sweep_id);
-
-        // Emit sweep event once transfer authorization/state update succeeds.
-        events::emit_sweep_executed_multi(&env, destination.clone(), &payments_vec);
+    /// Nonce bound into the next sweep message
+    pub fn get_sweep_nonce(env: Env) -> u64 {
+        AccountState::new(&env).get_sweep_nonce()
+    }
 
-        // Reclaim base reserve only after successful sweep state transition.
-        Self::reclaim_reserve_to(&env, &destination, sweep_id)?;
+    /// Digest the sweep signer must sign to authorize a sweep to `destination`
+    ///
+    /// # Errors
+    /// Returns Error::NotInitialized if the account is not initialized
+    pub fn get_sweep_message(env: Env, destination: Address) -> Result<BytesN<32>, Error> {
+        let state = AccountState::new(&env);
+        Self::check_storage_version(&env, &state)?;
 
-        Ok(())
+        Ok(Self::sweep_message(&env, &state, &destination))
     }
 
     /// Check if account has expired
     pub fn is_expired(env: Env) -> bool {
-        if !storage::is_initialized(&env) {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
             return false;
         }
 
-        let expiry_ledger = storage::get_expiry_ledger(&env);
+        let expiry_ledger = state.get_expiry_ledger();
         let current_ledger = env.ledger().sequence();
 
         current_ledger >= expiry_ledger
@@ -198,236 +383,4531 @@ impl EphemeralAccountContract {
 
     /// Get current account status
     pub fn get_status(env: Env) -> AccountStatus {
-        if !storage::is_initialized(&env) {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
             return AccountStatus::Active;
         }
 
-        storage::get_status(&env)
+        state.get_status()
     }
 
     /// Expire the account and return funds to recovery address
     /// Can only be called after expiry ledger is reached
     ///
+    /// Permissionless unless the account was initialized with
+    /// `AuthPolicy::Restricted`; restricted accounts use `expire_as`.
+    ///
     /// # Errors
     /// Returns Error::NotExpired if called before expiry ledger
+    /// Returns Error::Unauthorized if the account is restricted
     pub fn expire(env: Env) -> Result<(), Error> {
-        // Check initialized
-        if !storage::is_initialized(&env) {
-            return Err(Error::NotInitialized);
-        }
-
-        // Check not already swept or expired
-        let status = storage::get_status(&env);
-        if status == AccountStatus::Swept || status == AccountStatus::Expired {
-            return Err(Error::InvalidStatus);
-        }
-
-        // Check if expired
-        if !Self::is_expired(env.clone()) {
-            return Err(Error::NotExpired);
-        }
-
-        // Get recovery address
-        let recovery_address = storage::get_recovery_address(&env);
-
-        // Update status
-        storage::set_status(&env, AccountStatus::Expired);
-        storage::set_swept_to(&env, &recovery_address);
-
-        // Get total amount from all payments if any payments were received
-        let total_amount = if storage::has_payment_received(&env) {
-            let payments = storage::get_all_payments(&env);
-            let mut total = 0i128;
-            for (_, payment) in payments.iter() {
-                total = total
-                    .checked_add(payment.amount)
-                    .ok_or(Error::InvalidAmount)?;
-            }
-            total
-        } else {
-            0
-        };
-
-        let sweep_id = env.ledger().sequence() as u64;
-        storage::set_last_sweep_id(&env, sweep_id);
-
-        // Reclaim reserve to recovery destination.
-        let reclaimed_reserve = Self::reclaim_reserve_to(&env, &recovery_address, sweep_id)?;
-
-        // Emit expiration event with reserve amount reclaimed in this call.
-        events::emit_account_expired(&env, recovery_address, total_amount, reclaimed_reserve);
-
-        Ok(())
+        let result = Self::apply_expire(&env, None);
+        Self::report_failure(&env, "expire", &result, || Vec::new(&env));
+        result
     }
 
     /// Reclaim remaining base reserve for a previously swept/expired account.
     /// This is safe to call repeatedly: once fully reclaimed, subsequent calls transfer 0.
+    ///
+    /// Permissionless unless the account was initialized with
+    /// `AuthPolicy::Restricted`; restricted accounts use
+    /// `reclaim_reserve_as`.
     pub fn reclaim_reserve(env: Env) -> Result<i128, Error> {
-        if !storage::is_initialized(&env) {
-            return Err(Error::NotInitialized);
-        }
-
-        let status = storage::get_status(&env);
-        if status != AccountStatus::Swept && status != AccountStatus::Expired {
-            return Err(Error::InvalidStatus);
-        }
-
-        let destination = storage::get_swept_to(&env).ok_or(Error::InvalidStatus)?;
-        let sweep_id = storage::get_last_sweep_id(&env);
-
-        Self::reclaim_reserve_to(&env, &destination, sweep_id)
+        let result = Self::apply_reclaim_reserve(&env, None);
+        Self::report_failure(&env, "reclaim_reserve", &result, || Vec::new(&env));
+        result
     }
 
     /// Remaining reserve amount (stroops) still eligible for reclaim.
     pub fn get_reserve_remaining(env: Env) -> i128 {
-        if !storage::is_initialized(&env) {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
             return 0;
         }
 
-        storage::get_base_reserve_remaining(&env)
+        state.get_base_reserve_remaining()
     }
 
     /// Tracked reserve currently available for transfer (stroops).
     pub fn get_reserve_available(env: Env) -> i128 {
-        if !storage::is_initialized(&env) {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
             return 0;
         }
 
-        storage::get_available_reserve(&env)
+        state.get_available_reserve()
     }
 
     /// Whether reserve has been fully reclaimed.
     pub fn is_reserve_reclaimed(env: Env) -> bool {
-        if !storage::is_initialized(&env) {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
             return false;
         }
 
-        storage::is_reserve_reclaimed(&env)
+        state.is_reserve_reclaimed()
     }
 
     /// Last reserve reclaim event payload emitted by this contract.
     pub fn get_last_reserve_event(env: Env) -> Option<ReserveReclaimed> {
-        if !storage::is_initialized(&env) {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
             return None;
         }
 
-        storage::get_last_reserve_event(&env)
+        let count = state.get_reserve_summary().event_count;
+        state.get_reserve_event(count.checked_sub(1)?)
     }
 
     /// Number of reserve reclaim events emitted by this contract.
     pub fn get_reserve_reclaim_event_count(env: Env) -> u32 {
-        if !storage::is_initialized(&env) {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
             return 0;
         }
 
-        storage::get_reserve_event_count(&env)
+        state.get_reserve_summary().event_count
     }
 
     /// Get account information
     pub fn get_info(env: Env) -> Result<AccountInfo, Error> {
-        if !storage::is_initialized(&env) {
-            return Err(Error::NotInitialized);
-        }
+        let state = AccountState::new(&env);
+        Self::check_storage_version(&env, &state)?;
 
-        let payments = storage::get_all_payments(&env);
+        let payments = state.get_payments();
         let payment_count = payments.len();
 
         Ok(AccountInfo {
-            creator: storage::get_creator(&env),
-            status: storage::get_status(&env),
-            expiry_ledger: storage::get_expiry_ledger(&env),
-            recovery_address: storage::get_recovery_address(&env),
+            creator: state.get_creator(),
+            status: state.get_status(),
+            expiry_ledger: state.get_expiry_ledger(),
+            recovery_address: state.get_recovery_address(),
             payment_received: payment_count > 0,
             payment_count,
-            payments: {
-                let mut payments_vec = Vec::new(&env);
-                for payment in payments.values() {
-                    payments_vec.push_back(payment);
-                }
-                payments_vec
-            },
-            swept_to: storage::get_swept_to(&env),
+            payments,
+            swept_to: state.get_swept_to(),
+            wasm_version: state.get_wasm_version(),
+            config_version: state.get_config_version(),
         })
     }
 
     // Private helper functions
 
-    fn verify_sweep_authorization(
-        _env: &Env,
-        _destination: &Address,
-        _signature: &BytesN<64>,
+    fn apply_initialize(
+        env: &Env,
+        creator: &Address,
+        expiry_ledger: u32,
+        recovery_address: &Address,
+        config: &AccountConfig,
     ) -> Result<(), Error> {
-        // TODO: Implement proper signature verification
-        // For MVP, we rely on off-chain SDK to only call with valid auth
-        // Future: Verify signature against authorized signer
-        Ok(())
-    }
+        let state = AccountState::new(env);
+        // Check if already initialized
+        if state.is_initialized() {
+            return Err(Error::AlreadyInitialized);
+        }
 
-    fn reclaim_reserve_to(env: &Env, destination: &Address, sweep_id: u64) -> Result<i128, Error> {
-        let reserve_remaining = storage::get_base_reserve_remaining(env);
-        let reserve_available = storage::get_available_reserve(env);
+        // Verify creator authorization
+        creator.require_auth();
 
-        if reserve_remaining < 0 || reserve_available < 0 {
-            return Err(Error::InvalidAmount);
+        // Validate expiry is in future
+        let current_ledger = env.ledger().sequence();
+        if expiry_ledger <= current_ledger {
+            return Err(Error::InvalidExpiry);
         }
 
-        if reserve_remaining == 0 {
-            storage::set_reserve_reclaimed(env, true);
-            let event = ReserveReclaimed {
-                destination: destination.clone(),
-                amount: 0,
-                sweep_id,
-                fully_reclaimed: true,
-                remaining_reserve: 0,
-            };
-            Self::emit_and_store_reserve_event(env, event)?;
-            return Ok(0);
+        // Store initialization data
+        state.set_initialized(true);
+        state.next_state_seq();
+        state.set_creator(creator);
+        state.set_expiry_ledger(expiry_ledger);
+        state.set_recovery_address(recovery_address);
+        state.set_versions(WASM_VERSION, CONFIG_SCHEMA_VERSION);
+        state.set_storage_version(STORAGE_VERSION);
+        if let Some(kill_switch) = &config.kill_switch {
+            state.set_kill_switch(kill_switch);
+            Self::check_not_halted(env, &state)?;
         }
+        if let Some(prefix) = &config.event_prefix {
+            state.set_event_prefix(prefix);
+        }
+        let base_reserve = Self::apply_config(env, &state, config)?;
+        match &config.factory {
+            #[cfg(feature = "reuse")]
+            Some(factory) => state.set_factory(factory),
+            #[cfg(not(feature = "reuse"))]
+            Some(_) => return Err(Error::FeatureDisabled),
+            None => {}
+        }
+        state.set_native_asset(&AssetKind::native_address(env));
+        state.set_status(AccountStatus::Active);
+        state.init_reserve_tracking(base_reserve);
 
-        let reclaim_amount = if reserve_available < reserve_remaining {
-            reserve_available
-        } else {
-            reserve_remaining
-        };
+        // Stay live through the grace period even if nothing is written
+        // until then
+        state.extend_ttl_past(Self::grace_end_ledger(&state));
 
-        let new_available = reserve_available
-            .checked_sub(reclaim_amount)
-            .ok_or(Error::InvalidAmount)?;
-        let new_remaining = reserve_remaining
-            .checked_sub(reclaim_amount)
-            .ok_or(Error::InvalidAmount)?;
+        // Emit event
+        events::emit_account_created(env, creator.clone(), expiry_ledger);
 
-        storage::set_available_reserve(env, new_available);
-        storage::set_base_reserve_remaining(env, new_remaining);
-        storage::set_reserve_reclaimed(env, new_remaining == 0);
+        Ok(())
+    }
 
-        let event = ReserveReclaimed {
-            destination: destination.clone(),
-            amount: reclaim_amount,
-            sweep_id,
-            fully_reclaimed: new_remaining == 0,
-            remaining_reserve: new_remaining,
-        };
-        Self::emit_and_store_reserve_event(env, event)?;
+    /// Store the optional settings of `config` and return the account's
+    /// base reserve.
+    #[cfg(feature = "config")]
+    fn apply_config(
+        env: &Env,
+        state: &AccountState,
+        config: &AccountConfig,
+    ) -> Result<i128, Error> {
+        Self::check_config_features(config)?;
+        #[cfg(feature = "diagnostics")]
+        state.set_debug_events(config.debug_events);
+        state.set_accumulate_payments(config.accumulate_payments);
+        #[cfg(feature = "quarantine")]
+        if config.quarantine_failed_transfers {
+            state.set_quarantine_mode(true);
+        }
+        match config.signer_kind {
+            #[cfg(feature = "passkey")]
+            SignerKind::Secp256r1 => {
+                let key = config
+                    .passkey_signer
+                    .as_ref()
+                    .ok_or(Error::InvalidSignerSet)?;
+                state.set_passkey_signer(key);
+            }
+            #[cfg(not(feature = "passkey"))]
+            SignerKind::Secp256r1 => {}
+            SignerKind::Ed25519 => {
+                if let Some(signers) = &config.sweep_signers {
+                    Self::check_signer_set(signers, config.sweep_threshold)?;
+                    state.set_sweep_signers(signers, config.sweep_threshold);
+                } else if let Some(signer) = &config.sweep_signer {
+                    state.set_sweep_signers(&vec![env, signer.clone()], 1);
+                }
+            }
+        }
+        state.set_signer_kind(config.signer_kind);
+        #[cfg(feature = "escrow")]
+        if let Some(arbiter) = &config.escrow_arbiter {
+            state.set_escrow(arbiter, config.hold_ledgers);
+        }
+        #[cfg(feature = "escrow")]
+        if let Some(holds) = &config.clawback_hold_ledgers {
+            state.set_clawback_holds(holds);
+        }
+        if config.grace_ledgers > 0 {
+            state.set_grace_ledgers(config.grace_ledgers);
+        }
+        #[cfg(feature = "fees")]
+        if let Some(collector) = &config.fee_collector {
+            if !units::is_valid_bps(config.fee_bps) {
+                return Err(Error::InvalidFee);
+            }
+            state.set_fee(collector, config.fee_bps);
+        }
+        if let Some(destination) = &config.locked_destination {
+            state.set_locked_destination(destination);
+        }
+        #[cfg(feature = "guardian")]
+        if let Some(guardian) = &config.guardian {
+            // Emergency recovery has to open before the account expires
+            let emergency_ledger = match config.emergency_delay_ledgers {
+                0 => None,
+                delay => Some(env.ledger().sequence().saturating_add(delay)),
+            };
+            if emergency_ledger.is_some_and(|ledger| ledger >= state.get_expiry_ledger()) {
+                return Err(Error::InvalidExpiry);
+            }
+            state.set_guardian(&GuardianConfig {
+                guardian: guardian.clone(),
+                destination_change_delay: config.destination_change_delay,
+                emergency_ledger,
+            });
+        }
+        if config.batch_min_amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if config.batch_min_amount > 0 {
+            state.set_batch_window(config.batch_min_amount, config.batch_max_wait_ledgers);
+        }
+        if config.min_sweep_value < 0 || config.dust_threshold < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        #[cfg(feature = "oracle")]
+        if let Some(oracle) = &config.price_oracle {
+            if config.min_sweep_value > 0 {
+                state.set_min_sweep_value(oracle, config.min_sweep_value);
+            }
+            #[cfg(feature = "dust")]
+            if config.dust_threshold > 0 {
+                state.set_dust_threshold(oracle, config.dust_threshold);
+            }
+            #[cfg(feature = "depeg")]
+            if let Some(pegs) = &config.depeg_pegs {
+                if !units::is_valid_bps(config.depeg_threshold_bps)
+                    || pegs.values().iter().any(|peg| peg <= 0)
+                {
+                    return Err(Error::InvalidAmount);
+                }
+                state.set_depeg_guard(oracle, pegs, config.depeg_threshold_bps);
+            }
+        }
+        if config.external_chain_ref != ChainLink::Unlinked {
+            state.set_external_chain_ref(&config.external_chain_ref);
+        }
+        if let Some(reserve_contract) = &config.reserve_contract {
+            state.set_reserve_contract(reserve_contract);
+        }
+        if config.trustlines > 0 {
+            state.set_trustline_count(config.trustlines);
+        }
+        if let AccountLabel::Labeled(metadata) = &config.metadata {
+            state.set_metadata(metadata);
+        }
+        #[cfg(feature = "keeper")]
+        if config.auth_policy == AuthPolicy::Restricted {
+            state.set_access_policy(config.auth_policy, &config.keeper);
+        }
+        #[cfg(feature = "hooks")]
+        if let Some(hook) = &config.settlement_hook {
+            state.set_settlement_hook(hook);
+        }
+        #[cfg(feature = "compliance")]
+        if let Some(gate) = &config.compliance_gate {
+            state.set_compliance_gate(gate);
+        }
+        // One read, so the reserve and bounty come from the same epoch
+        let snapshot = config
+            .reserve_contract
+            .as_ref()
+            .map(|reserve_contract| ReserveClient::new(env, reserve_contract).get_snapshot());
+        #[cfg(feature = "keeper")]
+        {
+            let keeper_bounty_bps = Self::resolve_keeper_bounty_bps(config, snapshot.as_ref())?;
+            if keeper_bounty_bps > 0 {
+                state.set_keeper_bounty_bps(keeper_bounty_bps);
+            }
+            if let Some(max_bps) = config.keeper_bounty_max_bps {
+                if !units::is_valid_bps(max_bps) || max_bps < keeper_bounty_bps {
+                    return Err(Error::InvalidFee);
+                }
+                state.set_keeper_bounty_ramp(max_bps, config.keeper_bounty_ramp_ledgers);
+            }
+        }
+        if let Some(snapshot) = &snapshot {
+            state.set_reserve_config_epoch(snapshot.epoch);
+        }
+        Self::resolve_base_reserve(config, snapshot.as_ref())
+    }
 
-        Ok(reclaim_amount)
+    /// Built without `config`, every account opens with the default
+    /// settings and base reserve.
+    #[cfg(not(feature = "config"))]
+    fn apply_config(
+        _env: &Env,
+        _state: &AccountState,
+        _config: &AccountConfig,
+    ) -> Result<i128, Error> {
+        Ok(BASE_RESERVE_STROOPS)
     }
 
-    fn emit_and_store_reserve_event(env: &Env, event: ReserveReclaimed) -> Result<(), Error> {
-        events::emit_reserve_reclaimed(
-            env,
-            event.destination.clone(),
-            event.amount,
-            event.sweep_id,
-            event.fully_reclaimed,
-            event.remaining_reserve,
-        );
+    /// Fail if `config` asks for a subsystem this build was compiled
+    /// without, instead of opening an account that silently ignores it.
+    #[cfg(feature = "config")]
+    fn check_config_features(config: &AccountConfig) -> Result<(), Error> {
+        let disabled = (!cfg!(feature = "fees") && config.fee_collector.is_some())
+            || (!cfg!(feature = "passkey") && config.signer_kind == SignerKind::Secp256r1)
+            || (!cfg!(feature = "hooks") && config.settlement_hook.is_some())
+            || (!cfg!(feature = "compliance") && config.compliance_gate.is_some())
+            || (!cfg!(feature = "oracle") && config.price_oracle.is_some())
+            || (!cfg!(feature = "escrow")
+                && (config.escrow_arbiter.is_some() || config.clawback_hold_ledgers.is_some()))
+            || (!cfg!(feature = "guardian") && config.guardian.is_some())
+            || (!cfg!(feature = "quarantine") && config.quarantine_failed_transfers)
+            || (!cfg!(feature = "depeg") && config.depeg_pegs.is_some())
+            || (!cfg!(feature = "dust") && config.dust_threshold > 0)
+            || (!cfg!(feature = "keeper")
+                && (config.auth_policy == AuthPolicy::Restricted
+                    || config.keeper.is_some()
+                    || config.keeper_bounty_bps.is_some()
+                    || config.keeper_bounty_max_bps.is_some()))
+            || (!cfg!(feature = "diagnostics") && config.debug_events);
+        if disabled {
+            return Err(Error::FeatureDisabled);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "reuse")]
+    fn apply_reset(
+        env: &Env,
+        expiry_ledger: u32,
+        recovery_address: &Address,
+        factory: &Address,
+    ) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_not_halted(env, &state)?;
 
-        let event_count = storage::get_reserve_event_count(env);
-        let next_count = event_count.checked_add(1).ok_or(Error::InvalidAmount)?;
-        storage::set_last_reserve_event(env, &event);
-        storage::set_reserve_event_count(env, next_count);
+        if state.get_factory().as_ref() != Some(factory) {
+            return Err(Error::Unauthorized);
+        }
+        factory.require_auth();
 
-        Ok(())
+        if !state.get_status().is_closed() {
+            return Err(Error::InvalidStatus);
+        }
+        if state.get_base_reserve_remaining() != 0 || state.get_available_reserve() != 0 {
+            return Err(Error::FundsRemaining);
+        }
+        let this = env.current_contract_address();
+        for asset in state.get_payment_assets().iter() {
+            if token::TokenClient::new(env, &asset).balance(&this) != 0 {
+                return Err(Error::FundsRemaining);
+            }
+        }
+        if expiry_ledger <= env.ledger().sequence() {
+            return Err(Error::InvalidExpiry);
+        }
+
+        state.clear_cycle();
+        #[cfg(feature = "guardian")]
+        if let Some(guardian) = state.get_guardian_config() {
+            state.set_guardian(&GuardianConfig {
+                emergency_ledger: None,
+                ..guardian
+            });
+        }
+        state.set_expiry_ledger(expiry_ledger);
+        state.set_recovery_address(recovery_address);
+        state.set_status(AccountStatus::Active);
+        let epoch = state.next_epoch();
+        state.extend_ttl_past(Self::grace_end_ledger(&state));
+
+        events::emit_account_reset(
+            env,
+            factory.clone(),
+            epoch,
+            expiry_ledger,
+            recovery_address.clone(),
+        );
+
+        Ok(())
+    }
+
+    fn apply_record_payment(
+        env: &Env,
+        amount: i128,
+        asset: Address,
+        payer_info_hash: Option<BytesN<32>>,
+        sender: Option<Address>,
+        memo: Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        // Check initialized
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+        Self::check_not_paused(env, &state)?;
+
+        // Whoever the payment is attributed to vouches for it: its sender,
+        // or the creator when it names none
+        match &sender {
+            Some(sender) => sender.require_auth(),
+            None => state.get_creator().require_auth(),
+        }
+
+        // Validate amount
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        // A closed account, or one whose sweep is held, accepts no payments
+        let status = state.get_status();
+        if status.is_closed() || status == AccountStatus::PendingRelease {
+            return Err(Error::InvalidStatus);
+        }
+
+        // Enforce the creator's limits
+        let limits = state.get_payment_limits(MAX_PAYMENT_ASSETS);
+        Self::check_payment_limits(&limits, &asset, amount)?;
+        let ledger = env.ledger().sequence();
+        Self::check_payment_cooldown(&state, &limits, ledger)?;
+
+        // Native XLM shares its balance with the base reserve
+        if Self::asset_kind(env, &state, &asset) == AssetKind::Native {
+            Self::check_native_balance(env, &state, &asset, amount)?;
+            state.set_native_reserve_held(true);
+        }
+        Self::report_payment(env, &state, &sender, &asset, amount);
+
+        // A repeated asset is either a top-up or a duplicate
+        if let Some(existing) = state.get_payment(&asset) {
+            // Swept amounts are gone; a top-up could never be swept
+            if !state.is_accumulate_payments() || state.get_swept_assets().contains(&asset) {
+                return Err(Error::DuplicateAsset);
+            }
+            state.set_payment_ledger(ledger, false);
+            return Self::top_up_payment(
+                env,
+                &state,
+                existing,
+                amount,
+                payer_info_hash,
+                sender,
+                memo,
+            );
+        }
+
+        // Check payment limit to prevent gas issues
+        let payment_count = state.get_total_payments();
+        if payment_count >= limits.max_payments {
+            return Err(Error::TooManyPayments);
+        }
+
+        // Create payment with current timestamp
+        let payment = Payment {
+            asset: asset.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+            payer_info_hash,
+            sender,
+            decimals: Self::asset_decimals(env, &asset),
+            memo,
+        };
+
+        // Add payment and extend the payment commitment
+        Self::commit_payment(env, &state, &payment);
+        state.add_payment(payment.clone());
+        Self::track_trustlines(env, &state);
+
+        // Update status only on first payment
+        state.set_payment_ledger(ledger, payment_count == 0);
+        if payment_count == 0 {
+            state.set_status(AccountStatus::PaymentReceived);
+        }
+
+        // Emit appropriate event
+        if payment_count == 0 {
+            events::emit_payment_received(env, payment);
+        } else {
+            events::emit_multi_payment_received(env, vec![env, payment]);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "batch")]
+    fn apply_record_payments(env: &Env, payments: Vec<(Address, i128)>) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+        Self::check_not_paused(env, &state)?;
+        state.get_creator().require_auth();
+
+        if payments.is_empty() {
+            return Err(Error::InvalidAmount);
+        }
+        let status = state.get_status();
+        if status.is_closed() || status == AccountStatus::PendingRelease {
+            return Err(Error::InvalidStatus);
+        }
+
+        // Validate the whole batch before writing any of it
+        let limits = state.get_payment_limits(MAX_PAYMENT_ASSETS);
+        let ledger = env.ledger().sequence();
+        Self::check_payment_cooldown(&state, &limits, ledger)?;
+        let mut assets: Vec<Address> = Vec::new(env);
+        let mut native_held = false;
+        for (asset, amount) in payments.iter() {
+            if amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            Self::check_payment_limits(&limits, &asset, amount)?;
+            // A batch only records new assets; top-ups go one at a time
+            if assets.contains(&asset) || state.get_payment(&asset).is_some() {
+                return Err(Error::DuplicateAsset);
+            }
+            if Self::asset_kind(env, &state, &asset) == AssetKind::Native {
+                Self::check_native_balance(env, &state, &asset, amount)?;
+                native_held = true;
+            }
+            assets.push_back(asset);
+        }
+        let payment_count = state.get_total_payments();
+        if payment_count.saturating_add(payments.len()) > limits.max_payments {
+            return Err(Error::TooManyPayments);
+        }
+
+        let mut recorded = Vec::new(env);
+        for (asset, amount) in payments.iter() {
+            let payment = Payment {
+                decimals: Self::asset_decimals(env, &asset),
+                asset,
+                amount,
+                timestamp: env.ledger().timestamp(),
+                payer_info_hash: None,
+                sender: None,
+                memo: None,
+            };
+            Self::report_payment(env, &state, &None, &payment.asset, amount);
+            Self::commit_payment(env, &state, &payment);
+            state.add_payment(payment.clone());
+            recorded.push_back(payment);
+        }
+        if native_held {
+            state.set_native_reserve_held(true);
+        }
+        Self::track_trustlines(env, &state);
+
+        state.set_payment_ledger(ledger, payment_count == 0);
+        if payment_count == 0 {
+            state.set_status(AccountStatus::PaymentReceived);
+        }
+        events::emit_multi_payment_received(env, recorded);
+
+        Ok(())
+    }
+
+    /// Fail unless `amount` of `asset` is within the creator's allowlist
+    /// and per-asset minimum.
+    fn check_payment_limits(
+        limits: &PaymentLimits,
+        asset: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        if let Some(allowed_assets) = &limits.allowed_assets {
+            if !allowed_assets.contains(asset) {
+                return Err(Error::AssetNotAllowed);
+            }
+        }
+        if amount < limits.min_amounts.get(asset.clone()).unwrap_or(0) {
+            return Err(Error::BelowMinPayment);
+        }
+        Ok(())
+    }
+
+    /// Fail while the previous payment is less than `cooldown_ledgers` old.
+    fn check_payment_cooldown(
+        state: &AccountState,
+        limits: &PaymentLimits,
+        ledger: u32,
+    ) -> Result<(), Error> {
+        if let Some(last_ledger) = state.get_last_payment_ledger() {
+            if ledger < last_ledger.saturating_add(limits.cooldown_ledgers) {
+                return Err(Error::PaymentCooldown);
+            }
+        }
+        Ok(())
+    }
+
+    /// Native Stellar Asset Contract address; accounts initialized before
+    /// it was pinned derive it from the network.
+    fn native_asset(env: &Env, state: &AccountState) -> Address {
+        state
+            .get_native_asset()
+            .unwrap_or_else(|| AssetKind::native_address(env))
+    }
+
+    fn asset_kind(env: &Env, state: &AccountState, asset: &Address) -> AssetKind {
+        if *asset == Self::native_asset(env, state) {
+            AssetKind::Native
+        } else {
+            AssetKind::Contract(asset.clone())
+        }
+    }
+
+    /// Fail unless the XLM balance covers the tracked reserve, the XLM
+    /// already recorded and `amount` on top.
+    fn check_native_balance(
+        env: &Env,
+        state: &AccountState,
+        native_asset: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let recorded = state
+            .get_payment(native_asset)
+            .filter(|_| !state.get_swept_assets().contains(native_asset))
+            .map_or(0, |payment| payment.amount);
+        let required = state
+            .get_available_reserve()
+            .checked_add(recorded)
+            .and_then(|total| total.checked_add(amount))
+            .ok_or(Error::InvalidAmount)?;
+        let balance =
+            token::TokenClient::new(env, native_asset).balance(&env.current_contract_address());
+        if balance < required {
+            return Err(Error::InsufficientNativeBalance);
+        }
+        Ok(())
+    }
+
+    /// Decimals of `asset`, or `None` if it is not a token contract that
+    /// reports them.
+    fn asset_decimals(env: &Env, asset: &Address) -> Option<u32> {
+        match token::TokenClient::new(env, asset).try_decimals() {
+            Ok(Ok(decimals)) => Some(decimals),
+            _ => None,
+        }
+    }
+
+    /// Add `amount` to an asset's recorded payment (accumulation mode).
+    ///
+    /// The asset already counts towards `MAX_PAYMENT_ASSETS`, so a top-up
+    /// never hits the cap.
+    fn top_up_payment(
+        env: &Env,
+        state: &AccountState,
+        existing: Payment,
+        amount: i128,
+        payer_info_hash: Option<BytesN<32>>,
+        sender: Option<Address>,
+        memo: Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        let total_amount = existing
+            .amount
+            .checked_add(amount)
+            .ok_or(Error::InvalidAmount)?;
+        // Once a second sender pays in, keep what each one paid so a refund
+        // returns every sender their own share.  Amounts paid without a
+        // sender are left out, which keeps such a payment unrefundable.
+        let mut contributions = state.get_contributions(&existing.asset);
+        if contributions.is_none() && sender != existing.sender {
+            let mut shares = Map::new(env);
+            if let Some(first) = &existing.sender {
+                shares.set(first.clone(), existing.amount);
+            }
+            contributions = Some(shares);
+        }
+        if let Some(mut shares) = contributions {
+            if let Some(sender) = &sender {
+                let paid = shares.get(sender.clone()).unwrap_or(0);
+                shares.set(sender.clone(), paid + amount);
+            }
+            state.set_contributions(&existing.asset, &shares);
+        }
+
+        let payment = Payment {
+            asset: existing.asset.clone(),
+            amount: total_amount,
+            timestamp: env.ledger().timestamp(),
+            payer_info_hash: payer_info_hash.clone().or(existing.payer_info_hash),
+            sender: existing.sender.or(sender.clone()),
+            decimals: existing.decimals,
+            memo: existing.memo.or(memo.clone()),
+        };
+
+        Self::commit_payment(env, state, &payment);
+        state.add_payment(payment);
+
+        events::emit_payment_topped_up(
+            env,
+            existing.asset,
+            amount,
+            total_amount,
+            payer_info_hash,
+            sender,
+            memo,
+        );
+
+        Ok(())
+    }
+
+    fn apply_sweep(
+        env: &Env,
+        destination: &Address,
+        auth_signatures: &Vec<BytesN<64>>,
+        force: bool,
+    ) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_sweep_to(env, &state, destination, force)?;
+
+        // Verify authorization signature, then consume the nonce
+        let message = Self::sweep_message(env, &state, destination);
+        Self::verify_sweep_authorization(env, &state, message, auth_signatures)?;
+        state.increment_sweep_nonce();
+
+        Self::settle_sweep(env, &state, destination)
+    }
+
+    #[cfg(feature = "auth")]
+    fn apply_sweep_authorized(env: &Env, destination: &Address) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_sweep_to(env, &state, destination, false)?;
+
+        // The host runs `__check_auth`, which enforces the signer policy
+        env.current_contract_address().require_auth();
+        state.increment_sweep_nonce();
+
+        Self::settle_sweep(env, &state, destination)
+    }
+
+    /// Hold an authorized sweep in escrow mode or while it moves assets
+    /// that can be clawed back, or complete it right away.
+    #[cfg(feature = "escrow")]
+    fn settle_sweep(env: &Env, state: &AccountState, destination: &Address) -> Result<(), Error> {
+        let escrow = state.get_escrow_arbiter().is_some();
+        let (clawback_hold, clawback_assets) = Self::clawback_held_assets(env, state);
+
+        // Held funds stay put until the hold is settled
+        if escrow || !clawback_assets.is_empty() {
+            let hold = if escrow {
+                state.get_hold_ledgers().max(clawback_hold)
+            } else {
+                clawback_hold
+            };
+            let release_after_ledger = env.ledger().sequence().saturating_add(hold);
+            state.set_status(AccountStatus::PendingRelease);
+            state.set_pending_release(&PendingRelease {
+                destination: destination.clone(),
+                release_after_ledger,
+            });
+            state.extend_ttl_past(release_after_ledger);
+            if escrow {
+                events::emit_sweep_held(env, destination.clone(), release_after_ledger);
+            }
+            if !clawback_assets.is_empty() {
+                events::emit_clawback_held(
+                    env,
+                    destination.clone(),
+                    clawback_assets,
+                    release_after_ledger,
+                );
+            }
+            return Ok(());
+        }
+
+        Self::complete_sweep(env, state, destination)
+    }
+
+    /// Built without `escrow`, a sweep is never held.
+    #[cfg(not(feature = "escrow"))]
+    fn settle_sweep(env: &Env, state: &AccountState, destination: &Address) -> Result<(), Error> {
+        Self::complete_sweep(env, state, destination)
+    }
+
+    /// Preconditions a `sweep` to `destination` checks before its
+    /// signatures.  `force` skips the minimum sweep value.
+    fn check_sweep_to(
+        env: &Env,
+        state: &AccountState,
+        destination: &Address,
+        force: bool,
+    ) -> Result<(), Error> {
+        Self::check_sweepable(env, state)?;
+        Self::check_not_halted(env, state)?;
+        Self::check_not_paused(env, state)?;
+        Self::check_compliance(env, state)?;
+        Self::check_destination(state, destination)?;
+        let outstanding = Self::outstanding_payments(env, state);
+        let assets = outstanding.keys();
+        // A quarantining account sweeps what it can and keeps the rest
+        if !state.is_quarantine_mode() {
+            for asset in assets.iter() {
+                Self::check_can_receive(env, &asset, destination)?;
+            }
+        }
+        Self::check_pegged(env, state, &assets)?;
+        if !force {
+            Self::check_sweep_value(env, state, &outstanding.values())?;
+        }
+        Ok(())
+    }
+
+    /// Transfer every outstanding payment to `destination`, mark the account
+    /// swept and reclaim the reserve there.
+    fn complete_sweep(env: &Env, state: &AccountState, destination: &Address) -> Result<(), Error> {
+        // Get outstanding payments, highest priority first, less anything
+        // clawed back while they were held, in one pass over the payments
+        #[cfg_attr(not(feature = "escrow"), allow(unused_variables))]
+        let (payments_vec, clawback_assets) =
+            Self::net_of_clawbacks(env, state, Self::payments_in_sweep_order(env, state));
+        #[cfg_attr(not(feature = "dust"), allow(unused_variables))]
+        let (payments_vec, dust) = Self::split_dust(env, state, payments_vec);
+
+        // Update status before transfer to prevent reentrancy
+        state.set_status(AccountStatus::Swept);
+        state.set_swept_to(destination);
+
+        // Move every recorded balance, less the protocol fee, to the
+        // destination. A failing transfer aborts the whole invocation, so
+        // the sweep is all-or-nothing, unless the account quarantines it.
+        let mut settled = Vec::new(env);
+        let mut amounts = Vec::new(env);
+        #[cfg_attr(not(feature = "quarantine"), allow(unused_mut))]
+        let mut quarantined = Vec::new(env);
+        for payment in payments_vec.iter() {
+            match Self::send_payment(env, state, &payment, destination)? {
+                Some(amount) => {
+                    amounts.push_back(amount);
+                    settled.push_back(payment);
+                }
+                None => quarantined.push_back(payment),
+            }
+        }
+        #[cfg(feature = "quarantine")]
+        if !quarantined.is_empty() {
+            let mut assets = Vec::new(env);
+            for payment in quarantined.iter() {
+                assets.push_back(payment.asset);
+            }
+            let mut retries = Map::new(env);
+            for asset in assets.iter() {
+                retries.set(asset, Self::quarantine_retry(env, 0));
+            }
+            state.set_quarantined(&quarantined);
+            state.set_quarantine_retries(&retries);
+            events::emit_assets_quarantined(env, destination.clone(), assets);
+        }
+        #[cfg(feature = "dust")]
+        if !dust.is_empty() {
+            let mut assets = Vec::new(env);
+            for payment in dust.iter() {
+                assets.push_back(payment.asset);
+            }
+            state.set_dust(&dust);
+            events::emit_dust_left(env, destination.clone(), assets);
+        }
+
+        let sweep_id = Self::record_sweep_id(env, state, destination);
+
+        // Emit sweep event once the transfers and state update succeed.
+        events::emit_sweep_executed_multi(
+            env,
+            destination.clone(),
+            &settled,
+            state.get_external_chain_ref(),
+            amounts,
+            sweep_id.clone(),
+        );
+        #[cfg(feature = "escrow")]
+        if !clawback_assets.is_empty() {
+            events::emit_clawback_released(env, destination.clone(), clawback_assets);
+        }
+
+        // Reclaim base reserve only after successful sweep state transition.
+        Self::reclaim_reserve_to(env, destination, sweep_id, None)?;
+        Self::notify_settled(env, state);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "partial")]
+    fn apply_sweep_assets(
+        env: &Env,
+        destination: &Address,
+        assets: &Vec<Address>,
+        auth_signatures: &Vec<BytesN<64>>,
+    ) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_sweepable(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+        Self::check_not_paused(env, &state)?;
+        Self::check_compliance(env, &state)?;
+        Self::check_not_escrow(&state)?;
+        Self::check_destination(&state, destination)?;
+
+        // Every listed asset must have an outstanding payment, listed once
+        let mut swept_assets = state.get_swept_assets();
+        let mut payments = Vec::new(env);
+        for (i, asset) in assets.iter().enumerate() {
+            if assets.iter().skip(i + 1).any(|other| other == asset) {
+                return Err(Error::DuplicateAsset);
+            }
+            if swept_assets.contains(&asset) {
+                return Err(Error::AssetNotFound);
+            }
+            // Only a held `sweep` may move assets that can be clawed back
+            #[cfg(feature = "escrow")]
+            if state.get_clawback_hold(&asset) > 0 {
+                return Err(Error::HoldRequired);
+            }
+            payments.push_back(state.get_payment(&asset).ok_or(Error::AssetNotFound)?);
+        }
+        if payments.is_empty() {
+            return Err(Error::AssetNotFound);
+        }
+        for asset in assets.iter() {
+            Self::check_can_receive(env, &asset, destination)?;
+        }
+        Self::check_pegged(env, &state, assets)?;
+        Self::check_sweep_value(env, &state, &payments)?;
+
+        // Verify authorization signature, then consume the nonce
+        let message = Self::sweep_assets_message(env, &state, destination, assets);
+        Self::verify_sweep_authorization(env, &state, message, auth_signatures)?;
+        state.increment_sweep_nonce();
+
+        // Mark the assets swept before transfer to prevent reentrancy
+        for asset in assets.iter() {
+            swept_assets.push_back(asset);
+        }
+        state.set_swept_assets(&swept_assets);
+        state.set_partial_sweep_to(destination);
+        let remaining_assets = state.get_total_payments() - swept_assets.len();
+
+        let this = env.current_contract_address();
+        let mut amounts = Vec::new(env);
+        for payment in payments.iter() {
+            let fee = Self::collect_fee(env, &state, &payment)?;
+            let net = payment.amount - fee;
+            token::TokenClient::new(env, &payment.asset).transfer(&this, destination, &net);
+            amounts.push_back(SweepAmount {
+                asset: payment.asset.clone(),
+                gross: payment.amount,
+                fee,
+                net,
+            });
+        }
+
+        // Every partial sweep has its own id; the last one closes the account
+        let sweep_id = Self::sweep_id(env, &state, destination);
+        events::emit_partial_sweep_executed(
+            env,
+            destination.clone(),
+            &payments,
+            amounts,
+            remaining_assets,
+            sweep_id.clone(),
+        );
+
+        // The last outstanding payment completes the sweep
+        if remaining_assets == 0 {
+            state.set_status(AccountStatus::Swept);
+            state.set_swept_to(destination);
+
+            state.set_last_sweep_id(&sweep_id);
+            Self::reclaim_reserve_to(env, destination, sweep_id, None)?;
+            Self::notify_settled(env, &state);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "partial")]
+    fn apply_sweep_split(
+        env: &Env,
+        destinations: &Vec<(Address, u32)>,
+        auth_signatures: &Vec<BytesN<64>>,
+    ) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_sweepable(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+        Self::check_not_paused(env, &state)?;
+        Self::check_compliance(env, &state)?;
+        Self::check_not_escrow(&state)?;
+        #[cfg(feature = "escrow")]
+        if !Self::clawback_held_assets(env, &state).1.is_empty() {
+            return Err(Error::HoldRequired);
+        }
+
+        if destinations.is_empty() || destinations.len() > MAX_SPLIT_DESTINATIONS {
+            return Err(Error::InvalidShares);
+        }
+        let mut total_bps = 0u32;
+        for (destination, share_bps) in destinations.iter() {
+            if share_bps == 0 {
+                return Err(Error::InvalidShares);
+            }
+            Self::check_destination(&state, &destination)?;
+            total_bps = total_bps.saturating_add(share_bps);
+        }
+        if total_bps != BPS_DENOMINATOR {
+            return Err(Error::InvalidShares);
+        }
+        let outstanding = Self::outstanding_payments(env, &state);
+        let assets = outstanding.keys();
+        for asset in assets.iter() {
+            for (destination, _) in destinations.iter() {
+                Self::check_can_receive(env, &asset, &destination)?;
+            }
+        }
+        Self::check_pegged(env, &state, &assets)?;
+        Self::check_sweep_value(env, &state, &outstanding.values())?;
+
+        // Verify authorization signature, then consume the nonce
+        let message = Self::sweep_split_message(env, &state, destinations);
+        Self::verify_sweep_authorization(env, &state, message, auth_signatures)?;
+        state.increment_sweep_nonce();
+
+        // Work out every destination's amounts before moving anything
+        let payments_vec = Self::payments_in_sweep_order(env, &state);
+        let mut shares: Vec<Vec<Payment>> = Vec::new(env);
+        for _ in destinations.iter() {
+            shares.push_back(Vec::new(env));
+        }
+        for payment in payments_vec.iter() {
+            // The protocol fee comes off the top; every other destination
+            // is rounded down and the first takes the rest, rounding dust
+            // included.
+            let net = payment.amount - Self::fee_for(&state, payment.amount)?;
+            let mut first_amount = net;
+            for i in 1..destinations.len() {
+                let (_, share_bps) = destinations.get_unchecked(i);
+                let amount = units::bps_of(net, share_bps).ok_or(Error::InvalidAmount)?;
+                first_amount -= amount;
+                Self::push_share(&mut shares, i, &payment, amount);
+            }
+            Self::push_share(&mut shares, 0, &payment, first_amount);
+        }
+
+        let (primary, _) = destinations.get_unchecked(0);
+
+        // Update status before transfer to prevent reentrancy
+        state.set_status(AccountStatus::Swept);
+        state.set_swept_to(&primary);
+
+        let sweep_id = Self::record_sweep_id(env, &state, &primary);
+        let this = env.current_contract_address();
+        for payment in payments_vec.iter() {
+            Self::collect_fee(env, &state, &payment)?;
+        }
+        for (i, (destination, share_bps)) in destinations.iter().enumerate() {
+            let share = shares.get_unchecked(i as u32);
+            for payment in share.iter() {
+                if payment.amount > 0 {
+                    token::TokenClient::new(env, &payment.asset).transfer(
+                        &this,
+                        &destination,
+                        &payment.amount,
+                    );
+                }
+            }
+            events::emit_split_sweep_executed(env, destination, share_bps, share, sweep_id.clone());
+        }
+
+        Self::reclaim_reserve_to(env, &primary, sweep_id, None)?;
+        Self::notify_settled(env, &state);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "refunds")]
+    fn apply_refund_payment(
+        env: &Env,
+        asset: &Address,
+        auth_signatures: &Vec<BytesN<64>>,
+    ) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_sweepable(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+        Self::check_not_paused(env, &state)?;
+        Self::check_compliance(env, &state)?;
+
+        if state.get_swept_assets().contains(asset) {
+            return Err(Error::AssetNotFound);
+        }
+        let payment = state.get_payment(asset).ok_or(Error::AssetNotFound)?;
+        let sender = payment.sender.clone().ok_or(Error::SenderUnknown)?;
+        let shares = Self::refund_shares(env, &state, &payment, &sender)?;
+
+        // Verify authorization signature, then consume the nonce
+        let message = Self::refund_message(env, &state, &sender, asset);
+        Self::verify_sweep_authorization(env, &state, message, auth_signatures)?;
+        state.increment_sweep_nonce();
+
+        // Forget the payment before transfer to prevent reentrancy
+        state.remove_payment(asset);
+        let completed_to = Self::refund_completes_sweep(&state);
+        if !state.has_payments() {
+            state.set_status(AccountStatus::Active);
+        } else if let Some(destination) = &completed_to {
+            state.set_status(AccountStatus::Swept);
+            state.set_swept_to(destination);
+        }
+
+        let token = token::TokenClient::new(env, asset);
+        for (sender, amount) in shares.iter() {
+            token.transfer(&env.current_contract_address(), &sender, &amount);
+            events::emit_payment_refunded(env, asset.clone(), sender, amount);
+        }
+
+        // Refunding the last payment partial sweeps left closes the account
+        // as the last partial sweep would have
+        if let Some(destination) = completed_to {
+            let sweep_id = Self::record_sweep_id(env, &state, &destination);
+            Self::reclaim_reserve_to(env, &destination, sweep_id, None)?;
+            Self::notify_settled(env, &state);
+        }
+
+        Ok(())
+    }
+
+    /// What a refund of `payment` returns to each sender: the whole amount
+    /// to `sender`, or each contributor's share when top-ups came from more
+    /// than one address.  Fails if part of it was paid without a sender.
+    #[cfg(feature = "refunds")]
+    fn refund_shares(
+        env: &Env,
+        state: &AccountState,
+        payment: &Payment,
+        sender: &Address,
+    ) -> Result<Map<Address, i128>, Error> {
+        let Some(shares) = state.get_contributions(&payment.asset) else {
+            let mut whole = Map::new(env);
+            whole.set(sender.clone(), payment.amount);
+            return Ok(whole);
+        };
+        let known: i128 = shares.values().iter().sum();
+        if known != payment.amount {
+            return Err(Error::SenderUnknown);
+        }
+        Ok(shares)
+    }
+
+    /// Destination of the earlier partial sweeps when they moved every
+    /// payment a refund left.
+    #[cfg(all(feature = "refunds", feature = "partial"))]
+    fn refund_completes_sweep(state: &AccountState) -> Option<Address> {
+        let swept_assets = state.get_swept_assets();
+        if swept_assets.is_empty() || swept_assets.len() != state.get_total_payments() {
+            return None;
+        }
+        state.get_partial_sweep_to()
+    }
+
+    /// Built without `partial`, nothing is ever partially swept.
+    #[cfg(all(feature = "refunds", not(feature = "partial")))]
+    fn refund_completes_sweep(_state: &AccountState) -> Option<Address> {
+        None
+    }
+
+    #[cfg(feature = "escrow")]
+    fn apply_finalize_sweep(env: &Env) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+        Self::check_not_paused(env, &state)?;
+
+        let pending = Self::pending_release(&state)?;
+        if env.ledger().sequence() < pending.release_after_ledger {
+            return Err(Error::HoldNotElapsed);
+        }
+
+        state.remove_pending_release();
+        Self::complete_sweep(env, &state, &pending.destination)
+    }
+
+    #[cfg(feature = "escrow")]
+    fn apply_dispute(env: &Env) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+
+        let pending = Self::pending_release(&state)?;
+        let arbiter = state.get_escrow_arbiter().ok_or(Error::InvalidStatus)?;
+        arbiter.require_auth();
+
+        if env.ledger().sequence() >= pending.release_after_ledger {
+            return Err(Error::HoldElapsed);
+        }
+
+        state.remove_pending_release();
+        let recovery_address = state.get_recovery_address();
+        Self::complete_sweep(env, &state, &recovery_address)?;
+
+        events::emit_sweep_disputed(env, arbiter, pending.destination, recovery_address);
+
+        Ok(())
+    }
+
+    fn apply_expire(env: &Env, caller: Option<&Address>) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        // Check initialized
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+        Self::check_caller(&state, caller)?;
+
+        // Check not already closed or held for release
+        let status = state.get_status();
+        if status.is_closed() || status == AccountStatus::PendingRelease {
+            return Err(Error::InvalidStatus);
+        }
+
+        // Check if expired, and past the grace period a late sweep gets
+        if !Self::is_expired(env.clone()) {
+            return Err(Error::NotExpired);
+        }
+        if !Self::is_past_grace(env, &state) {
+            return Err(Error::GracePeriodActive);
+        }
+
+        let (recovery_address, total_amount, reclaimed_reserve) =
+            Self::close_to_recovery(env, &state, caller)?;
+
+        // Emit expiration event with reserve amount reclaimed in this call.
+        events::emit_account_expired(env, recovery_address, total_amount, reclaimed_reserve);
+        Self::claim_insurance(env, &state, total_amount);
+        Self::notify_settled(env, &state);
+
+        Ok(())
+    }
+
+    /// Open a claim with the insurance pool when an insured account
+    /// expires with payments in it, and emit `insured` with the claim id.
+    /// A pool that refuses the claim does not block the expiry.
+    #[cfg(feature = "insurance")]
+    fn claim_insurance(env: &Env, state: &AccountState, amount_returned: i128) {
+        let Some(pool) = state.get_insurance_pool() else {
+            return;
+        };
+        if amount_returned <= 0 {
+            return;
+        }
+        let claim = InsurancePoolClient::new(env, &pool)
+            .try_open_claim(&env.current_contract_address(), &amount_returned);
+        if let Ok(Ok(claim_id)) = claim {
+            events::emit_insured_expiry(env, pool, claim_id);
+        }
+    }
+
+    #[cfg(not(feature = "insurance"))]
+    fn claim_insurance(_env: &Env, _state: &AccountState, _amount_returned: i128) {}
+
+    #[cfg(feature = "lifecycle")]
+    fn apply_cancel(env: &Env, reason: CloseReason) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+
+        if reason == CloseReason::Timeout {
+            return Err(Error::InvalidReason);
+        }
+
+        // Only an account nobody has paid yet can be cancelled; a paid one
+        // settles through sweep or expire.
+        let status = state.get_status();
+        if status.is_closed()
+            || status == AccountStatus::PendingRelease
+            || state.has_payment_received()
+        {
+            return Err(Error::InvalidStatus);
+        }
+
+        let creator = state.get_creator();
+        creator.require_auth();
+
+        // The account was opened by mistake, so the reserve goes back to
+        // the creator who funded it.
+        state.set_status(AccountStatus::Cancelled);
+        state.set_swept_to(&creator);
+
+        let sweep_id = Self::record_sweep_id(env, &state, &creator);
+        let reclaimed_reserve = Self::reclaim_reserve_to(env, &creator, sweep_id, None)?;
+
+        events::emit_account_cancelled(env, creator, 0, reclaimed_reserve, reason);
+        Self::notify_settled(env, &state);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "guardian")]
+    fn apply_emergency_recover(env: &Env) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+
+        let guardian = state.get_guardian_config().ok_or(Error::Unauthorized)?;
+        let emergency_ledger = guardian.emergency_ledger.ok_or(Error::Unauthorized)?;
+        guardian.guardian.require_auth();
+
+        let status = state.get_status();
+        if status.is_closed() || status == AccountStatus::PendingRelease {
+            return Err(Error::InvalidStatus);
+        }
+        if env.ledger().sequence() < emergency_ledger {
+            return Err(Error::TimelockActive);
+        }
+
+        // Held payments go out less anything already clawed back
+        let (payments, _) =
+            Self::net_of_clawbacks(env, &state, Self::payments_in_sweep_order(env, &state));
+        let recovery_address = state.get_recovery_address();
+
+        // Update status before transfer to prevent reentrancy
+        state.set_status(AccountStatus::EmergencyRecovered);
+        state.set_swept_to(&recovery_address);
+
+        let this = env.current_contract_address();
+        for payment in payments.iter() {
+            if payment.amount > 0 {
+                token::TokenClient::new(env, &payment.asset).transfer(
+                    &this,
+                    &recovery_address,
+                    &payment.amount,
+                );
+            }
+        }
+
+        let sweep_id = Self::record_sweep_id(env, &state, &recovery_address);
+        let reclaimed_reserve = Self::reclaim_reserve_to(env, &recovery_address, sweep_id, None)?;
+
+        events::emit_emergency_recovered(
+            env,
+            guardian.guardian,
+            recovery_address,
+            payments,
+            reclaimed_reserve,
+        );
+        Self::notify_settled(env, &state);
+
+        Ok(())
+    }
+
+    /// Call the settlement hook, if one is registered, with the account's
+    /// new status and the recorded amount of each asset.  A failing hook
+    /// is reported with an event instead of failing the settlement.
+    #[cfg(feature = "hooks")]
+    fn notify_settled(env: &Env, state: &AccountState) {
+        let Some(hook) = state.get_settlement_hook() else {
+            return;
+        };
+        let mut total_by_asset = Map::new(env);
+        let mut payers = Vec::new(env);
+        for payment in state.get_payments().iter() {
+            total_by_asset.set(payment.asset, payment.amount);
+            if let Some(sender) = payment.sender {
+                if !payers.contains(&sender) {
+                    payers.push_back(sender);
+                }
+            }
+        }
+        let reference = state.get_metadata().map(|metadata| metadata.reference);
+        let status = state.get_status();
+        let result = SettlementHookClient::new(env, &hook).try_on_account_settled(
+            &env.current_contract_address(),
+            &status,
+            &total_by_asset,
+            &reference,
+            &payers,
+        );
+        if !matches!(result, Ok(Ok(()))) {
+            events::emit_hook_failed(env, hook, status);
+        }
+    }
+
+    /// Built without `hooks`, no settlement hook is ever registered.
+    #[cfg(not(feature = "hooks"))]
+    fn notify_settled(_env: &Env, _state: &AccountState) {}
+
+    /// Mark the account expired in favour of its recovery address, move
+    /// every outstanding payment there and reclaim the reserve there, less
+    /// any bounty owed to `keeper`.
+    ///
+    /// Returns the recovery address, the total transferred and the reserve
+    /// reclaimed in this call.
+    fn close_to_recovery(
+        env: &Env,
+        state: &AccountState,
+        keeper: Option<&Address>,
+    ) -> Result<(Address, i128, i128), Error> {
+        let recovery_address = state.get_recovery_address();
+
+        // Payments that were not swept, less anything clawed back
+        let (payments, _) =
+            Self::net_of_clawbacks(env, state, Self::payments_in_sweep_order(env, state));
+
+        // Update status before transfer to prevent reentrancy
+        state.set_status(AccountStatus::Expired);
+        state.set_swept_to(&recovery_address);
+
+        let this = env.current_contract_address();
+        let mut total_amount = 0i128;
+        for payment in payments.iter() {
+            if payment.amount > 0 {
+                token::TokenClient::new(env, &payment.asset).transfer(
+                    &this,
+                    &recovery_address,
+                    &payment.amount,
+                );
+            }
+            total_amount = total_amount
+                .checked_add(payment.amount)
+                .ok_or(Error::InvalidAmount)?;
+        }
+
+        let sweep_id = Self::record_sweep_id(env, state, &recovery_address);
+
+        // Reclaim reserve to recovery destination.
+        let keeper = keeper.map(|keeper| (keeper, Self::expiry_bounty_bps(env, state)));
+        let reclaimed_reserve = Self::reclaim_reserve_to(env, &recovery_address, sweep_id, keeper)?;
+
+        Ok((recovery_address, total_amount, reclaimed_reserve))
+    }
+
+    fn apply_reclaim_reserve(env: &Env, caller: Option<&Address>) -> Result<i128, Error> {
+        let state = AccountState::new(env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+        Self::check_caller(&state, caller)?;
+
+        let status = state.get_status();
+        if !status.is_closed() {
+            return Err(Error::InvalidStatus);
+        }
+
+        let destination = state.get_swept_to().ok_or(Error::InvalidStatus)?;
+        // Accounts closed before any id was recorded have the zero id
+        let sweep_id = state
+            .get_last_sweep_id()
+            .unwrap_or_else(|| BytesN::from_array(env, &[0u8; 32]));
+
+        let caller = caller.map(|caller| (caller, state.get_keeper_bounty_bps()));
+        Self::reclaim_reserve_to(env, &destination, sweep_id, caller)
+    }
+
+    #[cfg(feature = "reconcile")]
+    fn apply_record_reserve_topup(env: &Env, amount: i128) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+
+        state.get_creator().require_auth();
+
+        let remaining = state.get_base_reserve_remaining();
+        let shortfall = remaining.saturating_sub(state.get_available_reserve());
+        if amount <= 0 || amount > shortfall {
+            return Err(Error::InvalidAmount);
+        }
+        let available = state.get_available_reserve() + amount;
+        Self::check_native_reserve_backing(env, &state, available)?;
+
+        state.set_available_reserve(available);
+        events::emit_reserve_topped_up(env, amount, available, remaining);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "reconcile")]
+    fn apply_reconcile_reserve(env: &Env, actual_balance: i128) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+
+        state.get_creator().require_auth();
+
+        if actual_balance < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let remaining = state.get_base_reserve_remaining();
+        let available = actual_balance.min(remaining.max(0));
+        Self::check_native_reserve_backing(env, &state, available)?;
+
+        let old_available = state.get_available_reserve();
+        state.set_available_reserve(available);
+        events::emit_reserve_reconciled(env, actual_balance, old_available, available, remaining);
+
+        Ok(())
+    }
+
+    /// Fail if the reserve is held in XLM and the account's XLM balance
+    /// does not cover `available` plus the XLM payments it still holds.
+    #[cfg(feature = "reconcile")]
+    fn check_native_reserve_backing(
+        env: &Env,
+        state: &AccountState,
+        available: i128,
+    ) -> Result<(), Error> {
+        if !state.is_native_reserve_held() {
+            return Ok(());
+        }
+        let native_asset = Self::native_asset(env, state);
+        // A closed account no longer holds its payments
+        let held_payments = if state.get_status().is_closed() {
+            0
+        } else {
+            Self::outstanding_payments(env, state)
+                .get(native_asset.clone())
+                .map_or(0, |payment| payment.amount)
+        };
+        let balance =
+            token::TokenClient::new(env, &native_asset).balance(&env.current_contract_address());
+        if balance < available.saturating_add(held_payments) {
+            return Err(Error::InsufficientNativeBalance);
+        }
+        Ok(())
+    }
+
+    /// Publish a `FailureDiagnostic` for a failed call when debug events are on.
+    ///
+    /// `params` is only evaluated on the failure path.
+    #[cfg(feature = "diagnostics")]
+    fn report_failure<T>(
+        env: &Env,
+        function: &str,
+        result: &Result<T, Error>,
+        params: impl FnOnce() -> Vec<Val>,
+    ) {
+        let state = AccountState::new(env);
+        if let Err(error) = result {
+            if state.is_debug_events() {
+                events::emit_failure_diagnostic(
+                    env,
+                    Symbol::new(env, function),
+                    *error as u32,
+                    params(),
+                );
+            }
+        }
+    }
+
+    /// Built without `diagnostics`, debug events are never on.
+    #[cfg(not(feature = "diagnostics"))]
+    fn report_failure<T>(
+        _env: &Env,
+        _function: &str,
+        _result: &Result<T, Error>,
+        _params: impl FnOnce() -> Vec<Val>,
+    ) {
+    }
+
+    /// Base reserve to track for a new account: the configured
+    /// `ReserveContract`'s reserve for `config.trustlines`, taken from
+    /// `snapshot`, else the explicit override, else the default.
+    #[cfg(feature = "config")]
+    fn resolve_base_reserve(
+        config: &AccountConfig,
+        snapshot: Option<&ReserveSnapshot>,
+    ) -> Result<i128, Error> {
+        if let Some(snapshot) = snapshot {
+            let base_reserve = snapshot.base_reserve.ok_or(Error::ReserveNotSet)?;
+            let trustline_reserve = snapshot.trustline_reserve.unwrap_or(0);
+            // Both are capped at 10 000 XLM, so this cannot overflow
+            return Ok(base_reserve + trustline_reserve * config.trustlines as i128);
+        }
+        match config.base_reserve {
+            Some(amount) if amount < 0 => Err(Error::InvalidAmount),
+            Some(amount) => Ok(amount),
+            None => Ok(BASE_RESERVE_STROOPS),
+        }
+    }
+
+    /// Keeper bounty for a new account: the explicit setting, else the
+    /// configured `ReserveContract`'s from `snapshot`, else none.
+    #[cfg(feature = "keeper")]
+    fn resolve_keeper_bounty_bps(
+        config: &AccountConfig,
+        snapshot: Option<&ReserveSnapshot>,
+    ) -> Result<u32, Error> {
+        let bps = match (config.keeper_bounty_bps, snapshot) {
+            (Some(bps), _) => bps,
+            (None, Some(snapshot)) => snapshot.keeper_bounty_bps.map_or(0, |bps| bps as u32),
+            (None, None) => 0,
+        };
+        if !units::is_valid_bps(bps) {
+            return Err(Error::InvalidFee);
+        }
+        Ok(bps)
+    }
+
+    /// Keeper bounty `expire_as` pays now: the keeper bounty, rising
+    /// linearly over the ramp from the end of the grace period to its cap.
+    #[cfg(feature = "keeper")]
+    fn expiry_bounty_bps(env: &Env, state: &AccountState) -> u32 {
+        let bounty_bps = state.get_keeper_bounty_bps();
+        let Some((max_bps, ramp_ledgers)) = state.get_keeper_bounty_ramp() else {
+            return bounty_bps;
+        };
+        let overdue = env
+            .ledger()
+            .sequence()
+            .saturating_sub(Self::grace_end_ledger(state));
+        if overdue >= ramp_ledgers {
+            return max_bps;
+        }
+        let raise = u64::from(max_bps - bounty_bps) * u64::from(overdue) / u64::from(ramp_ledgers);
+        bounty_bps + raise as u32
+    }
+
+    /// Built without `keeper`, nobody is paid for expiring the account.
+    #[cfg(not(feature = "keeper"))]
+    fn expiry_bounty_bps(_env: &Env, _state: &AccountState) -> u32 {
+        0
+    }
+
+    /// Raise the trustline count to the tokens the account has been paid
+    /// in, once it holds more than it was opened with.
+    fn track_trustlines(env: &Env, state: &AccountState) {
+        let native_asset = Self::native_asset(env, state);
+        let held = state
+            .get_payment_assets()
+            .iter()
+            .filter(|asset| *asset != native_asset)
+            .count() as u32;
+        if held > state.get_trustline_count() {
+            state.set_trustline_count(held);
+        }
+    }
+
+    /// Fold `payment` into the running payments commitment.
+    fn commit_payment(env: &Env, state: &AccountState, payment: &Payment) {
+        let mut preimage = Bytes::from_array(env, &state.get_payments_root().to_array());
+        preimage.append(&payment.clone().to_xdr(env));
+        state.set_payments_root(&env.crypto().sha256(&preimage).to_bytes());
+    }
+
+    /// Entry of a state-changing call: take the call's state sequence
+    /// number, which every event it publishes carries, and migrate the
+    /// storage if needed.
+    fn migrate_if_needed(env: &Env, state: &AccountState) -> Result<(), Error> {
+        if !state.is_initialized() {
+            return Ok(());
+        }
+        state.next_state_seq();
+        Self::upgrade_storage(env, state)
+    }
+
+    /// Storage migration hook: bring an account written by older code up to
+    /// the current layout and record the running code version.  Runs on
+    /// the first call that touches the account after an `upgrade`, getters
+    /// included, or through `migrate`; a no-op once the recorded versions
+    /// are current.
+    fn upgrade_storage(env: &Env, state: &AccountState) -> Result<(), Error> {
+        let from_version = state.get_storage_version();
+        if from_version > STORAGE_VERSION {
+            // Written by newer code; this code cannot read it
+            return Err(Error::StorageVersionMismatch);
+        }
+        if from_version < STORAGE_VERSION {
+            Self::migrate_layout(env, state, from_version)?;
+            state.set_storage_version(STORAGE_VERSION);
+            events::emit_storage_migrated(env, from_version, STORAGE_VERSION);
+        }
+        if state.get_wasm_version() != WASM_VERSION {
+            state.set_wasm_version(WASM_VERSION);
+        }
+        Ok(())
+    }
+
+    /// Layout changes, one step per version, oldest first.  Version 0 is
+    /// the unversioned layout of the first release: payments in an
+    /// instance map, ledger-sequence sweep ids, and only the latest reserve
+    /// event.
+    #[cfg(feature = "upgrade")]
+    fn migrate_layout(env: &Env, state: &AccountState, from_version: u32) -> Result<(), Error> {
+        if from_version < 1 {
+            for payment in state.take_payments_v0().iter() {
+                Self::commit_payment(env, state, &payment);
+                state.add_payment(payment);
+            }
+            state.migrate_reserve_v0(BASE_RESERVE_STROOPS);
+        }
+        Ok(())
+    }
+
+    /// Built without `upgrade`, an account written in an older layout
+    /// cannot be read.
+    #[cfg(not(feature = "upgrade"))]
+    fn migrate_layout(_env: &Env, _state: &AccountState, _from_version: u32) -> Result<(), Error> {
+        Err(Error::StorageVersionMismatch)
+    }
+
+    /// Bring the account's storage into the layout this code reads before a
+    /// getter reads it, so an account left in a pre-upgrade layout is
+    /// migrated on first touch rather than refused.  Storage written by
+    /// newer code, or an older layout in a build without `upgrade`, still
+    /// fails with `StorageVersionMismatch` instead of being misread.
+    ///
+    /// Only a getter that migrates changes state, so only that one takes a
+    /// state sequence number.
+    fn check_storage_version(env: &Env, state: &AccountState) -> Result<(), Error> {
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        if state.get_storage_version() < STORAGE_VERSION {
+            state.next_state_seq();
+        }
+        Self::upgrade_storage(env, state)
+    }
+
+    /// Account state for a view answered from the per-asset payment
+    /// entries, which a version 0 account does not hold until it is
+    /// migrated.
+    #[cfg(feature = "views")]
+    fn payments_view(env: &Env) -> AccountState<'_> {
+        let state = AccountState::new(env);
+        // An uninitialized account or newer layout has no totals to derive
+        let _ = Self::check_storage_version(env, &state);
+        state
+    }
+
+    /// Authorize the caller of a transition that needs no signature of its
+    /// own.  A named caller always authorizes; under
+    /// `AuthPolicy::Restricted` it must also be the creator, the recovery
+    /// address or the keeper.
+    fn check_caller(state: &AccountState, caller: Option<&Address>) -> Result<(), Error> {
+        if state.get_auth_policy() == AuthPolicy::Restricted {
+            let caller = caller.ok_or(Error::Unauthorized)?;
+            let allowed = *caller == state.get_creator()
+                || *caller == state.get_recovery_address()
+                || state.get_keeper().as_ref() == Some(caller);
+            if !allowed {
+                return Err(Error::Unauthorized);
+            }
+        }
+        if let Some(caller) = caller {
+            caller.require_auth();
+        }
+        Ok(())
+    }
+
+    /// Fail while the configured kill switch halts accounts.
+    fn check_not_halted(env: &Env, state: &AccountState) -> Result<(), Error> {
+        match state.get_kill_switch() {
+            Some(kill_switch)
+                if KillSwitchClient::new(env, &kill_switch).is_halted(&KILL_SWITCH_SUBSYSTEM) =>
+            {
+                Err(Error::Halted)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fail while the `ReserveContract` the account was configured from is
+    /// paused.  Guards payment recording and every sweep.
+    fn check_not_paused(env: &Env, state: &AccountState) -> Result<(), Error> {
+        match state.get_reserve_contract() {
+            Some(reserve_contract) if ReserveClient::new(env, &reserve_contract).is_paused() => {
+                Err(Error::SystemPaused)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fail while the compliance gate holds the account's sweeps.
+    #[cfg(feature = "compliance")]
+    fn check_compliance(env: &Env, state: &AccountState) -> Result<(), Error> {
+        match state.get_compliance_gate() {
+            Some(gate) => match ComplianceGateClient::new(env, &gate)
+                .try_check_sweep(&env.current_contract_address())
+            {
+                Ok(Ok(())) => Ok(()),
+                _ => Err(Error::ComplianceHold),
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Built without `compliance`, no gate ever holds a sweep.
+    #[cfg(not(feature = "compliance"))]
+    fn check_compliance(_env: &Env, _state: &AccountState) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Tell the compliance gate of a payment about to be recorded, so it
+    /// counts towards the merchant's and `sender`'s rolling volume.
+    #[cfg(feature = "compliance")]
+    fn report_payment(
+        env: &Env,
+        state: &AccountState,
+        sender: &Option<Address>,
+        asset: &Address,
+        amount: i128,
+    ) {
+        if let Some(gate) = state.get_compliance_gate() {
+            ComplianceGateClient::new(env, &gate).check_payment(
+                &env.current_contract_address(),
+                sender,
+                asset,
+                &amount,
+            );
+        }
+    }
+
+    /// Built without `compliance`, payments are reported to no one.
+    #[cfg(not(feature = "compliance"))]
+    fn report_payment(
+        _env: &Env,
+        _state: &AccountState,
+        _sender: &Option<Address>,
+        _asset: &Address,
+        _amount: i128,
+    ) {
+    }
+
+    /// Longest clawback hold among the outstanding payments, and the
+    /// assets that have one.
+    #[cfg(feature = "escrow")]
+    fn clawback_held_assets(env: &Env, state: &AccountState) -> (u32, Vec<Address>) {
+        let mut hold = 0;
+        let mut assets = Vec::new(env);
+        for payment in Self::outstanding_payments(env, state).values() {
+            let asset_hold = state.get_clawback_hold(&payment.asset);
+            if asset_hold > 0 {
+                hold = hold.max(asset_hold);
+                assets.push_back(payment.asset);
+            }
+        }
+        (hold, assets)
+    }
+
+    /// Cap each clawback-held payment at the balance actually left, so a
+    /// clawback during the hold is reported instead of failing the sweep.
+    /// Also returns the clawback-held assets.
+    #[cfg(feature = "escrow")]
+    fn net_of_clawbacks(
+        env: &Env,
+        state: &AccountState,
+        payments: Vec<Payment>,
+    ) -> (Vec<Payment>, Vec<Address>) {
+        let this = env.current_contract_address();
+        let mut netted = Vec::new(env);
+        let mut held_assets = Vec::new(env);
+        for mut payment in payments.iter() {
+            if state.get_clawback_hold(&payment.asset) > 0 {
+                held_assets.push_back(payment.asset.clone());
+                let balance = token::TokenClient::new(env, &payment.asset).balance(&this);
+                if balance < payment.amount {
+                    let remaining = balance.max(0);
+                    events::emit_payment_clawed_back(
+                        env,
+                        payment.asset.clone(),
+                        payment.amount,
+                        payment.amount - remaining,
+                    );
+                    payment.amount = remaining;
+                }
+            }
+            netted.push_back(payment);
+        }
+        (netted, held_assets)
+    }
+
+    /// Built without `escrow`, no payment is held for clawbacks.
+    #[cfg(not(feature = "escrow"))]
+    fn net_of_clawbacks(
+        env: &Env,
+        _state: &AccountState,
+        payments: Vec<Payment>,
+    ) -> (Vec<Payment>, Vec<Address>) {
+        (payments, Vec::new(env))
+    }
+
+    /// Fail in escrow mode, where only a held `sweep` may move funds.
+    #[cfg(feature = "partial")]
+    fn check_not_escrow(state: &AccountState) -> Result<(), Error> {
+        if state.get_escrow_arbiter().is_some() {
+            return Err(Error::HoldRequired);
+        }
+        Ok(())
+    }
+
+    /// Protocol fee owed on a swept `amount`, rounded down.
+    #[cfg(feature = "fees")]
+    fn fee_for(state: &AccountState, amount: i128) -> Result<i128, Error> {
+        if state.get_fee_collector().is_none() {
+            return Ok(0);
+        }
+        units::bps_of(amount, state.get_fee_bps()).ok_or(Error::InvalidAmount)
+    }
+
+    /// Built without `fees`, no fee is ever configured.
+    #[cfg(not(feature = "fees"))]
+    fn fee_for(_state: &AccountState, _amount: i128) -> Result<i128, Error> {
+        Ok(0)
+    }
+
+    /// Send the protocol fee on `payment` to the fee collector and return it.
+    #[cfg(feature = "fees")]
+    fn collect_fee(env: &Env, state: &AccountState, payment: &Payment) -> Result<i128, Error> {
+        let fee = Self::fee_for(state, payment.amount)?;
+        if fee > 0 {
+            if let Some(collector) = state.get_fee_collector() {
+                token::TokenClient::new(env, &payment.asset).transfer(
+                    &env.current_contract_address(),
+                    &collector,
+                    &fee,
+                );
+            }
+        }
+        Ok(fee)
+    }
+
+    #[cfg(not(feature = "fees"))]
+    fn collect_fee(_env: &Env, state: &AccountState, payment: &Payment) -> Result<i128, Error> {
+        Self::fee_for(state, payment.amount)
+    }
+
+    /// Send `payment`, less the protocol fee, to `destination`.  In
+    /// quarantine mode a failing transfer returns `None` and leaves the
+    /// payment in the account instead of reverting.
+    fn send_payment(
+        env: &Env,
+        state: &AccountState,
+        payment: &Payment,
+        destination: &Address,
+    ) -> Result<Option<SweepAmount>, Error> {
+        let fee = Self::fee_for(state, payment.amount)?;
+        let net = payment.amount - fee;
+        let token = token::TokenClient::new(env, &payment.asset);
+        let this = env.current_contract_address();
+        if state.is_quarantine_mode() {
+            if !matches!(token.try_transfer(&this, destination, &net), Ok(Ok(()))) {
+                return Ok(None);
+            }
+        } else {
+            token.transfer(&this, destination, &net);
+        }
+        Self::collect_fee(env, state, payment)?;
+        Ok(Some(SweepAmount {
+            asset: payment.asset.clone(),
+            gross: payment.amount,
+            fee,
+            net,
+        }))
+    }
+
+    #[cfg(feature = "dust")]
+    fn apply_sweep_dust(env: &Env) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+
+        let dust = state.get_dust();
+        if dust.is_empty() {
+            return Err(Error::AssetNotFound);
+        }
+        let destination = state.get_swept_to().ok_or(Error::InvalidStatus)?;
+
+        // Clear before transferring; a failure reverts both
+        state.set_dust(&Vec::new(env));
+        let mut amounts = Vec::new(env);
+        for payment in dust.iter() {
+            let amount = Self::send_payment(env, &state, &payment, &destination)?
+                .ok_or(Error::DestinationCannotReceive)?;
+            amounts.push_back(amount);
+        }
+        events::emit_dust_swept(env, destination, amounts);
+        Ok(())
+    }
+
+    #[cfg(feature = "quarantine")]
+    fn apply_retry_quarantined(env: &Env, asset: &Address) -> Result<bool, Error> {
+        let state = AccountState::new(env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(env, &state)?;
+        Self::check_not_halted(env, &state)?;
+
+        let quarantined = state.get_quarantined();
+        let index = quarantined
+            .iter()
+            .position(|payment| payment.asset == *asset)
+            .ok_or(Error::AssetNotFound)?;
+        let payment = quarantined.get_unchecked(index as u32);
+        let destination = state.get_swept_to().ok_or(Error::InvalidStatus)?;
+        let mut retries = state.get_quarantine_retries();
+        // Quarantined before retries were tracked: no wait
+        let attempts = match retries.get(asset.clone()) {
+            Some(retry) if env.ledger().sequence() < retry.next_retry_ledger => {
+                return Err(Error::RetryTooEarly);
+            }
+            Some(retry) => retry.attempts,
+            None => 0,
+        };
+
+        // Remove before transferring; a failed transfer puts it back
+        let mut remaining = quarantined.clone();
+        remaining.remove(index as u32);
+        state.set_quarantined(&remaining);
+        let Some(amount) = Self::send_payment(env, &state, &payment, &destination)? else {
+            let retry = Self::quarantine_retry(env, attempts + 1);
+            retries.set(asset.clone(), retry.clone());
+            state.set_quarantined(&quarantined);
+            state.set_quarantine_retries(&retries);
+            events::emit_quarantine_retry_failed(env, asset.clone(), retry);
+            return Ok(false);
+        };
+        retries.remove(asset.clone());
+        state.set_quarantine_retries(&retries);
+        events::emit_quarantine_released(env, destination, amount);
+        Ok(true)
+    }
+
+    /// Retry state after `attempts` failed retries.  The wait starts at
+    /// `QUARANTINE_RETRY_BASE_LEDGERS` and doubles with each failure, up
+    /// to `QUARANTINE_RETRY_MAX_LEDGERS`.
+    #[cfg(feature = "quarantine")]
+    fn quarantine_retry(env: &Env, attempts: u32) -> QuarantineRetry {
+        let wait = QUARANTINE_RETRY_BASE_LEDGERS
+            .saturating_mul(2u32.saturating_pow(attempts))
+            .min(QUARANTINE_RETRY_MAX_LEDGERS);
+        QuarantineRetry {
+            attempts,
+            next_retry_ledger: env.ledger().sequence().saturating_add(wait),
+        }
+    }
+
+    /// Fail if the account is locked to a different destination.
+    fn check_destination(state: &AccountState, destination: &Address) -> Result<(), Error> {
+        match state.get_locked_destination() {
+            Some(locked) if locked != *destination => Err(Error::DestinationMismatch),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether `destination` can hold `asset`, so a transfer of it would
+    /// not revert.
+    fn can_receive(env: &Env, asset: &Address, destination: &Address) -> bool {
+        // A Stellar Asset Contract fails the read for a classic account
+        // without a trustline
+        if token::TokenClient::new(env, asset)
+            .try_balance(destination)
+            .is_err()
+        {
+            return false;
+        }
+        // Only Stellar Asset Contracts answer `authorized`; other tokens
+        // get the benefit of the doubt
+        !matches!(
+            token::StellarAssetClient::new(env, asset).try_authorized(destination),
+            Ok(Ok(false))
+        )
+    }
+
+    /// Fail unless `destination` can hold `asset`.
+    fn check_can_receive(env: &Env, asset: &Address, destination: &Address) -> Result<(), Error> {
+        if Self::can_receive(env, asset, destination) {
+            Ok(())
+        } else {
+            Err(Error::DestinationCannotReceive)
+        }
+    }
+
+    /// First ledger at which the account can be expired.
+    fn grace_end_ledger(state: &AccountState) -> u32 {
+        state
+            .get_expiry_ledger()
+            .saturating_add(state.get_grace_ledgers())
+    }
+
+    /// Whether the grace period after expiry is over, closing the account
+    /// to sweeps.
+    fn is_past_grace(env: &Env, state: &AccountState) -> bool {
+        env.ledger().sequence() >= Self::grace_end_ledger(state)
+    }
+
+    /// The sweep held for release, if the account is waiting on one.
+    #[cfg(feature = "escrow")]
+    fn pending_release(state: &AccountState) -> Result<PendingRelease, Error> {
+        if state.get_status() != AccountStatus::PendingRelease {
+            return Err(Error::InvalidStatus);
+        }
+        state.get_pending_release().ok_or(Error::InvalidStatus)
+    }
+
+    /// Preconditions shared by `sweep` and `sweep_assets`.
+    fn check_sweepable(env: &Env, state: &AccountState) -> Result<(), Error> {
+        // Check initialized
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+
+        // Check not already swept, held or cancelled
+        match state.get_status() {
+            AccountStatus::Swept => return Err(Error::AlreadySwept),
+            AccountStatus::Cancelled
+            | AccountStatus::PendingRelease
+            | AccountStatus::EmergencyRecovered => return Err(Error::InvalidStatus),
+            _ => {}
+        }
+
+        // Check payment received
+        if !state.has_payment_received() {
+            return Err(Error::NoPaymentReceived);
+        }
+
+        // Check not expired, by ledger past the grace period or by status
+        if Self::is_past_grace(env, state) || state.get_status() == AccountStatus::Expired {
+            return Err(Error::AccountExpired);
+        }
+
+        Self::check_batch_window(env, state)
+    }
+
+    /// Fail while the batching window holds sweeps back: the recorded
+    /// payments are below `batch_min_amount` and the maximum wait since
+    /// the first payment has not elapsed.
+    fn check_batch_window(env: &Env, state: &AccountState) -> Result<(), Error> {
+        let min_amount = state.get_batch_min_amount();
+        if min_amount == 0 {
+            return Ok(());
+        }
+
+        let mut received = 0i128;
+        for payment in state.get_payments().iter() {
+            received = received.saturating_add(payment.amount);
+        }
+        if received >= min_amount {
+            return Ok(());
+        }
+
+        let max_wait = state.get_batch_max_wait();
+        if let Some(first_payment_ledger) = state.get_first_payment_ledger() {
+            if max_wait > 0
+                && env.ledger().sequence() >= first_payment_ledger.saturating_add(max_wait)
+            {
+                return Ok(());
+            }
+        }
+        Err(Error::BatchWindowOpen)
+    }
+
+    /// Fail while `payments`, the ones a sweep moves, are worth less than
+    /// `min_sweep_value`.
+    #[cfg(feature = "oracle")]
+    fn check_sweep_value(
+        env: &Env,
+        state: &AccountState,
+        payments: &Vec<Payment>,
+    ) -> Result<(), Error> {
+        let min_value = state.get_min_sweep_value();
+        if min_value == 0 {
+            return Ok(());
+        }
+        match Self::sweep_value(env, state, payments) {
+            Some(value) if value < min_value => Err(Error::BelowMinSweepValue),
+            _ => Ok(()),
+        }
+    }
+
+    /// Built without `oracle`, no minimum sweep value is ever set.
+    #[cfg(not(feature = "oracle"))]
+    fn check_sweep_value(
+        _env: &Env,
+        _state: &AccountState,
+        _payments: &Vec<Payment>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// `payments` priced by the account's oracle, or `None` without one.
+    ///
+    /// Each payment is worth `amount * price / 10^decimals`, in the scale
+    /// of the oracle's prices.  A quote the oracle cannot give, or a call
+    /// to it that fails, counts as zero; a value too large for an `i128`
+    /// saturates.
+    #[cfg(feature = "oracle")]
+    fn sweep_value(env: &Env, state: &AccountState, payments: &Vec<Payment>) -> Option<i128> {
+        let oracle = PriceOracleClient::new(env, &state.get_price_oracle()?);
+        let mut value = 0i128;
+        for payment in payments.iter() {
+            let worth = Self::payment_value(&oracle, &payment).unwrap_or(0);
+            value = value.saturating_add(worth);
+        }
+        Some(value)
+    }
+
+    /// `payment` priced by `oracle`, or `None` if the oracle cannot quote
+    /// it.
+    #[cfg(feature = "oracle")]
+    fn payment_value(oracle: &PriceOracleClient, payment: &Payment) -> Option<i128> {
+        let price = match oracle.try_lastprice(&OracleAsset::Stellar(payment.asset.clone())) {
+            Ok(Ok(Some(quote))) if quote.price > 0 => quote.price,
+            _ => return None,
+        };
+        let decimals = payment.decimals.unwrap_or(units::XLM_DECIMALS);
+        Some(
+            payment
+                .amount
+                .checked_mul(price)
+                .map_or(i128::MAX, |scaled| {
+                    units::scale_decimals(scaled, decimals, 0).unwrap_or(0)
+                }),
+        )
+    }
+
+    /// Split `payments` into those to sweep now and the dust left for
+    /// `sweep_dust`: payments the oracle prices below the dust threshold.
+    /// A payment the oracle cannot quote is swept.
+    #[cfg(feature = "dust")]
+    fn split_dust(
+        env: &Env,
+        state: &AccountState,
+        payments: Vec<Payment>,
+    ) -> (Vec<Payment>, Vec<Payment>) {
+        let Some((oracle, threshold)) = state.get_dust_threshold() else {
+            return (payments, Vec::new(env));
+        };
+        let oracle = PriceOracleClient::new(env, &oracle);
+        let mut swept = Vec::new(env);
+        let mut dust = Vec::new(env);
+        for payment in payments.iter() {
+            match Self::payment_value(&oracle, &payment) {
+                Some(value) if value < threshold => dust.push_back(payment),
+                _ => swept.push_back(payment),
+            }
+        }
+        (swept, dust)
+    }
+
+    /// Built without `dust`, every payment is swept at once.
+    #[cfg(not(feature = "dust"))]
+    fn split_dust(
+        env: &Env,
+        _state: &AccountState,
+        payments: Vec<Payment>,
+    ) -> (Vec<Payment>, Vec<Payment>) {
+        (payments, Vec::new(env))
+    }
+
+    /// Guarded stablecoins among `assets` that the oracle prices more than
+    /// the allowed deviation away from their peg.
+    ///
+    /// A stablecoin the oracle cannot quote counts as off peg, so an oracle
+    /// outage does not open the guard.  Empty while the guardian's override
+    /// lasts.
+    #[cfg(feature = "depeg")]
+    fn depegged_assets(env: &Env, state: &AccountState, assets: &Vec<Address>) -> Vec<Address> {
+        let mut depegged = Vec::new(env);
+        let Some((oracle, pegs, threshold_bps)) = state.get_depeg_guard() else {
+            return depegged;
+        };
+        if state
+            .get_depeg_override()
+            .is_some_and(|until_ledger| env.ledger().sequence() <= until_ledger)
+        {
+            return depegged;
+        }
+        let oracle = PriceOracleClient::new(env, &oracle);
+        for asset in assets.iter() {
+            let Some(peg) = pegs.get(asset.clone()) else {
+                continue;
+            };
+            let on_peg = match oracle.try_lastprice(&OracleAsset::Stellar(asset.clone())) {
+                Ok(Ok(Some(quote))) => units::bps_of(peg, threshold_bps).is_some_and(|band| {
+                    quote
+                        .price
+                        .checked_sub(peg)
+                        .is_some_and(|deviation| deviation.saturating_abs() <= band)
+                }),
+                _ => false,
+            };
+            if !on_peg {
+                depegged.push_back(asset);
+            }
+        }
+        depegged
+    }
+
+    /// Fail while a guarded stablecoin among `assets` is off peg.
+    #[cfg(feature = "depeg")]
+    fn check_pegged(env: &Env, state: &AccountState, assets: &Vec<Address>) -> Result<(), Error> {
+        if Self::depegged_assets(env, state, assets).is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Depegged)
+        }
+    }
+
+    /// Built without `depeg`, no stablecoin is ever guarded.
+    #[cfg(not(feature = "depeg"))]
+    fn check_pegged(
+        _env: &Env,
+        _state: &AccountState,
+        _assets: &Vec<Address>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Payments not yet moved by `sweep_assets`, keyed by asset.
+    ///
+    /// Reads each payment entry once.
+    fn outstanding_payments(env: &Env, state: &AccountState) -> Map<Address, Payment> {
+        let swept_assets = state.get_swept_assets();
+        let mut payments = Map::new(env);
+        for payment in state.get_payments().iter() {
+            if !swept_assets.contains(&payment.asset) {
+                payments.set(payment.asset.clone(), payment);
+            }
+        }
+        payments
+    }
+
+    /// Outstanding payments ordered by the creator's sweep priority, then
+    /// by asset address.
+    fn payments_in_sweep_order(env: &Env, state: &AccountState) -> Vec<Payment> {
+        let mut payments = Self::outstanding_payments(env, state);
+        let mut ordered = Vec::new(env);
+        for asset in state.get_sweep_priority().iter() {
+            if let Some(payment) = payments.get(asset.clone()) {
+                ordered.push_back(payment);
+                payments.remove(asset);
+            }
+        }
+        for payment in payments.values() {
+            ordered.push_back(payment);
+        }
+        ordered
+    }
+
+    /// Fail if no key that could authorize a sweep is configured.
+    #[cfg(feature = "views")]
+    fn check_sweep_signer(state: &AccountState) -> Result<(), Error> {
+        let configured = match state.get_signer_kind() {
+            SignerKind::Secp256r1 => state.get_passkey_signer().is_some(),
+            SignerKind::Ed25519 => !state.get_sweep_signers().is_empty(),
+        };
+        if !configured {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
+    }
+
+    /// Check that at least the threshold of sweep signers signed `message`.
+    ///
+    /// Slot `i` of `signatures` belongs to signer `i`; an all-zero slot
+    /// means that signer did not sign, so each signer counts at most once.
+    /// A passkey account takes its single signature in slot 0.
+    ///
+    /// A missing signer, or a key or signature no verification could
+    /// accept, fails with `Error::Unauthorized` before the host is asked.
+    /// A well-formed signature that does not verify is expected to trap in
+    /// the host's verifier: the SDK offers no non-trapping check, so the
+    /// caller sees a host crypto error rather than `Error::Unauthorized`,
+    /// and nothing the call did is kept.
+    fn verify_sweep_authorization(
+        env: &Env,
+        state: &AccountState,
+        message: BytesN<32>,
+        signatures: &Vec<BytesN<64>>,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "passkey")]
+        if state.get_signer_kind() == SignerKind::Secp256r1 {
+            return Self::verify_passkey_authorization(env, state, message, signatures);
+        }
+
+        let signers = state.get_sweep_signers();
+        if signers.is_empty() {
+            return Err(Error::Unauthorized);
+        }
+        if signatures.len() > signers.len() {
+            return Err(Error::InvalidSignature);
+        }
+
+        let message: Bytes = message.into();
+        let unsigned = BytesN::from_array(env, &[0u8; 64]);
+        let mut signed = 0;
+        for (signer, signature) in signers.iter().zip(signatures.iter()) {
+            if signature == unsigned {
+                continue;
+            }
+            // An `s` of 2^253 or more is never below the group order
+            if signature.get(63).unwrap_or(0) & 0xe0 != 0 {
+                return Err(Error::Unauthorized);
+            }
+            // Traps on a bad signature, aborting the whole invocation.
+            env.crypto().ed25519_verify(&signer, &message, &signature);
+            signed += 1;
+        }
+
+        if signed < state.get_sweep_threshold() {
+            return Err(Error::ThresholdNotMet);
+        }
+        Ok(())
+    }
+
+    /// Check the passkey's ECDSA signature (r || s, low-S) over `message`.
+    ///
+    /// The signature is over the SHA-256 digest of the 32-byte message, as
+    /// produced by a standard P-256 signer.
+    #[cfg(feature = "passkey")]
+    fn verify_passkey_authorization(
+        env: &Env,
+        state: &AccountState,
+        message: BytesN<32>,
+        signatures: &Vec<BytesN<64>>,
+    ) -> Result<(), Error> {
+        let key = state.get_passkey_signer().ok_or(Error::Unauthorized)?;
+        if signatures.len() > 1 {
+            return Err(Error::InvalidSignature);
+        }
+        let signature = signatures.first().ok_or(Error::ThresholdNotMet)?;
+        // The host takes an uncompressed SEC1 key and a low-S signature
+        // with non-zero scalars
+        let bytes = signature.to_array();
+        let (r, s) = bytes.split_at(32);
+        let zero = [0u8; 32];
+        if key.get(0) != Some(0x04) || r == zero || s == zero || s > &P256_HALF_ORDER[..] {
+            return Err(Error::Unauthorized);
+        }
+
+        let digest = env.crypto().sha256(&message.into());
+        // Traps on a bad signature, aborting the whole invocation.
+        env.crypto().secp256r1_verify(&key, &digest, &signature);
+        Ok(())
+    }
+
+    /// Validate an M-of-N sweep signer set.
+    #[cfg(any(feature = "config", feature = "multisig"))]
+    fn check_signer_set(signers: &Vec<BytesN<32>>, threshold: u32) -> Result<(), Error> {
+        if signers.is_empty() || signers.len() > MAX_SWEEP_SIGNERS {
+            return Err(Error::InvalidSignerSet);
+        }
+        if threshold == 0 || threshold > signers.len() {
+            return Err(Error::InvalidSignerSet);
+        }
+        for (i, signer) in signers.iter().enumerate() {
+            if signers.iter().skip(i + 1).any(|other| other == signer) {
+                return Err(Error::InvalidSignerSet);
+            }
+        }
+        Ok(())
+    }
+
+    /// sha256(contract_id || destination || nonce || expiry_ledger)
+    ///
+    /// Addresses are XDR-encoded, the nonce is a big-endian u64 and the
+    /// expiry ledger a big-endian u32.
+    fn sweep_message(env: &Env, state: &AccountState, destination: &Address) -> BytesN<32> {
+        let message = Self::sweep_preimage(env, state, destination);
+        env.crypto().sha256(&message).to_bytes()
+    }
+
+    /// sha256(contract_id || destination || nonce || expiry_ledger || assets)
+    ///
+    /// The sweep preimage followed by the XDR-encoded asset list, so a
+    /// signature for one subset cannot be used to sweep another.
+    #[cfg(feature = "partial")]
+    fn sweep_assets_message(
+        env: &Env,
+        state: &AccountState,
+        destination: &Address,
+        assets: &Vec<Address>,
+    ) -> BytesN<32> {
+        let mut message = Self::sweep_preimage(env, state, destination);
+        message.append(&assets.clone().to_xdr(env));
+        env.crypto().sha256(&message).to_bytes()
+    }
+
+    /// Append `payment`, carrying `amount`, to the share of destination `index`.
+    #[cfg(feature = "partial")]
+    fn push_share(shares: &mut Vec<Vec<Payment>>, index: u32, payment: &Payment, amount: i128) {
+        let mut share = shares.get_unchecked(index);
+        share.push_back(Payment {
+            amount,
+            ..payment.clone()
+        });
+        shares.set(index, share);
+    }
+
+    /// sha256(contract_id || destinations || nonce || expiry_ledger)
+    ///
+    /// The XDR-encoded `(destination, share_bps)` list takes the place of
+    /// the single destination, binding the signature to the exact split.
+    #[cfg(feature = "partial")]
+    fn sweep_split_message(
+        env: &Env,
+        state: &AccountState,
+        destinations: &Vec<(Address, u32)>,
+    ) -> BytesN<32> {
+        let mut message = env.current_contract_address().to_xdr(env);
+        message.append(&destinations.clone().to_xdr(env));
+        message.extend_from_array(&state.get_sweep_nonce().to_be_bytes());
+        message.extend_from_array(&state.get_expiry_ledger().to_be_bytes());
+        env.crypto().sha256(&message).to_bytes()
+    }
+
+    /// sha256(contract_id || sender || nonce || expiry_ledger || "refund" || asset)
+    ///
+    /// The tag keeps a refund signature from matching any sweep message.
+    #[cfg(feature = "refunds")]
+    fn refund_message(
+        env: &Env,
+        state: &AccountState,
+        sender: &Address,
+        asset: &Address,
+    ) -> BytesN<32> {
+        let mut message = Self::sweep_preimage(env, state, sender);
+        message.append(&symbol_short!("refund").to_xdr(env));
+        message.append(&asset.clone().to_xdr(env));
+        env.crypto().sha256(&message).to_bytes()
+    }
+
+    /// sha256(contract_id || destination || nonce || epoch)
+    ///
+    /// Id of a sweep or closure to `destination`, taken with the sweep
+    /// nonce as it stands once the sweep consumed its own.  It depends
+    /// only on the account's history, not on the ledger the transaction
+    /// lands in, so a retried or re-simulated settlement keeps its id.
+    /// Closures do not consume the nonce and `reset` keeps it, so the
+    /// epoch tells apart closures to the same address in different cycles.
+    fn sweep_id(env: &Env, state: &AccountState, destination: &Address) -> BytesN<32> {
+        let mut preimage = env.current_contract_address().to_xdr(env);
+        preimage.append(&destination.clone().to_xdr(env));
+        preimage.extend_from_array(&state.get_sweep_nonce().to_be_bytes());
+        preimage.extend_from_array(&state.get_epoch().to_be_bytes());
+        env.crypto().sha256(&preimage).to_bytes()
+    }
+
+    /// `sweep_id` of a settlement closing the account, recorded as its
+    /// last.
+    fn record_sweep_id(env: &Env, state: &AccountState, destination: &Address) -> BytesN<32> {
+        let sweep_id = Self::sweep_id(env, state, destination);
+        state.set_last_sweep_id(&sweep_id);
+        sweep_id
+    }
+
+    fn sweep_preimage(env: &Env, state: &AccountState, destination: &Address) -> Bytes {
+        let mut message = env.current_contract_address().to_xdr(env);
+        message.append(&destination.clone().to_xdr(env));
+        message.extend_from_array(&state.get_sweep_nonce().to_be_bytes());
+        message.extend_from_array(&state.get_expiry_ledger().to_be_bytes());
+        message
+    }
+
+    /// Reclaim what is available of the reserve to `destination`.
+    ///
+    /// A `keeper` other than the destination, closing the account through
+    /// `expire_as` or `reclaim_reserve_as`, is paid its bounty, in basis
+    /// points, out of the reclaimed amount.  The bounty can only be paid
+    /// from a reserve the account holds in XLM; on any other reserve the
+    /// keeper still closes the account, and is paid and recorded a zero
+    /// bounty.
+    fn reclaim_reserve_to(
+        env: &Env,
+        destination: &Address,
+        sweep_id: BytesN<32>,
+        keeper: Option<(&Address, u32)>,
+    ) -> Result<i128, Error> {
+        let state = AccountState::new(env);
+        let reserve_remaining = state.get_base_reserve_remaining();
+        let reserve_available = state.get_available_reserve();
+
+        if reserve_remaining < 0 || reserve_available < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        if reserve_remaining == 0 {
+            state.set_reserve_reclaimed(true);
+            let event = ReserveReclaimed {
+                destination: destination.clone(),
+                amount: 0,
+                sweep_id,
+                fully_reclaimed: true,
+                remaining_reserve: 0,
+                keeper: None,
+                keeper_bounty: 0,
+            };
+            Self::emit_and_store_reserve_event(env, event)?;
+            return Ok(0);
+        }
+
+        let reclaim_amount = if reserve_available < reserve_remaining {
+            reserve_available
+        } else {
+            reserve_remaining
+        };
+
+        // The bounty is owed on the reclaimed reserve wherever it is held
+        let keeper = keeper.filter(|(keeper, _)| *keeper != destination);
+        let keeper_bounty = match keeper {
+            Some((_, bounty_bps)) => {
+                units::bps_of(reclaim_amount, bounty_bps).ok_or(Error::InvalidAmount)?
+            }
+            None => 0,
+        };
+        let keeper = keeper
+            .filter(|_| keeper_bounty > 0)
+            .map(|(keeper, _)| keeper.clone());
+
+        let new_available = reserve_available
+            .checked_sub(reclaim_amount)
+            .ok_or(Error::InvalidAmount)?;
+        let new_remaining = reserve_remaining
+            .checked_sub(reclaim_amount)
+            .ok_or(Error::InvalidAmount)?;
+
+        state.set_available_reserve(new_available);
+        state.set_base_reserve_remaining(new_remaining);
+        state.set_reserve_reclaimed(new_remaining == 0);
+
+        // A reserve held in XLM next to native payments leaves with them,
+        // bounty included; any other reserve, and the bounty owed on it,
+        // is settled off-chain from the event
+        if state.is_native_reserve_held() && reclaim_amount > 0 {
+            let native = token::TokenClient::new(env, &Self::native_asset(env, &state));
+            let this = env.current_contract_address();
+            native.transfer(&this, destination, &(reclaim_amount - keeper_bounty));
+            if let Some(keeper) = &keeper {
+                native.transfer(&this, keeper, &keeper_bounty);
+            }
+        }
+
+        let event = ReserveReclaimed {
+            destination: destination.clone(),
+            amount: reclaim_amount,
+            sweep_id,
+            fully_reclaimed: new_remaining == 0,
+            remaining_reserve: new_remaining,
+            keeper,
+            keeper_bounty,
+        };
+        Self::emit_and_store_reserve_event(env, event)?;
+
+        Ok(reclaim_amount)
+    }
+
+    fn emit_and_store_reserve_event(env: &Env, event: ReserveReclaimed) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        events::emit_reserve_reclaimed(env, event.clone());
+
+        let mut summary = state.get_reserve_summary();
+        state.set_reserve_event(summary.event_count, &event);
+        summary.event_count = summary
+            .event_count
+            .checked_add(1)
+            .ok_or(Error::InvalidAmount)?;
+        if event.amount > 0 {
+            summary.total_reclaimed = summary
+                .total_reclaimed
+                .checked_add(event.amount)
+                .ok_or(Error::InvalidAmount)?;
+            summary.reclaim_count = summary
+                .reclaim_count
+                .checked_add(1)
+                .ok_or(Error::InvalidAmount)?;
+        }
+        state.set_reserve_summary(&summary);
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "config")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Initialize the ephemeral account with optional settings
+    ///
+    /// Behaves like `initialize`, with the optional features selected by
+    /// `config` enabled for the lifetime of the account.
+    ///
+    /// # Errors
+    /// Returns Error::AlreadyInitialized if called more than once
+    /// Returns Error::InvalidExpiry if `expiry_ledger` is not in the future
+    pub fn initialize_with_config(
+        env: Env,
+        creator: Address,
+        expiry_ledger: u32,
+        recovery_address: Address,
+        config: AccountConfig,
+    ) -> Result<(), Error> {
+        let result =
+            Self::apply_initialize(&env, &creator, expiry_ledger, &recovery_address, &config);
+        #[cfg(feature = "diagnostics")]
+        if let Err(error) = result {
+            // The flag is not stored yet, so honour the one being requested.
+            if config.debug_events {
+                events::emit_failure_diagnostic(
+                    &env,
+                    Symbol::new(&env, "initialize"),
+                    error as u32,
+                    vec![&env, creator.into_val(&env), expiry_ledger.into_val(&env)],
+                );
+            }
+        }
+        result
+    }
+
+    /// Merchant, reference and tag the account was initialized with
+    pub fn get_metadata(env: Env) -> Option<AccountMetadata> {
+        AccountState::new(&env).get_metadata()
+    }
+
+    /// Counterpart leg recorded at initialization, if any
+    pub fn get_external_chain_ref(env: Env) -> Option<ExternalChainRef> {
+        AccountState::new(&env)
+            .get_external_chain_ref()
+            .into_option()
+    }
+
+    /// Only destination the account may be swept to, if locked at initialization
+    pub fn get_locked_destination(env: Env) -> Option<Address> {
+        AccountState::new(&env).get_locked_destination()
+    }
+
+    /// Configuration epoch of the `ReserveContract` the account was
+    /// initialized against, or None without a reserve contract
+    ///
+    /// The reserve and keeper bounty were read together from that epoch's
+    /// snapshot.  Not to be confused with `get_epoch`, which counts resets.
+    pub fn get_reserve_config_epoch(env: Env) -> Option<u32> {
+        AccountState::new(&env).get_reserve_config_epoch()
+    }
+}
+
+#[cfg(feature = "upgrade")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Replace this account's code with the uploaded wasm `new_wasm_hash`
+    ///
+    /// Must be authorized by the creator.  The new code takes over from the
+    /// next call, whose storage migration brings the account's layout up to
+    /// the new version.  Allowed while the kill switch halts accounts, so a
+    /// fix can be rolled out during an incident.
+    ///
+    /// # Errors
+    /// Returns Error::NotInitialized if the account is not initialized
+    /// An unknown wasm hash aborts the invocation
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(&env, &state)?;
+
+        state.get_creator().require_auth();
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        events::emit_contract_upgraded(&env, new_wasm_hash, WASM_VERSION);
+
+        Ok(())
+    }
+
+    /// Bring the account's storage up to the layout of the running code
+    ///
+    /// State-changing calls migrate on their own; calling this after an
+    /// `upgrade` makes the getters usable again straight away.  Anyone may
+    /// call it, and it is a no-op on a current account.
+    ///
+    /// # Errors
+    /// Returns Error::NotInitialized if the account is not initialized
+    /// Returns Error::StorageVersionMismatch if the storage was written by
+    /// newer code
+    pub fn migrate(env: Env) -> Result<(), Error> {
+        Self::check_storage_version(&env, &AccountState::new(&env))
+    }
+
+    /// Version of the running code and the contract kind (`account`)
+    pub fn get_version(_env: Env) -> (u32, Symbol) {
+        (WASM_VERSION, CONTRACT_NAME)
+    }
+
+    /// Storage layout version the account was written in (`0` before
+    /// versioning)
+    pub fn get_storage_version(env: Env) -> u32 {
+        AccountState::new(&env).get_storage_version()
+    }
+}
+
+#[cfg(feature = "attribution")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Record an inbound payment together with a payer identity hint
+    ///
+    /// `payer_info_hash` is the hash of the off-chain KYC payload held by
+    /// the PSP.  It is stored on the payment and emitted with the payment
+    /// event, so operators can prove which payments carried an identity
+    /// attestation without putting PII on-chain.
+    ///
+    /// # Errors
+    /// Same as `record_payment`
+    pub fn record_payment_with_payer_info(
+        env: Env,
+        amount: i128,
+        asset: Address,
+        payer_info_hash: Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        let result =
+            Self::apply_record_payment(&env, amount, asset.clone(), payer_info_hash, None, None);
+        Self::report_failure(&env, "record_payment", &result, || {
+            vec![&env, amount.into_val(&env), asset.into_val(&env)]
+        });
+        result
+    }
+
+    /// Record an inbound payment together with the address it came from
+    ///
+    /// The sender is stored on the payment so it can later be returned
+    /// with `refund_payment`.  A top-up keeps the first recorded sender on
+    /// the payment and tracks what each sender paid, so a refund returns
+    /// every sender their own share.
+    ///
+    /// # Errors
+    /// Same as `record_payment`
+    pub fn record_payment_from(
+        env: Env,
+        amount: i128,
+        asset: Address,
+        sender: Address,
+        payer_info_hash: Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        let result = Self::apply_record_payment(
+            &env,
+            amount,
+            asset.clone(),
+            payer_info_hash,
+            Some(sender.clone()),
+            None,
+        );
+        Self::report_failure(&env, "record_payment", &result, || {
+            vec![
+                &env,
+                amount.into_val(&env),
+                asset.into_val(&env),
+                sender.into_val(&env),
+            ]
+        });
+        result
+    }
+
+    /// Record an inbound payment with its sender and an order reference
+    ///
+    /// `memo` is an opaque 32-byte reference, such as a hashed invoice id
+    /// or the transaction's hash memo.  Sender and memo are stored on the
+    /// payment and emitted with the payment event, so indexers can match
+    /// payments to orders.  A top-up keeps the first recorded memo.
+    ///
+    /// # Errors
+    /// Same as `record_payment`
+    pub fn record_payment_with_memo(
+        env: Env,
+        amount: i128,
+        asset: Address,
+        from: Address,
+        memo: Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        let result =
+            Self::apply_record_payment(&env, amount, asset.clone(), None, Some(from.clone()), memo);
+        Self::report_failure(&env, "record_payment", &result, || {
+            vec![
+                &env,
+                amount.into_val(&env),
+                asset.into_val(&env),
+                from.into_val(&env),
+            ]
+        });
+        result
+    }
+}
+
+#[cfg(feature = "batch")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Record payments in several assets in one call
+    ///
+    /// For checkouts that take more than one token at once.  The whole
+    /// batch is validated first, against the same rules as
+    /// `record_payment`, and then recorded together with a single
+    /// `MultiPaymentReceived` event listing every entry.  Requires the
+    /// creator's authorization.  Each asset must be new to the account;
+    /// top-ups go through `record_payment`.
+    ///
+    /// # Errors
+    /// Same as `record_payment`, for any entry
+    /// Returns Error::InvalidAmount if `payments` is empty
+    /// Returns Error::DuplicateAsset if an asset repeats in the batch or
+    /// already has a payment
+    /// Returns Error::TooManyPayments if the batch would exceed the payment
+    /// limit
+    pub fn record_payments(env: Env, payments: Vec<(Address, i128)>) -> Result<(), Error> {
+        let result = Self::apply_record_payments(&env, payments.clone());
+        Self::report_failure(&env, "record_payments", &result, || {
+            vec![&env, payments.into_val(&env)]
+        });
+        result
+    }
+}
+
+#[cfg(feature = "native")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Record an inbound payment of native XLM
+    ///
+    /// The account's XLM balance also holds its base reserve, so the
+    /// payment must be covered by the balance on top of the tracked
+    /// reserve and any XLM already recorded.  Once a native payment is
+    /// recorded, reclaiming the reserve pays it out in XLM.
+    ///
+    /// # Errors
+    /// Same as `record_payment`
+    /// Returns Error::InsufficientNativeBalance if the XLM balance does not
+    /// cover the reserve and every recorded XLM payment
+    pub fn record_native_payment(
+        env: Env,
+        amount: i128,
+        sender: Option<Address>,
+    ) -> Result<(), Error> {
+        let asset = Self::native_asset(&env, &AccountState::new(&env));
+        let result = Self::apply_record_payment(&env, amount, asset, None, sender, None);
+        Self::report_failure(&env, "record_payment", &result, || {
+            vec![&env, amount.into_val(&env)]
+        });
+        result
+    }
+}
+
+#[cfg(feature = "auth")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Sweep to `destination`, authorized through the host auth framework
+    ///
+    /// Requires authorization of the account's own address, which the host
+    /// checks by calling `__check_auth` with the sweep signers' signatures
+    /// over the standard Soroban authorization payload.  The payload binds
+    /// the destination and carries the host's replay protection, and the
+    /// authorization shows up in the transaction footprint.  Otherwise
+    /// behaves like `sweep`.
+    ///
+    /// # Errors
+    /// Returns Error::AlreadySwept if sweep already executed
+    /// Returns Error::DestinationMismatch if the account is locked to
+    /// another destination
+    /// Returns Error::DestinationCannotReceive if `destination` cannot
+    /// hold one of the assets
+    /// Returns Error::BelowMinSweepValue if the payments are worth less
+    /// than `min_sweep_value`
+    /// A failed authorization aborts the invocation
+    pub fn sweep_authorized(env: Env, destination: Address) -> Result<(), Error> {
+        let result = Self::apply_sweep_authorized(&env, &destination);
+        Self::report_failure(&env, "sweep_authorized", &result, || {
+            vec![&env, destination.into_val(&env)]
+        });
+        result
+    }
+}
+
+#[cfg(feature = "keeper")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// `expire` on behalf of `caller`, who must authorize the call
+    ///
+    /// Under `AuthPolicy::Restricted` the caller must be the creator, the
+    /// recovery address or the keeper.
+    ///
+    /// # Errors
+    /// Same as `expire`
+    /// Returns Error::Unauthorized if the account is restricted and
+    /// `caller` is none of those
+    pub fn expire_as(env: Env, caller: Address) -> Result<(), Error> {
+        let result = Self::apply_expire(&env, Some(&caller));
+        Self::report_failure(&env, "expire", &result, || {
+            vec![&env, caller.into_val(&env)]
+        });
+        result
+    }
+
+    /// Basis points of the reclaimed reserve `expire_as` would pay a keeper
+    /// at the current ledger, escalation included
+    pub fn get_expiry_bounty_bps(env: Env) -> u32 {
+        Self::expiry_bounty_bps(&env, &AccountState::new(&env))
+    }
+
+    /// `reclaim_reserve` on behalf of `caller`, who must authorize the call
+    ///
+    /// # Errors
+    /// Returns Error::Unauthorized if the account is restricted and
+    /// `caller` is not its creator, recovery address or keeper
+    pub fn reclaim_reserve_as(env: Env, caller: Address) -> Result<i128, Error> {
+        let result = Self::apply_reclaim_reserve(&env, Some(&caller));
+        Self::report_failure(&env, "reclaim_reserve", &result, || {
+            vec![&env, caller.into_val(&env)]
+        });
+        result
+    }
+}
+
+#[cfg(feature = "lifecycle")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Close the account before expiry.
+    ///
+    /// Requires creator authorization.  `reason` is recorded in the
+    /// `AccountCancelled` event; `Timeout` is reserved for [`Self::expire`].
+    ///
+    /// Only an account nobody has paid yet can be cancelled: it becomes
+    /// `Cancelled` and the base reserve returns to the creator.  A paid
+    /// account is refused and settles through `sweep` or `expire`.
+    ///
+    /// # Errors
+    /// Returns Error::InvalidReason if `reason` is `Timeout`
+    /// Returns Error::InvalidStatus if the account is already closed,
+    /// awaiting an escrow release, or has received a payment
+    pub fn cancel(env: Env, reason: CloseReason) -> Result<(), Error> {
+        let result = Self::apply_cancel(&env, reason);
+        Self::report_failure(&env, "cancel", &result, || {
+            vec![&env, (reason as u32).into()]
+        });
+        result
+    }
+
+    /// Give the payer more time by moving the expiry ledger later
+    ///
+    /// Must be authorized by the creator.  Sweep messages bind the expiry
+    /// ledger, so signatures made before the extension no longer verify.
+    ///
+    /// # Errors
+    /// Returns Error::InvalidStatus if the account was already swept or expired
+    /// Returns Error::AccountExpired if the current expiry ledger has passed
+    /// Returns Error::InvalidExpiry if `new_expiry_ledger` is not later than
+    /// the current expiry ledger
+    pub fn extend_expiry(env: Env, new_expiry_ledger: u32) -> Result<(), Error> {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(&env, &state)?;
+        Self::check_not_halted(&env, &state)?;
+
+        state.get_creator().require_auth();
+
+        let status = state.get_status();
+        if status != AccountStatus::Active && status != AccountStatus::PaymentReceived {
+            return Err(Error::InvalidStatus);
+        }
+        if Self::is_expired(env.clone()) {
+            return Err(Error::AccountExpired);
+        }
+
+        let old_expiry_ledger = state.get_expiry_ledger();
+        if new_expiry_ledger <= old_expiry_ledger {
+            return Err(Error::InvalidExpiry);
+        }
+
+        state.set_expiry_ledger(new_expiry_ledger);
+        state.extend_ttl_past(new_expiry_ledger);
+        events::emit_expiry_extended(&env, old_expiry_ledger, new_expiry_ledger);
+
+        Ok(())
+    }
+
+    /// Ledgers left until `expiry_ledger`, negative once it has passed
+    ///
+    /// `0` for an uninitialized account.
+    pub fn ledgers_until_expiry(env: Env) -> i64 {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
+            return 0;
+        }
+        state.get_expiry_ledger() as i64 - env.ledger().sequence() as i64
+    }
+
+    /// Whether `expire` would currently go through
+    pub fn can_expire(env: Env) -> bool {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() || state.get_storage_version() > STORAGE_VERSION {
+            return false;
+        }
+        let status = state.get_status();
+        !status.is_closed()
+            && status != AccountStatus::PendingRelease
+            && Self::is_past_grace(&env, &state)
+            && Self::check_not_halted(&env, &state).is_ok()
+    }
+
+    /// Expiry ledger, grace period and which of the sweep and `expire`
+    /// windows the account is in
+    pub fn get_lifecycle(env: Env) -> Result<Lifecycle, Error> {
+        let state = AccountState::new(&env);
+        Self::check_storage_version(&env, &state)?;
+
+        let grace_end_ledger = Self::grace_end_ledger(&state);
+        Ok(Lifecycle {
+            status: state.get_status(),
+            expiry_ledger: state.get_expiry_ledger(),
+            grace_ledgers: state.get_grace_ledgers(),
+            grace_end_ledger,
+            in_grace: Self::is_expired(env.clone()) && env.ledger().sequence() < grace_end_ledger,
+        })
+    }
+}
+
+#[cfg(feature = "history")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Most recent reserve reclaim events (oldest first), bounded in size.
+    pub fn get_recent_reserve_events(env: Env) -> Vec<ReserveReclaimed> {
+        match Self::get_reserve_events(env.clone(), None, RESERVE_EVENT_HISTORY) {
+            Ok(page) => page.items,
+            Err(_) => Vec::new(&env),
+        }
+    }
+
+    /// Up to `limit` retained reserve reclaim events, oldest first, from
+    /// `cursor` on.
+    ///
+    /// Pass `None` to start from the oldest retained event and the returned
+    /// `next_cursor` to continue.  The last `RESERVE_EVENT_HISTORY` events
+    /// are retained, so an indexer that falls behind by fewer reclaims than
+    /// that loses none of them; a cursor to an event since dropped resumes
+    /// at the oldest one retained.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidCursor`] – `cursor` was not issued by this contract.
+    pub fn get_reserve_events(
+        env: Env,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<ReserveEventPage, Error> {
+        let state = AccountState::new(&env);
+        let count = if state.is_initialized() {
+            state.get_reserve_summary().event_count
+        } else {
+            0
+        };
+
+        // Cursors carry event numbers, so they stay valid as the ring
+        // buffer rotates
+        let first = count - count.min(RESERVE_EVENT_HISTORY);
+        let (positions, next_cursor) =
+            page_bounds(&env, cursor, limit, first..count).ok_or(Error::InvalidCursor)?;
+        let mut items = Vec::new(&env);
+        for number in positions {
+            if let Some(event) = state.get_reserve_event(number) {
+                items.push_back(event);
+            }
+        }
+        Ok(ReserveEventPage { items, next_cursor })
+    }
+
+    /// Cumulative reserve reclaim totals.
+    pub fn get_reserve_reclaim_summary(env: Env) -> ReserveReclaimSummary {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
+            return ReserveReclaimSummary::default();
+        }
+
+        state.get_reserve_summary()
+    }
+}
+
+#[cfg(feature = "views")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Outstanding payment assets `destination` cannot receive
+    ///
+    /// An asset is listed when reading the destination's balance fails,
+    /// as it does for a classic account without a trustline, or when its
+    /// Stellar Asset Contract reports the destination deauthorized.  Sweeps
+    /// fail with Error::DestinationCannotReceive while any asset they
+    /// would move is listed, before any transfer is attempted.
+    pub fn get_unreceivable_assets(env: Env, destination: Address) -> Vec<Address> {
+        let state = AccountState::new(&env);
+        let mut assets = Vec::new(&env);
+        for asset in Self::outstanding_payments(&env, &state).keys() {
+            if !Self::can_receive(&env, &asset, &destination) {
+                assets.push_back(asset);
+            }
+        }
+        assets
+    }
+
+    /// Whether `asset` is native XLM or another token contract.
+    pub fn get_asset_kind(env: Env, asset: Address) -> AssetKind {
+        Self::asset_kind(&env, &AccountState::new(&env), &asset)
+    }
+
+    /// Signature scheme that authorizes sweeps
+    pub fn get_signer_kind(env: Env) -> SignerKind {
+        AccountState::new(&env).get_signer_kind()
+    }
+
+    /// Running SHA-256 commitment over every recorded payment
+    ///
+    /// Starts as 32 zero bytes; each `record_payment` replaces it with
+    /// `sha256(previous_root || xdr(payment))`.  Auditors replay an
+    /// off-chain payment list in recording order and compare the result
+    /// with this value to verify it matches on-chain history.
+    pub fn get_payments_root(env: Env) -> BytesN<32> {
+        AccountState::new(&env).get_payments_root()
+    }
+
+    /// Trustlines the account holds: the count it was opened with, raised
+    /// once it has been paid in more tokens than that
+    ///
+    /// The reserve tracked since initialization covers the trustlines it
+    /// was opened with; a higher count means the account outgrew it.
+    pub fn get_trustline_count(env: Env) -> u32 {
+        AccountState::new(&env).get_trustline_count()
+    }
+
+    /// Id of the sweep or closure that settled the account, if any
+    ///
+    /// sha256(contract_id || destination || sweep nonce || epoch), with
+    /// the nonce and epoch as big-endian bytes, also carried in the sweep
+    /// and reserve events, so off-chain reconciliation has a key that does
+    /// not depend on the ledger the settlement landed in.
+    pub fn get_sweep_id(env: Env) -> Option<BytesN<32>> {
+        AccountState::new(&env).get_last_sweep_id()
+    }
+
+    /// Number of times the account was `reset`, carried by its events
+    pub fn get_epoch(env: Env) -> u32 {
+        AccountState::new(&env).get_epoch()
+    }
+
+    /// Sequence number of the latest state-changing call
+    ///
+    /// Each state-changing call takes the next number, and every event it
+    /// publishes carries that number as the topic after the version.  An
+    /// indexer that sees a gap, or a higher number here than its last
+    /// event, missed a change and should resync from `get_info`.
+    pub fn get_state_seq(env: Env) -> u64 {
+        AccountState::new(&env).get_state_seq()
+    }
+
+    /// Up to `limit` recorded payments, in recording order, from `cursor` on
+    ///
+    /// Lets indexers page through an account without fetching every
+    /// payment through `get_info`.  Pass `None` to start from the first
+    /// payment and the returned `next_cursor` to continue.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidCursor`] – `cursor` was not issued by this contract.
+    pub fn get_payments(
+        env: Env,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<PaymentPage, Error> {
+        let state = Self::payments_view(&env);
+        let assets = state.get_payment_assets();
+        let (positions, next_cursor) =
+            page_bounds(&env, cursor, limit, 0..assets.len()).ok_or(Error::InvalidCursor)?;
+        // Only the page's entries are read
+        let mut items = Vec::new(&env);
+        for asset in assets.slice(positions).iter() {
+            if let Some(payment) = state.get_payment(&asset) {
+                items.push_back(payment);
+            }
+        }
+        Ok(PaymentPage { items, next_cursor })
+    }
+
+    /// Payment recorded for `asset`, if any
+    pub fn get_payment_by_asset(env: Env, asset: Address) -> Option<Payment> {
+        Self::payments_view(&env).get_payment(&asset)
+    }
+
+    /// Number of recorded payments, without loading them
+    pub fn get_payment_count(env: Env) -> u32 {
+        Self::payments_view(&env).get_total_payments()
+    }
+
+    /// Running total recorded for `asset`; `0` if none was
+    pub fn get_payment_total(env: Env, asset: Address) -> i128 {
+        Self::payments_view(&env).get_payment_total(&asset)
+    }
+
+    /// Optional features enabled on this account
+    ///
+    /// # Errors
+    /// Returns Error::NotInitialized if the account is not initialized
+    pub fn get_capabilities(env: Env) -> Result<Capabilities, Error> {
+        let state = AccountState::new(&env);
+        Self::check_storage_version(&env, &state)?;
+
+        let signer_kind = state.get_signer_kind();
+        let limits = state.get_payment_limits(MAX_PAYMENT_ASSETS);
+        Ok(Capabilities {
+            wasm_version: state.get_wasm_version(),
+            multisig: signer_kind == SignerKind::Ed25519 && state.get_sweep_signers().len() > 1,
+            passkey: signer_kind == SignerKind::Secp256r1,
+            fees: state.get_fee_collector().is_some(),
+            allowlist: limits.allowed_assets.is_some(),
+            escrow: state.get_escrow_arbiter().is_some(),
+            locked_destination: state.get_locked_destination().is_some(),
+            batch_window: state.get_batch_min_amount() > 0,
+            accumulate_payments: state.is_accumulate_payments(),
+            kill_switch: state.get_kill_switch().is_some(),
+            min_sweep_value: state.get_min_sweep_value() > 0,
+            dust_sweep: state.get_dust_threshold().is_some(),
+            depeg_guard: state.get_depeg_guard().is_some(),
+            emergency_recovery: state.get_emergency_ledger().is_some(),
+            restricted_auth: state.get_auth_policy() == AuthPolicy::Restricted,
+            settlement_hook: state.get_settlement_hook().is_some(),
+            reusable: state.get_factory().is_some(),
+            quarantine: state.is_quarantine_mode(),
+        })
+    }
+
+    /// Name of the error raised as `Error(Contract, #code)`, or `Unknown`
+    /// for a code this contract never raises
+    pub fn describe_error(env: Env, code: u32) -> Symbol {
+        let name = Error::try_from(soroban_sdk::Error::from_contract_error(code))
+            .map_or(UNKNOWN_ERROR, Error::name);
+        Symbol::new(&env, name)
+    }
+
+    /// Whether a `sweep` would currently go through
+    ///
+    /// Runs the checks `sweep` makes before its destination and
+    /// signatures, in the same order, and returns the error it would fail
+    /// with.  A configured sweep signer is required, as by `sweep`.
+    pub fn can_sweep(env: Env) -> Result<(), Error> {
+        let state = AccountState::new(&env);
+        if state.get_storage_version() > STORAGE_VERSION {
+            return Err(Error::StorageVersionMismatch);
+        }
+        Self::check_sweepable(&env, &state)?;
+        Self::check_not_halted(&env, &state)?;
+        Self::check_not_paused(&env, &state)?;
+        Self::check_compliance(&env, &state)?;
+        let outstanding = Self::outstanding_payments(&env, &state).values();
+        Self::check_sweep_value(&env, &state, &outstanding)?;
+        Self::check_sweep_signer(&state)
+    }
+}
+
+#[cfg(feature = "fees")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Protocol fee collector and fee in basis points, if a fee is configured
+    pub fn get_fee(env: Env) -> Option<(Address, u32)> {
+        let state = AccountState::new(&env);
+        state
+            .get_fee_collector()
+            .map(|collector| (collector, state.get_fee_bps()))
+    }
+}
+
+#[cfg(feature = "multisig")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Require `threshold` of `signers` to authorize sweeps (M-of-N)
+    ///
+    /// Must be authorized by the creator; replaces any passkey, and can be
+    /// changed until the account is swept or expired.  Slot `i` of every
+    /// `auth_signatures` argument
+    /// then holds the signature of `signers[i]`, or 64 zero bytes when that
+    /// signer did not sign.
+    ///
+    /// # Errors
+    /// Returns Error::InvalidSignerSet if `signers` is empty, longer than
+    /// ten, repeats a key, or `threshold` is zero or above its length
+    /// Returns Error::InvalidStatus if the account was already swept or expired
+    pub fn set_sweep_signers(
+        env: Env,
+        signers: Vec<BytesN<32>>,
+        threshold: u32,
+    ) -> Result<(), Error> {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(&env, &state)?;
+        Self::check_not_halted(&env, &state)?;
+
+        state.get_creator().require_auth();
+
+        let status = state.get_status();
+        if status.is_closed() {
+            return Err(Error::InvalidStatus);
+        }
+
+        Self::check_signer_set(&signers, threshold)?;
+        state.set_sweep_signers(&signers, threshold);
+        state.set_signer_kind(SignerKind::Ed25519);
+        events::emit_sweep_signers_set(&env, signers, threshold);
+
+        Ok(())
+    }
+
+    /// Public keys of the sweep signer set, in signature slot order
+    pub fn get_sweep_signers(env: Env) -> Vec<BytesN<32>> {
+        AccountState::new(&env).get_sweep_signers()
+    }
+
+    /// Number of sweep signers that must sign
+    pub fn get_sweep_threshold(env: Env) -> u32 {
+        AccountState::new(&env).get_sweep_threshold()
+    }
+}
+
+#[cfg(feature = "passkey")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// secp256r1 key authorizing sweeps, if the account uses a passkey
+    pub fn get_passkey_signer(env: Env) -> Option<BytesN<65>> {
+        let state = AccountState::new(&env);
+        match state.get_signer_kind() {
+            SignerKind::Secp256r1 => state.get_passkey_signer(),
+            SignerKind::Ed25519 => None,
+        }
+    }
+}
+
+#[cfg(feature = "oracle")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Sweep to `destination` even if the payments are worth less than
+    /// `min_sweep_value`
+    ///
+    /// For operators who decide a dust account is worth clearing anyway.
+    /// Takes the same signatures as `sweep`, over
+    /// `get_sweep_message(destination)`, and otherwise behaves like it.
+    ///
+    /// # Errors
+    /// Same as `sweep`, except Error::BelowMinSweepValue
+    pub fn sweep_forced(
+        env: Env,
+        destination: Address,
+        auth_signatures: Vec<BytesN<64>>,
+    ) -> Result<(), Error> {
+        let result = Self::apply_sweep(&env, &destination, &auth_signatures, true);
+        Self::report_failure(&env, "sweep_forced", &result, || {
+            vec![&env, destination.into_val(&env)]
+        });
+        result
+    }
+
+    /// Value of the outstanding payments, priced by the account's oracle
+    ///
+    /// Denominated in the oracle's quote asset and scaled like its prices.
+    /// Assets the oracle does not quote count as worthless.  `None` without
+    /// a price oracle.
+    pub fn get_sweep_value(env: Env) -> Option<i128> {
+        let state = AccountState::new(&env);
+        Self::sweep_value(
+            &env,
+            &state,
+            &Self::outstanding_payments(&env, &state).values(),
+        )
+    }
+
+    /// Whether the outstanding payments are worth at least
+    /// `min_sweep_value`
+    ///
+    /// Always `true` without a minimum.  A `false` here is what makes
+    /// `sweep` fail with Error::BelowMinSweepValue.
+    pub fn is_sweep_worthwhile(env: Env) -> bool {
+        let state = AccountState::new(&env);
+        let outstanding = Self::outstanding_payments(&env, &state).values();
+        Self::check_sweep_value(&env, &state, &outstanding).is_ok()
+    }
+}
+
+#[cfg(feature = "escrow")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Release a held sweep to its destination once the hold has elapsed
+    ///
+    /// Anyone may call this; the destination was fixed by the sweep
+    /// signature.  Transfers the funds exactly like a plain `sweep`.
+    ///
+    /// # Errors
+    /// Returns Error::InvalidStatus if no sweep is pending release
+    /// Returns Error::HoldNotElapsed before `release_after_ledger`
+    pub fn finalize_sweep(env: Env) -> Result<(), Error> {
+        let result = Self::apply_finalize_sweep(&env);
+        Self::report_failure(&env, "finalize_sweep", &result, || Vec::new(&env));
+        result
+    }
+
+    /// Redirect a held sweep to the recovery address
+    ///
+    /// Must be authorized by the escrow arbiter, before the hold elapses.
+    /// The account ends `Swept` with the recovery address as destination.
+    ///
+    /// # Errors
+    /// Returns Error::InvalidStatus if no sweep is pending release
+    /// Returns Error::HoldElapsed once `release_after_ledger` is reached
+    pub fn dispute(env: Env) -> Result<(), Error> {
+        let result = Self::apply_dispute(&env);
+        Self::report_failure(&env, "dispute", &result, || Vec::new(&env));
+        result
+    }
+
+    /// Sweep waiting out its hold, if any
+    pub fn get_pending_release(env: Env) -> Option<PendingRelease> {
+        AccountState::new(&env).get_pending_release()
+    }
+}
+
+#[cfg(feature = "prepared")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Commit to a sweep destination without revealing it
+    ///
+    /// `commitment` is sha256(destination || salt), the destination
+    /// XDR-encoded and the salt 32 random bytes kept off-chain until
+    /// `execute_prepared_sweep`.  `auth_signatures` are the sweep signers'
+    /// signatures over `get_prepare_message(commitment)`, which consumes
+    /// the sweep nonce.  The commitment can be executed for
+    /// `PREPARED_SWEEP_LEDGERS` ledgers; preparing again replaces it.
+    ///
+    /// # Errors
+    /// Returns Error::Unauthorized if no sweep signer is configured
+    /// Returns Error::AlreadySwept if sweep already executed
+    /// An invalid signature aborts the invocation
+    pub fn prepare_sweep(
+        env: Env,
+        commitment: BytesN<32>,
+        auth_signatures: Vec<BytesN<64>>,
+    ) -> Result<(), Error> {
+        let state = AccountState::new(&env);
+        Self::migrate_if_needed(&env, &state)?;
+        Self::check_sweepable(&env, &state)?;
+        Self::check_not_halted(&env, &state)?;
+
+        let message = Self::prepared_message(&env, &state, b"prepare", &commitment);
+        Self::verify_sweep_authorization(&env, &state, message, &auth_signatures)?;
+        state.increment_sweep_nonce();
+
+        let expires_ledger = env
+            .ledger()
+            .sequence()
+            .saturating_add(PREPARED_SWEEP_LEDGERS);
+        state.set_prepared_sweep(&PreparedSweep {
+            commitment: commitment.clone(),
+            expires_ledger,
+        });
+        events::emit_sweep_prepared(&env, commitment, expires_ledger);
+
+        Ok(())
+    }
+
+    /// Reveal a prepared sweep's destination and sweep to it
+    ///
+    /// Callable by anyone; the sweep signers authorized the commitment,
+    /// which fixes the destination.  Otherwise behaves as `sweep`.
+    ///
+    /// # Errors
+    /// Returns Error::NoPreparedSweep if no sweep is prepared, or it expired
+    /// Returns Error::CommitmentMismatch if `destination` and `salt` do not
+    /// hash to the commitment
+    /// Returns the errors of `sweep` other than its signature checks
+    pub fn execute_prepared_sweep(
+        env: Env,
+        destination: Address,
+        salt: BytesN<32>,
+    ) -> Result<(), Error> {
+        let result = Self::apply_execute_prepared_sweep(&env, &destination, &salt);
+        Self::report_failure(&env, "execute_prepared_sweep", &result, || {
+            vec![&env, destination.into_val(&env)]
+        });
+        result
+    }
+
+    /// Withdraw a prepared sweep before it is executed
+    ///
+    /// With no `auth_signatures` the creator must authorize; otherwise they
+    /// are the sweep signers' signatures over `get_cancel_message()`, which
+    /// consumes the sweep nonce.  A mistaken or compromised destination can
+    /// so be withdrawn without waiting for the commitment to expire.
+    ///
+    /// # Errors
+    /// Returns Error::NoPreparedSweep if no sweep is prepared
+    /// An invalid signature aborts the invocation
+    pub fn cancel_prepared_sweep(env: Env, auth_signatures: Vec<BytesN<64>>) -> Result<(), Error> {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(&env, &state)?;
+        Self::check_not_halted(&env, &state)?;
+
+        let prepared = state.get_prepared_sweep().ok_or(Error::NoPreparedSweep)?;
+        let by_creator = auth_signatures.is_empty();
+        if by_creator {
+            state.get_creator().require_auth();
+        } else {
+            let message = Self::prepared_message(&env, &state, b"cancel", &prepared.commitment);
+            Self::verify_sweep_authorization(&env, &state, message, &auth_signatures)?;
+            state.increment_sweep_nonce();
+        }
+
+        state.remove_prepared_sweep();
+        events::emit_prepared_sweep_cancelled(&env, prepared.commitment, by_creator);
+
+        Ok(())
+    }
+
+    /// Sweep committed to by `prepare_sweep` and not yet executed, if any
+    pub fn get_prepared_sweep(env: Env) -> Option<PreparedSweep> {
+        AccountState::new(&env).get_prepared_sweep()
+    }
+
+    /// Digest the sweep signer must sign to prepare a sweep to `commitment`
+    ///
+    /// # Errors
+    /// Returns Error::NotInitialized if the account is not initialized
+    pub fn get_prepare_message(env: Env, commitment: BytesN<32>) -> Result<BytesN<32>, Error> {
+        let state = AccountState::new(&env);
+        Self::check_storage_version(&env, &state)?;
+
+        Ok(Self::prepared_message(
+            &env,
+            &state,
+            b"prepare",
+            &commitment,
+        ))
+    }
+
+    /// Digest the sweep signer must sign to cancel the prepared sweep
+    ///
+    /// # Errors
+    /// Returns Error::NoPreparedSweep if no sweep is prepared
+    pub fn get_cancel_message(env: Env) -> Result<BytesN<32>, Error> {
+        let state = AccountState::new(&env);
+        Self::check_storage_version(&env, &state)?;
+        let prepared = state.get_prepared_sweep().ok_or(Error::NoPreparedSweep)?;
+
+        Ok(Self::prepared_message(
+            &env,
+            &state,
+            b"cancel",
+            &prepared.commitment,
+        ))
+    }
+}
+
+#[cfg(feature = "prepared")]
+impl EphemeralAccountContract {
+    fn apply_execute_prepared_sweep(
+        env: &Env,
+        destination: &Address,
+        salt: &BytesN<32>,
+    ) -> Result<(), Error> {
+        let state = AccountState::new(env);
+        Self::migrate_if_needed(env, &state)?;
+        let prepared = state.get_prepared_sweep().ok_or(Error::NoPreparedSweep)?;
+        if env.ledger().sequence() > prepared.expires_ledger {
+            return Err(Error::NoPreparedSweep);
+        }
+
+        let mut preimage = destination.clone().to_xdr(env);
+        preimage.append(&salt.clone().into());
+        if env.crypto().sha256(&preimage).to_bytes() != prepared.commitment {
+            return Err(Error::CommitmentMismatch);
+        }
+        Self::check_sweep_to(env, &state, destination, false)?;
+
+        state.remove_prepared_sweep();
+        Self::settle_sweep(env, &state, destination)
+    }
+
+    /// sha256(contract_id || action || commitment || nonce || expiry_ledger)
+    ///
+    /// `action` is the ASCII `prepare` or `cancel`, so neither signature
+    /// can be used as the other, nor as a `sweep` signature.
+    fn prepared_message(
+        env: &Env,
+        state: &AccountState,
+        action: &[u8],
+        commitment: &BytesN<32>,
+    ) -> BytesN<32> {
+        let mut message = env.current_contract_address().to_xdr(env);
+        message.append(&Bytes::from_slice(env, action));
+        message.append(&commitment.clone().into());
+        message.extend_from_array(&state.get_sweep_nonce().to_be_bytes());
+        message.extend_from_array(&state.get_expiry_ledger().to_be_bytes());
+        env.crypto().sha256(&message).to_bytes()
+    }
+}
+
+#[cfg(feature = "guardian")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Propose moving the locked destination to `new_destination`
+    ///
+    /// Must be authorized by both the creator and the guardian, so a single
+    /// compromised key cannot redirect settlement.  The change can be
+    /// applied once `destination_change_delay` ledgers have passed;
+    /// proposing again replaces the pending change and restarts the delay.
+    ///
+    /// # Errors
+    /// Returns Error::Unauthorized if the account has no locked destination
+    /// or no guardian
+    /// Returns Error::InvalidStatus if the account was already swept or
+    /// expired, or its sweep is held for release
+    pub fn propose_destination_change(env: Env, new_destination: Address) -> Result<(), Error> {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(&env, &state)?;
+        Self::check_not_halted(&env, &state)?;
+
+        if state.get_locked_destination().is_none() {
+            return Err(Error::Unauthorized);
+        }
+        let guardian = state.get_guardian().ok_or(Error::Unauthorized)?;
+        state.get_creator().require_auth();
+        guardian.require_auth();
+
+        let status = state.get_status();
+        if status.is_closed() || status == AccountStatus::PendingRelease {
+            return Err(Error::InvalidStatus);
+        }
+
+        let effective_ledger = env
+            .ledger()
+            .sequence()
+            .saturating_add(state.get_destination_change_delay());
+        state.set_pending_destination(&PendingDestination {
+            destination: new_destination.clone(),
+            effective_ledger,
+        });
+        events::emit_destination_change_proposed(&env, new_destination, effective_ledger);
+
+        Ok(())
+    }
+
+    /// Apply a proposed destination change once its timelock has passed
+    ///
+    /// Callable by anyone; the authorization happened at proposal time.
+    ///
+    /// # Errors
+    /// Returns Error::InvalidStatus if no change is pending, or the account
+    /// was already swept or expired, or its sweep is held for release
+    /// Returns Error::TimelockActive before the change's effective ledger
+    pub fn apply_destination_change(env: Env) -> Result<(), Error> {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(&env, &state)?;
+        Self::check_not_halted(&env, &state)?;
+
+        let status = state.get_status();
+        if status.is_closed() || status == AccountStatus::PendingRelease {
+            return Err(Error::InvalidStatus);
+        }
+        let pending = state
+            .get_pending_destination()
+            .ok_or(Error::InvalidStatus)?;
+        if env.ledger().sequence() < pending.effective_ledger {
+            return Err(Error::TimelockActive);
+        }
+        let old_destination = state.get_locked_destination().ok_or(Error::InvalidStatus)?;
+
+        state.set_locked_destination(&pending.destination);
+        state.remove_pending_destination();
+        events::emit_destination_changed(&env, old_destination, pending.destination);
+
+        Ok(())
+    }
+
+    /// Destination change waiting out its timelock, if any
+    pub fn get_pending_destination(env: Env) -> Option<PendingDestination> {
+        AccountState::new(&env).get_pending_destination()
+    }
+
+    /// Move every outstanding payment and the reserve to the recovery
+    /// address before expiry
+    ///
+    /// For when the creator and sweep keys are both lost.  Must be
+    /// authorized by the guardian, once `emergency_delay_ledgers` have
+    /// passed since initialization.  The account closes as
+    /// `EmergencyRecovered`.
+    ///
+    /// # Errors
+    /// Returns Error::Unauthorized if the account has no guardian allowed to
+    /// recover it
+    /// Returns Error::TimelockActive before the emergency delay has passed
+    /// Returns Error::InvalidStatus if the account is already closed or its
+    /// sweep is held for release
+    pub fn emergency_recover(env: Env) -> Result<(), Error> {
+        let result = Self::apply_emergency_recover(&env);
+        Self::report_failure(&env, "emergency_recover", &result, || Vec::new(&env));
+        result
+    }
+}
+
+#[cfg(feature = "dust")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Send the dust a sweep left out on to that sweep's destination
+    ///
+    /// Anyone may call this, since the destination was fixed by the
+    /// signed sweep; `SweepCoordinator::sweep_dust` does so for many
+    /// accounts at once.  The fee is taken as it would have been on sweep.
+    ///
+    /// # Errors
+    /// Returns Error::AssetNotFound if no dust is waiting
+    /// Returns Error::DestinationCannotReceive if a transfer fails in
+    /// quarantine mode
+    pub fn sweep_dust(env: Env) -> Result<(), Error> {
+        let result = Self::apply_sweep_dust(&env);
+        Self::report_failure(&env, "sweep_dust", &result, || Vec::new(&env));
+        result
+    }
+
+    /// Payments a sweep left out as dust, with the amounts it would have
+    /// sent
+    pub fn get_dust(env: Env) -> Vec<Payment> {
+        AccountState::new(&env).get_dust()
+    }
+}
+
+#[cfg(feature = "depeg")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Let sweeps settle guarded stablecoins off peg through `until_ledger`
+    ///
+    /// Must be authorized by the guardian, who judged the deviation
+    /// acceptable.  Overriding again replaces the window; an
+    /// `until_ledger` already past ends it.
+    ///
+    /// # Errors
+    /// Returns Error::Unauthorized if the account has no guardian
+    /// Returns Error::InvalidStatus if the account is already closed
+    pub fn override_depeg(env: Env, until_ledger: u32) -> Result<(), Error> {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(&env, &state)?;
+        Self::check_not_halted(&env, &state)?;
+
+        let guardian = state.get_guardian().ok_or(Error::Unauthorized)?;
+        guardian.require_auth();
+        if state.get_status().is_closed() {
+            return Err(Error::InvalidStatus);
+        }
+
+        state.set_depeg_override(until_ledger);
+        events::emit_depeg_overridden(&env, guardian, until_ledger);
+
+        Ok(())
+    }
+
+    /// Outstanding guarded stablecoins a sweep would refuse as off peg
+    pub fn get_depegged_assets(env: Env) -> Vec<Address> {
+        let state = AccountState::new(&env);
+        let assets = Self::outstanding_payments(&env, &state).keys();
+        Self::depegged_assets(&env, &state, &assets)
+    }
+}
+
+#[cfg(feature = "partial")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Sweep only the listed assets to `destination`
+    ///
+    /// Transfers the recorded payment of each listed asset and marks it
+    /// swept.  The account stays in `PaymentReceived` until every payment
+    /// is cleared; the call that clears the last one completes the sweep
+    /// (status `Swept`, reserve reclaimed to `destination`).  A later full
+    /// `sweep` only moves the assets that are still outstanding.
+    ///
+    /// `auth_signatures` are the sweep signers' ed25519 signatures over the
+    /// digest returned by `get_sweep_assets_message(destination, assets)`.
+    ///
+    /// # Errors
+    /// Returns Error::AssetNotFound if an asset has no outstanding payment
+    /// Returns Error::DuplicateAsset if an asset is listed twice
+    /// Returns Error::HoldRequired in escrow mode, where only `sweep` is
+    /// available
+    /// Returns Error::DestinationMismatch if the account is locked to
+    /// another destination
+    /// Returns Error::DestinationCannotReceive if `destination` cannot
+    /// hold one of the listed assets
+    /// Returns Error::BelowMinSweepValue if the outstanding payments are
+    /// worth less than `min_sweep_value`
+    pub fn sweep_assets(
+        env: Env,
+        destination: Address,
+        assets: Vec<Address>,
+        auth_signatures: Vec<BytesN<64>>,
+    ) -> Result<(), Error> {
+        let result = Self::apply_sweep_assets(&env, &destination, &assets, &auth_signatures);
+        Self::report_failure(&env, "sweep_assets", &result, || {
+            vec![&env, destination.into_val(&env), assets.into_val(&env)]
+        });
+        result
+    }
+
+    /// Sweep every outstanding payment, split between several destinations
+    ///
+    /// `destinations` pairs each recipient with its share in basis points;
+    /// the shares must add up to 10 000.  Each payment is divided
+    /// proportionally, rounding down, and the rounding dust goes to the
+    /// first destination, which also receives the reclaimed reserve.  One
+    /// `swept_spl` event is published per destination.
+    ///
+    /// `auth_signatures` are the sweep signers' ed25519 signatures over the
+    /// digest returned by `get_sweep_split_message(destinations)`.
+    ///
+    /// # Errors
+    /// Returns Error::InvalidShares if there are no destinations, more than
+    /// ten, a zero share, or shares that do not add up to 10 000
+    /// Returns Error::AlreadySwept if sweep already executed
+    /// Returns Error::HoldRequired in escrow mode
+    /// Returns Error::DestinationMismatch if the account is locked and any
+    /// destination differs from it
+    /// Returns Error::DestinationCannotReceive if a destination cannot
+    /// hold one of the assets
+    /// Returns Error::BelowMinSweepValue if the outstanding payments are
+    /// worth less than `min_sweep_value`
+    pub fn sweep_split(
+        env: Env,
+        destinations: Vec<(Address, u32)>,
+        auth_signatures: Vec<BytesN<64>>,
+    ) -> Result<(), Error> {
+        let result = Self::apply_sweep_split(&env, &destinations, &auth_signatures);
+        Self::report_failure(&env, "sweep_split", &result, || {
+            vec![&env, destinations.into_val(&env)]
+        });
+        result
+    }
+
+    /// Digest the sweep signer must sign to authorize `sweep_assets`
+    ///
+    /// # Errors
+    /// Returns Error::NotInitialized if the account is not initialized
+    pub fn get_sweep_assets_message(
+        env: Env,
+        destination: Address,
+        assets: Vec<Address>,
+    ) -> Result<BytesN<32>, Error> {
+        let state = AccountState::new(&env);
+        Self::check_storage_version(&env, &state)?;
+
+        Ok(Self::sweep_assets_message(
+            &env,
+            &state,
+            &destination,
+            &assets,
+        ))
+    }
+
+    /// Digest the sweep signer must sign to authorize `sweep_split`
+    ///
+    /// # Errors
+    /// Returns Error::NotInitialized if the account is not initialized
+    pub fn get_sweep_split_message(
+        env: Env,
+        destinations: Vec<(Address, u32)>,
+    ) -> Result<BytesN<32>, Error> {
+        let state = AccountState::new(&env);
+        Self::check_storage_version(&env, &state)?;
+
+        Ok(Self::sweep_split_message(&env, &state, &destinations))
+    }
+
+    /// Assets already moved by `sweep_assets`
+    pub fn get_swept_assets(env: Env) -> Vec<Address> {
+        AccountState::new(&env).get_swept_assets()
+    }
+}
+
+#[cfg(feature = "refunds")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Return one recorded payment to the address it came from
+    ///
+    /// For overpayments and payments in the wrong asset.  The payment is
+    /// transferred back to its sender and forgotten; other payments are
+    /// untouched.  A payment topped up by several senders returns each
+    /// one what they paid, with a `refunded` event per sender.  If it was the only payment the account returns to
+    /// `Active` and can be paid again.
+    ///
+    /// `auth_signatures` are the sweep signers' ed25519 signatures over the
+    /// digest returned by `get_refund_message(asset)`.
+    ///
+    /// # Errors
+    /// Returns Error::AssetNotFound if the asset has no outstanding payment
+    /// Returns Error::SenderUnknown if any part of the payment was recorded
+    /// without a sender
+    pub fn refund_payment(
+        env: Env,
+        asset: Address,
+        auth_signatures: Vec<BytesN<64>>,
+    ) -> Result<(), Error> {
+        let result = Self::apply_refund_payment(&env, &asset, &auth_signatures);
+        Self::report_failure(&env, "refund_payment", &result, || {
+            vec![&env, asset.into_val(&env)]
+        });
+        result
+    }
+
+    /// Digest the sweep signer must sign to authorize `refund_payment`
+    ///
+    /// # Errors
+    /// Returns Error::AssetNotFound if the asset has no recorded payment
+    /// Returns Error::SenderUnknown if any part of the payment was recorded
+    /// without a sender
+    pub fn get_refund_message(env: Env, asset: Address) -> Result<BytesN<32>, Error> {
+        let state = AccountState::new(&env);
+        Self::check_storage_version(&env, &state)?;
+
+        let payment = state.get_payment(&asset).ok_or(Error::AssetNotFound)?;
+        let sender = payment.sender.clone().ok_or(Error::SenderUnknown)?;
+        Self::refund_shares(&env, &state, &payment, &sender)?;
+        Ok(Self::refund_message(&env, &state, &sender, &asset))
+    }
+}
+
+#[cfg(feature = "limits")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Restrict the payments this account accepts
+    ///
+    /// Must be authorized by the creator and called before the first
+    /// payment is recorded.  Replaces any previously configured limits.
+    ///
+    /// # Arguments
+    /// * `max_payments` - Maximum number of distinct assets (1 to 10)
+    /// * `min_amounts` - Smallest accepted payment per asset
+    /// * `allowed_assets` - Accepted assets, or `None` to accept any asset
+    /// * `cooldown_ledgers` - Ledgers between two recorded payments, or `0`
+    ///
+    /// # Errors
+    /// Returns Error::InvalidStatus if a payment was already recorded
+    /// Returns Error::TooManyPayments if `max_payments` exceeds 10
+    /// Returns Error::InvalidAmount if `max_payments` is zero or a minimum
+    /// is not positive
+    pub fn configure_limits(
+        env: Env,
+        max_payments: u32,
+        min_amounts: Map<Address, i128>,
+        allowed_assets: Option<Vec<Address>>,
+        cooldown_ledgers: u32,
+    ) -> Result<(), Error> {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(&env, &state)?;
+        Self::check_not_halted(&env, &state)?;
+
+        state.get_creator().require_auth();
+
+        if state.get_status() != AccountStatus::Active {
+            return Err(Error::InvalidStatus);
+        }
+
+        if max_payments > MAX_PAYMENT_ASSETS {
+            return Err(Error::TooManyPayments);
+        }
+        if max_payments == 0 || min_amounts.values().iter().any(|amount| amount <= 0) {
+            return Err(Error::InvalidAmount);
+        }
+
+        state.set_payment_limits(&PaymentLimits {
+            max_payments,
+            min_amounts: min_amounts.clone(),
+            allowed_assets: allowed_assets.clone(),
+            cooldown_ledgers,
+        });
+        events::emit_payment_limits_set(
+            &env,
+            max_payments,
+            min_amounts,
+            allowed_assets,
+            cooldown_ledgers,
+        );
+
+        Ok(())
+    }
+
+    /// Restrictions currently applied to inbound payments
+    pub fn get_payment_limits(env: Env) -> PaymentLimits {
+        AccountState::new(&env).get_payment_limits(MAX_PAYMENT_ASSETS)
+    }
+
+    /// Set the order in which assets are settled by `sweep`
+    ///
+    /// Listed assets are swept first, in the given order; any other asset
+    /// follows in the default (asset address) order.  If a sweep cannot
+    /// complete every transfer, the most important assets have settled first.
+    /// Must be authorized by the creator and can be changed until the
+    /// account is swept or expired.
+    ///
+    /// # Errors
+    /// Returns Error::InvalidStatus if the account was already swept or expired
+    /// Returns Error::TooManyPayments if more assets are listed than can be paid
+    /// Returns Error::DuplicateAsset if an asset is listed more than once
+    pub fn set_sweep_priority(env: Env, assets: Vec<Address>) -> Result<(), Error> {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(&env, &state)?;
+        Self::check_not_halted(&env, &state)?;
+
+        state.get_creator().require_auth();
+
+        let status = state.get_status();
+        if status.is_closed() {
+            return Err(Error::InvalidStatus);
+        }
+
+        if assets.len() > MAX_PAYMENT_ASSETS {
+            return Err(Error::TooManyPayments);
+        }
+        for (i, asset) in assets.iter().enumerate() {
+            if assets.iter().skip(i + 1).any(|other| other == asset) {
+                return Err(Error::DuplicateAsset);
+            }
+        }
+
+        state.set_sweep_priority(&assets);
+        events::emit_sweep_priority_set(&env, assets);
+
+        Ok(())
+    }
+
+    /// Assets settled first by `sweep`, in priority order
+    pub fn get_sweep_priority(env: Env) -> Vec<Address> {
+        AccountState::new(&env).get_sweep_priority()
+    }
+}
+
+#[cfg(feature = "reuse")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Return a settled account to `Active` for a new payment
+    ///
+    /// Lets the factory re-use accounts instead of deploying one per
+    /// invoice.  `factory` must be the contract the account was
+    /// initialized with (`AccountConfig.factory`) and authorize the call.
+    /// The account must be closed and hold nothing: every recorded asset's
+    /// balance and the reserve are zero.
+    ///
+    /// Payments, sweep results, pending holds and reserve history are
+    /// wiped, and the account starts the next epoch, carried by every
+    /// later event.  Configuration is kept; an emergency recovery window
+    /// is not, since it was measured from initialization.  The sweep nonce
+    /// keeps counting, so signatures from earlier epochs stay spent.
+    ///
+    /// # Errors
+    /// Returns Error::Unauthorized if `factory` is not the account's factory
+    /// Returns Error::InvalidStatus if the account is not closed
+    /// Returns Error::FundsRemaining if it still holds a recorded asset or
+    /// reserve
+    /// Returns Error::InvalidExpiry if `expiry_ledger` is not in the future
+    pub fn reset(
+        env: Env,
+        expiry_ledger: u32,
+        recovery_address: Address,
+        factory: Address,
+    ) -> Result<(), Error> {
+        let result = Self::apply_reset(&env, expiry_ledger, &recovery_address, &factory);
+        Self::report_failure(&env, "reset", &result, || {
+            vec![&env, expiry_ledger.into_val(&env), factory.into_val(&env)]
+        });
+        result
+    }
+}
+
+#[cfg(feature = "quarantine")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Send a quarantined payment on to the destination of the sweep that
+    /// quarantined it, returning whether it was sent
+    ///
+    /// Anyone may call this, since the destination was fixed by the
+    /// signed sweep; typically once the destination has fixed whatever
+    /// made the transfer fail, such as a missing trustline or
+    /// authorization.  The fee is taken as it would have been on sweep.
+    /// A transfer that still fails leaves the payment quarantined, counts
+    /// the attempt and doubles the wait before the next one, so retries
+    /// cannot hammer a frozen token every ledger.
+    ///
+    /// # Errors
+    /// Returns Error::AssetNotFound if `asset` is not quarantined
+    /// Returns Error::RetryTooEarly before the asset's `next_retry_ledger`
+    pub fn retry_quarantined(env: Env, asset: Address) -> Result<bool, Error> {
+        let result = Self::apply_retry_quarantined(&env, &asset);
+        Self::report_failure(&env, "retry_quarantined", &result, || {
+            vec![&env, asset.into_val(&env)]
+        });
+        result
+    }
+
+    /// Payments a sweep could not transfer, with the amounts it would
+    /// have sent
+    pub fn get_quarantined(env: Env) -> Vec<Payment> {
+        AccountState::new(&env).get_quarantined()
+    }
+
+    /// Failed retries of a quarantined `asset` and when it may next be
+    /// retried
+    pub fn get_quarantine_retry(env: Env, asset: Address) -> Option<QuarantineRetry> {
+        AccountState::new(&env).get_quarantine_retries().get(asset)
+    }
+}
+
+#[cfg(feature = "reconcile")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Record that `amount` more XLM arrived to cover a reserve shortfall
+    ///
+    /// Must be authorized by the creator.  Raises the available reserve,
+    /// which `reclaim_reserve` pays out, towards the reserve still owed.
+    /// Allowed after the account closes, so a reserve that was only
+    /// partly reclaimed can be completed.  When the reserve is held in XLM
+    /// next to native payments, the account's XLM balance must back it.
+    ///
+    /// # Errors
+    /// Returns Error::InvalidAmount if `amount` is not positive or exceeds
+    /// the shortfall (`get_reserve_remaining` less `get_reserve_available`)
+    /// Returns Error::InsufficientNativeBalance if the XLM balance does not
+    /// cover the raised reserve and every outstanding XLM payment
+    pub fn record_reserve_topup(env: Env, amount: i128) -> Result<(), Error> {
+        let result = Self::apply_record_reserve_topup(&env, amount);
+        Self::report_failure(&env, "record_reserve_topup", &result, || {
+            vec![&env, amount.into_val(&env)]
+        });
+        result
+    }
+
+    /// Reset the available reserve from the XLM actually backing it
+    ///
+    /// Must be authorized by the creator.  For balance changes the account
+    /// did not see, in either direction.  `actual_balance` is the XLM held
+    /// for the reserve; anything above the reserve still owed is not
+    /// reserve and is left out.
+    ///
+    /// # Errors
+    /// Returns Error::InvalidAmount if `actual_balance` is negative
+    /// Returns Error::InsufficientNativeBalance if the reserve is held in
+    /// XLM and the account's XLM balance does not cover it
+    pub fn reconcile_reserve(env: Env, actual_balance: i128) -> Result<(), Error> {
+        let result = Self::apply_reconcile_reserve(&env, actual_balance);
+        Self::report_failure(&env, "reconcile_reserve", &result, || {
+            vec![&env, actual_balance.into_val(&env)]
+        });
+        result
+    }
+}
+
+#[cfg(feature = "audit")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Everything the account recorded, in one read
+    ///
+    /// Initialization parameters, every payment, the sweep or expiry
+    /// outcome, the reserve reclaim history and the account's counters.
+    pub fn get_audit_trail(env: Env) -> Result<AuditTrail, Error> {
+        let state = AccountState::new(&env);
+        Self::check_storage_version(&env, &state)?;
+
+        let payments = state.get_payments();
+        let reserve_summary = state.get_reserve_summary();
+        let reclaim_events = Self::get_recent_reserve_events(env.clone());
+
+        Ok(AuditTrail {
+            ledger: env.ledger().sequence(),
+            creator: state.get_creator(),
+            recovery_address: state.get_recovery_address(),
+            expiry_ledger: state.get_expiry_ledger(),
+            grace_ledgers: state.get_grace_ledgers(),
+            config_version: state.get_config_version(),
+            wasm_version: state.get_wasm_version(),
+            storage_version: state.get_storage_version(),
+            payment_count: payments.len(),
+            payments,
+            swept_assets: state.get_swept_assets(),
+            payments_root: state.get_payments_root(),
+            status: state.get_status(),
+            swept_to: state.get_swept_to(),
+            last_sweep_id: state.get_last_sweep_id(),
+            reserve_remaining: state.get_base_reserve_remaining(),
+            reserve_available: state.get_available_reserve(),
+            reserve_reclaimed: state.is_reserve_reclaimed(),
+            total_reclaimed: reserve_summary.total_reclaimed,
+            reclaim_events,
+            sweep_nonce: state.get_sweep_nonce(),
+            reclaim_count: reserve_summary.reclaim_count,
+            reclaim_event_count: reserve_summary.event_count,
+        })
+    }
+
+    /// Key state fields at the current ledger, with a hash committing to them
+    ///
+    /// `hash` is sha256 of the XDR-encoded tuple
+    /// `("attest", account, ledger, timestamp, status, expiry_ledger,
+    /// payment_count, payments_root, swept_to, sweep_nonce)`.  The sweep
+    /// signer countersigns it off-chain as a receipt of on-chain state for
+    /// dispute resolution and reporting.
+    ///
+    /// # Errors
+    /// Returns Error::NotInitialized if the account is not initialized
+    pub fn get_state_attestation(env: Env) -> Result<StateAttestation, Error> {
+        let state = AccountState::new(&env);
+        Self::check_storage_version(&env, &state)?;
+
+        let account = env.current_contract_address();
+        let ledger = env.ledger().sequence();
+        let timestamp = env.ledger().timestamp();
+        let status = state.get_status();
+        let expiry_ledger = state.get_expiry_ledger();
+        let payment_count = state.get_total_payments();
+        let payments_root = state.get_payments_root();
+        let swept_to = state.get_swept_to();
+        let sweep_nonce = state.get_sweep_nonce();
+
+        let preimage = (
+            symbol_short!("attest"),
+            account.clone(),
+            ledger,
+            timestamp,
+            status,
+            expiry_ledger,
+            payment_count,
+            payments_root.clone(),
+            swept_to.clone(),
+            sweep_nonce,
+        )
+            .to_xdr(&env);
+
+        Ok(StateAttestation {
+            account,
+            ledger,
+            timestamp,
+            status,
+            expiry_ledger,
+            payment_count,
+            payments_root,
+            swept_to,
+            sweep_nonce,
+            hash: env.crypto().sha256(&preimage).to_bytes(),
+        })
+    }
+
+    /// Dry run of `sweep` to `destination`
+    ///
+    /// Lists what each outstanding payment would move, the protocol fee
+    /// taken from it and the reserve that would be reclaimed, and which
+    /// error the sweep would currently fail with.  Signatures are not
+    /// checked and no state is modified.
+    pub fn preview_sweep(env: Env, destination: Address) -> SweepPreview {
+        let state = AccountState::new(&env);
+        let error_code = Self::check_sweep_to(&env, &state, &destination, false)
+            .err()
+            .map(|error| error as u32);
+
+        let mut amounts = Vec::new(&env);
+        let mut fee_error = None;
+        for payment in Self::payments_in_sweep_order(&env, &state).iter() {
+            let fee = Self::fee_for(&state, payment.amount).unwrap_or_else(|error| {
+                fee_error = Some(error as u32);
+                0
+            });
+            amounts.push_back(SweepAmount {
+                asset: payment.asset,
+                gross: payment.amount,
+                fee,
+                net: payment.amount - fee,
+            });
+        }
+
+        #[cfg(feature = "escrow")]
+        let held = state.get_escrow_arbiter().is_some()
+            || !Self::clawback_held_assets(&env, &state).1.is_empty();
+        #[cfg(not(feature = "escrow"))]
+        let held = false;
+
+        let reserve_reclaim = state
+            .get_base_reserve_remaining()
+            .min(state.get_available_reserve())
+            .max(0);
+
+        SweepPreview {
+            destination,
+            amounts,
+            reserve_reclaim,
+            held,
+            error_code: error_code.or(fee_error),
+        }
+    }
+}
+
+#[cfg(feature = "invariants")]
+#[contractimpl]
+impl EphemeralAccountContract {
+    /// Consistency rules the account's current state breaks
+    ///
+    /// Debugging aid: an empty result means status, payments and reserve
+    /// flags agree with each other.
+    ///
+    /// # Errors
+    /// Returns Error::NotInitialized if the account is not initialized
+    pub fn check_invariants(env: Env) -> Result<Vec<Invariant>, Error> {
+        let state = AccountState::new(&env);
+        Self::check_storage_version(&env, &state)?;
+        Ok(bridgelet_shared::violations(&env, &state.snapshot()))
+    }
+}
+
+#[cfg(feature = "auth")]
+#[contractimpl]
+impl CustomAccountInterface for EphemeralAccountContract {
+    type Signature = Vec<BytesN<64>>;
+    type Error = Error;
+
+    /// Authorization policy of the account's own address
+    ///
+    /// `signatures` follows the slot layout of `auth_signatures` and must
+    /// meet the sweep signer threshold over `signature_payload`.  The
+    /// account must still be open and unexpired, and the only call it
+    /// authorizes is its own `sweep_authorized`; token transfers and calls
+    /// into other contracts are refused.
+    #[allow(non_snake_case)]
+    fn __check_auth(
+        env: Env,
+        signature_payload: Hash<32>,
+        signatures: Vec<BytesN<64>>,
+        auth_contexts: Vec<Context>,
+    ) -> Result<(), Error> {
+        let state = AccountState::new(&env);
+        if !state.is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        if state.get_status().is_closed() {
+            return Err(Error::InvalidStatus);
+        }
+        if Self::is_past_grace(&env, &state) {
+            return Err(Error::AccountExpired);
+        }
+
+        let sweep_fn = Symbol::new(&env, "sweep_authorized");
+        for context in auth_contexts.iter() {
+            match context {
+                Context::Contract(call)
+                    if call.contract == env.current_contract_address()
+                        && call.fn_name == sweep_fn => {}
+                _ => return Err(Error::Unauthorized),
+            }
+        }
+
+        Self::verify_sweep_authorization(&env, &state, signature_payload.to_bytes(), &signatures)
     }
 }
@@ -1,206 +1,1550 @@
+use crate::errors::Error;
 use crate::events::ReserveReclaimed;
-use bridgelet_shared::{AccountStatus, Payment};
-use soroban_sdk::{contracttype, Address, Env, Map};
+#[cfg(feature = "audit")]
+use crate::events::SweepAmount;
+use crate::RESERVE_EVENT_HISTORY;
+use bridgelet_shared::{
+    AccountMetadata, AccountStatus, AuthPolicy, ChainLink, Payment, SignerKind,
+};
+#[cfg(feature = "upgrade")]
+use soroban_sdk::vec;
+use soroban_sdk::{
+    contracttype, panic_with_error,
+    storage::{Instance, Persistent},
+    Address, BytesN, Env, IntoVal, Map, Symbol, TryFromVal, Val, Vec,
+};
 
-#[contracttype]
+#[contracttype(export = false)]
 pub enum DataKey {
     Initialized,
     Creator,
     ExpiryLedger,
     RecoveryAddress,
-    Payments,
+    /// Assets with a recorded payment, in recording order (persistent).
+    PaymentAssets,
+    /// Payment recorded for one asset, holding its running total
+    /// (persistent).
+    Payment(Address),
     Status,
     SweptTo,
     BaseReserveRemaining,
     AvailableReserve,
     ReserveReclaimed,
     LastSweepId,
-    ReserveEventCount,
-    LastReserveEvent,
+    ReserveReclaimSummary,
+    SweepPriority,
+    PaymentsRoot,
+    WasmVersion,
+    ConfigVersion,
+    SweepSigners,
+    SweepThreshold,
+    SignerKind,
+    EventPrefix,
+    SweepNonce,
+    SweptAssets,
+    PaymentLimits,
+    KillSwitch,
+    PendingRelease,
+    /// First and latest ledger a payment was recorded at, as one entry.
+    PaymentLedgers,
+    PendingDestination,
+    StorageVersion,
+    /// Native Stellar Asset Contract address, pinned at initialization.
+    NativeAsset,
+    /// Whether the base reserve is held in the account's native balance.
+    NativeReserveHeld,
+    TrustlineCount,
+    /// Reserve reclaim event in ring buffer slot `n` (persistent).
+    ReserveEvent(u32),
 }
 
-// Initialization
-pub fn is_initialized(env: &Env) -> bool {
-    env.storage().instance().has(&DataKey::Initialized)
+/// Instance keys added after `DataKey` reached the 50 variants a contract
+/// type allows.  A unit variant is stored as its name, so names must not
+/// repeat one of `DataKey`'s.
+#[contracttype(export = false)]
+pub enum ExtDataKey {
+    /// Sequence number of the latest state-changing call.
+    StateSeq,
+    /// Contract allowed to reset the account for re-use.
+    Factory,
+    /// Times the account was reset; absent before the first.
+    Epoch,
+    /// Payments whose sweep transfer failed, awaiting `retry_quarantined`.
+    Quarantined,
+    /// `QuarantineRetry` per quarantined asset.
+    QuarantineRetries,
+    /// Payments a sweep left out as dust, awaiting `sweep_dust`.
+    Dust,
+    /// Last ledger of the guardian's depeg override.
+    DepegOverride,
+    /// Destination of the latest `sweep_assets`; absent before the first.
+    PartialSweepTo,
+    /// Commitment of a prepared sweep awaiting execution.
+    PreparedSweep,
+    /// Insurance pool the account opens a claim with if it expires with
+    /// payments in it; covers one cycle.
+    InsurancePool,
+    /// Amount each known sender paid towards one asset's payment
+    /// (persistent); absent while a single sender paid all of it.
+    Contributions(Address),
 }
 
-pub fn set_initialized(env: &Env, value: bool) {
-    env.storage().instance().set(&DataKey::Initialized, &value);
+/// Instance keys of settings only `initialize_with_config` stores, kept out
+/// of `DataKey` and `ExtDataKey` so builds without `config` do not carry
+/// their conversion.  Names must not repeat one of theirs.
+#[cfg(feature = "config")]
+#[contracttype(export = false)]
+pub enum ConfigDataKey {
+    DebugEvents,
+    ExternalChainRef,
+    PasskeySigner,
+    AccumulatePayments,
+    EscrowArbiter,
+    HoldLedgers,
+    LockedDestination,
+    FeeCollector,
+    FeeBps,
+    BatchMinAmount,
+    BatchMaxWait,
+    /// Guardian and its delays, as one `GuardianConfig` entry.
+    Guardian,
+    ReserveContract,
+    /// Per-asset sweep hold for assets that can be clawed back.
+    ClawbackHolds,
+    GraceLedgers,
+    /// Price oracle and minimum sweep value, as one entry.
+    SweepValueFloor,
+    Metadata,
+    /// Auth policy and keeper, as one entry; absent when permissionless.
+    AccessPolicy,
+    /// Keeper bounty in basis points; absent when none is paid.
+    KeeperBountyBps,
+    /// Contract notified when the account settles.
+    SettlementHook,
+    /// `ReserveContract` epoch read at initialization; absent without a
+    /// reserve contract.
+    ReserveConfigEpoch,
+    /// Keeper bounty cap and ramp length, as one entry; absent when the
+    /// bounty does not escalate.
+    KeeperBountyRamp,
+    /// Whether failed sweep transfers are quarantined; absent when not.
+    QuarantineMode,
+    /// Oracle and value below which `sweep` leaves an asset out, as one
+    /// entry; absent without a dust threshold.
+    DustThreshold,
+    /// Oracle, guarded stablecoins with their pegs, and the allowed
+    /// deviation, as one entry; absent without a depeg guard.
+    DepegGuard,
+    /// Contract told of payments and asked before sweeps.
+    ComplianceGate,
 }
 
-// Creator
-pub fn set_creator(env: &Env, creator: &Address) {
-    env.storage().instance().set(&DataKey::Creator, creator);
+/// Key of an instance entry of the first release's unversioned layout
+/// (storage version 0) that this code no longer writes.
+#[cfg(feature = "upgrade")]
+pub fn v0_key(env: &Env, name: &str) -> Vec<Symbol> {
+    vec![env, Symbol::new(env, name)]
 }
 
-pub fn get_creator(env: &Env) -> Address {
-    env.storage().instance().get(&DataKey::Creator).unwrap()
+/// `Payment` as the first release stored it, in an instance map under
+/// `Payments`.  Only read by the storage migration.
+#[cfg(feature = "upgrade")]
+#[contracttype(export = false)]
+#[derive(Clone)]
+pub struct PaymentV0 {
+    pub asset: Address,
+    pub amount: i128,
+    pub timestamp: u64,
 }
 
-// Expiry
-pub fn set_expiry_ledger(env: &Env, ledger: u32) {
-    env.storage()
-        .instance()
-        .set(&DataKey::ExpiryLedger, &ledger);
+/// `ReserveReclaimed` as the first release stored its latest one, with
+/// the ledger sequence as its sweep id.  Only read by the storage
+/// migration.
+#[cfg(feature = "upgrade")]
+#[contracttype(export = false)]
+#[derive(Clone)]
+pub struct ReserveReclaimedV0 {
+    pub destination: Address,
+    pub amount: i128,
+    pub sweep_id: u64,
+    pub fully_reclaimed: bool,
+    pub remaining_reserve: i128,
 }
 
-pub fn get_expiry_ledger(env: &Env) -> u32 {
-    env.storage()
-        .instance()
-        .get(&DataKey::ExpiryLedger)
-        .unwrap()
+/// Sweep id of a sweep made by the first release: its ledger sequence,
+/// big-endian in the last eight bytes.
+#[cfg(feature = "upgrade")]
+pub fn legacy_sweep_id(env: &Env, ledger: u64) -> BytesN<32> {
+    let mut id = [0u8; 32];
+    id[24..].copy_from_slice(&ledger.to_be_bytes());
+    BytesN::from_array(env, &id)
 }
 
-// Recovery address
-pub fn set_recovery_address(env: &Env, address: &Address) {
-    env.storage()
-        .instance()
-        .set(&DataKey::RecoveryAddress, address);
+/// Sweep waiting out its hold in escrow mode.
+#[cfg(feature = "escrow")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingRelease {
+    /// Destination authorized by the sweep signature.
+    pub destination: Address,
+    /// First ledger at which `finalize_sweep` may release the funds.
+    pub release_after_ledger: u32,
 }
 
-pub fn get_recovery_address(env: &Env) -> Address {
-    env.storage()
-        .instance()
-        .get(&DataKey::RecoveryAddress)
-        .unwrap()
+/// Sweep destination committed to by `prepare_sweep`, not yet revealed.
+#[cfg(feature = "prepared")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PreparedSweep {
+    /// sha256(destination || salt), the destination XDR-encoded.
+    pub commitment: BytesN<32>,
+    /// Last ledger at which `execute_prepared_sweep` may reveal it.
+    pub expires_ledger: u32,
 }
 
-// Payments
-pub fn has_payments(env: &Env) -> bool {
-    env.storage().instance().has(&DataKey::Payments)
+/// Guardian of an account and the delays on what it may do.
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GuardianConfig {
+    pub guardian: Address,
+    /// Ledgers between proposing a destination change and applying it.
+    pub destination_change_delay: u32,
+    /// First ledger at which the guardian may `emergency_recover`, or
+    /// `None` if it may not.
+    pub emergency_ledger: Option<u32>,
 }
 
-pub fn get_all_payments(env: &Env) -> Map<Address, Payment> {
-    env.storage()
-        .instance()
-        .get(&DataKey::Payments)
-        .unwrap_or_else(|| Map::new(env))
+/// Change of the locked destination waiting out its timelock.
+#[cfg(feature = "guardian")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingDestination {
+    pub destination: Address,
+    /// First ledger at which `apply_destination_change` may apply it.
+    pub effective_ledger: u32,
 }
 
-pub fn set_all_payments(env: &Env, payments: &Map<Address, Payment>) {
-    env.storage().instance().set(&DataKey::Payments, payments);
+/// Retry state of a quarantined asset.
+#[cfg(feature = "quarantine")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QuarantineRetry {
+    /// Failed `retry_quarantined` calls so far.
+    pub attempts: u32,
+    /// First ledger at which `retry_quarantined` may try again.
+    pub next_retry_ledger: u32,
 }
 
-pub fn add_payment(env: &Env, payment: Payment) {
-    let mut payments = get_all_payments(env);
-    payments.set(payment.asset.clone(), payment);
-    set_all_payments(env, &payments);
+/// Creator-configured restrictions on inbound payments.
+#[cfg_attr(feature = "limits", contracttype)]
+#[cfg_attr(not(feature = "limits"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentLimits {
+    /// Maximum number of distinct assets, at most `MAX_PAYMENT_ASSETS`.
+    pub max_payments: u32,
+    /// Smallest accepted payment per asset; unlisted assets have none.
+    pub min_amounts: Map<Address, i128>,
+    /// Assets accepted for payment, or `None` to accept any asset.
+    pub allowed_assets: Option<Vec<Address>>,
+    /// Ledgers that must pass after a recorded payment before the next
+    /// one is accepted.  `0` disables the cooldown.
+    pub cooldown_ledgers: u32,
 }
 
-pub fn get_payment(env: &Env, asset: &Address) -> Option<Payment> {
-    let payments = get_all_payments(env);
-    payments.get(asset.clone())
+/// What a `sweep` to one destination would do at the current ledger.
+#[cfg(feature = "audit")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SweepPreview {
+    pub destination: Address,
+    /// Per-asset gross amount, protocol fee and net amount, in sweep order.
+    pub amounts: Vec<SweepAmount>,
+    /// Reserve (stroops) that would be reclaimed to the destination.
+    pub reserve_reclaim: i128,
+    /// The sweep would only be held for release (escrow mode or a
+    /// clawback hold).
+    pub held: bool,
+    /// Code of the `Error` the sweep would currently fail with, before
+    /// signature checks; `None` if it would go through.
+    pub error_code: Option<u32>,
 }
 
-pub fn get_total_payments(env: &Env) -> u32 {
-    get_all_payments(env).len()
+/// Where an account stands relative to its expiry and grace period.
+#[cfg_attr(feature = "lifecycle", contracttype)]
+#[cfg_attr(not(feature = "lifecycle"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lifecycle {
+    pub status: AccountStatus,
+    /// Sweeps are accepted before this ledger, without a grace period.
+    pub expiry_ledger: u32,
+    pub grace_ledgers: u32,
+    /// End of the sweep window and start of the `expire` window:
+    /// `expiry_ledger + grace_ledgers`.
+    pub grace_end_ledger: u32,
+    /// The account is past its expiry ledger but still in its grace period.
+    pub in_grace: bool,
 }
 
-pub fn has_payment_received(env: &Env) -> bool {
-    has_payments(env)
+/// Totals of reserve reclaim activity.
+///
+/// Totals only grow when reserve actually moves.  The events themselves
+/// are kept in a ring buffer of `RESERVE_EVENT_HISTORY` persistent slots,
+/// event number `n` in slot `n % RESERVE_EVENT_HISTORY`, so a burst of
+/// reclaims never grows the instance.
+#[cfg_attr(feature = "history", contracttype)]
+#[cfg_attr(not(feature = "history"), contracttype(export = false))]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReserveReclaimSummary {
+    /// Cumulative reserve reclaimed across all calls (stroops).
+    pub total_reclaimed: i128,
+    /// Number of reclaim operations that moved a non-zero amount.
+    pub reclaim_count: u32,
+    /// Number of `ReserveReclaimed` events emitted, including no-op reclaims.
+    pub event_count: u32,
 }
 
-// Status
-pub fn set_status(env: &Env, status: AccountStatus) {
-    env.storage().instance().set(&DataKey::Status, &status);
-}
+/// If the remaining instance TTL drops below this threshold (in ledgers,
+/// ~15 days), a write extends it.
+const INSTANCE_TTL_THRESHOLD: u32 = 259_200;
 
-pub fn get_status(env: &Env) -> AccountStatus {
-    env.storage()
-        .instance()
-        .get(&DataKey::Status)
-        .unwrap_or(AccountStatus::Active)
-}
+/// Extend the instance TTL to this many ledgers (~30 days).
+const INSTANCE_TTL_EXTEND_TO: u32 = 518_400;
 
-// Swept to
-pub fn set_swept_to(env: &Env, address: &Address) {
-    env.storage().instance().set(&DataKey::SweptTo, address);
+/// Typed accessor over the account's storage.
+///
+/// Owns the key layout, the default returned for unset keys and the TTL
+/// policy, so entrypoints never touch `DataKey` or `env.storage()`
+/// directly.  A schema change (new key, different storage tier, renamed
+/// field) only has to be made here.
+///
+/// Payments live in persistent storage, one entry per asset plus an index
+/// of assets, rather than in the instance: the instance is loaded by every
+/// call, so keeping it free of payments means calls that do not need them
+/// never pay to read them.  Each entry holds its asset's running total, so
+/// recording a payment rewrites only that asset's entry, and counts and
+/// lookups read the index or a single entry.
+pub struct AccountState<'a> {
+    env: &'a Env,
 }
 
-pub fn get_swept_to(env: &Env) -> Option<Address> {
-    env.storage().instance().get(&DataKey::SweptTo)
-}
+impl<'a> AccountState<'a> {
+    pub fn new(env: &'a Env) -> Self {
+        Self { env }
+    }
 
-// Reserve lifecycle
-pub fn init_reserve_tracking(env: &Env, base_reserve: i128) {
-    set_base_reserve_remaining(env, base_reserve);
-    set_available_reserve(env, base_reserve);
-    set_reserve_reclaimed(env, base_reserve == 0);
-    set_last_sweep_id(env, 0);
-    set_reserve_event_count(env, 0);
-}
+    // Raw access
 
-pub fn set_base_reserve_remaining(env: &Env, amount: i128) {
-    env.storage()
-        .instance()
-        .set(&DataKey::BaseReserveRemaining, &amount);
-}
+    fn store(&self) -> Instance {
+        self.env.storage().instance()
+    }
 
-pub fn get_base_reserve_remaining(env: &Env) -> i128 {
-    env.storage()
-        .instance()
-        .get(&DataKey::BaseReserveRemaining)
-        .unwrap_or(0)
-}
+    fn get_ext<V: TryFromVal<Env, Val>>(&self, key: &ExtDataKey) -> Option<V> {
+        self.store().get(key)
+    }
 
-pub fn set_available_reserve(env: &Env, amount: i128) {
-    env.storage()
-        .instance()
-        .set(&DataKey::AvailableReserve, &amount);
-}
+    fn set_ext<V: IntoVal<Env, Val>>(&self, key: &ExtDataKey, value: &V) {
+        let store = self.store();
+        store.set(key, value);
+        store.extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND_TO);
+    }
 
-pub fn get_available_reserve(env: &Env) -> i128 {
-    env.storage()
-        .instance()
-        .get(&DataKey::AvailableReserve)
-        .unwrap_or(0)
-}
+    #[cfg(feature = "config")]
+    fn get_cfg<V: TryFromVal<Env, Val>>(&self, key: &ConfigDataKey) -> Option<V> {
+        self.store().get(key)
+    }
 
-pub fn set_reserve_reclaimed(env: &Env, reclaimed: bool) {
-    env.storage()
-        .instance()
-        .set(&DataKey::ReserveReclaimed, &reclaimed);
-}
+    #[cfg(feature = "config")]
+    fn set_cfg<V: IntoVal<Env, Val>>(&self, key: &ConfigDataKey, value: &V) {
+        let store = self.store();
+        store.set(key, value);
+        store.extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND_TO);
+    }
 
-pub fn is_reserve_reclaimed(env: &Env) -> bool {
-    env.storage()
-        .instance()
-        .get(&DataKey::ReserveReclaimed)
-        .unwrap_or(false)
-}
+    fn get<V: TryFromVal<Env, Val>>(&self, key: &DataKey) -> Option<V> {
+        self.store().get(key)
+    }
 
-pub fn set_last_sweep_id(env: &Env, sweep_id: u64) {
-    env.storage()
-        .instance()
-        .set(&DataKey::LastSweepId, &sweep_id);
-}
+    /// Read a key every initialized account holds.  A missing key means the
+    /// layout is not the one this code expects, which fails the call with
+    /// `StorageVersionMismatch` rather than an opaque panic.
+    fn require<V: TryFromVal<Env, Val>>(&self, key: &DataKey) -> V {
+        self.get(key)
+            .unwrap_or_else(|| panic_with_error!(self.env, Error::StorageVersionMismatch))
+    }
 
-pub fn get_last_sweep_id(env: &Env) -> u64 {
-    env.storage()
-        .instance()
-        .get(&DataKey::LastSweepId)
-        .unwrap_or(0)
-}
+    fn has(&self, key: &DataKey) -> bool {
+        self.store().has(key)
+    }
 
-pub fn set_reserve_event_count(env: &Env, count: u32) {
-    env.storage()
-        .instance()
-        .set(&DataKey::ReserveEventCount, &count);
-}
+    /// Every write keeps the instance (and so the whole account) alive.
+    fn set<V: IntoVal<Env, Val>>(&self, key: &DataKey, value: &V) {
+        let store = self.store();
+        store.set(key, value);
+        store.extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND_TO);
+    }
 
-pub fn get_reserve_event_count(env: &Env) -> u32 {
-    env.storage()
-        .instance()
-        .get(&DataKey::ReserveEventCount)
-        .unwrap_or(0)
-}
+    fn persistent(&self) -> Persistent {
+        self.env.storage().persistent()
+    }
 
-pub fn set_last_reserve_event(env: &Env, event: &ReserveReclaimed) {
-    env.storage()
-        .instance()
-        .set(&DataKey::LastReserveEvent, event);
-}
+    /// Persistent reads renew the entry like instance writes do, so a
+    /// payment outlives neither the instance nor the other payments.
+    fn get_persistent<K, V>(&self, key: &K) -> Option<V>
+    where
+        K: IntoVal<Env, Val>,
+        V: TryFromVal<Env, Val>,
+    {
+        let value = self.persistent().get(key);
+        if value.is_some() {
+            self.persistent()
+                .extend_ttl(key, INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND_TO);
+        }
+        value
+    }
+
+    /// Persistent writes live at least as long as the account is expected
+    /// to: past its expiry ledger plus a full extension window.
+    fn set_persistent<K, V>(&self, key: &K, value: &V)
+    where
+        K: IntoVal<Env, Val>,
+        V: IntoVal<Env, Val>,
+    {
+        let extend_to = self.ttl_past(self.get(&DataKey::ExpiryLedger).unwrap_or(0));
+        self.persistent().set(key, value);
+        self.persistent().extend_ttl(key, extend_to, extend_to);
+    }
+
+    /// TTL that keeps an entry alive until `ledger` plus a full extension
+    /// window, capped at the network's maximum TTL.
+    fn ttl_past(&self, ledger: u32) -> u32 {
+        let needed = ledger
+            .saturating_sub(self.env.ledger().sequence())
+            .saturating_add(INSTANCE_TTL_EXTEND_TO);
+        needed.min(self.env.storage().max_ttl())
+    }
+
+    /// Keep the account alive until `ledger` plus a full extension window,
+    /// capped at the network's maximum TTL.
+    ///
+    /// Used at initialization and when the expiry is extended, so an
+    /// account whose expiry lies beyond the regular window is not archived
+    /// before it can be swept or expired.  Recorded payments and reserve
+    /// events are extended along with the instance.
+    pub fn extend_ttl_past(&self, ledger: u32) {
+        let extend_to = self.ttl_past(ledger);
+        self.store().extend_ttl(extend_to, extend_to);
+
+        let retained = self.get_reserve_summary().event_count;
+        for slot in 0..retained.min(RESERVE_EVENT_HISTORY) {
+            self.persistent()
+                .extend_ttl(&DataKey::ReserveEvent(slot), extend_to, extend_to);
+        }
+
+        if !self.persistent().has(&DataKey::PaymentAssets) {
+            return;
+        }
+        self.persistent()
+            .extend_ttl(&DataKey::PaymentAssets, extend_to, extend_to);
+        for asset in self.get_payment_assets().iter() {
+            let contributions = ExtDataKey::Contributions(asset.clone());
+            if self.persistent().has(&contributions) {
+                self.persistent()
+                    .extend_ttl(&contributions, extend_to, extend_to);
+            }
+            self.persistent()
+                .extend_ttl(&DataKey::Payment(asset), extend_to, extend_to);
+        }
+    }
+
+    // Initialization
+    pub fn is_initialized(&self) -> bool {
+        self.has(&DataKey::Initialized)
+    }
+
+    pub fn set_initialized(&self, value: bool) {
+        self.set(&DataKey::Initialized, &value);
+    }
+
+    // Creator
+    pub fn set_creator(&self, creator: &Address) {
+        self.set(&DataKey::Creator, creator);
+    }
+
+    pub fn get_creator(&self) -> Address {
+        self.require(&DataKey::Creator)
+    }
+
+    // Expiry
+    pub fn set_expiry_ledger(&self, ledger: u32) {
+        self.set(&DataKey::ExpiryLedger, &ledger);
+    }
+
+    pub fn get_expiry_ledger(&self) -> u32 {
+        self.require(&DataKey::ExpiryLedger)
+    }
+
+    // Recovery address
+    pub fn set_recovery_address(&self, address: &Address) {
+        self.set(&DataKey::RecoveryAddress, address);
+    }
+
+    pub fn get_recovery_address(&self) -> Address {
+        self.require(&DataKey::RecoveryAddress)
+    }
+
+    // Payments
+    pub fn has_payments(&self) -> bool {
+        self.get_total_payments() > 0
+    }
+
+    /// Assets with a recorded payment, in recording order.
+    pub fn get_payment_assets(&self) -> Vec<Address> {
+        self.get_persistent(&DataKey::PaymentAssets)
+            .unwrap_or_else(|| Vec::new(self.env))
+    }
+
+    /// Every recorded payment, in recording order.  Reads one entry per
+    /// asset; prefer `get_payment` or `get_total_payments` when they
+    /// suffice.
+    pub fn get_payments(&self) -> Vec<Payment> {
+        let mut payments = Vec::new(self.env);
+        for asset in self.get_payment_assets().iter() {
+            if let Some(payment) = self.get_payment(&asset) {
+                payments.push_back(payment);
+            }
+        }
+        payments
+    }
+
+    /// Running total recorded for `asset`; `0` if none was.
+    #[cfg(feature = "views")]
+    pub fn get_payment_total(&self, asset: &Address) -> i128 {
+        self.get_payment(asset).map_or(0, |payment| payment.amount)
+    }
+
+    /// Record `payment`, replacing the one recorded for its asset.  Only a
+    /// new asset rewrites the index.
+    pub fn add_payment(&self, payment: Payment) {
+        let mut assets = self.get_payment_assets();
+        if !assets.contains(&payment.asset) {
+            assets.push_back(payment.asset.clone());
+            self.set_persistent(&DataKey::PaymentAssets, &assets);
+        }
+        self.set_persistent(&DataKey::Payment(payment.asset.clone()), &payment);
+    }
+
+    /// Forget the payment recorded for `asset`.
+    #[cfg(feature = "refunds")]
+    pub fn remove_payment(&self, asset: &Address) {
+        let mut assets = self.get_payment_assets();
+        if let Some(index) = assets.first_index_of(asset) {
+            assets.remove(index);
+            if assets.is_empty() {
+                self.persistent().remove(&DataKey::PaymentAssets);
+            } else {
+                self.set_persistent(&DataKey::PaymentAssets, &assets);
+            }
+        }
+        self.persistent().remove(&DataKey::Payment(asset.clone()));
+        self.persistent()
+            .remove(&ExtDataKey::Contributions(asset.clone()));
+    }
+
+    /// Amount each known sender paid towards `asset`'s payment, once a
+    /// top-up came from a different sender than the payment before it.
+    pub fn get_contributions(&self, asset: &Address) -> Option<Map<Address, i128>> {
+        self.get_persistent(&ExtDataKey::Contributions(asset.clone()))
+    }
+
+    pub fn set_contributions(&self, asset: &Address, contributions: &Map<Address, i128>) {
+        self.set_persistent(&ExtDataKey::Contributions(asset.clone()), contributions);
+    }
+
+    /// Forget every recorded payment.
+    #[cfg(feature = "reuse")]
+    fn clear_payments(&self) {
+        for asset in self.get_payment_assets().iter() {
+            self.persistent()
+                .remove(&ExtDataKey::Contributions(asset.clone()));
+            self.persistent().remove(&DataKey::Payment(asset));
+        }
+        self.persistent().remove(&DataKey::PaymentAssets);
+    }
+
+    /// Take the payments of a version 0 account out of the instance map
+    /// the first release kept them in.
+    #[cfg(feature = "upgrade")]
+    pub fn take_payments_v0(&self) -> Vec<Payment> {
+        let key = v0_key(self.env, "Payments");
+        let mut payments = Vec::new(self.env);
+        let Some(old) = self.store().get::<_, Map<Address, PaymentV0>>(&key) else {
+            return payments;
+        };
+        self.store().remove(&key);
+        for payment in old.values() {
+            payments.push_back(Payment {
+                asset: payment.asset,
+                amount: payment.amount,
+                timestamp: payment.timestamp,
+                payer_info_hash: None,
+                sender: None,
+                decimals: None,
+                memo: None,
+            });
+        }
+        payments
+    }
+
+    pub fn get_payment(&self, asset: &Address) -> Option<Payment> {
+        self.get_persistent(&DataKey::Payment(asset.clone()))
+    }
+
+    pub fn get_total_payments(&self) -> u32 {
+        self.get_payment_assets().len()
+    }
+
+    pub fn has_payment_received(&self) -> bool {
+        self.has_payments()
+    }
+
+    pub fn set_payments_root(&self, root: &BytesN<32>) {
+        self.set(&DataKey::PaymentsRoot, root);
+    }
+
+    pub fn get_payments_root(&self) -> BytesN<32> {
+        self.get(&DataKey::PaymentsRoot)
+            .unwrap_or_else(|| BytesN::from_array(self.env, &[0u8; 32]))
+    }
+
+    // Storage layout version
+    pub fn set_storage_version(&self, version: u32) {
+        self.set(&DataKey::StorageVersion, &version);
+    }
+
+    /// `0` for accounts written before the layout was versioned.
+    pub fn get_storage_version(&self) -> u32 {
+        self.get(&DataKey::StorageVersion).unwrap_or(0)
+    }
+
+    // Template versions
+    pub fn set_versions(&self, wasm_version: u32, config_version: u32) {
+        self.set(&DataKey::WasmVersion, &wasm_version);
+        self.set(&DataKey::ConfigVersion, &config_version);
+    }
+
+    pub fn set_wasm_version(&self, wasm_version: u32) {
+        self.set(&DataKey::WasmVersion, &wasm_version);
+    }
+
+    pub fn get_wasm_version(&self) -> u32 {
+        self.get(&DataKey::WasmVersion).unwrap_or(0)
+    }
+
+    pub fn get_config_version(&self) -> u32 {
+        self.get(&DataKey::ConfigVersion).unwrap_or(0)
+    }
+
+    // Debug events
+    #[cfg(feature = "diagnostics")]
+    pub fn set_debug_events(&self, enabled: bool) {
+        self.set_cfg(&ConfigDataKey::DebugEvents, &enabled);
+    }
+
+    #[cfg(feature = "diagnostics")]
+    pub fn is_debug_events(&self) -> bool {
+        self.get_cfg(&ConfigDataKey::DebugEvents).unwrap_or(false)
+    }
+
+    #[cfg(feature = "config")]
+    pub fn set_accumulate_payments(&self, enabled: bool) {
+        self.set_cfg(&ConfigDataKey::AccumulatePayments, &enabled);
+    }
+
+    #[cfg(feature = "config")]
+    pub fn is_accumulate_payments(&self) -> bool {
+        self.get_cfg(&ConfigDataKey::AccumulatePayments)
+            .unwrap_or(false)
+    }
+
+    /// Built without `config`, no accumulation flag is ever stored.
+    #[cfg(not(feature = "config"))]
+    pub fn is_accumulate_payments(&self) -> bool {
+        false
+    }
+
+    #[cfg(feature = "limits")]
+    pub fn set_payment_limits(&self, limits: &PaymentLimits) {
+        self.set(&DataKey::PaymentLimits, limits);
+    }
+
+    /// Configured limits, or `max_payments` alone when none were set.
+    pub fn get_payment_limits(&self, max_payments: u32) -> PaymentLimits {
+        #[cfg(feature = "limits")]
+        if let Some(limits) = self.get(&DataKey::PaymentLimits) {
+            return limits;
+        }
+        PaymentLimits {
+            max_payments,
+            min_amounts: Map::new(self.env),
+            allowed_assets: None,
+            cooldown_ledgers: 0,
+        }
+    }
+
+    pub fn set_kill_switch(&self, kill_switch: &Address) {
+        self.set(&DataKey::KillSwitch, kill_switch);
+    }
+
+    pub fn get_kill_switch(&self) -> Option<Address> {
+        self.get(&DataKey::KillSwitch)
+    }
+
+    #[cfg(feature = "config")]
+    pub fn set_reserve_contract(&self, reserve_contract: &Address) {
+        self.set_cfg(&ConfigDataKey::ReserveContract, reserve_contract);
+    }
+
+    #[cfg(feature = "config")]
+    pub fn get_reserve_contract(&self) -> Option<Address> {
+        self.get_cfg(&ConfigDataKey::ReserveContract)
+    }
+
+    /// Built without `config`, no reserve contract is ever stored.
+    #[cfg(not(feature = "config"))]
+    pub fn get_reserve_contract(&self) -> Option<Address> {
+        None
+    }
+
+    pub fn set_native_asset(&self, native_asset: &Address) {
+        self.set(&DataKey::NativeAsset, native_asset);
+    }
+
+    pub fn get_native_asset(&self) -> Option<Address> {
+        self.get(&DataKey::NativeAsset)
+    }
+
+    pub fn set_native_reserve_held(&self, held: bool) {
+        self.set(&DataKey::NativeReserveHeld, &held);
+    }
+
+    pub fn is_native_reserve_held(&self) -> bool {
+        self.get(&DataKey::NativeReserveHeld).unwrap_or(false)
+    }
+
+    pub fn set_event_prefix(&self, prefix: &Symbol) {
+        self.set(&DataKey::EventPrefix, prefix);
+    }
+
+    pub fn get_event_prefix(&self) -> Option<Symbol> {
+        self.get(&DataKey::EventPrefix)
+    }
+
+    // Escrow
+    #[cfg(feature = "escrow")]
+    pub fn set_escrow(&self, arbiter: &Address, hold_ledgers: u32) {
+        self.set_cfg(&ConfigDataKey::EscrowArbiter, arbiter);
+        self.set_cfg(&ConfigDataKey::HoldLedgers, &hold_ledgers);
+    }
+
+    #[cfg(feature = "escrow")]
+    pub fn get_escrow_arbiter(&self) -> Option<Address> {
+        self.get_cfg(&ConfigDataKey::EscrowArbiter)
+    }
+
+    /// Built without `escrow`, no arbiter is ever stored.
+    #[cfg(all(not(feature = "escrow"), any(feature = "partial", feature = "views")))]
+    pub fn get_escrow_arbiter(&self) -> Option<Address> {
+        None
+    }
+
+    #[cfg(feature = "escrow")]
+    pub fn get_hold_ledgers(&self) -> u32 {
+        self.get_cfg(&ConfigDataKey::HoldLedgers).unwrap_or(0)
+    }
+
+    #[cfg(feature = "config")]
+    pub fn set_grace_ledgers(&self, grace_ledgers: u32) {
+        self.set_cfg(&ConfigDataKey::GraceLedgers, &grace_ledgers);
+    }
+
+    #[cfg(feature = "config")]
+    pub fn get_grace_ledgers(&self) -> u32 {
+        self.get_cfg(&ConfigDataKey::GraceLedgers).unwrap_or(0)
+    }
+
+    /// Built without `config`, no grace period is ever stored.
+    #[cfg(not(feature = "config"))]
+    pub fn get_grace_ledgers(&self) -> u32 {
+        0
+    }
+
+    #[cfg(feature = "escrow")]
+    pub fn set_clawback_holds(&self, holds: &Map<Address, u32>) {
+        self.set_cfg(&ConfigDataKey::ClawbackHolds, holds);
+    }
+
+    /// Sweep hold configured for `asset` (`0` if it cannot be clawed back).
+    #[cfg(feature = "escrow")]
+    pub fn get_clawback_hold(&self, asset: &Address) -> u32 {
+        self.get_cfg::<Map<Address, u32>>(&ConfigDataKey::ClawbackHolds)
+            .and_then(|holds| holds.get(asset.clone()))
+            .unwrap_or(0)
+    }
+
+    // Protocol fee
+    #[cfg(feature = "fees")]
+    pub fn set_fee(&self, collector: &Address, fee_bps: u32) {
+        self.set_cfg(&ConfigDataKey::FeeCollector, collector);
+        self.set_cfg(&ConfigDataKey::FeeBps, &fee_bps);
+    }
+
+    #[cfg(feature = "fees")]
+    pub fn get_fee_collector(&self) -> Option<Address> {
+        self.get_cfg(&ConfigDataKey::FeeCollector)
+    }
+
+    /// Built without `fees`, no fee collector is ever stored.
+    #[cfg(all(not(feature = "fees"), feature = "views"))]
+    pub fn get_fee_collector(&self) -> Option<Address> {
+        None
+    }
+
+    #[cfg(feature = "fees")]
+    pub fn get_fee_bps(&self) -> u32 {
+        self.get_cfg(&ConfigDataKey::FeeBps).unwrap_or(0)
+    }
+
+    #[cfg(feature = "config")]
+    pub fn set_batch_window(&self, min_amount: i128, max_wait_ledgers: u32) {
+        self.set_cfg(&ConfigDataKey::BatchMinAmount, &min_amount);
+        self.set_cfg(&ConfigDataKey::BatchMaxWait, &max_wait_ledgers);
+    }
+
+    #[cfg(feature = "config")]
+    pub fn get_batch_min_amount(&self) -> i128 {
+        self.get_cfg(&ConfigDataKey::BatchMinAmount).unwrap_or(0)
+    }
+
+    /// Built without `config`, no batching window is ever stored.
+    #[cfg(not(feature = "config"))]
+    pub fn get_batch_min_amount(&self) -> i128 {
+        0
+    }
+
+    #[cfg(feature = "config")]
+    pub fn get_batch_max_wait(&self) -> u32 {
+        self.get_cfg(&ConfigDataKey::BatchMaxWait).unwrap_or(0)
+    }
+
+    /// Built without `config`, no batching window is ever stored.
+    #[cfg(not(feature = "config"))]
+    pub fn get_batch_max_wait(&self) -> u32 {
+        0
+    }
+
+    // Minimum sweep value
+    #[cfg(feature = "oracle")]
+    pub fn set_min_sweep_value(&self, oracle: &Address, min_value: i128) {
+        self.set_cfg(
+            &ConfigDataKey::SweepValueFloor,
+            &(oracle.clone(), min_value),
+        );
+    }
+
+    #[cfg(feature = "oracle")]
+    pub fn get_price_oracle(&self) -> Option<Address> {
+        self.get_cfg::<(Address, i128)>(&ConfigDataKey::SweepValueFloor)
+            .map(|(oracle, _)| oracle)
+    }
+
+    #[cfg(feature = "oracle")]
+    pub fn get_min_sweep_value(&self) -> i128 {
+        self.get_cfg::<(Address, i128)>(&ConfigDataKey::SweepValueFloor)
+            .map_or(0, |(_, min_value)| min_value)
+    }
+
+    /// Built without `oracle`, no minimum is ever stored.
+    #[cfg(all(not(feature = "oracle"), feature = "views"))]
+    pub fn get_min_sweep_value(&self) -> i128 {
+        0
+    }
+
+    pub fn set_trustline_count(&self, count: u32) {
+        self.set(&DataKey::TrustlineCount, &count);
+    }
+
+    pub fn get_trustline_count(&self) -> u32 {
+        self.get(&DataKey::TrustlineCount).unwrap_or(0)
+    }
+
+    #[cfg(feature = "keeper")]
+    pub fn set_access_policy(&self, policy: AuthPolicy, keeper: &Option<Address>) {
+        self.set_cfg(&ConfigDataKey::AccessPolicy, &(policy, keeper.clone()));
+    }
+
+    pub fn get_state_seq(&self) -> u64 {
+        self.get_ext(&ExtDataKey::StateSeq).unwrap_or(0)
+    }
+
+    /// Bump the state sequence number, once per state-changing call.
+    pub fn next_state_seq(&self) {
+        self.set_ext(&ExtDataKey::StateSeq, &(self.get_state_seq() + 1));
+    }
+
+    #[cfg(feature = "keeper")]
+    pub fn set_keeper_bounty_bps(&self, bps: u32) {
+        self.set_cfg(&ConfigDataKey::KeeperBountyBps, &bps);
+    }
+
+    #[cfg(feature = "keeper")]
+    pub fn get_keeper_bounty_bps(&self) -> u32 {
+        self.get_cfg(&ConfigDataKey::KeeperBountyBps).unwrap_or(0)
+    }
+
+    /// Built without `keeper`, no keeper bounty is ever stored.
+    #[cfg(not(feature = "keeper"))]
+    pub fn get_keeper_bounty_bps(&self) -> u32 {
+        0
+    }
+
+    #[cfg(feature = "keeper")]
+    pub fn set_keeper_bounty_ramp(&self, max_bps: u32, ramp_ledgers: u32) {
+        self.set_cfg(&ConfigDataKey::KeeperBountyRamp, &(max_bps, ramp_ledgers));
+    }
+
+    /// Cap and ramp length of the escalating keeper bounty, if any.
+    #[cfg(feature = "keeper")]
+    pub fn get_keeper_bounty_ramp(&self) -> Option<(u32, u32)> {
+        self.get_cfg(&ConfigDataKey::KeeperBountyRamp)
+    }
+
+    // Settlement hook
+    #[cfg(feature = "hooks")]
+    pub fn set_settlement_hook(&self, hook: &Address) {
+        self.set_cfg(&ConfigDataKey::SettlementHook, hook);
+    }
+
+    #[cfg(feature = "hooks")]
+    pub fn get_settlement_hook(&self) -> Option<Address> {
+        self.get_cfg(&ConfigDataKey::SettlementHook)
+    }
+
+    #[cfg(feature = "compliance")]
+    pub fn set_compliance_gate(&self, gate: &Address) {
+        self.set_cfg(&ConfigDataKey::ComplianceGate, gate);
+    }
+
+    #[cfg(feature = "compliance")]
+    pub fn get_compliance_gate(&self) -> Option<Address> {
+        self.get_cfg(&ConfigDataKey::ComplianceGate)
+    }
+
+    /// Built without `hooks`, no settlement hook is ever stored.
+    #[cfg(all(not(feature = "hooks"), feature = "views"))]
+    pub fn get_settlement_hook(&self) -> Option<Address> {
+        None
+    }
+
+    // Re-use
+    #[cfg(feature = "reuse")]
+    pub fn set_factory(&self, factory: &Address) {
+        self.set_ext(&ExtDataKey::Factory, factory);
+    }
+
+    #[cfg(feature = "reuse")]
+    pub fn get_factory(&self) -> Option<Address> {
+        self.get_ext(&ExtDataKey::Factory)
+    }
+
+    /// Built without `reuse`, no factory is ever stored.
+    #[cfg(all(not(feature = "reuse"), feature = "views"))]
+    pub fn get_factory(&self) -> Option<Address> {
+        None
+    }
+
+    // Insurance
+    #[cfg(feature = "insurance")]
+    pub fn set_insurance_pool(&self, pool: &Address) {
+        self.set_ext(&ExtDataKey::InsurancePool, pool);
+    }
+
+    #[cfg(feature = "insurance")]
+    pub fn get_insurance_pool(&self) -> Option<Address> {
+        self.get_ext(&ExtDataKey::InsurancePool)
+    }
+
+    // Dust
+    #[cfg(feature = "dust")]
+    pub fn set_dust_threshold(&self, oracle: &Address, threshold: i128) {
+        self.set_cfg(&ConfigDataKey::DustThreshold, &(oracle.clone(), threshold));
+    }
+
+    #[cfg(feature = "dust")]
+    pub fn get_dust_threshold(&self) -> Option<(Address, i128)> {
+        self.get_cfg(&ConfigDataKey::DustThreshold)
+    }
+
+    /// Built without `dust`, every asset is swept.
+    #[cfg(all(not(feature = "dust"), feature = "views"))]
+    pub fn get_dust_threshold(&self) -> Option<(Address, i128)> {
+        None
+    }
+
+    #[cfg(feature = "dust")]
+    pub fn set_dust(&self, payments: &Vec<Payment>) {
+        if payments.is_empty() {
+            self.store().remove(&ExtDataKey::Dust);
+        } else {
+            self.set_ext(&ExtDataKey::Dust, payments);
+        }
+    }
+
+    #[cfg(feature = "dust")]
+    pub fn get_dust(&self) -> Vec<Payment> {
+        self.get_ext(&ExtDataKey::Dust)
+            .unwrap_or_else(|| Vec::new(self.env))
+    }
+
+    // Depeg guard
+    #[cfg(feature = "depeg")]
+    pub fn set_depeg_guard(&self, oracle: &Address, pegs: &Map<Address, i128>, threshold_bps: u32) {
+        self.set_cfg(
+            &ConfigDataKey::DepegGuard,
+            &(oracle.clone(), pegs.clone(), threshold_bps),
+        );
+    }
+
+    /// Oracle, guarded stablecoins with their pegs, and allowed deviation.
+    #[cfg(feature = "depeg")]
+    pub fn get_depeg_guard(&self) -> Option<(Address, Map<Address, i128>, u32)> {
+        self.get_cfg(&ConfigDataKey::DepegGuard)
+    }
+
+    #[cfg(feature = "depeg")]
+    pub fn set_depeg_override(&self, until_ledger: u32) {
+        self.set_ext(&ExtDataKey::DepegOverride, &until_ledger);
+    }
+
+    #[cfg(feature = "depeg")]
+    pub fn get_depeg_override(&self) -> Option<u32> {
+        self.get_ext(&ExtDataKey::DepegOverride)
+    }
+
+    /// Built without `depeg`, no stablecoin is ever guarded.
+    #[cfg(all(not(feature = "depeg"), feature = "views"))]
+    pub fn get_depeg_guard(&self) -> Option<(Address, Map<Address, i128>, u32)> {
+        None
+    }
+
+    // Quarantine
+    #[cfg(feature = "quarantine")]
+    pub fn set_quarantine_mode(&self, enabled: bool) {
+        self.set_cfg(&ConfigDataKey::QuarantineMode, &enabled);
+    }
+
+    #[cfg(feature = "quarantine")]
+    pub fn is_quarantine_mode(&self) -> bool {
+        self.get_cfg(&ConfigDataKey::QuarantineMode)
+            .unwrap_or(false)
+    }
+
+    /// Built without `quarantine`, failed transfers always revert.
+    #[cfg(not(feature = "quarantine"))]
+    pub fn is_quarantine_mode(&self) -> bool {
+        false
+    }
+
+    #[cfg(feature = "quarantine")]
+    pub fn set_quarantined(&self, payments: &Vec<Payment>) {
+        if payments.is_empty() {
+            self.store().remove(&ExtDataKey::Quarantined);
+        } else {
+            self.set_ext(&ExtDataKey::Quarantined, payments);
+        }
+    }
+
+    #[cfg(feature = "quarantine")]
+    pub fn get_quarantined(&self) -> Vec<Payment> {
+        self.get_ext(&ExtDataKey::Quarantined)
+            .unwrap_or_else(|| Vec::new(self.env))
+    }
+
+    #[cfg(feature = "quarantine")]
+    pub fn set_quarantine_retries(&self, retries: &Map<Address, QuarantineRetry>) {
+        if retries.is_empty() {
+            self.store().remove(&ExtDataKey::QuarantineRetries);
+        } else {
+            self.set_ext(&ExtDataKey::QuarantineRetries, retries);
+        }
+    }
+
+    #[cfg(feature = "quarantine")]
+    pub fn get_quarantine_retries(&self) -> Map<Address, QuarantineRetry> {
+        self.get_ext(&ExtDataKey::QuarantineRetries)
+            .unwrap_or_else(|| Map::new(self.env))
+    }
+
+    pub fn get_epoch(&self) -> u32 {
+        self.get_ext(&ExtDataKey::Epoch).unwrap_or(0)
+    }
+
+    #[cfg(feature = "config")]
+    pub fn set_reserve_config_epoch(&self, epoch: u32) {
+        self.set_cfg(&ConfigDataKey::ReserveConfigEpoch, &epoch);
+    }
+
+    #[cfg(feature = "config")]
+    pub fn get_reserve_config_epoch(&self) -> Option<u32> {
+        self.get_cfg(&ConfigDataKey::ReserveConfigEpoch)
+    }
+
+    /// Start the next epoch and return its number.
+    #[cfg(feature = "reuse")]
+    pub fn next_epoch(&self) -> u32 {
+        let epoch = self.get_epoch() + 1;
+        self.set_ext(&ExtDataKey::Epoch, &epoch);
+        epoch
+    }
+
+    #[cfg(feature = "keeper")]
+    pub fn get_auth_policy(&self) -> AuthPolicy {
+        self.get_cfg::<(AuthPolicy, Option<Address>)>(&ConfigDataKey::AccessPolicy)
+            .map_or(AuthPolicy::Permissionless, |(policy, _)| policy)
+    }
+
+    /// Built without `keeper`, no auth policy is ever stored.
+    #[cfg(not(feature = "keeper"))]
+    pub fn get_auth_policy(&self) -> AuthPolicy {
+        AuthPolicy::Permissionless
+    }
+
+    #[cfg(feature = "keeper")]
+    pub fn get_keeper(&self) -> Option<Address> {
+        self.get_cfg::<(AuthPolicy, Option<Address>)>(&ConfigDataKey::AccessPolicy)
+            .and_then(|(_, keeper)| keeper)
+    }
+
+    /// Built without `keeper`, no keeper is ever stored.
+    #[cfg(not(feature = "keeper"))]
+    pub fn get_keeper(&self) -> Option<Address> {
+        None
+    }
+
+    #[cfg(feature = "config")]
+    pub fn set_metadata(&self, metadata: &AccountMetadata) {
+        self.set_cfg(&ConfigDataKey::Metadata, metadata);
+    }
+
+    #[cfg(feature = "config")]
+    pub fn get_metadata(&self) -> Option<AccountMetadata> {
+        self.get_cfg(&ConfigDataKey::Metadata)
+    }
+
+    /// Built without `config`, no metadata is ever stored.
+    #[cfg(not(feature = "config"))]
+    pub fn get_metadata(&self) -> Option<AccountMetadata> {
+        None
+    }
+
+    /// Note a payment recorded at `ledger`.  `first` marks the payment
+    /// that moved the account to `PaymentReceived`.
+    pub fn set_payment_ledger(&self, ledger: u32, first: bool) {
+        let first_ledger = match self.get_first_payment_ledger() {
+            Some(first_ledger) if !first => first_ledger,
+            _ => ledger,
+        };
+        self.set(&DataKey::PaymentLedgers, &(first_ledger, ledger));
+    }
+
+    pub fn get_first_payment_ledger(&self) -> Option<u32> {
+        self.get::<(u32, u32)>(&DataKey::PaymentLedgers)
+            .map(|(first, _)| first)
+    }
+
+    pub fn get_last_payment_ledger(&self) -> Option<u32> {
+        self.get::<(u32, u32)>(&DataKey::PaymentLedgers)
+            .map(|(_, last)| last)
+    }
+
+    #[cfg(feature = "config")]
+    pub fn set_locked_destination(&self, destination: &Address) {
+        self.set_cfg(&ConfigDataKey::LockedDestination, destination);
+    }
+
+    #[cfg(feature = "config")]
+    pub fn get_locked_destination(&self) -> Option<Address> {
+        self.get_cfg(&ConfigDataKey::LockedDestination)
+    }
+
+    /// Built without `config`, no locked destination is ever stored.
+    #[cfg(not(feature = "config"))]
+    pub fn get_locked_destination(&self) -> Option<Address> {
+        None
+    }
+
+    // Guardian
+    #[cfg(feature = "guardian")]
+    pub fn set_guardian(&self, config: &GuardianConfig) {
+        self.set_cfg(&ConfigDataKey::Guardian, config);
+    }
+
+    #[cfg(feature = "guardian")]
+    pub fn get_guardian_config(&self) -> Option<GuardianConfig> {
+        self.get_cfg(&ConfigDataKey::Guardian)
+    }
+
+    /// Built without `guardian`, no guardian is ever stored.
+    #[cfg(all(not(feature = "guardian"), feature = "views"))]
+    pub fn get_guardian_config(&self) -> Option<GuardianConfig> {
+        None
+    }
+
+    #[cfg(feature = "guardian")]
+    pub fn get_guardian(&self) -> Option<Address> {
+        self.get_guardian_config().map(|config| config.guardian)
+    }
+
+    #[cfg(feature = "guardian")]
+    pub fn get_destination_change_delay(&self) -> u32 {
+        self.get_guardian_config()
+            .map_or(0, |config| config.destination_change_delay)
+    }
+
+    #[cfg(feature = "views")]
+    pub fn get_emergency_ledger(&self) -> Option<u32> {
+        self.get_guardian_config()
+            .and_then(|config| config.emergency_ledger)
+    }
+
+    #[cfg(feature = "guardian")]
+    pub fn set_pending_destination(&self, pending: &PendingDestination) {
+        self.set(&DataKey::PendingDestination, pending);
+    }
+
+    #[cfg(feature = "guardian")]
+    pub fn get_pending_destination(&self) -> Option<PendingDestination> {
+        self.get(&DataKey::PendingDestination)
+    }
+
+    #[cfg(feature = "guardian")]
+    pub fn remove_pending_destination(&self) {
+        self.store().remove(&DataKey::PendingDestination);
+    }
+
+    #[cfg(feature = "escrow")]
+    pub fn set_pending_release(&self, pending: &PendingRelease) {
+        self.set(&DataKey::PendingRelease, pending);
+    }
+
+    #[cfg(feature = "escrow")]
+    pub fn get_pending_release(&self) -> Option<PendingRelease> {
+        self.get(&DataKey::PendingRelease)
+    }
+
+    #[cfg(feature = "escrow")]
+    pub fn remove_pending_release(&self) {
+        self.store().remove(&DataKey::PendingRelease);
+    }
+
+    // Sweep authorization
+    pub fn set_sweep_signers(&self, signers: &Vec<BytesN<32>>, threshold: u32) {
+        self.set(&DataKey::SweepSigners, signers);
+        self.set(&DataKey::SweepThreshold, &threshold);
+    }
+
+    pub fn get_sweep_signers(&self) -> Vec<BytesN<32>> {
+        self.get(&DataKey::SweepSigners)
+            .unwrap_or_else(|| Vec::new(self.env))
+    }
+
+    pub fn get_sweep_threshold(&self) -> u32 {
+        self.get(&DataKey::SweepThreshold).unwrap_or(0)
+    }
+
+    pub fn set_signer_kind(&self, kind: SignerKind) {
+        self.set(&DataKey::SignerKind, &kind);
+    }
+
+    #[cfg(any(feature = "views", feature = "passkey"))]
+    pub fn get_signer_kind(&self) -> SignerKind {
+        self.get(&DataKey::SignerKind).unwrap_or_default()
+    }
+
+    #[cfg(feature = "passkey")]
+    pub fn set_passkey_signer(&self, key: &BytesN<65>) {
+        self.set_cfg(&ConfigDataKey::PasskeySigner, key);
+    }
+
+    #[cfg(feature = "passkey")]
+    pub fn get_passkey_signer(&self) -> Option<BytesN<65>> {
+        self.get_cfg(&ConfigDataKey::PasskeySigner)
+    }
+
+    /// Built without `passkey`, no passkey is ever stored.
+    #[cfg(all(not(feature = "passkey"), feature = "views"))]
+    pub fn get_passkey_signer(&self) -> Option<BytesN<65>> {
+        None
+    }
+
+    pub fn get_sweep_nonce(&self) -> u64 {
+        self.get(&DataKey::SweepNonce).unwrap_or(0)
+    }
+
+    pub fn increment_sweep_nonce(&self) {
+        self.set(&DataKey::SweepNonce, &(self.get_sweep_nonce() + 1));
+    }
+
+    // Partially swept assets
+    pub fn get_swept_assets(&self) -> Vec<Address> {
+        #[cfg(feature = "partial")]
+        if let Some(assets) = self.get(&DataKey::SweptAssets) {
+            return assets;
+        }
+        Vec::new(self.env)
+    }
+
+    #[cfg(feature = "partial")]
+    pub fn set_swept_assets(&self, assets: &Vec<Address>) {
+        self.set(&DataKey::SweptAssets, assets);
+    }
+
+    #[cfg(feature = "partial")]
+    pub fn set_partial_sweep_to(&self, destination: &Address) {
+        self.set_ext(&ExtDataKey::PartialSweepTo, destination);
+    }
+
+    #[cfg(all(feature = "partial", feature = "refunds"))]
+    pub fn get_partial_sweep_to(&self) -> Option<Address> {
+        self.get_ext(&ExtDataKey::PartialSweepTo)
+    }
+
+    #[cfg(feature = "prepared")]
+    pub fn set_prepared_sweep(&self, prepared: &PreparedSweep) {
+        self.set_ext(&ExtDataKey::PreparedSweep, prepared);
+    }
+
+    #[cfg(feature = "prepared")]
+    pub fn get_prepared_sweep(&self) -> Option<PreparedSweep> {
+        self.get_ext(&ExtDataKey::PreparedSweep)
+    }
+
+    #[cfg(feature = "prepared")]
+    pub fn remove_prepared_sweep(&self) {
+        self.store().remove(&ExtDataKey::PreparedSweep);
+    }
+
+    // Sweep priority
+    #[cfg(feature = "limits")]
+    pub fn set_sweep_priority(&self, assets: &Vec<Address>) {
+        self.set(&DataKey::SweepPriority, assets);
+    }
+
+    pub fn get_sweep_priority(&self) -> Vec<Address> {
+        #[cfg(feature = "limits")]
+        if let Some(assets) = self.get(&DataKey::SweepPriority) {
+            return assets;
+        }
+        Vec::new(self.env)
+    }
+
+    // External chain reference
+    #[cfg(feature = "config")]
+    pub fn set_external_chain_ref(&self, link: &ChainLink) {
+        self.set_cfg(&ConfigDataKey::ExternalChainRef, link);
+    }
+
+    #[cfg(feature = "config")]
+    pub fn get_external_chain_ref(&self) -> ChainLink {
+        self.get_cfg(&ConfigDataKey::ExternalChainRef)
+            .unwrap_or_default()
+    }
+
+    /// Built without `config`, no external chain reference is ever stored.
+    #[cfg(not(feature = "config"))]
+    pub fn get_external_chain_ref(&self) -> ChainLink {
+        ChainLink::Unlinked
+    }
+
+    // Status
+    pub fn set_status(&self, status: AccountStatus) {
+        self.set(&DataKey::Status, &status);
+    }
+
+    pub fn get_status(&self) -> AccountStatus {
+        self.get(&DataKey::Status).unwrap_or(AccountStatus::Active)
+    }
+
+    // Swept to
+    pub fn set_swept_to(&self, address: &Address) {
+        self.set(&DataKey::SweptTo, address);
+    }
+
+    pub fn get_swept_to(&self) -> Option<Address> {
+        self.get(&DataKey::SweptTo)
+    }
+
+    // Reserve lifecycle
+    pub fn init_reserve_tracking(&self, base_reserve: i128) {
+        self.set_base_reserve_remaining(base_reserve);
+        self.set_available_reserve(base_reserve);
+        self.set_reserve_reclaimed(base_reserve == 0);
+        self.set_reserve_summary(&ReserveReclaimSummary::default());
+    }
+
+    /// Forget what the last cycle recorded: payments, sweep results,
+    /// quarantined and dust payments, the depeg override, pending holds and
+    /// reserve history.  Configuration, the sweep nonce
+    /// and the state sequence number carry over, so old signatures stay
+    /// spent and event numbering stays gap-free.
+    #[cfg(feature = "reuse")]
+    pub fn clear_cycle(&self) {
+        self.clear_payments();
+        for slot in 0..RESERVE_EVENT_HISTORY {
+            self.persistent().remove(&DataKey::ReserveEvent(slot));
+        }
+        for key in [
+            DataKey::SweptTo,
+            DataKey::LastSweepId,
+            DataKey::SweptAssets,
+            DataKey::PaymentsRoot,
+            DataKey::PaymentLedgers,
+            DataKey::PendingRelease,
+            DataKey::PendingDestination,
+        ] {
+            self.store().remove(&key);
+        }
+        // What the last sweep left behind is owed to its destination, not
+        // to the next cycle's, even once the balance itself is gone
+        for key in [
+            ExtDataKey::Quarantined,
+            ExtDataKey::QuarantineRetries,
+            ExtDataKey::Dust,
+            ExtDataKey::DepegOverride,
+            ExtDataKey::PartialSweepTo,
+            ExtDataKey::PreparedSweep,
+            // The premium paid for the first cycle only
+            ExtDataKey::InsurancePool,
+        ] {
+            self.store().remove(&key);
+        }
+        // The reserve was reclaimed in full; the new cycle tracks none
+        self.init_reserve_tracking(0);
+        self.set_reserve_reclaimed(false);
+        self.set_native_reserve_held(false);
+    }
+
+    pub fn set_base_reserve_remaining(&self, amount: i128) {
+        self.set(&DataKey::BaseReserveRemaining, &amount);
+    }
+
+    /// Fields checked by the shared account invariants.
+    #[cfg(any(test, feature = "invariants"))]
+    pub fn snapshot(&self) -> bridgelet_shared::AccountSnapshot {
+        bridgelet_shared::AccountSnapshot {
+            status: self.get_status(),
+            payment_count: self.get_total_payments(),
+            swept_to: self.get_swept_to(),
+            reserve_remaining: self.get_base_reserve_remaining(),
+            reserve_reclaimed: self.is_reserve_reclaimed(),
+        }
+    }
+
+    pub fn get_base_reserve_remaining(&self) -> i128 {
+        self.get(&DataKey::BaseReserveRemaining).unwrap_or(0)
+    }
+
+    pub fn set_available_reserve(&self, amount: i128) {
+        self.set(&DataKey::AvailableReserve, &amount);
+    }
+
+    pub fn get_available_reserve(&self) -> i128 {
+        self.get(&DataKey::AvailableReserve).unwrap_or(0)
+    }
+
+    pub fn set_reserve_reclaimed(&self, reclaimed: bool) {
+        self.set(&DataKey::ReserveReclaimed, &reclaimed);
+    }
+
+    pub fn is_reserve_reclaimed(&self) -> bool {
+        self.get(&DataKey::ReserveReclaimed).unwrap_or(false)
+    }
+
+    pub fn set_last_sweep_id(&self, sweep_id: &BytesN<32>) {
+        self.set(&DataKey::LastSweepId, sweep_id);
+    }
+
+    pub fn get_last_sweep_id(&self) -> Option<BytesN<32>> {
+        self.get(&DataKey::LastSweepId)
+    }
+
+    pub fn set_reserve_summary(&self, summary: &ReserveReclaimSummary) {
+        self.set(&DataKey::ReserveReclaimSummary, summary);
+    }
+
+    pub fn get_reserve_summary(&self) -> ReserveReclaimSummary {
+        self.get(&DataKey::ReserveReclaimSummary)
+            .unwrap_or_default()
+    }
+
+    /// Store reserve event number `seq`, overwriting the one
+    /// `RESERVE_EVENT_HISTORY` events before it.
+    pub fn set_reserve_event(&self, seq: u32, event: &ReserveReclaimed) {
+        self.set_persistent(&DataKey::ReserveEvent(seq % RESERVE_EVENT_HISTORY), event);
+    }
+
+    /// Reserve event number `seq`.  Only meaningful for the last
+    /// `RESERVE_EVENT_HISTORY` events; older ones have been overwritten.
+    pub fn get_reserve_event(&self, seq: u32) -> Option<ReserveReclaimed> {
+        self.get_persistent(&DataKey::ReserveEvent(seq % RESERVE_EVENT_HISTORY))
+    }
+
+    /// Turn the reserve tracking of a version 0 account into the current
+    /// one: ledger-sequence sweep ids become `legacy_sweep_id`s, and the
+    /// event count and latest event the first release kept become the
+    /// summary and the ring buffer slot of that event.
+    ///
+    /// The first release reclaimed from `initial_reserve` in full on the
+    /// first reclaim, so whatever was reclaimed took exactly one reclaim.
+    #[cfg(feature = "upgrade")]
+    pub fn migrate_reserve_v0(&self, initial_reserve: i128) {
+        if let Some(raw) = self.get::<Val>(&DataKey::LastSweepId) {
+            match u64::try_from_val(self.env, &raw) {
+                // 0 stood for "no sweep yet"
+                Ok(0) => self.store().remove(&DataKey::LastSweepId),
+                Ok(ledger) => self.set_last_sweep_id(&legacy_sweep_id(self.env, ledger)),
+                Err(_) => {}
+            }
+        }
+
+        let count_key = v0_key(self.env, "ReserveEventCount");
+        let last_key = v0_key(self.env, "LastReserveEvent");
+        let Some(event_count) = self.store().get::<_, u32>(&count_key) else {
+            return;
+        };
+        if let Some(old) = self.store().get::<_, ReserveReclaimedV0>(&last_key) {
+            if event_count > 0 {
+                self.set_reserve_event(
+                    event_count - 1,
+                    &ReserveReclaimed {
+                        destination: old.destination,
+                        amount: old.amount,
+                        sweep_id: legacy_sweep_id(self.env, old.sweep_id),
+                        fully_reclaimed: old.fully_reclaimed,
+                        remaining_reserve: old.remaining_reserve,
+                        keeper: None,
+                        keeper_bounty: 0,
+                    },
+                );
+            }
+        }
+        self.store().remove(&count_key);
+        self.store().remove(&last_key);
 
-pub fn get_last_reserve_event(env: &Env) -> Option<ReserveReclaimed> {
-    env.storage().instance().get(&DataKey::LastReserveEvent)
+        let total_reclaimed = initial_reserve - self.get_base_reserve_remaining();
+        self.set_reserve_summary(&ReserveReclaimSummary {
+            total_reclaimed,
+            reclaim_count: u32::from(total_reclaimed > 0),
+            event_count,
+        });
+    }
 }
@@ -2,20 +2,258 @@
 mod test {
     extern crate std;
 
+    #[cfg(all(feature = "history", feature = "reuse", feature = "views"))]
+    use crate::AccountReset;
+    #[cfg(all(feature = "native", feature = "views"))]
+    use crate::AssetKind;
+    #[cfg(all(feature = "audit", feature = "views"))]
+    use crate::AuditTrail;
+    #[cfg(all(
+        feature = "fees",
+        feature = "limits",
+        feature = "multisig",
+        feature = "views"
+    ))]
+    use crate::Capabilities;
+    #[cfg(feature = "depeg")]
+    use crate::DepegOverridden;
+    #[cfg(all(feature = "guardian", feature = "views"))]
+    use crate::EmergencyRecovered;
+    #[cfg(feature = "diagnostics")]
+    use crate::FailureDiagnostic;
+    #[cfg(feature = "hooks")]
+    use crate::HookFailed;
+    #[cfg(feature = "insurance")]
+    use crate::InsuredExpiry;
+    #[cfg(all(feature = "config", feature = "lifecycle"))]
+    use crate::Lifecycle;
+    #[cfg(all(feature = "attribution", feature = "refunds"))]
+    use crate::PaymentRefunded;
+    #[cfg(feature = "passkey")]
+    use crate::SignerKind;
+    #[cfg(feature = "fees")]
+    use crate::SweepAmount;
+    #[cfg(any(feature = "config", feature = "views", feature = "limits"))]
+    use crate::SweepExecutedMulti;
     use crate::{
-        storage, AccountStatus, EphemeralAccountContract, EphemeralAccountContractClient,
-        ReserveReclaimed,
+        storage::AccountState, AccountExpired, AccountStatus, CloseReason, DeploymentSettings,
+        EphemeralAccountContract, EphemeralAccountContractClient, Error, Invariant,
+        PaymentReceived, ReserveReclaimed,
     };
-    use soroban_sdk::{testutils::Address as _, Address, BytesN, Env};
+    #[cfg(feature = "upgrade")]
+    use crate::{
+        storage::{legacy_sweep_id, v0_key, PaymentV0, ReserveReclaimedV0},
+        DataKey,
+    };
+    #[cfg(feature = "lifecycle")]
+    use crate::{AccountCancelled, ExpiryExtended};
+    #[cfg(feature = "config")]
+    use crate::{
+        AccountConfig, AccountCreated, AccountLabel, AccountMetadata, ChainLink, ExternalChainRef,
+        PaymentToppedUp,
+    };
+    #[cfg(feature = "quarantine")]
+    use crate::{AssetsQuarantined, QuarantineReleased, QuarantineRetry, QuarantineRetryFailed};
+    #[cfg(all(feature = "escrow", feature = "partial"))]
+    use crate::{ClawbackHeld, ClawbackReleased, PaymentClawedBack};
+    #[cfg(feature = "guardian")]
+    use crate::{DestinationChanged, PendingDestination};
+    #[cfg(feature = "dust")]
+    use crate::{DustLeft, DustSwept};
+    #[cfg(feature = "partial")]
+    use crate::{PartialSweepExecuted, SplitSweepExecuted};
+    #[cfg(feature = "escrow")]
+    use crate::{PendingRelease, SweepDisputed, SweepHeld};
+    #[cfg(feature = "prepared")]
+    use crate::{PreparedSweep, PreparedSweepCancelled, SweepPrepared, PREPARED_SWEEP_LEDGERS};
+    #[cfg(feature = "reconcile")]
+    use crate::{ReserveReconciled, ReserveToppedUp};
+    #[cfg(feature = "views")]
+    use bridgelet_shared::errors::UNKNOWN_ERROR;
+    #[cfg(feature = "upgrade")]
+    use bridgelet_shared::Payment;
+    use bridgelet_shared::{
+        errors::{EPHEMERAL_ACCOUNT_ERRORS, KEEPER_BOUNTY_UNPAYABLE},
+        EventTopics, OracleAsset, PriceData,
+    };
+    #[cfg(all(feature = "config", feature = "views"))]
+    use bridgelet_shared::{EphemeralAccountClient, ReserveClient};
+    #[cfg(feature = "compliance")]
+    use compliance_gate::{ComplianceGate, ComplianceGateClient, Role, VelocityLimits, Volume};
+    use ed25519_dalek::{Signer, SigningKey};
+    #[cfg(feature = "insurance")]
+    use insurance_pool::{ClaimStatus, InsurancePool, InsurancePoolClient};
+    use kill_switch::{KillSwitch, KillSwitchClient, ALL_SUBSYSTEMS};
+    #[cfg(all(feature = "keeper", feature = "views"))]
+    use reserve_contract::ConfigKey;
+    #[cfg(feature = "config")]
+    use reserve_contract::{ReserveContract, ReserveContractClient};
+    #[cfg(feature = "views")]
+    use soroban_sdk::xdr::{ContractDataDurability, LedgerKey};
+    #[cfg(feature = "auth")]
+    use soroban_sdk::{
+        auth::{Context, ContractContext},
+        xdr::{
+            HashIdPreimage, HashIdPreimageSorobanAuthorization, InvokeContractArgs, Limits,
+            ScSymbol, ScVal, SorobanAddressCredentials, SorobanAuthorizationEntry,
+            SorobanAuthorizedFunction, SorobanAuthorizedInvocation, SorobanCredentials, VecM,
+            WriteXdr,
+        },
+    };
+    use soroban_sdk::{
+        contract, contractimpl, symbol_short,
+        testutils::{Address as _, Events, Ledger},
+        token::{StellarAssetClient, TokenClient},
+        vec,
+        xdr::ToXdr,
+        Address, BytesN, Env, IntoVal, Map, Symbol, TryFromVal, Val, Vec,
+    };
+    #[cfg(any(feature = "auth", feature = "views"))]
+    use soroban_sdk::{xdr::ScAddress, Bytes};
 
     const BASE_RESERVE_STROOPS: i128 = 1_000_000_000;
 
+    /// Deploy a Stellar asset contract and mint `amount` of it to `holder`.
+    fn fund_asset(env: &Env, holder: &Address, amount: i128) -> Address {
+        let issuer = Address::generate(env);
+        let asset = env.register_stellar_asset_contract_v2(issuer).address();
+        StellarAssetClient::new(env, &asset).mint(holder, &amount);
+        asset
+    }
+
+    /// Secret key of the test sweep signer.
+    fn sweep_signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn sweep_signer_key(env: &Env) -> BytesN<32> {
+        BytesN::from_array(env, &sweep_signing_key().verifying_key().to_bytes())
+    }
+
+    /// Sign a sweep to `destination`, building the message independently
+    /// of the contract: sha256(contract || destination || nonce || expiry).
+    fn sign_sweep(
+        env: &Env,
+        client: &EphemeralAccountContractClient,
+        destination: &Address,
+    ) -> Vec<BytesN<64>> {
+        let mut message = client.address.clone().to_xdr(env);
+        message.append(&destination.clone().to_xdr(env));
+        message.extend_from_array(&client.get_sweep_nonce().to_be_bytes());
+        message.extend_from_array(&client.get_info().expiry_ledger.to_be_bytes());
+        let digest = env.crypto().sha256(&message).to_array();
+        vec![
+            env,
+            BytesN::from_array(env, &sweep_signing_key().sign(&digest).to_bytes()),
+        ]
+    }
+
     fn latest_reserve_event(client: &EphemeralAccountContractClient) -> ReserveReclaimed {
         client
             .get_last_reserve_event()
             .expect("reserve event was not emitted")
     }
 
+    /// Fail the test if the account breaks a shared consistency rule.
+    fn assert_invariants(client: &EphemeralAccountContractClient) {
+        let env = &client.env;
+        let snapshot = env.as_contract(&client.address, || AccountState::new(env).snapshot());
+        assert_eq!(
+            bridgelet_shared::violations(env, &snapshot),
+            Vec::<Invariant>::new(env)
+        );
+    }
+
+    /// Integrators match on `Error(Contract, #n)`; the codes must never
+    /// move when variants are added or helpers are refactored.
+    #[test]
+    fn test_error_codes_are_stable() {
+        let codes = [
+            (Error::AlreadyInitialized, 1),
+            (Error::NotInitialized, 2),
+            (Error::PaymentAlreadyReceived, 3),
+            (Error::InvalidAmount, 4),
+            (Error::InvalidExpiry, 5),
+            (Error::NotExpired, 6),
+            (Error::AlreadySwept, 7),
+            (Error::Unauthorized, 8),
+            (Error::InvalidSignature, 9),
+            (Error::NoPaymentReceived, 10),
+            (Error::AccountExpired, 11),
+            (Error::InvalidStatus, 12),
+            (Error::DuplicateAsset, 13),
+            (Error::TooManyPayments, 14),
+            (Error::InvalidReason, 15),
+            (Error::AssetNotFound, 16),
+            (Error::InvalidShares, 17),
+            (Error::AssetNotAllowed, 18),
+            (Error::Halted, 19),
+            (Error::SenderUnknown, 20),
+            (Error::HoldNotElapsed, 21),
+            (Error::HoldElapsed, 22),
+            (Error::HoldRequired, 23),
+            (Error::ThresholdNotMet, 24),
+            (Error::InvalidSignerSet, 25),
+            (Error::DestinationMismatch, 26),
+            (Error::InvalidFee, 27),
+            (Error::BatchWindowOpen, 28),
+            (Error::TimelockActive, 29),
+            (Error::SystemPaused, 30),
+            (Error::StorageVersionMismatch, 31),
+            (Error::InsufficientNativeBalance, 32),
+            (Error::GracePeriodActive, 33),
+            (Error::BelowMinSweepValue, 34),
+            (Error::BelowMinPayment, 35),
+            (Error::PaymentCooldown, 36),
+            (Error::FundsRemaining, 37),
+            (Error::KeeperBountyUnpayable, 38),
+            (Error::ReserveNotSet, 39),
+            (Error::InvalidCursor, 40),
+            (Error::DestinationCannotReceive, 41),
+            (Error::FeatureDisabled, 42),
+            (Error::Depegged, 43),
+            (Error::RetryTooEarly, 44),
+            (Error::ComplianceHold, 45),
+            (Error::NoPreparedSweep, 46),
+            (Error::CommitmentMismatch, 47),
+        ];
+        for (error, code) in codes {
+            assert_eq!(
+                soroban_sdk::Error::from(error),
+                soroban_sdk::Error::from_contract_error(code)
+            );
+            assert!(EPHEMERAL_ACCOUNT_ERRORS.contains(&code));
+        }
+        // sweep_coordinator falls back to `expire` on this code
+        assert_eq!(Error::KeeperBountyUnpayable as u32, KEEPER_BOUNTY_UNPAYABLE);
+    }
+
+    #[test]
+    #[cfg(feature = "views")]
+    fn test_describe_error_names_every_code() {
+        let env = Env::default();
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        for code in EPHEMERAL_ACCOUNT_ERRORS {
+            let name = client.describe_error(&code);
+            if let Ok(error) = Error::try_from(soroban_sdk::Error::from_contract_error(code)) {
+                assert_eq!(name, Symbol::new(&env, error.name()));
+            } else {
+                assert_eq!(name, Symbol::new(&env, UNKNOWN_ERROR));
+            }
+        }
+        assert_eq!(
+            client.describe_error(&4),
+            Symbol::new(&env, "InvalidAmount")
+        );
+        // The reserve contract's codes are not this contract's
+        assert_eq!(
+            client.describe_error(&101),
+            Symbol::new(&env, UNKNOWN_ERROR)
+        );
+    }
+
     #[test]
     fn test_initialize() {
         let env = Env::default();
@@ -35,6 +273,254 @@ mod test {
         assert_eq!(client.get_reserve_remaining(), BASE_RESERVE_STROOPS);
         assert_eq!(client.get_reserve_available(), BASE_RESERVE_STROOPS);
         assert!(!client.is_reserve_reclaimed());
+
+        let info = client.get_info();
+        assert_eq!(info.wasm_version, crate::WASM_VERSION);
+        assert_eq!(info.config_version, crate::CONFIG_SCHEMA_VERSION);
+    }
+
+    #[test]
+    #[cfg(feature = "upgrade")]
+    fn test_upgrade_and_storage_migration() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        assert_eq!(
+            client.get_version(),
+            (crate::WASM_VERSION, symbol_short!("account"))
+        );
+        let unknown_hash = BytesN::from_array(&env, &[7u8; 32]);
+        assert_eq!(
+            client.try_upgrade(&unknown_hash),
+            Err(Ok(Error::NotInitialized))
+        );
+
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        assert!(client.try_upgrade(&unknown_hash).is_err());
+
+        // An account written before versioning is migrated by its next
+        // call, state-changing or not.
+        env.as_contract(&contract_id, || AccountState::new(&env).set_wasm_version(0));
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+        assert_eq!(client.get_info().wasm_version, crate::WASM_VERSION);
+
+        env.as_contract(&contract_id, || AccountState::new(&env).set_wasm_version(0));
+        assert_eq!(client.get_info().wasm_version, crate::WASM_VERSION);
+    }
+
+    #[test]
+    #[cfg(all(feature = "lifecycle", feature = "upgrade"))]
+    fn test_storage_version_guards() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        assert_eq!(client.get_storage_version(), crate::STORAGE_VERSION);
+
+        // Storage predating versioning: the first getter migrates it.
+        env.as_contract(&contract_id, || {
+            AccountState::new(&env).set_storage_version(0)
+        });
+        assert_eq!(client.get_info().status, AccountStatus::Active);
+        assert_eq!(client.get_storage_version(), crate::STORAGE_VERSION);
+
+        // Storage written by newer code is never touched.
+        env.as_contract(&contract_id, || {
+            AccountState::new(&env).set_storage_version(crate::STORAGE_VERSION + 1)
+        });
+        assert_eq!(client.try_migrate(), Err(Ok(Error::StorageVersionMismatch)));
+        assert_eq!(
+            client.try_get_info().err(),
+            Some(Ok(Error::StorageVersionMismatch))
+        );
+        assert_eq!(
+            client.try_extend_expiry(&(env.ledger().sequence() + 2000)),
+            Err(Ok(Error::StorageVersionMismatch))
+        );
+
+        // A key every account holds is missing.
+        env.as_contract(&contract_id, || {
+            AccountState::new(&env).set_storage_version(crate::STORAGE_VERSION);
+            env.storage().instance().remove(&DataKey::Creator);
+        });
+        assert_eq!(
+            client.try_extend_expiry(&(env.ledger().sequence() + 2000)),
+            Err(Ok(Error::StorageVersionMismatch))
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "upgrade"))]
+    fn test_older_storage_refused_without_upgrade_feature() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+
+        // No migration code is built in, so an older layout is never read.
+        env.as_contract(&contract_id, || {
+            AccountState::new(&env).set_storage_version(crate::STORAGE_VERSION - 1)
+        });
+        let asset = fund_asset(&env, &contract_id, 100);
+        assert_eq!(
+            client.try_record_payment(&100, &asset),
+            Err(Ok(Error::StorageVersionMismatch))
+        );
+        assert_eq!(
+            client.try_get_info().err(),
+            Some(Ok(Error::StorageVersionMismatch))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "config")]
+    fn test_initialize_reads_base_reserve_from_reserve_contract() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let reserve_id = env.register(ReserveContract, ());
+        let reserve = ReserveContractClient::new(&env, &reserve_id);
+        let admin = Address::generate(&env);
+        reserve.initialize(&admin);
+        reserve.set_base_reserve(&admin, &25_000_000);
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                reserve_contract: Some(reserve_id),
+                // Ignored while a reserve contract is supplied.
+                base_reserve: Some(1),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(client.get_reserve_remaining(), 25_000_000);
+        assert_eq!(client.get_reserve_available(), 25_000_000);
+    }
+
+    /// The tracked reserve covers the trustlines the account is opened
+    /// with; tokens paid beyond them raise the trustline count.
+    #[test]
+    #[cfg(all(feature = "keeper", feature = "views"))]
+    fn test_reserve_covers_configured_trustlines() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let reserve_id = env.register(ReserveContract, ());
+        let reserve = ReserveContractClient::new(&env, &reserve_id);
+        let admin = Address::generate(&env);
+        reserve.initialize(&admin);
+        reserve.set_base_reserve(&admin, &25_000_000);
+        reserve.set_trustline_reserve(&admin, &5_000_000);
+        reserve.set_i128(&admin, &ConfigKey::KeeperBountyBps, &500);
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                reserve_contract: Some(reserve_id),
+                trustlines: 1,
+                ..Default::default()
+            },
+        );
+        assert_eq!(client.get_reserve_remaining(), 30_000_000);
+        assert_eq!(client.get_trustline_count(), 1);
+        // Every value came from the snapshot of the third update
+        assert_eq!(client.get_reserve_config_epoch(), Some(3));
+        assert_eq!(client.get_epoch(), 0);
+
+        client.record_payment(&10, &fund_asset(&env, &contract_id, 10));
+        assert_eq!(client.get_trustline_count(), 1);
+        client.record_payment(&10, &fund_asset(&env, &contract_id, 10));
+        assert_eq!(client.get_trustline_count(), 2);
+        assert_eq!(client.get_reserve_remaining(), 30_000_000);
+
+        // The snapshot's keeper bounty applies, owed on the reserve
+        env.ledger().with_mut(|li| li.sequence_number += 1000);
+        client.expire_as(&Address::generate(&env));
+        assert_eq!(latest_reserve_event(&client).keeper_bounty, 1_500_000);
+    }
+
+    /// A reserve contract that was never configured aborts initialization.
+    #[test]
+    #[cfg(feature = "config")]
+    fn test_initialize_fails_when_reserve_contract_unset() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let reserve_id = env.register(ReserveContract, ());
+        ReserveContractClient::new(&env, &reserve_id).initialize(&Address::generate(&env));
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let result = client.try_initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                reserve_contract: Some(reserve_id),
+                ..Default::default()
+            },
+        );
+        assert_eq!(result, Err(Ok(Error::ReserveNotSet)));
+        assert_eq!(client.get_reserve_remaining(), 0);
+        assert_eq!(client.get_reserve_config_epoch(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "config")]
+    fn test_initialize_uses_explicit_base_reserve_override() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let config = AccountConfig {
+            base_reserve: Some(-1),
+            ..Default::default()
+        };
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        assert_eq!(
+            client.try_initialize_with_config(&creator, &expiry_ledger, &recovery, &config),
+            Err(Ok(Error::InvalidAmount))
+        );
+
+        let config = AccountConfig {
+            base_reserve: Some(0),
+            ..config
+        };
+        client.initialize_with_config(&creator, &expiry_ledger, &recovery, &config);
+        assert_eq!(client.get_reserve_remaining(), 0);
+        assert!(client.is_reserve_reclaimed());
     }
 
     #[test]
@@ -56,6 +542,46 @@ mod test {
         assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
     }
 
+    #[test]
+    fn test_record_payment_reads_asset_decimals() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+
+        let token = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &token);
+        let received: PaymentReceived = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| event_name(&env, topics) == Some(symbol_short!("payment")))
+            .map(|(_, _, data)| data.into_val(&env))
+            .expect("payment event was not emitted");
+        assert_eq!(received.decimals, Some(7));
+
+        // An address that is not a token contract has no decimals to report
+        let unknown = Address::generate(&env);
+        client.record_payment(&5, &unknown);
+
+        let payments = client.get_info().payments;
+        let decimals_of = |asset: &Address| {
+            payments
+                .iter()
+                .find(|payment| payment.asset == *asset)
+                .unwrap()
+                .decimals
+        };
+        assert_eq!(decimals_of(&token), Some(7));
+        assert_eq!(decimals_of(&unknown), None);
+    }
+
     #[test]
     fn test_multiple_payments() {
         let env = Env::default();
@@ -84,6 +610,82 @@ mod test {
     }
 
     #[test]
+    #[cfg(feature = "views")]
+    fn test_paginated_payment_queries() {
+        use bridgelet_shared::encode_cursor;
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        assert_eq!(client.get_payment_count(), 0);
+        assert_eq!(client.get_payments(&None, &10).items.len(), 0);
+
+        let assets: std::vec::Vec<Address> = (0..3).map(|_| Address::generate(&env)).collect();
+        for (i, asset) in assets.iter().enumerate() {
+            client.record_payment(&(100 * (i as i128 + 1)), asset);
+        }
+        assert_eq!(client.get_payment_count(), 3);
+
+        let first = client.get_payments(&None, &1);
+        assert_eq!(first.items.get(0).unwrap().asset, assets[0]);
+        let page = client.get_payments(&first.next_cursor, &10);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.items.get(0).unwrap().asset, assets[1]);
+        assert_eq!(page.items.get(1).unwrap().amount, 300);
+        assert_eq!(page.next_cursor, None);
+        let page = client.get_payments(&None, &2);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.next_cursor, Some(encode_cursor(&env, 2)));
+        let past_end = Some(encode_cursor(&env, u32::MAX));
+        assert_eq!(client.get_payments(&past_end, &u32::MAX).items.len(), 0);
+        assert_eq!(
+            client.try_get_payments(&Some(BytesN::from_array(&env, &[0xff; 8])), &10),
+            Err(Ok(Error::InvalidCursor))
+        );
+
+        assert_eq!(client.get_payment_by_asset(&assets[0]).unwrap().amount, 100);
+        assert_eq!(client.get_payment_by_asset(&Address::generate(&env)), None);
+    }
+
+    #[test]
+    #[cfg(feature = "invariants")]
+    fn test_check_invariants_entrypoint() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        assert_eq!(
+            client.try_check_invariants(),
+            Err(Ok(Error::NotInitialized))
+        );
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.record_payment(&100, &Address::generate(&env));
+        assert_eq!(client.check_invariants(), Vec::new(&env));
+
+        // A paid account wrongly reopened is reported
+        env.as_contract(&contract_id, || {
+            AccountState::new(&env).set_status(AccountStatus::Active)
+        });
+        assert_eq!(
+            client.check_invariants(),
+            vec![&env, Invariant::ActiveWithoutPayments]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "views")]
     fn test_sweep_single_asset() {
         let env = Env::default();
         env.mock_all_auths();
@@ -93,29 +695,71 @@ mod test {
 
         let creator = Address::generate(&env);
         let recovery = Address::generate(&env);
-        let asset = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
         let destination = Address::generate(&env);
         let expiry_ledger = env.ledger().sequence() + 1000;
 
         client.initialize(&creator, &expiry_ledger, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
         client.record_payment(&100, &asset);
 
-        let auth_sig = BytesN::from_array(&env, &[0u8; 64]);
+        let auth_sig = sign_sweep(&env, &client, &destination);
         client.sweep(&destination, &auth_sig);
 
         assert_eq!(client.get_status(), AccountStatus::Swept);
         assert_eq!(client.get_reserve_remaining(), 0);
         assert!(client.is_reserve_reclaimed());
+        assert_invariants(&client);
+
+        let token = TokenClient::new(&env, &asset);
+        assert_eq!(token.balance(&destination), 100);
+        assert_eq!(token.balance(&contract_id), 0);
 
         let reserve_event = latest_reserve_event(&client);
         assert_eq!(reserve_event.destination, destination);
         assert_eq!(reserve_event.amount, BASE_RESERVE_STROOPS);
         assert_eq!(reserve_event.remaining_reserve, 0);
         assert!(reserve_event.fully_reclaimed);
-        assert_eq!(reserve_event.sweep_id, env.ledger().sequence() as u64);
+        assert_eq!(Some(reserve_event.sweep_id), client.get_sweep_id());
         assert_eq!(client.get_reserve_reclaim_event_count(), 1);
     }
 
+    #[test]
+    #[cfg(feature = "views")]
+    fn test_sweep_id_is_derived_from_contract_destination_and_nonce() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let asset = fund_asset(&env, &contract_id, 100);
+        let destination = Address::generate(&env);
+
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        client.record_payment(&100, &asset);
+        assert_eq!(client.get_sweep_id(), None);
+
+        let auth_sig = sign_sweep(&env, &client, &destination);
+        env.ledger().with_mut(|ledger| ledger.sequence_number += 7);
+        client.sweep(&destination, &auth_sig);
+        let swept: SweepExecutedMulti = event_data(&env, symbol_short!("swept_mul"));
+
+        // Independent of the ledger the sweep landed in
+        let mut preimage = contract_id.clone().to_xdr(&env);
+        preimage.append(&destination.clone().to_xdr(&env));
+        preimage.extend_from_array(&client.get_sweep_nonce().to_be_bytes());
+        preimage.extend_from_array(&client.get_epoch().to_be_bytes());
+        let expected: BytesN<32> = env.crypto().sha256(&preimage).to_bytes();
+        assert_eq!(client.get_sweep_id(), Some(expected.clone()));
+        assert_eq!(swept.sweep_id, expected);
+        assert_eq!(latest_reserve_event(&client).sweep_id, expected);
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #13)")]
     fn test_duplicate_asset() {
@@ -171,13 +815,14 @@ mod test {
         let expiry_ledger = env.ledger().sequence() + 1000;
 
         client.initialize(&creator, &expiry_ledger, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
 
-        let asset1 = Address::generate(&env);
-        let asset2 = Address::generate(&env);
+        let asset1 = fund_asset(&env, &contract_id, 100);
+        let asset2 = fund_asset(&env, &contract_id, 200);
         client.record_payment(&100, &asset1);
         client.record_payment(&200, &asset2);
 
-        let auth_sig = BytesN::from_array(&env, &[0u8; 64]);
+        let auth_sig = sign_sweep(&env, &client, &destination);
         client.sweep(&destination, &auth_sig);
 
         assert_eq!(client.get_status(), AccountStatus::Swept);
@@ -203,13 +848,14 @@ mod test {
         let creator = Address::generate(&env);
         let recovery = Address::generate(&env);
         let destination = Address::generate(&env);
-        let asset = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
         let expiry_ledger = env.ledger().sequence() + 1000;
 
         client.initialize(&creator, &expiry_ledger, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
         client.record_payment(&100, &asset);
 
-        let auth_sig = BytesN::from_array(&env, &[0u8; 64]);
+        let auth_sig = sign_sweep(&env, &client, &destination);
         client.sweep(&destination, &auth_sig);
 
         assert_eq!(client.get_reserve_remaining(), 0);
@@ -238,18 +884,19 @@ mod test {
         let creator = Address::generate(&env);
         let recovery = Address::generate(&env);
         let destination = Address::generate(&env);
-        let asset = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
         let expiry_ledger = env.ledger().sequence() + 1000;
 
         client.initialize(&creator, &expiry_ledger, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
         client.record_payment(&100, &asset);
 
         let initial_available = 250_000_000i128;
         env.as_contract(&contract_id, || {
-            storage::set_available_reserve(&env, initial_available);
+            AccountState::new(&env).set_available_reserve(initial_available);
         });
 
-        let auth_sig = BytesN::from_array(&env, &[0u8; 64]);
+        let auth_sig = sign_sweep(&env, &client, &destination);
         client.sweep(&destination, &auth_sig);
 
         let expected_remaining = BASE_RESERVE_STROOPS - initial_available;
@@ -270,7 +917,7 @@ mod test {
         assert!(!client.is_reserve_reclaimed());
 
         env.as_contract(&contract_id, || {
-            storage::set_available_reserve(&env, expected_remaining);
+            AccountState::new(&env).set_available_reserve(expected_remaining);
         });
         let final_reclaim = client.reclaim_reserve();
         assert_eq!(final_reclaim, expected_remaining);
@@ -283,6 +930,64 @@ mod test {
         assert_eq!(client.get_reserve_reclaim_event_count(), 4);
     }
 
+    #[test]
+    #[cfg(feature = "reconcile")]
+    fn test_reserve_topup_and_reconcile_track_balance() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let destination = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        client.initialize(&creator, &expiry_ledger, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        client.record_payment(&100, &asset);
+
+        assert_eq!(
+            client.try_reconcile_reserve(&-1),
+            Err(Ok(Error::InvalidAmount))
+        );
+        client.reconcile_reserve(&400);
+        let reconciled: ReserveReconciled = event_data(&env, symbol_short!("rsv_recon"));
+        assert_eq!(reconciled.old_available_reserve, BASE_RESERVE_STROOPS);
+        assert_eq!(reconciled.new_available_reserve, 400);
+        assert_eq!(client.get_reserve_available(), 400);
+
+        let auth_sig = sign_sweep(&env, &client, &destination);
+        client.sweep(&destination, &auth_sig);
+        let shortfall = BASE_RESERVE_STROOPS - 400;
+        assert_eq!(client.get_reserve_remaining(), shortfall);
+        assert_eq!(client.get_reserve_available(), 0);
+
+        // Only the shortfall can be topped up
+        assert_eq!(
+            client.try_record_reserve_topup(&(shortfall + 1)),
+            Err(Ok(Error::InvalidAmount))
+        );
+        assert_eq!(
+            client.try_record_reserve_topup(&0),
+            Err(Ok(Error::InvalidAmount))
+        );
+        client.record_reserve_topup(&shortfall);
+        let topped_up: ReserveToppedUp = event_data(&env, symbol_short!("rsv_topup"));
+        assert_eq!(topped_up.amount, shortfall);
+        assert_eq!(topped_up.available_reserve, shortfall);
+        assert_eq!(topped_up.remaining_reserve, shortfall);
+
+        assert_eq!(client.reclaim_reserve(), shortfall);
+        assert!(client.is_reserve_reclaimed());
+
+        // Nothing is owed, so no balance counts as reserve
+        client.reconcile_reserve(&1_000);
+        assert_eq!(client.get_reserve_available(), 0);
+    }
+
     #[test]
     fn test_replay_sweep_call_does_not_reclaim_twice() {
         let env = Env::default();
@@ -294,13 +999,14 @@ mod test {
         let creator = Address::generate(&env);
         let recovery = Address::generate(&env);
         let destination = Address::generate(&env);
-        let asset = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
         let expiry_ledger = env.ledger().sequence() + 1000;
 
         client.initialize(&creator, &expiry_ledger, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
         client.record_payment(&100, &asset);
 
-        let auth_sig = BytesN::from_array(&env, &[0u8; 64]);
+        let auth_sig = sign_sweep(&env, &client, &destination);
         client.sweep(&destination, &auth_sig);
 
         let reserve_events_before = client.get_reserve_reclaim_event_count();
@@ -317,4 +1023,5844 @@ mod test {
             reserve_events_before
         );
     }
+
+    #[test]
+    #[cfg(feature = "history")]
+    fn test_reserve_summary_ignores_zero_amount_reclaims() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let destination = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        client.initialize(&creator, &expiry_ledger, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        client.record_payment(&100, &asset);
+
+        let auth_sig = sign_sweep(&env, &client, &destination);
+        client.sweep(&destination, &auth_sig);
+        client.reclaim_reserve();
+        client.reclaim_reserve();
+
+        let summary = client.get_reserve_reclaim_summary();
+        assert_eq!(summary.total_reclaimed, BASE_RESERVE_STROOPS);
+        assert_eq!(summary.reclaim_count, 1);
+        assert_eq!(summary.event_count, 3);
+        let events = client.get_reserve_events(&None, &10).items;
+        assert_eq!(events.len(), 3);
+        assert_eq!(events.get(0).unwrap().amount, BASE_RESERVE_STROOPS);
+    }
+
+    #[test]
+    #[cfg(feature = "history")]
+    fn test_reserve_events_ring_buffer_pages() {
+        use crate::storage::ReserveReclaimSummary;
+        use bridgelet_shared::encode_cursor;
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        assert_eq!(client.get_last_reserve_event(), None);
+
+        let destination = Address::generate(&env);
+        let event = |amount: i128| ReserveReclaimed {
+            destination: destination.clone(),
+            amount,
+            sweep_id: BytesN::from_array(&env, &[1u8; 32]),
+            fully_reclaimed: false,
+            remaining_reserve: 0,
+            keeper: None,
+            keeper_bounty: 0,
+        };
+
+        // Seven events, of which the ring buffer keeps the last five
+        env.as_contract(&contract_id, || {
+            let state = AccountState::new(&env);
+            for seq in 0..7 {
+                state.set_reserve_event(seq, &event(seq as i128 + 1));
+            }
+            state.set_reserve_summary(&ReserveReclaimSummary {
+                total_reclaimed: 28,
+                reclaim_count: 7,
+                event_count: 7,
+            });
+        });
+        assert_eq!(client.get_reserve_reclaim_summary().event_count, 7);
+        assert_eq!(client.get_last_reserve_event(), Some(event(7)));
+
+        // Each event overwrites the oldest one, never the rest
+        env.as_contract(&contract_id, || {
+            let state = AccountState::new(&env);
+            state.set_reserve_event(7, &event(8));
+            state.set_reserve_summary(&ReserveReclaimSummary {
+                total_reclaimed: 36,
+                reclaim_count: 8,
+                event_count: 8,
+            });
+        });
+        let amounts = |events: Vec<ReserveReclaimed>| {
+            events
+                .iter()
+                .map(|e| e.amount)
+                .collect::<std::vec::Vec<_>>()
+        };
+        assert_eq!(amounts(client.get_recent_reserve_events()), [4, 5, 6, 7, 8]);
+        let page = client.get_reserve_events(&None, &2);
+        assert_eq!(amounts(page.items), [4, 5]);
+        let page = client.get_reserve_events(&page.next_cursor, &2);
+        assert_eq!(amounts(page.items), [6, 7]);
+        let page = client.get_reserve_events(&page.next_cursor, &10);
+        assert_eq!(amounts(page.items), [8]);
+        assert_eq!(page.next_cursor, None);
+        // A cursor to an event since overwritten resumes at the oldest kept
+        let stale = Some(encode_cursor(&env, 0));
+        assert_eq!(amounts(client.get_reserve_events(&stale, &1).items), [4]);
+        assert_eq!(client.get_last_reserve_event(), Some(event(8)));
+    }
+
+    #[test]
+    #[cfg(feature = "history")]
+    fn test_recent_reserve_events_are_bounded() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let destination = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        client.initialize(&creator, &expiry_ledger, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        client.record_payment(&100, &asset);
+
+        env.as_contract(&contract_id, || {
+            AccountState::new(&env).set_available_reserve(100);
+        });
+
+        let auth_sig = sign_sweep(&env, &client, &destination);
+        client.sweep(&destination, &auth_sig);
+
+        for _ in 0..4 {
+            env.as_contract(&contract_id, || {
+                AccountState::new(&env).set_available_reserve(100);
+            });
+            client.reclaim_reserve();
+        }
+        for _ in 0..3 {
+            client.reclaim_reserve();
+        }
+
+        let summary = client.get_reserve_reclaim_summary();
+        assert_eq!(summary.total_reclaimed, 500);
+        assert_eq!(summary.reclaim_count, 5);
+        assert_eq!(summary.event_count, 8);
+
+        let recent = client.get_recent_reserve_events();
+        assert_eq!(recent.len(), 5);
+        assert_eq!(recent.get(0).unwrap().amount, 100);
+        assert_eq!(recent.get(1).unwrap().amount, 100);
+        assert_eq!(recent.get(2).unwrap().amount, 0);
+        assert_eq!(latest_reserve_event(&client).amount, 0);
+        assert_eq!(
+            latest_reserve_event(&client).remaining_reserve,
+            BASE_RESERVE_STROOPS - 500
+        );
+    }
+
+    /// Topics of every `diag` event published so far.
+    /// `diag` events of the last invocation, as simulation reports them:
+    /// with whether the call that published them failed, in which case
+    /// they never reach the ledger.
+    fn diagnostic_events(env: &Env) -> std::vec::Vec<(bool, Val)> {
+        use soroban_sdk::xdr::{ContractEventBody, ContractEventType};
+
+        env.host()
+            .get_events()
+            .unwrap()
+            .0
+            .into_iter()
+            .filter(|event| event.event.type_ == ContractEventType::Contract)
+            .filter_map(|event| {
+                let ContractEventBody::V0(body) = event.event.body;
+                let mut topics: Vec<Val> = Vec::new(env);
+                for topic in body.topics.iter() {
+                    topics.push_back(Val::try_from_val(env, topic).unwrap());
+                }
+                (event_name(env, &topics) == Some(symbol_short!("diag"))).then(|| {
+                    (
+                        event.failed_call,
+                        Val::try_from_val(env, &body.data).unwrap(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "diagnostics")]
+    fn test_failure_emits_diagnostic_when_enabled() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let asset = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        client.initialize_with_config(
+            &creator,
+            &expiry_ledger,
+            &recovery,
+            &AccountConfig {
+                debug_events: true,
+                ..Default::default()
+            },
+        );
+
+        let result = client.try_record_payment(&0, &asset);
+        assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+        // Rolled back with the call: simulation shows it, the ledger never
+        // records it
+        let diagnostics = diagnostic_events(&env);
+        assert_eq!(diagnostics.len(), 1);
+        let (failed_call, data) = &diagnostics[0];
+        assert!(failed_call);
+        let diagnostic: FailureDiagnostic = data.into_val(&env);
+        assert_eq!(diagnostic.function, Symbol::new(&env, "record_payment"));
+        assert_eq!(diagnostic.error_code, Error::InvalidAmount as u32);
+        assert_eq!(diagnostic.params.len(), 2);
+        let amount: i128 = diagnostic.params.get(0).unwrap().into_val(&env);
+        let params_asset: Address = diagnostic.params.get(1).unwrap().into_val(&env);
+        assert_eq!(amount, 0);
+        assert_eq!(params_asset, asset);
+    }
+
+    #[test]
+    fn test_failure_is_silent_when_debug_events_disabled() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        client.initialize(&creator, &expiry_ledger, &recovery);
+
+        assert_eq!(client.try_expire(), Err(Ok(Error::NotExpired)));
+        assert!(diagnostic_events(&env).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "limits")]
+    fn test_sweep_follows_priority_order() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let destination = Address::generate(&env);
+        let assets = [
+            fund_asset(&env, &contract_id, 100),
+            fund_asset(&env, &contract_id, 100),
+            fund_asset(&env, &contract_id, 100),
+        ];
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        client.initialize(&creator, &expiry_ledger, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        for asset in assets.iter() {
+            client.record_payment(&100, asset);
+        }
+
+        // Prioritise the asset that would otherwise be swept last.
+        let last_by_default = client.get_info().payments.get(2).unwrap().asset;
+        let priority = Vec::from_array(&env, [last_by_default.clone()]);
+        client.set_sweep_priority(&priority);
+        assert_eq!(client.get_sweep_priority(), priority);
+
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+
+        let swept: SweepExecutedMulti = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| event_name(&env, topics) == Some(symbol_short!("swept_mul")))
+            .map(|(_, _, data)| data.into_val(&env))
+            .expect("sweep event was not emitted");
+        assert_eq!(swept.payments.len(), 3);
+        assert_eq!(swept.payments.get(0).unwrap().asset, last_by_default);
+    }
+
+    #[test]
+    #[cfg(feature = "limits")]
+    fn test_sweep_priority_rejects_duplicates() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let asset = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        client.initialize(&creator, &expiry_ledger, &recovery);
+
+        let result = client.try_set_sweep_priority(&Vec::from_array(&env, [asset.clone(), asset]));
+        assert_eq!(result, Err(Ok(Error::DuplicateAsset)));
+    }
+
+    #[test]
+    fn test_repeated_asset_rejected_without_accumulation() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+
+        let asset = Address::generate(&env);
+        client.record_payment(&60, &asset);
+        assert_eq!(
+            client.try_record_payment(&40, &asset),
+            Err(Ok(Error::DuplicateAsset))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "config")]
+    fn test_accumulate_payments_tops_up_asset() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                accumulate_payments: true,
+                sweep_signer: Some(sweep_signer_key(&env)),
+                ..Default::default()
+            },
+        );
+
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&60, &asset);
+        env.ledger().with_mut(|li| li.timestamp += 30);
+        client.record_payment(&40, &asset);
+
+        let topped_up: PaymentToppedUp = event_data(&env, symbol_short!("topped_up"));
+        assert_eq!(topped_up.amount, 40);
+        assert_eq!(topped_up.total_amount, 100);
+
+        let info = client.get_info();
+        assert_eq!(info.payment_count, 1);
+        let payment = info.payments.get(0).unwrap();
+        assert_eq!(payment.amount, 100);
+        assert_eq!(payment.timestamp, env.ledger().timestamp());
+
+        // The installments are swept together
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+    }
+
+    /// Accumulation does not lift the asset-count cap for new assets.
+    #[test]
+    #[cfg(feature = "config")]
+    fn test_accumulate_payments_keeps_asset_cap() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                accumulate_payments: true,
+                ..Default::default()
+            },
+        );
+
+        let first = Address::generate(&env);
+        client.record_payment(&1, &first);
+        for _ in 1..10 {
+            client.record_payment(&1, &Address::generate(&env));
+        }
+
+        assert_eq!(
+            client.try_record_payment(&1, &Address::generate(&env)),
+            Err(Ok(Error::TooManyPayments))
+        );
+        client.record_payment(&5, &first);
+        assert_eq!(client.get_info().payments.get(0).unwrap().amount, 6);
+    }
+
+    #[test]
+    #[cfg(feature = "limits")]
+    fn test_configure_limits_restricts_payments() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+
+        let usdc = Address::generate(&env);
+        let eurc = Address::generate(&env);
+        let mut min_amounts = Map::new(&env);
+        min_amounts.set(usdc.clone(), 50);
+        client.configure_limits(
+            &1,
+            &min_amounts,
+            &Some(vec![&env, usdc.clone(), eurc.clone()]),
+            &0,
+        );
+        assert_eq!(client.get_payment_limits().max_payments, 1);
+
+        assert_eq!(
+            client.try_record_payment(&100, &Address::generate(&env)),
+            Err(Ok(Error::AssetNotAllowed))
+        );
+        assert_eq!(
+            client.try_record_payment(&49, &usdc),
+            Err(Ok(Error::BelowMinPayment))
+        );
+        client.record_payment(&50, &usdc);
+        assert_eq!(
+            client.try_record_payment(&1, &eurc),
+            Err(Ok(Error::TooManyPayments))
+        );
+
+        // Limits are fixed once a payment has been recorded.
+        assert_eq!(
+            client.try_configure_limits(&2, &Map::new(&env), &None, &0),
+            Err(Ok(Error::InvalidStatus))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "config", feature = "limits", feature = "views"))]
+    fn test_payment_cooldown_spaces_recordings() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                accumulate_payments: true,
+                ..Default::default()
+            },
+        );
+        client.configure_limits(&10, &Map::new(&env), &None, &5);
+        assert_eq!(client.get_payment_limits().cooldown_ledgers, 5);
+
+        let usdc = Address::generate(&env);
+        client.record_payment(&100, &usdc);
+        // Top-ups and other assets wait out the cooldown alike
+        assert_eq!(
+            client.try_record_payment(&100, &usdc),
+            Err(Ok(Error::PaymentCooldown))
+        );
+        env.ledger().with_mut(|li| li.sequence_number += 4);
+        assert_eq!(
+            client.try_record_payment(&100, &Address::generate(&env)),
+            Err(Ok(Error::PaymentCooldown))
+        );
+        env.ledger().with_mut(|li| li.sequence_number += 1);
+        client.record_payment(&100, &usdc);
+        assert_eq!(client.get_payment_by_asset(&usdc).unwrap().amount, 200);
+        assert_eq!(
+            client.try_record_payment(&100, &Address::generate(&env)),
+            Err(Ok(Error::PaymentCooldown))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "attribution", feature = "views"))]
+    fn test_record_payment_requires_attributed_auth() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let creator = Address::generate(&env);
+        client.initialize(
+            &creator,
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+
+        client.record_payment(&100, &Address::generate(&env));
+        let auths: std::vec::Vec<Address> = env.auths().into_iter().map(|(addr, _)| addr).collect();
+        assert_eq!(auths, std::vec![creator]);
+
+        let sender = Address::generate(&env);
+        client.record_payment_from(&100, &Address::generate(&env), &sender, &None);
+        let auths: std::vec::Vec<Address> = env.auths().into_iter().map(|(addr, _)| addr).collect();
+        assert_eq!(auths, std::vec![sender]);
+
+        // Without the sender's signature the payment is refused
+        env.set_auths(&[]);
+        assert!(client
+            .try_record_payment_from(
+                &100,
+                &Address::generate(&env),
+                &Address::generate(&env),
+                &None
+            )
+            .is_err());
+        assert_eq!(client.get_payment_count(), 2);
+    }
+
+    #[test]
+    #[cfg(all(feature = "batch", feature = "limits", feature = "views"))]
+    fn test_record_payments_records_batch_atomically() {
+        use crate::MultiPaymentReceived;
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        let usdc = fund_asset(&env, &contract_id, 100);
+        let eurc = fund_asset(&env, &contract_id, 50);
+
+        // One bad entry rejects the whole batch
+        assert_eq!(
+            client.try_record_payments(&vec![&env, (usdc.clone(), 100), (usdc.clone(), 50)]),
+            Err(Ok(Error::DuplicateAsset))
+        );
+        assert_eq!(
+            client.try_record_payments(&vec![&env, (usdc.clone(), 100), (eurc.clone(), 0)]),
+            Err(Ok(Error::InvalidAmount))
+        );
+        assert_eq!(
+            client.try_record_payments(&Vec::new(&env)),
+            Err(Ok(Error::InvalidAmount))
+        );
+        client.configure_limits(&1, &Map::new(&env), &None, &0);
+        assert_eq!(
+            client.try_record_payments(&vec![&env, (usdc.clone(), 100), (eurc.clone(), 50)]),
+            Err(Ok(Error::TooManyPayments))
+        );
+        assert_eq!(client.get_payment_count(), 0);
+        assert_eq!(client.get_status(), AccountStatus::Active);
+
+        client.configure_limits(&10, &Map::new(&env), &None, &0);
+        client.record_payments(&vec![&env, (usdc.clone(), 100), (eurc.clone(), 50)]);
+        let event: MultiPaymentReceived = event_data(&env, symbol_short!("multi_pay"));
+        assert_eq!(event.payments.len(), 2);
+        assert_eq!(event.payments.get_unchecked(0).asset, usdc);
+        assert_eq!(event.payments.get_unchecked(1).amount, 50);
+
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+        assert_eq!(client.get_payment_count(), 2);
+        assert_eq!(client.get_payment_by_asset(&eurc).unwrap().amount, 50);
+        assert_eq!(
+            client.try_record_payments(&vec![&env, (eurc, 10)]),
+            Err(Ok(Error::DuplicateAsset))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "lifecycle", feature = "limits", feature = "views"))]
+    fn test_mutators_reject_unauthorized_callers() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+
+        env.set_auths(&[]);
+        assert!(client
+            .try_record_payment(&100, &Address::generate(&env))
+            .is_err());
+        assert!(client.try_cancel(&CloseReason::CreatorCancelled).is_err());
+        assert!(client
+            .try_configure_limits(&10, &Map::new(&env), &None, &0)
+            .is_err());
+        assert!(client
+            .try_extend_expiry(&(env.ledger().sequence() + 2000))
+            .is_err());
+        assert_eq!(client.get_status(), AccountStatus::Active);
+        assert_eq!(client.get_payment_count(), 0);
+    }
+
+    #[test]
+    #[cfg(all(feature = "keeper", feature = "views"))]
+    fn test_restricted_policy_limits_expire_and_reclaim() {
+        use crate::AuthPolicy;
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let recovery = Address::generate(&env);
+        let keeper = Address::generate(&env);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 100),
+            &recovery,
+            &AccountConfig {
+                auth_policy: AuthPolicy::Restricted,
+                keeper: Some(keeper.clone()),
+                ..Default::default()
+            },
+        );
+        assert!(client.get_capabilities().restricted_auth);
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+
+        // Anonymous and unknown callers are refused
+        assert_eq!(client.try_expire(), Err(Ok(Error::Unauthorized)));
+        assert_eq!(
+            client.try_expire_as(&Address::generate(&env)),
+            Err(Ok(Error::Unauthorized))
+        );
+
+        client.expire_as(&keeper);
+        let auths: std::vec::Vec<Address> = env.auths().into_iter().map(|(addr, _)| addr).collect();
+        assert_eq!(auths, std::vec![keeper]);
+        assert_eq!(client.get_status(), AccountStatus::Expired);
+
+        assert_eq!(client.try_reclaim_reserve(), Err(Ok(Error::Unauthorized)));
+        client.reclaim_reserve_as(&recovery);
+        let auths: std::vec::Vec<Address> = env.auths().into_iter().map(|(addr, _)| addr).collect();
+        assert_eq!(auths, std::vec![recovery]);
+    }
+
+    #[test]
+    #[cfg(feature = "limits")]
+    fn test_configure_limits_rejects_invalid_values() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+
+        assert_eq!(
+            client.try_configure_limits(&11, &Map::new(&env), &None, &0),
+            Err(Ok(Error::TooManyPayments))
+        );
+        assert_eq!(
+            client.try_configure_limits(&0, &Map::new(&env), &None, &0),
+            Err(Ok(Error::InvalidAmount))
+        );
+        let mut min_amounts = Map::new(&env);
+        min_amounts.set(Address::generate(&env), 0);
+        assert_eq!(
+            client.try_configure_limits(&5, &min_amounts, &None, &0),
+            Err(Ok(Error::InvalidAmount))
+        );
+
+        let limits = client.get_payment_limits();
+        assert_eq!(limits.max_payments, 10);
+        assert_eq!(limits.allowed_assets, None);
+    }
+
+    #[test]
+    #[cfg(feature = "config")]
+    fn test_kill_switch_halts_state_changes() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let kill_switch_id = env.register(KillSwitch, ());
+        let kill_switch = KillSwitchClient::new(&env, &kill_switch_id);
+        kill_switch.initialize(&Address::generate(&env));
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                kill_switch: Some(kill_switch_id),
+                sweep_signer: Some(sweep_signer_key(&env)),
+                ..Default::default()
+            },
+        );
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+
+        // Another subsystem's flag does not affect accounts.
+        kill_switch.set_halted(&symbol_short!("factory"), &true);
+        let destination = Address::generate(&env);
+        let signature = sign_sweep(&env, &client, &destination);
+
+        kill_switch.set_halted(&ALL_SUBSYSTEMS, &true);
+        assert_eq!(
+            client.try_sweep(&destination, &signature),
+            Err(Ok(Error::Halted))
+        );
+        assert_eq!(
+            client.try_record_payment(&1, &Address::generate(&env)),
+            Err(Ok(Error::Halted))
+        );
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+
+        kill_switch.set_halted(&ALL_SUBSYSTEMS, &false);
+        client.sweep(&destination, &signature);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+    }
+
+    /// A factory stamps its kill switch in without `config`, so every
+    /// account build honours it.
+    #[test]
+    fn test_deployment_kill_switch_halts_state_changes() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let kill_switch_id = env.register(KillSwitch, ());
+        let kill_switch = KillSwitchClient::new(&env, &kill_switch_id);
+        kill_switch.initialize(&Address::generate(&env));
+        let deployment = DeploymentSettings {
+            kill_switch: Some(kill_switch_id),
+            ..Default::default()
+        };
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_deployment(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &deployment,
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+        let destination = Address::generate(&env);
+        let signature = sign_sweep(&env, &client, &destination);
+
+        kill_switch.set_halted(&ALL_SUBSYSTEMS, &true);
+        assert_eq!(
+            client.try_sweep(&destination, &signature),
+            Err(Ok(Error::Halted))
+        );
+        let halted =
+            EphemeralAccountContractClient::new(&env, &env.register(EphemeralAccountContract, ()));
+        assert_eq!(
+            halted.try_initialize_with_deployment(
+                &Address::generate(&env),
+                &(env.ledger().sequence() + 1000),
+                &Address::generate(&env),
+                &deployment,
+            ),
+            Err(Ok(Error::Halted))
+        );
+
+        kill_switch.set_halted(&ALL_SUBSYSTEMS, &false);
+        client.sweep(&destination, &signature);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+    }
+
+    /// Without `reuse` nothing could `reset` the account, so naming a
+    /// factory is refused rather than ignored.
+    #[test]
+    #[cfg(not(feature = "reuse"))]
+    fn test_deployment_factory_needs_reuse() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            EphemeralAccountContractClient::new(&env, &env.register(EphemeralAccountContract, ()));
+        let result = client.try_initialize_with_deployment(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &DeploymentSettings {
+                factory: Some(Address::generate(&env)),
+                ..Default::default()
+            },
+        );
+        assert_eq!(result, Err(Ok(Error::FeatureDisabled)));
+    }
+
+    /// An account insured with a pool whose factory is a stand-in, and
+    /// the pool.  The account expires at ledger 100.
+    #[cfg(feature = "insurance")]
+    fn setup_insured(
+        env: &Env,
+        insure: bool,
+    ) -> (
+        EphemeralAccountContractClient<'static>,
+        InsurancePoolClient<'static>,
+    ) {
+        let pool = InsurancePoolClient::new(env, &env.register(InsurancePool, ()));
+        pool.initialize(&Address::generate(env));
+        pool.set_factory(&Some(Address::generate(env)));
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(env, &contract_id);
+        client.initialize_with_deployment(
+            &Address::generate(env),
+            &100,
+            &Address::generate(env),
+            &DeploymentSettings {
+                insurance_pool: Some(pool.address.clone()),
+                ..Default::default()
+            },
+        );
+        if insure {
+            pool.insure(&contract_id, &Address::generate(env), &10);
+        }
+        (client, pool)
+    }
+
+    /// The customer paid and the merchant never swept: the expiry opens a
+    /// claim with the pool and links to it.
+    #[test]
+    #[cfg(feature = "insurance")]
+    fn test_insured_expiry_opens_claim() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, pool) = setup_insured(&env, true);
+        let asset = fund_asset(&env, &client.address, 100);
+        client.record_payment(&100, &asset);
+
+        env.ledger().with_mut(|ledger| ledger.sequence_number = 100);
+        client.expire();
+
+        let event: InsuredExpiry = event_data(&env, symbol_short!("insured"));
+        assert_eq!(
+            event,
+            InsuredExpiry {
+                pool: pool.address.clone(),
+                claim_id: 0,
+            }
+        );
+        let claim = pool.get_claim(&0).unwrap();
+        assert_eq!(claim.account, client.address);
+        assert_eq!(claim.amount, 100);
+        assert_eq!(claim.status, ClaimStatus::Open);
+        assert_eq!(pool.get_policy(&client.address), None);
+    }
+
+    /// Nothing to refund: the policy is left unclaimed.
+    #[test]
+    #[cfg(feature = "insurance")]
+    fn test_insured_expiry_without_payments_opens_no_claim() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, pool) = setup_insured(&env, true);
+
+        env.ledger().with_mut(|ledger| ledger.sequence_number = 100);
+        client.expire();
+
+        assert!(env
+            .events()
+            .all()
+            .iter()
+            .all(|(contract, topics, _)| contract != client.address
+                || EventTopics::decode(&env, &topics).unwrap().name != symbol_short!("insured")));
+        assert_eq!(pool.get_claim_count(), 0);
+        assert!(pool.get_policy(&client.address).is_some());
+    }
+
+    /// A pool that refuses the claim does not keep the funds in the
+    /// account.
+    #[test]
+    #[cfg(feature = "insurance")]
+    fn test_refused_claim_does_not_block_expiry() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, pool) = setup_insured(&env, false);
+        let asset = fund_asset(&env, &client.address, 100);
+        client.record_payment(&100, &asset);
+
+        env.ledger().with_mut(|ledger| ledger.sequence_number = 100);
+        client.expire();
+
+        assert_eq!(client.get_status(), AccountStatus::Expired);
+        let recovery = client.get_info().recovery_address;
+        assert_eq!(TokenClient::new(&env, &asset).balance(&recovery), 100);
+        assert_eq!(pool.get_claim_count(), 0);
+    }
+
+    /// Without `insurance` the account could never claim, so naming a pool
+    /// is refused rather than ignored.
+    #[test]
+    #[cfg(not(feature = "insurance"))]
+    fn test_deployment_insurance_pool_needs_insurance() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            EphemeralAccountContractClient::new(&env, &env.register(EphemeralAccountContract, ()));
+        let result = client.try_initialize_with_deployment(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &DeploymentSettings {
+                insurance_pool: Some(Address::generate(&env)),
+                ..Default::default()
+            },
+        );
+        assert_eq!(result, Err(Ok(Error::FeatureDisabled)));
+    }
+
+    #[test]
+    #[cfg(all(feature = "config", feature = "views"))]
+    fn test_shared_clients_match_contract_entrypoints() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let reserve_id = env.register(ReserveContract, ());
+        let admin = Address::generate(&env);
+        ReserveContractClient::new(&env, &reserve_id).initialize(&admin);
+        ReserveContractClient::new(&env, &reserve_id).set_base_reserve(&admin, &25_000_000);
+        let reserve = ReserveClient::new(&env, &reserve_id);
+        assert_eq!(reserve.require_base_reserve(), 25_000_000);
+        assert_eq!(reserve.get_base_reserve(), Some(25_000_000));
+        assert!(!reserve.is_paused());
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let account = EphemeralAccountClient::new(&env, &contract_id);
+        assert_eq!(
+            account.try_can_sweep(),
+            Err(Ok(Error::NotInitialized.into()))
+        );
+
+        account.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                reserve_contract: Some(reserve_id),
+                sweep_signer: Some(sweep_signer_key(&env)),
+                ..Default::default()
+            },
+        );
+        let asset = fund_asset(&env, &contract_id, 100);
+        account.record_payment(&100, &asset);
+        assert_eq!(account.get_status(), AccountStatus::PaymentReceived);
+        assert_eq!(account.get_payments(&None, &10).items.len(), 1);
+        assert_eq!(account.get_reserve_remaining(), 25_000_000);
+        account.can_sweep();
+
+        let destination = Address::generate(&env);
+        let signature = sign_sweep(
+            &env,
+            &EphemeralAccountContractClient::new(&env, &contract_id),
+            &destination,
+        );
+        account.sweep(&destination, &signature);
+        assert_eq!(account.get_info().swept_to, Some(destination.clone()));
+        assert_eq!(
+            account.get_last_reserve_event().unwrap().destination,
+            destination
+        );
+        assert_eq!(
+            account.try_sweep(&destination, &signature),
+            Err(Ok(Error::AlreadySwept.into()))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "config")]
+    fn test_reserve_pause_blocks_payments_and_sweeps() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let reserve_id = env.register(ReserveContract, ());
+        let reserve = ReserveContractClient::new(&env, &reserve_id);
+        let admin = Address::generate(&env);
+        reserve.initialize(&admin);
+        reserve.set_base_reserve(&admin, &25_000_000);
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                reserve_contract: Some(reserve_id),
+                sweep_signer: Some(sweep_signer_key(&env)),
+                ..Default::default()
+            },
+        );
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+        let destination = Address::generate(&env);
+        let signature = sign_sweep(&env, &client, &destination);
+
+        reserve.pause(&admin);
+        assert_eq!(
+            client.try_sweep(&destination, &signature),
+            Err(Ok(Error::SystemPaused))
+        );
+        assert_eq!(
+            client.try_record_payment(&1, &Address::generate(&env)),
+            Err(Ok(Error::SystemPaused))
+        );
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+
+        reserve.unpause(&admin);
+        client.sweep(&destination, &signature);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+    }
+
+    #[test]
+    #[cfg(feature = "attribution")]
+    fn test_payer_info_hash_is_recorded() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let attested_asset = Address::generate(&env);
+        let plain_asset = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+        let payer_info_hash = BytesN::from_array(&env, &[7u8; 32]);
+
+        client.initialize(&creator, &expiry_ledger, &recovery);
+        client.record_payment_with_payer_info(
+            &100,
+            &attested_asset,
+            &Some(payer_info_hash.clone()),
+        );
+        client.record_payment(&50, &plain_asset);
+
+        let payments = client.get_info().payments;
+        let attested = payments
+            .iter()
+            .find(|payment| payment.asset == attested_asset)
+            .unwrap();
+        let plain = payments
+            .iter()
+            .find(|payment| payment.asset == plain_asset)
+            .unwrap();
+        assert_eq!(attested.payer_info_hash, Some(payer_info_hash));
+        assert_eq!(plain.payer_info_hash, None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "attribution", feature = "views"))]
+    fn test_payment_memo_is_recorded_and_emitted() {
+        use crate::MultiPaymentReceived;
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+
+        let asset = Address::generate(&env);
+        let sender = Address::generate(&env);
+        let memo = BytesN::from_array(&env, &[9u8; 32]);
+        client.record_payment_with_memo(&100, &asset, &sender, &Some(memo.clone()));
+
+        let received: PaymentReceived = event_data(&env, symbol_short!("payment"));
+        assert_eq!(received.sender, Some(sender.clone()));
+        assert_eq!(received.memo, Some(memo.clone()));
+
+        let payment = client.get_payment_by_asset(&asset).unwrap();
+        assert_eq!(payment.sender, Some(sender));
+        assert_eq!(payment.memo, Some(memo));
+
+        client.record_payment(&50, &Address::generate(&env));
+        let plain: MultiPaymentReceived = event_data(&env, symbol_short!("multi_pay"));
+        let plain = plain.payments.get_unchecked(0);
+        assert_eq!(plain.sender, None);
+        assert_eq!(plain.memo, None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "audit", feature = "views"))]
+    fn test_audit_trail_covers_whole_lifecycle() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        client.initialize(&creator, &100, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
+
+        let asset1 = fund_asset(&env, &contract_id, 100);
+        let asset2 = fund_asset(&env, &contract_id, 200);
+        client.record_payment(&100, &asset1);
+        client.record_payment(&200, &asset2);
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+
+        let trail: AuditTrail = client.get_audit_trail();
+        assert_eq!(trail.creator, creator);
+        assert_eq!(trail.recovery_address, recovery);
+        assert_eq!(trail.expiry_ledger, 100);
+        assert_eq!(trail.config_version, crate::CONFIG_SCHEMA_VERSION);
+        assert_eq!(trail.storage_version, crate::STORAGE_VERSION);
+        assert_eq!(trail.payment_count, 2);
+        assert_eq!(trail.payments.get_unchecked(0).asset, asset1);
+        assert_eq!(trail.payments.get_unchecked(1).asset, asset2);
+        assert_eq!(trail.payments_root, client.get_payments_root());
+        assert_eq!(trail.status, AccountStatus::Swept);
+        assert_eq!(trail.swept_to, Some(destination));
+        assert_eq!(trail.sweep_nonce, 1);
+        assert_eq!(trail.reserve_remaining, 0);
+        assert!(trail.reserve_reclaimed);
+        assert_eq!(trail.total_reclaimed, BASE_RESERVE_STROOPS);
+        assert_eq!(trail.reclaim_count, 1);
+        assert_eq!(
+            trail.reclaim_events,
+            vec![&env, latest_reserve_event(&client)]
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "lifecycle", feature = "views"))]
+    fn test_expiry_countdown_and_sweepability_views() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        assert_eq!(client.try_can_sweep(), Err(Ok(Error::NotInitialized)));
+
+        client.initialize(&Address::generate(&env), &100, &Address::generate(&env));
+        assert_eq!(client.ledgers_until_expiry(), 100);
+        assert_eq!(client.try_can_sweep(), Err(Ok(Error::NoPaymentReceived)));
+
+        client.record_payment(&100, &fund_asset(&env, &contract_id, 100));
+        assert_eq!(client.try_can_sweep(), Err(Ok(Error::Unauthorized)));
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        assert_eq!(client.try_can_sweep(), Ok(Ok(())));
+        assert!(!client.can_expire());
+
+        env.ledger().with_mut(|li| li.sequence_number = 110);
+        assert_eq!(client.ledgers_until_expiry(), -10);
+        assert_eq!(client.try_can_sweep(), Err(Ok(Error::AccountExpired)));
+        assert!(client.can_expire());
+
+        client.expire();
+        assert!(!client.can_expire());
+    }
+
+    /// Account expiring at ledger 100 with a 50 ledger grace period and a
+    /// recorded payment.
+    #[cfg(all(feature = "config", feature = "lifecycle"))]
+    fn setup_grace(env: &Env) -> (EphemeralAccountContractClient<'static>, Address) {
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(env),
+            &100,
+            &Address::generate(env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(env)),
+                grace_ledgers: 50,
+                ..Default::default()
+            },
+        );
+        let asset = fund_asset(env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+        (client, asset)
+    }
+
+    #[test]
+    #[cfg(all(feature = "config", feature = "lifecycle"))]
+    fn test_sweep_accepted_during_grace_period() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, asset) = setup_grace(&env);
+        assert!(!client.get_lifecycle().in_grace);
+
+        env.ledger().with_mut(|li| li.sequence_number = 120);
+        assert!(client.is_expired());
+        assert_eq!(
+            client.get_lifecycle(),
+            Lifecycle {
+                status: AccountStatus::PaymentReceived,
+                expiry_ledger: 100,
+                grace_ledgers: 50,
+                grace_end_ledger: 150,
+                in_grace: true,
+            }
+        );
+        assert_eq!(client.try_expire(), Err(Ok(Error::GracePeriodActive)));
+
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+    }
+
+    #[test]
+    #[cfg(feature = "partial")]
+    fn test_expire_moves_outstanding_payments_to_recovery() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let recovery = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 100;
+        client.initialize(&Address::generate(&env), &expiry_ledger, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        let first = fund_asset(&env, &contract_id, 100);
+        let second = fund_asset(&env, &contract_id, 200);
+        client.record_payment(&100, &first);
+        client.record_payment(&200, &second);
+
+        // A partial sweep already moved the first asset elsewhere
+        let destination = Address::generate(&env);
+        let assets = vec![&env, first.clone()];
+        let digest = client
+            .get_sweep_assets_message(&destination, &assets)
+            .to_array();
+        let signature = vec![
+            &env,
+            BytesN::from_array(&env, &sweep_signing_key().sign(&digest).to_bytes()),
+        ];
+        client.sweep_assets(&destination, &assets, &signature);
+
+        env.ledger()
+            .with_mut(|ledger| ledger.sequence_number = expiry_ledger);
+        client.expire();
+        let expired: AccountExpired = event_data(&env, symbol_short!("expired"));
+        assert_eq!(expired.amount_returned, 200);
+
+        let (first, second) = (
+            TokenClient::new(&env, &first),
+            TokenClient::new(&env, &second),
+        );
+        assert_eq!(first.balance(&destination), 100);
+        assert_eq!(first.balance(&recovery), 0);
+        assert_eq!(second.balance(&recovery), 200);
+        assert_eq!(second.balance(&contract_id), 0);
+        assert_invariants(&client);
+    }
+
+    #[test]
+    #[cfg(all(feature = "config", feature = "lifecycle"))]
+    fn test_expire_after_grace_period() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _) = setup_grace(&env);
+        env.ledger().with_mut(|li| li.sequence_number = 150);
+        assert!(!client.get_lifecycle().in_grace);
+
+        let destination = Address::generate(&env);
+        let auth_sig = sign_sweep(&env, &client, &destination);
+        assert_eq!(
+            client.try_sweep(&destination, &auth_sig),
+            Err(Ok(Error::AccountExpired))
+        );
+        client.expire();
+        assert_eq!(client.get_status(), AccountStatus::Expired);
+    }
+
+    #[test]
+    #[cfg(all(feature = "native", feature = "views"))]
+    fn test_native_payment_is_netted_against_reserve() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+
+        // The balance holds the base reserve plus a 500 stroop payment
+        let native = fund_asset(&env, &contract_id, BASE_RESERVE_STROOPS + 500);
+        env.as_contract(&contract_id, || {
+            AccountState::new(&env).set_native_asset(&native)
+        });
+        let token = Address::generate(&env);
+        assert_eq!(client.get_asset_kind(&native), AssetKind::Native);
+        assert_eq!(
+            client.get_asset_kind(&token),
+            AssetKind::Contract(token.clone())
+        );
+
+        // The reserve is not a payment, whichever path records it
+        assert_eq!(
+            client.try_record_native_payment(&501, &None),
+            Err(Ok(Error::InsufficientNativeBalance))
+        );
+        assert_eq!(
+            client.try_record_payment(&BASE_RESERVE_STROOPS, &native),
+            Err(Ok(Error::InsufficientNativeBalance))
+        );
+        let sender = Address::generate(&env);
+        client.record_native_payment(&500, &Some(sender.clone()));
+        let payment = client.get_payment_by_asset(&native).unwrap();
+        assert_eq!(payment.amount, 500);
+        assert_eq!(payment.sender, Some(sender));
+
+        // The sweep pays out the payment and the reclaimed reserve in XLM
+        let destination = Address::generate(&env);
+        let auth_sig = sign_sweep(&env, &client, &destination);
+        client.sweep(&destination, &auth_sig);
+        let native_client = TokenClient::new(&env, &native);
+        assert_eq!(
+            native_client.balance(&destination),
+            BASE_RESERVE_STROOPS + 500
+        );
+        assert_eq!(native_client.balance(&contract_id), 0);
+        assert_eq!(latest_reserve_event(&client).amount, BASE_RESERVE_STROOPS);
+    }
+
+    #[test]
+    #[cfg(all(feature = "keeper", feature = "native"))]
+    fn test_keeper_bounty_paid_from_reclaimed_reserve() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let recovery = Address::generate(&env);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 100),
+            &recovery,
+            &AccountConfig {
+                keeper_bounty_bps: Some(500),
+                ..Default::default()
+            },
+        );
+        let native = fund_asset(&env, &contract_id, BASE_RESERVE_STROOPS + 500);
+        env.as_contract(&contract_id, || {
+            AccountState::new(&env).set_native_asset(&native)
+        });
+        client.record_native_payment(&500, &None);
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+
+        // 5% of the reclaimed reserve goes to whoever expired the account
+        let keeper = Address::generate(&env);
+        client.expire_as(&keeper);
+        let bounty = BASE_RESERVE_STROOPS / 20;
+        let event = latest_reserve_event(&client);
+        assert_eq!(event.amount, BASE_RESERVE_STROOPS);
+        assert_eq!(event.keeper, Some(keeper.clone()));
+        assert_eq!(event.keeper_bounty, bounty);
+        // The payment goes to the recovery address along with the rest
+        let native_client = TokenClient::new(&env, &native);
+        assert_eq!(native_client.balance(&keeper), bounty);
+        assert_eq!(
+            native_client.balance(&recovery),
+            500 + BASE_RESERVE_STROOPS - bounty
+        );
+        assert_eq!(native_client.balance(&contract_id), 0);
+    }
+
+    /// A reserve not held in XLM is settled off-chain, so the bounty on it
+    /// is reported as owed to the keeper rather than transferred.
+    #[test]
+    #[cfg(feature = "keeper")]
+    fn test_keeper_bounty_owed_without_native_reserve() {
+        use crate::AuthPolicy;
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let recovery = Address::generate(&env);
+        let keeper = Address::generate(&env);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 100),
+            &recovery,
+            &AccountConfig {
+                auth_policy: AuthPolicy::Restricted,
+                keeper: Some(keeper.clone()),
+                keeper_bounty_bps: Some(500),
+                ..Default::default()
+            },
+        );
+        let asset = fund_asset(&env, &contract_id, 500);
+        client.record_payment(&500, &asset);
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+
+        client.expire_as(&keeper);
+        assert_eq!(client.get_status(), AccountStatus::Expired);
+        let event = latest_reserve_event(&client);
+        assert_eq!(event.amount, BASE_RESERVE_STROOPS);
+        assert_eq!(event.keeper, Some(keeper.clone()));
+        assert_eq!(event.keeper_bounty, BASE_RESERVE_STROOPS / 20);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&recovery), 500);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&keeper), 0);
+    }
+
+    /// The bounty rises from the end of the grace period until it reaches
+    /// its cap, so a keeper is eventually paid enough to expire any account.
+    #[test]
+    #[cfg(all(feature = "keeper", feature = "native"))]
+    fn test_keeper_bounty_escalates_past_grace_period() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 100),
+            &Address::generate(&env),
+            &AccountConfig {
+                grace_ledgers: 50,
+                keeper_bounty_bps: Some(100),
+                keeper_bounty_max_bps: Some(1_100),
+                keeper_bounty_ramp_ledgers: 1_000,
+                ..Default::default()
+            },
+        );
+        let native = fund_asset(&env, &contract_id, BASE_RESERVE_STROOPS + 500);
+        env.as_contract(&contract_id, || {
+            AccountState::new(&env).set_native_asset(&native)
+        });
+        client.record_native_payment(&500, &None);
+        assert_eq!(client.get_expiry_bounty_bps(), 100);
+
+        // Flat until the account can be expired, then linear to the cap
+        env.ledger().with_mut(|li| li.sequence_number += 150);
+        assert_eq!(client.get_expiry_bounty_bps(), 100);
+        env.ledger().with_mut(|li| li.sequence_number += 500);
+        assert_eq!(client.get_expiry_bounty_bps(), 600);
+        env.ledger().with_mut(|li| li.sequence_number += 5_000);
+        assert_eq!(client.get_expiry_bounty_bps(), 1_100);
+
+        let keeper = Address::generate(&env);
+        client.expire_as(&keeper);
+        let bounty = BASE_RESERVE_STROOPS * 11 / 100;
+        assert_eq!(latest_reserve_event(&client).keeper_bounty, bounty);
+        assert_eq!(TokenClient::new(&env, &native).balance(&keeper), bounty);
+    }
+
+    #[test]
+    #[cfg(feature = "keeper")]
+    fn test_keeper_bounty_cap_must_cover_bounty() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let config = |max_bps| AccountConfig {
+            keeper_bounty_bps: Some(500),
+            keeper_bounty_max_bps: Some(max_bps),
+            ..Default::default()
+        };
+        for max_bps in [499, 10_001] {
+            assert_eq!(
+                client.try_initialize_with_config(
+                    &Address::generate(&env),
+                    &(env.ledger().sequence() + 100),
+                    &Address::generate(&env),
+                    &config(max_bps),
+                ),
+                Err(Ok(Error::InvalidFee))
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "keeper")]
+    fn test_keeper_bounty_must_be_valid_bps() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        assert_eq!(
+            client.try_initialize_with_config(
+                &Address::generate(&env),
+                &(env.ledger().sequence() + 100),
+                &Address::generate(&env),
+                &AccountConfig {
+                    keeper_bounty_bps: Some(10_001),
+                    ..Default::default()
+                },
+            ),
+            Err(Ok(Error::InvalidFee))
+        );
+    }
+
+    /// Write the unversioned layout of the first release into an
+    /// uninitialized contract: payments in an instance map, the settlement
+    /// ledger as the sweep id, and only the latest reserve event.
+    #[cfg(feature = "upgrade")]
+    fn seed_v0_account(
+        env: &Env,
+        contract_id: &Address,
+        status: AccountStatus,
+        payments: &[(Address, i128)],
+        last_reclaim: Option<ReserveReclaimedV0>,
+    ) {
+        env.as_contract(contract_id, || {
+            let storage = env.storage().instance();
+            storage.set(&DataKey::Initialized, &true);
+            storage.set(&DataKey::Creator, &Address::generate(env));
+            storage.set(&DataKey::ExpiryLedger, &(env.ledger().sequence() + 1000));
+            storage.set(&DataKey::RecoveryAddress, &Address::generate(env));
+            storage.set(&DataKey::Status, &status);
+
+            let mut map = Map::new(env);
+            for (asset, amount) in payments {
+                map.set(
+                    asset.clone(),
+                    PaymentV0 {
+                        asset: asset.clone(),
+                        amount: *amount,
+                        timestamp: 0,
+                    },
+                );
+            }
+            if !map.is_empty() {
+                storage.set(&v0_key(env, "Payments"), &map);
+            }
+
+            let remaining = match &last_reclaim {
+                Some(event) => event.remaining_reserve,
+                None => BASE_RESERVE_STROOPS,
+            };
+            storage.set(&DataKey::BaseReserveRemaining, &remaining);
+            storage.set(&DataKey::AvailableReserve, &remaining);
+            storage.set(&DataKey::ReserveReclaimed, &(remaining == 0));
+            match last_reclaim {
+                Some(event) => {
+                    storage.set(&DataKey::SweptTo, &event.destination);
+                    storage.set(&DataKey::LastSweepId, &event.sweep_id);
+                    storage.set(&v0_key(env, "ReserveEventCount"), &1u32);
+                    storage.set(&v0_key(env, "LastReserveEvent"), &event);
+                }
+                None => {
+                    storage.set(&DataKey::LastSweepId, &0u64);
+                    storage.set(&v0_key(env, "ReserveEventCount"), &0u32);
+                }
+            }
+        });
+    }
+
+    #[test]
+    #[cfg(all(feature = "history", feature = "upgrade", feature = "views"))]
+    fn test_migration_from_first_release_layout() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        // A paid account, not yet swept
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let first = fund_asset(&env, &contract_id, 10);
+        let second = fund_asset(&env, &contract_id, 20);
+        seed_v0_account(
+            &env,
+            &contract_id,
+            AccountStatus::PaymentReceived,
+            &[(first.clone(), 10), (second.clone(), 20)],
+            None,
+        );
+        assert_eq!(client.get_storage_version(), 0);
+
+        client.migrate();
+        assert_eq!(client.get_storage_version(), crate::STORAGE_VERSION);
+        assert_eq!(client.get_payment_count(), 2);
+        let payment = client.get_payment_by_asset(&second).unwrap();
+        assert_eq!(payment.amount, 20);
+        assert_eq!(payment.sender, None);
+        assert_eq!(payment.memo, None);
+        assert_eq!(client.get_sweep_id(), None);
+        assert_eq!(client.get_reserve_reclaim_summary().event_count, 0);
+        env.as_contract(&contract_id, || {
+            let storage = env.storage().instance();
+            assert!(!storage.has(&v0_key(&env, "Payments")));
+            assert!(!storage.has(&v0_key(&env, "ReserveEventCount")));
+        });
+        assert_invariants(&client);
+
+        // The migrated payments sweep like recorded ones
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(TokenClient::new(&env, &first).balance(&destination), 10);
+        assert_eq!(TokenClient::new(&env, &second).balance(&destination), 20);
+
+        // A swept account, its reserve reclaimed at ledger 1234
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let destination = Address::generate(&env);
+        seed_v0_account(
+            &env,
+            &contract_id,
+            AccountStatus::Swept,
+            &[(Address::generate(&env), 10)],
+            Some(ReserveReclaimedV0 {
+                destination: destination.clone(),
+                amount: BASE_RESERVE_STROOPS,
+                sweep_id: 1234,
+                fully_reclaimed: true,
+                remaining_reserve: 0,
+            }),
+        );
+
+        client.migrate();
+        assert_eq!(client.get_sweep_id(), Some(legacy_sweep_id(&env, 1234)));
+        assert_eq!(
+            client.get_last_reserve_event(),
+            Some(ReserveReclaimed {
+                destination,
+                amount: BASE_RESERVE_STROOPS,
+                sweep_id: legacy_sweep_id(&env, 1234),
+                fully_reclaimed: true,
+                remaining_reserve: 0,
+                keeper: None,
+                keeper_bounty: 0,
+            })
+        );
+        let summary = client.get_reserve_reclaim_summary();
+        assert_eq!(summary.total_reclaimed, BASE_RESERVE_STROOPS);
+        assert_eq!(summary.reclaim_count, 1);
+        assert_eq!(summary.event_count, 1);
+        assert_invariants(&client);
+    }
+
+    #[test]
+    #[cfg(feature = "upgrade")]
+    fn test_getter_migrates_first_release_layout() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let asset = Address::generate(&env);
+        seed_v0_account(
+            &env,
+            &contract_id,
+            AccountStatus::PaymentReceived,
+            &[(asset.clone(), 25)],
+            None,
+        );
+
+        // No migrate call: the first getter brings the layout up to date
+        let info = client.get_info();
+        assert_eq!(info.payment_count, 1);
+        assert_eq!(info.payments.get(0).unwrap().asset, asset);
+        assert_eq!(info.payments.get(0).unwrap().amount, 25);
+        assert_eq!(client.get_storage_version(), crate::STORAGE_VERSION);
+        env.as_contract(&contract_id, || {
+            assert!(!env.storage().instance().has(&v0_key(&env, "Payments")));
+        });
+        assert_invariants(&client);
+    }
+
+    #[test]
+    #[cfg(feature = "config")]
+    fn test_external_chain_ref_is_included_in_sweep_event() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let destination = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+        let chain_ref = ExternalChainRef {
+            chain_id: 1,
+            commitment: BytesN::from_array(&env, &[9u8; 32]),
+        };
+
+        client.initialize_with_config(
+            &creator,
+            &expiry_ledger,
+            &recovery,
+            &AccountConfig {
+                external_chain_ref: ChainLink::Linked(chain_ref.clone()),
+                sweep_signer: Some(sweep_signer_key(&env)),
+                ..Default::default()
+            },
+        );
+        assert_eq!(client.get_external_chain_ref(), Some(chain_ref.clone()));
+
+        client.record_payment(&100, &fund_asset(&env, &contract_id, 100));
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+
+        let swept: SweepExecutedMulti = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| event_name(&env, topics) == Some(symbol_short!("swept_mul")))
+            .map(|(_, _, data)| data.into_val(&env))
+            .expect("sweep event was not emitted");
+        assert_eq!(swept.external_chain_ref, ChainLink::Linked(chain_ref));
+    }
+
+    #[cfg(feature = "partial")]
+    fn sign_sweep_assets(
+        env: &Env,
+        client: &EphemeralAccountContractClient,
+        destination: &Address,
+        assets: &Vec<Address>,
+    ) -> Vec<BytesN<64>> {
+        let digest = client
+            .get_sweep_assets_message(destination, assets)
+            .to_array();
+        vec![
+            env,
+            BytesN::from_array(env, &sweep_signing_key().sign(&digest).to_bytes()),
+        ]
+    }
+
+    #[test]
+    #[cfg(feature = "partial")]
+    fn test_sweep_assets_moves_only_listed_payments() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+
+        let wanted = fund_asset(&env, &contract_id, 100);
+        let stray = fund_asset(&env, &contract_id, 40);
+        client.record_payment(&100, &wanted);
+        client.record_payment(&40, &stray);
+
+        let refund = Address::generate(&env);
+        let assets = vec![&env, stray.clone()];
+        client.sweep_assets(
+            &refund,
+            &assets,
+            &sign_sweep_assets(&env, &client, &refund, &assets),
+        );
+
+        assert_eq!(TokenClient::new(&env, &stray).balance(&refund), 40);
+        assert_eq!(TokenClient::new(&env, &wanted).balance(&contract_id), 100);
+        assert_eq!(client.get_swept_assets(), assets);
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+        assert!(!client.is_reserve_reclaimed());
+
+        // An already swept asset cannot be swept again.
+        assert_eq!(
+            client.try_sweep_assets(
+                &refund,
+                &assets,
+                &sign_sweep_assets(&env, &client, &refund, &assets)
+            ),
+            Err(Ok(Error::AssetNotFound))
+        );
+
+        // The full sweep only moves what is still outstanding.
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(TokenClient::new(&env, &wanted).balance(&destination), 100);
+        assert_eq!(TokenClient::new(&env, &stray).balance(&destination), 0);
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+    }
+
+    #[test]
+    #[cfg(feature = "partial")]
+    fn test_sweep_assets_clearing_last_payment_completes_sweep() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+
+        let destination = Address::generate(&env);
+        let unknown = vec![&env, Address::generate(&env)];
+        assert_eq!(
+            client.try_sweep_assets(
+                &destination,
+                &unknown,
+                &sign_sweep_assets(&env, &client, &destination, &unknown)
+            ),
+            Err(Ok(Error::AssetNotFound))
+        );
+
+        let assets = vec![&env, asset.clone()];
+        client.sweep_assets(
+            &destination,
+            &assets,
+            &sign_sweep_assets(&env, &client, &destination, &assets),
+        );
+
+        let partial: PartialSweepExecuted = event_data(&env, symbol_short!("swept_prt"));
+        assert_eq!(partial.remaining_assets, 0);
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+        assert_eq!(client.get_info().swept_to, Some(destination));
+        assert!(client.is_reserve_reclaimed());
+    }
+
+    #[cfg(all(feature = "attribution", feature = "refunds"))]
+    fn sign_refund(
+        env: &Env,
+        client: &EphemeralAccountContractClient,
+        asset: &Address,
+    ) -> Vec<BytesN<64>> {
+        let digest = client.get_refund_message(asset).to_array();
+        vec![
+            env,
+            BytesN::from_array(env, &sweep_signing_key().sign(&digest).to_bytes()),
+        ]
+    }
+
+    #[test]
+    #[cfg(all(feature = "attribution", feature = "refunds"))]
+    fn test_refund_payment_returns_funds_to_sender() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+
+        let sender = Address::generate(&env);
+        let wanted = fund_asset(&env, &contract_id, 100);
+        let wrong = fund_asset(&env, &contract_id, 40);
+        client.record_payment(&100, &wanted);
+        client.record_payment_from(&40, &wrong, &sender, &None);
+        assert_eq!(
+            client.get_info().payments.get(1).unwrap().sender,
+            Some(sender.clone())
+        );
+
+        client.refund_payment(&wrong, &sign_refund(&env, &client, &wrong));
+        let refunded: PaymentRefunded = event_data(&env, symbol_short!("refunded"));
+        assert_eq!(refunded.asset, wrong);
+        assert_eq!(refunded.sender, sender);
+        assert_eq!(refunded.amount, 40);
+
+        assert_eq!(TokenClient::new(&env, &wrong).balance(&sender), 40);
+        let remaining = client.get_info().payments;
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining.get(0).unwrap().asset, wanted);
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+
+        // A payment without a recorded sender cannot be refunded.
+        assert_eq!(
+            client.try_get_refund_message(&wanted),
+            Err(Ok(Error::SenderUnknown))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "attribution", feature = "config", feature = "refunds"))]
+    fn test_refund_returns_each_sender_their_share() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                accumulate_payments: true,
+                sweep_signer: Some(sweep_signer_key(&env)),
+                ..Default::default()
+            },
+        );
+
+        // Two senders top up the same asset
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment_from(&60, &asset, &first, &None);
+        client.record_payment_from(&30, &asset, &second, &None);
+        client.record_payment_from(&10, &asset, &first, &None);
+        assert_eq!(client.get_info().payments.get(0).unwrap().amount, 100);
+
+        client.refund_payment(&asset, &sign_refund(&env, &client, &asset));
+        let refunds: std::vec::Vec<PaymentRefunded> = env
+            .events()
+            .all()
+            .iter()
+            .filter(|(_, topics, _)| event_name(&env, topics) == Some(symbol_short!("refunded")))
+            .map(|(_, _, data)| PaymentRefunded::try_from_val(&env, &data).unwrap())
+            .collect();
+        assert_eq!(refunds.len(), 2);
+        for (sender, amount) in [(&first, 70), (&second, 30)] {
+            assert!(refunds.contains(&PaymentRefunded {
+                asset: asset.clone(),
+                sender: sender.clone(),
+                amount,
+            }));
+        }
+        let token = TokenClient::new(&env, &asset);
+        assert_eq!(token.balance(&first), 70);
+        assert_eq!(token.balance(&second), 30);
+        assert_eq!(token.balance(&contract_id), 0);
+        assert_eq!(client.get_status(), AccountStatus::Active);
+        assert_invariants(&client);
+
+        // A share paid without a sender blocks the refund
+        client.record_payment_from(&50, &asset, &first, &None);
+        client.record_payment(&20, &asset);
+        assert_eq!(
+            client.try_get_refund_message(&asset),
+            Err(Ok(Error::SenderUnknown))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "attribution", feature = "refunds"))]
+    fn test_refund_only_payment_reopens_account() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+
+        let sender = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment_from(&100, &asset, &sender, &None);
+        let signature = sign_refund(&env, &client, &asset);
+        client.refund_payment(&asset, &signature);
+
+        assert_eq!(client.get_status(), AccountStatus::Active);
+        assert_eq!(client.get_info().payment_count, 0);
+        assert_invariants(&client);
+
+        // The refund cannot be replayed against the reopened account.
+        assert_eq!(
+            client.try_refund_payment(&asset, &signature),
+            Err(Ok(Error::NoPaymentReceived))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "attribution", feature = "refunds", feature = "config"))]
+    fn test_reserve_pause_blocks_refunds() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let reserve_id = env.register(ReserveContract, ());
+        let reserve = ReserveContractClient::new(&env, &reserve_id);
+        let admin = Address::generate(&env);
+        reserve.initialize(&admin);
+        reserve.set_base_reserve(&admin, &25_000_000);
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                reserve_contract: Some(reserve_id),
+                sweep_signer: Some(sweep_signer_key(&env)),
+                ..Default::default()
+            },
+        );
+        let sender = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment_from(&100, &asset, &sender, &None);
+        let signature = sign_refund(&env, &client, &asset);
+
+        reserve.pause(&admin);
+        assert_eq!(
+            client.try_refund_payment(&asset, &signature),
+            Err(Ok(Error::SystemPaused))
+        );
+        assert_eq!(TokenClient::new(&env, &asset).balance(&sender), 0);
+
+        reserve.unpause(&admin);
+        client.refund_payment(&asset, &signature);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&sender), 100);
+    }
+
+    #[test]
+    #[cfg(all(feature = "attribution", feature = "refunds", feature = "partial"))]
+    fn test_refund_of_last_unswept_payment_completes_sweep() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+
+        let sender = Address::generate(&env);
+        let wanted = fund_asset(&env, &contract_id, 100);
+        let wrong = fund_asset(&env, &contract_id, 40);
+        client.record_payment(&100, &wanted);
+        client.record_payment_from(&40, &wrong, &sender, &None);
+
+        let destination = Address::generate(&env);
+        let assets = vec![&env, wanted.clone()];
+        client.sweep_assets(
+            &destination,
+            &assets,
+            &sign_sweep_assets(&env, &client, &destination, &assets),
+        );
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+
+        client.refund_payment(&wrong, &sign_refund(&env, &client, &wrong));
+
+        assert_eq!(TokenClient::new(&env, &wrong).balance(&sender), 40);
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+        assert_eq!(client.get_info().swept_to, Some(destination));
+        assert!(client.is_reserve_reclaimed());
+        assert_invariants(&client);
+    }
+
+    /// Initialize an escrow-mode account holding one paid asset.
+    #[cfg(feature = "escrow")]
+    fn setup_escrow(
+        env: &Env,
+        arbiter: &Address,
+        recovery: &Address,
+    ) -> (EphemeralAccountContractClient<'static>, Address) {
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(env),
+            &(env.ledger().sequence() + 1000),
+            recovery,
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(env)),
+                escrow_arbiter: Some(arbiter.clone()),
+                hold_ledgers: 100,
+                ..Default::default()
+            },
+        );
+        let asset = fund_asset(env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+        (client, asset)
+    }
+
+    #[test]
+    #[cfg(feature = "escrow")]
+    fn test_escrow_sweep_held_until_finalized() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, asset) =
+            setup_escrow(&env, &Address::generate(&env), &Address::generate(&env));
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+
+        let held: SweepHeld = event_data(&env, symbol_short!("held"));
+        let release_after_ledger = env.ledger().sequence() + 100;
+        assert_eq!(held.release_after_ledger, release_after_ledger);
+        assert_eq!(client.get_status(), AccountStatus::PendingRelease);
+        assert_eq!(
+            client.get_pending_release(),
+            Some(PendingRelease {
+                destination: destination.clone(),
+                release_after_ledger,
+            })
+        );
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 0);
+        assert_invariants(&client);
+
+        // Held accounts accept no payments and cannot be expired.
+        assert_eq!(
+            client.try_record_payment(&10, &Address::generate(&env)),
+            Err(Ok(Error::InvalidStatus))
+        );
+        assert_eq!(client.try_finalize_sweep(), Err(Ok(Error::HoldNotElapsed)));
+
+        // The hold outlives the account's expiry ledger.
+        env.ledger().with_mut(|li| li.sequence_number = 1000);
+        assert_eq!(client.try_expire(), Err(Ok(Error::InvalidStatus)));
+        assert_eq!(client.try_dispute(), Err(Ok(Error::HoldElapsed)));
+
+        client.finalize_sweep();
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+        assert_eq!(client.get_info().swept_to, Some(destination));
+        assert_eq!(client.get_pending_release(), None);
+        assert_invariants(&client);
+        assert_eq!(client.try_finalize_sweep(), Err(Ok(Error::InvalidStatus)));
+    }
+
+    #[test]
+    #[cfg(feature = "escrow")]
+    fn test_escrow_dispute_redirects_to_recovery() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let arbiter = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let (client, asset) = setup_escrow(&env, &arbiter, &recovery);
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+
+        client.dispute();
+        let disputed: SweepDisputed = event_data(&env, symbol_short!("disputed"));
+        assert_eq!(disputed.arbiter, arbiter);
+        assert_eq!(disputed.destination, destination);
+        assert_eq!(disputed.recovery_address, recovery);
+
+        assert_eq!(TokenClient::new(&env, &asset).balance(&recovery), 100);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 0);
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+        assert_eq!(client.get_info().swept_to, Some(recovery));
+        assert_invariants(&client);
+    }
+
+    #[test]
+    #[cfg(all(feature = "escrow", feature = "partial"))]
+    fn test_escrow_rejects_partial_sweeps() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, asset) =
+            setup_escrow(&env, &Address::generate(&env), &Address::generate(&env));
+        let destination = Address::generate(&env);
+        let assets = vec![&env, asset];
+        assert_eq!(
+            client.try_sweep_assets(
+                &destination,
+                &assets,
+                &sign_sweep_assets(&env, &client, &destination, &assets)
+            ),
+            Err(Ok(Error::HoldRequired))
+        );
+        assert_eq!(client.try_dispute(), Err(Ok(Error::InvalidStatus)));
+    }
+
+    /// A signature over one asset subset must not authorize another.
+    #[test]
+    #[cfg(feature = "partial")]
+    fn test_sweep_assets_signature_is_bound_to_asset_list() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+
+        let first = fund_asset(&env, &contract_id, 10);
+        let second = fund_asset(&env, &contract_id, 20);
+        client.record_payment(&10, &first);
+        client.record_payment(&20, &second);
+
+        let destination = Address::generate(&env);
+        let signed = vec![&env, first.clone()];
+        let signature = sign_sweep_assets(&env, &client, &destination, &signed);
+
+        // A well-formed signature over other assets traps in the host verifier
+        let result = client.try_sweep_assets(&destination, &vec![&env, second], &signature);
+        assert!(matches!(result, Err(Err(_))));
+        assert_eq!(client.get_swept_assets().len(), 0);
+    }
+
+    #[cfg(feature = "partial")]
+    fn sign_sweep_split(
+        env: &Env,
+        client: &EphemeralAccountContractClient,
+        destinations: &Vec<(Address, u32)>,
+    ) -> Vec<BytesN<64>> {
+        let digest = client.get_sweep_split_message(destinations).to_array();
+        vec![
+            env,
+            BytesN::from_array(env, &sweep_signing_key().sign(&digest).to_bytes()),
+        ]
+    }
+
+    #[test]
+    #[cfg(feature = "partial")]
+    fn test_sweep_split_divides_payments_by_share() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+
+        let asset = fund_asset(&env, &contract_id, 1_001);
+        client.record_payment(&1_001, &asset);
+
+        let seller = Address::generate(&env);
+        let platform = Address::generate(&env);
+        let destinations = vec![&env, (seller.clone(), 9_750), (platform.clone(), 250)];
+        client.sweep_split(
+            &destinations,
+            &sign_sweep_split(&env, &client, &destinations),
+        );
+
+        let split_events: std::vec::Vec<SplitSweepExecuted> = env
+            .events()
+            .all()
+            .iter()
+            .filter(|(_, topics, _)| event_name(&env, topics) == Some(symbol_short!("swept_spl")))
+            .map(|(_, _, data)| data.into_val(&env))
+            .collect();
+        assert_eq!(split_events.len(), 2);
+        assert_eq!(split_events[0].destination, seller);
+        assert_eq!(split_events[0].payments.get(0).unwrap().amount, 976);
+        assert_eq!(split_events[1].destination, platform);
+        assert_eq!(split_events[1].share_bps, 250);
+
+        // 2.5% of 1001 is 25.025: the platform gets 25, the seller the rest.
+        let token = TokenClient::new(&env, &asset);
+        assert_eq!(token.balance(&platform), 25);
+        assert_eq!(token.balance(&seller), 976);
+        assert_eq!(token.balance(&contract_id), 0);
+
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+        assert_eq!(client.get_info().swept_to, Some(seller.clone()));
+        assert_eq!(latest_reserve_event(&client).destination, seller);
+    }
+
+    #[test]
+    #[cfg(feature = "partial")]
+    fn test_sweep_split_rejects_invalid_shares() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        client.record_payment(&100, &fund_asset(&env, &contract_id, 100));
+
+        let seller = Address::generate(&env);
+        let platform = Address::generate(&env);
+        for destinations in [
+            Vec::new(&env),
+            vec![&env, (seller.clone(), 9_000), (platform.clone(), 500)],
+            vec![&env, (seller.clone(), 10_000), (platform.clone(), 0)],
+        ] {
+            assert_eq!(
+                client.try_sweep_split(
+                    &destinations,
+                    &sign_sweep_split(&env, &client, &destinations)
+                ),
+                Err(Ok(Error::InvalidShares))
+            );
+        }
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "audit",
+        feature = "config",
+        feature = "partial",
+        feature = "views"
+    ))]
+    fn test_sweep_refused_to_destination_that_cannot_receive() {
+        use soroban_sdk::testutils::IssuerFlags;
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(&env)),
+                ..Default::default()
+            },
+        );
+        let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        sac.issuer().set_flag(IssuerFlags::RevocableFlag);
+        let regulated = sac.address();
+        StellarAssetClient::new(&env, &regulated).mint(&contract_id, &100);
+        let plain = fund_asset(&env, &contract_id, 50);
+        client.record_payment(&100, &regulated);
+        client.record_payment(&50, &plain);
+
+        // The issuer deauthorized the destination
+        let destination = Address::generate(&env);
+        StellarAssetClient::new(&env, &regulated).set_authorized(&destination, &false);
+        assert_eq!(
+            client.get_unreceivable_assets(&destination),
+            vec![&env, regulated.clone()]
+        );
+        assert_eq!(
+            client.preview_sweep(&destination).error_code,
+            Some(Error::DestinationCannotReceive as u32)
+        );
+        assert_eq!(
+            client.try_sweep(&destination, &sign_sweep(&env, &client, &destination)),
+            Err(Ok(Error::DestinationCannotReceive))
+        );
+        let assets = vec![&env, regulated.clone()];
+        assert_eq!(
+            client.try_sweep_assets(
+                &destination,
+                &assets,
+                &sign_sweep_assets(&env, &client, &destination, &assets)
+            ),
+            Err(Ok(Error::DestinationCannotReceive))
+        );
+        assert_eq!(
+            TokenClient::new(&env, &regulated).balance(&contract_id),
+            100
+        );
+
+        // Assets it can still hold go through
+        let assets = vec![&env, plain.clone()];
+        client.sweep_assets(
+            &destination,
+            &assets,
+            &sign_sweep_assets(&env, &client, &destination, &assets),
+        );
+        assert_eq!(TokenClient::new(&env, &plain).balance(&destination), 50);
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+    }
+
+    #[test]
+    #[cfg(feature = "quarantine")]
+    fn test_quarantine_settles_what_it_can_and_retries_the_rest() {
+        use soroban_sdk::testutils::IssuerFlags;
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(&env)),
+                quarantine_failed_transfers: true,
+                ..Default::default()
+            },
+        );
+        let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        sac.issuer().set_flag(IssuerFlags::RevocableFlag);
+        let regulated = sac.address();
+        StellarAssetClient::new(&env, &regulated).mint(&contract_id, &100);
+        let plain = fund_asset(&env, &contract_id, 50);
+        client.record_payment(&100, &regulated);
+        client.record_payment(&50, &plain);
+
+        // The issuer deauthorized the destination; the sweep still settles
+        // the plain asset and keeps the regulated one
+        let destination = Address::generate(&env);
+        StellarAssetClient::new(&env, &regulated).set_authorized(&destination, &false);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        let quarantined: AssetsQuarantined = event_data(&env, symbol_short!("quarantin"));
+        assert_eq!(
+            quarantined,
+            AssetsQuarantined {
+                destination: destination.clone(),
+                assets: vec![&env, regulated.clone()],
+            }
+        );
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+        assert_eq!(TokenClient::new(&env, &plain).balance(&destination), 50);
+        assert_eq!(
+            TokenClient::new(&env, &regulated).balance(&contract_id),
+            100
+        );
+        assert_eq!(client.get_quarantined().len(), 1);
+
+        // Still refused, and still quarantined
+        env.ledger().with_mut(|li| li.sequence_number += 12);
+        assert!(!client.retry_quarantined(&regulated));
+        assert_eq!(client.get_quarantined().len(), 1);
+        assert_eq!(
+            client.try_retry_quarantined(&plain),
+            Err(Ok(Error::AssetNotFound))
+        );
+
+        // Once re-authorized and the backoff has passed, anyone can send it
+        // on
+        StellarAssetClient::new(&env, &regulated).set_authorized(&destination, &true);
+        env.ledger().with_mut(|li| li.sequence_number += 24);
+        assert!(client.retry_quarantined(&regulated));
+        let released: QuarantineReleased = event_data(&env, symbol_short!("q_release"));
+        assert_eq!(released.destination, destination);
+        assert_eq!(released.amount.net, 100);
+        assert_eq!(
+            TokenClient::new(&env, &regulated).balance(&destination),
+            100
+        );
+        assert_eq!(client.get_quarantined().len(), 0);
+        assert_eq!(client.get_quarantine_retry(&regulated), None);
+    }
+
+    /// A retry before the asset's next retry ledger is refused, and each
+    /// failed retry doubles the wait before the next.
+    #[test]
+    #[cfg(feature = "quarantine")]
+    fn test_quarantine_retry_backs_off_exponentially() {
+        use soroban_sdk::testutils::IssuerFlags;
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(&env)),
+                quarantine_failed_transfers: true,
+                ..Default::default()
+            },
+        );
+        let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        sac.issuer().set_flag(IssuerFlags::RevocableFlag);
+        let regulated = sac.address();
+        StellarAssetClient::new(&env, &regulated).mint(&contract_id, &100);
+        client.record_payment(&100, &regulated);
+        let destination = Address::generate(&env);
+        StellarAssetClient::new(&env, &regulated).set_authorized(&destination, &false);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+
+        // The sweep's failed transfer starts the base wait
+        let swept_at = env.ledger().sequence();
+        assert_eq!(
+            client.get_quarantine_retry(&regulated),
+            Some(QuarantineRetry {
+                attempts: 0,
+                next_retry_ledger: swept_at + 12,
+            })
+        );
+        assert_eq!(
+            client.try_retry_quarantined(&regulated),
+            Err(Ok(Error::RetryTooEarly))
+        );
+
+        // Each failure doubles the wait: 24, then 48 ledgers
+        let mut waits = std::vec::Vec::new();
+        for _ in 0..2 {
+            let next = client.get_quarantine_retry(&regulated).unwrap();
+            env.ledger()
+                .with_mut(|li| li.sequence_number = next.next_retry_ledger);
+            assert!(!client.retry_quarantined(&regulated));
+            let failed: QuarantineRetryFailed = event_data(&env, symbol_short!("q_retry"));
+            assert_eq!(failed.asset, regulated);
+            waits.push(failed.next_retry_ledger - next.next_retry_ledger);
+            assert_eq!(
+                client.try_retry_quarantined(&regulated),
+                Err(Ok(Error::RetryTooEarly))
+            );
+        }
+        assert_eq!(waits, [24, 48]);
+        assert_eq!(client.get_quarantine_retry(&regulated).unwrap().attempts, 2);
+        assert_eq!(client.get_quarantined().len(), 1);
+    }
+
+    #[test]
+    #[cfg(all(feature = "escrow", feature = "partial"))]
+    fn test_clawback_hold_delays_release_and_nets_clawbacks() {
+        use soroban_sdk::testutils::IssuerFlags;
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        sac.issuer().set_flag(IssuerFlags::ClawbackEnabledFlag);
+        let regulated = sac.address();
+        let mut holds = Map::new(&env);
+        holds.set(regulated.clone(), 100u32);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(&env)),
+                clawback_hold_ledgers: Some(holds),
+                ..Default::default()
+            },
+        );
+        StellarAssetClient::new(&env, &regulated).mint(&contract_id, &100);
+        let plain = fund_asset(&env, &contract_id, 50);
+        client.record_payment(&100, &regulated);
+        client.record_payment(&50, &plain);
+
+        // Only a held sweep may move a regulated asset
+        let destination = Address::generate(&env);
+        assert_eq!(
+            client.try_sweep_assets(
+                &destination,
+                &vec![&env, regulated.clone()],
+                &Vec::new(&env)
+            ),
+            Err(Ok(Error::HoldRequired))
+        );
+        assert_eq!(
+            client.try_sweep_split(&vec![&env, (destination.clone(), 10_000)], &Vec::new(&env)),
+            Err(Ok(Error::HoldRequired))
+        );
+
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        let held: ClawbackHeld = event_data(&env, symbol_short!("cb_held"));
+        let release_after_ledger = env.ledger().sequence() + 100;
+        assert_eq!(held.assets, vec![&env, regulated.clone()]);
+        assert_eq!(held.release_after_ledger, release_after_ledger);
+        assert_eq!(client.get_status(), AccountStatus::PendingRelease);
+        assert_eq!(TokenClient::new(&env, &plain).balance(&destination), 0);
+        assert_eq!(client.try_finalize_sweep(), Err(Ok(Error::HoldNotElapsed)));
+
+        // The issuer claws part of the payment back during the hold
+        StellarAssetClient::new(&env, &regulated).clawback(&contract_id, &30);
+        env.ledger()
+            .with_mut(|li| li.sequence_number = release_after_ledger);
+
+        client.finalize_sweep();
+        let clawed: PaymentClawedBack = event_data(&env, symbol_short!("clawback"));
+        assert_eq!(clawed.asset, regulated);
+        assert_eq!(clawed.recorded, 100);
+        assert_eq!(clawed.clawed_back, 30);
+        let released: ClawbackReleased = event_data(&env, symbol_short!("cb_freed"));
+        assert_eq!(released.destination, destination);
+        assert_eq!(released.assets, vec![&env, regulated.clone()]);
+
+        assert_eq!(TokenClient::new(&env, &regulated).balance(&destination), 70);
+        assert_eq!(TokenClient::new(&env, &plain).balance(&destination), 50);
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+    }
+
+    /// Name of an event, read from its schema topics.
+    fn event_name(env: &Env, topics: &Vec<Val>) -> Option<Symbol> {
+        EventTopics::decode(env, topics).map(|topics| topics.name)
+    }
+
+    /// Data of the event published under `topic` by the last invocation.
+    fn event_data<T: TryFromVal<Env, Val>>(env: &Env, topic: Symbol) -> T {
+        env.events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| event_name(env, topics) == Some(topic.clone()))
+            .map(|(_, _, data)| T::try_from_val(env, &data).ok().unwrap())
+            .expect("event was not emitted")
+    }
+
+    #[test]
+    #[cfg(feature = "config")]
+    fn test_event_prefix_leads_event_topics() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let creator = Address::generate(&env);
+        let prefix = symbol_short!("acme_prd");
+        client.initialize_with_config(
+            &creator,
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                event_prefix: Some(prefix.clone()),
+                ..Default::default()
+            },
+        );
+
+        let (_, topics, _) = env.events().all().last().unwrap();
+        assert_eq!(topics.len(), 6);
+        assert_eq!(
+            EventTopics::decode(&env, &topics),
+            Some(
+                EventTopics::with_namespace(prefix, symbol_short!("created"))
+                    .with_seq(1)
+                    .with_epoch(0)
+            )
+        );
+        assert_eq!(
+            Address::try_from_val(&env, &topics.get(5).unwrap()),
+            Ok(creator)
+        );
+    }
+
+    /// A factory stamps its event prefix in without `config`, so every
+    /// account build namespaces its events.
+    #[test]
+    fn test_deployment_event_prefix_leads_event_topics() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let prefix = symbol_short!("acme_prd");
+        client.initialize_with_deployment(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &DeploymentSettings {
+                event_prefix: Some(prefix.clone()),
+                ..Default::default()
+            },
+        );
+
+        let (_, topics, _) = env.events().all().last().unwrap();
+        assert_eq!(
+            EventTopics::decode(&env, &topics),
+            Some(
+                EventTopics::with_namespace(prefix, symbol_short!("created"))
+                    .with_seq(1)
+                    .with_epoch(0)
+            )
+        );
+    }
+
+    #[test]
+    fn test_events_use_versioned_schema_topics() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        let asset = Address::generate(&env);
+        client.record_payment(&100, &asset);
+
+        let (_, topics, _) = env.events().all().last().unwrap();
+        assert_eq!(
+            EventTopics::decode(&env, &topics),
+            Some(EventTopics {
+                namespace: symbol_short!("bridgelet"),
+                name: symbol_short!("payment"),
+                version: 9,
+                seq: Some(2),
+                epoch: Some(0),
+            })
+        );
+        assert_eq!(
+            Address::try_from_val(&env, &topics.get(5).unwrap()),
+            Ok(asset)
+        );
+    }
+
+    /// State sequence numbers carried by the events of the last invocation.
+    #[cfg(feature = "views")]
+    fn event_seqs(env: &Env) -> std::vec::Vec<u64> {
+        env.events()
+            .all()
+            .iter()
+            .filter_map(|(_, topics, _)| EventTopics::decode(env, &topics)?.seq)
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "views")]
+    fn test_state_seq_numbers_every_call() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        assert_eq!(client.get_state_seq(), 0);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        assert_eq!(client.get_state_seq(), 1);
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        assert_eq!(event_seqs(&env), std::vec![2]);
+
+        for seq in 3..6 {
+            client.record_payment(&100, &fund_asset(&env, &contract_id, 100));
+            assert_eq!(event_seqs(&env), std::vec![seq]);
+        }
+        assert_eq!(client.get_state_seq(), 5);
+
+        // Calls that change nothing publish nothing and take no number
+        #[cfg(feature = "upgrade")]
+        client.migrate();
+        client.get_info();
+        assert!(event_seqs(&env).is_empty());
+        assert!(client
+            .try_record_payment(&0, &Address::generate(&env))
+            .is_err());
+        assert_eq!(client.get_state_seq(), 5);
+
+        // Every event of one call shares its number
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        let seqs = event_seqs(&env);
+        assert!(seqs.len() > 1, "sweep published {seqs:?}");
+        assert!(seqs.iter().all(|seq| *seq == 6), "sweep published {seqs:?}");
+        assert_eq!(client.get_state_seq(), 6);
+    }
+
+    #[test]
+    #[cfg(all(feature = "history", feature = "reuse", feature = "views"))]
+    fn test_reset_starts_a_new_epoch() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let factory = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
+        let destination = Address::generate(&env);
+        client.initialize_with_deployment(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &DeploymentSettings {
+                factory: Some(factory.clone()),
+                ..Default::default()
+            },
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        assert!(client.get_capabilities().reusable);
+        client.record_payment(&100, &asset);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        let nonce = client.get_sweep_nonce();
+
+        let recovery = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 500;
+        client.reset(&expiry_ledger, &recovery, &factory);
+        let reset: AccountReset = event_data(&env, symbol_short!("reset"));
+        assert_eq!(reset.epoch, 1);
+        let (_, topics, _) = env.events().all().last().unwrap();
+        assert_eq!(EventTopics::decode(&env, &topics).unwrap().epoch, Some(1));
+
+        let info = client.get_info();
+        assert_eq!(info.status, AccountStatus::Active);
+        assert_eq!(info.expiry_ledger, expiry_ledger);
+        assert_eq!(info.recovery_address, recovery);
+        assert_eq!(info.payment_count, 0);
+        assert_eq!(info.swept_to, None);
+        assert_eq!(client.get_sweep_id(), None);
+        assert_eq!(client.get_reserve_reclaim_summary().event_count, 0);
+        assert_eq!(client.get_epoch(), 1);
+        assert_eq!(client.get_sweep_nonce(), nonce);
+        assert_invariants(&client);
+
+        // The same asset can be paid and swept again
+        StellarAssetClient::new(&env, &asset).mint(&contract_id, &40);
+        client.record_payment(&40, &asset);
+        let (_, topics, _) = env.events().all().last().unwrap();
+        assert_eq!(EventTopics::decode(&env, &topics).unwrap().epoch, Some(1));
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 140);
+    }
+
+    /// Expiring to the same recovery address in two epochs yields two
+    /// sweep ids, and the new epoch holds no reserve in XLM.
+    #[test]
+    #[cfg(all(feature = "native", feature = "reuse", feature = "views"))]
+    fn test_reset_cycles_keep_sweep_ids_unique() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let factory = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 100;
+        client.initialize_with_deployment(
+            &Address::generate(&env),
+            &expiry_ledger,
+            &recovery,
+            &DeploymentSettings {
+                factory: Some(factory.clone()),
+                ..Default::default()
+            },
+        );
+        let native = fund_asset(&env, &contract_id, BASE_RESERVE_STROOPS + 500);
+        env.as_contract(&contract_id, || {
+            AccountState::new(&env).set_native_asset(&native)
+        });
+        client.record_native_payment(&500, &None);
+        let native_reserve_held = || {
+            env.as_contract(&contract_id, || {
+                AccountState::new(&env).is_native_reserve_held()
+            })
+        };
+        assert!(native_reserve_held());
+
+        env.ledger()
+            .with_mut(|ledger| ledger.sequence_number = expiry_ledger);
+        client.expire();
+        let first = client.get_sweep_id().unwrap();
+
+        let expiry_ledger = expiry_ledger + 100;
+        client.reset(&expiry_ledger, &recovery, &factory);
+        assert!(!native_reserve_held());
+        env.ledger()
+            .with_mut(|ledger| ledger.sequence_number = expiry_ledger);
+        client.expire();
+        let second = client.get_sweep_id().unwrap();
+        assert_ne!(first, second);
+
+        client.reset(&(expiry_ledger + 100), &recovery, &factory);
+        assert_eq!(client.get_epoch(), 2);
+        assert_eq!(
+            TokenClient::new(&env, &native).balance(&recovery),
+            BASE_RESERVE_STROOPS + 500
+        );
+    }
+
+    /// A quarantined payment whose balance left the account out of band,
+    /// and dust or a depeg override from the last sweep, do not survive a
+    /// reset to be paid out of the next cycle's balances.
+    #[test]
+    #[cfg(all(
+        feature = "quarantine",
+        feature = "dust",
+        feature = "depeg",
+        feature = "reuse"
+    ))]
+    fn test_reset_drops_quarantine_and_dust_left_by_last_sweep() {
+        use soroban_sdk::testutils::IssuerFlags;
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let factory = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &recovery,
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(&env)),
+                quarantine_failed_transfers: true,
+                factory: Some(factory.clone()),
+                ..Default::default()
+            },
+        );
+        let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        sac.issuer().set_flag(IssuerFlags::RevocableFlag);
+        let regulated = sac.address();
+        StellarAssetClient::new(&env, &regulated).mint(&contract_id, &100);
+        client.record_payment(&100, &regulated);
+        let destination = Address::generate(&env);
+        StellarAssetClient::new(&env, &regulated).set_authorized(&destination, &false);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(client.get_quarantined().len(), 1);
+
+        // The quarantined balance leaves out of band, and the last sweep
+        // also left dust and a depeg override behind
+        TokenClient::new(&env, &regulated).transfer(&contract_id, &recovery, &100);
+        let dust = Address::generate(&env);
+        env.as_contract(&contract_id, || {
+            let state = AccountState::new(&env);
+            state.set_dust(&vec![
+                &env,
+                Payment {
+                    asset: dust.clone(),
+                    amount: 5,
+                    timestamp: 0,
+                    payer_info_hash: None,
+                    sender: None,
+                    decimals: None,
+                    memo: None,
+                },
+            ]);
+            state.set_depeg_override(env.ledger().sequence() + 1000);
+        });
+
+        client.reset(&(env.ledger().sequence() + 500), &recovery, &factory);
+        assert_eq!(client.get_quarantined().len(), 0);
+        assert_eq!(client.get_quarantine_retry(&regulated), None);
+        assert_eq!(client.get_dust().len(), 0);
+        env.as_contract(&contract_id, || {
+            assert_eq!(AccountState::new(&env).get_depeg_override(), None);
+        });
+
+        // The next cycle's balance of the same asset is not paid out to
+        // the old destination
+        StellarAssetClient::new(&env, &regulated).set_authorized(&destination, &true);
+        StellarAssetClient::new(&env, &regulated).mint(&contract_id, &100);
+        client.record_payment(&100, &regulated);
+        assert_eq!(
+            client.try_retry_quarantined(&regulated),
+            Err(Ok(Error::AssetNotFound))
+        );
+        assert_eq!(client.try_sweep_dust(), Err(Ok(Error::AssetNotFound)));
+        assert_eq!(
+            TokenClient::new(&env, &regulated).balance(&contract_id),
+            100
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "reuse")]
+    fn test_reset_requires_factory_and_empty_settled_account() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let factory = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 150);
+        let expiry_ledger = env.ledger().sequence() + 100;
+        client.initialize_with_deployment(
+            &Address::generate(&env),
+            &expiry_ledger,
+            &recovery,
+            &DeploymentSettings {
+                factory: Some(factory.clone()),
+                ..Default::default()
+            },
+        );
+        client.record_payment(&100, &asset);
+        let next_expiry = expiry_ledger + 1000;
+
+        assert_eq!(
+            client.try_reset(&next_expiry, &recovery, &Address::generate(&env)),
+            Err(Ok(Error::Unauthorized))
+        );
+        assert_eq!(
+            client.try_reset(&next_expiry, &recovery, &factory),
+            Err(Ok(Error::InvalidStatus))
+        );
+
+        // Expired, but more of a paid asset arrived than was recorded
+        env.ledger()
+            .with_mut(|ledger| ledger.sequence_number = expiry_ledger);
+        client.expire();
+        assert_eq!(TokenClient::new(&env, &asset).balance(&recovery), 100);
+        assert_eq!(
+            client.try_reset(&next_expiry, &recovery, &factory),
+            Err(Ok(Error::FundsRemaining))
+        );
+
+        TokenClient::new(&env, &asset).transfer(&contract_id, &recovery, &50);
+        assert_eq!(
+            client.try_reset(&expiry_ledger, &recovery, &factory),
+            Err(Ok(Error::InvalidExpiry))
+        );
+        client.reset(&next_expiry, &recovery, &factory);
+        assert_eq!(client.get_status(), AccountStatus::Active);
+    }
+
+    #[test]
+    fn test_expire_event_reports_timeout_reason() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let recovery = Address::generate(&env);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 10),
+            &recovery,
+        );
+
+        env.ledger().with_mut(|li| li.sequence_number += 11);
+        client.expire();
+
+        let expired: AccountExpired = event_data(&env, symbol_short!("expired"));
+        assert_eq!(expired.reason, CloseReason::Timeout);
+        assert_eq!(expired.recovery_address, recovery);
+        assert_eq!(expired.reference, None);
+        assert_invariants(&client);
+    }
+
+    #[test]
+    #[cfg(feature = "config")]
+    fn test_metadata_reference_is_carried_in_lifecycle_events() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        assert_eq!(client.get_metadata(), None);
+
+        let metadata = AccountMetadata {
+            merchant_id: BytesN::from_array(&env, &[1u8; 32]),
+            reference: BytesN::from_array(&env, &[2u8; 32]),
+            tag: symbol_short!("checkout"),
+        };
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(&env)),
+                metadata: AccountLabel::Labeled(metadata.clone()),
+                ..Default::default()
+            },
+        );
+        let created: AccountCreated = event_data(&env, symbol_short!("created"));
+        assert_eq!(created.reference, Some(metadata.reference.clone()));
+        assert_eq!(client.get_metadata(), Some(metadata.clone()));
+
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        let swept: SweepExecutedMulti = event_data(&env, symbol_short!("swept_mul"));
+        assert_eq!(swept.reference, Some(metadata.reference));
+    }
+
+    #[test]
+    fn test_expired_account_rejects_payments() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 10),
+            &Address::generate(&env),
+        );
+
+        env.ledger().with_mut(|li| li.sequence_number += 11);
+        client.expire();
+
+        assert_eq!(
+            client.try_record_payment(&1, &Address::generate(&env)),
+            Err(Ok(Error::InvalidStatus))
+        );
+        assert_eq!(client.get_status(), AccountStatus::Expired);
+        assert_invariants(&client);
+    }
+
+    #[test]
+    #[cfg(feature = "lifecycle")]
+    fn test_extend_expiry_postpones_expiration() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let old_expiry = env.ledger().sequence() + 10;
+        client.initialize(
+            &Address::generate(&env),
+            &old_expiry,
+            &Address::generate(&env),
+        );
+
+        assert_eq!(
+            client.try_extend_expiry(&old_expiry),
+            Err(Ok(Error::InvalidExpiry))
+        );
+
+        let new_expiry = old_expiry + 100;
+        client.extend_expiry(&new_expiry);
+        let extended: ExpiryExtended = event_data(&env, symbol_short!("extended"));
+        assert_eq!(extended.old_expiry_ledger, old_expiry);
+        assert_eq!(extended.new_expiry_ledger, new_expiry);
+
+        env.ledger()
+            .with_mut(|li| li.sequence_number = old_expiry + 1);
+        assert!(!client.is_expired());
+        assert_eq!(client.try_expire(), Err(Ok(Error::NotExpired)));
+        assert_eq!(client.get_info().expiry_ledger, new_expiry);
+    }
+
+    #[test]
+    #[cfg(feature = "lifecycle")]
+    fn test_extend_expiry_rejected_once_closed() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let expiry = env.ledger().sequence() + 10;
+        client.initialize(&Address::generate(&env), &expiry, &Address::generate(&env));
+
+        // Past the expiry ledger the account can only be expired.
+        env.ledger().with_mut(|li| li.sequence_number = expiry);
+        assert_eq!(
+            client.try_extend_expiry(&(expiry + 100)),
+            Err(Ok(Error::AccountExpired))
+        );
+
+        client.expire();
+        assert_eq!(
+            client.try_extend_expiry(&(expiry + 100)),
+            Err(Ok(Error::InvalidStatus))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "lifecycle")]
+    fn test_cancel_closes_account_with_reason() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let creator = Address::generate(&env);
+        client.initialize(
+            &creator,
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+
+        // Timeout is reserved for expire().
+        assert_eq!(
+            client.try_cancel(&CloseReason::Timeout),
+            Err(Ok(Error::InvalidReason))
+        );
+
+        client.cancel(&CloseReason::ComplianceBlock);
+
+        let cancelled: AccountCancelled = event_data(&env, symbol_short!("cancelled"));
+        assert_eq!(cancelled.reason, CloseReason::ComplianceBlock);
+        assert_eq!(cancelled.destination, creator);
+        assert_eq!(cancelled.reserve_amount, BASE_RESERVE_STROOPS);
+
+        assert_eq!(client.get_status(), AccountStatus::Cancelled);
+        assert_invariants(&client);
+
+        // A cancelled account can no longer be swept, even before expiry.
+        let destination = Address::generate(&env);
+        let signature = sign_sweep(&env, &client, &destination);
+        assert_eq!(
+            client.try_sweep(&destination, &signature),
+            Err(Ok(Error::InvalidStatus))
+        );
+    }
+
+    /// Once paid, funds settle through sweep or expire; cancel would
+    /// close the account without moving them.
+    #[test]
+    #[cfg(feature = "lifecycle")]
+    fn test_cancel_refused_once_paid() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+
+        assert_eq!(
+            client.try_cancel(&CloseReason::ComplianceBlock),
+            Err(Ok(Error::InvalidStatus))
+        );
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+    }
+
+    #[test]
+    #[cfg(feature = "lifecycle")]
+    fn test_cancel_before_payment_returns_reserve_to_creator() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let creator = Address::generate(&env);
+        client.initialize(
+            &creator,
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+
+        client.cancel(&CloseReason::CreatorCancelled);
+
+        let cancelled: AccountCancelled = event_data(&env, symbol_short!("cancelled"));
+        assert_eq!(cancelled.destination, creator);
+        assert_eq!(cancelled.amount_returned, 0);
+        assert_eq!(cancelled.reserve_amount, BASE_RESERVE_STROOPS);
+        assert_eq!(latest_reserve_event(&client).destination, creator);
+
+        assert_eq!(client.get_status(), AccountStatus::Cancelled);
+        assert!(client.is_reserve_reclaimed());
+        assert_invariants(&client);
+        assert_eq!(
+            client.try_record_payment(&100, &Address::generate(&env)),
+            Err(Ok(Error::InvalidStatus))
+        );
+        assert_eq!(
+            client.try_cancel(&CloseReason::CreatorCancelled),
+            Err(Ok(Error::InvalidStatus))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "views")]
+    fn test_payments_root_chains_recorded_payments() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        client.initialize(&creator, &expiry_ledger, &recovery);
+        let mut expected = BytesN::from_array(&env, &[0u8; 32]);
+        assert_eq!(client.get_payments_root(), expected);
+
+        for amount in [100i128, 250] {
+            let asset = Address::generate(&env);
+            client.record_payment(&amount, &asset);
+
+            // Replay the commitment the way an off-chain auditor would.
+            let payment = client
+                .get_info()
+                .payments
+                .iter()
+                .find(|p| p.asset == asset)
+                .unwrap();
+            let mut preimage = Bytes::from_array(&env, &expected.to_array());
+            preimage.append(&payment.to_xdr(&env));
+            expected = env.crypto().sha256(&preimage).to_bytes();
+            assert_eq!(client.get_payments_root(), expected);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "audit", feature = "views"))]
+    fn test_state_attestation_commits_to_state() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        assert_eq!(
+            client.try_get_state_attestation(),
+            Err(Ok(Error::NotInitialized))
+        );
+
+        let expiry_ledger = env.ledger().sequence() + 1000;
+        client.initialize(
+            &Address::generate(&env),
+            &expiry_ledger,
+            &Address::generate(&env),
+        );
+        client.record_payment(&100, &Address::generate(&env));
+
+        let attestation = client.get_state_attestation();
+        assert_eq!(attestation.account, contract_id);
+        assert_eq!(attestation.ledger, env.ledger().sequence());
+        assert_eq!(attestation.status, AccountStatus::PaymentReceived);
+        assert_eq!(attestation.expiry_ledger, expiry_ledger);
+        assert_eq!(attestation.payment_count, 1);
+        assert_eq!(attestation.payments_root, client.get_payments_root());
+        assert_eq!(attestation.swept_to, None);
+        assert_eq!(attestation.sweep_nonce, 0);
+
+        // Recompute the hash independently of the contract.
+        let preimage = (
+            symbol_short!("attest"),
+            contract_id.clone(),
+            attestation.ledger,
+            attestation.timestamp,
+            attestation.status,
+            attestation.expiry_ledger,
+            attestation.payment_count,
+            attestation.payments_root.clone(),
+            attestation.swept_to.clone(),
+            attestation.sweep_nonce,
+        )
+            .to_xdr(&env);
+        assert_eq!(attestation.hash, env.crypto().sha256(&preimage).to_bytes());
+
+        // The same state at a later ledger yields a different receipt.
+        env.ledger().with_mut(|li| li.sequence_number += 1);
+        assert_ne!(client.get_state_attestation().hash, attestation.hash);
+    }
+
+    /// A sweep whose recorded payment is not backed by a balance must
+    /// fail as a whole and leave the account untouched.
+    #[test]
+    fn test_sweep_is_atomic_when_a_transfer_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let destination = Address::generate(&env);
+        let funded = fund_asset(&env, &contract_id, 100);
+        let unfunded = fund_asset(&env, &contract_id, 10);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        client.initialize(&creator, &expiry_ledger, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        client.record_payment(&100, &funded);
+        client.record_payment(&50, &unfunded);
+
+        let result = client.try_sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert!(result.is_err());
+
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+        assert_eq!(TokenClient::new(&env, &funded).balance(&contract_id), 100);
+        assert_eq!(TokenClient::new(&env, &funded).balance(&destination), 0);
+    }
+
+    #[test]
+    fn test_sweep_without_signer_is_unauthorized() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let destination = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        client.initialize(&creator, &expiry_ledger, &recovery);
+        client.record_payment(&100, &asset);
+
+        let result = client.try_sweep(
+            &destination,
+            &vec![&env, BytesN::from_array(&env, &[0u8; 64])],
+        );
+        assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    }
+
+    #[test]
+    fn test_sweep_rejects_signature_for_other_destination() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let destination = Address::generate(&env);
+        let attacker = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        client.initialize(&creator, &expiry_ledger, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        client.record_payment(&100, &asset);
+        let signature = sign_sweep(&env, &client, &destination);
+        assert!(matches!(
+            client.try_sweep(&attacker, &signature),
+            Err(Err(_))
+        ));
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+
+        client.sweep(&destination, &signature);
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+        assert_eq!(client.get_sweep_nonce(), 1);
+    }
+
+    #[test]
+    fn test_sweep_rejects_malformed_signature() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let destination = Address::generate(&env);
+        let asset = fund_asset(&env, &contract_id, 100);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        client.initialize(&creator, &expiry_ledger, &recovery);
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        client.record_payment(&100, &asset);
+
+        // An `s` at or above the group order is rejected before the host
+        let mut bytes = sign_sweep(&env, &client, &destination)
+            .get(0)
+            .unwrap()
+            .to_array();
+        bytes[63] |= 0xf0;
+        let result = client.try_sweep(&destination, &vec![&env, BytesN::from_array(&env, &bytes)]);
+        assert_eq!(result, Err(Ok(Error::Unauthorized)));
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+        assert_eq!(client.get_sweep_nonce(), 0);
+    }
+
+    /// Authorization entry for `sweep_authorized(destination)` signed by
+    /// the test sweep signer, built the way a wallet would submit it.
+    #[cfg(feature = "auth")]
+    fn sweep_auth_entry(
+        env: &Env,
+        account: &Address,
+        destination: &Address,
+    ) -> SorobanAuthorizationEntry {
+        let nonce = 1;
+        let signature_expiration_ledger = env.ledger().sequence() + 100;
+        let invocation = SorobanAuthorizedInvocation {
+            function: SorobanAuthorizedFunction::ContractFn(InvokeContractArgs {
+                contract_address: ScAddress::from(account),
+                function_name: ScSymbol("sweep_authorized".try_into().unwrap()),
+                args: std::vec![ScVal::from(destination)].try_into().unwrap(),
+            }),
+            sub_invocations: VecM::default(),
+        };
+        let preimage = HashIdPreimage::SorobanAuthorization(HashIdPreimageSorobanAuthorization {
+            network_id: env.ledger().network_id().to_array().into(),
+            nonce,
+            signature_expiration_ledger,
+            invocation: invocation.clone(),
+        });
+        let payload = env.crypto().sha256(&Bytes::from_slice(
+            env,
+            &preimage.to_xdr(Limits::none()).unwrap(),
+        ));
+        let signatures = vec![
+            env,
+            BytesN::from_array(
+                env,
+                &sweep_signing_key().sign(&payload.to_array()).to_bytes(),
+            ),
+        ];
+        SorobanAuthorizationEntry {
+            credentials: SorobanCredentials::Address(SorobanAddressCredentials {
+                address: ScAddress::from(account),
+                nonce,
+                signature_expiration_ledger,
+                signature: ScVal::try_from_val(env, &signatures.to_val()).unwrap(),
+            }),
+            root_invocation: invocation,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "auth")]
+    fn test_sweep_authorized_through_check_auth() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+
+        let destination = Address::generate(&env);
+        client
+            .set_auths(&[sweep_auth_entry(&env, &contract_id, &destination)])
+            .sweep_authorized(&destination);
+
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+        assert_eq!(client.get_sweep_nonce(), 1);
+    }
+
+    /// A signature for one destination does not authorize another.
+    #[test]
+    #[should_panic]
+    #[cfg(feature = "auth")]
+    fn test_sweep_authorized_binds_destination() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        client.record_payment(&100, &fund_asset(&env, &contract_id, 100));
+
+        let signed_for = Address::generate(&env);
+        client
+            .set_auths(&[sweep_auth_entry(&env, &contract_id, &signed_for)])
+            .sweep_authorized(&Address::generate(&env));
+    }
+
+    #[test]
+    #[cfg(feature = "auth")]
+    fn test_check_auth_refuses_other_calls() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        let asset = fund_asset(&env, &contract_id, 100);
+
+        let payload = BytesN::from_array(&env, &[3u8; 32]);
+        let signatures = vec![
+            &env,
+            BytesN::from_array(
+                &env,
+                &sweep_signing_key().sign(&payload.to_array()).to_bytes(),
+            ),
+        ];
+        let transfer = Context::Contract(ContractContext {
+            contract: asset,
+            fn_name: symbol_short!("transfer"),
+            args: vec![&env],
+        });
+        assert_eq!(
+            env.try_invoke_contract_check_auth::<Error>(
+                &contract_id,
+                &payload,
+                signatures.to_val(),
+                &vec![&env, transfer],
+            ),
+            Err(Ok(Error::Unauthorized))
+        );
+
+        // The same signature authorizes the account's own sweep.
+        let sweep = Context::Contract(ContractContext {
+            contract: contract_id.clone(),
+            fn_name: Symbol::new(&env, "sweep_authorized"),
+            args: vec![&env],
+        });
+        assert_eq!(
+            env.try_invoke_contract_check_auth::<Error>(
+                &contract_id,
+                &payload,
+                signatures.to_val(),
+                &vec![&env, sweep],
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "config", feature = "partial"))]
+    fn test_locked_destination_rejects_other_destinations() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let merchant = Address::generate(&env);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(&env)),
+                locked_destination: Some(merchant.clone()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(client.get_locked_destination(), Some(merchant.clone()));
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+
+        // A valid signature for another destination is still refused
+        let other = Address::generate(&env);
+        assert_eq!(
+            client.try_sweep(&other, &sign_sweep(&env, &client, &other)),
+            Err(Ok(Error::DestinationMismatch))
+        );
+        let assets = vec![&env, asset.clone()];
+        assert_eq!(
+            client.try_sweep_assets(
+                &other,
+                &assets,
+                &sign_sweep_assets(&env, &client, &other, &assets)
+            ),
+            Err(Ok(Error::DestinationMismatch))
+        );
+        let destinations = vec![&env, (merchant.clone(), 5_000), (other, 5_000)];
+        assert_eq!(
+            client.try_sweep_split(
+                &destinations,
+                &sign_sweep_split(&env, &client, &destinations)
+            ),
+            Err(Ok(Error::DestinationMismatch))
+        );
+
+        client.sweep(&merchant, &sign_sweep(&env, &client, &merchant));
+        assert_eq!(TokenClient::new(&env, &asset).balance(&merchant), 100);
+    }
+
+    #[test]
+    #[cfg(feature = "guardian")]
+    fn test_destination_change_requires_guardian_and_timelock() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let creator = Address::generate(&env);
+        let guardian = Address::generate(&env);
+        let merchant = Address::generate(&env);
+        client.initialize_with_config(
+            &creator,
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(&env)),
+                locked_destination: Some(merchant.clone()),
+                guardian: Some(guardian.clone()),
+                destination_change_delay: 10,
+                ..Default::default()
+            },
+        );
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+
+        let new_merchant = Address::generate(&env);
+        client.propose_destination_change(&new_merchant);
+        let auths: std::vec::Vec<Address> = env.auths().into_iter().map(|(addr, _)| addr).collect();
+        assert!(auths.contains(&creator));
+        assert!(auths.contains(&guardian));
+        let effective_ledger = env.ledger().sequence() + 10;
+        assert_eq!(
+            client.get_pending_destination(),
+            Some(PendingDestination {
+                destination: new_merchant.clone(),
+                effective_ledger,
+            })
+        );
+
+        assert_eq!(
+            client.try_apply_destination_change(),
+            Err(Ok(Error::TimelockActive))
+        );
+        assert_eq!(client.get_locked_destination(), Some(merchant.clone()));
+
+        env.ledger().with_mut(|li| li.sequence_number += 10);
+        client.apply_destination_change();
+        let event = env.events().all().last().unwrap();
+        assert_eq!(
+            DestinationChanged::try_from_val(&env, &event.2).unwrap(),
+            DestinationChanged {
+                old_destination: merchant.clone(),
+                new_destination: new_merchant.clone(),
+            }
+        );
+        assert_eq!(client.get_locked_destination(), Some(new_merchant.clone()));
+        assert_eq!(client.get_pending_destination(), None);
+        assert_eq!(
+            client.try_apply_destination_change(),
+            Err(Ok(Error::InvalidStatus))
+        );
+
+        assert_eq!(
+            client.try_sweep(&merchant, &sign_sweep(&env, &client, &merchant)),
+            Err(Ok(Error::DestinationMismatch))
+        );
+        client.sweep(&new_merchant, &sign_sweep(&env, &client, &new_merchant));
+        assert_eq!(TokenClient::new(&env, &asset).balance(&new_merchant), 100);
+    }
+
+    #[test]
+    #[cfg(feature = "guardian")]
+    fn test_destination_change_needs_guardian() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                locked_destination: Some(Address::generate(&env)),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            client.try_propose_destination_change(&Address::generate(&env)),
+            Err(Ok(Error::Unauthorized))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "guardian", feature = "views"))]
+    fn test_guardian_emergency_recover_before_expiry() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let guardian = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+        let config = AccountConfig {
+            guardian: Some(guardian.clone()),
+            emergency_delay_ledgers: 1000,
+            ..Default::default()
+        };
+        // Recovery has to open before expiry
+        assert_eq!(
+            client.try_initialize_with_config(
+                &Address::generate(&env),
+                &expiry_ledger,
+                &recovery,
+                &config
+            ),
+            Err(Ok(Error::InvalidExpiry))
+        );
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &expiry_ledger,
+            &recovery,
+            &AccountConfig {
+                emergency_delay_ledgers: 100,
+                ..config
+            },
+        );
+        assert!(client.get_capabilities().emergency_recovery);
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+
+        assert_eq!(
+            client.try_emergency_recover(),
+            Err(Ok(Error::TimelockActive))
+        );
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+        client.emergency_recover();
+        let auths: std::vec::Vec<Address> = env.auths().into_iter().map(|(addr, _)| addr).collect();
+        assert_eq!(auths, std::vec![guardian.clone()]);
+        let event: EmergencyRecovered = event_data(&env, symbol_short!("emergency"));
+        assert_eq!(event.guardian, guardian);
+        assert_eq!(event.recovery_address, recovery);
+        assert_eq!(event.payments.len(), 1);
+        assert_eq!(event.reserve_amount, BASE_RESERVE_STROOPS);
+
+        assert_eq!(client.get_status(), AccountStatus::EmergencyRecovered);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&recovery), 100);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&contract_id), 0);
+        assert!(client.is_reserve_reclaimed());
+        assert_eq!(
+            client.try_emergency_recover(),
+            Err(Ok(Error::InvalidStatus))
+        );
+        assert_eq!(client.try_expire(), Err(Ok(Error::InvalidStatus)));
+    }
+
+    #[test]
+    #[cfg(all(feature = "guardian", feature = "views"))]
+    fn test_emergency_recover_needs_emergency_delay() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                guardian: Some(Address::generate(&env)),
+                ..Default::default()
+            },
+        );
+
+        assert!(!client.get_capabilities().emergency_recovery);
+        assert_eq!(client.try_emergency_recover(), Err(Ok(Error::Unauthorized)));
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "fees",
+        feature = "limits",
+        feature = "multisig",
+        feature = "views"
+    ))]
+    fn test_get_capabilities_reflects_config() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        assert_eq!(
+            client.try_get_capabilities(),
+            Err(Ok(Error::NotInitialized))
+        );
+
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                fee_collector: Some(Address::generate(&env)),
+                fee_bps: 100,
+                locked_destination: Some(Address::generate(&env)),
+                accumulate_payments: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            client.get_capabilities(),
+            Capabilities {
+                wasm_version: crate::WASM_VERSION,
+                fees: true,
+                locked_destination: true,
+                accumulate_payments: true,
+                ..Default::default()
+            }
+        );
+
+        let keys = multisig_keys();
+        let mut signers = Vec::new(&env);
+        for key in keys.iter() {
+            signers.push_back(BytesN::from_array(&env, &key.verifying_key().to_bytes()));
+        }
+        client.set_sweep_signers(&signers, &2);
+        client.configure_limits(
+            &3,
+            &Map::new(&env),
+            &Some(vec![&env, Address::generate(&env)]),
+            &0,
+        );
+        let capabilities = client.get_capabilities();
+        assert!(capabilities.multisig);
+        assert!(capabilities.allowlist);
+        assert!(!capabilities.passkey);
+    }
+
+    #[test]
+    #[cfg(feature = "config")]
+    fn test_batch_window_waits_for_threshold() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(&env)),
+                accumulate_payments: true,
+                batch_min_amount: 100,
+                batch_max_wait_ledgers: 500,
+                ..Default::default()
+            },
+        );
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&60, &asset);
+
+        let destination = Address::generate(&env);
+        assert_eq!(
+            client.try_sweep(&destination, &sign_sweep(&env, &client, &destination)),
+            Err(Ok(Error::BatchWindowOpen))
+        );
+
+        client.record_payment(&40, &asset);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+    }
+
+    #[test]
+    #[cfg(feature = "config")]
+    fn test_batch_window_opens_after_max_wait() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(&env)),
+                batch_min_amount: 1_000,
+                batch_max_wait_ledgers: 500,
+                ..Default::default()
+            },
+        );
+        client.record_payment(&10, &fund_asset(&env, &contract_id, 10));
+
+        let destination = Address::generate(&env);
+        env.ledger().with_mut(|li| li.sequence_number += 499);
+        assert_eq!(
+            client.try_sweep(&destination, &sign_sweep(&env, &client, &destination)),
+            Err(Ok(Error::BatchWindowOpen))
+        );
+
+        env.ledger().with_mut(|li| li.sequence_number += 1);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+    }
+
+    /// SEP-40 oracle quoting prices set by the test, with 14 decimals.
+    #[contract]
+    struct MockOracle;
+
+    #[contractimpl]
+    impl MockOracle {
+        pub fn set_price(env: Env, asset: Address, price: i128) {
+            env.storage().instance().set(&asset, &price);
+        }
+
+        pub fn decimals(_env: Env) -> u32 {
+            14
+        }
+
+        pub fn lastprice(env: Env, asset: OracleAsset) -> Option<PriceData> {
+            let OracleAsset::Stellar(asset) = asset else {
+                return None;
+            };
+            let price = env.storage().instance().get(&asset)?;
+            Some(PriceData {
+                price,
+                timestamp: env.ledger().timestamp(),
+            })
+        }
+    }
+
+    /// Account refusing sweeps worth less than one unit of the oracle's
+    /// quote asset.
+    #[cfg(feature = "oracle")]
+    fn setup_min_sweep_value(
+        env: &Env,
+    ) -> (EphemeralAccountContractClient<'_>, MockOracleClient<'_>) {
+        let oracle = MockOracleClient::new(env, &env.register(MockOracle, ()));
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(env)),
+                accumulate_payments: true,
+                price_oracle: Some(oracle.address.clone()),
+                min_sweep_value: 100_000_000_000_000,
+                ..Default::default()
+            },
+        );
+        (client, oracle)
+    }
+
+    #[test]
+    #[cfg(all(feature = "audit", feature = "oracle", feature = "views"))]
+    fn test_min_sweep_value_refuses_dust_unless_forced() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, oracle) = setup_min_sweep_value(&env);
+
+        // Half a token at one unit each
+        let asset = fund_asset(&env, &client.address, 5_000_000);
+        oracle.set_price(&asset, &100_000_000_000_000);
+        client.record_payment(&5_000_000, &asset);
+        assert_eq!(client.get_sweep_value(), Some(50_000_000_000_000));
+        assert!(!client.is_sweep_worthwhile());
+        assert_eq!(client.try_can_sweep(), Err(Ok(Error::BelowMinSweepValue)));
+
+        let destination = Address::generate(&env);
+        assert_eq!(
+            client.try_sweep(&destination, &sign_sweep(&env, &client, &destination)),
+            Err(Ok(Error::BelowMinSweepValue))
+        );
+        assert_eq!(
+            client.preview_sweep(&destination).error_code,
+            Some(Error::BelowMinSweepValue as u32)
+        );
+
+        client.sweep_forced(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+        assert_eq!(
+            TokenClient::new(&env, &asset).balance(&destination),
+            5_000_000
+        );
+        assert_invariants(&client);
+    }
+
+    #[test]
+    #[cfg(feature = "oracle")]
+    fn test_min_sweep_value_sums_quoted_assets() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, oracle) = setup_min_sweep_value(&env);
+
+        // An asset the oracle does not quote is worth nothing
+        let unquoted = fund_asset(&env, &client.address, 1_000_000_000);
+        client.record_payment(&1_000_000_000, &unquoted);
+        assert_eq!(client.get_sweep_value(), Some(0));
+
+        let asset = fund_asset(&env, &client.address, 10_000_000);
+        oracle.set_price(&asset, &60_000_000_000_000);
+        client.record_payment(&5_000_000, &asset);
+        assert!(!client.is_sweep_worthwhile());
+
+        client.record_payment(&5_000_000, &asset);
+        assert_eq!(client.get_sweep_value(), Some(60_000_000_000_000));
+        assert!(!client.is_sweep_worthwhile());
+
+        oracle.set_price(&asset, &100_000_000_000_000);
+        assert!(client.is_sweep_worthwhile());
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+    }
+
+    #[test]
+    #[cfg(all(feature = "oracle", feature = "partial"))]
+    fn test_min_sweep_value_applies_to_sweep_assets() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, oracle) = setup_min_sweep_value(&env);
+
+        let asset = fund_asset(&env, &client.address, 5_000_000);
+        oracle.set_price(&asset, &100_000_000_000_000);
+        client.record_payment(&5_000_000, &asset);
+
+        // Only the assets being swept count, not the rest of the account
+        let valuable = fund_asset(&env, &client.address, 20_000_000);
+        oracle.set_price(&valuable, &100_000_000_000_000);
+        client.record_payment(&20_000_000, &valuable);
+        assert!(client.is_sweep_worthwhile());
+
+        let destination = Address::generate(&env);
+        let assets = vec![&env, asset.clone()];
+        assert_eq!(
+            client.try_sweep_assets(
+                &destination,
+                &assets,
+                &sign_sweep_assets(&env, &client, &destination, &assets),
+            ),
+            Err(Ok(Error::BelowMinSweepValue))
+        );
+        assert_eq!(client.get_sweep_nonce(), 0);
+
+        oracle.set_price(&asset, &200_000_000_000_000);
+        client.sweep_assets(
+            &destination,
+            &assets,
+            &sign_sweep_assets(&env, &client, &destination, &assets),
+        );
+        assert_eq!(
+            TokenClient::new(&env, &asset).balance(&destination),
+            5_000_000
+        );
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+    }
+
+    #[test]
+    #[cfg(all(feature = "oracle", feature = "partial"))]
+    fn test_min_sweep_value_applies_to_sweep_split() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, oracle) = setup_min_sweep_value(&env);
+
+        let asset = fund_asset(&env, &client.address, 5_000_000);
+        oracle.set_price(&asset, &100_000_000_000_000);
+        client.record_payment(&5_000_000, &asset);
+
+        let destinations = vec![
+            &env,
+            (Address::generate(&env), 5_000),
+            (Address::generate(&env), 5_000),
+        ];
+        assert_eq!(
+            client.try_sweep_split(
+                &destinations,
+                &sign_sweep_split(&env, &client, &destinations),
+            ),
+            Err(Ok(Error::BelowMinSweepValue))
+        );
+        assert_eq!(client.get_sweep_nonce(), 0);
+
+        oracle.set_price(&asset, &200_000_000_000_000);
+        client.sweep_split(
+            &destinations,
+            &sign_sweep_split(&env, &client, &destinations),
+        );
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+    }
+
+    #[test]
+    #[cfg(feature = "dust")]
+    fn test_dust_threshold_leaves_dust_for_sweep_dust() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let oracle = MockOracleClient::new(&env, &env.register(MockOracle, ()));
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(&env)),
+                price_oracle: Some(oracle.address.clone()),
+                dust_threshold: 100_000_000_000_000,
+                ..Default::default()
+            },
+        );
+        // Worth a unit each, half a unit, and unquoted
+        let valuable = fund_asset(&env, &contract_id, 10_000_000);
+        let dust = fund_asset(&env, &contract_id, 5_000_000);
+        let unquoted = fund_asset(&env, &contract_id, 1);
+        oracle.set_price(&valuable, &100_000_000_000_000);
+        oracle.set_price(&dust, &100_000_000_000_000);
+        client.record_payment(&10_000_000, &valuable);
+        client.record_payment(&5_000_000, &dust);
+        client.record_payment(&1, &unquoted);
+        assert_eq!(client.try_sweep_dust(), Err(Ok(Error::AssetNotFound)));
+
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        let left: DustLeft = event_data(&env, symbol_short!("dust_left"));
+        assert_eq!(
+            left,
+            DustLeft {
+                destination: destination.clone(),
+                assets: vec![&env, dust.clone()],
+            }
+        );
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+        assert_eq!(
+            TokenClient::new(&env, &valuable).balance(&destination),
+            10_000_000
+        );
+        assert_eq!(TokenClient::new(&env, &unquoted).balance(&destination), 1);
+        assert_eq!(TokenClient::new(&env, &dust).balance(&destination), 0);
+        assert_eq!(client.get_dust().len(), 1);
+
+        // Anyone sends it on, to the sweep's destination
+        client.sweep_dust();
+        let swept: DustSwept = event_data(&env, symbol_short!("dust_sent"));
+        assert_eq!(swept.destination, destination);
+        assert_eq!(swept.amounts.len(), 1);
+        assert_eq!(
+            TokenClient::new(&env, &dust).balance(&destination),
+            5_000_000
+        );
+        assert_eq!(client.get_dust().len(), 0);
+        assert_eq!(client.try_sweep_dust(), Err(Ok(Error::AssetNotFound)));
+    }
+
+    #[test]
+    #[cfg(feature = "depeg")]
+    fn test_depeg_guard_blocks_sweep_until_guardian_overrides() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let oracle = MockOracleClient::new(&env, &env.register(MockOracle, ()));
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let stablecoin = fund_asset(&env, &contract_id, 1_000);
+        let other = fund_asset(&env, &contract_id, 1_000);
+        // Pegged at one unit of the quote asset, within 2%
+        let peg = 100_000_000_000_000;
+        let mut pegs = Map::new(&env);
+        pegs.set(stablecoin.clone(), peg);
+        let guardian = Address::generate(&env);
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(&env)),
+                price_oracle: Some(oracle.address.clone()),
+                depeg_pegs: Some(pegs),
+                depeg_threshold_bps: 200,
+                guardian: Some(guardian.clone()),
+                ..Default::default()
+            },
+        );
+        client.record_payment(&1_000, &stablecoin);
+        client.record_payment(&1_000, &other);
+
+        // Unquoted counts as off peg; an unguarded asset is never checked
+        let destination = Address::generate(&env);
+        assert_eq!(client.get_depegged_assets(), vec![&env, stablecoin.clone()]);
+        oracle.set_price(&stablecoin, &(peg - peg / 50));
+        assert_eq!(client.get_depegged_assets().len(), 0);
+
+        oracle.set_price(&stablecoin, &(peg - peg / 20));
+        assert_eq!(
+            client.try_sweep(&destination, &sign_sweep(&env, &client, &destination)),
+            Err(Ok(Error::Depegged))
+        );
+        assert_eq!(client.get_sweep_nonce(), 0);
+
+        // The guardian's override covers sweeps through its last ledger
+        let until_ledger = env.ledger().sequence() + 10;
+        client.override_depeg(&until_ledger);
+        assert_eq!(
+            env.auths()[0].0,
+            guardian,
+            "override must be authorized by the guardian"
+        );
+        let overridden: DepegOverridden = event_data(&env, symbol_short!("depeg_ovr"));
+        assert_eq!(
+            overridden,
+            DepegOverridden {
+                guardian,
+                until_ledger,
+            }
+        );
+        env.ledger()
+            .with_mut(|li| li.sequence_number = until_ledger + 1);
+        assert_eq!(
+            client.try_sweep(&destination, &sign_sweep(&env, &client, &destination)),
+            Err(Ok(Error::Depegged))
+        );
+        client.override_depeg(&(until_ledger + 10));
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+        assert_eq!(
+            TokenClient::new(&env, &stablecoin).balance(&destination),
+            1_000
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "depeg")]
+    fn test_depeg_guard_validates_pegs() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+        let mut pegs = Map::new(&env);
+        pegs.set(Address::generate(&env), 0);
+        for (pegs, threshold_bps) in [(pegs, 100), (Map::new(&env), 10_001)] {
+            assert_eq!(
+                client.try_initialize_with_config(
+                    &creator,
+                    &expiry_ledger,
+                    &recovery,
+                    &AccountConfig {
+                        price_oracle: Some(Address::generate(&env)),
+                        depeg_pegs: Some(pegs),
+                        depeg_threshold_bps: threshold_bps,
+                        ..Default::default()
+                    },
+                ),
+                Err(Ok(Error::InvalidAmount))
+            );
+        }
+    }
+
+    #[cfg(feature = "fees")]
+    fn setup_with_fee(
+        env: &Env,
+        fee_bps: u32,
+    ) -> (Address, Address, EphemeralAccountContractClient<'_>) {
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(env, &contract_id);
+        let collector = Address::generate(env);
+        client.initialize_with_config(
+            &Address::generate(env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(env)),
+                fee_collector: Some(collector.clone()),
+                fee_bps,
+                ..Default::default()
+            },
+        );
+        (contract_id, collector, client)
+    }
+
+    #[test]
+    #[cfg(feature = "fees")]
+    fn test_sweep_deducts_protocol_fee() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, collector, client) = setup_with_fee(&env, 250);
+        assert_eq!(client.get_fee(), Some((collector.clone(), 250)));
+        let asset = fund_asset(&env, &contract_id, 1_000);
+        client.record_payment(&1_000, &asset);
+
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+
+        let swept: SweepExecutedMulti = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| event_name(&env, topics) == Some(symbol_short!("swept_mul")))
+            .map(|(_, _, data)| data.into_val(&env))
+            .expect("sweep event was not emitted");
+        assert_eq!(
+            swept.amounts,
+            vec![
+                &env,
+                SweepAmount {
+                    asset: asset.clone(),
+                    gross: 1_000,
+                    fee: 25,
+                    net: 975,
+                }
+            ]
+        );
+
+        let token = TokenClient::new(&env, &asset);
+        assert_eq!(token.balance(&collector), 25);
+        assert_eq!(token.balance(&destination), 975);
+    }
+
+    #[test]
+    #[cfg(all(feature = "audit", feature = "fees"))]
+    fn test_preview_sweep_matches_sweep() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, _, client) = setup_with_fee(&env, 250);
+        let destination = Address::generate(&env);
+        assert_eq!(
+            client.preview_sweep(&destination).error_code,
+            Some(Error::NoPaymentReceived as u32)
+        );
+
+        let asset = fund_asset(&env, &contract_id, 1_000);
+        client.record_payment(&1_000, &asset);
+        let preview = client.preview_sweep(&destination);
+        assert_eq!(preview.error_code, None);
+        assert!(!preview.held);
+        assert_eq!(
+            preview.amounts,
+            vec![
+                &env,
+                SweepAmount {
+                    asset: asset.clone(),
+                    gross: 1_000,
+                    fee: 25,
+                    net: 975,
+                }
+            ]
+        );
+
+        // Previewing changes nothing
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&contract_id), 1_000);
+
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(
+            client.get_last_reserve_event().unwrap().amount,
+            preview.reserve_reclaim
+        );
+        assert_eq!(
+            client.preview_sweep(&destination).error_code,
+            Some(Error::AlreadySwept as u32)
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "audit", feature = "config"))]
+    fn test_preview_sweep_reports_expiry_and_destination() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let destination = Address::generate(&env);
+        assert_eq!(
+            client.preview_sweep(&destination).error_code,
+            Some(Error::NotInitialized as u32)
+        );
+
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 10),
+            &Address::generate(&env),
+            &AccountConfig {
+                locked_destination: Some(destination.clone()),
+                ..Default::default()
+            },
+        );
+        client.record_payment(&100, &fund_asset(&env, &contract_id, 100));
+        assert_eq!(
+            client.preview_sweep(&Address::generate(&env)).error_code,
+            Some(Error::DestinationMismatch as u32)
+        );
+        assert_eq!(client.preview_sweep(&destination).error_code, None);
+
+        env.ledger().with_mut(|li| li.sequence_number += 11);
+        assert_eq!(
+            client.preview_sweep(&destination).error_code,
+            Some(Error::AccountExpired as u32)
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "fees", feature = "partial"))]
+    fn test_sweep_assets_reports_protocol_fee() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, collector, client) = setup_with_fee(&env, 250);
+        let asset = fund_asset(&env, &contract_id, 1_000);
+        client.record_payment(&1_000, &asset);
+
+        let destination = Address::generate(&env);
+        let assets = vec![&env, asset.clone()];
+        client.sweep_assets(
+            &destination,
+            &assets,
+            &sign_sweep_assets(&env, &client, &destination, &assets),
+        );
+
+        let partial: PartialSweepExecuted = event_data(&env, symbol_short!("swept_prt"));
+        assert_eq!(
+            partial.amounts,
+            vec![
+                &env,
+                SweepAmount {
+                    asset: asset.clone(),
+                    gross: 1_000,
+                    fee: 25,
+                    net: 975,
+                }
+            ]
+        );
+
+        let token = TokenClient::new(&env, &asset);
+        assert_eq!(token.balance(&collector), 25);
+        assert_eq!(token.balance(&destination), 975);
+    }
+
+    /// Split shares are taken from what is left after the fee.
+    #[test]
+    #[cfg(all(feature = "fees", feature = "partial"))]
+    fn test_sweep_split_deducts_protocol_fee() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, collector, client) = setup_with_fee(&env, 1_000);
+        let asset = fund_asset(&env, &contract_id, 1_000);
+        client.record_payment(&1_000, &asset);
+
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        let destinations = vec![&env, (first.clone(), 5_000), (second.clone(), 5_000)];
+        client.sweep_split(
+            &destinations,
+            &sign_sweep_split(&env, &client, &destinations),
+        );
+
+        let token = TokenClient::new(&env, &asset);
+        assert_eq!(token.balance(&collector), 100);
+        assert_eq!(token.balance(&first), 450);
+        assert_eq!(token.balance(&second), 450);
+    }
+
+    #[test]
+    #[cfg(all(feature = "config", not(feature = "fees")))]
+    fn test_fee_rejected_without_fees_feature() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        assert_eq!(
+            client.try_initialize_with_config(
+                &Address::generate(&env),
+                &(env.ledger().sequence() + 1000),
+                &Address::generate(&env),
+                &AccountConfig {
+                    fee_collector: Some(Address::generate(&env)),
+                    fee_bps: 100,
+                    ..Default::default()
+                },
+            ),
+            Err(Ok(Error::FeatureDisabled))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "config", not(feature = "keeper")))]
+    fn test_keeper_settings_rejected_without_keeper_feature() {
+        use crate::AuthPolicy;
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+        for config in [
+            AccountConfig {
+                auth_policy: AuthPolicy::Restricted,
+                ..Default::default()
+            },
+            AccountConfig {
+                keeper_bounty_bps: Some(100),
+                ..Default::default()
+            },
+        ] {
+            assert_eq!(
+                client.try_initialize_with_config(&creator, &expiry_ledger, &recovery, &config),
+                Err(Ok(Error::FeatureDisabled))
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "fees")]
+    fn test_fee_above_whole_payment_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let result = client.try_initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                fee_collector: Some(Address::generate(&env)),
+                fee_bps: 10_001,
+                ..Default::default()
+            },
+        );
+        assert_eq!(result, Err(Ok(Error::InvalidFee)));
+    }
+
+    #[cfg(feature = "passkey")]
+    fn passkey_signing_key() -> p256::ecdsa::SigningKey {
+        p256::ecdsa::SigningKey::from_bytes(&[9u8; 32].into()).unwrap()
+    }
+
+    #[cfg(feature = "passkey")]
+    fn passkey_setup(env: &Env) -> (Address, EphemeralAccountContractClient<'_>) {
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(env, &contract_id);
+        let point = passkey_signing_key()
+            .verifying_key()
+            .to_encoded_point(false);
+        client.initialize_with_config(
+            &Address::generate(env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(env),
+            &AccountConfig {
+                signer_kind: SignerKind::Secp256r1,
+                passkey_signer: Some(BytesN::from_array(
+                    env,
+                    point.as_bytes().try_into().unwrap(),
+                )),
+                ..Default::default()
+            },
+        );
+        (contract_id, client)
+    }
+
+    #[test]
+    #[cfg(all(feature = "passkey", feature = "views"))]
+    fn test_passkey_authorizes_sweep() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, client) = passkey_setup(&env);
+        assert_eq!(client.get_signer_kind(), SignerKind::Secp256r1);
+        assert!(client.get_passkey_signer().is_some());
+        assert_eq!(client.get_sweep_signer(), None);
+
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+
+        let destination = Address::generate(&env);
+        let message = client.get_sweep_message(&destination).to_array();
+        let signature: p256::ecdsa::Signature = passkey_signing_key().sign(&message);
+        let signature = signature.normalize_s().unwrap_or(signature);
+        assert_eq!(
+            client.try_sweep(&destination, &vec![&env]),
+            Err(Ok(Error::ThresholdNotMet))
+        );
+        client.sweep(
+            &destination,
+            &vec![&env, BytesN::from_array(&env, &signature.to_bytes().into())],
+        );
+
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+    }
+
+    /// A passkey signature no verifier could accept fails before the host
+    /// is asked.
+    #[test]
+    #[cfg(feature = "passkey")]
+    fn test_passkey_rejects_malformed_signature() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, client) = passkey_setup(&env);
+        client.record_payment(&100, &fund_asset(&env, &contract_id, 100));
+
+        let destination = Address::generate(&env);
+        let message = client.get_sweep_message(&destination).to_array();
+        let signature: p256::ecdsa::Signature = passkey_signing_key().sign(&message);
+        let low = signature.normalize_s().unwrap_or(signature);
+        let high = p256::ecdsa::Signature::from_scalars(low.r(), -*low.s()).unwrap();
+        let mut zero_r: [u8; 64] = low.to_bytes().into();
+        zero_r[..32].fill(0);
+
+        for bytes in [high.to_bytes().into(), zero_r] {
+            assert_eq!(
+                client.try_sweep(&destination, &vec![&env, BytesN::from_array(&env, &bytes)]),
+                Err(Ok(Error::Unauthorized))
+            );
+        }
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+    }
+
+    /// An ed25519 signature does not verify against a passkey account.
+    #[test]
+    #[should_panic]
+    #[cfg(feature = "passkey")]
+    fn test_passkey_rejects_ed25519_signature() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, client) = passkey_setup(&env);
+        client.record_payment(&100, &fund_asset(&env, &contract_id, 100));
+
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+    }
+
+    #[test]
+    #[cfg(feature = "passkey")]
+    fn test_passkey_kind_requires_key() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let result = client.try_initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                signer_kind: SignerKind::Secp256r1,
+                sweep_signer: Some(sweep_signer_key(&env)),
+                ..Default::default()
+            },
+        );
+        assert_eq!(result, Err(Ok(Error::InvalidSignerSet)));
+    }
+
+    /// Keys of a 2-of-3 sweep signer set.
+    #[cfg(feature = "multisig")]
+    fn multisig_keys() -> [SigningKey; 3] {
+        [
+            SigningKey::from_bytes(&[1u8; 32]),
+            SigningKey::from_bytes(&[2u8; 32]),
+            SigningKey::from_bytes(&[3u8; 32]),
+        ]
+    }
+
+    #[test]
+    #[cfg(all(feature = "config", feature = "multisig"))]
+    fn test_multisig_sweep_requires_threshold() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let keys = multisig_keys();
+        let mut signers = Vec::new(&env);
+        for key in keys.iter() {
+            signers.push_back(BytesN::from_array(&env, &key.verifying_key().to_bytes()));
+        }
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                sweep_signers: Some(signers.clone()),
+                sweep_threshold: 2,
+                ..Default::default()
+            },
+        );
+        assert_eq!(client.get_sweep_signers(), signers);
+        assert_eq!(client.get_sweep_threshold(), 2);
+        assert_eq!(client.get_sweep_signer(), None);
+
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+
+        let destination = Address::generate(&env);
+        let digest = client.get_sweep_message(&destination).to_array();
+        let signed_by = |key: &SigningKey| BytesN::from_array(&env, &key.sign(&digest).to_bytes());
+        let unsigned = BytesN::from_array(&env, &[0u8; 64]);
+
+        // One signature is not enough, and there is no slot past the last signer.
+        assert_eq!(
+            client.try_sweep(&destination, &vec![&env, signed_by(&keys[0])]),
+            Err(Ok(Error::ThresholdNotMet))
+        );
+        assert_eq!(
+            client.try_sweep(
+                &destination,
+                &vec![
+                    &env,
+                    signed_by(&keys[0]),
+                    unsigned.clone(),
+                    unsigned.clone(),
+                    unsigned.clone()
+                ]
+            ),
+            Err(Ok(Error::InvalidSignature))
+        );
+
+        client.sweep(
+            &destination,
+            &vec![&env, signed_by(&keys[0]), unsigned, signed_by(&keys[2])],
+        );
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+    }
+
+    /// A signature in the wrong slot does not verify and aborts the sweep.
+    #[test]
+    #[should_panic]
+    #[cfg(feature = "multisig")]
+    fn test_multisig_sweep_rejects_signature_in_wrong_slot() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        let keys = multisig_keys();
+        let mut signers = Vec::new(&env);
+        for key in keys.iter() {
+            signers.push_back(BytesN::from_array(&env, &key.verifying_key().to_bytes()));
+        }
+        client.set_sweep_signers(&signers, &1);
+        client.record_payment(&100, &fund_asset(&env, &contract_id, 100));
+
+        let destination = Address::generate(&env);
+        let digest = client.get_sweep_message(&destination).to_array();
+        let signature = BytesN::from_array(&env, &keys[0].sign(&digest).to_bytes());
+        client.sweep(
+            &destination,
+            &vec![&env, BytesN::from_array(&env, &[0u8; 64]), signature],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "multisig")]
+    fn test_set_sweep_signers_validates_set() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+
+        let key = sweep_signer_key(&env);
+        let other = BytesN::from_array(&env, &[5u8; 32]);
+        for (signers, threshold) in [
+            (Vec::new(&env), 1),
+            (vec![&env, key.clone(), other.clone()], 0),
+            (vec![&env, key.clone(), other.clone()], 3),
+            (vec![&env, key.clone(), key.clone()], 1),
+        ] {
+            assert_eq!(
+                client.try_set_sweep_signers(&signers, &threshold),
+                Err(Ok(Error::InvalidSignerSet))
+            );
+        }
+
+        client.set_sweep_signers(&vec![&env, key.clone(), other], &2);
+        assert_eq!(client.get_sweep_threshold(), 2);
+
+        // A single signer replaces the set with 1-of-1.
+        client.set_sweep_signer(&key);
+        assert_eq!(client.get_sweep_signers(), vec![&env, key.clone()]);
+        assert_eq!(client.get_sweep_signer(), Some(key));
+    }
+
+    #[test]
+    #[cfg(all(feature = "config", not(feature = "multisig")))]
+    fn test_signer_set_limited_to_one_key_without_multisig_feature() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        let key = sweep_signer_key(&env);
+        let other = BytesN::from_array(&env, &[5u8; 32]);
+        let config = |signers: Vec<BytesN<32>>| AccountConfig {
+            sweep_signers: Some(signers),
+            sweep_threshold: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            client.try_initialize_with_config(
+                &creator,
+                &expiry_ledger,
+                &recovery,
+                &config(vec![&env, key.clone(), other]),
+            ),
+            Err(Ok(Error::InvalidSignerSet))
+        );
+        client.initialize_with_config(
+            &creator,
+            &expiry_ledger,
+            &recovery,
+            &config(vec![&env, key.clone()]),
+        );
+        assert_eq!(client.get_sweep_signer(), Some(key));
+    }
+
+    /// A well-formed signature by another key traps in the host verifier
+    /// instead of returning a contract error, and the sweep is undone.
+    #[test]
+    fn test_sweep_with_wrong_signature_fails_in_host() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(&env));
+        let asset = fund_asset(&env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+
+        let destination = Address::generate(&env);
+        let digest = client.get_sweep_message(&destination).to_array();
+        let forged = SigningKey::from_bytes(&[9u8; 32]).sign(&digest).to_bytes();
+        let result = client.try_sweep(&destination, &vec![&env, BytesN::from_array(&env, &forged)]);
+        assert!(matches!(result, Err(Err(_))));
+
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+        assert_eq!(client.get_sweep_nonce(), 0);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&contract_id), 100);
+    }
+
+    /// A signature captured for one account must not authorize a sweep of
+    /// a clone that shares its signer, expiry and nonce.
+    #[test]
+    fn test_sweep_signature_is_bound_to_account() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let destination = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 1000;
+
+        let mut clients = std::vec::Vec::new();
+        for _ in 0..2 {
+            let contract_id = env.register(EphemeralAccountContract, ());
+            let client = EphemeralAccountContractClient::new(&env, &contract_id);
+            client.initialize(&creator, &expiry_ledger, &recovery);
+            client.set_sweep_signer(&sweep_signer_key(&env));
+            client.record_payment(&100, &fund_asset(&env, &contract_id, 100));
+            clients.push(client);
+        }
+        assert_eq!(clients[0].get_sweep_nonce(), clients[1].get_sweep_nonce());
+
+        let captured = sign_sweep(&env, &clients[0], &destination);
+        assert!(matches!(
+            clients[1].try_sweep(&destination, &captured),
+            Err(Err(_))
+        ));
+        assert_eq!(clients[1].get_status(), AccountStatus::PaymentReceived);
+
+        clients[0].sweep(&destination, &captured);
+        assert_eq!(clients[0].get_sweep_nonce(), 1);
+    }
+
+    //  Long-running TTL / archival
+
+    /// 180 days of ledgers, so expiries beyond the 30-day window fit.
+    const SOAK_MAX_ENTRY_TTL: u32 = 3_110_400;
+
+    fn soak_env() -> Env {
+        let env = Env::default();
+        env.ledger().with_mut(|li| {
+            li.sequence_number = 100_000;
+            li.min_persistent_entry_ttl = 4_096;
+            li.max_entry_ttl = SOAK_MAX_ENTRY_TTL;
+        });
+        env.mock_all_auths();
+        env
+    }
+
+    fn advance_ledgers(env: &Env, ledgers: u32) {
+        env.ledger().with_mut(|li| li.sequence_number += ledgers);
+    }
+
+    /// Simulate a `RestoreFootprint` of every archived persistent entry.
+    ///
+    /// The test host offers no restore operation, so the ledger is
+    /// round-tripped through a snapshot with the archived entries given the
+    /// minimum persistent TTL, as the network does on restore.  Returns the
+    /// new environment and the number of entries that were restored.
+    #[cfg(feature = "views")]
+    fn restore_archived(env: &Env) -> (Env, u32) {
+        let mut snapshot = env.to_ledger_snapshot();
+        let sequence = snapshot.sequence_number;
+        let live_until = sequence + snapshot.min_persistent_entry_ttl - 1;
+
+        let mut restored = 0;
+        for (key, (_, entry_live_until)) in snapshot.ledger_entries.iter_mut() {
+            let temporary = matches!(
+                key.as_ref(),
+                LedgerKey::ContractData(data)
+                    if data.durability == ContractDataDurability::Temporary
+            );
+            if let Some(entry_live_until) = entry_live_until {
+                if !temporary && *entry_live_until < sequence {
+                    *entry_live_until = live_until;
+                    restored += 1;
+                }
+            }
+        }
+
+        let restored_env = Env::from_ledger_snapshot(snapshot);
+        restored_env.mock_all_auths();
+        (restored_env, restored)
+    }
+
+    #[cfg(feature = "views")]
+    fn rebind_address(env: &Env, address: &Address) -> Address {
+        Address::try_from_val(env, &ScAddress::from(address)).unwrap()
+    }
+
+    /// An account is never written to between creation and expiry, so
+    /// initialization alone must keep it live past an expiry that lies
+    /// beyond the regular 30-day extension window.
+    #[test]
+    fn test_idle_account_survives_until_far_expiry() {
+        let env = soak_env();
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let expiry_ledger = env.ledger().sequence() + 1_500_000;
+        client.initialize(
+            &Address::generate(&env),
+            &expiry_ledger,
+            &Address::generate(&env),
+        );
+
+        // Idle for ~87 days, then expire it a ledger past its expiry.
+        advance_ledgers(&env, 1_500_001);
+        client.expire();
+        assert_eq!(client.get_status(), AccountStatus::Expired);
+    }
+
+    /// Activity every 20 days keeps an account alive indefinitely: the
+    /// renewal threshold sits at half of the extension window.
+    #[test]
+    fn test_periodic_activity_keeps_account_alive() {
+        let env = soak_env();
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let expiry_ledger = env.ledger().sequence() + 3_000_000;
+        client.initialize(
+            &Address::generate(&env),
+            &expiry_ledger,
+            &Address::generate(&env),
+        );
+
+        for round in 1..=8 {
+            advance_ledgers(&env, 345_600);
+            client.record_payment(&round, &Address::generate(&env));
+        }
+        assert_eq!(client.get_info().payment_count, 8);
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+    }
+
+    /// An account left idle past its TTL is archived, and once restored it
+    /// resumes from exactly the state it had.
+    #[test]
+    #[cfg(feature = "views")]
+    fn test_archived_account_resumes_after_restore() {
+        let env = soak_env();
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+
+        let recovery = Address::generate(&env);
+        let expiry_ledger = env.ledger().sequence() + 1_000;
+        client.initialize(&Address::generate(&env), &expiry_ledger, &recovery);
+        let asset = fund_asset(&env, &contract_id, 250);
+        client.record_payment(&250, &asset);
+        let root = client.get_payments_root();
+
+        // Nobody calls expire() within the grace window after expiry.
+        advance_ledgers(&env, 1_000 + 518_400 + 1);
+
+        let (restored_env, restored) = restore_archived(&env);
+        assert!(restored > 0, "the idle account should have been archived");
+
+        // Host objects do not cross environments, so carry ids over as XDR.
+        let contract_id = rebind_address(&restored_env, &contract_id);
+        let recovery = rebind_address(&restored_env, &recovery);
+        let asset = rebind_address(&restored_env, &asset);
+        let env = restored_env;
+
+        env.register_at(&contract_id, EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        assert_eq!(client.get_payments_root().to_array(), root.to_array());
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+
+        client.expire();
+        assert_eq!(client.get_status(), AccountStatus::Expired);
+        assert_eq!(client.get_info().swept_to, Some(recovery.clone()));
+        assert_eq!(TokenClient::new(&env, &asset).balance(&recovery), 250);
+    }
+
+    /// Settlement hook keeping the arguments of its last call, or failing
+    /// once told to.
+    #[contract]
+    struct MockHook;
+
+    #[contractimpl]
+    impl MockHook {
+        pub fn set_failing(env: Env, failing: bool) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("failing"), &failing);
+        }
+
+        pub fn on_account_settled(
+            env: Env,
+            account: Address,
+            status: AccountStatus,
+            total_by_asset: Map<Address, i128>,
+            _reference: Option<BytesN<32>>,
+            _payers: Vec<Address>,
+        ) {
+            let storage = env.storage().instance();
+            if storage.get(&symbol_short!("failing")).unwrap_or(false) {
+                panic!("hook is broken");
+            }
+            storage.set(
+                &symbol_short!("settled"),
+                &(account, status, total_by_asset),
+            );
+        }
+
+        pub fn last_call(env: Env) -> Option<(Address, AccountStatus, Map<Address, i128>)> {
+            env.storage().instance().get(&symbol_short!("settled"))
+        }
+    }
+
+    #[cfg(feature = "hooks")]
+    fn setup_with_hook(env: &Env, hook: &Address) -> EphemeralAccountContractClient<'static> {
+        let client =
+            EphemeralAccountContractClient::new(env, &env.register(EphemeralAccountContract, ()));
+        client.initialize_with_config(
+            &Address::generate(env),
+            &(env.ledger().sequence() + 100),
+            &Address::generate(env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(env)),
+                settlement_hook: Some(hook.clone()),
+                ..Default::default()
+            },
+        );
+        client
+    }
+
+    #[test]
+    #[cfg(all(feature = "hooks", feature = "views"))]
+    fn test_settlement_hook_called_on_sweep() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let hook = MockHookClient::new(&env, &env.register(MockHook, ()));
+        let client = setup_with_hook(&env, &hook.address);
+        assert!(client.get_capabilities().settlement_hook);
+        let asset = fund_asset(&env, &client.address, 100);
+        client.record_payment(&100, &asset);
+        assert_eq!(hook.last_call(), None);
+
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        let (account, status, total_by_asset) = hook.last_call().unwrap();
+        assert_eq!(account, client.address);
+        assert_eq!(status, AccountStatus::Swept);
+        assert_eq!(total_by_asset, Map::from_array(&env, [(asset, 100)]));
+    }
+
+    /// Every terminal transition reaches the hook, not only sweep and
+    /// expire.
+    #[test]
+    #[cfg(all(feature = "guardian", feature = "hooks", feature = "lifecycle"))]
+    fn test_settlement_hook_called_on_emergency_recover_and_cancel() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let hook = MockHookClient::new(&env, &env.register(MockHook, ()));
+        let client =
+            EphemeralAccountContractClient::new(&env, &env.register(EphemeralAccountContract, ()));
+        client.initialize_with_config(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+            &AccountConfig {
+                settlement_hook: Some(hook.address.clone()),
+                guardian: Some(Address::generate(&env)),
+                emergency_delay_ledgers: 100,
+                ..Default::default()
+            },
+        );
+        let asset = fund_asset(&env, &client.address, 100);
+        client.record_payment(&100, &asset);
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+
+        client.emergency_recover();
+        let (account, status, total_by_asset) = hook.last_call().unwrap();
+        assert_eq!(account, client.address);
+        assert_eq!(status, AccountStatus::EmergencyRecovered);
+        assert_eq!(total_by_asset, Map::from_array(&env, [(asset, 100)]));
+
+        let client = setup_with_hook(&env, &hook.address);
+        client.cancel(&CloseReason::CreatorCancelled);
+        let (account, status, total_by_asset) = hook.last_call().unwrap();
+        assert_eq!(account, client.address);
+        assert_eq!(status, AccountStatus::Cancelled);
+        assert!(total_by_asset.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "hooks")]
+    fn test_failing_settlement_hook_does_not_block_expire() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let hook = MockHookClient::new(&env, &env.register(MockHook, ()));
+        hook.set_failing(&true);
+        let client = setup_with_hook(&env, &hook.address);
+        client.record_payment(&100, &fund_asset(&env, &client.address, 100));
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+
+        client.expire();
+        let event: HookFailed = event_data(&env, symbol_short!("hook_fail"));
+        assert_eq!(event.hook, hook.address);
+        assert_eq!(event.status, AccountStatus::Expired);
+        assert_eq!(client.get_status(), AccountStatus::Expired);
+    }
+
+    //  Prepared sweeps
+
+    /// An account with one payment of 100 and the test sweep signer.
+    #[cfg(feature = "prepared")]
+    fn setup_prepared(env: &Env) -> (EphemeralAccountContractClient<'static>, Address) {
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(env, &contract_id);
+        client.initialize(
+            &Address::generate(env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(env));
+        let asset = fund_asset(env, &contract_id, 100);
+        client.record_payment(&100, &asset);
+        (client, asset)
+    }
+
+    /// sha256(destination || salt), built independently of the contract.
+    #[cfg(feature = "prepared")]
+    fn commit_to(env: &Env, destination: &Address, salt: &BytesN<32>) -> BytesN<32> {
+        let mut preimage = destination.clone().to_xdr(env);
+        preimage.extend_from_array(&salt.to_array());
+        env.crypto().sha256(&preimage).to_bytes()
+    }
+
+    /// Sign `action` (`prepare` or `cancel`) of `commitment`:
+    /// sha256(contract || action || commitment || nonce || expiry).
+    #[cfg(feature = "prepared")]
+    fn sign_prepared(
+        env: &Env,
+        client: &EphemeralAccountContractClient,
+        action: &[u8],
+        commitment: &BytesN<32>,
+    ) -> Vec<BytesN<64>> {
+        let mut message = client.address.clone().to_xdr(env);
+        message.extend_from_slice(action);
+        message.extend_from_array(&commitment.to_array());
+        message.extend_from_array(&client.get_sweep_nonce().to_be_bytes());
+        message.extend_from_array(&client.get_info().expiry_ledger.to_be_bytes());
+        let digest = env.crypto().sha256(&message).to_array();
+        vec![
+            env,
+            BytesN::from_array(env, &sweep_signing_key().sign(&digest).to_bytes()),
+        ]
+    }
+
+    #[test]
+    #[cfg(feature = "prepared")]
+    fn test_prepared_sweep_reveals_committed_destination() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, asset) = setup_prepared(&env);
+        let destination = Address::generate(&env);
+        let salt = BytesN::from_array(&env, &[9u8; 32]);
+        let commitment = commit_to(&env, &destination, &salt);
+
+        let signatures = sign_prepared(&env, &client, b"prepare", &commitment);
+        client.prepare_sweep(&commitment, &signatures);
+
+        let expires_ledger = env.ledger().sequence() + PREPARED_SWEEP_LEDGERS;
+        let event: SweepPrepared = event_data(&env, symbol_short!("prepared"));
+        assert_eq!(
+            event,
+            SweepPrepared {
+                commitment: commitment.clone(),
+                expires_ledger,
+            }
+        );
+        assert_eq!(
+            client.get_prepared_sweep(),
+            Some(PreparedSweep {
+                commitment,
+                expires_ledger,
+            })
+        );
+        assert_eq!(client.get_sweep_nonce(), 1);
+
+        client.execute_prepared_sweep(&destination, &salt);
+
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+        assert_eq!(client.get_prepared_sweep(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "prepared")]
+    fn test_prepared_sweep_refuses_other_reveal() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _) = setup_prepared(&env);
+        let destination = Address::generate(&env);
+        let salt = BytesN::from_array(&env, &[9u8; 32]);
+        let commitment = commit_to(&env, &destination, &salt);
+        client.prepare_sweep(
+            &commitment,
+            &sign_prepared(&env, &client, b"prepare", &commitment),
+        );
+
+        assert_eq!(
+            client.try_execute_prepared_sweep(&Address::generate(&env), &salt),
+            Err(Ok(Error::CommitmentMismatch))
+        );
+        assert_eq!(
+            client.try_execute_prepared_sweep(&destination, &BytesN::from_array(&env, &[8u8; 32])),
+            Err(Ok(Error::CommitmentMismatch))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "prepared")]
+    fn test_prepared_sweep_expires() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _) = setup_prepared(&env);
+        let destination = Address::generate(&env);
+        let salt = BytesN::from_array(&env, &[9u8; 32]);
+        let commitment = commit_to(&env, &destination, &salt);
+        client.prepare_sweep(
+            &commitment,
+            &sign_prepared(&env, &client, b"prepare", &commitment),
+        );
+
+        env.ledger()
+            .with_mut(|li| li.sequence_number += PREPARED_SWEEP_LEDGERS + 1);
+        assert_eq!(
+            client.try_execute_prepared_sweep(&destination, &salt),
+            Err(Ok(Error::NoPreparedSweep))
+        );
+    }
+
+    /// The creator withdraws a mistaken destination without the signers.
+    #[test]
+    #[cfg(feature = "prepared")]
+    fn test_creator_cancels_prepared_sweep() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _) = setup_prepared(&env);
+        let destination = Address::generate(&env);
+        let salt = BytesN::from_array(&env, &[9u8; 32]);
+        let commitment = commit_to(&env, &destination, &salt);
+        client.prepare_sweep(
+            &commitment,
+            &sign_prepared(&env, &client, b"prepare", &commitment),
+        );
+
+        client.cancel_prepared_sweep(&Vec::new(&env));
+
+        let creator = env.auths()[0].0.clone();
+        let event: PreparedSweepCancelled = event_data(&env, symbol_short!("prep_cncl"));
+        assert_eq!(creator, client.get_info().creator);
+        assert_eq!(
+            event,
+            PreparedSweepCancelled {
+                commitment,
+                by_creator: true,
+            }
+        );
+        assert_eq!(client.get_prepared_sweep(), None);
+        assert_eq!(
+            client.try_execute_prepared_sweep(&destination, &salt),
+            Err(Ok(Error::NoPreparedSweep))
+        );
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+    }
+
+    #[test]
+    #[cfg(feature = "prepared")]
+    fn test_signers_cancel_prepared_sweep() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _) = setup_prepared(&env);
+        let commitment = commit_to(
+            &env,
+            &Address::generate(&env),
+            &BytesN::from_array(&env, &[9u8; 32]),
+        );
+        client.prepare_sweep(
+            &commitment,
+            &sign_prepared(&env, &client, b"prepare", &commitment),
+        );
+
+        let signatures = sign_prepared(&env, &client, b"cancel", &commitment);
+        assert_eq!(
+            client.get_cancel_message(),
+            BytesN::from_array(&env, &{
+                let mut message = client.address.clone().to_xdr(&env);
+                message.extend_from_slice(b"cancel");
+                message.extend_from_array(&commitment.to_array());
+                message.extend_from_array(&client.get_sweep_nonce().to_be_bytes());
+                message.extend_from_array(&client.get_info().expiry_ledger.to_be_bytes());
+                env.crypto().sha256(&message).to_array()
+            })
+        );
+        client.cancel_prepared_sweep(&signatures);
+
+        let event: PreparedSweepCancelled = event_data(&env, symbol_short!("prep_cncl"));
+        assert!(!event.by_creator);
+        assert_eq!(client.get_prepared_sweep(), None);
+        assert_eq!(client.get_sweep_nonce(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "prepared")]
+    fn test_cancel_without_prepared_sweep_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _) = setup_prepared(&env);
+        assert_eq!(
+            client.try_cancel_prepared_sweep(&Vec::new(&env)),
+            Err(Ok(Error::NoPreparedSweep))
+        );
+        assert_eq!(
+            client.try_get_cancel_message(),
+            Err(Ok(Error::NoPreparedSweep))
+        );
+    }
+
+    /// An account reporting to a gate that caps its merchant at `daily_cap`
+    /// a day in every asset it is paid in.
+    #[cfg(feature = "compliance")]
+    fn setup_with_gate(
+        env: &Env,
+        asset: &Address,
+        daily_cap: i128,
+    ) -> (
+        EphemeralAccountContractClient<'static>,
+        ComplianceGateClient<'static>,
+    ) {
+        let gate = ComplianceGateClient::new(env, &env.register(ComplianceGate, ()));
+        gate.initialize(&Address::generate(env));
+        let limits = VelocityLimits {
+            daily: Some(daily_cap),
+            weekly: None,
+        };
+        gate.set_default_limits(&Role::Merchant, asset, &limits);
+        gate.set_default_limits(&Role::Payer, asset, &limits);
+
+        let client =
+            EphemeralAccountContractClient::new(env, &env.register(EphemeralAccountContract, ()));
+        let creator = Address::generate(env);
+        gate.enroll(&client.address, &creator);
+        client.initialize_with_config(
+            &creator,
+            &(env.ledger().sequence() + 100),
+            &Address::generate(env),
+            &AccountConfig {
+                sweep_signer: Some(sweep_signer_key(env)),
+                accumulate_payments: true,
+                compliance_gate: Some(gate.address.clone()),
+                ..Default::default()
+            },
+        );
+        (client, gate)
+    }
+
+    #[test]
+    #[cfg(feature = "compliance")]
+    fn test_compliance_gate_counts_payments_and_allows_sweep() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let asset = fund_asset(&env, &Address::generate(&env), 0);
+        let (client, gate) = setup_with_gate(&env, &asset, 100);
+        StellarAssetClient::new(&env, &asset).mint(&client.address, &100);
+
+        client.record_payment(&60, &asset);
+        client.record_payment(&40, &asset);
+
+        let merchant = client.get_info().creator;
+        assert_eq!(
+            gate.get_volume(&Role::Merchant, &merchant, &asset),
+            Volume {
+                daily: 100,
+                weekly: 100
+            }
+        );
+        let destination = Address::generate(&env);
+        client.sweep(&destination, &sign_sweep(&env, &client, &destination));
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+    }
+
+    /// A payment past the merchant's cap is still recorded, since the
+    /// funds already arrived, but sweeps wait for the risk team.
+    #[test]
+    #[cfg(feature = "compliance")]
+    fn test_compliance_gate_holds_sweep_over_cap() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let asset = fund_asset(&env, &Address::generate(&env), 0);
+        let (client, gate) = setup_with_gate(&env, &asset, 100);
+        StellarAssetClient::new(&env, &asset).mint(&client.address, &150);
+
+        client.record_payment(&150, &asset);
+        let limit_hit = symbol_short!("limit_hit");
+        assert!(env.events().all().iter().any(|(contract, topics, _)| {
+            contract == gate.address
+                && Symbol::try_from_val(&env, &topics.get(1).unwrap()) == Ok(limit_hit.clone())
+        }));
+        assert_eq!(client.get_status(), AccountStatus::PaymentReceived);
+        assert!(gate.is_held(&client.address));
+
+        let destination = Address::generate(&env);
+        let signatures = sign_sweep(&env, &client, &destination);
+        assert_eq!(
+            client.try_sweep(&destination, &signatures),
+            Err(Ok(Error::ComplianceHold))
+        );
+
+        gate.release(&client.address);
+        client.sweep(&destination, &signatures);
+        assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 150);
+    }
+
+    #[test]
+    #[cfg(all(feature = "compliance", feature = "attribution"))]
+    fn test_compliance_gate_caps_payer() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let asset = fund_asset(&env, &Address::generate(&env), 0);
+        let (client, gate) = setup_with_gate(&env, &asset, 100);
+        gate.set_default_limits(&Role::Merchant, &asset, &VelocityLimits::default());
+        StellarAssetClient::new(&env, &asset).mint(&client.address, &150);
+
+        let payer = Address::generate(&env);
+        client.record_payment_from(&100, &asset, &payer, &None);
+        assert!(!gate.is_held(&client.address));
+        client.record_payment_from(&50, &asset, &payer, &None);
+
+        assert_eq!(gate.get_volume(&Role::Payer, &payer, &asset).daily, 150);
+        assert!(gate.is_held(&client.address));
+    }
+
+    //  Storage cost benchmark
+
+    /// Budget ceilings for a ten-payment account, about 1.5x the measured
+    /// cost.  `get_info` reads the instance, the asset index and each
+    /// asset's payment.
+    const GET_INFO_CPU_LIMIT: u64 = 650_000;
+    const GET_INFO_MEM_LIMIT: u64 = 75_000;
+    const GET_INFO_READ_ENTRIES: u32 = 2 + crate::MAX_PAYMENT_ASSETS;
+    /// `get_status` reads the instance alone.
+    #[cfg(feature = "attribution")]
+    const STATUS_READ_BYTES_LIMIT: u32 = 1_900;
+    /// `get_payment_count` reads the instance and the asset index.
+    #[cfg(all(feature = "attribution", feature = "views"))]
+    const PAYMENT_COUNT_READ_ENTRIES: u32 = 2;
+    /// `record_payment` reads the instance, the asset index and the
+    /// asset's own payment.
+    const RECORD_PAYMENT_READ_ENTRIES: u32 = 3;
+    const RECORD_PAYMENT_READ_BYTES_LIMIT: u32 = 6_000;
+    const RECORD_PAYMENT_CPU_LIMIT: u64 = 1_200_000;
+    const RECORD_PAYMENT_MEM_LIMIT: u64 = 200_000;
+    /// `sweep` reads the instance and the asset index, then each asset's
+    /// payment, contract instance and balance.
+    const SWEEP_READ_ENTRIES: u32 = 2 + 3 * crate::MAX_PAYMENT_ASSETS;
+    const SWEEP_READ_BYTES_LIMIT: u32 = 18_500;
+    const SWEEP_CPU_LIMIT: u64 = 8_000_000;
+    const SWEEP_MEM_LIMIT: u64 = 1_500_000;
+
+    /// Ledger bytes read by `get_status` on an account holding `payments`
+    /// recorded payments.
+    #[cfg(feature = "attribution")]
+    fn status_read_bytes(payments: u32) -> u32 {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        for _ in 0..payments {
+            client.record_payment_with_payer_info(
+                &100,
+                &Address::generate(&env),
+                &Some(BytesN::from_array(&env, &[9u8; 32])),
+            );
+        }
+
+        client.get_status();
+        env.cost_estimate().resources().read_bytes
+    }
+
+    /// Payments are kept out of the instance, so calls that do not touch
+    /// them read the same number of bytes however many were recorded.
+    /// With the previous single payments map in instance storage this grew
+    /// by the size of every payment.
+    #[test]
+    #[cfg(feature = "attribution")]
+    fn test_bench_status_read_bytes_independent_of_payments() {
+        let single = status_read_bytes(1);
+        let full = status_read_bytes(crate::MAX_PAYMENT_ASSETS);
+        assert_eq!(single, full);
+        assert!(
+            full < STATUS_READ_BYTES_LIMIT,
+            "get_status read {full} bytes"
+        );
+    }
+
+    /// Recording a payment reads the index and its own asset's entry, not
+    /// one entry per previously recorded payment.
+    #[test]
+    fn test_bench_record_payment_reads_one_entry() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+
+        client.record_payment(&100, &Address::generate(&env));
+        client.record_payment(&100, &Address::generate(&env));
+        let second = env.cost_estimate().resources().read_entries;
+
+        for _ in 2..(crate::MAX_PAYMENT_ASSETS - 1) {
+            client.record_payment(&100, &Address::generate(&env));
+        }
+        client.record_payment(&100, &Address::generate(&env));
+        let resources = env.cost_estimate().resources();
+        let budget = env.cost_estimate().budget();
+        let (cpu, mem) = (budget.cpu_instruction_cost(), budget.memory_bytes_cost());
+
+        assert_eq!(second, RECORD_PAYMENT_READ_ENTRIES);
+        assert_eq!(resources.read_entries, RECORD_PAYMENT_READ_ENTRIES);
+        assert!(
+            resources.read_bytes < RECORD_PAYMENT_READ_BYTES_LIMIT,
+            "record_payment read {} bytes",
+            resources.read_bytes
+        );
+        assert!(cpu < RECORD_PAYMENT_CPU_LIMIT, "record_payment cpu {cpu}");
+        assert!(mem < RECORD_PAYMENT_MEM_LIMIT, "record_payment mem {mem}");
+    }
+
+    /// Counting payments reads the asset index, and a total or lookup
+    /// reads one asset's entry, never every payment with its payer hash.
+    #[test]
+    #[cfg(all(feature = "attribution", feature = "views"))]
+    fn test_bench_payment_count_reads_index_only() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        let asset = Address::generate(&env);
+        client.record_payment_with_payer_info(
+            &100,
+            &asset,
+            &Some(BytesN::from_array(&env, &[9u8; 32])),
+        );
+        for _ in 1..crate::MAX_PAYMENT_ASSETS {
+            client.record_payment_with_payer_info(
+                &100,
+                &Address::generate(&env),
+                &Some(BytesN::from_array(&env, &[9u8; 32])),
+            );
+        }
+
+        client.get_info();
+        let info_bytes = env.cost_estimate().resources().read_bytes;
+
+        assert_eq!(client.get_payment_count(), crate::MAX_PAYMENT_ASSETS);
+        let count = env.cost_estimate().resources();
+        assert_eq!(count.read_entries, PAYMENT_COUNT_READ_ENTRIES);
+        assert!(
+            count.read_bytes < info_bytes,
+            "count read {}",
+            count.read_bytes
+        );
+
+        assert_eq!(client.get_payment_total(&asset), 100);
+        let total = env.cost_estimate().resources();
+        assert_eq!(total.read_entries, PAYMENT_COUNT_READ_ENTRIES);
+        assert!(
+            total.read_bytes < info_bytes,
+            "total read {}",
+            total.read_bytes
+        );
+
+        assert_eq!(client.get_payment_by_asset(&Address::generate(&env)), None);
+        let missing = env.cost_estimate().resources();
+        assert_eq!(missing.read_entries, PAYMENT_COUNT_READ_ENTRIES);
+        assert!(missing.read_bytes < total.read_bytes);
+    }
+
+    /// A page of payments reads the asset index and the page's own
+    /// entries, however many payments the account holds.
+    #[test]
+    #[cfg(feature = "views")]
+    fn test_bench_payment_page_reads_page_entries_only() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(&env),
+        );
+        for _ in 0..crate::MAX_PAYMENT_ASSETS {
+            client.record_payment(&100, &Address::generate(&env));
+        }
+
+        let page = client.get_payments(&None, &2);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(env.cost_estimate().resources().read_entries, 2 + 2);
+    }
+
+    /// An account holding the most payments it accepts, each backed by a
+    /// funded asset, ready to be swept.
+    fn full_account(env: &Env) -> EphemeralAccountContractClient<'static> {
+        let contract_id = env.register(EphemeralAccountContract, ());
+        let client = EphemeralAccountContractClient::new(env, &contract_id);
+        client.initialize(
+            &Address::generate(env),
+            &(env.ledger().sequence() + 1000),
+            &Address::generate(env),
+        );
+        client.set_sweep_signer(&sweep_signer_key(env));
+        for _ in 0..crate::MAX_PAYMENT_ASSETS {
+            client.record_payment(&100, &fund_asset(env, &contract_id, 100));
+        }
+        client
+    }
+
+    /// `get_info` loads the index and one entry per payment, within a fixed
+    /// CPU and memory budget.
+    #[test]
+    fn test_bench_get_info_budget() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = full_account(&env);
+
+        assert_eq!(client.get_info().payment_count, crate::MAX_PAYMENT_ASSETS);
+        let budget = env.cost_estimate().budget();
+        let (cpu, mem) = (budget.cpu_instruction_cost(), budget.memory_bytes_cost());
+        let read_entries = env.cost_estimate().resources().read_entries;
+        assert!(cpu < GET_INFO_CPU_LIMIT, "get_info cpu {cpu}");
+        assert!(mem < GET_INFO_MEM_LIMIT, "get_info mem {mem}");
+        assert_eq!(read_entries, GET_INFO_READ_ENTRIES);
+    }
+
+    /// Sweeping every asset reads each payment once and stays within a
+    /// fixed CPU and memory budget.
+    #[test]
+    fn test_bench_sweep_budget() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = full_account(&env);
+        let destination = Address::generate(&env);
+        let auth_sig = sign_sweep(&env, &client, &destination);
+
+        client.sweep(&destination, &auth_sig);
+        let resources = env.cost_estimate().resources();
+        let budget = env.cost_estimate().budget();
+        let (cpu, mem) = (budget.cpu_instruction_cost(), budget.memory_bytes_cost());
+        assert_eq!(client.get_status(), AccountStatus::Swept);
+        assert_eq!(resources.read_entries, SWEEP_READ_ENTRIES);
+        assert!(
+            resources.read_bytes < SWEEP_READ_BYTES_LIMIT,
+            "sweep read {} bytes",
+            resources.read_bytes
+        );
+        assert!(cpu < SWEEP_CPU_LIMIT, "sweep cpu {cpu}");
+        assert!(mem < SWEEP_MEM_LIMIT, "sweep mem {mem}");
+    }
 }
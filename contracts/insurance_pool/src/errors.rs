@@ -0,0 +1,44 @@
+use soroban_sdk::contracterror;
+
+/// Codes sit in [`bridgelet_shared::errors::INSURANCE_POOL_ERRORS`], so
+/// they never collide with another Bridgelet contract's.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// [`InsurancePool::initialize`] was called more than once.
+    AlreadyInitialized = 1201,
+
+    /// A state-changing operation was attempted before initialization.
+    NotInitialized = 1202,
+
+    /// No factory is allowed to insure accounts.
+    NoFactory = 1203,
+
+    /// The account holds no policy, or already opened its claim.
+    NotInsured = 1204,
+
+    /// No claim has this id.
+    ClaimNotFound = 1205,
+
+    /// The claim was already paid or rejected.
+    ClaimClosed = 1206,
+
+    /// A premium is negative, or a payout not positive.
+    InvalidAmount = 1207,
+}
+
+impl Error {
+    /// Name of the variant, as `describe_error` reports it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Error::AlreadyInitialized => "AlreadyInitialized",
+            Error::NotInitialized => "NotInitialized",
+            Error::NoFactory => "NoFactory",
+            Error::NotInsured => "NotInsured",
+            Error::ClaimNotFound => "ClaimNotFound",
+            Error::ClaimClosed => "ClaimClosed",
+            Error::InvalidAmount => "InvalidAmount",
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use bridgelet_shared::EventTopics;
+use soroban_sdk::{contracttype, symbol_short, Address, Env};
+
+// ─── Event payloads ─────────────────────────────────────────────────────────
+
+/// Emitted when the factory insures an account it deployed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PolicyIssued {
+    pub account: Address,
+    pub token: Address,
+    pub premium: i128,
+}
+
+/// Emitted when an insured account expires with payments in it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimOpened {
+    pub claim_id: u64,
+    pub account: Address,
+    /// Recorded payments the account returned to its recovery address.
+    pub amount: i128,
+}
+
+/// Emitted when the admin pays a claim out of the pool.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimPaid {
+    pub claim_id: u64,
+    /// The payer refunded.
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Emitted when the admin rejects a claim.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimRejected {
+    pub claim_id: u64,
+}
+
+// ─── Emit helpers ───────────────────────────────────────────────────────────
+
+/// Publish the `policy` event, indexed by the account.
+pub fn emit_policy_issued(env: &Env, event: PolicyIssued) {
+    EventTopics::new(symbol_short!("policy")).publish_indexed(env, event.account.clone(), event);
+}
+
+/// Publish the `claim` event, indexed by the account.
+pub fn emit_claim_opened(env: &Env, event: ClaimOpened) {
+    EventTopics::new(symbol_short!("claim")).publish_indexed(env, event.account.clone(), event);
+}
+
+/// Publish the `paid` event, indexed by the recipient.
+pub fn emit_claim_paid(env: &Env, event: ClaimPaid) {
+    EventTopics::new(symbol_short!("paid")).publish_indexed(env, event.recipient.clone(), event);
+}
+
+/// Publish the `rejected` event.
+pub fn emit_claim_rejected(env: &Env, claim_id: u64) {
+    EventTopics::new(symbol_short!("rejected")).publish(env, ClaimRejected { claim_id });
+}
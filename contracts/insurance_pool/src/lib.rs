@@ -0,0 +1,289 @@
+#![no_std]
+
+mod errors;
+mod events;
+mod storage;
+#[cfg(test)]
+mod test;
+
+use bridgelet_shared::errors::UNKNOWN_ERROR;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Symbol};
+
+pub use errors::Error;
+pub use events::{ClaimOpened, ClaimPaid, ClaimRejected, PolicyIssued};
+pub use storage::DataKey;
+
+/// Cover bought for one account at deployment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Policy {
+    /// Token the premium was paid in.
+    pub token: Address,
+    pub premium: i128,
+    /// Ledger the account was insured at.
+    pub insured_ledger: u32,
+}
+
+/// Where a claim stands.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ClaimStatus {
+    /// Waiting for the admin.
+    Open,
+    /// Paid out of the pool.
+    Paid,
+    /// Closed without a payout.
+    Rejected,
+}
+
+/// Claim an insured account opened when it expired with payments.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Claim {
+    pub account: Address,
+    /// Recorded payments the account returned to its recovery address.
+    pub amount: i128,
+    pub opened_ledger: u32,
+    pub status: ClaimStatus,
+}
+
+/// Cover for customers whose payment an account never settled.
+///
+/// The account factory collects a small premium on every deployment into
+/// the pool and registers the account with [`InsurancePool::insure`].  An
+/// insured account that expires with payments in it (the customer paid,
+/// the merchant never swept) returns them to its recovery address and
+/// opens a claim with [`InsurancePool::open_claim`].  The admin then runs
+/// the expedited manual refund: they work out who paid from the account's
+/// recorded payments and refund them out of the pool with
+/// [`InsurancePool::pay_claim`], or close the claim with
+/// [`InsurancePool::reject_claim`].
+#[contract]
+pub struct InsurancePool;
+
+#[contractimpl]
+impl InsurancePool {
+    /// One-time initialization that sets the admin address.
+    ///
+    /// # Errors
+    /// * [`Error::AlreadyInitialized`] – called more than once.
+    pub fn initialize(env: Env, admin: Address) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        if storage::has_admin(&env) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+        storage::set_admin(&env, &admin);
+
+        Ok(())
+    }
+
+    /// Set or clear the factory allowed to insure accounts.  Admin-only.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    pub fn set_factory(env: Env, factory: Option<Address>) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+        Self::require_admin(&env)?;
+
+        match &factory {
+            Some(factory) => storage::set_factory(&env, factory),
+            None => storage::remove_factory(&env),
+        }
+
+        Ok(())
+    }
+
+    /// Insure `account`, whose creator paid `premium` of `token` into the
+    /// pool.  Requires the factory's authorization.
+    ///
+    /// # Errors
+    /// * [`Error::NoFactory`]     – no factory is set.
+    /// * [`Error::InvalidAmount`] – `premium` is negative.
+    pub fn insure(env: Env, account: Address, token: Address, premium: i128) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        let factory = storage::get_factory(&env).ok_or(Error::NoFactory)?;
+        factory.require_auth();
+
+        if premium < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let policy = Policy {
+            token: token.clone(),
+            premium,
+            insured_ledger: env.ledger().sequence(),
+        };
+        storage::set_policy(&env, &account, &policy);
+        events::emit_policy_issued(
+            &env,
+            PolicyIssued {
+                account,
+                token,
+                premium,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Open the claim of an insured `account` that expired having
+    /// returned `amount` of recorded payments.  Requires `account`'s
+    /// authorization and uses up its policy.
+    ///
+    /// Returns the claim id.
+    ///
+    /// # Errors
+    /// * [`Error::NotInsured`] – `account` holds no policy, or already
+    ///   opened its claim.
+    pub fn open_claim(env: Env, account: Address, amount: i128) -> Result<u64, Error> {
+        storage::extend_instance_ttl(&env);
+        account.require_auth();
+
+        if storage::get_policy(&env, &account).is_none() {
+            return Err(Error::NotInsured);
+        }
+        storage::remove_policy(&env, &account);
+
+        let claim_id = storage::get_claim_count(&env);
+        storage::set_claim_count(&env, claim_id + 1);
+        let claim = Claim {
+            account: account.clone(),
+            amount,
+            opened_ledger: env.ledger().sequence(),
+            status: ClaimStatus::Open,
+        };
+        storage::set_claim(&env, claim_id, &claim);
+        events::emit_claim_opened(
+            &env,
+            ClaimOpened {
+                claim_id,
+                account,
+                amount,
+            },
+        );
+
+        Ok(claim_id)
+    }
+
+    /// Refund `amount` of `token` out of the pool to `recipient`, the
+    /// payer of an open claim, and close it.  Admin-only.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::ClaimNotFound`]  – no claim has this id.
+    /// * [`Error::ClaimClosed`]    – the claim was already paid or rejected.
+    /// * [`Error::InvalidAmount`]  – `amount` is not positive.
+    ///
+    /// A pool holding less than `amount` aborts the invocation with the
+    /// token's error.
+    pub fn pay_claim(
+        env: Env,
+        claim_id: u64,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+        Self::require_admin(&env)?;
+
+        let mut claim = Self::open_claim_by_id(&env, claim_id)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        claim.status = ClaimStatus::Paid;
+        storage::set_claim(&env, claim_id, &claim);
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &recipient,
+            &amount,
+        );
+        events::emit_claim_paid(
+            &env,
+            ClaimPaid {
+                claim_id,
+                recipient,
+                token,
+                amount,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Close an open claim without a payout.  Admin-only.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::ClaimNotFound`]  – no claim has this id.
+    /// * [`Error::ClaimClosed`]    – the claim was already paid or rejected.
+    pub fn reject_claim(env: Env, claim_id: u64) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+        Self::require_admin(&env)?;
+
+        let mut claim = Self::open_claim_by_id(&env, claim_id)?;
+        claim.status = ClaimStatus::Rejected;
+        storage::set_claim(&env, claim_id, &claim);
+        events::emit_claim_rejected(&env, claim_id);
+
+        Ok(())
+    }
+
+    /// Policy of `account`, until it opens its claim.
+    pub fn get_policy(env: Env, account: Address) -> Option<Policy> {
+        storage::extend_instance_ttl(&env);
+        storage::get_policy(&env, &account)
+    }
+
+    /// Claim with id `claim_id`, if any.
+    pub fn get_claim(env: Env, claim_id: u64) -> Option<Claim> {
+        storage::extend_instance_ttl(&env);
+        storage::get_claim(&env, claim_id)
+    }
+
+    /// Number of claims opened so far; ids run from `0` below it.
+    pub fn get_claim_count(env: Env) -> u64 {
+        storage::extend_instance_ttl(&env);
+        storage::get_claim_count(&env)
+    }
+
+    /// Factory allowed to insure accounts, if any.
+    pub fn get_factory(env: Env) -> Option<Address> {
+        storage::extend_instance_ttl(&env);
+        storage::get_factory(&env)
+    }
+
+    /// Current admin address, if the contract has been initialized.
+    pub fn get_admin(env: Env) -> Option<Address> {
+        storage::extend_instance_ttl(&env);
+        storage::get_admin(&env)
+    }
+
+    /// Return the name of the error raised as `Error(Contract, #code)`, or
+    /// `Unknown` for a code this contract never raises.
+    pub fn describe_error(env: Env, code: u32) -> Symbol {
+        let name = Error::try_from(soroban_sdk::Error::from_contract_error(code))
+            .map_or(UNKNOWN_ERROR, Error::name);
+        Symbol::new(&env, name)
+    }
+}
+
+impl InsurancePool {
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin = storage::get_admin(env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn open_claim_by_id(env: &Env, claim_id: u64) -> Result<Claim, Error> {
+        let claim = storage::get_claim(env, claim_id).ok_or(Error::ClaimNotFound)?;
+        if claim.status != ClaimStatus::Open {
+            return Err(Error::ClaimClosed);
+        }
+        Ok(claim)
+    }
+}
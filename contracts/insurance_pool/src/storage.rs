@@ -0,0 +1,129 @@
+use crate::{Claim, Policy};
+use soroban_sdk::{contracttype, Address, Env, TryFromVal, Val};
+
+/// Storage keys used by the insurance pool.
+///
+/// The admin, the factory and the claim counter live in instance storage.
+/// Policies and claims are persistent, so the instance does not grow with
+/// every account insured.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// The admin who settles claims.
+    Admin,
+
+    /// Factory allowed to insure the accounts it deploys.
+    Factory,
+
+    /// Number of claims opened; the next claim takes this id.
+    ClaimCount,
+
+    /// Policy of an insured account with no claim yet.
+    Policy(Address),
+
+    /// A claim, by id.
+    Claim(u64),
+}
+
+// Admin helpers
+
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
+
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Admin)
+}
+
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().instance().has(&DataKey::Admin)
+}
+
+// Factory helpers
+
+pub fn set_factory(env: &Env, factory: &Address) {
+    env.storage().instance().set(&DataKey::Factory, factory);
+}
+
+pub fn remove_factory(env: &Env) {
+    env.storage().instance().remove(&DataKey::Factory);
+}
+
+pub fn get_factory(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Factory)
+}
+
+// Policy helpers
+
+pub fn set_policy(env: &Env, account: &Address, policy: &Policy) {
+    let key = DataKey::Policy(account.clone());
+    env.storage().persistent().set(&key, policy);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn remove_policy(env: &Env, account: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Policy(account.clone()));
+}
+
+pub fn get_policy(env: &Env, account: &Address) -> Option<Policy> {
+    read_persistent(env, &DataKey::Policy(account.clone()))
+}
+
+// Claim helpers
+
+pub fn get_claim_count(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::ClaimCount)
+        .unwrap_or(0)
+}
+
+pub fn set_claim_count(env: &Env, count: u64) {
+    env.storage().instance().set(&DataKey::ClaimCount, &count);
+}
+
+pub fn set_claim(env: &Env, claim_id: u64, claim: &Claim) {
+    let key = DataKey::Claim(claim_id);
+    env.storage().persistent().set(&key, claim);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn get_claim(env: &Env, claim_id: u64) -> Option<Claim> {
+    read_persistent(env, &DataKey::Claim(claim_id))
+}
+
+// TTL management
+
+/// If the remaining TTL drops below this threshold (in ledgers), extend it.
+/// 259 200 ledgers ≈ 15 days, half of the extension window.
+const TTL_THRESHOLD: u32 = 259_200;
+
+/// Extend TTLs to this many ledgers.
+/// 518 400 ledgers ≈ 30 days (at ~5 s per ledger).
+const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Keep the contract instance alive; called from every public entry-point.
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+/// Extend a persistent entry after it is written or read.
+fn extend_persistent_ttl(env: &Env, key: &DataKey) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+/// Read a persistent entry, extending it if present, so a policy or an
+/// open claim that is only looked at does not lapse.
+fn read_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        extend_persistent_ttl(env, key);
+    }
+    value
+}
@@ -0,0 +1,280 @@
+extern crate std;
+
+use crate::{
+    Claim, ClaimOpened, ClaimPaid, ClaimStatus, Error, InsurancePool, InsurancePoolClient, Policy,
+};
+use bridgelet_shared::errors::{INSURANCE_POOL_ERRORS, UNKNOWN_ERROR};
+use bridgelet_shared::{EventTopics, InsurancePoolClient as SharedPoolClient};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env, Symbol, TryFromVal,
+};
+
+// HELPERS
+
+struct Setup {
+    env: Env,
+    client: InsurancePoolClient<'static>,
+    factory: Address,
+    token: Address,
+}
+
+/// A pool trusting `factory`, holding 1 000 of `token` in premiums.
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client = InsurancePoolClient::new(&env, &env.register(InsurancePool, ()));
+    client.initialize(&Address::generate(&env));
+    let factory = Address::generate(&env);
+    client.set_factory(&Some(factory.clone()));
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    StellarAssetClient::new(&env, &token).mint(&client.address, &1_000);
+
+    Setup {
+        env,
+        client,
+        factory,
+        token,
+    }
+}
+
+/// An account the factory insured for a premium of 10.
+fn insured_account(s: &Setup) -> Address {
+    let account = Address::generate(&s.env);
+    s.client.insure(&account, &s.token, &10);
+    account
+}
+
+//  Policies
+
+#[test]
+fn test_insure_issues_policy() {
+    let s = setup();
+    let account = insured_account(&s);
+
+    assert_eq!(s.env.auths()[0].0, s.factory);
+    assert_eq!(
+        s.client.get_policy(&account),
+        Some(Policy {
+            token: s.token.clone(),
+            premium: 10,
+            insured_ledger: s.env.ledger().sequence(),
+        })
+    );
+}
+
+#[test]
+fn test_insure_without_factory_fails() {
+    let s = setup();
+    s.client.set_factory(&None);
+    assert_eq!(
+        s.client
+            .try_insure(&Address::generate(&s.env), &s.token, &10),
+        Err(Ok(Error::NoFactory))
+    );
+}
+
+#[test]
+fn test_insure_rejects_negative_premium() {
+    let s = setup();
+    assert_eq!(
+        s.client
+            .try_insure(&Address::generate(&s.env), &s.token, &-1),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+//  Claims
+
+#[test]
+fn test_open_claim_uses_up_policy() {
+    let s = setup();
+    let account = insured_account(&s);
+
+    let claim_id = s.client.open_claim(&account, &250);
+
+    assert_eq!(claim_id, 0);
+    assert_eq!(s.env.auths()[0].0, account);
+    let (_, topics, data) = s.env.events().all().last().unwrap();
+    assert_eq!(
+        EventTopics::decode(&s.env, &topics),
+        Some(EventTopics::new(symbol_short!("claim")))
+    );
+    assert_eq!(
+        Address::try_from_val(&s.env, &topics.get(3).unwrap()).unwrap(),
+        account
+    );
+    assert_eq!(
+        ClaimOpened::try_from_val(&s.env, &data).unwrap(),
+        ClaimOpened {
+            claim_id,
+            account: account.clone(),
+            amount: 250,
+        }
+    );
+    assert_eq!(s.client.get_policy(&account), None);
+    assert_eq!(
+        s.client.get_claim(&claim_id),
+        Some(Claim {
+            account: account.clone(),
+            amount: 250,
+            opened_ledger: s.env.ledger().sequence(),
+            status: ClaimStatus::Open,
+        })
+    );
+    assert_eq!(s.client.get_claim_count(), 1);
+
+    // One claim per policy
+    assert_eq!(
+        s.client.try_open_claim(&account, &250),
+        Err(Ok(Error::NotInsured))
+    );
+}
+
+#[test]
+fn test_open_claim_without_policy_fails() {
+    let s = setup();
+    assert_eq!(
+        s.client.try_open_claim(&Address::generate(&s.env), &250),
+        Err(Ok(Error::NotInsured))
+    );
+}
+
+#[test]
+fn test_claim_ids_increase() {
+    let s = setup();
+    let first = insured_account(&s);
+    let second = insured_account(&s);
+    assert_eq!(s.client.open_claim(&first, &1), 0);
+    assert_eq!(s.client.open_claim(&second, &1), 1);
+}
+
+/// Accounts reach the pool through the shared interface.
+#[test]
+fn test_shared_client_opens_claim() {
+    let s = setup();
+    let account = insured_account(&s);
+    let claim_id = SharedPoolClient::new(&s.env, &s.client.address).open_claim(&account, &5);
+    assert_eq!(s.client.get_claim(&claim_id).unwrap().account, account);
+}
+
+#[test]
+fn test_pay_claim_refunds_payer_from_pool() {
+    let s = setup();
+    let account = insured_account(&s);
+    let claim_id = s.client.open_claim(&account, &250);
+    let payer = Address::generate(&s.env);
+
+    s.client.pay_claim(&claim_id, &payer, &s.token, &250);
+
+    let (_, _, data) = s.env.events().all().last().unwrap();
+    assert_eq!(
+        ClaimPaid::try_from_val(&s.env, &data).unwrap(),
+        ClaimPaid {
+            claim_id,
+            recipient: payer.clone(),
+            token: s.token.clone(),
+            amount: 250,
+        }
+    );
+    let token = TokenClient::new(&s.env, &s.token);
+    assert_eq!(token.balance(&payer), 250);
+    assert_eq!(token.balance(&s.client.address), 750);
+    assert_eq!(
+        s.client.get_claim(&claim_id).unwrap().status,
+        ClaimStatus::Paid
+    );
+
+    assert_eq!(
+        s.client.try_pay_claim(&claim_id, &payer, &s.token, &250),
+        Err(Ok(Error::ClaimClosed))
+    );
+}
+
+#[test]
+fn test_pay_claim_rejects_invalid_amount() {
+    let s = setup();
+    let account = insured_account(&s);
+    let claim_id = s.client.open_claim(&account, &250);
+    assert_eq!(
+        s.client
+            .try_pay_claim(&claim_id, &Address::generate(&s.env), &s.token, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_reject_claim_closes_it() {
+    let s = setup();
+    let account = insured_account(&s);
+    let claim_id = s.client.open_claim(&account, &250);
+
+    s.client.reject_claim(&claim_id);
+
+    assert_eq!(
+        s.client.get_claim(&claim_id).unwrap().status,
+        ClaimStatus::Rejected
+    );
+    assert_eq!(
+        s.client
+            .try_pay_claim(&claim_id, &Address::generate(&s.env), &s.token, &1),
+        Err(Ok(Error::ClaimClosed))
+    );
+    assert_eq!(
+        s.client.try_reject_claim(&claim_id),
+        Err(Ok(Error::ClaimClosed))
+    );
+}
+
+#[test]
+fn test_unknown_claim_fails() {
+    let s = setup();
+    assert_eq!(s.client.try_reject_claim(&7), Err(Ok(Error::ClaimNotFound)));
+    assert_eq!(s.client.get_claim(&7), None);
+}
+
+//  Admin
+
+#[test]
+fn test_initialize_twice_fails() {
+    let s = setup();
+    assert_eq!(
+        s.client.try_initialize(&Address::generate(&s.env)),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
+
+#[test]
+fn test_admin_calls_before_initialize_fail() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = InsurancePoolClient::new(&env, &env.register(InsurancePool, ()));
+    assert_eq!(
+        client.try_set_factory(&Some(Address::generate(&env))),
+        Err(Ok(Error::NotInitialized))
+    );
+    assert_eq!(client.try_reject_claim(&0), Err(Ok(Error::NotInitialized)));
+}
+
+/// Codes stay inside the pool's shared range, so off-chain tooling can
+/// tell them from another contract's.
+#[test]
+fn test_error_codes_use_reserved_range() {
+    let env = Env::default();
+    let client = InsurancePoolClient::new(&env, &env.register(InsurancePool, ()));
+    for code in INSURANCE_POOL_ERRORS {
+        let name = client.describe_error(&code);
+        if let Ok(error) = Error::try_from(soroban_sdk::Error::from_contract_error(code)) {
+            assert_eq!(name, Symbol::new(&env, error.name()));
+        } else {
+            assert_eq!(name, Symbol::new(&env, UNKNOWN_ERROR));
+        }
+    }
+    assert_eq!(client.describe_error(&1), Symbol::new(&env, UNKNOWN_ERROR));
+}
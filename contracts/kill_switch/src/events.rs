@@ -0,0 +1,19 @@
+use soroban_sdk::{contracttype, symbol_short, Env, Symbol};
+
+// ─── Event payloads ─────────────────────────────────────────────────────────
+
+/// Emitted when a subsystem is halted or resumed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HaltedSet {
+    pub subsystem: Symbol,
+    pub halted: bool,
+}
+
+// ─── Emit helpers ───────────────────────────────────────────────────────────
+
+/// Publish the `halted` event.
+pub fn emit_halted_set(env: &Env, subsystem: Symbol, halted: bool) {
+    let event = HaltedSet { subsystem, halted };
+    env.events().publish((symbol_short!("halted"),), event);
+}
@@ -0,0 +1,87 @@
+#![no_std]
+
+mod errors;
+mod events;
+mod storage;
+#[cfg(test)]
+mod test;
+
+use bridgelet_shared::errors::UNKNOWN_ERROR;
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, Symbol};
+
+pub use errors::Error;
+pub use events::HaltedSet;
+pub use storage::DataKey;
+
+/// Subsystem flag that halts every subsystem at once.
+pub const ALL_SUBSYSTEMS: Symbol = symbol_short!("all");
+
+/// Global emergency stop shared by every Bridgelet contract.
+///
+/// Holds one admin-controlled flag per subsystem (e.g. `account`,
+/// `factory`, `sweeper`).  Contracts configured with a kill switch call
+/// [`is_halted`] with their own subsystem at the start of every
+/// state-changing call and refuse to proceed while it returns `true`, so a
+/// critical vulnerability can be contained with a single transaction
+/// instead of pausing contracts one by one.
+///
+/// Halting [`ALL_SUBSYSTEMS`] stops every subsystem regardless of its own
+/// flag.
+#[contract]
+pub struct KillSwitch;
+
+#[contractimpl]
+impl KillSwitch {
+    /// One-time initialization that sets the admin address.
+    ///
+    /// # Errors
+    /// * [`Error::AlreadyInitialized`] – called more than once.
+    pub fn initialize(env: Env, admin: Address) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        if storage::has_admin(&env) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+        storage::set_admin(&env, &admin);
+
+        Ok(())
+    }
+
+    /// Halt or resume a subsystem.  Admin-only.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    pub fn set_halted(env: Env, subsystem: Symbol, halted: bool) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        storage::set_halted(&env, &subsystem, halted);
+        events::emit_halted_set(&env, subsystem, halted);
+
+        Ok(())
+    }
+
+    /// Returns `true` if `subsystem` or [`ALL_SUBSYSTEMS`] is halted.
+    pub fn is_halted(env: Env, subsystem: Symbol) -> bool {
+        storage::extend_instance_ttl(&env);
+        storage::is_halted(&env, &subsystem) || storage::is_halted(&env, &ALL_SUBSYSTEMS)
+    }
+
+    /// Returns the admin address, if the contract has been initialized.
+    pub fn get_admin(env: Env) -> Option<Address> {
+        storage::extend_instance_ttl(&env);
+        storage::get_admin(&env)
+    }
+
+    /// Return the name of the error raised as `Error(Contract, #code)`, or
+    /// `Unknown` for a code this contract never raises.
+    pub fn describe_error(env: Env, code: u32) -> Symbol {
+        let name = Error::try_from(soroban_sdk::Error::from_contract_error(code))
+            .map_or(UNKNOWN_ERROR, Error::name);
+        Symbol::new(&env, name)
+    }
+}
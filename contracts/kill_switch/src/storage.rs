@@ -0,0 +1,64 @@
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+/// Storage keys used by the kill switch.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// The admin address allowed to halt and resume subsystems.
+    Admin,
+
+    /// Whether a subsystem is halted.
+    ///
+    /// Kept in instance storage: the handful of flags is read by every
+    /// state-changing call across the system, and loading the instance
+    /// is the cheapest read available.
+    Halted(Symbol),
+}
+
+// Admin helpers
+
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
+
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Admin)
+}
+
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().instance().has(&DataKey::Admin)
+}
+
+// Flag helpers
+
+pub fn set_halted(env: &Env, subsystem: &Symbol, halted: bool) {
+    let key = DataKey::Halted(subsystem.clone());
+    if halted {
+        env.storage().instance().set(&key, &true);
+    } else {
+        env.storage().instance().remove(&key);
+    }
+}
+
+pub fn is_halted(env: &Env, subsystem: &Symbol) -> bool {
+    env.storage()
+        .instance()
+        .has(&DataKey::Halted(subsystem.clone()))
+}
+
+// TTL management
+
+/// If the remaining TTL drops below this threshold (in ledgers), extend it.
+/// 259 200 ledgers ≈ 15 days, half of the extension window.
+const TTL_THRESHOLD: u32 = 259_200;
+
+/// Extend TTLs to this many ledgers.
+/// 518 400 ledgers ≈ 30 days (at ~5 s per ledger).
+const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Keep the contract instance alive; called from every public entry-point.
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
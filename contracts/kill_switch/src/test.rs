@@ -0,0 +1,89 @@
+extern crate std;
+
+use crate::{Error, KillSwitch, KillSwitchClient, ALL_SUBSYSTEMS};
+use bridgelet_shared::errors::{KILL_SWITCH_ERRORS, UNKNOWN_ERROR};
+use soroban_sdk::{symbol_short, testutils::Address as _, Address, Env, Symbol};
+
+// HELPERS
+
+fn setup() -> (Env, KillSwitchClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(KillSwitch, ());
+    let client = KillSwitchClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    (env, client, admin)
+}
+
+//  Initialization
+
+#[test]
+fn test_initialize_stores_admin() {
+    let (_env, client, admin) = setup();
+    assert_eq!(client.get_admin(), Some(admin));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #601)")]
+fn test_initialize_twice_panics() {
+    let (env, client, _admin) = setup();
+    client.initialize(&Address::generate(&env));
+}
+
+//  Flags
+
+#[test]
+fn test_halt_is_per_subsystem() {
+    let (_env, client, _admin) = setup();
+    let account = symbol_short!("account");
+    let factory = symbol_short!("factory");
+
+    assert!(!client.is_halted(&account));
+
+    client.set_halted(&account, &true);
+    assert!(client.is_halted(&account));
+    assert!(!client.is_halted(&factory));
+
+    client.set_halted(&account, &false);
+    assert!(!client.is_halted(&account));
+}
+
+#[test]
+fn test_halting_all_subsystems_halts_each() {
+    let (_env, client, _admin) = setup();
+
+    client.set_halted(&ALL_SUBSYSTEMS, &true);
+    assert!(client.is_halted(&symbol_short!("account")));
+    assert!(client.is_halted(&symbol_short!("sweeper")));
+
+    client.set_halted(&ALL_SUBSYSTEMS, &false);
+    assert!(!client.is_halted(&symbol_short!("account")));
+}
+
+/// Flags cannot be set before initialization (error #2).
+#[test]
+#[should_panic(expected = "Error(Contract, #602)")]
+fn test_set_halted_before_initialize_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = KillSwitchClient::new(&env, &env.register(KillSwitch, ()));
+    client.set_halted(&symbol_short!("account"), &true);
+}
+
+/// Codes stay inside the kill switch's shared range, so off-chain tooling can
+/// tell them from another contract's.
+#[test]
+fn test_error_codes_use_reserved_range() {
+    let env = Env::default();
+    let client = KillSwitchClient::new(&env, &env.register(KillSwitch, ()));
+    for code in KILL_SWITCH_ERRORS {
+        let name = client.describe_error(&code);
+        if let Ok(error) = Error::try_from(soroban_sdk::Error::from_contract_error(code)) {
+            assert_eq!(name, Symbol::new(&env, error.name()));
+        } else {
+            assert_eq!(name, Symbol::new(&env, UNKNOWN_ERROR));
+        }
+    }
+    assert_eq!(client.describe_error(&1), Symbol::new(&env, UNKNOWN_ERROR));
+}
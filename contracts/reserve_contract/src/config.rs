@@ -0,0 +1,78 @@
+use soroban_sdk::contracttype;
+
+use crate::MAX_RESERVE_STROOPS;
+use bridgelet_shared::units::BPS_DENOMINATOR;
+
+/// Global parameter held by the configuration registry.
+///
+/// Each key has a fixed value type; setting it through the setter of
+/// another type fails with [`crate::Error::WrongConfigType`].
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ConfigKey {
+    /// Base reserve in stroops (`i128`).  Also served by the dedicated
+    /// base-reserve API, which this key shares its storage, roles,
+    /// timelock and history with.
+    BaseReserve = 0,
+    /// Per-merchant daily fee-bump sponsorship cap in stroops (`i128`).
+    SponsorshipBudget = 1,
+    /// Protocol fee in basis points (`i128`).
+    FeeBps = 2,
+    /// Maximum number of payments an account accepts (`i128`).
+    MaxPayments = 3,
+    /// Default ledgers between account creation and expiry (`i128`).
+    DefaultExpiryLedgers = 4,
+    /// Address collecting the protocol fee (`Address`).
+    FeeCollector = 5,
+    /// Whether new accounts emit diagnostic events (`bool`).
+    DebugEvents = 6,
+    /// Extra reserve in stroops for each trustline an account holds
+    /// (`i128`).
+    TrustlineReserve = 7,
+    /// Basis points of a reclaimed reserve paid to the keeper that
+    /// expired or reclaimed the account (`i128`).
+    KeeperBountyBps = 8,
+}
+
+/// Value type of a [`ConfigKey`], with the accepted range for integers.
+pub enum ConfigKind {
+    I128 { min: i128, max: i128 },
+    Address,
+    Bool,
+}
+
+impl ConfigKey {
+    pub fn kind(self) -> ConfigKind {
+        match self {
+            ConfigKey::BaseReserve => ConfigKind::I128 {
+                min: 1,
+                max: MAX_RESERVE_STROOPS,
+            },
+            ConfigKey::SponsorshipBudget => ConfigKind::I128 {
+                min: 0,
+                max: MAX_RESERVE_STROOPS,
+            },
+            ConfigKey::FeeBps => ConfigKind::I128 {
+                min: 0,
+                max: BPS_DENOMINATOR as i128,
+            },
+            ConfigKey::MaxPayments => ConfigKind::I128 { min: 1, max: 100 },
+            // Up to about a year of ledgers at ~5 s each
+            ConfigKey::DefaultExpiryLedgers => ConfigKind::I128 {
+                min: 1,
+                max: 6_312_000,
+            },
+            ConfigKey::TrustlineReserve => ConfigKind::I128 {
+                min: 0,
+                max: MAX_RESERVE_STROOPS,
+            },
+            ConfigKey::KeeperBountyBps => ConfigKind::I128 {
+                min: 0,
+                max: BPS_DENOMINATOR as i128,
+            },
+            ConfigKey::FeeCollector => ConfigKind::Address,
+            ConfigKey::DebugEvents => ConfigKind::Bool,
+        }
+    }
+}
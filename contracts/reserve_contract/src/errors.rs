@@ -1,39 +1,98 @@
 use soroban_sdk::contracterror;
 
+/// Codes sit in [`bridgelet_shared::errors::RESERVE_CONTRACT_ERRORS`], so
+/// they never collide with another Bridgelet contract's.
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum Error {
     /// The supplied amount is zero or negative; only positive stroops are valid.
-    InvalidAmount = 1,
+    InvalidAmount = 101,
 
     /// A read operation was attempted before any base reserve was stored.
     ///
     /// Callers should check [`ReserveContract::has_base_reserve`] or use the
     /// `Option`-returning [`ReserveContract::get_base_reserve`] instead of
     /// any helper that returns a bare value.
-    ReserveNotSet = 2,
+    ReserveNotSet = 102,
 
-    /// The caller is not the admin set during initialization.
+    /// The caller lacks the role the operation requires.
     ///
-    /// Only the admin address provided in [`ReserveContract::initialize`] may
-    /// call state-changing operations such as [`ReserveContract::set_base_reserve`].
-    Unauthorized = 3,
+    /// [`ReserveContract::set_base_reserve`] needs the `Admin` or `Operator`
+    /// role; every other state-changing operation needs `Admin`.
+    Unauthorized = 103,
 
     /// [`ReserveContract::initialize`] was called more than once.
     ///
     /// The contract may only be initialized once; subsequent calls are rejected
     /// to prevent admin takeover.
-    AlreadyInitialized = 4,
+    AlreadyInitialized = 104,
 
     /// A state-changing operation was attempted before [`ReserveContract::initialize`]
     /// was called.
-    NotInitialized = 5,
+    NotInitialized = 105,
 
     /// The supplied amount exceeds the maximum allowed value.
     ///
     /// An upper bound prevents accidental misconfiguration
     /// (e.g. storing a value in XLM instead of stroops).
     /// Current ceiling: 10,000 XLM = 100_000_000_000 stroops.
-    AmountTooLarge = 6,
+    AmountTooLarge = 106,
+
+    /// [`ReserveContract::revoke_role`] would remove the last `Admin`.
+    ///
+    /// At least one admin must remain so roles can still be managed.
+    LastAdmin = 107,
+
+    /// A reserve timelock is configured, so the base reserve can only be
+    /// changed through [`ReserveContract::propose_base_reserve`]; or the
+    /// timelock itself was lowered.
+    TimelockRequired = 108,
+
+    /// [`ReserveContract::execute_pending_reserve`] was called before the
+    /// pending value's activation ledger.
+    TimelockNotElapsed = 109,
+
+    /// There is no pending base reserve to execute or cancel.
+    NoPendingReserve = 110,
+
+    /// A [`crate::ConfigKey`] was set through the setter of another value
+    /// type (e.g. an address key through `set_i128`).
+    WrongConfigType = 111,
+
+    /// [`ReserveContract::pause`] was called while paused, or
+    /// [`ReserveContract::unpause`] while not paused.
+    PauseUnchanged = 112,
+
+    /// Storage is not in the layout this code reads: it predates an
+    /// upgrade and awaits [`ReserveContract::migrate`], or was written by
+    /// newer code.
+    StorageVersionMismatch = 113,
+
+    /// The pagination cursor passed to
+    /// [`ReserveContract::get_reserve_history`] was not issued by this
+    /// contract.
+    InvalidCursor = 114,
+}
+
+impl Error {
+    /// Name of the variant, as `describe_error` reports it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Error::InvalidAmount => "InvalidAmount",
+            Error::ReserveNotSet => "ReserveNotSet",
+            Error::Unauthorized => "Unauthorized",
+            Error::AlreadyInitialized => "AlreadyInitialized",
+            Error::NotInitialized => "NotInitialized",
+            Error::AmountTooLarge => "AmountTooLarge",
+            Error::LastAdmin => "LastAdmin",
+            Error::TimelockRequired => "TimelockRequired",
+            Error::TimelockNotElapsed => "TimelockNotElapsed",
+            Error::NoPendingReserve => "NoPendingReserve",
+            Error::WrongConfigType => "WrongConfigType",
+            Error::PauseUnchanged => "PauseUnchanged",
+            Error::StorageVersionMismatch => "StorageVersionMismatch",
+            Error::InvalidCursor => "InvalidCursor",
+        }
+    }
 }
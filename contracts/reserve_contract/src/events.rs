@@ -1,4 +1,8 @@
-use soroban_sdk::{contracttype, symbol_short, Address, Env};
+use bridgelet_shared::EventTopics;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env};
+
+use crate::config::ConfigKey;
+use crate::storage::Role;
 
 // ─── Event payloads ─────────────────────────────────────────────────────────
 
@@ -10,6 +14,8 @@ pub struct ContractInitialized {
 }
 
 /// Emitted every time [`ReserveContract::set_base_reserve`] stores a new value.
+///
+/// `admin` is the caller, which may hold the `Operator` role instead.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BaseReserveUpdated {
@@ -18,12 +24,134 @@ pub struct BaseReserveUpdated {
     pub admin: Address,
 }
 
+/// Emitted every time [`ReserveContract::set_sponsorship_budget`] stores a new value.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SponsorshipBudgetUpdated {
+    pub old_value: i128,
+    pub new_value: i128,
+    pub admin: Address,
+}
+
+/// Emitted when [`ReserveContract::grant_role`] gives an address a role.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleGranted {
+    pub role: Role,
+    pub account: Address,
+    pub admin: Address,
+}
+
+/// Emitted when [`ReserveContract::revoke_role`] takes a role away.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub account: Address,
+    pub admin: Address,
+}
+
+/// Emitted when [`ReserveContract::pause`] or [`ReserveContract::unpause`]
+/// flips the system-wide pause.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PauseChanged {
+    pub paused: bool,
+    pub admin: Address,
+}
+
+/// Emitted when [`ReserveContract::upgrade`] replaces the contract's code.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractUpgraded {
+    pub new_wasm_hash: BytesN<32>,
+    /// Code version replaced by the upgrade.
+    pub from_version: u32,
+    pub admin: Address,
+}
+
+/// Emitted when the first call after an upgrade migrates storage.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StorageMigrated {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+/// Emitted when [`ReserveContract::propose_base_reserve`] records a new
+/// pending value.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveProposed {
+    pub amount: i128,
+    pub activation_ledger: u32,
+    pub proposed_by: Address,
+}
+
+/// Emitted when [`ReserveContract::execute_pending_reserve`] applies the
+/// pending value.  A `BaseReserveUpdated` event follows it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveExecuted {
+    pub amount: i128,
+    pub proposed_by: Address,
+}
+
+/// Emitted when [`ReserveContract::cancel_pending_reserve`] drops the
+/// pending value.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveCancelled {
+    pub amount: i128,
+    pub cancelled_by: Address,
+}
+
+/// Emitted when [`ReserveContract::set_reserve_timelock`] changes the delay.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveTimelockUpdated {
+    pub old_ledgers: u32,
+    pub new_ledgers: u32,
+    pub admin: Address,
+}
+
+/// Emitted when an integer registry value changes.  `old_value` is `None`
+/// the first time the key is set.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct I128ConfigUpdated {
+    pub key: ConfigKey,
+    pub old_value: Option<i128>,
+    pub new_value: i128,
+    pub admin: Address,
+}
+
+/// Emitted when an address registry value changes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AddressConfigUpdated {
+    pub key: ConfigKey,
+    pub old_value: Option<Address>,
+    pub new_value: Address,
+    pub admin: Address,
+}
+
+/// Emitted when a boolean registry value changes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BoolConfigUpdated {
+    pub key: ConfigKey,
+    pub old_value: Option<bool>,
+    pub new_value: bool,
+    pub admin: Address,
+}
+
 // ─── Emit helpers ───────────────────────────────────────────────────────────
 
 /// Publish the `initialized` event.
 pub fn emit_initialized(env: &Env, admin: Address) {
     let event = ContractInitialized { admin };
-    env.events().publish((symbol_short!("init"),), event);
+    EventTopics::new(symbol_short!("init")).publish_indexed(env, event.admin.clone(), event);
 }
 
 /// Publish the `reserve` event with old and new values for auditability.
@@ -35,5 +163,189 @@ pub fn emit_base_reserve_updated(env: &Env, old_value: i128, new_value: i128, ad
         new_value,
         admin,
     };
-    env.events().publish((symbol_short!("reserve"),), event);
+    EventTopics::new(symbol_short!("reserve")).publish_indexed(env, event.admin.clone(), event);
+}
+
+/// Publish the `proposed` event.
+pub fn emit_reserve_proposed(
+    env: &Env,
+    amount: i128,
+    activation_ledger: u32,
+    proposed_by: Address,
+) {
+    let event = ReserveProposed {
+        amount,
+        activation_ledger,
+        proposed_by,
+    };
+    EventTopics::new(symbol_short!("proposed")).publish_indexed(
+        env,
+        event.proposed_by.clone(),
+        event,
+    );
+}
+
+/// Publish the `executed` event.
+pub fn emit_reserve_executed(env: &Env, amount: i128, proposed_by: Address) {
+    let event = ReserveExecuted {
+        amount,
+        proposed_by,
+    };
+    EventTopics::new(symbol_short!("executed")).publish_indexed(
+        env,
+        event.proposed_by.clone(),
+        event,
+    );
+}
+
+/// Publish the `cancelled` event.
+pub fn emit_reserve_cancelled(env: &Env, amount: i128, cancelled_by: Address) {
+    let event = ReserveCancelled {
+        amount,
+        cancelled_by,
+    };
+    EventTopics::new(symbol_short!("cancelled")).publish_indexed(
+        env,
+        event.cancelled_by.clone(),
+        event,
+    );
+}
+
+/// Publish the `timelock` event.
+pub fn emit_reserve_timelock_updated(
+    env: &Env,
+    old_ledgers: u32,
+    new_ledgers: u32,
+    admin: Address,
+) {
+    let event = ReserveTimelockUpdated {
+        old_ledgers,
+        new_ledgers,
+        admin,
+    };
+    EventTopics::new(symbol_short!("timelock")).publish_indexed(env, event.admin.clone(), event);
+}
+
+/// Publish the `config` event, with the key as indexed topic so indexers
+/// can follow a single parameter.
+pub fn emit_i128_config_updated(
+    env: &Env,
+    key: ConfigKey,
+    old_value: Option<i128>,
+    new_value: i128,
+    admin: Address,
+) {
+    let event = I128ConfigUpdated {
+        key,
+        old_value,
+        new_value,
+        admin,
+    };
+    EventTopics::new(symbol_short!("config")).publish_indexed(env, key, event);
+}
+
+/// Publish the `config` event for an address value.
+pub fn emit_address_config_updated(
+    env: &Env,
+    key: ConfigKey,
+    old_value: Option<Address>,
+    new_value: Address,
+    admin: Address,
+) {
+    let event = AddressConfigUpdated {
+        key,
+        old_value,
+        new_value,
+        admin,
+    };
+    EventTopics::new(symbol_short!("config")).publish_indexed(env, key, event);
+}
+
+/// Publish the `config` event for a boolean value.
+pub fn emit_bool_config_updated(
+    env: &Env,
+    key: ConfigKey,
+    old_value: Option<bool>,
+    new_value: bool,
+    admin: Address,
+) {
+    let event = BoolConfigUpdated {
+        key,
+        old_value,
+        new_value,
+        admin,
+    };
+    EventTopics::new(symbol_short!("config")).publish_indexed(env, key, event);
+}
+
+/// Publish the `paused` or `unpaused` event.
+pub fn emit_pause_changed(env: &Env, paused: bool, admin: Address) {
+    let topic = if paused {
+        symbol_short!("paused")
+    } else {
+        symbol_short!("unpaused")
+    };
+    let event = PauseChanged { paused, admin };
+    EventTopics::new(topic).publish_indexed(env, event.admin.clone(), event);
+}
+
+/// Publish the `upgraded` event.
+pub fn emit_contract_upgraded(
+    env: &Env,
+    new_wasm_hash: BytesN<32>,
+    from_version: u32,
+    admin: Address,
+) {
+    let event = ContractUpgraded {
+        new_wasm_hash,
+        from_version,
+        admin,
+    };
+    EventTopics::new(symbol_short!("upgraded")).publish_indexed(env, event.admin.clone(), event);
+}
+
+/// Publish the `migrated` event.
+pub fn emit_storage_migrated(env: &Env, from_version: u32, to_version: u32) {
+    let event = StorageMigrated {
+        from_version,
+        to_version,
+    };
+    EventTopics::new(symbol_short!("migrated")).publish(env, event);
+}
+
+/// Publish the `granted` event.
+pub fn emit_role_granted(env: &Env, role: Role, account: Address, admin: Address) {
+    let event = RoleGranted {
+        role,
+        account,
+        admin,
+    };
+    EventTopics::new(symbol_short!("granted")).publish_indexed(env, event.account.clone(), event);
+}
+
+/// Publish the `revoked` event.
+pub fn emit_role_revoked(env: &Env, role: Role, account: Address, admin: Address) {
+    let event = RoleRevoked {
+        role,
+        account,
+        admin,
+    };
+    EventTopics::new(symbol_short!("revoked")).publish_indexed(env, event.account.clone(), event);
+}
+
+/// Publish the `sponsor` event with old and new values for auditability.
+///
+/// `old_value` is `0` when no previous budget existed.
+pub fn emit_sponsorship_budget_updated(
+    env: &Env,
+    old_value: i128,
+    new_value: i128,
+    admin: Address,
+) {
+    let event = SponsorshipBudgetUpdated {
+        old_value,
+        new_value,
+        admin,
+    };
+    EventTopics::new(symbol_short!("sponsor")).publish_indexed(env, event.admin.clone(), event);
 }
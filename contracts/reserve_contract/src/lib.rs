@@ -1,23 +1,52 @@
 #![no_std]
 
+mod config;
 mod errors;
 mod events;
 mod storage;
 #[cfg(test)]
 mod test;
 
-use soroban_sdk::{contract, contractimpl, Address, Env};
+use bridgelet_shared::{errors::UNKNOWN_ERROR, page_bounds, units::STROOPS_PER_XLM, Cursor};
+use config::ConfigKind;
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, Symbol, Vec};
+use storage::ReserveState;
 
+pub use bridgelet_shared::ReserveSnapshot;
+pub use config::ConfigKey;
 pub use errors::Error;
-pub use events::{BaseReserveUpdated, ContractInitialized};
-pub use storage::DataKey;
+pub use events::{
+    AddressConfigUpdated, BaseReserveUpdated, BoolConfigUpdated, ContractInitialized,
+    ContractUpgraded, I128ConfigUpdated, PauseChanged, ReserveCancelled, ReserveExecuted,
+    ReserveProposed, ReserveTimelockUpdated, RoleGranted, RoleRevoked, SponsorshipBudgetUpdated,
+    StorageMigrated,
+};
+pub use storage::{DataKey, PendingReserve, ReserveChange, ReserveChangePage, Role};
 
 /// Maximum allowed base reserve: 10 000 XLM = 100_000_000_000 stroops.
 ///
 /// This ceiling exists to catch operator mistakes (e.g. passing a value in
 /// XLM instead of stroops).  It can be raised if the Stellar network ever
 /// increases its base reserve beyond this threshold.
-const MAX_RESERVE_STROOPS: i128 = 100_000_000_000;
+const MAX_RESERVE_STROOPS: i128 = 10_000 * STROOPS_PER_XLM;
+
+/// Version of this contract's code, reported by
+/// [`ReserveContract::get_version`].
+pub const WASM_VERSION: u32 = 1;
+
+/// Version of the storage layout this code reads and writes, recorded at
+/// initialization and by the storage migration after an upgrade.
+///
+/// Bump whenever a key is added, removed or changes type, adding the
+/// matching step to `migrate_if_needed`.
+pub const STORAGE_VERSION: u32 = 1;
+
+/// Contract kind reported by [`ReserveContract::get_version`].
+const CONTRACT_NAME: Symbol = symbol_short!("reserve");
+
+/// Maximum number of history entries returned by one
+/// [`ReserveContract::get_reserve_history`] call.
+const MAX_HISTORY_PAGE: u32 = 50;
 
 /// A focused on-chain contract that stores and exposes the base reserve
 /// configuration for the Bridgelet system.
@@ -36,10 +65,21 @@ const MAX_RESERVE_STROOPS: i128 = 100_000_000_000;
 /// One XLM = 10,000,000 stroops.  Storing the value as an integer number
 /// of stroops avoids floating-point arithmetic inside the contract.
 ///
+/// ## Configuration registry
+///
+/// Other global parameters (fee bps, max payments, default expiry window,
+/// fee collector, ...) live alongside it, keyed by [`ConfigKey`] and read
+/// and written through typed accessors (`get_i128` / `set_i128`, and the
+/// address and bool variants) with per-key validation ranges.  The
+/// base-reserve API is a thin wrapper over the `BaseReserve` key.
+///
 /// ## Access control
 ///
 /// The contract must be initialized once via [`initialize`] which stores
-/// the admin address.  Only that admin may call [`set_base_reserve`].
+/// the admin address and grants it the `Admin` role.  Admins manage roles
+/// with [`grant_role`] / [`revoke_role`] and every configuration value;
+/// the `Operator` role may only call [`set_base_reserve`], so incident
+/// responders do not need the full admin key.
 #[contract]
 pub struct ReserveContract;
 
@@ -54,15 +94,18 @@ impl ReserveContract {
     /// # Errors
     /// * [`Error::AlreadyInitialized`] – called more than once.
     pub fn initialize(env: Env, admin: Address) -> Result<(), Error> {
-        storage::extend_instance_ttl(&env);
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
 
-        if storage::has_admin(&env) {
+        if state.has_admin() {
             return Err(Error::AlreadyInitialized);
         }
 
         admin.require_auth();
 
-        storage::set_admin(&env, &admin);
+        state.set_admin(&admin);
+        state.set_storage_version(STORAGE_VERSION);
+        state.grant_role(Role::Admin, &admin);
         events::emit_initialized(&env, admin);
 
         Ok(())
@@ -70,51 +113,160 @@ impl ReserveContract {
 
     /// Store a new base reserve amount (in stroops).
     ///
-    /// Only the admin set during [`initialize`] may call this function.
-    /// Each call overwrites the previous value and emits a
+    /// `caller` must authorize the call and hold the `Admin` or `Operator`
+    /// role.  Each call overwrites the previous value and emits a
     /// `BaseReserveUpdated` event for off-chain auditability.
     ///
     /// # Arguments
+    /// * `caller` – Address holding the `Admin` or `Operator` role.
     /// * `amount` – Base reserve expressed in stroops.  Must satisfy
     ///              `0 < amount <= MAX_RESERVE_STROOPS` (currently
     ///              100 000 000 000, i.e. 10 000 XLM).
     ///
     /// # Errors
     /// * [`Error::NotInitialized`] – contract has not been initialized.
-    /// * [`Error::Unauthorized`]   – caller is not the admin.
+    /// * [`Error::Unauthorized`]   – caller holds neither role.
+    /// * [`Error::TimelockRequired`] – a reserve timelock is configured;
+    ///   use [`propose_base_reserve`] instead.
     /// * [`Error::InvalidAmount`]  – `amount` is zero or negative.
     /// * [`Error::AmountTooLarge`] – `amount` exceeds the safety ceiling.
     ///
     /// # Example
     /// ```ignore
     /// // 100 XLM = 1_000_000_000 stroops
-    /// client.set_base_reserve(&1_000_000_000i128);
+    /// client.set_base_reserve(&operator, &1_000_000_000i128);
     /// ```
-    pub fn set_base_reserve(env: Env, amount: i128) -> Result<(), Error> {
-        storage::extend_instance_ttl(&env);
+    pub fn set_base_reserve(env: Env, caller: Address, amount: i128) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(&env, &state)?;
 
-        // 1. Contract must be initialized
-        let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+        Self::set_i128_as(&env, &state, ConfigKey::BaseReserve, amount, caller)
+    }
 
-        // 2. Caller must be the admin
-        admin.require_auth();
+    /// Propose a new base reserve amount (in stroops) that takes effect
+    /// after the reserve timelock.
+    ///
+    /// `caller` must authorize the call and hold the `Admin` or `Operator`
+    /// role.  A new proposal replaces any pending one and restarts the
+    /// delay.  The value becomes executable at `current ledger + timelock`.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::Unauthorized`]   – caller holds neither role.
+    /// * [`Error::InvalidAmount`]  – `amount` is zero or negative.
+    /// * [`Error::AmountTooLarge`] – `amount` exceeds the safety ceiling.
+    pub fn propose_base_reserve(env: Env, caller: Address, amount: i128) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(&env, &state)?;
+        Self::require_reserve_role(&state, &caller)?;
+        Self::validate_i128(ConfigKey::BaseReserve, amount)?;
 
-        // 3. Amount validation
-        if amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
-        if amount > MAX_RESERVE_STROOPS {
-            return Err(Error::AmountTooLarge);
+        let activation_ledger = env
+            .ledger()
+            .sequence()
+            .saturating_add(state.get_reserve_timelock());
+        state.set_pending_reserve(&PendingReserve {
+            amount,
+            activation_ledger,
+            proposed_by: caller.clone(),
+        });
+        events::emit_reserve_proposed(&env, amount, activation_ledger, caller);
+
+        Ok(())
+    }
+
+    /// Apply the pending base reserve once its activation ledger is reached.
+    ///
+    /// Callable by anyone: the value was authorized when it was proposed,
+    /// and the delay gave admins time to cancel it.
+    ///
+    /// # Errors
+    /// * [`Error::NoPendingReserve`]   – nothing was proposed.
+    /// * [`Error::TimelockNotElapsed`] – the activation ledger is not reached.
+    pub fn execute_pending_reserve(env: Env) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(&env, &state)?;
+
+        let pending = state.get_pending_reserve().ok_or(Error::NoPendingReserve)?;
+        if env.ledger().sequence() < pending.activation_ledger {
+            return Err(Error::TimelockNotElapsed);
         }
 
-        // ── 4. Persist & emit
-        let old_value = storage::get_base_reserve(&env).unwrap_or(0);
-        storage::set_base_reserve(&env, amount);
-        events::emit_base_reserve_updated(&env, old_value, amount, admin);
+        state.remove_pending_reserve();
+        events::emit_reserve_executed(&env, pending.amount, pending.proposed_by.clone());
+        Self::apply_base_reserve(&env, &state, pending.amount, pending.proposed_by);
+
+        Ok(())
+    }
+
+    /// Drop the pending base reserve.
+    ///
+    /// `caller` must authorize the call and hold the `Admin` or `Operator`
+    /// role.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`]   – contract has not been initialized.
+    /// * [`Error::Unauthorized`]     – caller holds neither role.
+    /// * [`Error::NoPendingReserve`] – nothing was proposed.
+    pub fn cancel_pending_reserve(env: Env, caller: Address) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(&env, &state)?;
+        Self::require_reserve_role(&state, &caller)?;
+
+        let pending = state.get_pending_reserve().ok_or(Error::NoPendingReserve)?;
+        state.remove_pending_reserve();
+        events::emit_reserve_cancelled(&env, pending.amount, caller);
 
         Ok(())
     }
 
+    /// Return the pending base reserve, if one was proposed.
+    pub fn get_pending_reserve(env: Env) -> Option<PendingReserve> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.get_pending_reserve()
+    }
+
+    /// Set the number of ledgers a proposed base reserve waits before it
+    /// can be executed.
+    ///
+    /// `admin` must authorize the call and hold the `Admin` role.  While
+    /// the timelock is non-zero, [`set_base_reserve`] is disabled.  The
+    /// timelock can only be raised: lowering it would let a compromised
+    /// admin key skip the delay.  A pending proposal keeps its activation
+    /// ledger.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`]   – contract has not been initialized.
+    /// * [`Error::Unauthorized`]     – `admin` does not hold the `Admin` role.
+    /// * [`Error::TimelockRequired`] – `ledgers` is below the current timelock.
+    pub fn set_reserve_timelock(env: Env, admin: Address, ledgers: u32) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(&env, &state)?;
+        Self::require_admin_role(&state, &admin)?;
+
+        let old_ledgers = state.get_reserve_timelock();
+        if ledgers < old_ledgers {
+            return Err(Error::TimelockRequired);
+        }
+        state.set_reserve_timelock(ledgers);
+        events::emit_reserve_timelock_updated(&env, old_ledgers, ledgers, admin);
+
+        Ok(())
+    }
+
+    /// Return the reserve timelock in ledgers (`0` when none is configured).
+    pub fn get_reserve_timelock(env: Env) -> u32 {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.get_reserve_timelock()
+    }
+
     /// Return the current base reserve amount (in stroops), if configured.
     ///
     /// # Returns
@@ -124,8 +276,9 @@ impl ReserveContract {
     /// This safe default means consumers **must** handle the unset case
     /// explicitly, preventing silent use of a zero or garbage value.
     pub fn get_base_reserve(env: Env) -> Option<i128> {
-        storage::extend_instance_ttl(&env);
-        storage::get_base_reserve(&env)
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.get_base_reserve()
     }
 
     /// Return the current base reserve amount (in stroops), or an error if
@@ -135,10 +288,14 @@ impl ReserveContract {
     /// before proceeding (e.g. during a sweep flow that reads the reserve).
     ///
     /// # Errors
-    /// Returns [`Error::ReserveNotSet`] when no value has been stored.
+    /// * [`Error::ReserveNotSet`]          – no value has been stored.
+    /// * [`Error::StorageVersionMismatch`] – storage is awaiting
+    ///   [`migrate`] after an upgrade.
     pub fn require_base_reserve(env: Env) -> Result<i128, Error> {
-        storage::extend_instance_ttl(&env);
-        storage::get_base_reserve(&env).ok_or(Error::ReserveNotSet)
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::check_storage_version(&state)?;
+        state.get_base_reserve().ok_or(Error::ReserveNotSet)
     }
 
     /// Returns `true` if a base reserve has been stored, `false` otherwise.
@@ -146,13 +303,589 @@ impl ReserveContract {
     /// Cheaper than calling [`get_base_reserve`] when only the presence of
     /// the key matters.
     pub fn has_base_reserve(env: Env) -> bool {
-        storage::extend_instance_ttl(&env);
-        storage::has_base_reserve(&env)
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.has_base_reserve()
+    }
+
+    /// Store the per-merchant daily fee-bump sponsorship cap (in stroops).
+    ///
+    /// The paymaster reads this value to cap how much it sponsors for a
+    /// single merchant per day.  `0` disables sponsorship.  Like every
+    /// parameter update this bumps the configuration epoch.  `caller`
+    /// must authorize the call and hold the `Admin` role.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::Unauthorized`]   – caller does not hold the `Admin` role.
+    /// * [`Error::InvalidAmount`]  – `amount` is negative.
+    /// * [`Error::AmountTooLarge`] – `amount` exceeds the safety ceiling.
+    pub fn set_sponsorship_budget(env: Env, caller: Address, amount: i128) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(&env, &state)?;
+
+        Self::set_i128_as(&env, &state, ConfigKey::SponsorshipBudget, amount, caller)
+    }
+
+    /// Store the extra reserve (in stroops) each trustline adds to an
+    /// account's minimum balance.
+    ///
+    /// Stellar accounts lock reserve for every subentry, so an account
+    /// paid in several assets needs more than the base reserve.  `0`
+    /// counts trustlines as free.  Like every parameter update this bumps
+    /// the configuration epoch.  `caller` must authorize the call and hold
+    /// the `Admin` role.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::Unauthorized`]   – caller does not hold the `Admin` role.
+    /// * [`Error::InvalidAmount`]  – `amount` is negative.
+    /// * [`Error::AmountTooLarge`] – `amount` exceeds the safety ceiling.
+    pub fn set_trustline_reserve(env: Env, caller: Address, amount: i128) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(&env, &state)?;
+
+        Self::set_i128_as(&env, &state, ConfigKey::TrustlineReserve, amount, caller)
+    }
+
+    /// Return the per-trustline reserve (in stroops), if configured.
+    pub fn get_trustline_reserve(env: Env) -> Option<i128> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.get_i128_config(ConfigKey::TrustlineReserve)
+    }
+
+    /// Return the keeper bounty (in basis points of the reclaimed
+    /// reserve), if configured.  Accounts created without their own
+    /// `keeper_bounty_bps` take it at initialization; it is set through
+    /// [`set_i128`] with [`ConfigKey::KeeperBountyBps`].
+    pub fn get_keeper_bounty_bps(env: Env) -> Option<i128> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.get_i128_config(ConfigKey::KeeperBountyBps)
+    }
+
+    /// Reserve (in stroops) an account holding `trustline_count`
+    /// trustlines must keep: the base reserve plus the per-trustline
+    /// reserve for each of them.
+    ///
+    /// An unset per-trustline reserve counts as zero.
+    ///
+    /// # Errors
+    /// * [`Error::ReserveNotSet`]          – no base reserve has been stored.
+    /// * [`Error::StorageVersionMismatch`] – storage is awaiting
+    ///   [`migrate`] after an upgrade.
+    pub fn compute_required_reserve(env: Env, trustline_count: u32) -> Result<i128, Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::check_storage_version(&state)?;
+
+        let base_reserve = state.get_base_reserve().ok_or(Error::ReserveNotSet)?;
+        let trustline_reserve = state
+            .get_i128_config(ConfigKey::TrustlineReserve)
+            .unwrap_or(0);
+        // Both are capped at 10 000 XLM, so this cannot overflow
+        Ok(base_reserve + trustline_reserve * trustline_count as i128)
+    }
+
+    /// Store an integer registry value.
+    ///
+    /// `caller` must authorize the call and hold the `Admin` role, except
+    /// for `BaseReserve`, which follows [`set_base_reserve`]: the
+    /// `Operator` role suffices and a configured timelock applies.  Every
+    /// successful update bumps the configuration epoch and emits a
+    /// `config` event topic-keyed by `key`.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`]  – contract has not been initialized.
+    /// * [`Error::Unauthorized`]    – caller lacks the required role.
+    /// * [`Error::WrongConfigType`] – `key` does not hold an integer.
+    /// * [`Error::InvalidAmount`]   – `value` is below the key's range.
+    /// * [`Error::AmountTooLarge`]  – `value` is above the key's range.
+    pub fn set_i128(env: Env, caller: Address, key: ConfigKey, value: i128) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(&env, &state)?;
+        Self::set_i128_as(&env, &state, key, value, caller)
+    }
+
+    /// Return an integer registry value, if set.
+    pub fn get_i128(env: Env, key: ConfigKey) -> Option<i128> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.get_i128_config(key)
+    }
+
+    /// Store an address registry value.
+    ///
+    /// `admin` must authorize the call and hold the `Admin` role.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`]  – contract has not been initialized.
+    /// * [`Error::Unauthorized`]    – `admin` does not hold the `Admin` role.
+    /// * [`Error::WrongConfigType`] – `key` does not hold an address.
+    pub fn set_address(
+        env: Env,
+        admin: Address,
+        key: ConfigKey,
+        value: Address,
+    ) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(&env, &state)?;
+        Self::require_admin_role(&state, &admin)?;
+        if !matches!(key.kind(), ConfigKind::Address) {
+            return Err(Error::WrongConfigType);
+        }
+
+        let old_value = state.get_address_config(key);
+        state.set_address_config(key, &value);
+        state.bump_epoch();
+        events::emit_address_config_updated(&env, key, old_value, value, admin);
+
+        Ok(())
+    }
+
+    /// Return an address registry value, if set.
+    pub fn get_address(env: Env, key: ConfigKey) -> Option<Address> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.get_address_config(key)
+    }
+
+    /// Store a boolean registry value.
+    ///
+    /// `admin` must authorize the call and hold the `Admin` role.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`]  – contract has not been initialized.
+    /// * [`Error::Unauthorized`]    – `admin` does not hold the `Admin` role.
+    /// * [`Error::WrongConfigType`] – `key` does not hold a boolean.
+    pub fn set_bool(env: Env, admin: Address, key: ConfigKey, value: bool) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(&env, &state)?;
+        Self::require_admin_role(&state, &admin)?;
+        if !matches!(key.kind(), ConfigKind::Bool) {
+            return Err(Error::WrongConfigType);
+        }
+
+        let old_value = state.get_bool_config(key);
+        state.set_bool_config(key, value);
+        state.bump_epoch();
+        events::emit_bool_config_updated(&env, key, old_value, value, admin);
+
+        Ok(())
+    }
+
+    /// Return a boolean registry value, if set.
+    pub fn get_bool(env: Env, key: ConfigKey) -> Option<bool> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.get_bool_config(key)
+    }
+
+    /// Pause the system: every ephemeral account reading its configuration
+    /// from this contract refuses to record payments or sweep until
+    /// [`unpause`] is called.
+    ///
+    /// A kill switch for incident response, e.g. when the off-chain sweep
+    /// signer is suspected compromised.  `admin` must authorize the call
+    /// and hold the `Admin` role.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::Unauthorized`]   – `admin` does not hold the `Admin` role.
+    /// * [`Error::PauseUnchanged`] – the system is already paused.
+    pub fn pause(env: Env, admin: Address) -> Result<(), Error> {
+        Self::set_paused_as(&env, admin, true)
+    }
+
+    /// Lift a [`pause`].
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::Unauthorized`]   – `admin` does not hold the `Admin` role.
+    /// * [`Error::PauseUnchanged`] – the system is not paused.
+    pub fn unpause(env: Env, admin: Address) -> Result<(), Error> {
+        Self::set_paused_as(&env, admin, false)
+    }
+
+    /// Replace the contract's code with the uploaded wasm `new_wasm_hash`.
+    ///
+    /// `admin` must authorize the call and hold the `Admin` role.  The new
+    /// code takes over from the next call; the first state-changing call
+    /// then migrates storage to the new layout.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::Unauthorized`]   – `admin` does not hold the `Admin` role.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(&env, &state)?;
+        Self::require_admin_role(&state, &admin)?;
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        events::emit_contract_upgraded(&env, new_wasm_hash, WASM_VERSION, admin);
+
+        Ok(())
+    }
+
+    /// Return the version of the running code and the contract kind
+    /// (`reserve`).
+    pub fn get_version(_env: Env) -> (u32, Symbol) {
+        (WASM_VERSION, CONTRACT_NAME)
+    }
+
+    /// Return the name of the error raised as `Error(Contract, #code)`, or
+    /// `Unknown` for a code this contract never raises.
+    pub fn describe_error(env: Env, code: u32) -> Symbol {
+        let name = Error::try_from(soroban_sdk::Error::from_contract_error(code))
+            .map_or(UNKNOWN_ERROR, Error::name);
+        Symbol::new(&env, name)
+    }
+
+    /// Bring storage up to the layout of the running code.
+    ///
+    /// State-changing calls migrate on their own; calling this after an
+    /// [`upgrade`] makes [`require_base_reserve`] usable again straight
+    /// away.  Anyone may call it, and it is a no-op when storage is current.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`]         – contract has not been initialized.
+    /// * [`Error::StorageVersionMismatch`] – storage was written by newer code.
+    pub fn migrate(env: Env) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        if !state.has_admin() {
+            return Err(Error::NotInitialized);
+        }
+        Self::migrate_if_needed(&env, &state)
+    }
+
+    /// Return the storage layout version (`0` before versioning).
+    pub fn get_storage_version(env: Env) -> u32 {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.get_storage_version()
+    }
+
+    /// Returns `true` while the system is paused.
+    pub fn is_paused(env: Env) -> bool {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.is_paused()
+    }
+
+    /// Give `account` a role.
+    ///
+    /// `admin` must authorize the call and hold the `Admin` role.
+    /// Granting a role the account already holds is a no-op.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::Unauthorized`]   – `admin` does not hold the `Admin` role.
+    pub fn grant_role(env: Env, admin: Address, role: Role, account: Address) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(&env, &state)?;
+        Self::require_admin_role(&state, &admin)?;
+
+        if state.grant_role(role, &account) {
+            events::emit_role_granted(&env, role, account, admin);
+        }
+        Ok(())
+    }
+
+    /// Take a role away from `account`.
+    ///
+    /// `admin` must authorize the call and hold the `Admin` role.
+    /// Revoking a role the account does not hold is a no-op.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::Unauthorized`]   – `admin` does not hold the `Admin` role.
+    /// * [`Error::LastAdmin`]      – `account` is the only remaining admin.
+    pub fn revoke_role(
+        env: Env,
+        admin: Address,
+        role: Role,
+        account: Address,
+    ) -> Result<(), Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(&env, &state)?;
+        Self::require_admin_role(&state, &admin)?;
+
+        if role == Role::Admin
+            && state.has_role(Role::Admin, &account)
+            && state.get_admin_count() <= 1
+        {
+            return Err(Error::LastAdmin);
+        }
+        if state.revoke_role(role, &account) {
+            events::emit_role_revoked(&env, role, account, admin);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if `account` holds `role`.
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.has_role(role, &account)
+    }
+
+    /// Return up to `limit` base reserve changes, oldest first, from
+    /// `cursor` on.
+    ///
+    /// Pass `None` to start from the first change and the returned
+    /// `next_cursor` to continue; it is `None` once the history is
+    /// exhausted.  `limit` is capped at 50 entries per call.
+    ///
+    /// # Errors
+    /// * [`Error::InvalidCursor`] – `cursor` was not issued by this contract.
+    pub fn get_reserve_history(
+        env: Env,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<ReserveChangePage, Error> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+
+        let (positions, next_cursor) = page_bounds(
+            &env,
+            cursor,
+            limit.min(MAX_HISTORY_PAGE),
+            0..state.get_history_len(),
+        )
+        .ok_or(Error::InvalidCursor)?;
+        let mut items = Vec::new(&env);
+        for index in positions {
+            if let Some(change) = state.get_history(index) {
+                items.push_back(change);
+            }
+        }
+        Ok(ReserveChangePage { items, next_cursor })
+    }
+
+    /// Return the number of recorded base reserve changes.
+    pub fn get_reserve_history_len(env: Env) -> u32 {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.get_history_len()
+    }
+
+    /// Return the base reserve that was in effect at the end of `ledger`.
+    ///
+    /// `None` if no value had been set by then.  Binary search over the
+    /// history, so the cost grows with the log of its length.
+    pub fn get_reserve_at_ledger(env: Env, ledger: u32) -> Option<i128> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+
+        // Find the last change recorded at or before `ledger`.
+        let (mut low, mut high) = (0u32, state.get_history_len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match state.get_history(mid) {
+                Some(change) if change.ledger <= ledger => low = mid + 1,
+                _ => high = mid,
+            }
+        }
+        if low == 0 {
+            return None;
+        }
+        state.get_history(low - 1).map(|change| change.new_value)
+    }
+
+    /// Return the per-merchant daily sponsorship cap, if configured.
+    pub fn get_sponsorship_budget(env: Env) -> Option<i128> {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.get_sponsorship_budget()
+    }
+
+    /// Return all configured values together with the current epoch.
+    ///
+    /// The epoch starts at `0` and increases by one on every successful
+    /// update, so a cached epoch tells a consumer exactly which
+    /// configuration it was built against.
+    pub fn get_snapshot(env: Env) -> ReserveSnapshot {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        ReserveSnapshot {
+            base_reserve: state.get_base_reserve(),
+            sponsorship_budget: state.get_sponsorship_budget(),
+            trustline_reserve: state.get_i128_config(ConfigKey::TrustlineReserve),
+            keeper_bounty_bps: state.get_i128_config(ConfigKey::KeeperBountyBps),
+            epoch: state.get_epoch(),
+        }
+    }
+
+    /// Returns the current configuration epoch.
+    pub fn get_epoch(env: Env) -> u32 {
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.get_epoch()
     }
 
     /// Returns the admin address, if the contract has been initialized.
     pub fn get_admin(env: Env) -> Option<Address> {
-        storage::extend_instance_ttl(&env);
-        storage::get_admin(&env)
+        let state = ReserveState::new(&env);
+        state.extend_instance_ttl();
+        state.get_admin()
+    }
+}
+
+impl ReserveContract {
+    /// Check that `key` holds an integer and `value` is within its range.
+    fn validate_i128(key: ConfigKey, value: i128) -> Result<(), Error> {
+        let ConfigKind::I128 { min, max } = key.kind() else {
+            return Err(Error::WrongConfigType);
+        };
+        if value < min {
+            return Err(Error::InvalidAmount);
+        }
+        if value > max {
+            return Err(Error::AmountTooLarge);
+        }
+        Ok(())
+    }
+
+    /// Shared body of [`Self::set_i128`] and the dedicated setters.
+    fn set_i128_as(
+        env: &Env,
+        state: &ReserveState,
+        key: ConfigKey,
+        value: i128,
+        caller: Address,
+    ) -> Result<(), Error> {
+        if key == ConfigKey::BaseReserve {
+            // 1. Contract must be initialized, caller an admin or operator
+            Self::require_reserve_role(state, &caller)?;
+
+            // 2. Instant updates are off once a timelock is configured
+            if state.get_reserve_timelock() > 0 {
+                return Err(Error::TimelockRequired);
+            }
+
+            // 3. Amount validation
+            Self::validate_i128(key, value)?;
+
+            // ── 4. Persist & emit
+            Self::apply_base_reserve(env, state, value, caller);
+            return Ok(());
+        }
+
+        Self::require_admin_role(state, &caller)?;
+        Self::validate_i128(key, value)?;
+
+        let old_value = state.get_i128_config(key);
+        state.set_i128_config(key, value);
+        state.bump_epoch();
+        if key == ConfigKey::SponsorshipBudget {
+            events::emit_sponsorship_budget_updated(
+                env,
+                old_value.unwrap_or(0),
+                value,
+                caller.clone(),
+            );
+        }
+        events::emit_i128_config_updated(env, key, old_value, value, caller);
+
+        Ok(())
+    }
+
+    /// Storage migration hook: bring storage written by older code up to
+    /// the current layout.  Runs on the first state-changing call after an
+    /// [`Self::upgrade`], or through [`Self::migrate`]; a no-op once the
+    /// recorded version is current.
+    fn migrate_if_needed(env: &Env, state: &ReserveState) -> Result<(), Error> {
+        if !state.has_admin() {
+            return Ok(());
+        }
+        let from_version = state.get_storage_version();
+        if from_version > STORAGE_VERSION {
+            // Written by newer code; this code cannot read it
+            return Err(Error::StorageVersionMismatch);
+        }
+        if from_version < STORAGE_VERSION {
+            // Layout changes go here, one step per version, oldest first.
+            // Version 1 is the first versioned layout, so older deployments
+            // only need the version recorded.
+            state.set_storage_version(STORAGE_VERSION);
+            events::emit_storage_migrated(env, from_version, STORAGE_VERSION);
+        }
+        Ok(())
+    }
+
+    /// Fail if an initialized contract's storage is not in the layout this
+    /// code reads.
+    fn check_storage_version(state: &ReserveState) -> Result<(), Error> {
+        if state.has_admin() && state.get_storage_version() != STORAGE_VERSION {
+            return Err(Error::StorageVersionMismatch);
+        }
+        Ok(())
+    }
+
+    /// Shared body of [`Self::pause`] and [`Self::unpause`].
+    fn set_paused_as(env: &Env, admin: Address, paused: bool) -> Result<(), Error> {
+        let state = ReserveState::new(env);
+        state.extend_instance_ttl();
+        Self::migrate_if_needed(env, &state)?;
+        Self::require_admin_role(&state, &admin)?;
+        if state.is_paused() == paused {
+            return Err(Error::PauseUnchanged);
+        }
+
+        state.set_paused(paused);
+        events::emit_pause_changed(env, paused, admin);
+
+        Ok(())
+    }
+
+    /// Store a validated base reserve, bump the epoch and emit the update.
+    fn apply_base_reserve(env: &Env, state: &ReserveState, amount: i128, changed_by: Address) {
+        let previous = state.get_base_reserve();
+        let old_value = previous.unwrap_or(0);
+        state.set_base_reserve(amount);
+        state.bump_epoch();
+        state.push_history(&ReserveChange {
+            ledger: env.ledger().sequence(),
+            old_value,
+            new_value: amount,
+            changed_by: changed_by.clone(),
+        });
+        events::emit_base_reserve_updated(env, old_value, amount, changed_by.clone());
+        events::emit_i128_config_updated(env, ConfigKey::BaseReserve, previous, amount, changed_by);
+    }
+
+    /// Require `caller`'s authorization and the `Admin` or `Operator` role.
+    fn require_reserve_role(state: &ReserveState, caller: &Address) -> Result<(), Error> {
+        if !state.has_admin() {
+            return Err(Error::NotInitialized);
+        }
+        caller.require_auth();
+        if !state.has_role(Role::Admin, caller) && !state.has_role(Role::Operator, caller) {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
+    }
+
+    /// Require `admin`'s authorization and the `Admin` role.
+    fn require_admin_role(state: &ReserveState, admin: &Address) -> Result<(), Error> {
+        if !state.has_admin() {
+            return Err(Error::NotInitialized);
+        }
+        admin.require_auth();
+        if !state.has_role(Role::Admin, admin) {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
     }
 }
@@ -1,4 +1,10 @@
-use soroban_sdk::{contracttype, Address, Env};
+use crate::config::ConfigKey;
+use bridgelet_shared::Cursor;
+use soroban_sdk::{
+    contracttype,
+    storage::{Instance, Persistent},
+    Address, Env, IntoVal, TryFromVal, Val, Vec,
+};
 
 /// Storage keys used by the reserve contract.
 ///
@@ -18,80 +24,366 @@ pub enum DataKey {
     /// Set once during [`ReserveContract::initialize`] and immutable
     /// afterwards.
     Admin,
-}
 
-// Base Reserve helpers
+    /// Configuration epoch, bumped on every parameter update.
+    ///
+    /// Lets consumers detect that values read in separate calls came from
+    /// different configurations.
+    Epoch,
 
-/// Persist the base reserve amount (in stroops) to contract storage.
-///
-/// Calling this function a second time silently overwrites the previous
-/// value – callers are responsible for validating the amount before
-/// invoking this function.
-///
-/// # Arguments
-/// * `env`    – Soroban environment handle.
-/// * `amount` – Base reserve in stroops.  Must already be validated as
-///              positive by the caller.
-pub fn set_base_reserve(env: &Env, amount: i128) {
-    env.storage()
-        .instance()
-        .set(&DataKey::BaseReserve, &amount);
-}
+    /// Per-merchant daily fee-bump sponsorship cap, expressed in stroops.
+    ///
+    /// Read by the paymaster so the subsidy policy is governed together
+    /// with the other economic parameters.
+    SponsorshipBudget,
 
-/// Read the base reserve amount from contract storage.
-///
-/// # Returns
-/// * `Some(amount)` – the value previously stored via [`set_base_reserve`].
-/// * `None`         – the base reserve has never been configured.
-pub fn get_base_reserve(env: &Env) -> Option<i128> {
-    env.storage().instance().get(&DataKey::BaseReserve)
-}
+    /// Whether an address holds a role.
+    Role(Role, Address),
 
-/// Returns `true` if a base reserve has been stored, `false` otherwise.
-///
-/// Cheaper than calling [`get_base_reserve`] when only the presence of the
-/// key matters, not its value.
-pub fn has_base_reserve(env: &Env) -> bool {
-    env.storage().instance().has(&DataKey::BaseReserve)
+    /// Number of addresses holding the `Admin` role.
+    AdminCount,
+
+    /// Ledgers a proposed base reserve waits before it can be executed.
+    ReserveTimelock,
+
+    /// Base reserve proposed through the timelock, awaiting execution.
+    PendingReserve,
+
+    /// Number of entries in the base reserve history.
+    HistoryLen,
+
+    /// One base reserve change, by position in the history (persistent).
+    History(u32),
+
+    /// Integer registry value.  `BaseReserve` and `SponsorshipBudget` keep
+    /// their dedicated keys above.
+    ConfigI128(ConfigKey),
+
+    /// Address registry value.
+    ConfigAddress(ConfigKey),
+
+    /// Boolean registry value.
+    ConfigBool(ConfigKey),
+
+    /// System-wide emergency pause, checked by ephemeral accounts before
+    /// recording payments and sweeping.
+    Paused,
+
+    /// Version of the storage layout, see [`crate::STORAGE_VERSION`].
+    StorageVersion,
 }
 
-// Admin helpers
+/// One change of the base reserve, as recorded in the history.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveChange {
+    pub ledger: u32,
+    pub old_value: i128,
+    pub new_value: i128,
+    pub changed_by: Address,
+}
 
-/// Store the admin address.  Intended to be called exactly once during
-/// contract initialization.
-pub fn set_admin(env: &Env, admin: &Address) {
-    env.storage().instance().set(&DataKey::Admin, admin);
+/// One page of the base reserve history plus the cursor for the following
+/// page.
+///
+/// `next_cursor` is `None` once the history is exhausted.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveChangePage {
+    pub items: Vec<ReserveChange>,
+    pub next_cursor: Option<Cursor>,
 }
 
-/// Read the admin address, if set.
-pub fn get_admin(env: &Env) -> Option<Address> {
-    env.storage().instance().get(&DataKey::Admin)
+/// A proposed base reserve and the ledger from which it can be executed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingReserve {
+    pub amount: i128,
+    pub activation_ledger: u32,
+    pub proposed_by: Address,
 }
 
-/// Returns `true` if an admin has been configured (i.e. contract is initialized).
-pub fn has_admin(env: &Env) -> bool {
-    env.storage().instance().has(&DataKey::Admin)
+/// Access level granted to an address.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum Role {
+    /// Manages roles and every configuration value.
+    Admin = 0,
+    /// Updates the base reserve during incident response.
+    Operator = 1,
 }
 
 // TTL management
 
 /// If the remaining TTL drops below this threshold (in ledgers), extend it.
-/// ~100 ledgers ≈ ~8 minutes — gives a comfortable buffer.
-const INSTANCE_TTL_THRESHOLD: u32 = 100;
+/// 259 200 ledgers ≈ 15 days — any call in the second half of the window
+/// renews it, so a contract touched at least every two weeks never lapses.
+const INSTANCE_TTL_THRESHOLD: u32 = 259_200;
 
 /// Extend the instance TTL to this many ledgers.
 /// 518 400 ledgers ≈ 30 days (at ~5 s per ledger).
 const INSTANCE_TTL_EXTEND_TO: u32 = 518_400;
 
-/// Proactively extend the instance storage TTL so the contract (and all
-/// its instance-stored data) does not get archived during periods of
-/// inactivity.
+/// Typed accessor over the reserve contract's storage.
 ///
-/// Should be called from **every** public entry-point (reads included)
-/// to guarantee the data stays alive as long as anyone interacts with
-/// the contract.
-pub fn extend_instance_ttl(env: &Env) {
-    env.storage()
-        .instance()
-        .extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND_TO);
+/// Encapsulates the key layout, the defaults returned for unset keys and
+/// the TTL policy.  Entrypoints go through this type only, so a schema
+/// change (new key, different storage tier) is made in this module and
+/// nowhere else.
+pub struct ReserveState<'a> {
+    env: &'a Env,
+}
+
+impl<'a> ReserveState<'a> {
+    pub fn new(env: &'a Env) -> Self {
+        Self { env }
+    }
+
+    fn store(&self) -> Instance {
+        self.env.storage().instance()
+    }
+
+    fn persistent(&self) -> Persistent {
+        self.env.storage().persistent()
+    }
+
+    fn get<V: TryFromVal<Env, Val>>(&self, key: &DataKey) -> Option<V> {
+        self.store().get(key)
+    }
+
+    fn set<V: IntoVal<Env, Val>>(&self, key: &DataKey, value: &V) {
+        self.store().set(key, value);
+    }
+
+    // Base Reserve helpers
+
+    /// Persist the base reserve amount (in stroops) to contract storage.
+    ///
+    /// Calling this function a second time silently overwrites the previous
+    /// value – callers are responsible for validating the amount before
+    /// invoking this function.
+    ///
+    /// # Arguments
+    /// * `amount` – Base reserve in stroops.  Must already be validated as
+    ///   positive by the caller.
+    pub fn set_base_reserve(&self, amount: i128) {
+        self.set(&DataKey::BaseReserve, &amount);
+    }
+
+    /// Read the base reserve amount from contract storage.
+    ///
+    /// # Returns
+    /// * `Some(amount)` – the value previously stored via [`Self::set_base_reserve`].
+    /// * `None`         – the base reserve has never been configured.
+    pub fn get_base_reserve(&self) -> Option<i128> {
+        self.get(&DataKey::BaseReserve)
+    }
+
+    /// Returns `true` if a base reserve has been stored, `false` otherwise.
+    ///
+    /// Cheaper than calling [`Self::get_base_reserve`] when only the presence
+    /// of the key matters, not its value.
+    pub fn has_base_reserve(&self) -> bool {
+        self.store().has(&DataKey::BaseReserve)
+    }
+
+    // Registry helpers
+
+    /// Storage slot of an integer registry value.  The base reserve and
+    /// sponsorship budget predate the registry and stay where they were.
+    fn i128_key(key: ConfigKey) -> DataKey {
+        match key {
+            ConfigKey::BaseReserve => DataKey::BaseReserve,
+            ConfigKey::SponsorshipBudget => DataKey::SponsorshipBudget,
+            _ => DataKey::ConfigI128(key),
+        }
+    }
+
+    pub fn get_i128_config(&self, key: ConfigKey) -> Option<i128> {
+        self.get(&Self::i128_key(key))
+    }
+
+    pub fn set_i128_config(&self, key: ConfigKey, value: i128) {
+        self.set(&Self::i128_key(key), &value);
+    }
+
+    pub fn get_address_config(&self, key: ConfigKey) -> Option<Address> {
+        self.get(&DataKey::ConfigAddress(key))
+    }
+
+    pub fn set_address_config(&self, key: ConfigKey, value: &Address) {
+        self.set(&DataKey::ConfigAddress(key), value);
+    }
+
+    pub fn get_bool_config(&self, key: ConfigKey) -> Option<bool> {
+        self.get(&DataKey::ConfigBool(key))
+    }
+
+    pub fn set_bool_config(&self, key: ConfigKey, value: bool) {
+        self.set(&DataKey::ConfigBool(key), &value);
+    }
+
+    // Sponsorship budget helpers
+
+    /// Read the sponsorship cap, or `None` if it has never been configured.
+    pub fn get_sponsorship_budget(&self) -> Option<i128> {
+        self.get(&DataKey::SponsorshipBudget)
+    }
+
+    // History helpers
+
+    /// Number of recorded base reserve changes.
+    pub fn get_history_len(&self) -> u32 {
+        self.get(&DataKey::HistoryLen).unwrap_or(0)
+    }
+
+    /// Append a change to the history.  Entries are never rewritten.
+    ///
+    /// Each entry lives in its own persistent slot so the history can grow
+    /// without inflating the instance entry read by every call.
+    pub fn push_history(&self, change: &ReserveChange) {
+        let index = self.get_history_len();
+        let key = DataKey::History(index);
+        self.persistent().set(&key, change);
+        self.persistent()
+            .extend_ttl(&key, INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND_TO);
+        self.set(&DataKey::HistoryLen, &(index + 1));
+    }
+
+    /// Read the history entry at `index`, if it exists.
+    pub fn get_history(&self, index: u32) -> Option<ReserveChange> {
+        self.persistent().get(&DataKey::History(index))
+    }
+
+    // Timelock helpers
+
+    /// Read the reserve timelock in ledgers (`0` when none is configured).
+    pub fn get_reserve_timelock(&self) -> u32 {
+        self.get(&DataKey::ReserveTimelock).unwrap_or(0)
+    }
+
+    /// Persist the reserve timelock in ledgers.
+    pub fn set_reserve_timelock(&self, ledgers: u32) {
+        self.set(&DataKey::ReserveTimelock, &ledgers);
+    }
+
+    /// Read the pending base reserve, if one was proposed.
+    pub fn get_pending_reserve(&self) -> Option<PendingReserve> {
+        self.get(&DataKey::PendingReserve)
+    }
+
+    /// Persist a proposed base reserve, replacing any earlier proposal.
+    pub fn set_pending_reserve(&self, pending: &PendingReserve) {
+        self.set(&DataKey::PendingReserve, pending);
+    }
+
+    /// Forget the pending base reserve.
+    pub fn remove_pending_reserve(&self) {
+        self.store().remove(&DataKey::PendingReserve);
+    }
+
+    // Version helpers
+
+    /// Storage layout version (`0` for deployments that predate
+    /// versioning).
+    pub fn get_storage_version(&self) -> u32 {
+        self.get(&DataKey::StorageVersion).unwrap_or(0)
+    }
+
+    pub fn set_storage_version(&self, version: u32) {
+        self.set(&DataKey::StorageVersion, &version);
+    }
+
+    // Pause helpers
+
+    /// Whether the system is paused (`false` when never set).
+    pub fn is_paused(&self) -> bool {
+        self.get(&DataKey::Paused).unwrap_or(false)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.set(&DataKey::Paused, &paused);
+    }
+
+    // Epoch helpers
+
+    /// Read the current configuration epoch (`0` before any update).
+    pub fn get_epoch(&self) -> u32 {
+        self.get(&DataKey::Epoch).unwrap_or(0)
+    }
+
+    /// Advance the configuration epoch and return the new value.
+    pub fn bump_epoch(&self) -> u32 {
+        let epoch = self.get_epoch() + 1;
+        self.set(&DataKey::Epoch, &epoch);
+        epoch
+    }
+
+    // Admin helpers
+
+    /// Store the admin address.  Intended to be called exactly once during
+    /// contract initialization.
+    pub fn set_admin(&self, admin: &Address) {
+        self.set(&DataKey::Admin, admin);
+    }
+
+    /// Read the admin address, if set.
+    pub fn get_admin(&self) -> Option<Address> {
+        self.get(&DataKey::Admin)
+    }
+
+    /// Returns `true` if `account` holds `role`.
+    pub fn has_role(&self, role: Role, account: &Address) -> bool {
+        self.get(&DataKey::Role(role, account.clone()))
+            .unwrap_or(false)
+    }
+
+    /// Grant `role` to `account`, keeping the admin count in step.
+    /// Returns `false` if the account already held it.
+    pub fn grant_role(&self, role: Role, account: &Address) -> bool {
+        if self.has_role(role, account) {
+            return false;
+        }
+        self.set(&DataKey::Role(role, account.clone()), &true);
+        if role == Role::Admin {
+            self.set(&DataKey::AdminCount, &(self.get_admin_count() + 1));
+        }
+        true
+    }
+
+    /// Revoke `role` from `account`, keeping the admin count in step.
+    /// Returns `false` if the account did not hold it.
+    pub fn revoke_role(&self, role: Role, account: &Address) -> bool {
+        if !self.has_role(role, account) {
+            return false;
+        }
+        self.store().remove(&DataKey::Role(role, account.clone()));
+        if role == Role::Admin {
+            self.set(&DataKey::AdminCount, &(self.get_admin_count() - 1));
+        }
+        true
+    }
+
+    /// Number of addresses holding the `Admin` role.
+    pub fn get_admin_count(&self) -> u32 {
+        self.get(&DataKey::AdminCount).unwrap_or(0)
+    }
+
+    /// Returns `true` if an admin has been configured (i.e. contract is initialized).
+    pub fn has_admin(&self) -> bool {
+        self.store().has(&DataKey::Admin)
+    }
+
+    /// Proactively extend the instance storage TTL so the contract (and all
+    /// its instance-stored data) does not get archived during periods of
+    /// inactivity.
+    ///
+    /// Should be called from **every** public entry-point (reads included)
+    /// to guarantee the data stays alive as long as anyone interacts with
+    /// the contract.
+    pub fn extend_instance_ttl(&self) {
+        self.store()
+            .extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND_TO);
+    }
 }
@@ -2,10 +2,17 @@
 mod test {
     extern crate std;
 
-    use crate::{ReserveContract, ReserveContractClient};
+    use crate::{
+        AddressConfigUpdated, ConfigKey, DataKey, Error, PauseChanged, PendingReserve,
+        ReserveChange, ReserveContract, ReserveContractClient, Role, StorageMigrated,
+        STORAGE_VERSION,
+    };
+    use bridgelet_shared::errors::{RESERVE_CONTRACT_ERRORS, UNKNOWN_ERROR};
+    use bridgelet_shared::{encode_cursor, EventTopics};
     use soroban_sdk::{
-        testutils::{storage::Instance as _, Address as _},
-        Address, Env,
+        symbol_short,
+        testutils::{storage::Instance as _, Address as _, Events as _},
+        vec, Address, BytesN, Env, Symbol, TryFromVal,
     };
 
     use soroban_sdk::testutils::Ledger;
@@ -15,7 +22,7 @@ mod test {
     /// Build a test `Env` with ledger settings that let TTL extension reach
     /// `INSTANCE_TTL_EXTEND_TO` (518 400 ledgers) without being capped:
     ///
-    /// * `min_persistent_entry_ttl = 50` — below `INSTANCE_TTL_THRESHOLD` (100)
+    /// * `min_persistent_entry_ttl = 50` — below `INSTANCE_TTL_THRESHOLD`
     ///   so a freshly deployed instance always has TTL < threshold and
     ///   `extend_ttl` fires on the very first call.
     /// * `max_entry_ttl = 600_000` — well above 518 400 so the ledger cap
@@ -77,9 +84,9 @@ mod test {
         assert_ttl_extended(&env, &contract_id);
     }
 
-    /// Double initialization must fail with error #4 (AlreadyInitialized).
+    /// Double initialization must fail with error #104 (AlreadyInitialized).
     #[test]
-    #[should_panic(expected = "Error(Contract, #4)")]
+    #[should_panic(expected = "Error(Contract, #104)")]
     fn test_initialize_twice_panics() {
         let (env, client, _admin, _) = setup();
         let another = Address::generate(&env);
@@ -88,13 +95,13 @@ mod test {
 
     //  Not-initialized guard
 
-    /// set_base_reserve must fail with error #5 (NotInitialized) on a fresh
+    /// set_base_reserve must fail with error #105 (NotInitialized) on a fresh
     /// contract that was never initialized.
     #[test]
-    #[should_panic(expected = "Error(Contract, #5)")]
+    #[should_panic(expected = "Error(Contract, #105)")]
     fn test_set_base_reserve_before_initialize_panics() {
-        let (_env, client, _) = setup_uninitialized();
-        client.set_base_reserve(&1_000_000_000i128);
+        let (env, client, _) = setup_uninitialized();
+        client.set_base_reserve(&Address::generate(&env), &1_000_000_000i128);
     }
 
     //  Safe-default handling (reads don't require init)
@@ -115,9 +122,9 @@ mod test {
         assert_ttl_extended(&env, &contract_id);
     }
 
-    /// require_base_reserve() must panic (contract error #2) when not set.
+    /// require_base_reserve() must panic (contract error #102) when not set.
     #[test]
-    #[should_panic(expected = "Error(Contract, #2)")]
+    #[should_panic(expected = "Error(Contract, #102)")]
     fn test_require_base_reserve_panics_when_not_set() {
         let (_env, client, _) = setup_uninitialized();
         client.require_base_reserve();
@@ -128,11 +135,11 @@ mod test {
     /// A stored value must be returned verbatim by all three read functions.
     #[test]
     fn test_set_and_get_base_reserve() {
-        let (env, client, _admin, contract_id) = setup();
+        let (env, client, admin, contract_id) = setup();
 
         // 100 XLM expressed in stroops (1 XLM = 10_000_000 stroops)
         let reserve = 1_000_000_000i128;
-        client.set_base_reserve(&reserve);
+        client.set_base_reserve(&admin, &reserve);
 
         assert_eq!(client.get_base_reserve(), Some(reserve));
         assert!(client.has_base_reserve());
@@ -143,8 +150,8 @@ mod test {
     /// The minimum meaningful value (1 stroop) must be accepted.
     #[test]
     fn test_set_base_reserve_minimum_valid_value() {
-        let (env, client, _admin, contract_id) = setup();
-        client.set_base_reserve(&1i128);
+        let (env, client, admin, contract_id) = setup();
+        client.set_base_reserve(&admin, &1i128);
         assert_eq!(client.get_base_reserve(), Some(1i128));
         assert_ttl_extended(&env, &contract_id);
     }
@@ -154,12 +161,12 @@ mod test {
     /// set_base_reserve() must overwrite the previous value.
     #[test]
     fn test_set_base_reserve_overwrites_previous_value() {
-        let (env, client, _admin, contract_id) = setup();
+        let (env, client, admin, contract_id) = setup();
 
-        client.set_base_reserve(&1_000_000_000i128);
+        client.set_base_reserve(&admin, &1_000_000_000i128);
         assert_eq!(client.get_base_reserve(), Some(1_000_000_000i128));
 
-        client.set_base_reserve(&2_000_000_000i128);
+        client.set_base_reserve(&admin, &2_000_000_000i128);
         assert_eq!(client.get_base_reserve(), Some(2_000_000_000i128));
 
         assert!(client.has_base_reserve());
@@ -168,28 +175,28 @@ mod test {
 
     //  Input validation
 
-    /// Zero is not a valid reserve; the contract must reject it with error #1.
+    /// Zero is not a valid reserve; the contract must reject it with error #101.
     #[test]
-    #[should_panic(expected = "Error(Contract, #1)")]
+    #[should_panic(expected = "Error(Contract, #101)")]
     fn test_set_base_reserve_zero_is_rejected() {
-        let (_env, client, _admin, _) = setup();
-        client.set_base_reserve(&0i128);
+        let (_env, client, admin, _) = setup();
+        client.set_base_reserve(&admin, &0i128);
     }
 
-    /// Negative amounts are nonsensical and must be rejected with error #1.
+    /// Negative amounts are nonsensical and must be rejected with error #101.
     #[test]
-    #[should_panic(expected = "Error(Contract, #1)")]
+    #[should_panic(expected = "Error(Contract, #101)")]
     fn test_set_base_reserve_negative_is_rejected() {
-        let (_env, client, _admin, _) = setup();
-        client.set_base_reserve(&-1i128);
+        let (_env, client, admin, _) = setup();
+        client.set_base_reserve(&admin, &-1i128);
     }
 
     /// A large negative amount (i128::MIN) must also be rejected.
     #[test]
-    #[should_panic(expected = "Error(Contract, #1)")]
+    #[should_panic(expected = "Error(Contract, #101)")]
     fn test_set_base_reserve_min_i128_is_rejected() {
-        let (_env, client, _admin, _) = setup();
-        client.set_base_reserve(&i128::MIN);
+        let (_env, client, admin, _) = setup();
+        client.set_base_reserve(&admin, &i128::MIN);
     }
 
     //  Range validation (upper bound)
@@ -198,27 +205,27 @@ mod test {
     /// must be accepted.
     #[test]
     fn test_set_base_reserve_at_max_is_accepted() {
-        let (env, client, _admin, contract_id) = setup();
+        let (env, client, admin, contract_id) = setup();
         let max = 100_000_000_000i128;
-        client.set_base_reserve(&max);
+        client.set_base_reserve(&admin, &max);
         assert_eq!(client.get_base_reserve(), Some(max));
         assert_ttl_extended(&env, &contract_id);
     }
 
-    /// One stroop above the ceiling must be rejected with error #6.
+    /// One stroop above the ceiling must be rejected with error #106.
     #[test]
-    #[should_panic(expected = "Error(Contract, #6)")]
+    #[should_panic(expected = "Error(Contract, #106)")]
     fn test_set_base_reserve_above_max_is_rejected() {
-        let (_env, client, _admin, _) = setup();
-        client.set_base_reserve(&100_000_000_001i128);
+        let (_env, client, admin, _) = setup();
+        client.set_base_reserve(&admin, &100_000_000_001i128);
     }
 
-    /// An absurdly large value must be rejected with error #6.
+    /// An absurdly large value must be rejected with error #106.
     #[test]
-    #[should_panic(expected = "Error(Contract, #6)")]
+    #[should_panic(expected = "Error(Contract, #106)")]
     fn test_set_base_reserve_huge_value_is_rejected() {
-        let (_env, client, _admin, _) = setup();
-        client.set_base_reserve(&i128::MAX);
+        let (_env, client, admin, _) = setup();
+        client.set_base_reserve(&admin, &i128::MAX);
     }
 
     //  State isolation
@@ -240,7 +247,7 @@ mod test {
         client_a.initialize(&admin_a);
         client_b.initialize(&admin_b);
 
-        client_a.set_base_reserve(&500_000_000i128);
+        client_a.set_base_reserve(&admin_a, &500_000_000i128);
 
         // Contract B must still be unset.
         assert_eq!(client_b.get_base_reserve(), None);
@@ -272,6 +279,618 @@ mod test {
         assert_ttl_extended(&env, &contract_id);
     }
 
+    //  Snapshot / epoch
+
+    /// A fresh contract reports no reserve at epoch 0.
+    #[test]
+    fn test_snapshot_before_any_update() {
+        let (env, client, _admin, contract_id) = setup();
+        let snapshot = client.get_snapshot();
+        assert_eq!(snapshot.base_reserve, None);
+        assert_eq!(snapshot.epoch, 0);
+        assert_eq!(client.get_epoch(), 0);
+        assert_ttl_extended(&env, &contract_id);
+    }
+
+    /// Each successful update bumps the epoch and the snapshot reflects
+    /// the value written in that epoch.
+    #[test]
+    fn test_snapshot_tracks_updates() {
+        let (_env, client, admin, _) = setup();
+
+        client.set_base_reserve(&admin, &1_000_000_000i128);
+        let first = client.get_snapshot();
+        assert_eq!(first.base_reserve, Some(1_000_000_000i128));
+        assert_eq!(first.epoch, 1);
+
+        client.set_base_reserve(&admin, &2_000_000_000i128);
+        let second = client.get_snapshot();
+        assert_eq!(second.base_reserve, Some(2_000_000_000i128));
+        assert_eq!(second.epoch, 2);
+        assert_eq!(client.get_epoch(), 2);
+    }
+
+    /// A rejected update must not advance the epoch.
+    #[test]
+    fn test_rejected_update_keeps_epoch() {
+        let (_env, client, admin, _) = setup();
+        client.set_base_reserve(&admin, &1_000_000_000i128);
+
+        let result = client.try_set_base_reserve(&admin, &0i128);
+        assert!(result.is_err());
+        assert_eq!(client.get_epoch(), 1);
+    }
+
+    //  Sponsorship budget
+
+    /// The sponsorship budget is part of the snapshot and shares the epoch.
+    #[test]
+    fn test_sponsorship_budget_in_snapshot() {
+        let (_env, client, admin, _) = setup();
+        assert_eq!(client.get_sponsorship_budget(), None);
+
+        client.set_base_reserve(&admin, &1_000_000_000i128);
+        client.set_sponsorship_budget(&admin, &50_000_000i128);
+
+        let snapshot = client.get_snapshot();
+        assert_eq!(snapshot.base_reserve, Some(1_000_000_000i128));
+        assert_eq!(snapshot.sponsorship_budget, Some(50_000_000i128));
+        assert_eq!(snapshot.epoch, 2);
+        assert_eq!(client.get_sponsorship_budget(), Some(50_000_000i128));
+    }
+
+    /// Zero disables sponsorship; negative budgets are rejected (error #101).
+    #[test]
+    fn test_sponsorship_budget_validation() {
+        let (_env, client, admin, _) = setup();
+
+        client.set_sponsorship_budget(&admin, &0i128);
+        assert_eq!(client.get_sponsorship_budget(), Some(0));
+
+        let result = client.try_set_sponsorship_budget(&admin, &-1i128);
+        assert_eq!(result, Err(Ok(crate::Error::InvalidAmount)));
+        assert_eq!(client.get_epoch(), 1);
+    }
+
+    //  Trustline reserve
+
+    /// The required reserve is the base reserve plus one trustline reserve
+    /// per trustline; an unset trustline reserve counts as zero.
+    #[test]
+    fn test_compute_required_reserve() {
+        let (_env, client, admin, _) = setup();
+        assert_eq!(
+            client.try_compute_required_reserve(&0),
+            Err(Ok(crate::Error::ReserveNotSet))
+        );
+
+        client.set_base_reserve(&admin, &10_000_000i128);
+        assert_eq!(client.get_trustline_reserve(), None);
+        assert_eq!(client.compute_required_reserve(&3), 10_000_000);
+
+        client.set_trustline_reserve(&admin, &5_000_000i128);
+        assert_eq!(client.get_trustline_reserve(), Some(5_000_000));
+        assert_eq!(client.get_snapshot().trustline_reserve, Some(5_000_000));
+        assert_eq!(
+            client.get_i128(&ConfigKey::TrustlineReserve),
+            Some(5_000_000)
+        );
+        assert_eq!(client.compute_required_reserve(&0), 10_000_000);
+        assert_eq!(client.compute_required_reserve(&3), 25_000_000);
+
+        assert_eq!(
+            client.try_set_trustline_reserve(&admin, &-1i128),
+            Err(Ok(crate::Error::InvalidAmount))
+        );
+        assert_eq!(client.get_epoch(), 2);
+    }
+
+    /// The trustline reserve and sponsorship budget setters take an
+    /// explicit caller and require the `Admin` role, like `set_i128`.
+    #[test]
+    fn test_dedicated_setters_require_admin_role() {
+        let (env, client, admin, _) = setup();
+        let operator = Address::generate(&env);
+        client.grant_role(&admin, &Role::Operator, &operator);
+
+        assert_eq!(
+            client.try_set_trustline_reserve(&operator, &5_000_000i128),
+            Err(Ok(Error::Unauthorized))
+        );
+        assert_eq!(
+            client.try_set_sponsorship_budget(&operator, &50_000_000i128),
+            Err(Ok(Error::Unauthorized))
+        );
+        assert_eq!(client.get_trustline_reserve(), None);
+        assert_eq!(client.get_sponsorship_budget(), None);
+        assert_eq!(client.get_epoch(), 0);
+    }
+
+    #[test]
+    fn test_keeper_bounty_bps_is_bounded() {
+        let (_env, client, admin, _) = setup();
+        assert_eq!(client.get_keeper_bounty_bps(), None);
+
+        client.set_i128(&admin, &ConfigKey::KeeperBountyBps, &500);
+        assert_eq!(client.get_keeper_bounty_bps(), Some(500));
+        assert_eq!(client.get_snapshot().keeper_bounty_bps, Some(500));
+        assert_eq!(
+            client.try_set_i128(&admin, &ConfigKey::KeeperBountyBps, &10_001),
+            Err(Ok(crate::Error::AmountTooLarge))
+        );
+    }
+
+    //  Roles
+
+    /// initialize() makes the admin the first holder of the Admin role.
+    #[test]
+    fn test_initialize_grants_admin_role() {
+        let (env, client, admin, _) = setup();
+        assert!(client.has_role(&Role::Admin, &admin));
+        assert!(!client.has_role(&Role::Operator, &admin));
+        assert!(!client.has_role(&Role::Admin, &Address::generate(&env)));
+    }
+
+    /// An operator may update the base reserve but not manage roles or
+    /// the sponsorship budget.
+    #[test]
+    fn test_operator_can_only_set_base_reserve() {
+        let (env, client, admin, _) = setup();
+        let operator = Address::generate(&env);
+        let outsider = Address::generate(&env);
+
+        assert_eq!(
+            client.try_set_base_reserve(&operator, &1_000_000_000i128),
+            Err(Ok(Error::Unauthorized))
+        );
+
+        client.grant_role(&admin, &Role::Operator, &operator);
+        assert!(client.has_role(&Role::Operator, &operator));
+        client.set_base_reserve(&operator, &1_000_000_000i128);
+        assert_eq!(client.get_base_reserve(), Some(1_000_000_000i128));
+
+        assert_eq!(
+            client.try_grant_role(&operator, &Role::Operator, &outsider),
+            Err(Ok(Error::Unauthorized))
+        );
+        assert_eq!(
+            client.try_revoke_role(&operator, &Role::Operator, &operator),
+            Err(Ok(Error::Unauthorized))
+        );
+
+        client.revoke_role(&admin, &Role::Operator, &operator);
+        assert!(!client.has_role(&Role::Operator, &operator));
+        assert_eq!(
+            client.try_set_base_reserve(&operator, &2_000_000_000i128),
+            Err(Ok(Error::Unauthorized))
+        );
+    }
+
+    /// The last admin cannot be revoked, so roles stay manageable.
+    #[test]
+    fn test_last_admin_cannot_be_revoked() {
+        let (env, client, admin, _) = setup();
+        assert_eq!(
+            client.try_revoke_role(&admin, &Role::Admin, &admin),
+            Err(Ok(Error::LastAdmin))
+        );
+
+        let second = Address::generate(&env);
+        client.grant_role(&admin, &Role::Admin, &second);
+        client.revoke_role(&second, &Role::Admin, &admin);
+        assert!(!client.has_role(&Role::Admin, &admin));
+        assert_eq!(
+            client.try_set_base_reserve(&admin, &1_000_000_000i128),
+            Err(Ok(Error::Unauthorized))
+        );
+        assert_eq!(
+            client.try_revoke_role(&second, &Role::Admin, &second),
+            Err(Ok(Error::LastAdmin))
+        );
+    }
+
+    //  Timelocked updates
+
+    /// With a timelock, changes go through propose / execute after the delay.
+    #[test]
+    fn test_timelocked_reserve_update() {
+        let (env, client, admin, _) = setup();
+        client.set_base_reserve(&admin, &1_000_000_000i128);
+        client.set_reserve_timelock(&admin, &100);
+        assert_eq!(client.get_reserve_timelock(), 100);
+
+        assert_eq!(
+            client.try_set_base_reserve(&admin, &2_000_000_000i128),
+            Err(Ok(Error::TimelockRequired))
+        );
+        assert_eq!(
+            client.try_execute_pending_reserve(),
+            Err(Ok(Error::NoPendingReserve))
+        );
+
+        client.propose_base_reserve(&admin, &2_000_000_000i128);
+        let activation_ledger = env.ledger().sequence() + 100;
+        assert_eq!(
+            client.get_pending_reserve(),
+            Some(PendingReserve {
+                amount: 2_000_000_000i128,
+                activation_ledger,
+                proposed_by: admin.clone(),
+            })
+        );
+
+        env.ledger().with_mut(|li| li.sequence_number += 99);
+        assert_eq!(
+            client.try_execute_pending_reserve(),
+            Err(Ok(Error::TimelockNotElapsed))
+        );
+        assert_eq!(client.get_base_reserve(), Some(1_000_000_000i128));
+
+        env.ledger().with_mut(|li| li.sequence_number += 1);
+        client.execute_pending_reserve();
+        assert_eq!(client.get_base_reserve(), Some(2_000_000_000i128));
+        assert_eq!(client.get_pending_reserve(), None);
+        assert_eq!(client.get_epoch(), 2);
+    }
+
+    /// A pending value can be cancelled, and proposals are validated and
+    /// gated on the reserve roles.
+    #[test]
+    fn test_cancel_pending_reserve() {
+        let (env, client, admin, _) = setup();
+        client.set_reserve_timelock(&admin, &100);
+
+        assert_eq!(
+            client.try_propose_base_reserve(&Address::generate(&env), &1_000_000_000i128),
+            Err(Ok(Error::Unauthorized))
+        );
+        assert_eq!(
+            client.try_propose_base_reserve(&admin, &0i128),
+            Err(Ok(Error::InvalidAmount))
+        );
+
+        let operator = Address::generate(&env);
+        client.grant_role(&admin, &Role::Operator, &operator);
+        client.propose_base_reserve(&operator, &1_000_000_000i128);
+        client.cancel_pending_reserve(&admin);
+        assert_eq!(client.get_pending_reserve(), None);
+        assert_eq!(
+            client.try_cancel_pending_reserve(&admin),
+            Err(Ok(Error::NoPendingReserve))
+        );
+
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+        assert_eq!(
+            client.try_execute_pending_reserve(),
+            Err(Ok(Error::NoPendingReserve))
+        );
+        assert_eq!(client.get_base_reserve(), None);
+    }
+
+    /// The timelock can be raised but never lowered.
+    #[test]
+    fn test_reserve_timelock_only_increases() {
+        let (env, client, admin, _) = setup();
+        client.set_reserve_timelock(&admin, &100);
+        assert_eq!(
+            client.try_set_reserve_timelock(&admin, &0),
+            Err(Ok(Error::TimelockRequired))
+        );
+        client.set_reserve_timelock(&admin, &200);
+        assert_eq!(client.get_reserve_timelock(), 200);
+
+        let operator = Address::generate(&env);
+        client.grant_role(&admin, &Role::Operator, &operator);
+        assert_eq!(
+            client.try_set_reserve_timelock(&operator, &300),
+            Err(Ok(Error::Unauthorized))
+        );
+    }
+
+    //  History
+
+    /// Every change is appended to the history with its ledger and caller.
+    #[test]
+    fn test_reserve_history_records_changes() {
+        let (env, client, admin, _) = setup();
+        assert_eq!(client.get_reserve_history(&None, &10).items.len(), 0);
+
+        let start = env.ledger().sequence();
+        client.set_base_reserve(&admin, &1_000_000_000i128);
+        env.ledger().with_mut(|li| li.sequence_number += 10);
+        client.set_base_reserve(&admin, &2_000_000_000i128);
+        env.ledger().with_mut(|li| li.sequence_number += 10);
+        client.set_base_reserve(&admin, &3_000_000_000i128);
+
+        assert_eq!(client.get_reserve_history_len(), 3);
+        let first = client.get_reserve_history(&None, &1);
+        assert_eq!(first.items.len(), 1);
+        assert_eq!(first.next_cursor, Some(encode_cursor(&env, 1)));
+        let history = client.get_reserve_history(&first.next_cursor, &10);
+        assert_eq!(history.next_cursor, None);
+        assert_eq!(
+            history.items,
+            vec![
+                &env,
+                ReserveChange {
+                    ledger: start + 10,
+                    old_value: 1_000_000_000i128,
+                    new_value: 2_000_000_000i128,
+                    changed_by: admin.clone(),
+                },
+                ReserveChange {
+                    ledger: start + 20,
+                    old_value: 2_000_000_000i128,
+                    new_value: 3_000_000_000i128,
+                    changed_by: admin.clone(),
+                },
+            ]
+        );
+        let past_end = Some(encode_cursor(&env, 3));
+        assert_eq!(client.get_reserve_history(&past_end, &10).items.len(), 0);
+        assert_eq!(
+            client.try_get_reserve_history(&Some(BytesN::from_array(&env, &[0xff; 8])), &10),
+            Err(Ok(Error::InvalidCursor))
+        );
+    }
+
+    /// get_reserve_at_ledger returns the value in effect at a past ledger.
+    #[test]
+    fn test_reserve_at_ledger() {
+        let (env, client, admin, _) = setup();
+        let start = env.ledger().sequence();
+        assert_eq!(client.get_reserve_at_ledger(&start), None);
+
+        client.set_base_reserve(&admin, &1_000_000_000i128);
+        env.ledger().with_mut(|li| li.sequence_number += 10);
+        client.set_base_reserve(&admin, &2_000_000_000i128);
+        // A second change in the same ledger wins
+        client.set_base_reserve(&admin, &2_500_000_000i128);
+
+        assert_eq!(client.get_reserve_at_ledger(&(start - 1)), None);
+        assert_eq!(
+            client.get_reserve_at_ledger(&start),
+            Some(1_000_000_000i128)
+        );
+        assert_eq!(
+            client.get_reserve_at_ledger(&(start + 9)),
+            Some(1_000_000_000i128)
+        );
+        assert_eq!(
+            client.get_reserve_at_ledger(&(start + 10)),
+            Some(2_500_000_000i128)
+        );
+        assert_eq!(
+            client.get_reserve_at_ledger(&u32::MAX),
+            Some(2_500_000_000i128)
+        );
+    }
+
+    //  Configuration registry
+
+    /// Integer keys are range-checked, admin-only and bump the epoch.
+    #[test]
+    fn test_registry_i128_keys() {
+        let (env, client, admin, _) = setup();
+        assert_eq!(client.get_i128(&ConfigKey::FeeBps), None);
+
+        client.set_i128(&admin, &ConfigKey::FeeBps, &250);
+        assert_eq!(client.get_i128(&ConfigKey::FeeBps), Some(250));
+        assert_eq!(client.get_epoch(), 1);
+
+        assert_eq!(
+            client.try_set_i128(&admin, &ConfigKey::FeeBps, &10_001),
+            Err(Ok(Error::AmountTooLarge))
+        );
+        assert_eq!(
+            client.try_set_i128(&admin, &ConfigKey::MaxPayments, &0),
+            Err(Ok(Error::InvalidAmount))
+        );
+        assert_eq!(
+            client.try_set_i128(&admin, &ConfigKey::FeeCollector, &1),
+            Err(Ok(Error::WrongConfigType))
+        );
+
+        let operator = Address::generate(&env);
+        client.grant_role(&admin, &Role::Operator, &operator);
+        assert_eq!(
+            client.try_set_i128(&operator, &ConfigKey::DefaultExpiryLedgers, &17_280),
+            Err(Ok(Error::Unauthorized))
+        );
+        assert_eq!(client.get_epoch(), 1);
+    }
+
+    /// The dedicated base-reserve and sponsorship API are views of the
+    /// registry keys.
+    #[test]
+    fn test_registry_wraps_base_reserve() {
+        let (env, client, admin, _) = setup();
+        let operator = Address::generate(&env);
+        client.grant_role(&admin, &Role::Operator, &operator);
+
+        client.set_i128(&operator, &ConfigKey::BaseReserve, &1_000_000_000i128);
+        assert_eq!(client.get_base_reserve(), Some(1_000_000_000i128));
+        assert_eq!(client.get_reserve_history_len(), 1);
+
+        client.set_base_reserve(&admin, &2_000_000_000i128);
+        assert_eq!(
+            client.get_i128(&ConfigKey::BaseReserve),
+            Some(2_000_000_000i128)
+        );
+
+        client.set_sponsorship_budget(&admin, &50_000_000i128);
+        assert_eq!(
+            client.get_i128(&ConfigKey::SponsorshipBudget),
+            Some(50_000_000i128)
+        );
+
+        client.set_reserve_timelock(&admin, &10);
+        assert_eq!(
+            client.try_set_i128(&admin, &ConfigKey::BaseReserve, &3_000_000_000i128),
+            Err(Ok(Error::TimelockRequired))
+        );
+    }
+
+    /// Address and bool keys have their own typed setters and events.
+    #[test]
+    fn test_registry_address_and_bool_keys() {
+        let (env, client, admin, _) = setup();
+        let collector = Address::generate(&env);
+
+        client.set_address(&admin, &ConfigKey::FeeCollector, &collector);
+        let (_, topics, data) = env.events().all().last().unwrap();
+        assert_eq!(
+            EventTopics::decode(&env, &topics),
+            Some(EventTopics::new(symbol_short!("config")))
+        );
+        assert_eq!(
+            ConfigKey::try_from_val(&env, &topics.get(3).unwrap()),
+            Ok(ConfigKey::FeeCollector)
+        );
+        assert_eq!(
+            AddressConfigUpdated::try_from_val(&env, &data).unwrap(),
+            AddressConfigUpdated {
+                key: ConfigKey::FeeCollector,
+                old_value: None,
+                new_value: collector.clone(),
+                admin: admin.clone(),
+            }
+        );
+        assert_eq!(
+            client.get_address(&ConfigKey::FeeCollector),
+            Some(collector)
+        );
+
+        client.set_bool(&admin, &ConfigKey::DebugEvents, &true);
+        assert_eq!(client.get_bool(&ConfigKey::DebugEvents), Some(true));
+
+        assert_eq!(
+            client.try_set_bool(&admin, &ConfigKey::FeeCollector, &true),
+            Err(Ok(Error::WrongConfigType))
+        );
+        assert_eq!(
+            client.try_set_address(&admin, &ConfigKey::FeeBps, &admin),
+            Err(Ok(Error::WrongConfigType))
+        );
+        assert_eq!(
+            client.try_set_bool(&Address::generate(&env), &ConfigKey::DebugEvents, &false),
+            Err(Ok(Error::Unauthorized))
+        );
+        assert_eq!(client.get_epoch(), 2);
+    }
+
+    //  Emergency pause
+
+    /// Only admins flip the pause, and each call must change it.
+    #[test]
+    fn test_pause_and_unpause() {
+        let (env, client, admin, _) = setup();
+        assert!(!client.is_paused());
+
+        let operator = Address::generate(&env);
+        client.grant_role(&admin, &Role::Operator, &operator);
+        assert_eq!(client.try_pause(&operator), Err(Ok(Error::Unauthorized)));
+
+        client.pause(&admin);
+        let (_, _, data) = env.events().all().last().unwrap();
+        assert_eq!(
+            PauseChanged::try_from_val(&env, &data).unwrap(),
+            PauseChanged {
+                paused: true,
+                admin: admin.clone(),
+            }
+        );
+        assert!(client.is_paused());
+        assert_eq!(client.try_pause(&admin), Err(Ok(Error::PauseUnchanged)));
+
+        client.unpause(&admin);
+        assert!(!client.is_paused());
+        assert_eq!(client.try_unpause(&admin), Err(Ok(Error::PauseUnchanged)));
+        assert_eq!(client.get_epoch(), 0);
+    }
+
+    //  Upgrades
+
+    /// Only admins can upgrade, and the hash must name uploaded code.
+    #[test]
+    fn test_upgrade_requires_admin() {
+        let (env, client, admin, _) = setup();
+        assert_eq!(client.get_version(), (1, symbol_short!("reserve")));
+
+        let unknown_hash = BytesN::from_array(&env, &[7u8; 32]);
+        assert_eq!(
+            client.try_upgrade(&Address::generate(&env), &unknown_hash),
+            Err(Ok(Error::Unauthorized))
+        );
+        assert!(client.try_upgrade(&admin, &unknown_hash).is_err());
+    }
+
+    /// Storage written before versioning is migrated by the next
+    /// state-changing call.
+    #[test]
+    fn test_first_write_after_upgrade_migrates_storage() {
+        let (env, client, admin, contract_id) = setup();
+        let stored_version = || {
+            env.as_contract(&contract_id, || {
+                env.storage()
+                    .instance()
+                    .get::<_, u32>(&DataKey::StorageVersion)
+            })
+        };
+        assert_eq!(stored_version(), Some(STORAGE_VERSION));
+
+        env.as_contract(&contract_id, || {
+            env.storage().instance().remove(&DataKey::StorageVersion)
+        });
+        client.get_base_reserve();
+        assert_eq!(stored_version(), None);
+
+        client.set_base_reserve(&admin, &1_000_000_000i128);
+        let migrated = env
+            .events()
+            .all()
+            .iter()
+            .find_map(|(_, _, data)| StorageMigrated::try_from_val(&env, &data).ok());
+        assert_eq!(
+            migrated,
+            Some(StorageMigrated {
+                from_version: 0,
+                to_version: STORAGE_VERSION,
+            })
+        );
+        assert_eq!(stored_version(), Some(STORAGE_VERSION));
+    }
+
+    /// Unmigrated or newer storage is reported instead of misread, and
+    /// `migrate` clears the former.
+    #[test]
+    fn test_storage_version_guards() {
+        let (env, client, admin, contract_id) = setup();
+        client.set_base_reserve(&admin, &1_000_000_000i128);
+        assert_eq!(client.get_storage_version(), STORAGE_VERSION);
+
+        env.as_contract(&contract_id, || {
+            env.storage().instance().remove(&DataKey::StorageVersion)
+        });
+        assert_eq!(
+            client.try_require_base_reserve(),
+            Err(Ok(Error::StorageVersionMismatch))
+        );
+        client.migrate();
+        assert_eq!(client.require_base_reserve(), 1_000_000_000i128);
+
+        env.as_contract(&contract_id, || {
+            env.storage()
+                .instance()
+                .set(&DataKey::StorageVersion, &(STORAGE_VERSION + 1))
+        });
+        assert_eq!(client.try_migrate(), Err(Ok(Error::StorageVersionMismatch)));
+        assert_eq!(
+            client.try_set_base_reserve(&admin, &2_000_000_000i128),
+            Err(Ok(Error::StorageVersionMismatch))
+        );
+    }
+
     //  TTL management
 
     /// After any interaction the instance TTL should be extended.
@@ -299,8 +918,52 @@ mod test {
         let client = ReserveContractClient::new(&env, &contract_id);
         let admin = Address::generate(&env);
         client.initialize(&admin);
-        client.set_base_reserve(&5_000_000i128);
+        client.set_base_reserve(&admin, &5_000_000i128);
 
         assert_ttl_extended(&env, &contract_id);
     }
+
+    /// Reads every 20 days keep the configuration alive for a year without
+    /// any admin write; each read lands below the renewal threshold.
+    #[test]
+    fn test_periodic_reads_keep_instance_alive() {
+        let (env, client, admin, contract_id) = setup();
+        client.set_base_reserve(&admin, &5_000_000i128);
+
+        for _ in 0..18 {
+            env.ledger().with_mut(|li| li.sequence_number += 345_600);
+            assert_eq!(client.get_base_reserve(), Some(5_000_000i128));
+        }
+        assert_ttl_extended(&env, &contract_id);
+    }
+
+    //  Error codes
+
+    /// Codes stay inside the reserve contract's shared range, so off-chain
+    /// tooling can tell them from the account's.
+    #[test]
+    fn test_error_codes_use_reserved_range() {
+        let (env, client, _admin, _) = setup();
+        for code in RESERVE_CONTRACT_ERRORS {
+            let name = client.describe_error(&code);
+            if let Ok(error) = Error::try_from(soroban_sdk::Error::from_contract_error(code)) {
+                assert_eq!(name, Symbol::new(&env, error.name()));
+            } else {
+                assert_eq!(name, Symbol::new(&env, UNKNOWN_ERROR));
+            }
+        }
+        assert_eq!(
+            soroban_sdk::Error::from(Error::InvalidAmount),
+            soroban_sdk::Error::from_contract_error(101)
+        );
+        assert_eq!(
+            client.describe_error(&101),
+            Symbol::new(&env, "InvalidAmount")
+        );
+        assert_eq!(
+            client.describe_error(&113),
+            Symbol::new(&env, "StorageVersionMismatch")
+        );
+        assert_eq!(client.describe_error(&1), Symbol::new(&env, UNKNOWN_ERROR));
+    }
 }
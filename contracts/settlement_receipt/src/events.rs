@@ -0,0 +1,11 @@
+use bridgelet_shared::EventTopics;
+use soroban_sdk::{symbol_short, Env};
+
+use crate::Receipt;
+
+// ─── Emit helpers ───────────────────────────────────────────────────────────
+
+/// Publish the `receipt` event, indexed by the receipt's owner.
+pub fn emit_receipt_minted(env: &Env, receipt: Receipt) {
+    EventTopics::new(symbol_short!("receipt")).publish_indexed(env, receipt.owner.clone(), receipt);
+}
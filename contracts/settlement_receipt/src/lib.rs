@@ -0,0 +1,147 @@
+#![no_std]
+
+mod errors;
+mod events;
+mod storage;
+#[cfg(test)]
+mod test;
+
+use bridgelet_shared::errors::UNKNOWN_ERROR;
+use bridgelet_shared::{page_bounds, AccountStatus, Cursor};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, xdr::ToXdr, Address, BytesN, Env, Map, Symbol, Vec,
+};
+
+pub use errors::Error;
+pub use storage::DataKey;
+
+/// Maximum number of receipts returned by a single page of a listing.
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Proof that `owner` paid into a settled account.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Receipt {
+    pub id: u64,
+    pub owner: Address,
+    /// Ephemeral account whose sweep the receipt was minted for.
+    pub account: Address,
+    /// `AccountMetadata.reference` of the account, if it had metadata.
+    pub order_id: Option<BytesN<32>>,
+    /// sha256 of the XDR of the account's recorded amount per asset, so
+    /// the amounts can be proven without being published.
+    pub amount_hash: BytesN<32>,
+    /// Ledger the sweep settled in.
+    pub ledger: u32,
+}
+
+/// One page of receipt ids plus the cursor for the following page.
+///
+/// `next_cursor` is `None` once the listing is exhausted.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReceiptPage {
+    pub items: Vec<u64>,
+    pub next_cursor: Option<Cursor>,
+}
+
+/// Soulbound receipts for settled payments.
+///
+/// Register the contract as an account's `AccountConfig.settlement_hook`.
+/// When the account is swept, every payer it recorded a sender for gets
+/// a receipt carrying the order reference, a hash of the amounts and the
+/// settlement ledger, which loyalty or dispute flows can check on-chain.
+/// Receipts cannot be transferred or burned: the contract has no
+/// entrypoint that moves them.
+#[contract]
+pub struct SettlementReceipt;
+
+#[contractimpl]
+impl SettlementReceipt {
+    /// Settlement hook entrypoint, see
+    /// [`bridgelet_shared::SettlementHookInterface`].
+    ///
+    /// Requires `account`'s authorization, which an account calling its
+    /// own hook provides, so no one can mint receipts in an account's
+    /// name.  Only `Swept` settlements mint; expired, cancelled and
+    /// recovered accounts did not deliver the payment.
+    pub fn on_account_settled(
+        env: Env,
+        account: Address,
+        status: AccountStatus,
+        total_by_asset: Map<Address, i128>,
+        reference: Option<BytesN<32>>,
+        payers: Vec<Address>,
+    ) {
+        storage::extend_instance_ttl(&env);
+        account.require_auth();
+
+        if status != AccountStatus::Swept {
+            return;
+        }
+
+        let amount_hash: BytesN<32> = env.crypto().sha256(&total_by_asset.to_xdr(&env)).into();
+        for owner in payers.iter() {
+            let receipt = Receipt {
+                id: storage::next_id(&env),
+                owner,
+                account: account.clone(),
+                order_id: reference.clone(),
+                amount_hash: amount_hash.clone(),
+                ledger: env.ledger().sequence(),
+            };
+            storage::set_receipt(&env, &receipt);
+            storage::push_owner_receipt(&env, &receipt.owner, receipt.id);
+            events::emit_receipt_minted(&env, receipt);
+        }
+    }
+
+    /// Receipt `id`, if it was minted.
+    pub fn get_receipt(env: Env, id: u64) -> Option<Receipt> {
+        storage::extend_instance_ttl(&env);
+        storage::get_receipt(&env, id)
+    }
+
+    /// Number of receipts held by `owner`.
+    pub fn get_receipt_count(env: Env, owner: Address) -> u32 {
+        storage::extend_instance_ttl(&env);
+        storage::get_owner_count(&env, &owner)
+    }
+
+    /// List the ids of the receipts held by `owner`, oldest first.
+    ///
+    /// Pass `None` to start from the beginning and the returned
+    /// `next_cursor` to continue; it is `None` once the listing is
+    /// exhausted.  `limit` is capped at [`MAX_PAGE_SIZE`].
+    ///
+    /// # Errors
+    /// * [`Error::InvalidCursor`] – `cursor` was not issued by this contract.
+    pub fn list_receipts(
+        env: Env,
+        owner: Address,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<ReceiptPage, Error> {
+        storage::extend_instance_ttl(&env);
+
+        let count = storage::get_owner_count(&env, &owner);
+        let (positions, next_cursor) =
+            page_bounds(&env, cursor, limit.min(MAX_PAGE_SIZE), 0..count)
+                .ok_or(Error::InvalidCursor)?;
+        let mut items = Vec::new(&env);
+        for position in positions {
+            if let Some(id) = storage::get_owner_receipt(&env, &owner, position) {
+                items.push_back(id);
+            }
+        }
+        Ok(ReceiptPage { items, next_cursor })
+    }
+
+    /// Return the name of the error raised as `Error(Contract, #code)`, or
+    /// `Unknown` for a code this contract never raises.
+    pub fn describe_error(env: Env, code: u32) -> Symbol {
+        let name = Error::try_from(soroban_sdk::Error::from_contract_error(code))
+            .map_or(UNKNOWN_ERROR, Error::name);
+        Symbol::new(&env, name)
+    }
+}
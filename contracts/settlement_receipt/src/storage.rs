@@ -0,0 +1,88 @@
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::Receipt;
+
+/// Storage keys used by the receipt contract.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Id the next receipt is minted with.
+    NextId,
+
+    /// A receipt, by id.
+    Receipt(u64),
+
+    /// Number of receipts held by an address.
+    OwnerCount(Address),
+
+    /// Id of the n-th receipt minted to an address.
+    OwnerReceipt(Address, u32),
+}
+
+// Receipt helpers
+
+/// Take the next receipt id.
+pub fn next_id(env: &Env) -> u64 {
+    let id = env.storage().instance().get(&DataKey::NextId).unwrap_or(0);
+    env.storage().instance().set(&DataKey::NextId, &(id + 1));
+    id
+}
+
+pub fn set_receipt(env: &Env, receipt: &Receipt) {
+    let key = DataKey::Receipt(receipt.id);
+    env.storage().persistent().set(&key, receipt);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn get_receipt(env: &Env, id: u64) -> Option<Receipt> {
+    env.storage().persistent().get(&DataKey::Receipt(id))
+}
+
+// Owner index helpers
+
+pub fn get_owner_count(env: &Env, owner: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::OwnerCount(owner.clone()))
+        .unwrap_or(0)
+}
+
+/// Append receipt `id` to the receipts held by `owner`.
+pub fn push_owner_receipt(env: &Env, owner: &Address, id: u64) {
+    let count = get_owner_count(env, owner);
+    let entry = DataKey::OwnerReceipt(owner.clone(), count);
+    let count_key = DataKey::OwnerCount(owner.clone());
+    env.storage().persistent().set(&entry, &id);
+    env.storage().persistent().set(&count_key, &(count + 1));
+    extend_persistent_ttl(env, &entry);
+    extend_persistent_ttl(env, &count_key);
+}
+
+pub fn get_owner_receipt(env: &Env, owner: &Address, position: u32) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::OwnerReceipt(owner.clone(), position))
+}
+
+// TTL management
+
+/// If the remaining TTL drops below this threshold (in ledgers), extend it.
+/// 259 200 ledgers ≈ 15 days, half of the extension window.
+const TTL_THRESHOLD: u32 = 259_200;
+
+/// Extend TTLs to this many ledgers.
+/// 518 400 ledgers ≈ 30 days (at ~5 s per ledger).
+const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Keep the contract instance alive; called from every public entry-point.
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+fn extend_persistent_ttl(env: &Env, key: &DataKey) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+}
@@ -0,0 +1,187 @@
+extern crate std;
+
+use crate::{Error, Receipt, SettlementReceipt, SettlementReceiptClient};
+use bridgelet_shared::errors::{SETTLEMENT_RECEIPT_ERRORS, UNKNOWN_ERROR};
+use bridgelet_shared::{AccountConfig, AccountLabel, AccountMetadata, AccountStatus, EventTopics};
+use bridgelet_testutils::{Scenario, ScenarioBuilder};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events as _},
+    token::StellarAssetClient,
+    vec,
+    xdr::ToXdr,
+    Address, BytesN, Env, Map, Symbol, TryFromVal,
+};
+
+// HELPERS
+
+/// An account wired to a fresh receipt contract, labeled with an order
+/// reference.
+fn setup() -> (Scenario, SettlementReceiptClient<'static>, BytesN<32>) {
+    let builder = ScenarioBuilder::new();
+    let env = builder.env().clone();
+    let receipts = SettlementReceiptClient::new(&env, &env.register(SettlementReceipt, ()));
+    let reference = BytesN::from_array(&env, &[9u8; 32]);
+    let scenario = builder
+        .config(AccountConfig {
+            settlement_hook: Some(receipts.address.clone()),
+            metadata: AccountLabel::Labeled(AccountMetadata {
+                merchant_id: BytesN::from_array(&env, &[1u8; 32]),
+                reference: reference.clone(),
+                tag: symbol_short!("shop"),
+            }),
+            ..Default::default()
+        })
+        .build();
+    (scenario, receipts, reference)
+}
+
+/// Mint `amount` to the account and record it as paid by `payer`.
+fn pay_from(s: &Scenario, payer: &Address, amount: i128) {
+    s.mint(&s.account.address, amount);
+    s.account
+        .record_payment_from(&amount, &s.asset, payer, &None);
+}
+
+//  Minting
+
+#[test]
+fn test_sweep_mints_receipt_to_payer() {
+    let (s, receipts, reference) = setup();
+    let payer = Address::generate(&s.env);
+    pay_from(&s, &payer, 100);
+
+    s.sweep_to(&Address::generate(&s.env));
+    let amounts = Map::from_array(&s.env, [(s.asset.clone(), 100i128)]);
+    let expected = Receipt {
+        id: 0,
+        owner: payer.clone(),
+        account: s.account.address.clone(),
+        order_id: Some(reference),
+        amount_hash: s.env.crypto().sha256(&amounts.to_xdr(&s.env)).into(),
+        ledger: s.env.ledger().sequence(),
+    };
+
+    // The receipt is announced to the payer
+    let (_, topics, data) = s
+        .env
+        .events()
+        .all()
+        .iter()
+        .find(|(contract, _, _)| *contract == receipts.address)
+        .unwrap();
+    let decoded = EventTopics::decode(&s.env, &topics).unwrap();
+    assert_eq!(decoded.name, symbol_short!("receipt"));
+    assert_eq!(
+        Receipt::try_from_val(&s.env, &data).unwrap(),
+        expected.clone()
+    );
+
+    let page = receipts.list_receipts(&payer, &None, &10);
+    assert_eq!(page.items, vec![&s.env, 0u64]);
+    assert_eq!(page.next_cursor, None);
+    assert_eq!(receipts.get_receipt(&0), Some(expected));
+    assert_eq!(receipts.get_receipt_count(&payer), 1);
+}
+
+#[test]
+fn test_each_payer_gets_one_receipt() {
+    let (s, receipts, _) = setup();
+    let first = Address::generate(&s.env);
+    let second = Address::generate(&s.env);
+    s.mint(&s.account.address, 100);
+    s.account.record_payment_from(&100, &s.asset, &first, &None);
+    let other = s
+        .env
+        .register_stellar_asset_contract_v2(Address::generate(&s.env))
+        .address();
+    StellarAssetClient::new(&s.env, &other).mint(&s.account.address, &50);
+    s.account.record_payment_from(&50, &other, &second, &None);
+
+    s.sweep_to(&Address::generate(&s.env));
+
+    assert_eq!(receipts.get_receipt_count(&first), 1);
+    assert_eq!(receipts.get_receipt_count(&second), 1);
+    assert_eq!(receipts.get_receipt(&1).unwrap().owner, second);
+    assert_eq!(receipts.get_receipt(&2), None);
+}
+
+/// Accounts closed without delivering the payment mint nothing.
+#[test]
+fn test_expired_account_mints_no_receipt() {
+    let (s, receipts, _) = setup();
+    let payer = Address::generate(&s.env);
+    pay_from(&s, &payer, 100);
+
+    s.advance_past_expiry();
+    s.account.expire();
+
+    assert_eq!(s.account.get_status(), AccountStatus::Expired);
+    assert_eq!(receipts.get_receipt_count(&payer), 0);
+}
+
+//  Authorization
+
+/// Only the account itself can report its settlement.
+#[test]
+fn test_receipts_cannot_be_minted_for_another_account() {
+    let env = Env::default();
+    let receipts = SettlementReceiptClient::new(&env, &env.register(SettlementReceipt, ()));
+    let payer = Address::generate(&env);
+
+    let result = receipts.try_on_account_settled(
+        &Address::generate(&env),
+        &AccountStatus::Swept,
+        &Map::new(&env),
+        &None,
+        &vec![&env, payer.clone()],
+    );
+    assert!(result.is_err());
+    assert_eq!(receipts.get_receipt_count(&payer), 0);
+}
+
+//  Listing
+
+#[test]
+fn test_list_receipts_pages_with_cursor() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let receipts = SettlementReceiptClient::new(&env, &env.register(SettlementReceipt, ()));
+    let payer = Address::generate(&env);
+    for _ in 0..3 {
+        receipts.on_account_settled(
+            &Address::generate(&env),
+            &AccountStatus::Swept,
+            &Map::new(&env),
+            &None,
+            &vec![&env, payer.clone()],
+        );
+    }
+
+    let page = receipts.list_receipts(&payer, &None, &2);
+    assert_eq!(page.items, vec![&env, 0u64, 1]);
+    let page = receipts.list_receipts(&payer, &page.next_cursor, &2);
+    assert_eq!(page.items, vec![&env, 2u64]);
+    assert_eq!(page.next_cursor, None);
+    assert_eq!(
+        receipts.try_list_receipts(&payer, &Some(BytesN::from_array(&env, &[0xff; 8])), &2),
+        Err(Ok(Error::InvalidCursor))
+    );
+}
+
+/// Codes stay inside the receipt contract's shared range, so off-chain tooling can
+/// tell them from another contract's.
+#[test]
+fn test_error_codes_use_reserved_range() {
+    let env = Env::default();
+    let client = SettlementReceiptClient::new(&env, &env.register(SettlementReceipt, ()));
+    for code in SETTLEMENT_RECEIPT_ERRORS {
+        let name = client.describe_error(&code);
+        if let Ok(error) = Error::try_from(soroban_sdk::Error::from_contract_error(code)) {
+            assert_eq!(name, Symbol::new(&env, error.name()));
+        } else {
+            assert_eq!(name, Symbol::new(&env, UNKNOWN_ERROR));
+        }
+    }
+    assert_eq!(client.describe_error(&1), Symbol::new(&env, UNKNOWN_ERROR));
+}
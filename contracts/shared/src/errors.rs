@@ -0,0 +1,79 @@
+//! Error numbering shared by the Bridgelet contracts.
+//!
+//! Each contract numbers its errors inside its own range, so a bare
+//! `Error(Contract, #n)` names both the contract and the error, and
+//! off-chain tooling can map a code without knowing which contract raised
+//! it.  Codes never move once released; new errors take the next free code
+//! in their contract's range.
+
+use core::ops::Range;
+
+/// Codes raised by `EphemeralAccountContract`.
+pub const EPHEMERAL_ACCOUNT_ERRORS: Range<u32> = 1..100;
+
+/// Codes raised by `ReserveContract`.
+pub const RESERVE_CONTRACT_ERRORS: Range<u32> = 100..200;
+
+/// Codes raised by `AccountFactory`.
+pub const ACCOUNT_FACTORY_ERRORS: Range<u32> = 200..300;
+
+/// Codes raised by `AccountRegistry`.
+pub const ACCOUNT_REGISTRY_ERRORS: Range<u32> = 300..400;
+
+/// Codes raised by `AccountViewer`.
+pub const ACCOUNT_VIEWER_ERRORS: Range<u32> = 400..500;
+
+/// Codes raised by `AttestationRegistry`.
+pub const ATTESTATION_REGISTRY_ERRORS: Range<u32> = 500..600;
+
+/// Codes raised by `KillSwitch`.
+pub const KILL_SWITCH_ERRORS: Range<u32> = 600..700;
+
+/// Codes raised by `SweepCoordinator`.
+pub const SWEEP_COORDINATOR_ERRORS: Range<u32> = 700..800;
+
+/// Codes raised by `SettlementReceipt`.
+pub const SETTLEMENT_RECEIPT_ERRORS: Range<u32> = 800..900;
+
+/// Codes raised by `SweepController`.
+pub const SWEEP_CONTROLLER_ERRORS: Range<u32> = 900..1000;
+
+/// Codes raised by `SwapAdapter`.
+pub const SWAP_ADAPTER_ERRORS: Range<u32> = 1000..1100;
+
+/// Codes raised by `ComplianceGate`.
+pub const COMPLIANCE_GATE_ERRORS: Range<u32> = 1100..1200;
+
+/// Codes raised by `InsurancePool`.
+pub const INSURANCE_POOL_ERRORS: Range<u32> = 1200..1300;
+
+/// `KeeperBountyUnpayable` of `EphemeralAccountContract`, which callers
+/// of `expire_as` match on to fall back to the plain `expire`.
+pub const KEEPER_BOUNTY_UNPAYABLE: u32 = 38;
+
+/// Name `describe_error` returns for a code the contract does not raise.
+pub const UNKNOWN_ERROR: &str = "Unknown";
+
+/// Crate name of the contract whose range holds `code`, or `None` if no
+/// range does.
+pub fn error_contract(code: u32) -> Option<&'static str> {
+    let ranges = [
+        (EPHEMERAL_ACCOUNT_ERRORS, "ephemeral_account"),
+        (RESERVE_CONTRACT_ERRORS, "reserve_contract"),
+        (ACCOUNT_FACTORY_ERRORS, "account_factory"),
+        (ACCOUNT_REGISTRY_ERRORS, "account_registry"),
+        (ACCOUNT_VIEWER_ERRORS, "account_viewer"),
+        (ATTESTATION_REGISTRY_ERRORS, "attestation_registry"),
+        (KILL_SWITCH_ERRORS, "kill_switch"),
+        (SWEEP_COORDINATOR_ERRORS, "sweep_coordinator"),
+        (SETTLEMENT_RECEIPT_ERRORS, "settlement_receipt"),
+        (SWEEP_CONTROLLER_ERRORS, "sweep_controller"),
+        (SWAP_ADAPTER_ERRORS, "swap_adapter"),
+        (COMPLIANCE_GATE_ERRORS, "compliance_gate"),
+        (INSURANCE_POOL_ERRORS, "insurance_pool"),
+    ];
+    ranges
+        .into_iter()
+        .find(|(range, _)| range.contains(&code))
+        .map(|(_, contract)| contract)
+}
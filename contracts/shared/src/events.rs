@@ -0,0 +1,116 @@
+use soroban_sdk::{symbol_short, Env, IntoVal, Symbol, TryFromVal, Val, Vec};
+
+/// First topic of every Bridgelet event, unless an account was stamped
+/// with its own event prefix.
+pub const EVENT_NAMESPACE: Symbol = symbol_short!("bridgelet");
+
+/// Third topic of every event.  Bumped whenever the topics or the data of
+/// any event change shape, so subscribers can tell layouts apart.
+pub const EVENT_VERSION: u32 = 9;
+
+/// Leading topics shared by every event the Bridgelet contracts publish.
+///
+/// Events are published as `(namespace, name, version)`, then the
+/// publisher's sequence number and epoch when it keeps them, then at most
+/// one indexed topic, usually the address the event is about (the asset of
+/// a payment, the destination of a sweep).  The data is the event's struct, so the
+/// indexed value is also found there.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventTopics {
+    pub namespace: Symbol,
+    pub name: Symbol,
+    pub version: u32,
+    /// Position of the event in its publisher's history.  Consecutive
+    /// events of one account carry consecutive numbers, so a gap means an
+    /// event was missed.
+    pub seq: Option<u64>,
+    /// How many times the publisher was reset for re-use.  Only published
+    /// after a `seq`.
+    pub epoch: Option<u32>,
+}
+
+impl EventTopics {
+    /// Topics of `name` under the default namespace.
+    pub fn new(name: Symbol) -> Self {
+        Self::with_namespace(EVENT_NAMESPACE, name)
+    }
+
+    pub fn with_namespace(namespace: Symbol, name: Symbol) -> Self {
+        Self {
+            namespace,
+            name,
+            version: EVENT_VERSION,
+            seq: None,
+            epoch: None,
+        }
+    }
+
+    /// The same topics followed by sequence number `seq`.
+    pub fn with_seq(self, seq: u64) -> Self {
+        Self {
+            seq: Some(seq),
+            ..self
+        }
+    }
+
+    /// The same topics with `epoch` after the sequence number.
+    pub fn with_epoch(self, epoch: u32) -> Self {
+        Self {
+            epoch: Some(epoch),
+            ..self
+        }
+    }
+
+    /// Publish `data` under these topics.
+    pub fn publish<D: IntoVal<Env, Val>>(self, env: &Env, data: D) {
+        match (self.seq, self.epoch) {
+            (Some(seq), Some(epoch)) => env
+                .events()
+                .publish((self.namespace, self.name, self.version, seq, epoch), data),
+            (Some(seq), None) => env
+                .events()
+                .publish((self.namespace, self.name, self.version, seq), data),
+            (None, _) => env
+                .events()
+                .publish((self.namespace, self.name, self.version), data),
+        }
+    }
+
+    /// Publish `data` under these topics followed by `indexed`.
+    pub fn publish_indexed<I, D>(self, env: &Env, indexed: I, data: D)
+    where
+        I: IntoVal<Env, Val>,
+        D: IntoVal<Env, Val>,
+    {
+        match (self.seq, self.epoch) {
+            (Some(seq), Some(epoch)) => env.events().publish(
+                (self.namespace, self.name, self.version, seq, epoch, indexed),
+                data,
+            ),
+            (Some(seq), None) => env.events().publish(
+                (self.namespace, self.name, self.version, seq, indexed),
+                data,
+            ),
+            (None, _) => env
+                .events()
+                .publish((self.namespace, self.name, self.version, indexed), data),
+        }
+    }
+
+    /// Read the leading topics of a published event, or `None` if it does
+    /// not follow the schema.
+    pub fn decode(env: &Env, topics: &Vec<Val>) -> Option<Self> {
+        let seq = topics
+            .get(3)
+            .and_then(|seq| u64::try_from_val(env, &seq).ok());
+        Some(Self {
+            namespace: Symbol::try_from_val(env, &topics.get(0)?).ok()?,
+            name: Symbol::try_from_val(env, &topics.get(1)?).ok()?,
+            version: u32::try_from_val(env, &topics.get(2)?).ok()?,
+            seq,
+            epoch: seq
+                .and_then(|_| topics.get(4))
+                .and_then(|epoch| u32::try_from_val(env, &epoch).ok()),
+        })
+    }
+}
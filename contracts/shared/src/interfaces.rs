@@ -0,0 +1,311 @@
+use crate::pagination::{Cursor, PaymentPage, ReserveEventPage};
+use crate::types::{
+    AccountConfig, AccountInfo, AccountMetadata, AccountStatus, AssetKind, AuditTrail,
+    Capabilities, DeploymentSettings, OracleAsset, Payment, PriceData, ReserveReclaimed,
+    ReserveSnapshot, StateAttestation,
+};
+use soroban_sdk::{contractclient, Address, BytesN, Env, Error, Map, Symbol, Vec};
+
+/// Entrypoints of `EphemeralAccountContract` that other contracts call.
+///
+/// Lets the factory, the coordinators and third-party contracts invoke an
+/// account through `EphemeralAccountClient` without linking the account
+/// crate, whose exports would end up in their own wasm.  Failures surface
+/// as the contract error codes listed in the API reference.
+#[contractclient(name = "EphemeralAccountClient")]
+pub trait EphemeralAccountInterface {
+    fn initialize(
+        env: Env,
+        creator: Address,
+        expiry_ledger: u32,
+        recovery_address: Address,
+    ) -> Result<(), Error>;
+
+    fn initialize_with_config(
+        env: Env,
+        creator: Address,
+        expiry_ledger: u32,
+        recovery_address: Address,
+        config: AccountConfig,
+    ) -> Result<(), Error>;
+
+    fn initialize_with_deployment(
+        env: Env,
+        creator: Address,
+        expiry_ledger: u32,
+        recovery_address: Address,
+        deployment: DeploymentSettings,
+    ) -> Result<(), Error>;
+
+    fn record_payment(env: Env, amount: i128, asset: Address) -> Result<(), Error>;
+
+    fn record_payments(env: Env, payments: Vec<(Address, i128)>) -> Result<(), Error>;
+
+    fn record_native_payment(env: Env, amount: i128, sender: Option<Address>) -> Result<(), Error>;
+
+    fn sweep(env: Env, destination: Address, auth_signatures: Vec<BytesN<64>>)
+        -> Result<(), Error>;
+
+    fn sweep_forced(
+        env: Env,
+        destination: Address,
+        auth_signatures: Vec<BytesN<64>>,
+    ) -> Result<(), Error>;
+
+    fn sweep_authorized(env: Env, destination: Address) -> Result<(), Error>;
+
+    fn sweep_assets(
+        env: Env,
+        destination: Address,
+        assets: Vec<Address>,
+        auth_signatures: Vec<BytesN<64>>,
+    ) -> Result<(), Error>;
+
+    fn sweep_split(
+        env: Env,
+        destinations: Vec<(Address, u32)>,
+        auth_signatures: Vec<BytesN<64>>,
+    ) -> Result<(), Error>;
+
+    fn finalize_sweep(env: Env) -> Result<(), Error>;
+
+    fn sweep_dust(env: Env) -> Result<(), Error>;
+
+    fn extend_expiry(env: Env, new_expiry_ledger: u32) -> Result<(), Error>;
+
+    fn expire(env: Env) -> Result<(), Error>;
+
+    fn expire_as(env: Env, caller: Address) -> Result<(), Error>;
+
+    fn reset(
+        env: Env,
+        expiry_ledger: u32,
+        recovery_address: Address,
+        factory: Address,
+    ) -> Result<(), Error>;
+
+    fn emergency_recover(env: Env) -> Result<(), Error>;
+
+    fn reclaim_reserve(env: Env) -> Result<i128, Error>;
+
+    fn reclaim_reserve_as(env: Env, caller: Address) -> Result<i128, Error>;
+
+    fn record_reserve_topup(env: Env, amount: i128) -> Result<(), Error>;
+
+    fn reconcile_reserve(env: Env, actual_balance: i128) -> Result<(), Error>;
+
+    fn migrate(env: Env) -> Result<(), Error>;
+
+    fn get_info(env: Env) -> Result<AccountInfo, Error>;
+
+    fn get_status(env: Env) -> AccountStatus;
+
+    fn is_expired(env: Env) -> bool;
+
+    fn ledgers_until_expiry(env: Env) -> i64;
+
+    fn can_sweep(env: Env) -> Result<(), Error>;
+
+    fn can_expire(env: Env) -> bool;
+
+    fn get_sweep_value(env: Env) -> Option<i128>;
+
+    fn is_sweep_worthwhile(env: Env) -> bool;
+
+    fn get_sweep_message(env: Env, destination: Address) -> Result<BytesN<32>, Error>;
+
+    fn get_sweep_nonce(env: Env) -> u64;
+
+    fn get_swept_assets(env: Env) -> Vec<Address>;
+
+    fn get_asset_kind(env: Env, asset: Address) -> AssetKind;
+
+    fn get_payments(env: Env, cursor: Option<Cursor>, limit: u32) -> Result<PaymentPage, Error>;
+
+    fn get_payment_by_asset(env: Env, asset: Address) -> Option<Payment>;
+
+    fn get_payment_count(env: Env) -> u32;
+
+    fn get_payments_root(env: Env) -> BytesN<32>;
+
+    fn get_reserve_remaining(env: Env) -> i128;
+
+    fn get_trustline_count(env: Env) -> u32;
+
+    fn get_metadata(env: Env) -> Option<AccountMetadata>;
+
+    fn get_state_seq(env: Env) -> u64;
+
+    fn get_sweep_id(env: Env) -> Option<BytesN<32>>;
+
+    fn get_epoch(env: Env) -> u32;
+
+    fn get_reserve_available(env: Env) -> i128;
+
+    fn is_reserve_reclaimed(env: Env) -> bool;
+
+    fn get_last_reserve_event(env: Env) -> Option<ReserveReclaimed>;
+
+    fn get_reserve_events(
+        env: Env,
+        cursor: Option<Cursor>,
+        limit: u32,
+    ) -> Result<ReserveEventPage, Error>;
+
+    fn get_audit_trail(env: Env) -> Result<AuditTrail, Error>;
+
+    fn get_capabilities(env: Env) -> Result<Capabilities, Error>;
+
+    fn get_state_attestation(env: Env) -> Result<StateAttestation, Error>;
+
+    fn get_version(env: Env) -> (u32, Symbol);
+
+    fn get_storage_version(env: Env) -> u32;
+
+    /// Name of the error raised with `code`.
+    fn describe_error(env: Env, code: u32) -> Symbol;
+}
+
+/// Read-only entrypoints of `ReserveContract` that other contracts call.
+///
+/// Administration stays with the reserve crate's own client, since it
+/// takes the reserve's `ConfigKey` and `Role` types.
+#[contractclient(name = "ReserveClient")]
+pub trait ReserveInterface {
+    /// Configured base reserve in stroops, if set.
+    fn get_base_reserve(env: Env) -> Option<i128>;
+
+    /// Configured base reserve in stroops; fails if it was never set.
+    fn require_base_reserve(env: Env) -> Result<i128, Error>;
+
+    fn has_base_reserve(env: Env) -> bool;
+
+    /// Extra reserve in stroops per trustline, if set.
+    fn get_trustline_reserve(env: Env) -> Option<i128>;
+
+    /// Basis points of a reclaimed reserve paid to the keeper that closed
+    /// the account, if set.
+    fn get_keeper_bounty_bps(env: Env) -> Option<i128>;
+
+    /// Base reserve plus the trustline reserve for `trustline_count`
+    /// trustlines; fails if no base reserve was set.
+    fn compute_required_reserve(env: Env, trustline_count: u32) -> Result<i128, Error>;
+
+    /// Base reserve in force at `ledger`, from the change history.
+    fn get_reserve_at_ledger(env: Env, ledger: u32) -> Option<i128>;
+
+    fn get_reserve_timelock(env: Env) -> u32;
+
+    fn get_sponsorship_budget(env: Env) -> Option<i128>;
+
+    /// Every configured value and the epoch it belongs to, in one read.
+    fn get_snapshot(env: Env) -> ReserveSnapshot;
+
+    /// Whether the system-wide emergency pause is on.
+    fn is_paused(env: Env) -> bool;
+
+    fn get_version(env: Env) -> (u32, Symbol);
+
+    fn get_storage_version(env: Env) -> u32;
+
+    /// Name of the error raised with `code`.
+    fn describe_error(env: Env, code: u32) -> Symbol;
+}
+
+/// The part of the SEP-40 price oracle interface accounts use to value
+/// their payments.
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracleInterface {
+    /// Decimals of every price the oracle quotes.
+    fn decimals(env: Env) -> u32;
+
+    /// Most recent price of `asset`, if the oracle quotes it.
+    fn lastprice(env: Env, asset: OracleAsset) -> Option<PriceData>;
+}
+
+/// Callback an account makes once it settles, so downstream contracts
+/// (loyalty points, accounting, escrow release) can react on-chain.
+///
+/// Registered with `AccountConfig.settlement_hook`.  Called at the end of
+/// every transition that closes the account, after the funds have moved;
+/// a failing hook is reported with a `hook_fail` event and does not undo
+/// the settlement.
+#[contractclient(name = "SettlementHookClient")]
+pub trait SettlementHookInterface {
+    /// `account` reached `status` (`Swept`, `Expired`, `Cancelled` or
+    /// `EmergencyRecovered`); `total_by_asset` is the recorded amount of
+    /// every asset it was paid in.  `reference` is the order reference of
+    /// the account's metadata, and `payers` the distinct senders its
+    /// payments were recorded with, so the hook need not call back into
+    /// the account, which the host would refuse as re-entry.
+    fn on_account_settled(
+        env: Env,
+        account: Address,
+        status: AccountStatus,
+        total_by_asset: Map<Address, i128>,
+        reference: Option<BytesN<32>>,
+        payers: Vec<Address>,
+    );
+}
+
+/// Asset conversion a sweep can route through, independent of the DEX
+/// behind it.
+///
+/// `SwapAdapter` implements it on top of the Soroswap router; another
+/// venue only needs a contract with the same entrypoint.
+#[contractclient(name = "SwapAdapterClient")]
+pub trait SwapAdapterInterface {
+    /// Sell exactly `amount` of `asset_in` held by `from` for at least
+    /// `min_out` of `asset_out`, paid to `from`.  Requires `from`'s
+    /// authorization and returns the amount of `asset_out` received.
+    fn swap_exact_in(
+        env: Env,
+        from: Address,
+        asset_in: Address,
+        asset_out: Address,
+        amount: i128,
+        min_out: i128,
+    ) -> Result<i128, Error>;
+
+    /// Amount of `asset_out` that `amount` of `asset_in` would buy now.
+    fn quote_exact_in(
+        env: Env,
+        asset_in: Address,
+        asset_out: Address,
+        amount: i128,
+    ) -> Result<i128, Error>;
+}
+
+/// Velocity checks an account makes against a compliance gate.
+///
+/// Registered with `AccountConfig.compliance_gate`.  The account reports
+/// every payment it records, and asks before every sweep; a payment that
+/// takes its merchant or payer past a rolling cap holds the account's
+/// sweeps until the gate releases it.
+#[contractclient(name = "ComplianceGateClient")]
+pub trait ComplianceGateInterface {
+    /// `account` recorded `amount` of `asset`, paid by `payer` if the
+    /// account knows the sender.  Requires `account`'s authorization.
+    fn check_payment(
+        env: Env,
+        account: Address,
+        payer: Option<Address>,
+        asset: Address,
+        amount: i128,
+    ) -> Result<(), Error>;
+
+    /// Fails while the gate holds `account`'s sweeps.
+    fn check_sweep(env: Env, account: Address) -> Result<(), Error>;
+}
+
+/// Claims an insured account opens with its insurance pool.
+///
+/// Registered by the factory, which collects the premium at deployment,
+/// through `DeploymentSettings.insurance_pool`.
+#[contractclient(name = "InsurancePoolClient")]
+pub trait InsurancePoolInterface {
+    /// `account` expired having returned `amount` of recorded payments to
+    /// its recovery address.  Requires `account`'s authorization.  Returns
+    /// the claim id.
+    fn open_claim(env: Env, account: Address, amount: i128) -> Result<u64, Error>;
+}
@@ -0,0 +1,87 @@
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::types::AccountStatus;
+
+/// Fields of an ephemeral account that the consistency rules relate.
+#[cfg_attr(feature = "invariants", contracttype)]
+#[cfg_attr(not(feature = "invariants"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountSnapshot {
+    pub status: AccountStatus,
+    pub payment_count: u32,
+    pub swept_to: Option<Address>,
+    pub reserve_remaining: i128,
+    pub reserve_reclaimed: bool,
+}
+
+/// A cross-field rule an account's state must satisfy.
+#[cfg_attr(feature = "invariants", contracttype)]
+#[cfg_attr(not(feature = "invariants"), contracttype(export = false))]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[repr(u32)]
+pub enum Invariant {
+    /// `Active` accounts hold no payments.
+    ActiveWithoutPayments = 1,
+    /// `PaymentReceived`, `PendingRelease` and `Swept` accounts hold at
+    /// least one payment.
+    PaidStatusHasPayments = 2,
+    /// `Cancelled` accounts were closed before any payment arrived.
+    CancelledWithoutPayments = 3,
+    /// `swept_to` is set exactly when the account is closed.
+    SweptToOnlyWhenClosed = 4,
+    /// The reserve is only reclaimed once the account is closed.
+    ReclaimOnlyWhenClosed = 5,
+    /// A fully reclaimed reserve has nothing remaining, and the remaining
+    /// reserve is never negative.
+    ReserveConsistent = 6,
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for Invariant {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(match self {
+            Invariant::ActiveWithoutPayments => "active_without_payments",
+            Invariant::PaidStatusHasPayments => "paid_status_has_payments",
+            Invariant::CancelledWithoutPayments => "cancelled_without_payments",
+            Invariant::SweptToOnlyWhenClosed => "swept_to_only_when_closed",
+            Invariant::ReclaimOnlyWhenClosed => "reclaim_only_when_closed",
+            Invariant::ReserveConsistent => "reserve_consistent",
+        })
+    }
+}
+
+/// Every rule `snapshot` breaks, in rule order; empty if it is consistent.
+pub fn violations(env: &Env, snapshot: &AccountSnapshot) -> Vec<Invariant> {
+    let status = snapshot.status;
+    let paid = snapshot.payment_count > 0;
+    let closed = status.is_closed();
+
+    let mut broken = Vec::new(env);
+    if status == AccountStatus::Active && paid {
+        broken.push_back(Invariant::ActiveWithoutPayments);
+    }
+    let paid_status = matches!(
+        status,
+        AccountStatus::PaymentReceived | AccountStatus::PendingRelease | AccountStatus::Swept
+    );
+    if paid_status && !paid {
+        broken.push_back(Invariant::PaidStatusHasPayments);
+    }
+    if status == AccountStatus::Cancelled && paid {
+        broken.push_back(Invariant::CancelledWithoutPayments);
+    }
+    if snapshot.swept_to.is_some() != closed {
+        broken.push_back(Invariant::SweptToOnlyWhenClosed);
+    }
+    if snapshot.reserve_reclaimed && !closed {
+        broken.push_back(Invariant::ReclaimOnlyWhenClosed);
+    }
+    if snapshot.reserve_remaining < 0
+        || (snapshot.reserve_reclaimed && snapshot.reserve_remaining != 0)
+    {
+        broken.push_back(Invariant::ReserveConsistent);
+    }
+    broken
+}
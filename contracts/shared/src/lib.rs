@@ -1,5 +1,29 @@
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod errors;
+mod events;
+mod interfaces;
+mod invariants;
+#[cfg(feature = "serde")]
+pub mod offchain;
+mod pagination;
 mod types;
+pub mod units;
 
-pub use types::{AccountInfo, AccountStatus, Payment};
+pub use events::{EventTopics, EVENT_NAMESPACE, EVENT_VERSION};
+pub use interfaces::{
+    ComplianceGateClient, ComplianceGateInterface, EphemeralAccountClient,
+    EphemeralAccountInterface, InsurancePoolClient, InsurancePoolInterface, PriceOracleClient,
+    PriceOracleInterface, ReserveClient, ReserveInterface, SettlementHookClient,
+    SettlementHookInterface, SwapAdapterClient, SwapAdapterInterface,
+};
+pub use invariants::{violations, AccountSnapshot, Invariant};
+pub use pagination::{
+    decode_cursor, encode_cursor, page_bounds, AddressPage, Cursor, PaymentPage, ReserveEventPage,
+};
+pub use types::{
+    AccountConfig, AccountInfo, AccountLabel, AccountMetadata, AccountStatus, AssetKind,
+    AuditTrail, AuthPolicy, Capabilities, ChainLink, DeploymentSettings, ExternalChainRef,
+    OracleAsset, Payment, PriceData, ReserveReclaimed, ReserveSnapshot, SignerKind,
+    StateAttestation,
+};
@@ -0,0 +1,382 @@
+//! Owned, serde-ready copies of the shared contract types.
+//!
+//! The contract types hold host objects (`Address`, `Vec`, `BytesN`) that
+//! only exist inside an `Env`, so they cannot be serialized directly.
+//! Off-chain services convert them here, with `From`, while the `Env` they
+//! were read with is still alive.
+//!
+//! Addresses are strkeys (`G...` / `C...`), hashes are lowercase hex and
+//! amounts are decimal strings, so JSON clients never round an `i128`
+//! through a float.
+
+use serde::{Deserialize, Serialize};
+use std::string::String;
+use std::vec::Vec;
+
+use crate::types::AccountStatus;
+
+/// Strkey of `address`.
+pub fn address_to_string(address: &soroban_sdk::Address) -> String {
+    let strkey = address.to_string();
+    let mut bytes = std::vec![0u8; strkey.len() as usize];
+    strkey.copy_into_slice(&mut bytes);
+    String::from_utf8(bytes).expect("strkeys are ASCII")
+}
+
+/// Lowercase hex of `bytes`.
+pub fn bytes_to_hex<const N: usize>(bytes: &soroban_sdk::BytesN<N>) -> String {
+    bytes
+        .to_array()
+        .iter()
+        .map(|byte| std::format!("{byte:02x}"))
+        .collect()
+}
+
+/// (De)serialize an `i128` amount as a decimal string.
+mod amount {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use std::string::{String, ToString};
+
+    pub fn serialize<S: Serializer>(amount: &i128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&amount.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i128, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Off-chain copy of [`crate::Payment`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Payment {
+    pub asset: String,
+    #[serde(with = "amount")]
+    pub amount: i128,
+    pub timestamp: u64,
+    pub payer_info_hash: Option<String>,
+    pub sender: Option<String>,
+    pub decimals: Option<u32>,
+    pub memo: Option<String>,
+}
+
+impl From<&crate::Payment> for Payment {
+    fn from(payment: &crate::Payment) -> Self {
+        Self {
+            asset: address_to_string(&payment.asset),
+            amount: payment.amount,
+            timestamp: payment.timestamp,
+            payer_info_hash: payment.payer_info_hash.as_ref().map(bytes_to_hex),
+            sender: payment.sender.as_ref().map(address_to_string),
+            decimals: payment.decimals,
+            memo: payment.memo.as_ref().map(bytes_to_hex),
+        }
+    }
+}
+
+/// Off-chain copy of [`crate::AccountInfo`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccountInfo {
+    pub creator: String,
+    pub status: AccountStatus,
+    pub expiry_ledger: u32,
+    pub recovery_address: String,
+    pub payment_received: bool,
+    pub payment_count: u32,
+    pub payments: Vec<Payment>,
+    pub swept_to: Option<String>,
+    pub wasm_version: u32,
+    pub config_version: u32,
+}
+
+impl From<&crate::AccountInfo> for AccountInfo {
+    fn from(info: &crate::AccountInfo) -> Self {
+        Self {
+            creator: address_to_string(&info.creator),
+            status: info.status,
+            expiry_ledger: info.expiry_ledger,
+            recovery_address: address_to_string(&info.recovery_address),
+            payment_received: info.payment_received,
+            payment_count: info.payment_count,
+            payments: info.payments.iter().map(|p| Payment::from(&p)).collect(),
+            swept_to: info.swept_to.as_ref().map(address_to_string),
+            wasm_version: info.wasm_version,
+            config_version: info.config_version,
+        }
+    }
+}
+
+/// Off-chain copy of [`crate::ReserveReclaimed`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReserveReclaimed {
+    pub destination: String,
+    #[serde(with = "amount")]
+    pub amount: i128,
+    pub sweep_id: String,
+    pub fully_reclaimed: bool,
+    #[serde(with = "amount")]
+    pub remaining_reserve: i128,
+    pub keeper: Option<String>,
+    #[serde(with = "amount")]
+    pub keeper_bounty: i128,
+}
+
+impl From<&crate::ReserveReclaimed> for ReserveReclaimed {
+    fn from(event: &crate::ReserveReclaimed) -> Self {
+        Self {
+            destination: address_to_string(&event.destination),
+            amount: event.amount,
+            sweep_id: bytes_to_hex(&event.sweep_id),
+            fully_reclaimed: event.fully_reclaimed,
+            remaining_reserve: event.remaining_reserve,
+            keeper: event.keeper.as_ref().map(address_to_string),
+            keeper_bounty: event.keeper_bounty,
+        }
+    }
+}
+
+/// Off-chain copy of [`crate::AuditTrail`], for compliance exports.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AuditTrail {
+    pub ledger: u32,
+    pub creator: String,
+    pub recovery_address: String,
+    pub expiry_ledger: u32,
+    pub grace_ledgers: u32,
+    pub config_version: u32,
+    pub wasm_version: u32,
+    pub storage_version: u32,
+    pub payments: Vec<Payment>,
+    pub swept_assets: Vec<String>,
+    pub payments_root: String,
+    pub status: AccountStatus,
+    pub swept_to: Option<String>,
+    pub last_sweep_id: Option<String>,
+    #[serde(with = "amount")]
+    pub reserve_remaining: i128,
+    #[serde(with = "amount")]
+    pub reserve_available: i128,
+    pub reserve_reclaimed: bool,
+    #[serde(with = "amount")]
+    pub total_reclaimed: i128,
+    pub reclaim_events: Vec<ReserveReclaimed>,
+    pub payment_count: u32,
+    pub sweep_nonce: u64,
+    pub reclaim_count: u32,
+    pub reclaim_event_count: u32,
+}
+
+impl From<&crate::AuditTrail> for AuditTrail {
+    fn from(trail: &crate::AuditTrail) -> Self {
+        Self {
+            ledger: trail.ledger,
+            creator: address_to_string(&trail.creator),
+            recovery_address: address_to_string(&trail.recovery_address),
+            expiry_ledger: trail.expiry_ledger,
+            grace_ledgers: trail.grace_ledgers,
+            config_version: trail.config_version,
+            wasm_version: trail.wasm_version,
+            storage_version: trail.storage_version,
+            payments: trail.payments.iter().map(|p| Payment::from(&p)).collect(),
+            swept_assets: trail
+                .swept_assets
+                .iter()
+                .map(|a| address_to_string(&a))
+                .collect(),
+            payments_root: bytes_to_hex(&trail.payments_root),
+            status: trail.status,
+            swept_to: trail.swept_to.as_ref().map(address_to_string),
+            last_sweep_id: trail.last_sweep_id.as_ref().map(bytes_to_hex),
+            reserve_remaining: trail.reserve_remaining,
+            reserve_available: trail.reserve_available,
+            reserve_reclaimed: trail.reserve_reclaimed,
+            total_reclaimed: trail.total_reclaimed,
+            reclaim_events: trail
+                .reclaim_events
+                .iter()
+                .map(|e| ReserveReclaimed::from(&e))
+                .collect(),
+            payment_count: trail.payment_count,
+            sweep_nonce: trail.sweep_nonce,
+            reclaim_count: trail.reclaim_count,
+            reclaim_event_count: trail.reclaim_event_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use super::{AccountInfo, AuditTrail, Payment, ReserveReclaimed};
+    use crate::{AccountStatus, AuthPolicy, Invariant, SignerKind};
+    use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, Env};
+    use std::string::{String, ToString};
+
+    fn payment(env: &Env, amount: i128) -> crate::Payment {
+        crate::Payment {
+            asset: Address::generate(env),
+            amount,
+            timestamp: 1_700_000_000,
+            payer_info_hash: Some(BytesN::from_array(env, &[0xab; 32])),
+            sender: Some(Address::generate(env)),
+            decimals: Some(7),
+            memo: None,
+        }
+    }
+
+    /// JSON round trip: serialize, parse back, and compare.
+    fn round_trip<T>(value: &T) -> String
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + core::fmt::Debug,
+    {
+        let json = serde_json::to_string(value).unwrap();
+        assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value);
+        json
+    }
+
+    /// Amounts beyond what a float holds exactly survive as strings.
+    #[test]
+    fn test_payment_round_trip() {
+        let env = Env::default();
+        let source = payment(&env, i128::MAX);
+        let converted = Payment::from(&source);
+        assert_eq!(converted.asset, super::address_to_string(&source.asset));
+        assert!(converted.asset.starts_with('C'));
+        assert_eq!(converted.payer_info_hash, Some("ab".repeat(32)));
+
+        let json = round_trip(&converted);
+        assert!(json.contains(&std::format!("\"amount\":\"{}\"", i128::MAX)));
+
+        let negative = Payment {
+            amount: -5,
+            ..converted
+        };
+        assert!(round_trip(&negative).contains("\"amount\":\"-5\""));
+    }
+
+    /// Amounts must be decimal strings; JSON numbers and fractions fail.
+    #[test]
+    fn test_amount_rejects_non_decimal_strings() {
+        let env = Env::default();
+        let json = serde_json::to_string(&Payment::from(&payment(&env, 10))).unwrap();
+        for bad in ["\"amount\":10", "\"amount\":\"1.5\"", "\"amount\":\"ten\""] {
+            let bad = json.replace("\"amount\":\"10\"", bad);
+            assert!(serde_json::from_str::<Payment>(&bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_account_info_round_trip() {
+        let env = Env::default();
+        let recipient = Address::generate(&env);
+        let info = crate::AccountInfo {
+            creator: Address::generate(&env),
+            status: AccountStatus::PaymentReceived,
+            expiry_ledger: 1_000,
+            recovery_address: Address::generate(&env),
+            payment_received: true,
+            payment_count: 2,
+            payments: vec![&env, payment(&env, 100), payment(&env, 250)],
+            swept_to: Some(recipient.clone()),
+            wasm_version: 3,
+            config_version: 23,
+        };
+
+        let converted = AccountInfo::from(&info);
+        assert_eq!(converted.payments.len(), 2);
+        assert_eq!(
+            converted.swept_to,
+            Some(super::address_to_string(&recipient))
+        );
+
+        let json = round_trip(&converted);
+        assert!(json.contains("\"status\":\"payment_received\""));
+        assert!(json.contains("\"amount\":\"250\""));
+    }
+
+    #[test]
+    fn test_audit_trail_round_trip() {
+        let env = Env::default();
+        let keeper = Address::generate(&env);
+        let reclaim = crate::ReserveReclaimed {
+            destination: Address::generate(&env),
+            amount: 1_000_000_000,
+            sweep_id: BytesN::from_array(&env, &[0x01; 32]),
+            fully_reclaimed: true,
+            remaining_reserve: 0,
+            keeper: Some(keeper.clone()),
+            keeper_bounty: 50_000_000,
+        };
+        let trail = crate::AuditTrail {
+            ledger: 500,
+            creator: Address::generate(&env),
+            recovery_address: Address::generate(&env),
+            expiry_ledger: 1_000,
+            grace_ledgers: 10,
+            config_version: 23,
+            wasm_version: 3,
+            storage_version: 1,
+            payments: vec![&env, payment(&env, 100)],
+            swept_assets: vec![&env, Address::generate(&env)],
+            payments_root: BytesN::from_array(&env, &[0xff; 32]),
+            status: AccountStatus::Swept,
+            swept_to: None,
+            last_sweep_id: Some(BytesN::from_array(&env, &[0x01; 32])),
+            reserve_remaining: 0,
+            reserve_available: 0,
+            reserve_reclaimed: true,
+            total_reclaimed: 1_000_000_000,
+            reclaim_events: vec![&env, reclaim],
+            payment_count: 1,
+            sweep_nonce: 4,
+            reclaim_count: 1,
+            reclaim_event_count: 1,
+        };
+
+        let converted = AuditTrail::from(&trail);
+        assert_eq!(converted.payments_root, "ff".repeat(32));
+        assert_eq!(converted.last_sweep_id, Some("01".repeat(32)));
+        let event: &ReserveReclaimed = &converted.reclaim_events[0];
+        assert_eq!(event.keeper, Some(super::address_to_string(&keeper)));
+        assert_eq!(event.sweep_id, "01".repeat(32));
+
+        let json = round_trip(&converted);
+        assert!(json.contains("\"total_reclaimed\":\"1000000000\""));
+        assert!(json.contains("\"keeper_bounty\":\"50000000\""));
+        assert!(json.contains("\"swept_to\":null"));
+    }
+
+    /// `Display` prints the same name the enums serialize to.
+    #[test]
+    fn test_display_matches_serialized_name() {
+        fn check<T: serde::Serialize + core::fmt::Display>(values: &[T]) {
+            for value in values {
+                let json = serde_json::to_string(value).unwrap();
+                assert_eq!(json, std::format!("\"{value}\""));
+            }
+        }
+
+        check(&[
+            AccountStatus::Active,
+            AccountStatus::PaymentReceived,
+            AccountStatus::Swept,
+            AccountStatus::Expired,
+            AccountStatus::Cancelled,
+            AccountStatus::PendingRelease,
+            AccountStatus::EmergencyRecovered,
+        ]);
+        check(&[SignerKind::Ed25519, SignerKind::Secp256r1]);
+        check(&[AuthPolicy::Permissionless, AuthPolicy::Restricted]);
+        check(&[
+            Invariant::ActiveWithoutPayments,
+            Invariant::PaidStatusHasPayments,
+            Invariant::CancelledWithoutPayments,
+            Invariant::SweptToOnlyWhenClosed,
+            Invariant::ReclaimOnlyWhenClosed,
+            Invariant::ReserveConsistent,
+        ]);
+        assert_eq!(AccountStatus::Swept.to_string(), "swept");
+    }
+}
@@ -0,0 +1,91 @@
+use core::ops::Range;
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::types::{Payment, ReserveReclaimed};
+
+/// Layout version written into the first byte of every cursor.
+const CURSOR_VERSION: u8 = 1;
+
+/// Opaque position in a paginated listing.
+///
+/// Every paginated getter returns the cursor for the next page instead of a
+/// raw offset.  Clients must treat it as an opaque token and hand it back
+/// unchanged; contracts are free to change how listings are stored or
+/// ordered without breaking clients that cached a cursor.
+pub type Cursor = BytesN<8>;
+
+/// Encode a position within a listing as a [`Cursor`].
+pub fn encode_cursor(env: &Env, position: u32) -> Cursor {
+    let mut bytes = [0u8; 8];
+    bytes[0] = CURSOR_VERSION;
+    bytes[4..].copy_from_slice(&position.to_be_bytes());
+    BytesN::from_array(env, &bytes)
+}
+
+/// Decode a [`Cursor`], or `None` if it was not produced by [`encode_cursor`].
+pub fn decode_cursor(cursor: &Cursor) -> Option<u32> {
+    let bytes = cursor.to_array();
+    if bytes[0] != CURSOR_VERSION || bytes[1..4] != [0u8; 3] {
+        return None;
+    }
+    let mut position = [0u8; 4];
+    position.copy_from_slice(&bytes[4..]);
+    Some(u32::from_be_bytes(position))
+}
+
+/// Positions of the page of a listing that starts at `cursor` and holds at
+/// most `limit` entries, plus the cursor for the following page.
+///
+/// `positions` are the positions the listing currently holds; a cursor
+/// pointing before them (entries since dropped from a bounded history)
+/// resumes at the oldest one still held.  Returns `None` if `cursor` was not
+/// produced by [`encode_cursor`].
+pub fn page_bounds(
+    env: &Env,
+    cursor: Option<Cursor>,
+    limit: u32,
+    positions: Range<u32>,
+) -> Option<(Range<u32>, Option<Cursor>)> {
+    let start = match cursor {
+        Some(cursor) => decode_cursor(&cursor)?.clamp(positions.start, positions.end),
+        None => positions.start,
+    };
+    let end = start.saturating_add(limit).min(positions.end);
+    let next_cursor = if end < positions.end {
+        Some(encode_cursor(env, end))
+    } else {
+        None
+    };
+    Some((start..end, next_cursor))
+}
+
+/// One page of addresses plus the cursor for the following page.
+///
+/// `next_cursor` is `None` once the listing is exhausted.
+#[cfg_attr(feature = "registry", contracttype)]
+#[cfg_attr(not(feature = "registry"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AddressPage {
+    pub items: Vec<Address>,
+    pub next_cursor: Option<Cursor>,
+}
+
+/// One page of recorded payments plus the cursor for the following page.
+#[cfg_attr(feature = "views", contracttype)]
+#[cfg_attr(not(feature = "views"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentPage {
+    pub items: Vec<Payment>,
+    pub next_cursor: Option<Cursor>,
+}
+
+/// One page of reserve reclaim events plus the cursor for the following
+/// page.
+#[cfg_attr(feature = "history", contracttype)]
+#[cfg_attr(not(feature = "history"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveEventPage {
+    pub items: Vec<ReserveReclaimed>,
+    pub next_cursor: Option<Cursor>,
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, Vec};
+use soroban_sdk::{contracttype, Address, Bytes, BytesN, Env, Map, Symbol, Vec};
 
 // Represents a payment received by the ephemeral account.
 #[contracttype]
@@ -7,16 +7,69 @@ pub struct Payment {
     pub asset: Address,
     pub amount: i128,
     pub timestamp: u64,
+    /// Hash of the off-chain payer identity payload held by the PSP, for
+    /// travel-rule compliance.  Never the payload itself.
+    pub payer_info_hash: Option<BytesN<32>>,
+    /// Address the payment came from, when the recorder supplied it.
+    /// `refund_payment` sends the payment back here.
+    pub sender: Option<Address>,
+    /// Decimals reported by the asset contract when the asset was first
+    /// paid, so `amount` can be read as `amount / 10^decimals` units.
+    /// `None` if the asset did not report them.
+    pub decimals: Option<u32>,
+    /// Opaque reference supplied by the recorder (e.g. a hashed invoice
+    /// id), for matching the payment to an order.  A top-up keeps the
+    /// first recorded memo.
+    pub memo: Option<BytesN<32>>,
 }
 // The current status of an ephemeral account.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 #[repr(u32)]
 pub enum AccountStatus {
     Active = 0,
     PaymentReceived = 1,
     Swept = 2,
     Expired = 3,
+    /// Closed by the creator before any payment arrived.
+    Cancelled = 4,
+    /// Swept in escrow mode; funds are held until `finalize_sweep` or
+    /// `dispute`.
+    PendingRelease = 5,
+    /// Recovered by the guardian with `emergency_recover` before expiry;
+    /// funds went to the recovery address.
+    EmergencyRecovered = 6,
+}
+
+impl AccountStatus {
+    /// Whether the account reached a final state and accepts no further
+    /// sweeps or configuration changes.
+    pub fn is_closed(&self) -> bool {
+        matches!(
+            self,
+            AccountStatus::Swept
+                | AccountStatus::Expired
+                | AccountStatus::Cancelled
+                | AccountStatus::EmergencyRecovered
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for AccountStatus {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(match self {
+            AccountStatus::Active => "active",
+            AccountStatus::PaymentReceived => "payment_received",
+            AccountStatus::Swept => "swept",
+            AccountStatus::Expired => "expired",
+            AccountStatus::Cancelled => "cancelled",
+            AccountStatus::PendingRelease => "pending_release",
+            AccountStatus::EmergencyRecovered => "emergency_recovered",
+        })
+    }
 }
 
 /// Account information structure
@@ -31,4 +84,507 @@ pub struct AccountInfo {
     pub payment_count: u32,
     pub payments: Vec<Payment>,
     pub swept_to: Option<Address>,
+    /// Version of the account contract code the account was initialized
+    /// with, or last migrated to after an upgrade (`0` for accounts created
+    /// before versioning and not touched since).
+    pub wasm_version: u32,
+    /// Schema version of the `AccountConfig` accepted at initialization
+    /// (`0` for accounts created before versioning).
+    pub config_version: u32,
+}
+
+/// Every value `ReserveContract` configures plus the epoch it belongs to,
+/// read atomically.
+///
+/// Consumers that need more than one parameter should read a snapshot
+/// instead of issuing separate getter calls, which could straddle an
+/// update and observe a mixture of old and new values.
+#[cfg_attr(feature = "reserve", contracttype)]
+#[cfg_attr(not(feature = "reserve"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveSnapshot {
+    pub base_reserve: Option<i128>,
+    pub sponsorship_budget: Option<i128>,
+    pub trustline_reserve: Option<i128>,
+    pub keeper_bounty_bps: Option<i128>,
+    pub epoch: u32,
+}
+
+/// One reserve reclaim, as published in the `reserve` event and kept in
+/// the account's reclaim history.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveReclaimed {
+    pub destination: Address,
+    pub amount: i128,
+    /// Id of the sweep or closure the reclaim belongs to.
+    pub sweep_id: BytesN<32>,
+    pub fully_reclaimed: bool,
+    pub remaining_reserve: i128,
+    /// Caller paid the keeper bounty, if one was due.
+    pub keeper: Option<Address>,
+    /// Part of `amount` paid to `keeper` rather than `destination`.
+    pub keeper_bounty: i128,
+}
+
+/// Everything an account recorded, in one read, for compliance exports.
+///
+/// Replaces stitching together `get_info`, the reserve getters and event
+/// scans.  `reclaim_events` is the account's bounded reclaim history, the
+/// most recent events oldest first; the totals cover every reclaim.
+#[cfg_attr(feature = "audit", contracttype)]
+#[cfg_attr(not(feature = "audit"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditTrail {
+    /// Ledger the trail was read at.
+    pub ledger: u32,
+
+    // Initialization
+    pub creator: Address,
+    pub recovery_address: Address,
+    pub expiry_ledger: u32,
+    pub grace_ledgers: u32,
+    pub config_version: u32,
+    pub wasm_version: u32,
+    pub storage_version: u32,
+
+    // Payments, in recording order, swept ones included
+    pub payments: Vec<Payment>,
+    /// Assets already moved by a partial sweep.
+    pub swept_assets: Vec<Address>,
+    pub payments_root: BytesN<32>,
+
+    // Outcome
+    pub status: AccountStatus,
+    /// Where the funds went: sweep destination, recovery address or, for
+    /// a cancelled account, the creator.
+    pub swept_to: Option<Address>,
+    pub last_sweep_id: Option<BytesN<32>>,
+
+    // Reserve
+    pub reserve_remaining: i128,
+    pub reserve_available: i128,
+    pub reserve_reclaimed: bool,
+    pub total_reclaimed: i128,
+    pub reclaim_events: Vec<ReserveReclaimed>,
+
+    // Counters
+    pub payment_count: u32,
+    pub sweep_nonce: u64,
+    pub reclaim_count: u32,
+    pub reclaim_event_count: u32,
+}
+
+/// Snapshot of an account's key state at one ledger.
+///
+/// `hash` commits to every other field, so the sweep signer can sign it
+/// off-chain and hand out a receipt anyone can check against the chain.
+#[cfg_attr(feature = "audit", contracttype)]
+#[cfg_attr(not(feature = "audit"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StateAttestation {
+    pub account: Address,
+    pub ledger: u32,
+    pub timestamp: u64,
+    pub status: AccountStatus,
+    pub expiry_ledger: u32,
+    pub payment_count: u32,
+    pub payments_root: BytesN<32>,
+    pub swept_to: Option<Address>,
+    pub sweep_nonce: u64,
+    pub hash: BytesN<32>,
+}
+
+/// Optional features enabled on one ephemeral account.
+///
+/// Lets tooling handle a fleet whose accounts were initialized with
+/// different configs and code versions without probing each setting.
+#[cfg_attr(feature = "views", contracttype)]
+#[cfg_attr(not(feature = "views"), contracttype(export = false))]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Capabilities {
+    /// Version of the account contract code, which fixes the available
+    /// entrypoints.
+    pub wasm_version: u32,
+    /// Sweeps need more than one ed25519 signer.
+    pub multisig: bool,
+    /// Sweeps are authorized by a secp256r1 passkey.
+    pub passkey: bool,
+    /// A protocol fee is taken on sweep.
+    pub fees: bool,
+    /// Only allowlisted assets are accepted.
+    pub allowlist: bool,
+    /// Sweeps are held for release or dispute.
+    pub escrow: bool,
+    /// The sweep destination is fixed.
+    pub locked_destination: bool,
+    /// Sweeps wait for a minimum amount or a maximum wait.
+    pub batch_window: bool,
+    /// Repeated payments in one asset are added up.
+    pub accumulate_payments: bool,
+    /// State-changing calls consult a kill switch.
+    pub kill_switch: bool,
+    /// Sweeps are refused below an oracle-priced minimum value.
+    pub min_sweep_value: bool,
+    /// Sweeps leave out dust for a later `sweep_dust`.
+    pub dust_sweep: bool,
+    /// Sweeps of a stablecoin off its peg wait for the guardian.
+    pub depeg_guard: bool,
+    /// A guardian can recover the funds before expiry.
+    pub emergency_recovery: bool,
+    /// Only named parties may expire the account or reclaim its reserve.
+    pub restricted_auth: bool,
+    /// A hook contract is notified when the account settles.
+    pub settlement_hook: bool,
+    /// The factory can reset the settled account for re-use.
+    pub reusable: bool,
+    /// Failed sweep transfers are quarantined instead of reverting.
+    pub quarantine: bool,
+}
+
+/// Ties an account to the merchant and order it was opened for, so
+/// indexers can attribute it without an off-chain lookup.
+#[cfg_attr(feature = "config", contracttype)]
+#[cfg_attr(not(feature = "config"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountMetadata {
+    /// Merchant the account collects for, e.g. a hash of its id.
+    pub merchant_id: BytesN<32>,
+    /// Invoice or order reference, carried in lifecycle events.
+    pub reference: BytesN<32>,
+    /// Free-form label for grouping accounts.
+    pub tag: Symbol,
+}
+
+/// Optional `AccountMetadata` usable as a contract type field, like
+/// [`ChainLink`].
+#[cfg_attr(feature = "config", contracttype)]
+#[cfg_attr(not(feature = "config"), contracttype(export = false))]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum AccountLabel {
+    #[default]
+    Unlabeled,
+    Labeled(AccountMetadata),
+}
+
+impl AccountLabel {
+    pub fn into_option(self) -> Option<AccountMetadata> {
+        match self {
+            AccountLabel::Unlabeled => None,
+            AccountLabel::Labeled(metadata) => Some(metadata),
+        }
+    }
+}
+
+/// Optional settings supplied when an ephemeral account is initialized.
+///
+/// Every field defaults to the behaviour of a plain `initialize` call, so
+/// integrators only set what they need.
+#[cfg_attr(feature = "config", contracttype)]
+#[cfg_attr(not(feature = "config"), contracttype(export = false))]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AccountConfig {
+    /// Emit a diagnostic event (function, error code, key parameters)
+    /// before a state-changing call returns a validation error.  The event
+    /// rolls back with the failed call: simulation reports it among its
+    /// diagnostic events, but it never reaches the ledger.
+    pub debug_events: bool,
+    /// Counterpart leg of a cross-chain settlement funded by this account.
+    pub external_chain_ref: ChainLink,
+    /// Ed25519 public key whose signature authorizes `sweep`.  Can also be
+    /// set later by the creator.
+    pub sweep_signer: Option<BytesN<32>>,
+    /// Ed25519 public keys of an M-of-N sweep signer set.  Takes precedence
+    /// over `sweep_signer`.
+    pub sweep_signers: Option<Vec<BytesN<32>>>,
+    /// Number of `sweep_signers` that must sign, M.  Ignored without
+    /// `sweep_signers`.
+    pub sweep_threshold: u32,
+    /// Signature scheme used to authorize sweeps.
+    pub signer_kind: SignerKind,
+    /// Uncompressed SEC1 secp256r1 public key (e.g. a WebAuthn passkey)
+    /// authorizing sweeps.  Required when `signer_kind` is `Secp256r1`,
+    /// ignored otherwise.
+    pub passkey_signer: Option<BytesN<65>>,
+    /// `ReserveContract` to read the base reserve from.  Takes precedence
+    /// over `base_reserve`.
+    pub reserve_contract: Option<Address>,
+    /// Explicit base reserve (stroops), used only when no
+    /// `reserve_contract` is supplied.
+    pub base_reserve: Option<i128>,
+    /// Trustlines the account is opened with.  With a `reserve_contract`
+    /// the tracked reserve includes the per-trustline reserve for each.
+    pub trustlines: u32,
+    /// Add a repeated payment in an already paid asset to the recorded
+    /// amount instead of rejecting it, for payers paying in installments.
+    pub accumulate_payments: bool,
+    /// A sweep transfer that fails leaves its asset quarantined in the
+    /// account while the other assets settle, instead of reverting the
+    /// whole sweep.  `retry_quarantined` sends it on later.
+    pub quarantine_failed_transfers: bool,
+    /// `KillSwitch` contract consulted before every state-changing call.
+    pub kill_switch: Option<Address>,
+    /// Enables escrow mode: `sweep` only schedules the release, and this
+    /// address may `dispute` it to redirect funds to the recovery address.
+    pub escrow_arbiter: Option<Address>,
+    /// Ledgers a sweep is held for in escrow mode before it can be
+    /// finalized.  Ignored without an `escrow_arbiter`.
+    pub hold_ledgers: u32,
+    /// Ledgers a sweep is held for when it moves a payment in one of these
+    /// assets, for regulated assets whose issuer can claw funds back.
+    /// Works with or without escrow mode; the longest hold applies.
+    pub clawback_hold_ledgers: Option<Map<Address, u32>>,
+    /// Address receiving the protocol fee taken from every swept payment.
+    pub fee_collector: Option<Address>,
+    /// Protocol fee in basis points of each swept payment, at most 10 000.
+    /// Ignored without a `fee_collector`.
+    pub fee_bps: u32,
+    /// Sweeps are refused until the recorded payments add up to this raw
+    /// amount (summed across assets), so micro-payments settle in batches.
+    /// `0` disables the batching window.
+    pub batch_min_amount: i128,
+    /// Ledgers after the first payment at which sweeps are allowed even
+    /// below `batch_min_amount`.  `0` means no time limit.
+    pub batch_max_wait_ledgers: u32,
+    /// Only destination the account may be swept to, for flows where the
+    /// merchant is known up front.
+    pub locked_destination: Option<Address>,
+    /// Co-signer, with the creator, of a change to `locked_destination`.
+    /// Without a guardian the locked destination is final.
+    pub guardian: Option<Address>,
+    /// Ledgers between proposing a destination change and applying it.
+    pub destination_change_delay: u32,
+    /// Ledgers after initialization from which the guardian may
+    /// `emergency_recover` the funds to the recovery address, for when the
+    /// creator and sweep keys are lost.  Must end before `expiry_ledger`.
+    /// `0` disables emergency recovery; ignored without a `guardian`.
+    pub emergency_delay_ledgers: u32,
+    /// First topic of every event the account publishes, identifying the
+    /// deployment to indexers shared by several.  Stamped by the factory.
+    pub event_prefix: Option<Symbol>,
+    /// Ledgers after `expiry_ledger` during which a late sweep is still
+    /// accepted and `expire` is refused.
+    pub grace_ledgers: u32,
+    /// SEP-40 price oracle valuing the outstanding payments against
+    /// `min_sweep_value`.
+    pub price_oracle: Option<Address>,
+    /// `sweep` is refused while the outstanding payments are worth less
+    /// than this, so dust is not swept at a loss.  Denominated in the
+    /// oracle's quote asset and scaled like its prices.  `sweep_forced`
+    /// bypasses it.  `0` disables the check; ignored without a
+    /// `price_oracle`.
+    pub min_sweep_value: i128,
+    /// `sweep` leaves out each asset the oracle prices below this and
+    /// holds it for a later `sweep_dust`, so dust does not delay or add to
+    /// the cost of the settlement.  Scaled like `min_sweep_value`.  `0`
+    /// disables it; ignored without a `price_oracle`.
+    pub dust_threshold: i128,
+    /// Stablecoins a sweep refuses to settle while the oracle prices them
+    /// more than `depeg_threshold_bps` away from their peg, each mapped to
+    /// its peg in the oracle's price scale.  The guardian can
+    /// `override_depeg`.  Ignored without a `price_oracle`.
+    pub depeg_pegs: Option<Map<Address, i128>>,
+    /// Largest deviation from peg, in basis points of the peg, at which a
+    /// guarded stablecoin is still swept.
+    pub depeg_threshold_bps: u32,
+    /// Merchant, reference and tag of the account, read back with
+    /// `get_metadata`.
+    pub metadata: AccountLabel,
+    /// Who may `expire` the account and `reclaim_reserve`.
+    pub auth_policy: AuthPolicy,
+    /// Service allowed, with the creator and the recovery address, to
+    /// drive those transitions.  Ignored under `AuthPolicy::Permissionless`.
+    pub keeper: Option<Address>,
+    /// Basis points of the reclaimed reserve paid to whoever closes the
+    /// account through `expire_as` or `reclaim_reserve_as`.  Unset falls
+    /// back to the `reserve_contract`'s keeper bounty, else none.
+    pub keeper_bounty_bps: Option<u32>,
+    /// Cap the keeper bounty of `expire_as` rises to while the account
+    /// stays unexpired past its grace period, so even a low-value account
+    /// eventually pays a keeper to close it.  At most 10 000 and no lower
+    /// than the keeper bounty.  Unset keeps the bounty flat.
+    pub keeper_bounty_max_bps: Option<u32>,
+    /// Ledgers past the end of the grace period over which the bounty
+    /// rises linearly to `keeper_bounty_max_bps`.  `0` pays the cap as
+    /// soon as the account can be expired.
+    pub keeper_bounty_ramp_ledgers: u32,
+    /// Contract implementing `SettlementHookInterface`, called once the
+    /// account is swept or expired.  Its failures do not block settlement.
+    pub settlement_hook: Option<Address>,
+    /// Contract implementing `ComplianceGateInterface`, told of every
+    /// recorded payment and asked before every sweep.
+    pub compliance_gate: Option<Address>,
+    /// Contract allowed to `reset` the account for re-use once it is
+    /// settled.  Stamped by the factory.
+    pub factory: Option<Address>,
+}
+
+/// Settings a factory stamps into every account it deploys, taken by
+/// `initialize_with_deployment`.  Unlike an [`AccountConfig`] they need no
+/// `config` feature in the account build, so any account wasm a factory
+/// deploys honours them.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DeploymentSettings {
+    /// Contract allowed to `reset` the account for re-use once it is
+    /// settled, as [`AccountConfig::factory`].
+    pub factory: Option<Address>,
+    /// `KillSwitch` contract consulted before every state-changing call,
+    /// as [`AccountConfig::kill_switch`].
+    pub kill_switch: Option<Address>,
+    /// Leading topic of every event the account emits, as
+    /// [`AccountConfig::event_prefix`].
+    pub event_prefix: Option<Symbol>,
+    /// Contract implementing `InsurancePoolInterface` the factory paid the
+    /// account's premium into.  The account opens a claim with it if it
+    /// expires with payments in it.  `AccountConfig` has no counterpart:
+    /// it is at Soroban's 40-field limit for a contract type.
+    pub insurance_pool: Option<Address>,
+}
+
+/// Signature scheme of an account's sweep authorization.
+#[cfg_attr(any(feature = "config", feature = "views"), contracttype)]
+#[cfg_attr(
+    not(any(feature = "config", feature = "views")),
+    contracttype(export = false)
+)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[repr(u32)]
+pub enum SignerKind {
+    /// Ed25519 sweep signers (`sweep_signer` / `sweep_signers`).
+    #[default]
+    Ed25519 = 0,
+    /// A single secp256r1 key (`passkey_signer`).
+    Secp256r1 = 1,
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for SignerKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(match self {
+            SignerKind::Ed25519 => "ed25519",
+            SignerKind::Secp256r1 => "secp256r1",
+        })
+    }
+}
+
+/// Who may drive the transitions that need no signature of their own:
+/// `expire` and `reclaim_reserve`.
+#[cfg_attr(feature = "config", contracttype)]
+#[cfg_attr(not(feature = "config"), contracttype(export = false))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[repr(u32)]
+pub enum AuthPolicy {
+    /// Anyone, once the transition's conditions hold.
+    #[default]
+    Permissionless = 0,
+    /// Only the creator, the recovery address or the keeper, naming
+    /// themselves through `expire_as` / `reclaim_reserve_as`.
+    Restricted = 1,
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for AuthPolicy {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(match self {
+            AuthPolicy::Permissionless => "permissionless",
+            AuthPolicy::Restricted => "restricted",
+        })
+    }
+}
+
+/// Reference to a settlement leg on another chain.
+#[cfg_attr(feature = "config", contracttype)]
+#[cfg_attr(not(feature = "config"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExternalChainRef {
+    /// Identifier of the counterpart chain (e.g. an EVM chain id).
+    pub chain_id: u64,
+    /// Transaction or commitment hash of the counterpart leg.
+    pub commitment: BytesN<32>,
+}
+
+/// Optional `ExternalChainRef` usable as a contract type field.
+///
+/// `Option<ExternalChainRef>` cannot be nested inside another contract
+/// type, so the absent case is an explicit variant.
+#[cfg_attr(feature = "config", contracttype)]
+#[cfg_attr(not(feature = "config"), contracttype(export = false))]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum ChainLink {
+    #[default]
+    Unlinked,
+    Linked(ExternalChainRef),
+}
+
+impl ChainLink {
+    pub fn into_option(self) -> Option<ExternalChainRef> {
+        match self {
+            ChainLink::Unlinked => None,
+            ChainLink::Linked(chain_ref) => Some(chain_ref),
+        }
+    }
+}
+
+/// XDR encoding of `Asset::Native`: just the `ASSET_TYPE_NATIVE`
+/// discriminant.
+const NATIVE_ASSET_XDR: [u8; 4] = [0, 0, 0, 0];
+
+/// How an asset held by an account is represented on-chain.
+///
+/// Token payments are plain contract balances.  Native XLM also funds the
+/// account's base reserve, so its balance is shared between payments and
+/// the reserve and has to be netted against it.
+#[cfg_attr(feature = "views", contracttype)]
+#[cfg_attr(not(feature = "views"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AssetKind {
+    /// Lumens, moved through the network's native Stellar Asset Contract.
+    Native,
+    /// Any other token contract.
+    Contract(Address),
+}
+
+impl AssetKind {
+    /// Address of the native Stellar Asset Contract on the current network.
+    pub fn native_address(env: &Env) -> Address {
+        env.deployer()
+            .with_stellar_asset(Bytes::from_array(env, &NATIVE_ASSET_XDR))
+            .deployed_address()
+    }
+
+    /// Token contract to call for this asset.
+    pub fn address(&self, env: &Env) -> Address {
+        match self {
+            AssetKind::Native => Self::native_address(env),
+            AssetKind::Contract(asset) => asset.clone(),
+        }
+    }
+}
+
+/// Asset identifier of a SEP-40 price oracle.
+#[cfg_attr(feature = "oracle", contracttype)]
+#[cfg_attr(not(feature = "oracle"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OracleAsset {
+    /// A Stellar asset, by its token contract address.
+    Stellar(Address),
+    /// An asset outside Stellar, by ticker.
+    Other(Symbol),
+}
+
+/// A SEP-40 price quote.
+#[cfg_attr(feature = "oracle", contracttype)]
+#[cfg_attr(not(feature = "oracle"), contracttype(export = false))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    /// Price of one whole unit of the asset in the oracle's quote asset,
+    /// scaled by `10^decimals()`.
+    pub price: i128,
+    /// Unix time of the quote, in seconds.
+    pub timestamp: u64,
 }
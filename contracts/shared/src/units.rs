@@ -0,0 +1,54 @@
+/// Stroops in one XLM.
+pub const STROOPS_PER_XLM: i128 = 10_000_000;
+
+/// Base reserve an account tracks when neither a reserve contract nor an
+/// explicit amount is configured: 100 XLM.
+pub const DEFAULT_BASE_RESERVE_STROOPS: i128 = 100 * STROOPS_PER_XLM;
+
+/// Decimals of the native Stellar Asset Contract: one stroop is `10^-7` XLM.
+pub const XLM_DECIMALS: u32 = 7;
+
+/// Basis points in a whole: fee rates and split shares are out of this.
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+/// `xlm` whole XLM in stroops, or `None` on overflow.
+pub fn xlm_to_stroops(xlm: i128) -> Option<i128> {
+    xlm.checked_mul(STROOPS_PER_XLM)
+}
+
+/// Split `stroops` into whole XLM and the stroops left over.
+///
+/// Both parts carry the sign of `stroops`, so
+/// `xlm * STROOPS_PER_XLM + remainder == stroops` always holds.
+pub fn stroops_to_xlm(stroops: i128) -> (i128, i128) {
+    (stroops / STROOPS_PER_XLM, stroops % STROOPS_PER_XLM)
+}
+
+/// `10^decimals`, or `None` if it does not fit an `i128`.
+pub fn pow10(decimals: u32) -> Option<i128> {
+    10i128.checked_pow(decimals)
+}
+
+/// Re-express `amount` of a token with `from_decimals` decimals in
+/// `to_decimals` decimals.
+///
+/// Scaling down rounds toward zero.  `None` if the result or the scale
+/// factor overflows.
+pub fn scale_decimals(amount: i128, from_decimals: u32, to_decimals: u32) -> Option<i128> {
+    if to_decimals >= from_decimals {
+        amount.checked_mul(pow10(to_decimals - from_decimals)?)
+    } else {
+        Some(amount / pow10(from_decimals - to_decimals)?)
+    }
+}
+
+/// Whether `bps` is a valid rate, at most [`BPS_DENOMINATOR`].
+pub fn is_valid_bps(bps: u32) -> bool {
+    bps <= BPS_DENOMINATOR
+}
+
+/// `bps` basis points of `amount`, rounded toward zero, or `None` on
+/// overflow.
+pub fn bps_of(amount: i128, bps: u32) -> Option<i128> {
+    Some(amount.checked_mul(bps as i128)? / BPS_DENOMINATOR as i128)
+}
@@ -0,0 +1,36 @@
+use soroban_sdk::contracterror;
+
+/// Codes sit in [`bridgelet_shared::errors::SWAP_ADAPTER_ERRORS`], so
+/// they never collide with another Bridgelet contract's.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// [`SwapAdapter::initialize`] was called more than once.
+    AlreadyInitialized = 1001,
+
+    /// A swap or quote was attempted before initialization.
+    NotInitialized = 1002,
+
+    /// The amount to sell is not positive, or `min_out` is negative.
+    InvalidAmount = 1003,
+
+    /// `asset_in` and `asset_out` are the same asset.
+    SameAsset = 1004,
+
+    /// The router delivered less than `min_out`.
+    SlippageExceeded = 1005,
+}
+
+impl Error {
+    /// Name of the variant, as `describe_error` reports it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Error::AlreadyInitialized => "AlreadyInitialized",
+            Error::NotInitialized => "NotInitialized",
+            Error::InvalidAmount => "InvalidAmount",
+            Error::SameAsset => "SameAsset",
+            Error::SlippageExceeded => "SlippageExceeded",
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use bridgelet_shared::EventTopics;
+use soroban_sdk::{contracttype, symbol_short, Address, Env};
+
+// ─── Event payloads ─────────────────────────────────────────────────────────
+
+/// Emitted when a swap completes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Swapped {
+    pub from: Address,
+    pub asset_in: Address,
+    pub asset_out: Address,
+    pub amount_in: i128,
+    pub amount_out: i128,
+}
+
+/// Emitted when the admin points the adapter at another router.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RouterSet {
+    pub router: Address,
+}
+
+// ─── Emit helpers ───────────────────────────────────────────────────────────
+
+/// Publish the `swapped` event, indexed by the seller.
+pub fn emit_swapped(env: &Env, swapped: Swapped) {
+    EventTopics::new(symbol_short!("swapped")).publish_indexed(env, swapped.from.clone(), swapped);
+}
+
+/// Publish the `router` event.
+pub fn emit_router_set(env: &Env, router: Address) {
+    EventTopics::new(symbol_short!("router")).publish(env, RouterSet { router });
+}
@@ -0,0 +1,183 @@
+#![no_std]
+
+mod errors;
+mod events;
+mod router;
+mod storage;
+#[cfg(test)]
+mod test;
+
+use bridgelet_shared::errors::UNKNOWN_ERROR;
+use soroban_sdk::{contract, contractimpl, vec, Address, Env, Symbol, Vec};
+
+pub use errors::Error;
+pub use events::{RouterSet, Swapped};
+pub use router::{SoroswapRouterClient, SoroswapRouterInterface};
+pub use storage::DataKey;
+
+use router::SoroswapRouterClient as RouterClient;
+
+/// [`bridgelet_shared::SwapAdapterInterface`] backed by the Soroswap
+/// router.
+///
+/// Gives a sweep that converts the assets it collects a first-party
+/// routing target: the caller names the asset pair, the amount and the
+/// least it accepts, and the adapter routes a direct swap through the
+/// configured router.  The router moves the funds straight between the
+/// seller and the pool, so the adapter holds no balances and needs no
+/// trustlines; its only state is the admin and the router address.
+#[contract]
+pub struct SwapAdapter;
+
+#[contractimpl]
+impl SwapAdapter {
+    /// One-time initialization that sets the admin and the router.
+    ///
+    /// # Errors
+    /// * [`Error::AlreadyInitialized`] – called more than once.
+    pub fn initialize(env: Env, admin: Address, router: Address) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        if storage::has_admin(&env) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+        storage::set_admin(&env, &admin);
+        storage::set_router(&env, &router);
+
+        Ok(())
+    }
+
+    /// Route later swaps through `router`.  Admin-only.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    pub fn set_router(env: Env, router: Address) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        storage::set_router(&env, &router);
+        events::emit_router_set(&env, router);
+
+        Ok(())
+    }
+
+    /// Sell exactly `amount` of `asset_in` held by `from` for at least
+    /// `min_out` of `asset_out`, paid to `from`.
+    ///
+    /// Requires `from`'s authorization, which also covers the router
+    /// pulling `amount` from it.  Returns the amount of `asset_out`
+    /// received.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::InvalidAmount`] – `amount` is not positive or `min_out`
+    ///   is negative.
+    /// * [`Error::SameAsset`] – `asset_in` equals `asset_out`.
+    /// * [`Error::SlippageExceeded`] – the router delivered less than
+    ///   `min_out`.
+    pub fn swap_exact_in(
+        env: Env,
+        from: Address,
+        asset_in: Address,
+        asset_out: Address,
+        amount: i128,
+        min_out: i128,
+    ) -> Result<i128, Error> {
+        storage::extend_instance_ttl(&env);
+
+        let router = storage::get_router(&env).ok_or(Error::NotInitialized)?;
+        Self::check_swap(&asset_in, &asset_out, amount)?;
+        if min_out < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        from.require_auth();
+
+        // Executes in this ledger or not at all
+        let path = Self::path(&env, &asset_in, &asset_out);
+        let deadline = env.ledger().timestamp();
+        let amounts = RouterClient::new(&env, &router)
+            .swap_exact_tokens_for_tokens(&amount, &min_out, &path, &from, &deadline);
+        let amount_out = amounts.last().unwrap_or(0);
+        if amount_out < min_out {
+            return Err(Error::SlippageExceeded);
+        }
+
+        events::emit_swapped(
+            &env,
+            Swapped {
+                from,
+                asset_in,
+                asset_out,
+                amount_in: amount,
+                amount_out,
+            },
+        );
+
+        Ok(amount_out)
+    }
+
+    /// Amount of `asset_out` that `amount` of `asset_in` would buy now.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    /// * [`Error::InvalidAmount`] – `amount` is not positive.
+    /// * [`Error::SameAsset`] – `asset_in` equals `asset_out`.
+    pub fn quote_exact_in(
+        env: Env,
+        asset_in: Address,
+        asset_out: Address,
+        amount: i128,
+    ) -> Result<i128, Error> {
+        storage::extend_instance_ttl(&env);
+
+        let router = storage::get_router(&env).ok_or(Error::NotInitialized)?;
+        Self::check_swap(&asset_in, &asset_out, amount)?;
+
+        let path = Self::path(&env, &asset_in, &asset_out);
+        let amounts = RouterClient::new(&env, &router).router_get_amounts_out(&amount, &path);
+        Ok(amounts.last().unwrap_or(0))
+    }
+
+    /// Returns the router swaps go through, if the contract has been
+    /// initialized.
+    pub fn get_router(env: Env) -> Option<Address> {
+        storage::extend_instance_ttl(&env);
+        storage::get_router(&env)
+    }
+
+    /// Returns the admin address, if the contract has been initialized.
+    pub fn get_admin(env: Env) -> Option<Address> {
+        storage::extend_instance_ttl(&env);
+        storage::get_admin(&env)
+    }
+
+    /// Return the name of the error raised as `Error(Contract, #code)`, or
+    /// `Unknown` for a code this contract never raises.
+    pub fn describe_error(env: Env, code: u32) -> Symbol {
+        let name = Error::try_from(soroban_sdk::Error::from_contract_error(code))
+            .map_or(UNKNOWN_ERROR, Error::name);
+        Symbol::new(&env, name)
+    }
+}
+
+impl SwapAdapter {
+    fn check_swap(asset_in: &Address, asset_out: &Address, amount: i128) -> Result<(), Error> {
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if asset_in == asset_out {
+            return Err(Error::SameAsset);
+        }
+        Ok(())
+    }
+
+    /// The direct route from `asset_in` to `asset_out`.
+    fn path(env: &Env, asset_in: &Address, asset_out: &Address) -> Vec<Address> {
+        vec![env, asset_in.clone(), asset_out.clone()]
+    }
+}
@@ -0,0 +1,23 @@
+use soroban_sdk::{contractclient, Address, Env, Vec};
+
+/// The part of the Soroswap router the adapter calls.
+///
+/// `path` lists the assets a swap goes through, first the one sold and
+/// last the one bought; the returned amounts follow the same order.  The
+/// router pulls the input from `to`, which must authorize the call, and
+/// pays the output to `to`, so the adapter never holds funds.  A swap
+/// the pools cannot fill for at least `amount_out_min`, or one past
+/// `deadline` (a ledger timestamp), fails in the router.
+#[contractclient(name = "SoroswapRouterClient")]
+pub trait SoroswapRouterInterface {
+    fn swap_exact_tokens_for_tokens(
+        env: Env,
+        amount_in: i128,
+        amount_out_min: i128,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<i128>;
+
+    fn router_get_amounts_out(env: Env, amount_in: i128, path: Vec<Address>) -> Vec<i128>;
+}
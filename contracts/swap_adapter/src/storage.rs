@@ -0,0 +1,53 @@
+use soroban_sdk::{contracttype, Address, Env};
+
+/// Storage keys used by the swap adapter.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// The admin address allowed to point the adapter at another router.
+    Admin,
+
+    /// The Soroswap router every swap goes through.
+    Router,
+}
+
+// Admin helpers
+
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
+
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Admin)
+}
+
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().instance().has(&DataKey::Admin)
+}
+
+// Router helpers
+
+pub fn set_router(env: &Env, router: &Address) {
+    env.storage().instance().set(&DataKey::Router, router);
+}
+
+pub fn get_router(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Router)
+}
+
+// TTL management
+
+/// If the remaining TTL drops below this threshold (in ledgers), extend it.
+/// 259 200 ledgers ≈ 15 days, half of the extension window.
+const TTL_THRESHOLD: u32 = 259_200;
+
+/// Extend TTLs to this many ledgers.
+/// 518 400 ledgers ≈ 30 days (at ~5 s per ledger).
+const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Keep the contract instance alive; called from every public entry-point.
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
@@ -0,0 +1,287 @@
+extern crate std;
+
+use crate::{Error, SwapAdapter, SwapAdapterClient, Swapped};
+use bridgelet_shared::errors::{SWAP_ADAPTER_ERRORS, UNKNOWN_ERROR};
+use bridgelet_shared::{EventTopics, SwapAdapterClient as GenericSwapClient};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Events},
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, Env, IntoVal, Symbol, TryFromVal, Vec,
+};
+
+// MOCK ROUTER
+
+/// Fills every swap from its own balance at a fixed rate, the way a
+/// Soroswap router backed by one pool would.  `set_skim` makes it pay
+/// out less than it quoted, past its own `amount_out_min` check.
+#[contract]
+struct MockRouter;
+
+#[contractimpl]
+impl MockRouter {
+    pub fn set_rate(env: Env, out_per_in: i128) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("rate"), &out_per_in);
+    }
+
+    pub fn set_skim(env: Env, skim: i128) {
+        env.storage().instance().set(&symbol_short!("skim"), &skim);
+    }
+
+    pub fn swap_exact_tokens_for_tokens(
+        env: Env,
+        amount_in: i128,
+        amount_out_min: i128,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<i128> {
+        to.require_auth();
+        assert!(deadline >= env.ledger().timestamp());
+        let quoted =
+            Self::router_get_amounts_out(env.clone(), amount_in, path.clone()).last_unchecked();
+        assert!(quoted >= amount_out_min, "insufficient output amount");
+
+        let skim: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("skim"))
+            .unwrap_or(0);
+        let amount_out = quoted - skim;
+        let router = env.current_contract_address();
+        TokenClient::new(&env, &path.first_unchecked()).transfer(&to, &router, &amount_in);
+        TokenClient::new(&env, &path.last_unchecked()).transfer(&router, &to, &amount_out);
+        vec![&env, amount_in, amount_out]
+    }
+
+    pub fn router_get_amounts_out(env: Env, amount_in: i128, path: Vec<Address>) -> Vec<i128> {
+        assert_eq!(path.len(), 2);
+        let rate: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("rate"))
+            .unwrap_or(1);
+        vec![&env, amount_in, amount_in * rate]
+    }
+}
+
+// HELPERS
+
+struct Setup {
+    env: Env,
+    client: SwapAdapterClient<'static>,
+    router: MockRouterClient<'static>,
+    seller: Address,
+    asset_in: Address,
+    asset_out: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let router_id = env.register(MockRouter, ());
+    let router = MockRouterClient::new(&env, &router_id);
+    router.set_rate(&2);
+
+    let asset_in = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let asset_out = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let seller = Address::generate(&env);
+    StellarAssetClient::new(&env, &asset_in).mint(&seller, &1_000);
+    StellarAssetClient::new(&env, &asset_out).mint(&router_id, &1_000_000);
+
+    let contract_id = env.register(SwapAdapter, ());
+    let client = SwapAdapterClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env), &router_id);
+
+    Setup {
+        env,
+        client,
+        router,
+        seller,
+        asset_in,
+        asset_out,
+    }
+}
+
+fn balance(env: &Env, asset: &Address, holder: &Address) -> i128 {
+    TokenClient::new(env, asset).balance(holder)
+}
+
+//  Initialization
+
+#[test]
+fn test_initialize_stores_admin_and_router() {
+    let s = setup();
+    assert!(s.client.get_admin().is_some());
+    assert_eq!(s.client.get_router(), Some(s.router.address.clone()));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1001)")]
+fn test_initialize_twice_panics() {
+    let s = setup();
+    s.client
+        .initialize(&Address::generate(&s.env), &s.router.address);
+}
+
+#[test]
+fn test_set_router_replaces_router() {
+    let s = setup();
+    let router = Address::generate(&s.env);
+    s.client.set_router(&router);
+    assert_eq!(s.client.get_router(), Some(router));
+}
+
+//  Swaps
+
+#[test]
+fn test_swap_exact_in_pays_seller_through_router() {
+    let s = setup();
+
+    let amount_out = s
+        .client
+        .swap_exact_in(&s.seller, &s.asset_in, &s.asset_out, &100, &150);
+
+    assert_eq!(amount_out, 200);
+    assert_eq!(balance(&s.env, &s.asset_in, &s.seller), 900);
+    assert_eq!(balance(&s.env, &s.asset_out, &s.seller), 200);
+    // Funds move between the seller and the router only
+    assert_eq!(balance(&s.env, &s.asset_in, &s.client.address), 0);
+    assert_eq!(balance(&s.env, &s.asset_out, &s.client.address), 0);
+}
+
+#[test]
+fn test_swap_exact_in_requires_seller_auth() {
+    let s = setup();
+    s.client
+        .swap_exact_in(&s.seller, &s.asset_in, &s.asset_out, &100, &0);
+
+    let auths = s.env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, s.seller);
+}
+
+#[test]
+fn test_swap_exact_in_emits_swapped() {
+    let s = setup();
+    s.client
+        .swap_exact_in(&s.seller, &s.asset_in, &s.asset_out, &100, &0);
+
+    let expected = Swapped {
+        from: s.seller.clone(),
+        asset_in: s.asset_in.clone(),
+        asset_out: s.asset_out.clone(),
+        amount_in: 100,
+        amount_out: 200,
+    };
+    let topics = EventTopics::new(symbol_short!("swapped"));
+    let event = s.env.events().all().last().unwrap();
+    assert_eq!(event.0, s.client.address);
+    assert_eq!(
+        event.1,
+        (
+            topics.namespace,
+            topics.name,
+            topics.version,
+            s.seller.clone()
+        )
+            .into_val(&s.env)
+    );
+    assert_eq!(Swapped::try_from_val(&s.env, &event.2).unwrap(), expected);
+}
+
+/// Callers that only know the generic interface reach the adapter too.
+#[test]
+fn test_generic_client_quotes_and_swaps() {
+    let s = setup();
+    let adapter = GenericSwapClient::new(&s.env, &s.client.address);
+
+    let quote = adapter.quote_exact_in(&s.asset_in, &s.asset_out, &100);
+    let amount_out = adapter.swap_exact_in(&s.seller, &s.asset_in, &s.asset_out, &100, &quote);
+    assert_eq!(quote, amount_out);
+}
+
+#[test]
+fn test_swap_rejects_invalid_amounts() {
+    let s = setup();
+    let swap = |amount: i128, min_out: i128| {
+        s.client
+            .try_swap_exact_in(&s.seller, &s.asset_in, &s.asset_out, &amount, &min_out)
+    };
+
+    assert_eq!(swap(0, 0), Err(Ok(Error::InvalidAmount)));
+    assert_eq!(swap(-1, 0), Err(Ok(Error::InvalidAmount)));
+    assert_eq!(swap(100, -1), Err(Ok(Error::InvalidAmount)));
+    assert_eq!(
+        s.client.try_quote_exact_in(&s.asset_in, &s.asset_out, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_swap_rejects_same_asset() {
+    let s = setup();
+    assert_eq!(
+        s.client
+            .try_swap_exact_in(&s.seller, &s.asset_in, &s.asset_in, &100, &0),
+        Err(Ok(Error::SameAsset))
+    );
+}
+
+/// A router that pays less than it was told to accept does not slip the
+/// shortfall past the adapter.
+#[test]
+fn test_swap_below_min_out_fails() {
+    let s = setup();
+    s.router.set_skim(&1);
+    assert_eq!(
+        s.client
+            .try_swap_exact_in(&s.seller, &s.asset_in, &s.asset_out, &100, &200),
+        Err(Ok(Error::SlippageExceeded))
+    );
+    assert_eq!(balance(&s.env, &s.asset_in, &s.seller), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "insufficient output amount")]
+fn test_swap_the_pool_cannot_fill_fails_in_router() {
+    let s = setup();
+    s.client
+        .swap_exact_in(&s.seller, &s.asset_in, &s.asset_out, &100, &201);
+}
+
+#[test]
+fn test_swap_before_initialize_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = SwapAdapterClient::new(&env, &env.register(SwapAdapter, ()));
+    let asset = Address::generate(&env);
+    assert_eq!(
+        client.try_swap_exact_in(&Address::generate(&env), &asset, &asset, &1, &0),
+        Err(Ok(Error::NotInitialized))
+    );
+}
+
+/// Codes stay inside the adapter's shared range, so off-chain tooling can
+/// tell them from another contract's.
+#[test]
+fn test_error_codes_use_reserved_range() {
+    let env = Env::default();
+    let client = SwapAdapterClient::new(&env, &env.register(SwapAdapter, ()));
+    for code in SWAP_ADAPTER_ERRORS {
+        let name = client.describe_error(&code);
+        if let Ok(error) = Error::try_from(soroban_sdk::Error::from_contract_error(code)) {
+            assert_eq!(name, Symbol::new(&env, error.name()));
+        } else {
+            assert_eq!(name, Symbol::new(&env, UNKNOWN_ERROR));
+        }
+    }
+    assert_eq!(client.describe_error(&1), Symbol::new(&env, UNKNOWN_ERROR));
+}
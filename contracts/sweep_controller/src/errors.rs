@@ -1,18 +1,42 @@
 use soroban_sdk::contracterror;
 
+/// Codes sit in [`bridgelet_shared::errors::SWEEP_CONTROLLER_ERRORS`], so
+/// they never collide with another Bridgelet contract's.
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Error {
-    InvalidAccount = 1,
-    TransferFailed = 2,
-    AuthorizationFailed = 3,
-    InsufficientBalance = 4,
-    AccountNotReady = 5,
-    AccountExpired = 6,
-    AccountAlreadySwept = 7,
-    InvalidSignature = 8,
-    SignatureVerificationFailed = 9,
-    AuthorizedSignerNotSet = 10,
-    InvalidNonce = 11,
-    UnauthorizedDestination = 13,
+    InvalidAccount = 901,
+    TransferFailed = 902,
+    AuthorizationFailed = 903,
+    InsufficientBalance = 904,
+    AccountNotReady = 905,
+    AccountExpired = 906,
+    AccountAlreadySwept = 907,
+    InvalidSignature = 908,
+    SignatureVerificationFailed = 909,
+    AuthorizedSignerNotSet = 910,
+    InvalidNonce = 911,
+    UnauthorizedDestination = 913,
+    Halted = 914,
+}
+
+impl Error {
+    /// Name of the variant, as `describe_error` reports it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Error::InvalidAccount => "InvalidAccount",
+            Error::TransferFailed => "TransferFailed",
+            Error::AuthorizationFailed => "AuthorizationFailed",
+            Error::InsufficientBalance => "InsufficientBalance",
+            Error::AccountNotReady => "AccountNotReady",
+            Error::AccountExpired => "AccountExpired",
+            Error::AccountAlreadySwept => "AccountAlreadySwept",
+            Error::InvalidSignature => "InvalidSignature",
+            Error::SignatureVerificationFailed => "SignatureVerificationFailed",
+            Error::AuthorizedSignerNotSet => "AuthorizedSignerNotSet",
+            Error::InvalidNonce => "InvalidNonce",
+            Error::UnauthorizedDestination => "UnauthorizedDestination",
+            Error::Halted => "Halted",
+        }
+    }
 }
@@ -5,13 +5,19 @@ mod errors;
 mod storage;
 // mod transfers;
 
-use ephemeral_account::EphemeralAccountContractClient as EphemeralAccountClient;
-use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env};
+use bridgelet_shared::errors::UNKNOWN_ERROR;
+use kill_switch::KillSwitchClient;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, vec, Address, BytesN, Env, Symbol,
+};
 
 use authorization::AuthContext;
-use bridgelet_shared::AccountStatus;
+use bridgelet_shared::{AccountStatus, EphemeralAccountClient};
 pub use errors::Error;
 
+/// Subsystem this contract checks on the kill switch
+const KILL_SWITCH_SUBSYSTEM: Symbol = symbol_short!("sweeper");
+
 #[contract]
 pub struct SweepController;
 
@@ -70,12 +76,20 @@ impl SweepController {
     /// Returns Error::InvalidAccount if account is not in valid state
     /// Returns Error::TransferFailed if token transfer fails
     /// Returns Error::UnauthorizedDestination if destination doesn't match authorized destination (when set)
+    /// Returns Error::Halted if the kill switch halts the sweeper
     pub fn execute_sweep(
         env: Env,
         ephemeral_account: Address,
         destination: Address,
         auth_signature: BytesN<64>,
     ) -> Result<(), Error> {
+        // Refuse to sweep while the kill switch halts the sweeper
+        if let Some(kill_switch) = storage::get_kill_switch(&env) {
+            if KillSwitchClient::new(&env, &kill_switch).is_halted(&KILL_SWITCH_SUBSYSTEM) {
+                return Err(Error::Halted);
+            }
+        }
+
         // Validate destination if authorized destination is set (locked mode)
         if storage::has_authorized_destination(&env) {
             let authorized_dest =
@@ -96,12 +110,12 @@ impl SweepController {
         // Increment nonce after successful verification to prevent replay attacks
         authorization::increment_nonce(&env);
 
-        // Call ephemeral account contract to validate and authorize sweep
-        // This triggers the account's sweep() method which updates state
+        // Call ephemeral account contract to validate and execute the sweep
+        // This triggers the account's sweep() method, which moves the funds
         let account_client = EphemeralAccountClient::new(&env, &ephemeral_account);
 
-        // The account contract validates state and authorizes the sweep
-        account_client.sweep(&destination, &auth_signature);
+        // The account contract validates state and transfers every balance
+        account_client.sweep(&destination, &vec![&env, auth_signature]);
 
         // Get payment details from account
         let info = account_client.get_info();
@@ -144,6 +158,22 @@ impl SweepController {
             && !account_client.is_expired()
     }
 
+    /// Set the kill switch checked before every sweep
+    ///
+    /// # Arguments
+    /// * `kill_switch` - Address of the `KillSwitch` contract
+    ///
+    /// # Errors
+    /// Returns Error::AuthorizationFailed if the controller is not initialized
+    pub fn set_kill_switch(env: Env, kill_switch: Address) -> Result<(), Error> {
+        let creator = storage::get_creator(&env).ok_or(Error::AuthorizationFailed)?;
+        creator.require_auth();
+
+        storage::set_kill_switch(&env, &kill_switch);
+
+        Ok(())
+    }
+
     /// Update the authorized destination address
     ///
     /// This function allows the creator to update the authorized destination before any sweep occurs.
@@ -175,6 +205,14 @@ impl SweepController {
 
         Ok(())
     }
+
+    /// Return the name of the error raised as `Error(Contract, #code)`, or
+    /// `Unknown` for a code this contract never raises.
+    pub fn describe_error(env: Env, code: u32) -> Symbol {
+        let name = Error::try_from(soroban_sdk::Error::from_contract_error(code))
+            .map_or(UNKNOWN_ERROR, Error::name);
+        Symbol::new(&env, name)
+    }
 }
 
 /// Sweep completed event
@@ -12,6 +12,8 @@ pub enum DataKey {
     AuthorizedDestination,
     /// Creator address (the address that initialized the contract)
     Creator,
+    /// Kill switch consulted before every sweep (optional)
+    KillSwitch,
 }
 
 /// Set the authorized signer public key
@@ -125,3 +127,25 @@ pub fn set_creator(env: &Env, creator: &Address) {
 pub fn get_creator(env: &Env) -> Option<Address> {
     env.storage().instance().get(&DataKey::Creator)
 }
+
+/// Set the kill switch contract address
+///
+/// # Arguments
+/// * `env` - Soroban environment
+/// * `kill_switch` - Kill switch contract address
+pub fn set_kill_switch(env: &Env, kill_switch: &Address) {
+    env.storage()
+        .instance()
+        .set(&DataKey::KillSwitch, kill_switch);
+}
+
+/// Get the kill switch contract address
+///
+/// # Arguments
+/// * `env` - Soroban environment
+///
+/// # Returns
+/// The kill switch address, or None if sweeps are never halted
+pub fn get_kill_switch(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::KillSwitch)
+}
@@ -1,7 +1,9 @@
 #![cfg(test)]
 
+use bridgelet_shared::errors::{SWEEP_CONTROLLER_ERRORS, UNKNOWN_ERROR};
 use ephemeral_account::{AccountStatus, EphemeralAccountContract, EphemeralAccountContractClient};
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env};
+use kill_switch::{KillSwitch, KillSwitchClient};
+use soroban_sdk::{symbol_short, testutils::Address as _, Address, BytesN, Env, Symbol};
 use sweep_controller::Error;
 use sweep_controller::{SweepController, SweepControllerClient};
 
@@ -513,3 +515,44 @@ fn test_update_destination_before_sweep() {
     // For now, we just check it doesn't panic with UnauthorizedDestination
     // (In a real test, we'd check the panic message)
 }
+
+/// Test that sweeps are refused while the kill switch halts the sweeper
+#[test]
+fn test_execute_sweep_rejected_while_halted() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let controller_id = env.register(SweepController, ());
+    let controller_client = SweepControllerClient::new(&env, &controller_id);
+    controller_client.initialize(&BytesN::from_array(&env, &[1u8; 32]), &None);
+
+    let kill_switch_id = env.register(KillSwitch, ());
+    let kill_switch = KillSwitchClient::new(&env, &kill_switch_id);
+    kill_switch.initialize(&Address::generate(&env));
+    kill_switch.set_halted(&symbol_short!("sweeper"), &true);
+    controller_client.set_kill_switch(&kill_switch_id);
+
+    let result = controller_client.try_execute_sweep(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[0u8; 64]),
+    );
+    assert_eq!(result, Err(Ok(Error::Halted)));
+}
+
+/// Codes stay inside the controller's shared range, so off-chain tooling can
+/// tell them from another contract's.
+#[test]
+fn test_error_codes_use_reserved_range() {
+    let env = Env::default();
+    let client = SweepControllerClient::new(&env, &env.register(SweepController, ()));
+    for code in SWEEP_CONTROLLER_ERRORS {
+        let name = client.describe_error(&code);
+        if let Ok(error) = Error::try_from(soroban_sdk::Error::from_contract_error(code)) {
+            assert_eq!(name, Symbol::new(&env, error.name()));
+        } else {
+            assert_eq!(name, Symbol::new(&env, UNKNOWN_ERROR));
+        }
+    }
+    assert_eq!(client.describe_error(&1), Symbol::new(&env, UNKNOWN_ERROR));
+}
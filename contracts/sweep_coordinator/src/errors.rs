@@ -0,0 +1,37 @@
+use soroban_sdk::contracterror;
+
+/// Codes sit in [`bridgelet_shared::errors::SWEEP_COORDINATOR_ERRORS`], so
+/// they never collide with another Bridgelet contract's.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// More than [`MAX_BATCH_SIZE`](crate::MAX_BATCH_SIZE) accounts in one
+    /// batch.
+    BatchTooLarge = 701,
+
+    /// `auth` does not hold exactly one signature list per account.
+    LengthMismatch = 702,
+
+    /// `set_kill_switch` called before `initialize`.
+    NotInitialized = 703,
+
+    /// `initialize` called more than once.
+    AlreadyInitialized = 704,
+
+    /// The kill switch halts the `sweeper` subsystem.
+    Halted = 705,
+}
+
+impl Error {
+    /// Name of the variant, as `describe_error` reports it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Error::BatchTooLarge => "BatchTooLarge",
+            Error::LengthMismatch => "LengthMismatch",
+            Error::NotInitialized => "NotInitialized",
+            Error::AlreadyInitialized => "AlreadyInitialized",
+            Error::Halted => "Halted",
+        }
+    }
+}
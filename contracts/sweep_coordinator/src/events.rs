@@ -0,0 +1,68 @@
+use bridgelet_shared::EventTopics;
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
+
+// ─── Event payloads ─────────────────────────────────────────────────────────
+
+/// Emitted once per `batch_sweep`, after every account was attempted.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchSweepCompleted {
+    pub destination: Address,
+    pub succeeded: u32,
+    pub failed: u32,
+    /// Accounts whose sweep failed, in input order, for a retry.
+    pub failed_accounts: Vec<Address>,
+}
+
+/// Emitted once per `sweep_dust`, after every account was attempted.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchDustCompleted {
+    pub succeeded: u32,
+    pub failed: u32,
+    /// Accounts with no dust waiting or failing to send it, in input order.
+    pub failed_accounts: Vec<Address>,
+}
+
+/// Emitted once per `expire_batch`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchExpireCompleted {
+    pub expired: u32,
+    /// Accounts not yet past expiry, already closed, or failing to expire.
+    pub skipped: u32,
+}
+
+// ─── Emit helpers ───────────────────────────────────────────────────────────
+
+/// Publish the `batch` event, indexed by the destination.
+pub fn emit_batch_sweep_completed(
+    env: &Env,
+    destination: Address,
+    succeeded: u32,
+    failed_accounts: Vec<Address>,
+) {
+    let event = BatchSweepCompleted {
+        destination,
+        succeeded,
+        failed: failed_accounts.len(),
+        failed_accounts,
+    };
+    EventTopics::new(symbol_short!("batch")).publish_indexed(env, event.destination.clone(), event);
+}
+
+/// Publish the `batch_dst` event.
+pub fn emit_batch_dust_completed(env: &Env, succeeded: u32, failed_accounts: Vec<Address>) {
+    let event = BatchDustCompleted {
+        succeeded,
+        failed: failed_accounts.len(),
+        failed_accounts,
+    };
+    EventTopics::new(symbol_short!("batch_dst")).publish(env, event);
+}
+
+/// Publish the `batch_exp` event.
+pub fn emit_batch_expire_completed(env: &Env, expired: u32, skipped: u32) {
+    let event = BatchExpireCompleted { expired, skipped };
+    EventTopics::new(symbol_short!("batch_exp")).publish(env, event);
+}
@@ -0,0 +1,232 @@
+#![no_std]
+
+mod errors;
+mod events;
+mod storage;
+#[cfg(test)]
+mod test;
+
+use bridgelet_shared::errors::{KEEPER_BOUNTY_UNPAYABLE, UNKNOWN_ERROR};
+use bridgelet_shared::EphemeralAccountClient;
+use kill_switch::KillSwitchClient;
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, Symbol, Vec};
+
+pub use errors::Error;
+pub use events::{BatchDustCompleted, BatchExpireCompleted, BatchSweepCompleted};
+
+/// Maximum number of accounts handled by a single batch call.
+///
+/// Each sweep verifies signatures and moves every recorded asset, so a
+/// batch stays well inside the per-transaction budget.
+pub const MAX_BATCH_SIZE: u32 = 25;
+
+/// Subsystem this contract checks on the kill switch, shared with
+/// `SweepController` so one flag halts every sweep path.
+const KILL_SWITCH_SUBSYSTEM: Symbol = symbol_short!("sweeper");
+
+/// Sweeps or expires many ephemeral accounts in one transaction.
+///
+/// Nightly settlement and expiry housekeeping otherwise take one
+/// transaction per account.  The coordinator holds no authority over the
+/// accounts: every sweep is still authorized by that account's sweep
+/// signers and `expire` is permissionless, so anyone may submit a batch.
+/// Its only state is an admin and the kill switch that can halt sweeps.
+#[contract]
+pub struct SweepCoordinator;
+
+#[contractimpl]
+impl SweepCoordinator {
+    /// One-time initialization.
+    ///
+    /// # Arguments
+    /// * `admin` – Address allowed to set the kill switch.
+    ///
+    /// # Errors
+    /// * [`Error::AlreadyInitialized`] – called more than once.
+    pub fn initialize(env: Env, admin: Address) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        if storage::has_admin(&env) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+        storage::set_admin(&env, &admin);
+
+        Ok(())
+    }
+
+    /// Set or clear the kill switch checked before every batch sweep.
+    /// Admin-only.
+    ///
+    /// # Errors
+    /// * [`Error::NotInitialized`] – contract has not been initialized.
+    pub fn set_kill_switch(env: Env, kill_switch: Option<Address>) -> Result<(), Error> {
+        storage::extend_instance_ttl(&env);
+
+        let admin = storage::get_admin(&env).ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        match &kill_switch {
+            Some(kill_switch) => storage::set_kill_switch(&env, kill_switch),
+            None => storage::remove_kill_switch(&env),
+        }
+
+        Ok(())
+    }
+
+    /// Kill switch checked before every batch sweep, if any.
+    pub fn get_kill_switch(env: Env) -> Option<Address> {
+        storage::extend_instance_ttl(&env);
+        storage::get_kill_switch(&env)
+    }
+
+    /// Sweep each of `accounts` to `destination`.
+    ///
+    /// `auth[i]` holds the sweep signatures for `accounts[i]`, over that
+    /// account's `get_sweep_message(destination)`.  A failing account
+    /// (bad signature, not yet paid, expired, ...) is rolled back and
+    /// skipped; the others are still swept.  Publishes a `batch` event
+    /// with the success and failure counts.
+    ///
+    /// Returns the number of accounts swept.
+    ///
+    /// # Errors
+    /// * [`Error::BatchTooLarge`]  – more than [`MAX_BATCH_SIZE`] accounts.
+    /// * [`Error::LengthMismatch`] – `auth` and `accounts` differ in length.
+    /// * [`Error::Halted`]         – the kill switch halts the `sweeper`
+    ///   subsystem.
+    pub fn batch_sweep(
+        env: Env,
+        accounts: Vec<Address>,
+        destination: Address,
+        auth: Vec<Vec<BytesN<64>>>,
+    ) -> Result<u32, Error> {
+        storage::extend_instance_ttl(&env);
+        Self::require_not_halted(&env)?;
+
+        if accounts.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+        if auth.len() != accounts.len() {
+            return Err(Error::LengthMismatch);
+        }
+
+        let mut succeeded = 0;
+        let mut failed_accounts = Vec::new(&env);
+        for (account, signatures) in accounts.iter().zip(auth.iter()) {
+            let client = EphemeralAccountClient::new(&env, &account);
+            match client.try_sweep(&destination, &signatures) {
+                Ok(Ok(())) => succeeded += 1,
+                _ => failed_accounts.push_back(account),
+            }
+        }
+
+        events::emit_batch_sweep_completed(&env, destination, succeeded, failed_accounts);
+        Ok(succeeded)
+    }
+
+    /// Send on the dust each of `accounts` left out of its sweep.
+    ///
+    /// Accounts initialized with a `dust_threshold` keep assets worth less
+    /// than it out of `sweep`; this settles them for many accounts in one
+    /// transaction, each to the destination of its own sweep.  Needs no
+    /// authorization, since those destinations were signed for.  An
+    /// account with no dust waiting, or whose transfer fails, is rolled
+    /// back and skipped.  Publishes a `batch_dst` event with the counts.
+    ///
+    /// Returns the number of accounts whose dust was sent.
+    ///
+    /// # Errors
+    /// * [`Error::BatchTooLarge`] – more than [`MAX_BATCH_SIZE`] accounts.
+    /// * [`Error::Halted`]        – the kill switch halts the `sweeper`
+    ///   subsystem.
+    pub fn sweep_dust(env: Env, accounts: Vec<Address>) -> Result<u32, Error> {
+        storage::extend_instance_ttl(&env);
+        Self::require_not_halted(&env)?;
+
+        if accounts.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let mut succeeded = 0;
+        let mut failed_accounts = Vec::new(&env);
+        for account in accounts.iter() {
+            match EphemeralAccountClient::new(&env, &account).try_sweep_dust() {
+                Ok(Ok(())) => succeeded += 1,
+                _ => failed_accounts.push_back(account),
+            }
+        }
+
+        events::emit_batch_dust_completed(&env, succeeded, failed_accounts);
+        Ok(succeeded)
+    }
+
+    /// Expire every account of `accounts` that is past its expiry ledger.
+    ///
+    /// Each account is expired through `expire_as(caller)`, so `caller`
+    /// must authorize the call and collects the keeper bounty of every
+    /// account that pays one.  An account running older code that refuses
+    /// a bounty it cannot pay (reserve not held in XLM) is expired without
+    /// one instead.
+    /// Accounts not yet expired are skipped, as are accounts whose expiry
+    /// fails (already closed, held in escrow, ...).  Callable by anyone,
+    /// so keepers can close lapsed accounts without tracking each one.
+    /// Publishes a `batch_exp` event with the counts.
+    ///
+    /// Returns the number of accounts expired.
+    ///
+    /// # Errors
+    /// * [`Error::BatchTooLarge`] – more than [`MAX_BATCH_SIZE`] accounts.
+    pub fn expire_batch(env: Env, caller: Address, accounts: Vec<Address>) -> Result<u32, Error> {
+        storage::extend_instance_ttl(&env);
+        if accounts.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+        caller.require_auth();
+
+        let mut expired = 0;
+        for account in accounts.iter() {
+            let client = EphemeralAccountClient::new(&env, &account);
+            if client.is_expired() && Self::expire_as_keeper(&client, &caller) {
+                expired += 1;
+            }
+        }
+
+        events::emit_batch_expire_completed(&env, expired, accounts.len() - expired);
+        Ok(expired)
+    }
+
+    /// Expire `client` on behalf of `caller`, falling back to the plain
+    /// `expire` when the account cannot pay the bounty.
+    fn expire_as_keeper(client: &EphemeralAccountClient, caller: &Address) -> bool {
+        match client.try_expire_as(caller) {
+            Ok(Ok(())) => true,
+            Err(Ok(error))
+                if error == soroban_sdk::Error::from_contract_error(KEEPER_BOUNTY_UNPAYABLE) =>
+            {
+                matches!(client.try_expire(), Ok(Ok(())))
+            }
+            _ => false,
+        }
+    }
+
+    fn require_not_halted(env: &Env) -> Result<(), Error> {
+        match storage::get_kill_switch(env) {
+            Some(kill_switch)
+                if KillSwitchClient::new(env, &kill_switch).is_halted(&KILL_SWITCH_SUBSYSTEM) =>
+            {
+                Err(Error::Halted)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Return the name of the error raised as `Error(Contract, #code)`, or
+    /// `Unknown` for a code this contract never raises.
+    pub fn describe_error(env: Env, code: u32) -> Symbol {
+        let name = Error::try_from(soroban_sdk::Error::from_contract_error(code))
+            .map_or(UNKNOWN_ERROR, Error::name);
+        Symbol::new(&env, name)
+    }
+}
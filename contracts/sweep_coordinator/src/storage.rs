@@ -0,0 +1,58 @@
+use soroban_sdk::{contracttype, Address, Env};
+
+/// Storage keys used by the sweep coordinator.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Address allowed to set the kill switch.
+    Admin,
+    /// `KillSwitch` contract checked before every batch sweep.
+    KillSwitch,
+}
+
+// Admin
+
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
+
+pub fn get_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Admin)
+}
+
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().instance().has(&DataKey::Admin)
+}
+
+// Kill switch
+
+pub fn set_kill_switch(env: &Env, kill_switch: &Address) {
+    env.storage()
+        .instance()
+        .set(&DataKey::KillSwitch, kill_switch);
+}
+
+pub fn remove_kill_switch(env: &Env) {
+    env.storage().instance().remove(&DataKey::KillSwitch);
+}
+
+pub fn get_kill_switch(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::KillSwitch)
+}
+
+// TTL management
+
+/// If the remaining TTL drops below this threshold (in ledgers), extend it.
+/// 259 200 ledgers ≈ 15 days, half of the extension window.
+const TTL_THRESHOLD: u32 = 259_200;
+
+/// Extend TTLs to this many ledgers.
+/// 518 400 ledgers ≈ 30 days (at ~5 s per ledger).
+const TTL_EXTEND_TO: u32 = 518_400;
+
+/// Keep the contract instance alive; called from every public entry-point.
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
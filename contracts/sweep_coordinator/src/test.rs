@@ -0,0 +1,478 @@
+extern crate std;
+
+use crate::{
+    BatchDustCompleted, BatchExpireCompleted, BatchSweepCompleted, Error, SweepCoordinator,
+    SweepCoordinatorClient,
+};
+use bridgelet_shared::errors::{SWEEP_COORDINATOR_ERRORS, UNKNOWN_ERROR};
+use bridgelet_shared::{EventTopics, OracleAsset, PriceData};
+use ed25519_dalek::{Signer, SigningKey};
+use ephemeral_account::{
+    AccountConfig, AccountStatus, DataKey, EphemeralAccountContract, EphemeralAccountContractClient,
+};
+use kill_switch::{KillSwitch, KillSwitchClient};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, BytesN, Env, IntoVal, Symbol, Vec,
+};
+
+// HELPERS
+
+/// Default base reserve of an account without a reserve contract, in
+/// stroops.
+const RESERVE: i128 = 1_000_000_000;
+
+fn signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32])
+}
+
+/// Deploy an ephemeral account holding a recorded payment of `amount`.
+fn deploy_paid_account(
+    env: &Env,
+    asset: &Address,
+    amount: i128,
+) -> EphemeralAccountContractClient<'static> {
+    let account_id = env.register(EphemeralAccountContract, ());
+    let account = EphemeralAccountContractClient::new(env, &account_id);
+    account.initialize(
+        &Address::generate(env),
+        &(env.ledger().sequence() + 1000),
+        &Address::generate(env),
+    );
+    account.set_sweep_signer(&BytesN::from_array(
+        env,
+        &signing_key().verifying_key().to_bytes(),
+    ));
+    StellarAssetClient::new(env, asset).mint(&account_id, &amount);
+    account.record_payment(&amount, asset);
+    account
+}
+
+fn sign(
+    env: &Env,
+    account: &EphemeralAccountContractClient,
+    destination: &Address,
+) -> Vec<BytesN<64>> {
+    let digest = account.get_sweep_message(destination).to_array();
+    vec![
+        env,
+        BytesN::from_array(env, &signing_key().sign(&digest).to_bytes()),
+    ]
+}
+
+fn setup() -> (Env, SweepCoordinatorClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let coordinator = SweepCoordinatorClient::new(&env, &env.register(SweepCoordinator, ()));
+    let asset = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    (env, coordinator, asset)
+}
+
+//  Kill switch
+
+/// A halted `sweeper` subsystem stops batch sweeps, the same flag that
+/// stops `SweepController`.
+#[test]
+fn test_batch_sweep_refused_while_halted() {
+    let (env, coordinator, asset) = setup();
+    let destination = Address::generate(&env);
+    let account = deploy_paid_account(&env, &asset, 100);
+
+    assert_eq!(
+        coordinator.try_set_kill_switch(&None),
+        Err(Ok(Error::NotInitialized))
+    );
+    let admin = Address::generate(&env);
+    coordinator.initialize(&admin);
+    assert_eq!(
+        coordinator.try_initialize(&admin),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+
+    let kill_switch_id = env.register(KillSwitch, ());
+    let kill_switch = KillSwitchClient::new(&env, &kill_switch_id);
+    kill_switch.initialize(&Address::generate(&env));
+    kill_switch.set_halted(&symbol_short!("sweeper"), &true);
+    coordinator.set_kill_switch(&Some(kill_switch_id.clone()));
+    assert_eq!(coordinator.get_kill_switch(), Some(kill_switch_id));
+
+    let accounts = vec![&env, account.address.clone()];
+    let auth = vec![&env, sign(&env, &account, &destination)];
+    assert_eq!(
+        coordinator.try_batch_sweep(&accounts, &destination, &auth),
+        Err(Ok(Error::Halted))
+    );
+    assert_eq!(account.get_status(), AccountStatus::PaymentReceived);
+
+    kill_switch.set_halted(&symbol_short!("sweeper"), &false);
+    assert_eq!(coordinator.batch_sweep(&accounts, &destination, &auth), 1);
+    assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+}
+
+//  Batch sweep
+
+#[test]
+fn test_batch_sweep_sweeps_every_account() {
+    let (env, coordinator, asset) = setup();
+    let destination = Address::generate(&env);
+    let first = deploy_paid_account(&env, &asset, 100);
+    let second = deploy_paid_account(&env, &asset, 50);
+
+    let swept = coordinator.batch_sweep(
+        &vec![&env, first.address.clone(), second.address.clone()],
+        &destination,
+        &vec![
+            &env,
+            sign(&env, &first, &destination),
+            sign(&env, &second, &destination),
+        ],
+    );
+
+    assert_eq!(swept, 2);
+    assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 150);
+    assert_eq!(first.get_status(), AccountStatus::Swept);
+    assert_eq!(second.get_status(), AccountStatus::Swept);
+}
+
+/// One bad account must not block the rest of the batch.
+#[test]
+fn test_batch_sweep_tolerates_failures() {
+    let (env, coordinator, asset) = setup();
+    let destination = Address::generate(&env);
+    let good = deploy_paid_account(&env, &asset, 100);
+    let bad_signature = deploy_paid_account(&env, &asset, 40);
+    let unpaid =
+        EphemeralAccountContractClient::new(&env, &env.register(EphemeralAccountContract, ()));
+    unpaid.initialize(
+        &Address::generate(&env),
+        &(env.ledger().sequence() + 1000),
+        &Address::generate(&env),
+    );
+
+    let accounts = vec![
+        &env,
+        bad_signature.address.clone(),
+        good.address.clone(),
+        unpaid.address.clone(),
+    ];
+    let auth = vec![
+        &env,
+        vec![&env, BytesN::from_array(&env, &[1u8; 64])],
+        sign(&env, &good, &destination),
+        vec![&env],
+    ];
+    let swept = coordinator.batch_sweep(&accounts, &destination, &auth);
+    assert_eq!(swept, 1);
+
+    let completed: BatchSweepCompleted = env
+        .events()
+        .all()
+        .iter()
+        .find(|(_, topics, _)| {
+            EventTopics::decode(&env, topics).map(|topics| topics.name)
+                == Some(symbol_short!("batch"))
+        })
+        .map(|(_, _, data)| data.into_val(&env))
+        .expect("batch event was not emitted");
+    assert_eq!(
+        completed,
+        BatchSweepCompleted {
+            destination: destination.clone(),
+            succeeded: 1,
+            failed: 2,
+            failed_accounts: vec![&env, bad_signature.address.clone(), unpaid.address.clone()],
+        }
+    );
+
+    assert_eq!(TokenClient::new(&env, &asset).balance(&destination), 100);
+    assert_eq!(bad_signature.get_status(), AccountStatus::PaymentReceived);
+}
+
+#[test]
+fn test_batch_sweep_rejects_mismatched_auth() {
+    let (env, coordinator, _) = setup();
+    let result = coordinator.try_batch_sweep(
+        &vec![&env, Address::generate(&env)],
+        &Address::generate(&env),
+        &Vec::new(&env),
+    );
+    assert_eq!(result, Err(Ok(Error::LengthMismatch)));
+}
+
+#[test]
+fn test_batch_sweep_rejects_oversized_batch() {
+    let (env, coordinator, _) = setup();
+    let mut accounts = Vec::new(&env);
+    let mut auth = Vec::new(&env);
+    for _ in 0..(crate::MAX_BATCH_SIZE + 1) {
+        accounts.push_back(Address::generate(&env));
+        auth.push_back(Vec::new(&env));
+    }
+    let result = coordinator.try_batch_sweep(&accounts, &Address::generate(&env), &auth);
+    assert_eq!(result, Err(Ok(Error::BatchTooLarge)));
+}
+
+//  Dust
+
+/// SEP-40 oracle quoting prices set by the test, with 14 decimals.
+#[contract]
+struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, asset: Address, price: i128) {
+        env.storage().instance().set(&asset, &price);
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        14
+    }
+
+    pub fn lastprice(env: Env, asset: OracleAsset) -> Option<PriceData> {
+        let OracleAsset::Stellar(asset) = asset else {
+            return None;
+        };
+        let price = env.storage().instance().get(&asset)?;
+        Some(PriceData {
+            price,
+            timestamp: env.ledger().timestamp(),
+        })
+    }
+}
+
+/// Dust left out of each account's sweep is sent on by one batch call, to
+/// the destination that account was swept to.
+#[test]
+fn test_sweep_dust_settles_what_sweeps_left_out() {
+    let (env, coordinator, asset) = setup();
+    let oracle = MockOracleClient::new(&env, &env.register(MockOracle, ()));
+    let dust_asset = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    // One whole token of `asset` is worth a unit, `dust_asset` nearly nothing
+    oracle.set_price(&asset, &100_000_000_000_000);
+    oracle.set_price(&dust_asset, &1);
+
+    let account =
+        EphemeralAccountContractClient::new(&env, &env.register(EphemeralAccountContract, ()));
+    account.initialize_with_config(
+        &Address::generate(&env),
+        &(env.ledger().sequence() + 1000),
+        &Address::generate(&env),
+        &AccountConfig {
+            sweep_signer: Some(BytesN::from_array(
+                &env,
+                &signing_key().verifying_key().to_bytes(),
+            )),
+            price_oracle: Some(oracle.address.clone()),
+            dust_threshold: 1_000_000_000_000,
+            ..Default::default()
+        },
+    );
+    StellarAssetClient::new(&env, &asset).mint(&account.address, &100_000_000);
+    StellarAssetClient::new(&env, &dust_asset).mint(&account.address, &500);
+    account.record_payment(&100_000_000, &asset);
+    account.record_payment(&500, &dust_asset);
+    let no_dust = deploy_paid_account(&env, &asset, 100);
+
+    let destination = Address::generate(&env);
+    coordinator.batch_sweep(
+        &vec![&env, account.address.clone()],
+        &destination,
+        &vec![&env, sign(&env, &account, &destination)],
+    );
+    assert_eq!(account.get_status(), AccountStatus::Swept);
+    assert_eq!(
+        TokenClient::new(&env, &asset).balance(&destination),
+        100_000_000
+    );
+    assert_eq!(TokenClient::new(&env, &dust_asset).balance(&destination), 0);
+
+    let accounts = vec![&env, account.address.clone(), no_dust.address.clone()];
+    assert_eq!(coordinator.sweep_dust(&accounts), 1);
+    let completed: BatchDustCompleted = env
+        .events()
+        .all()
+        .iter()
+        .find(|(_, topics, _)| {
+            EventTopics::decode(&env, topics).map(|topics| topics.name)
+                == Some(symbol_short!("batch_dst"))
+        })
+        .map(|(_, _, data)| data.into_val(&env))
+        .expect("batch dust event was not emitted");
+    assert_eq!(
+        completed,
+        BatchDustCompleted {
+            succeeded: 1,
+            failed: 1,
+            failed_accounts: vec![&env, no_dust.address.clone()],
+        }
+    );
+    assert_eq!(
+        TokenClient::new(&env, &dust_asset).balance(&destination),
+        500
+    );
+    assert_eq!(coordinator.sweep_dust(&accounts), 0);
+}
+
+#[test]
+fn test_sweep_dust_rejects_oversized_batch() {
+    let (env, coordinator, _) = setup();
+    let mut accounts = Vec::new(&env);
+    for _ in 0..(crate::MAX_BATCH_SIZE + 1) {
+        accounts.push_back(Address::generate(&env));
+    }
+    assert_eq!(
+        coordinator.try_sweep_dust(&accounts),
+        Err(Ok(Error::BatchTooLarge))
+    );
+}
+
+//  Batch expire
+
+#[test]
+fn test_expire_batch_skips_live_accounts() {
+    let (env, coordinator, asset) = setup();
+    let lapsed = deploy_paid_account(&env, &asset, 100);
+    let live = deploy_paid_account(&env, &asset, 100);
+    live.extend_expiry(&(env.ledger().sequence() + 5000));
+    env.ledger().with_mut(|li| li.sequence_number += 1001);
+
+    // The lapsed account appears twice; the second attempt finds it closed.
+    let expired = coordinator.expire_batch(
+        &Address::generate(&env),
+        &vec![
+            &env,
+            lapsed.address.clone(),
+            live.address.clone(),
+            lapsed.address.clone(),
+        ],
+    );
+    assert_eq!(expired, 1);
+
+    let completed: BatchExpireCompleted = env
+        .events()
+        .all()
+        .iter()
+        .find(|(_, topics, _)| {
+            EventTopics::decode(&env, topics).map(|topics| topics.name)
+                == Some(symbol_short!("batch_exp"))
+        })
+        .map(|(_, _, data)| data.into_val(&env))
+        .expect("batch expire event was not emitted");
+    assert_eq!(
+        completed,
+        BatchExpireCompleted {
+            expired: 1,
+            skipped: 2,
+        }
+    );
+
+    assert_eq!(lapsed.get_status(), AccountStatus::Expired);
+    assert_eq!(live.get_status(), AccountStatus::PaymentReceived);
+}
+
+/// The batch caller is the keeper: it collects the bounty of an account
+/// holding its reserve in XLM, and an account that cannot pay one is still
+/// expired.
+#[test]
+fn test_expire_batch_pays_keeper_bounty_to_caller() {
+    let (env, coordinator, asset) = setup();
+    let config = AccountConfig {
+        keeper_bounty_bps: Some(500),
+        ..Default::default()
+    };
+
+    // Reserve held in XLM next to a native payment
+    let native = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let native_recovery = Address::generate(&env);
+    let native_account =
+        EphemeralAccountContractClient::new(&env, &env.register(EphemeralAccountContract, ()));
+    native_account.initialize_with_config(
+        &Address::generate(&env),
+        &(env.ledger().sequence() + 1000),
+        &native_recovery,
+        &config,
+    );
+    env.as_contract(&native_account.address, || {
+        env.storage().instance().set(&DataKey::NativeAsset, &native)
+    });
+    StellarAssetClient::new(&env, &native).mint(&native_account.address, &(RESERVE + 500));
+    native_account.record_native_payment(&500, &None);
+
+    // Reserve not held by the account: the bounty is only reported
+    let token_recovery = Address::generate(&env);
+    let token_account =
+        EphemeralAccountContractClient::new(&env, &env.register(EphemeralAccountContract, ()));
+    token_account.initialize_with_config(
+        &Address::generate(&env),
+        &(env.ledger().sequence() + 1000),
+        &token_recovery,
+        &config,
+    );
+    StellarAssetClient::new(&env, &asset).mint(&token_account.address, &100);
+    token_account.record_payment(&100, &asset);
+
+    env.ledger().with_mut(|li| li.sequence_number += 1001);
+    let keeper = Address::generate(&env);
+    let expired = coordinator.expire_batch(
+        &keeper,
+        &vec![
+            &env,
+            native_account.address.clone(),
+            token_account.address.clone(),
+        ],
+    );
+    assert_eq!(expired, 2);
+
+    let bounty = RESERVE / 20;
+    let native_client = TokenClient::new(&env, &native);
+    assert_eq!(native_client.balance(&keeper), bounty);
+    assert_eq!(
+        native_client.balance(&native_recovery),
+        500 + RESERVE - bounty
+    );
+    assert_eq!(native_client.balance(&native_account.address), 0);
+    assert_eq!(TokenClient::new(&env, &asset).balance(&token_recovery), 100);
+    assert_eq!(token_account.get_status(), AccountStatus::Expired);
+    // Owed on the reserve settled off-chain, but not transferred
+    let event = token_account.get_last_reserve_event().unwrap();
+    assert_eq!(event.keeper, Some(keeper.clone()));
+    assert_eq!(event.keeper_bounty, bounty);
+}
+
+#[test]
+fn test_expire_batch_rejects_oversized_batch() {
+    let (env, coordinator, _) = setup();
+    let mut accounts = Vec::new(&env);
+    for _ in 0..(crate::MAX_BATCH_SIZE + 1) {
+        accounts.push_back(Address::generate(&env));
+    }
+    assert_eq!(
+        coordinator.try_expire_batch(&Address::generate(&env), &accounts),
+        Err(Ok(Error::BatchTooLarge))
+    );
+}
+
+/// Codes stay inside the coordinator's shared range, so off-chain tooling can
+/// tell them from another contract's.
+#[test]
+fn test_error_codes_use_reserved_range() {
+    let env = Env::default();
+    let client = SweepCoordinatorClient::new(&env, &env.register(SweepCoordinator, ()));
+    for code in SWEEP_COORDINATOR_ERRORS {
+        let name = client.describe_error(&code);
+        if let Ok(error) = Error::try_from(soroban_sdk::Error::from_contract_error(code)) {
+            assert_eq!(name, Symbol::new(&env, error.name()));
+        } else {
+            assert_eq!(name, Symbol::new(&env, UNKNOWN_ERROR));
+        }
+    }
+    assert_eq!(client.describe_error(&1), Symbol::new(&env, UNKNOWN_ERROR));
+}
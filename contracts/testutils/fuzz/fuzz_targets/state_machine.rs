@@ -0,0 +1,6 @@
+#![no_main]
+
+use bridgelet_testutils::state_machine::{run, Op};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|ops: Vec<Op>| run(&ops));
@@ -0,0 +1,223 @@
+//! Test scaffolding for contracts and services built on Bridgelet.
+//!
+//! [`ScenarioBuilder`] replaces the setup every integration test repeats:
+//! an `Env` with mocked auths, a Stellar asset contract, a configured
+//! `ReserveContract` and an initialized ephemeral account wired to it.
+//! Only for tests; it links the contract crates and the SDK test utilities.
+
+pub mod state_machine;
+#[cfg(test)]
+mod test;
+
+use bridgelet_shared::{units::STROOPS_PER_XLM, AccountConfig};
+use ed25519_dalek::{Signer, SigningKey};
+use ephemeral_account::{EphemeralAccountContract, EphemeralAccountContractClient};
+use reserve_contract::{ReserveContract, ReserveContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, BytesN, Env, Vec,
+};
+
+/// Base reserve configured on the scenario's reserve contract unless
+/// [`ScenarioBuilder::base_reserve`] overrides it.
+pub const DEFAULT_BASE_RESERVE: i128 = 100 * STROOPS_PER_XLM;
+
+/// Ledgers between the current ledger and the account's expiry unless
+/// [`ScenarioBuilder::expiry_ledgers`] overrides it.
+pub const DEFAULT_EXPIRY_LEDGERS: u32 = 1_000;
+
+/// Secret key of the scenario's sweep signer.
+pub fn sweep_signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32])
+}
+
+/// Public key of [`sweep_signing_key`], as configured on the account.
+pub fn sweep_signer_key(env: &Env) -> BytesN<32> {
+    BytesN::from_array(env, &sweep_signing_key().verifying_key().to_bytes())
+}
+
+/// Builder for a [`Scenario`].
+pub struct ScenarioBuilder {
+    env: Env,
+    expiry_ledgers: u32,
+    base_reserve: i128,
+    funding: i128,
+    payment: Option<i128>,
+    config: AccountConfig,
+}
+
+impl Default for ScenarioBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScenarioBuilder {
+    /// A fresh `Env` with every authorization mocked.
+    pub fn new() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+        Self {
+            env,
+            expiry_ledgers: DEFAULT_EXPIRY_LEDGERS,
+            base_reserve: DEFAULT_BASE_RESERVE,
+            funding: 0,
+            payment: None,
+            config: AccountConfig::default(),
+        }
+    }
+
+    /// The scenario's `Env`, for registering contracts the account is
+    /// configured with before it is built.
+    pub fn env(&self) -> &Env {
+        &self.env
+    }
+
+    /// Expire the account `ledgers` after the current ledger.
+    pub fn expiry_ledgers(mut self, ledgers: u32) -> Self {
+        self.expiry_ledgers = ledgers;
+        self
+    }
+
+    /// Base reserve (stroops) configured on the reserve contract.
+    pub fn base_reserve(mut self, stroops: i128) -> Self {
+        self.base_reserve = stroops;
+        self
+    }
+
+    /// Mint `amount` of the asset to the account without recording it.
+    pub fn funded(mut self, amount: i128) -> Self {
+        self.funding = amount;
+        self
+    }
+
+    /// Mint `amount` of the asset to the account and record it as paid.
+    pub fn paid(mut self, amount: i128) -> Self {
+        self.payment = Some(amount);
+        self
+    }
+
+    /// Initialize the account with `config`.  The reserve contract and
+    /// sweep signer are filled in when left unset.
+    pub fn config(mut self, config: AccountConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn build(self) -> Scenario {
+        let env = self.env;
+
+        let reserve_admin = Address::generate(&env);
+        let reserve = ReserveContractClient::new(&env, &env.register(ReserveContract, ()));
+        reserve.initialize(&reserve_admin);
+        reserve.set_base_reserve(&reserve_admin, &self.base_reserve);
+
+        let asset_issuer = Address::generate(&env);
+        let asset = env
+            .register_stellar_asset_contract_v2(asset_issuer.clone())
+            .address();
+
+        let mut config = self.config;
+        if config.reserve_contract.is_none() {
+            config.reserve_contract = Some(reserve.address.clone());
+        }
+        if config.sweep_signer.is_none() && config.passkey_signer.is_none() {
+            config.sweep_signer = Some(sweep_signer_key(&env));
+        }
+
+        let creator = Address::generate(&env);
+        let recovery = Address::generate(&env);
+        let account =
+            EphemeralAccountContractClient::new(&env, &env.register(EphemeralAccountContract, ()));
+        account.initialize_with_config(
+            &creator,
+            &(env.ledger().sequence() + self.expiry_ledgers),
+            &recovery,
+            &config,
+        );
+
+        let scenario = Scenario {
+            token: TokenClient::new(&env, &asset),
+            env,
+            account,
+            reserve,
+            reserve_admin,
+            asset,
+            asset_issuer,
+            creator,
+            recovery,
+        };
+        if self.funding > 0 {
+            scenario.mint(&scenario.account.address, self.funding);
+        }
+        if let Some(amount) = self.payment {
+            scenario.pay(amount);
+        }
+        scenario
+    }
+}
+
+/// An initialized account, its reserve contract and a test asset.
+pub struct Scenario {
+    pub env: Env,
+    pub account: EphemeralAccountContractClient<'static>,
+    pub reserve: ReserveContractClient<'static>,
+    pub reserve_admin: Address,
+    /// Stellar asset contract the scenario mints and pays in.
+    pub asset: Address,
+    pub asset_issuer: Address,
+    pub token: TokenClient<'static>,
+    pub creator: Address,
+    pub recovery: Address,
+}
+
+impl Scenario {
+    /// Mint `amount` of the asset to `to`.
+    pub fn mint(&self, to: &Address, amount: i128) {
+        StellarAssetClient::new(&self.env, &self.asset).mint(to, &amount);
+    }
+
+    /// Mint `amount` of the asset to the account and record the payment.
+    pub fn pay(&self, amount: i128) {
+        self.mint(&self.account.address, amount);
+        self.account.record_payment(&amount, &self.asset);
+    }
+
+    /// Balance of the asset held by `holder`.
+    pub fn balance(&self, holder: &Address) -> i128 {
+        self.token.balance(holder)
+    }
+
+    /// Move the ledger sequence `ledgers` forward.
+    pub fn advance_ledgers(&self, ledgers: u32) {
+        self.env
+            .ledger()
+            .with_mut(|ledger| ledger.sequence_number += ledgers);
+    }
+
+    /// Move to the first ledger at which `expire` goes through: past the
+    /// expiry ledger and any grace period.
+    pub fn advance_past_expiry(&self) {
+        let grace_end = self.account.get_lifecycle().grace_end_ledger;
+        self.env
+            .ledger()
+            .with_mut(|ledger| ledger.sequence_number = ledger.sequence_number.max(grace_end));
+    }
+
+    /// Signature of the scenario's sweep signer over a sweep to
+    /// `destination`, valid for the account's current nonce.
+    pub fn sign_sweep(&self, destination: &Address) -> Vec<BytesN<64>> {
+        let digest = self.account.get_sweep_message(destination).to_array();
+        vec![
+            &self.env,
+            BytesN::from_array(&self.env, &sweep_signing_key().sign(&digest).to_bytes()),
+        ]
+    }
+
+    /// Sign and execute a sweep to `destination`.
+    pub fn sweep_to(&self, destination: &Address) {
+        self.account
+            .sweep(destination, &self.sign_sweep(destination));
+    }
+}
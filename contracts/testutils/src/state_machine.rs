@@ -0,0 +1,257 @@
+//! Random operation sequences against an ephemeral account.
+//!
+//! [`run`] applies a sequence of [`Op`]s to a fresh [`Scenario`] and
+//! checks, after every step, the properties no sequence may break:
+//!
+//! * the account's own consistency rules (`check_invariants`) hold;
+//! * the reserve never goes negative, and what was reclaimed plus what
+//!   remains is always the base reserve;
+//! * a closed account (`Swept`, `Expired`, `Cancelled`) never changes
+//!   status again;
+//! * tokens are conserved: every minted unit is held by the account or
+//!   one of the scenario's addresses.
+//!
+//! Failed calls are expected (most random operations are invalid); only a
+//! broken property fails the run, by panicking.  Used by the proptest
+//! properties and by the fuzz target under `fuzz/`.
+
+use arbitrary::Arbitrary;
+use bridgelet_shared::AccountStatus;
+use ed25519_dalek::Signer;
+use ephemeral_account::CloseReason;
+use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, Vec};
+
+use crate::{sweep_signing_key, Scenario, ScenarioBuilder};
+
+/// Operations longer sequences are cut to, so a fuzz input cannot stall
+/// the run.
+pub const MAX_OPS: usize = 64;
+
+/// Ledgers between the start of a run and the account's expiry.
+const EXPIRY_LEDGERS: u32 = 200;
+
+/// Largest amount minted for a payment; larger ones are recorded unbacked.
+const MAX_MINT: i128 = 1_000_000_000_000_000;
+
+/// Distinct assets and destinations operations pick from.
+const ASSETS: u8 = 3;
+const DESTINATIONS: u8 = 3;
+
+/// One call or ledger step.  Indices are taken modulo the number of
+/// assets or destinations.
+#[derive(Arbitrary, Clone, Debug)]
+pub enum Op {
+    /// Mint `amount` to the account (when in range) and record it.
+    Pay {
+        asset: u8,
+        amount: i128,
+    },
+    /// Mint to the account without recording a payment.
+    Fund {
+        asset: u8,
+        amount: u32,
+    },
+    /// Signed sweep of every payment.
+    Sweep {
+        destination: u8,
+    },
+    /// Resubmit the last sweep signature, to a possibly different
+    /// destination.
+    ReplaySweep {
+        destination: u8,
+    },
+    /// Signed partial sweep of one asset.
+    SweepAsset {
+        destination: u8,
+        asset: u8,
+    },
+    Expire,
+    Cancel,
+    ReclaimReserve,
+    ExtendExpiry {
+        ledgers: u16,
+    },
+    AdvanceLedgers {
+        ledgers: u16,
+    },
+}
+
+/// A scenario plus what the properties compare against.
+pub struct Harness {
+    pub scenario: Scenario,
+    assets: Vec<Address>,
+    destinations: Vec<Address>,
+    /// Units minted per asset, in `assets` order.
+    minted: [i128; ASSETS as usize],
+    base_reserve: i128,
+    last_signature: Option<Vec<BytesN<64>>>,
+    closed_status: Option<AccountStatus>,
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let scenario = ScenarioBuilder::new()
+            .expiry_ledgers(EXPIRY_LEDGERS)
+            .build();
+        let env = &scenario.env;
+
+        let mut assets = vec![env, scenario.asset.clone()];
+        let mut destinations = Vec::new(env);
+        for _ in 1..ASSETS {
+            let issuer = Address::generate(env);
+            assets.push_back(env.register_stellar_asset_contract_v2(issuer).address());
+        }
+        for _ in 0..DESTINATIONS {
+            destinations.push_back(Address::generate(env));
+        }
+        let base_reserve = scenario.account.get_reserve_remaining();
+
+        Self {
+            scenario,
+            assets,
+            destinations,
+            minted: [0; ASSETS as usize],
+            base_reserve,
+            last_signature: None,
+            closed_status: None,
+        }
+    }
+
+    fn asset(&self, index: u8) -> (usize, Address) {
+        let index = index % ASSETS;
+        (index as usize, self.assets.get_unchecked(index as u32))
+    }
+
+    fn destination(&self, index: u8) -> Address {
+        self.destinations
+            .get_unchecked((index % DESTINATIONS) as u32)
+    }
+
+    fn mint(&mut self, asset: u8, amount: i128) {
+        let (index, address) = self.asset(asset);
+        soroban_sdk::token::StellarAssetClient::new(&self.scenario.env, &address)
+            .mint(&self.scenario.account.address, &amount);
+        self.minted[index] += amount;
+    }
+
+    /// Apply `op`, ignoring whether the call succeeds.
+    pub fn apply(&mut self, op: &Op) {
+        let account = &self.scenario.account;
+        match *op {
+            Op::Pay { asset, amount } => {
+                if amount > 0 && amount <= MAX_MINT {
+                    self.mint(asset, amount);
+                }
+                let _ = self
+                    .scenario
+                    .account
+                    .try_record_payment(&amount, &self.asset(asset).1);
+            }
+            Op::Fund { asset, amount } => {
+                if amount > 0 {
+                    self.mint(asset, amount as i128);
+                }
+            }
+            Op::Sweep { destination } => {
+                let destination = self.destination(destination);
+                if let Ok(Ok(digest)) = account.try_get_sweep_message(&destination) {
+                    let signature = self.sign(&digest);
+                    let _ = account.try_sweep(&destination, &signature);
+                    self.last_signature = Some(signature);
+                }
+            }
+            Op::ReplaySweep { destination } => {
+                if let Some(signature) = &self.last_signature {
+                    let _ = account.try_sweep(&self.destination(destination), signature);
+                }
+            }
+            Op::SweepAsset { destination, asset } => {
+                let destination = self.destination(destination);
+                let assets = vec![&self.scenario.env, self.asset(asset).1];
+                if let Ok(Ok(digest)) = account.try_get_sweep_assets_message(&destination, &assets)
+                {
+                    let signature = self.sign(&digest);
+                    let _ = account.try_sweep_assets(&destination, &assets, &signature);
+                }
+            }
+            Op::Expire => {
+                let _ = account.try_expire();
+            }
+            Op::Cancel => {
+                let _ = account.try_cancel(&CloseReason::CreatorCancelled);
+            }
+            Op::ReclaimReserve => {
+                let _ = account.try_reclaim_reserve();
+            }
+            Op::ExtendExpiry { ledgers } => {
+                let expiry = account.get_lifecycle().expiry_ledger;
+                let _ = account.try_extend_expiry(&expiry.saturating_add(ledgers as u32));
+            }
+            Op::AdvanceLedgers { ledgers } => self.scenario.advance_ledgers(ledgers as u32),
+        }
+    }
+
+    fn sign(&self, digest: &BytesN<32>) -> Vec<BytesN<64>> {
+        let signature = sweep_signing_key().sign(&digest.to_array()).to_bytes();
+        vec![
+            &self.scenario.env,
+            BytesN::from_array(&self.scenario.env, &signature),
+        ]
+    }
+
+    /// Panic if any property is broken.
+    pub fn check(&mut self) {
+        let account = &self.scenario.account;
+
+        let broken = account.check_invariants();
+        assert!(broken.is_empty(), "invariants broken: {broken:?}");
+
+        let remaining = account.get_reserve_remaining();
+        let summary = account.get_reserve_reclaim_summary();
+        assert!(remaining >= 0, "negative reserve: {remaining}");
+        assert!(account.get_reserve_available() >= 0);
+        assert!(
+            (0..=self.base_reserve).contains(&summary.total_reclaimed),
+            "reclaimed {} of a {} reserve",
+            summary.total_reclaimed,
+            self.base_reserve
+        );
+        assert_eq!(summary.total_reclaimed + remaining, self.base_reserve);
+
+        let status = account.get_status();
+        match self.closed_status {
+            Some(closed) => assert_eq!(status, closed, "left terminal status {closed:?}"),
+            None if status.is_closed() => self.closed_status = Some(status),
+            None => {}
+        }
+
+        let scenario = &self.scenario;
+        for (index, asset) in self.assets.iter().enumerate() {
+            let token = soroban_sdk::token::TokenClient::new(&scenario.env, &asset);
+            let mut held = token.balance(&account.address)
+                + token.balance(&scenario.creator)
+                + token.balance(&scenario.recovery);
+            for destination in self.destinations.iter() {
+                held += token.balance(&destination);
+            }
+            assert_eq!(held, self.minted[index], "tokens not conserved");
+        }
+    }
+}
+
+/// Apply up to [`MAX_OPS`] operations to a fresh account, checking every
+/// property after each one.
+pub fn run(ops: &[Op]) {
+    let mut harness = Harness::new();
+    harness.check();
+    for op in ops.iter().take(MAX_OPS) {
+        harness.apply(op);
+        harness.check();
+    }
+}
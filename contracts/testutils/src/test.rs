@@ -0,0 +1,78 @@
+use crate::state_machine::{self, Op};
+use crate::{ScenarioBuilder, DEFAULT_BASE_RESERVE};
+use bridgelet_shared::AccountStatus;
+use proptest::prelude::*;
+use soroban_sdk::{testutils::Address as _, Address};
+
+#[test]
+fn test_paid_scenario_sweeps_to_destination() {
+    let scenario = ScenarioBuilder::new().paid(500).build();
+    assert_eq!(
+        scenario.account.get_status(),
+        AccountStatus::PaymentReceived
+    );
+    assert_eq!(
+        scenario.account.get_reserve_remaining(),
+        DEFAULT_BASE_RESERVE
+    );
+
+    let destination = Address::generate(&scenario.env);
+    scenario.sweep_to(&destination);
+    assert_eq!(scenario.account.get_status(), AccountStatus::Swept);
+    assert_eq!(scenario.balance(&destination), 500);
+    assert_eq!(scenario.balance(&scenario.account.address), 0);
+}
+
+#[test]
+fn test_advance_past_expiry_allows_expire() {
+    let scenario = ScenarioBuilder::new()
+        .expiry_ledgers(50)
+        .base_reserve(1_000)
+        .funded(20)
+        .paid(300)
+        .build();
+    assert!(!scenario.account.can_expire());
+
+    scenario.advance_ledgers(10);
+    assert_eq!(scenario.account.ledgers_until_expiry(), 40);
+
+    scenario.advance_past_expiry();
+    assert!(scenario.account.can_expire());
+    scenario.account.expire();
+    assert_eq!(scenario.account.get_status(), AccountStatus::Expired);
+    assert_eq!(
+        scenario.account.get_info().swept_to,
+        Some(scenario.recovery.clone())
+    );
+    // The recorded payment goes to recovery; unrecorded funding stays
+    assert_eq!(scenario.balance(&scenario.recovery), 300);
+    assert_eq!(scenario.balance(&scenario.account.address), 20);
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let amount = prop_oneof![Just(0i128), Just(-1i128), 1i128..1_000_000, Just(i128::MAX),];
+    prop_oneof![
+        (any::<u8>(), amount).prop_map(|(asset, amount)| Op::Pay { asset, amount }),
+        (any::<u8>(), any::<u32>()).prop_map(|(asset, amount)| Op::Fund { asset, amount }),
+        any::<u8>().prop_map(|destination| Op::Sweep { destination }),
+        any::<u8>().prop_map(|destination| Op::ReplaySweep { destination }),
+        (any::<u8>(), any::<u8>())
+            .prop_map(|(destination, asset)| Op::SweepAsset { destination, asset }),
+        Just(Op::Expire),
+        Just(Op::Cancel),
+        Just(Op::ReclaimReserve),
+        (0u16..400).prop_map(|ledgers| Op::ExtendExpiry { ledgers }),
+        (0u16..300).prop_map(|ledgers| Op::AdvanceLedgers { ledgers }),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(48))]
+
+    #[test]
+    fn test_random_operations_keep_account_invariants(
+        ops in proptest::collection::vec(op(), 1..24)
+    ) {
+        state_machine::run(&ops);
+    }
+}
@@ -3,12 +3,23 @@ set -e
 
 echo "🔨 Building Bridgelet Core contracts..."
 
+# Reproducible build: the toolchain comes from rust-toolchain.toml, the
+# dependencies from Cargo.lock, and the cargo home is remapped so the
+# builder's paths do not end up in the wasm.  The release profile
+# (opt-level z, lto, one codegen unit, stripped) is the optimize step; no
+# unpinned post-processing runs on top of it.
+CARGO_HOME_DIR="${CARGO_HOME:-$HOME/.cargo}"
+
 # Build ephemeral_account contract
 echo "Building ephemeral_account..."
-cd contracts/ephemeral_account
-cargo build --target wasm32-unknown-unknown --release
-cd ../..
+cargo build --locked --target wasm32-unknown-unknown --release -p ephemeral_account \
+    --config "target.wasm32-unknown-unknown.rustflags=[\"--remap-path-prefix=$CARGO_HOME_DIR=/cargo\"]"
+
+WASM_DIR=target/wasm32-unknown-unknown/release
 
 echo "✅ Build complete!"
-echo "Contracts location: contracts/ephemeral_account/target/wasm32-unknown-unknown/release/"
-ls -lh contracts/ephemeral_account/target/wasm32-unknown-unknown/release/*.wasm
\ No newline at end of file
+echo "Contracts location: $WASM_DIR/"
+ls -lh "$WASM_DIR/ephemeral_account.wasm"
+echo ""
+echo "🔏 Wasm hashes (publish these to the attestation registry for a release):"
+sha256sum "$WASM_DIR/ephemeral_account.wasm"
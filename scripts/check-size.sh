@@ -0,0 +1,33 @@
+#!/bin/bash
+set -e
+
+# Soroban refuses to upload contract code larger than this
+MAX_WASM_BYTES=65536
+
+WASM=target/wasm32-unknown-unknown/release/ephemeral_account.wasm
+
+# The factory tests below deploy whichever account wasm was built last
+factory_tests() {
+    echo "🏭 Deploying and recycling the $1 build through the factory..."
+    cargo test -p account_factory -- --ignored
+}
+
+# The smallest account the factory can deploy, recycle and insure
+cargo build --target wasm32-unknown-unknown --release -p ephemeral_account \
+    --no-default-features --features reuse,insurance
+factory_tests "reuse and insurance"
+
+# The account wasm that is released and deployed, built exactly as
+# scripts/build.sh builds it; built last so it is the one left in target/
+./scripts/build.sh
+
+size=$(wc -c < "$WASM")
+echo "ephemeral_account.wasm (deployable): $size bytes (limit $MAX_WASM_BYTES)"
+if [ "$size" -gt "$MAX_WASM_BYTES" ]; then
+    echo "❌ The deployable build is over the Soroban contract size limit"
+    exit 1
+fi
+
+factory_tests "deployable"
+
+echo "✅ The deployable build fits and the factory deploys it!"
@@ -0,0 +1,57 @@
+#!/bin/bash
+set -e
+
+# Restore archived ledger entries of a Bridgelet contract.
+#
+# Usage: ./scripts/restore.sh <contract_id> [asset_contract_id ...]
+#
+# Always restores the contract instance, which holds the whole state of the
+# reserve contract and the core state of an ephemeral account.  For an
+# ephemeral account, pass the assets it recorded payments in to also
+# restore its persistent payment entries (the asset index and one entry
+# per asset).
+#
+# SOURCE and NETWORK override the signing identity and network
+# (default: deployer on testnet).
+
+CONTRACT_ID="$1"
+if [ -z "$CONTRACT_ID" ]; then
+    echo "Usage: $0 <contract_id> [asset_contract_id ...]"
+    exit 1
+fi
+shift
+
+SOURCE="${SOURCE:-deployer}"
+NETWORK="${NETWORK:-testnet}"
+
+# Base64 XDR of a contracttype DataKey, given as an ScVal JSON vector
+data_key_xdr() {
+    echo "$1" | stellar xdr encode --type ScVal
+}
+
+restore_key() {
+    stellar contract restore \
+        --id "$CONTRACT_ID" \
+        --key-xdr "$(data_key_xdr "$1")" \
+        --durability persistent \
+        --source "$SOURCE" \
+        --network "$NETWORK"
+}
+
+echo "♻️  Restoring contract instance of $CONTRACT_ID..."
+stellar contract restore \
+    --id "$CONTRACT_ID" \
+    --source "$SOURCE" \
+    --network "$NETWORK"
+
+if [ "$#" -gt 0 ]; then
+    echo "Restoring payment index..."
+    restore_key '{"vec":[{"symbol":"PaymentAssets"}]}'
+
+    for ASSET in "$@"; do
+        echo "Restoring payment in $ASSET..."
+        restore_key "{\"vec\":[{\"symbol\":\"Payment\"},{\"address\":\"$ASSET\"}]}"
+    done
+fi
+
+echo "✅ Restore complete!"
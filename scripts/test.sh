@@ -6,7 +6,7 @@ echo "🧪 Running tests..."
 # Test ephemeral_account
 echo "Testing ephemeral_account..."
 cd contracts/ephemeral_account
-cargo test
+cargo test --features full
 cd ../..
 
 echo "✅ All tests passed!"
\ No newline at end of file